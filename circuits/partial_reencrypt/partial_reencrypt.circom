@@ -0,0 +1,134 @@
+pragma circom 2.0.0;
+
+include "../common/babyjubjub.circom";
+include "../common/elgamal.circom";
+include "../../vendor/circomlib/circuits/bitify.circom";
+include "../../vendor/circomlib/circuits/comparators.circom";
+
+/// Re-randomizes numCards cards of a deck in place: V_i = U_i + (R_i * g, R_i * pk).
+/// Unlike ShuffleEncryptV2 there is no permutation, so the circuit is one ElGamal
+/// encryption per listed card. `indices` are the deck positions of U and V, strictly
+/// increasing and below deckSize; the verifier checks UX0/UX1 against the deck at them.
+template PartialReencryptTemplate(base, numCards, deckSize, numBits) {
+    assert(numCards <= deckSize);
+    assert(deckSize <= 64);
+    signal input pk[2];                 // group element on inner curve
+    signal input indices[numCards];     // deck positions of the re-randomized cards
+    signal input UX0[numCards];         // numCards x-coordinates of group elements on inner curve
+    signal input UX1[numCards];         // numCards x-coordinates of group elements on inner curve
+    signal input VX0[numCards];         // numCards x-coordinates of group elements on inner curve
+    signal input VX1[numCards];         // numCards x-coordinates of group elements on inner curve
+    signal input UDelta0[numCards];     // numCards base field elements on inner curve
+    signal input UDelta1[numCards];     // numCards base field elements on inner curve
+    signal input VDelta0[numCards];     // numCards base field elements on inner curve
+    signal input VDelta1[numCards];     // numCards base field elements on inner curve
+    signal input s_u[2];                // selector of y-coordinates
+    signal input s_v[2];                // selector of y-coordinates
+    signal input R[numCards];           // numCards scalars as randomness
+
+    component indexBits[numCards];
+    component inDeck[numCards];
+    component ascending[numCards];
+    for (var i = 0; i < numCards; i++) {
+        indexBits[i] = Num2Bits(6);
+        indexBits[i].in <== indices[i];
+        inDeck[i] = LessThan(6);
+        inDeck[i].in[0] <== indices[i];
+        inDeck[i].in[1] <== deckSize;
+        inDeck[i].out === 1;
+        if (i > 0) {
+            ascending[i] = LessThan(6);
+            ascending[i].in[0] <== indices[i-1];
+            ascending[i].in[1] <== indices[i];
+            ascending[i].out === 1;
+        }
+    }
+
+    component n2b_u0 = Num2Bits(numCards);
+    component n2b_u1 = Num2Bits(numCards);
+    component n2b_v0 = Num2Bits(numCards);
+    component n2b_v1 = Num2Bits(numCards);
+    n2b_u0.in <== s_u[0];
+    n2b_u1.in <== s_u[1];
+    n2b_v0.in <== s_v[0];
+    n2b_v1.in <== s_v[1];
+
+    component decompress[4*numCards];
+    component elgamal[numCards];
+    for (var i = 0; i < numCards; i++) {
+        decompress[i] = ecDecompress();
+        decompress[i].x <== UX0[i];
+        decompress[i].s <== n2b_u0.out[i];
+        decompress[i].delta <== UDelta0[i];
+        decompress[numCards + i] = ecDecompress();
+        decompress[numCards + i].x <== UX1[i];
+        decompress[numCards + i].s <== n2b_u1.out[i];
+        decompress[numCards + i].delta <== UDelta1[i];
+        decompress[2*numCards + i] = ecDecompress();
+        decompress[2*numCards + i].x <== VX0[i];
+        decompress[2*numCards + i].s <== n2b_v0.out[i];
+        decompress[2*numCards + i].delta <== VDelta0[i];
+        decompress[3*numCards + i] = ecDecompress();
+        decompress[3*numCards + i].x <== VX1[i];
+        decompress[3*numCards + i].s <== n2b_v1.out[i];
+        decompress[3*numCards + i].delta <== VDelta1[i];
+
+        elgamal[i] = ElGamalEncrypt(numBits, base);
+        elgamal[i].ic0[0] <== UX0[i];
+        elgamal[i].ic0[1] <== decompress[i].y;
+        elgamal[i].ic1[0] <== UX1[i];
+        elgamal[i].ic1[1] <== decompress[numCards + i].y;
+        elgamal[i].r <== R[i];
+        elgamal[i].pk[0] <== pk[0];
+        elgamal[i].pk[1] <== pk[1];
+        elgamal[i].c0[0] === VX0[i];
+        elgamal[i].c0[1] === decompress[2*numCards + i].y;
+        elgamal[i].c1[0] === VX1[i];
+        elgamal[i].c1[1] === decompress[3*numCards + i].y;
+    }
+}
+
+template PartialReencrypt(numCards) {
+    var numBits = 251;
+    // Base8 generator of Baby JubJub curve: https://github.com/iden3/circomlibjs/blob/main/src/babyjub.js#L18-L21
+    var base[2] = [5299619240641551281634865583518297030282874472190772894086521144482721001553,
+                   16950150798460657717958625567821834550301663161624707787222815936182638968203];
+    signal input pk[2];
+    signal input indices[numCards];
+    signal input UX0[numCards];
+    signal input UX1[numCards];
+    signal input VX0[numCards];
+    signal input VX1[numCards];
+    signal input UDelta0[numCards];
+    signal input UDelta1[numCards];
+    signal input VDelta0[numCards];
+    signal input VDelta1[numCards];
+    signal input s_u[2];
+    signal input s_v[2];
+    signal input R[numCards];
+
+    signal output dummy_output; // Circom requires at least 1 output signal.
+    dummy_output <== pk[0] * pk[1];
+
+    component reencrypt = PartialReencryptTemplate(base, numCards, 52, numBits);
+    for (var i = 0; i < 2; i++) {
+        reencrypt.pk[i] <== pk[i];
+        reencrypt.s_u[i] <== s_u[i];
+        reencrypt.s_v[i] <== s_v[i];
+    }
+    for (var i = 0; i < numCards; i++) {
+        reencrypt.indices[i] <== indices[i];
+        reencrypt.UX0[i] <== UX0[i];
+        reencrypt.UX1[i] <== UX1[i];
+        reencrypt.VX0[i] <== VX0[i];
+        reencrypt.VX1[i] <== VX1[i];
+        reencrypt.UDelta0[i] <== UDelta0[i];
+        reencrypt.UDelta1[i] <== UDelta1[i];
+        reencrypt.VDelta0[i] <== VDelta0[i];
+        reencrypt.VDelta1[i] <== VDelta1[i];
+        reencrypt.R[i] <== R[i];
+    }
+}
+
+// The proof is for exactly this many cards; other subset sizes need their own build
+component main {public [pk, indices, UX0, UX1, VX0, VX1, s_u, s_v]} = PartialReencrypt(4);
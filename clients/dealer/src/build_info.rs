@@ -0,0 +1,28 @@
+//! Version and build details for `--version`.
+
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+/// Chain the daemon targets unless `--chain-id` says otherwise
+pub const NETWORK: &str = "xion-testnet-2";
+
+/// "0.1.0 (git: abc1234, built: ..., rustc: 1.75.0, network: xion-testnet-2)"; clap
+/// prefixes the binary name.
+pub fn version_details() -> String {
+    // RUSTC_VERSION reads "rustc 1.75.0 (82e1608df 2023-12-21)"
+    let rustc = built_info::RUSTC_VERSION
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or(built_info::RUSTC_VERSION);
+    let git = match (built_info::GIT_COMMIT_HASH_SHORT, built_info::GIT_DIRTY) {
+        (Some(hash), Some(true)) => format!("{hash}-dirty"),
+        (Some(hash), _) => hash.to_string(),
+        (None, _) => "unknown".to_string(),
+    };
+    format!(
+        "{} (git: {git}, built: {}, rustc: {rustc}, network: {NETWORK})",
+        built_info::PKG_VERSION,
+        built_info::BUILT_TIME_UTC,
+    )
+}
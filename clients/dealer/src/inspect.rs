@@ -0,0 +1,268 @@
+//! Rendering for `inspect-game`: cards, partial decryptions, phase and player actions.
+
+use crate::tx_archive::TxRecord;
+use juodzekas::msg::{GameResponse, HandResponse};
+use std::fmt::Write;
+
+/// Position of the dealer's hole card in the deck; 0-1 go to the player, 2 is the upcard
+const HOLE_CARD_INDEX: u32 = 3;
+
+/// Our partial for a card compared against the one on-chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PartialCheck {
+    /// No key file for the game, so nothing to compare against
+    Unchecked,
+    /// The dealer has not submitted a partial for this card yet
+    Pending,
+    Match,
+    Mismatch,
+}
+
+/// One dealt card, as seen in the reveal history and pending reveals
+#[derive(Debug, Clone)]
+pub struct CardRow {
+    pub card_index: u32,
+    /// Card index (0-51), once revealed
+    pub value: Option<u8>,
+    pub player_partial: Option<Vec<u8>>,
+    pub dealer_partial: Option<Vec<u8>>,
+    /// Height of the latest partial, if any was recorded
+    pub block_height: Option<u64>,
+    pub check: PartialCheck,
+}
+
+#[derive(Clone, Copy)]
+enum Color {
+    Red,
+    Green,
+    Yellow,
+    Dim,
+}
+
+fn paint(text: &str, color: Color, enabled: bool) -> String {
+    if !enabled {
+        return text.to_string();
+    }
+    let code = match color {
+        Color::Red => "31;1",
+        Color::Green => "32",
+        Color::Yellow => "33",
+        Color::Dim => "2",
+    };
+    format!("\x1b[{code}m{text}\x1b[0m")
+}
+
+/// "A♠" for a revealed card, "??" otherwise
+pub fn card_label(value: Option<u8>) -> String {
+    value.map_or("??".to_string(), |v| {
+        blackjack::Card::from_index(v as usize).to_display()
+    })
+}
+
+/// First four bytes in hex, enough to tell partials apart
+pub fn short_hex(bytes: Option<&[u8]>) -> String {
+    match bytes {
+        None => "-".to_string(),
+        Some(bytes) => {
+            let hex: String = bytes.iter().take(4).map(|b| format!("{b:02x}")).collect();
+            if bytes.len() > 4 {
+                format!("{hex}…")
+            } else {
+                hex
+            }
+        }
+    }
+}
+
+/// "WaitingForReveal" from `WaitingForReveal { reveal_requests: [3], .. }`
+pub fn phase(status: &str) -> &str {
+    status
+        .split(|c: char| c == ' ' || c == '{')
+        .next()
+        .unwrap_or(status)
+}
+
+/// What the player did on a hand, inferred from its cards and status: the contract
+/// keeps no action log. Every card past the first two was a hit, or the double.
+pub fn hand_actions(hand: &HandResponse) -> Vec<&'static str> {
+    let mut actions = Vec::new();
+    if hand.split_depth > 0 {
+        actions.push("Split");
+    }
+    let mut hits = hand.cards.len().saturating_sub(2);
+    if hand.status == "Doubled" {
+        hits = hits.saturating_sub(1);
+    }
+    actions.extend(std::iter::repeat_n("Hit", hits));
+    match hand.status.as_str() {
+        "Stood" => actions.push("Stand"),
+        "Doubled" => actions.push("Double"),
+        "Surrendered" => actions.push("Surrender"),
+        _ => {}
+    }
+    actions
+}
+
+/// Who holds the card: the hand or dealer whose cards include its value
+fn card_owner(game: &GameResponse, row: &CardRow) -> String {
+    let Some(value) = row.value else {
+        return if row.card_index == HOLE_CARD_INDEX {
+            "Dealer (hole)".to_string()
+        } else {
+            "-".to_string()
+        };
+    };
+    if let Some(i) = game.hands.iter().position(|h| h.cards.contains(&value)) {
+        format!("Hand {i}")
+    } else if game.dealer_hand.contains(&value) {
+        "Dealer".to_string()
+    } else {
+        "-".to_string()
+    }
+}
+
+/// "1h 02m", "3m 05s" or "12s"
+pub fn format_elapsed(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+/// The whole report. `txs` are our archived transactions for this game, oldest first;
+/// `now` is unix seconds.
+pub fn render(
+    game_id: u64,
+    game: &GameResponse,
+    rows: &[CardRow],
+    txs: &[TxRecord],
+    now: u64,
+    color: bool,
+) -> String {
+    let mut out = String::new();
+    let since_action = now.saturating_sub(game.last_action_timestamp);
+    let phase = phase(&game.status);
+    let phase_color = if matches!(phase, "Settled" | "Forfeited" | "EmergencyRefunded") {
+        Color::Green
+    } else {
+        Color::Yellow
+    };
+    let _ = writeln!(
+        out,
+        "Game #{game_id}  player {}  bet {}",
+        game.player, game.bet
+    );
+    let _ = writeln!(
+        out,
+        "Phase: {} ({} since the last action)",
+        paint(phase, phase_color, color),
+        format_elapsed(since_action)
+    );
+
+    let _ = writeln!(out);
+    for (i, hand) in game.hands.iter().enumerate() {
+        let cards: Vec<String> = hand.cards.iter().map(|&c| card_label(Some(c))).collect();
+        let actions = hand_actions(hand);
+        let _ = writeln!(
+            out,
+            "Hand {i}: [{}]  bet {}  {}  actions: {}",
+            cards.join(" "),
+            hand.bet,
+            hand.status,
+            if actions.is_empty() {
+                "-".to_string()
+            } else {
+                actions.join(", ")
+            }
+        );
+    }
+    let mut dealer: Vec<String> = game
+        .dealer_hand
+        .iter()
+        .map(|&c| card_label(Some(c)))
+        .collect();
+    if dealer.len() == 1 {
+        dealer.push(card_label(None));
+    }
+    let _ = writeln!(out, "Dealer: [{}]", dealer.join(" "));
+
+    let _ = writeln!(out);
+    let _ = writeln!(
+        out,
+        "{:>4}  {:<4}  {:<13}  {:<10}  {:<10}  {:>8}  check",
+        "pos", "card", "owner", "player", "dealer", "height"
+    );
+    for row in rows {
+        let check = match row.check {
+            PartialCheck::Unchecked => paint("-", Color::Dim, color),
+            PartialCheck::Pending => paint("pending", Color::Yellow, color),
+            PartialCheck::Match => paint("ok", Color::Green, color),
+            PartialCheck::Mismatch => paint("MISMATCH", Color::Red, color),
+        };
+        let line = format!(
+            "{:>4}  {:<4}  {:<13}  {:<10}  {:<10}  {:>8}  {check}",
+            row.card_index,
+            card_label(row.value),
+            card_owner(game, row),
+            short_hex(row.player_partial.as_deref()),
+            short_hex(row.dealer_partial.as_deref()),
+            row.block_height.map_or("-".to_string(), |h| h.to_string()),
+        );
+        let _ = writeln!(out, "{line}");
+    }
+
+    let pending: Vec<String> = game
+        .pending_reveals
+        .iter()
+        .map(|p| {
+            let waiting_on: Vec<&str> = [
+                p.player_partial.is_none().then_some("player"),
+                p.dealer_partial.is_none().then_some("dealer"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            format!(
+                "card {} waits on {}",
+                p.card_index,
+                waiting_on.join(" and ")
+            )
+        })
+        .collect();
+    let _ = writeln!(out);
+    if pending.is_empty() && phase == "WaitingForReveal" {
+        // Requested, but neither side has submitted a partial yet
+        let _ = writeln!(out, "Pending: {}", game.status);
+    } else if pending.is_empty() {
+        let _ = writeln!(out, "Pending: none");
+    } else {
+        let _ = writeln!(out, "Pending: {}", pending.join("; "));
+    }
+
+    // Only the last action's time is on-chain, so phase durations come from our own
+    // transactions: the gap before each is how long the game sat waiting for it
+    if !txs.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Dealer transactions:");
+        let mut previous: Option<chrono::DateTime<chrono::FixedOffset>> = None;
+        for tx in txs {
+            let at = chrono::DateTime::parse_from_rfc3339(&tx.timestamp).ok();
+            let gap = match (previous, at) {
+                (Some(prev), Some(at)) => {
+                    format!(
+                        "+{}",
+                        format_elapsed((at - prev).num_seconds().max(0) as u64)
+                    )
+                }
+                _ => String::new(),
+            };
+            previous = at.or(previous);
+            let card = tx
+                .card_index
+                .map_or(String::new(), |c| format!(" card {c}"));
+            let _ = writeln!(out, "  {}  {}{card}  {gap}", tx.timestamp, tx.action);
+        }
+    }
+    out
+}
@@ -1,35 +1,69 @@
-use ark_ec::{AffineRepr, CurveGroup};
-use ark_ff::{BigInteger, PrimeField};
-use ark_std::UniformRand;
+use ark_ec::AffineRepr;
 use base64::{engine::general_purpose, Engine as _};
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use mob::{ChainConfig, Client, RustSigner};
 use prost::Message;
 use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::Arc;
 use zk_shuffle::babyjubjub::{Fr, Point};
-use zk_shuffle::decrypt::reveal_card;
-use zk_shuffle::elgamal::{encrypt, Ciphertext, KeyPair};
+use zk_shuffle::card_map::CardMap;
+use zk_shuffle::decrypt::{reveal_card, reveal_card_with_audit, RevealAuditTrail};
+use zk_shuffle::elgamal::{encrypt_batch_random, Ciphertext, KeyPair};
+use zk_shuffle::keypair::KeyStore;
 use zk_shuffle::proof::{
-    generate_reveal_proof_rapidsnark, generate_shuffle_proof_rapidsnark, CanonicalDeserialize,
-    CanonicalSerialize,
+    verify_reveal_proof, verify_shuffle_proof, CanonicalDeserialize, CanonicalSerialize,
+    ProofCache, ProofInputSerializer,
 };
-use zk_shuffle::shuffle::shuffle;
+use zk_shuffle::prover::{Prover, RapidsnarkProver, RevealProof};
+use zk_shuffle::sealed::storage_key_from_mnemonic;
+use zk_shuffle::shuffle::{shuffle, PrecomputedShuffleInputs, ShuffleResult};
+use zk_shuffle::vk::{self, VerificationKeys};
+
+mod build_info;
+mod inspect;
+mod notify;
+mod proof_log;
+mod tx_archive;
+use notify::{NotificationLevel, Notifier, TelegramNotifier};
+use proof_log::ProofLog;
+use tx_archive::{TransactionArchive, TxRecord};
 
 // Re-export contract types
 use juodzekas::msg::{
-    Config as ContractConfig, DealerBalanceResponse, DoubleRestriction, GameListItem, GameResponse,
-    InstantiateMsg, PayoutRatio,
+    Config as ContractConfig, ContractStateResponse, DealerBalanceResponse, DoubleRestriction,
+    GameListItem, GameResponse, GamesPageResponse, HandResponse, InstantiateMsg,
+    PauseStatusResponse, PayoutRatio, PendingReveal, RevealRecord, RevealRequestsResponse,
 };
 
 type BoxErr = Box<dyn std::error::Error + Send + Sync>;
 
+/// Games fetched per `GetGamesByStatus` page and per detail batch during export
+const EXPORT_BATCH_SIZE: usize = 50;
+/// Largest page `GetGamesByStatus` returns
+const MAX_GAMES_PAGE: u32 = 100;
+/// Directory for per-game key files and the transaction archive
+const DATA_DIR: &str = "data";
+/// Card points table, under `DATA_DIR`; written on first start
+const PRECOMPUTED_INPUTS_FILE: &str = "precomputed_inputs.bin";
+/// Shuffle proofs made ahead of time for the next game, under `DATA_DIR`
+const PROOF_CACHE_DIR: &str = "proof_cache";
+const PROOF_CACHE_ENTRIES: usize = 4;
+/// A proof taking longer than this raises an error notification
+const PROOF_ALERT_AFTER: std::time::Duration = std::time::Duration::from_secs(300);
+
 #[derive(Parser)]
-#[command(name = "juodzekas-dealer", about = "Juodzekas blackjack dealer daemon")]
+#[command(
+    name = "juodzekas-dealer",
+    version = build_info::version_details(),
+    about = "Juodzekas blackjack dealer daemon"
+)]
 struct Cli {
-    /// Dealer mnemonic
+    /// Dealer mnemonic (not needed for `analyze`)
     #[arg(long, env = "DEALER_MNEMONIC")]
-    mnemonic: String,
+    mnemonic: Option<String>,
 
     #[arg(
         long,
@@ -38,9 +72,17 @@ struct Cli {
     )]
     rpc_url: String,
 
-    #[arg(long, env = "CHAIN_ID", default_value = "xion-testnet-2")]
+    #[arg(long, env = "CHAIN_ID", default_value = build_info::NETWORK)]
     chain_id: String,
 
+    /// Log output format (filter with RUST_LOG)
+    #[arg(long, env = "LOG_FORMAT", value_enum, default_value_t = LogFormat::Compact)]
+    log_format: LogFormat,
+
+    /// Also append logs to this file
+    #[arg(long, env = "LOG_FILE")]
+    log_file: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Command,
 }
@@ -97,6 +139,10 @@ enum Command {
         #[arg(long, default_value_t = false, action = clap::ArgAction::Set)]
         can_hit_split_aces: bool,
 
+        /// Allow doubling down after a split
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        double_after_split: bool,
+
         /// Allow surrender
         #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
         surrender_allowed: bool,
@@ -130,6 +176,31 @@ enum Command {
         /// Auto-create new games after each settles
         #[arg(long, env = "AUTO_CREATE_GAME", default_value_t = true, action = clap::ArgAction::Set)]
         auto_create_game: bool,
+
+        /// Directory with shuffle_vk.bin and reveal_vk.bin replacing the embedded keys
+        #[arg(long, env = "VK_OVERRIDE")]
+        vk_override: Option<PathBuf>,
+
+        /// After each game, deposit `--auto-topup-amount` if the bankroll is below this
+        #[arg(long, env = "AUTO_TOPUP_THRESHOLD", requires = "auto_topup_amount")]
+        auto_topup_threshold: Option<u128>,
+
+        /// Amount to deposit from the dealer wallet when topping up
+        #[arg(long, env = "AUTO_TOPUP_AMOUNT", requires = "auto_topup_threshold")]
+        auto_topup_amount: Option<u128>,
+
+        /// Telegram bot token; with `--telegram-chat-id`, pushes game and alert messages
+        #[arg(long, env = "TELEGRAM_BOT_TOKEN", requires = "telegram_chat_id")]
+        telegram_bot_token: Option<String>,
+
+        /// Telegram chat to send notifications to
+        #[arg(long, env = "TELEGRAM_CHAT_ID", requires = "telegram_bot_token")]
+        telegram_chat_id: Option<String>,
+
+        /// Games to run at once. Above 1, each game proves its own deck instead of the
+        /// next one being pre-generated.
+        #[arg(long, env = "CONCURRENT_GAMES", default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        concurrent_games: u32,
     },
 
     /// Deposit bankroll into the contract
@@ -151,36 +222,339 @@ enum Command {
         #[arg(long, env = "CONTRACT_ADDR")]
         contract_addr: String,
     },
+
+    /// Report house edge, per-rule impact and expected profit for a config.
+    /// Reads the live config when a contract address is given, else uses the flags below.
+    Analyze {
+        #[arg(long, env = "CONTRACT_ADDR")]
+        contract_addr: Option<String>,
+
+        /// Bankroll for the solvency estimate (live dealer balance is used with --contract-addr)
+        #[arg(long)]
+        bankroll: Option<u128>,
+
+        /// Token denomination
+        #[arg(long, default_value = "uxion")]
+        denom: String,
+
+        /// Minimum bet
+        #[arg(long, default_value = "100000")]
+        min_bet: u128,
+
+        /// Maximum bet
+        #[arg(long, default_value = "1000000")]
+        max_bet: u128,
+
+        /// Blackjack payout ratio (e.g. "3:2")
+        #[arg(long, default_value = "3:2")]
+        blackjack_payout: String,
+
+        /// Dealer hits soft 17
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        dealer_hits_soft_17: bool,
+
+        /// Dealer peeks for blackjack
+        #[arg(long, default_value_t = false, action = clap::ArgAction::Set)]
+        dealer_peeks: bool,
+
+        /// Double restriction: any, hard9_10_11, hard10_11
+        #[arg(long, default_value = "any")]
+        double_restriction: String,
+
+        /// Maximum number of splits
+        #[arg(long, default_value = "3")]
+        max_splits: u32,
+
+        /// Allow splitting aces
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        can_split_aces: bool,
+
+        /// Allow hitting split aces
+        #[arg(long, default_value_t = false, action = clap::ArgAction::Set)]
+        can_hit_split_aces: bool,
+
+        /// Allow doubling down after a split
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        double_after_split: bool,
+
+        /// Allow surrender
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        surrender_allowed: bool,
+
+        /// Exit successfully even if the house edge is negative
+        #[arg(long, env = "ALLOW_NEGATIVE_EDGE", default_value_t = false, action = clap::ArgAction::Set)]
+        allow_negative_edge: bool,
+    },
+
+    /// Print the house edge for a set of rules and exit 0 if it favors the house, 1 if not.
+    /// Needs no wallet or network, so it can gate configs in CI.
+    EstimateEdge {
+        /// Number of decks (the ZK shuffle deals from one; more is for comparison only)
+        #[arg(long, default_value = "1")]
+        num_decks: u8,
+
+        /// Bankroll for the Kelly bet size
+        #[arg(long)]
+        bankroll: Option<u128>,
+
+        /// Token denomination
+        #[arg(long, default_value = "uxion")]
+        denom: String,
+
+        /// Minimum bet
+        #[arg(long, default_value = "100000")]
+        min_bet: u128,
+
+        /// Maximum bet
+        #[arg(long, default_value = "1000000")]
+        max_bet: u128,
+
+        /// Blackjack payout ratio (e.g. "3:2")
+        #[arg(long, default_value = "3:2")]
+        blackjack_payout: String,
+
+        /// Dealer hits soft 17
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        dealer_hits_soft_17: bool,
+
+        /// Dealer peeks for blackjack
+        #[arg(long, default_value_t = false, action = clap::ArgAction::Set)]
+        dealer_peeks: bool,
+
+        /// Double restriction: any, hard9_10_11, hard10_11
+        #[arg(long, default_value = "any")]
+        double_restriction: String,
+
+        /// Maximum number of splits
+        #[arg(long, default_value = "3")]
+        max_splits: u32,
+
+        /// Allow splitting aces
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        can_split_aces: bool,
+
+        /// Allow hitting split aces
+        #[arg(long, default_value_t = false, action = clap::ArgAction::Set)]
+        can_hit_split_aces: bool,
+
+        /// Allow doubling down after a split
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        double_after_split: bool,
+
+        /// Allow surrender
+        #[arg(long, default_value_t = true, action = clap::ArgAction::Set)]
+        surrender_allowed: bool,
+
+        /// Print {"edge_bps", "variance", "kelly_fraction"} as JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Export settled games to a file, one record per hand
+    Export {
+        #[arg(long, env = "CONTRACT_ADDR")]
+        contract_addr: String,
+
+        /// Output file path
+        #[arg(long)]
+        output: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+        format: ExportFormat,
+
+        /// Only export games with an ID greater than this
+        #[arg(long)]
+        since_game_id: Option<u64>,
+
+        /// Only export games settled after this unix timestamp (seconds)
+        #[arg(long)]
+        since: Option<u64>,
+    },
+
+    /// Print archived transactions as JSONL, optionally for one game
+    TxHistory {
+        /// Write to this file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        #[arg(long = "game-id")]
+        game_id_filter: Option<u64>,
+    },
+
+    /// Delete key files for games that are settled or no longer exist on-chain
+    GarbageCollect {
+        #[arg(long, env = "CONTRACT_ADDR")]
+        contract_addr: String,
+
+        /// List the files that would be deleted without deleting them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Only collect key files last modified at least this many hours ago
+        #[arg(long)]
+        min_age: Option<u64>,
+    },
+
+    /// Change bet limits, timeout or surrender for games created from now on; games
+    /// already created keep their terms. Omitted flags leave the value unchanged.
+    UpdateConfig {
+        #[arg(long, env = "CONTRACT_ADDR")]
+        contract_addr: String,
+
+        #[arg(long)]
+        min_bet: Option<u128>,
+
+        #[arg(long)]
+        max_bet: Option<u128>,
+
+        #[arg(long)]
+        timeout_seconds: Option<u64>,
+
+        #[arg(long)]
+        surrender_allowed: Option<bool>,
+
+        /// Allow a change that leaves a negative house edge (player-favorable)
+        #[arg(long, env = "ALLOW_NEGATIVE_EDGE", default_value_t = false, action = clap::ArgAction::Set)]
+        allow_negative_edge: bool,
+    },
+
+    /// Debugging: end a stuck game by claiming its timeout. The game is settled
+    /// against whichever side is blocking it.
+    KillGame {
+        #[arg(long, env = "CONTRACT_ADDR")]
+        contract_addr: String,
+
+        #[arg(long)]
+        game_id: u64,
+
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        force: bool,
+
+        /// Show the game and whether the claim would go through, without sending it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Required; the command refuses to run without it
+        #[arg(long)]
+        i_understand_this_is_destructive: bool,
+    },
+
+    /// Check a game's deck against our keys and saved proofs: every reveal proof, each
+    /// revealed card value, and the shuffle proof. Exits with code 1 if anything fails.
+    VerifyDeck {
+        #[arg(long, env = "CONTRACT_ADDR")]
+        contract_addr: String,
+
+        #[arg(long)]
+        game_id: u64,
+
+        /// Directory with shuffle_vk.bin and reveal_vk.bin replacing the embedded keys
+        #[arg(long, env = "VK_OVERRIDE")]
+        vk_override: Option<PathBuf>,
+
+        /// Print every point of each card's reveal under its row
+        #[arg(long)]
+        audit: bool,
+    },
+
+    /// Show a game's cards, partial decryptions, phase and player actions. With the
+    /// mnemonic and the game's key file, also checks each of our partials; exits with
+    /// code 1 on a mismatch.
+    InspectGame {
+        #[arg(long, env = "CONTRACT_ADDR")]
+        contract_addr: String,
+
+        #[arg(long)]
+        game_id: u64,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogFormat {
+    Json,
+    Pretty,
+    Compact,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Jsonl,
 }
 
 struct DealerConfig {
     contract_addr: String,
     rpc_url: String,
     auto_create_game: bool,
+    prover: Arc<dyn Prover>,
+    key_store: KeyStore,
+    /// Key file password, derived from the dealer mnemonic
+    key_password: Vec<u8>,
+    /// Checks our own proofs before they are submitted, when keys are available
+    verification_keys: Option<VerificationKeys>,
+    auto_topup: Option<AutoTopup>,
+    notifier: Option<Box<dyn Notifier>>,
+}
+
+impl DealerConfig {
+    fn notify(&self, level: NotificationLevel, message: String) {
+        if let Some(notifier) = &self.notifier {
+            notifier.send(level, message);
+        }
+    }
+}
+
+/// Refill the bankroll from the dealer wallet between games.
+struct AutoTopup {
+    threshold: u128,
+    amount: u128,
+    last_topup: std::sync::Mutex<Option<std::time::Instant>>,
+}
+
+/// Minimum time between two top-ups
+const TOPUP_COOLDOWN: std::time::Duration = std::time::Duration::from_secs(60);
+
+#[derive(Debug, PartialEq)]
+enum TopupDecision {
+    NotNeeded,
+    TooSoon,
+    InsufficientFunds,
+    Deposit,
+}
+
+impl AutoTopup {
+    fn decide(&self, bankroll: u128, wallet: u128, now: std::time::Instant) -> TopupDecision {
+        if bankroll >= self.threshold {
+            TopupDecision::NotNeeded
+        } else if self
+            .last_topup
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|last| now.duration_since(last) < TOPUP_COOLDOWN)
+        {
+            TopupDecision::TooSoon
+        } else if wallet < self.amount {
+            TopupDecision::InsufficientFunds
+        } else {
+            TopupDecision::Deposit
+        }
+    }
 }
 
 fn main() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
     dotenvy::dotenv().ok();
 
     let cli = Cli::parse();
+    if let Err(e) = init_logging(cli.log_format, cli.log_file.as_deref()) {
+        eprintln!("Failed to set up logging: {e}");
+        std::process::exit(1);
+    }
 
-    let signer = RustSigner::from_mnemonic(cli.mnemonic.clone(), "xion".into(), None)
-        .expect("Invalid mnemonic");
-    let address = signer.address();
-    log::info!("Dealer address: {address}");
-
-    let chain_config = ChainConfig::new(
-        cli.chain_id.clone(),
-        cli.rpc_url.clone(),
-        "xion".to_string(),
-    );
-    let client =
-        Client::new_with_signer(chain_config, Arc::new(signer)).expect("Failed to create client");
-
-    match cli.command {
-        Command::Init {
-            code_id,
+    // Read-only commands run before any wallet setup
+    let command = match cli.command {
+        Command::Analyze {
+            contract_addr,
             bankroll,
             denom,
             min_bet,
@@ -192,17 +566,13 @@ fn main() {
             max_splits,
             can_split_aces,
             can_hit_split_aces,
+            double_after_split,
             surrender_allowed,
-            shuffle_vk_id,
-            reveal_vk_id,
-            timeout_seconds,
-            label,
             allow_negative_edge,
         } => {
-            if let Err(e) = cmd_init(
-                &client,
-                &address,
-                code_id,
+            if let Err(e) = cmd_analyze(
+                &cli.rpc_url,
+                contract_addr.as_deref(),
                 bankroll,
                 &denom,
                 min_bet,
@@ -214,56 +584,297 @@ fn main() {
                 max_splits,
                 can_split_aces,
                 can_hit_split_aces,
+                double_after_split,
                 surrender_allowed,
-                &shuffle_vk_id,
-                &reveal_vk_id,
-                timeout_seconds,
-                &label,
                 allow_negative_edge,
             ) {
-                log::error!("Init failed: {e}");
+                tracing::error!("Analyze failed: {e}");
                 std::process::exit(1);
             }
+            return;
         }
-        Command::Run {
-            contract_addr,
-            auto_create_game,
-        } => {
-            let config = DealerConfig {
-                contract_addr,
-                rpc_url: cli.rpc_url,
-                auto_create_game,
-            };
-
-            std::fs::create_dir_all("data").ok();
-
-            loop {
-                match run_game(&client, &config, &address) {
-                    Ok(()) => log::info!("Game completed"),
-                    Err(e) => log::error!("Game failed: {e}"),
-                }
-
-                if !config.auto_create_game {
-                    log::info!("AUTO_CREATE_GAME=false, exiting");
-                    break;
-                }
-                log::info!("Starting next game...");
-            }
-        }
-        Command::Deposit {
-            contract_addr,
-            amount,
+        Command::EstimateEdge {
+            num_decks,
+            bankroll,
             denom,
-        } => {
-            log::info!("Depositing {amount} {denom} to contract {contract_addr}...");
-            let msg_json = serde_json::json!({ "deposit_bankroll": {} });
-            let msg_bytes = serde_json::to_vec(&msg_json).unwrap();
-            let funds = vec![mob::Coin::new(&denom, amount.to_string())];
-            match execute_and_confirm(&client, contract_addr, msg_bytes, funds, "Deposit bankroll")
-            {
-                Ok(resp) => log::info!("Deposit confirmed! Hash: {}", resp.txhash),
-                Err(e) => {
-                    log::error!("Deposit failed: {e}");
+            min_bet,
+            max_bet,
+            blackjack_payout,
+            dealer_hits_soft_17,
+            dealer_peeks,
+            double_restriction,
+            max_splits,
+            can_split_aces,
+            can_hit_split_aces,
+            double_after_split,
+            surrender_allowed,
+            json,
+        } => {
+            let result = flag_config(
+                &denom,
+                min_bet,
+                max_bet,
+                &blackjack_payout,
+                dealer_hits_soft_17,
+                dealer_peeks,
+                &double_restriction,
+                max_splits,
+                can_split_aces,
+                can_hit_split_aces,
+                double_after_split,
+                surrender_allowed,
+            )
+            .and_then(|config| cmd_estimate_edge(&config, num_decks, bankroll, json));
+            match result {
+                Ok(house_edge) if house_edge > 0.0 => return,
+                Ok(_) => std::process::exit(1),
+                Err(e) => {
+                    tracing::error!("Estimate failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Export {
+            contract_addr,
+            output,
+            format,
+            since_game_id,
+            since,
+        } => {
+            if let Err(e) = cmd_export(
+                &cli.rpc_url,
+                &contract_addr,
+                &output,
+                format,
+                since_game_id,
+                since,
+            ) {
+                tracing::error!("Export failed: {e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Command::TxHistory {
+            output,
+            game_id_filter,
+        } => {
+            if let Err(e) = cmd_tx_history(output.as_deref(), game_id_filter) {
+                tracing::error!("Tx history failed: {e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Command::GarbageCollect {
+            contract_addr,
+            dry_run,
+            min_age,
+        } => {
+            if let Err(e) = cmd_garbage_collect(&cli.rpc_url, &contract_addr, dry_run, min_age) {
+                tracing::error!("Garbage collect failed: {e}");
+                std::process::exit(1);
+            }
+            return;
+        }
+        Command::InspectGame {
+            contract_addr,
+            game_id,
+        } => {
+            let password = cli.mnemonic.as_deref().map(key_password);
+            match cmd_inspect_game(&cli.rpc_url, &contract_addr, game_id, password.as_deref()) {
+                Ok(true) => return,
+                Ok(false) => std::process::exit(1),
+                Err(e) => {
+                    tracing::error!("Inspect game failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        command => command,
+    };
+
+    let Some(mnemonic) = cli.mnemonic else {
+        tracing::error!("--mnemonic or DEALER_MNEMONIC is required");
+        std::process::exit(1);
+    };
+
+    let signer =
+        RustSigner::from_mnemonic(mnemonic.clone(), "xion".into(), None).expect("Invalid mnemonic");
+    let address = signer.address();
+    tracing::info!(dealer = %address, "Dealer wallet loaded");
+
+    let chain_config = ChainConfig::new(
+        cli.chain_id.clone(),
+        cli.rpc_url.clone(),
+        "xion".to_string(),
+    );
+    let client =
+        Client::new_with_signer(chain_config, Arc::new(signer)).expect("Failed to create client");
+
+    match command {
+        Command::Init {
+            code_id,
+            bankroll,
+            denom,
+            min_bet,
+            max_bet,
+            blackjack_payout,
+            dealer_hits_soft_17,
+            dealer_peeks,
+            double_restriction,
+            max_splits,
+            can_split_aces,
+            can_hit_split_aces,
+            double_after_split,
+            surrender_allowed,
+            shuffle_vk_id,
+            reveal_vk_id,
+            timeout_seconds,
+            label,
+            allow_negative_edge,
+        } => {
+            if let Err(e) = cmd_init(
+                &client,
+                &address,
+                code_id,
+                bankroll,
+                &denom,
+                min_bet,
+                max_bet,
+                &blackjack_payout,
+                dealer_hits_soft_17,
+                dealer_peeks,
+                &double_restriction,
+                max_splits,
+                can_split_aces,
+                can_hit_split_aces,
+                double_after_split,
+                surrender_allowed,
+                &shuffle_vk_id,
+                &reveal_vk_id,
+                timeout_seconds,
+                &label,
+                allow_negative_edge,
+            ) {
+                tracing::error!("Init failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        Command::Run {
+            contract_addr,
+            auto_create_game,
+            vk_override,
+            auto_topup_threshold,
+            auto_topup_amount,
+            telegram_bot_token,
+            telegram_chat_id,
+            concurrent_games,
+        } => {
+            // A bad override is a developer mistake; missing embedded keys only skips the checks
+            let verification_keys = match VerificationKeys::load(vk_override.as_deref()) {
+                Ok(keys) => Some(keys),
+                Err(e) if vk_override.is_some() => {
+                    tracing::error!("Failed to load --vk-override keys: {e}");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    tracing::warn!(embedded = vk::EMBEDDED, "Local proof checks disabled: {e}");
+                    None
+                }
+            };
+            let config = DealerConfig {
+                contract_addr,
+                rpc_url: cli.rpc_url,
+                auto_create_game,
+                prover: Arc::new(RapidsnarkProver),
+                key_store: KeyStore::new(DATA_DIR),
+                key_password: key_password(&mnemonic),
+                verification_keys,
+                auto_topup: auto_topup_threshold.zip(auto_topup_amount).map(
+                    |(threshold, amount)| AutoTopup {
+                        threshold,
+                        amount,
+                        last_topup: std::sync::Mutex::new(None),
+                    },
+                ),
+                notifier: telegram_bot_token
+                    .zip(telegram_chat_id)
+                    .map(|(token, chat_id)| {
+                        Box::new(TelegramNotifier::new(token, chat_id)) as Box<dyn Notifier>
+                    }),
+            };
+
+            // Only saves a little setup per game, so a bad file isn't fatal
+            let inputs_path = std::path::Path::new(DATA_DIR).join(PRECOMPUTED_INPUTS_FILE);
+            let card_points = match PrecomputedShuffleInputs::load_from_file(&inputs_path) {
+                Ok(inputs) => inputs.card_points,
+                Err(e) => {
+                    tracing::warn!(
+                        path = %inputs_path.display(),
+                        "Precomputed shuffle inputs unavailable, computing them now: {e}"
+                    );
+                    PrecomputedShuffleInputs::new().card_points
+                }
+            };
+
+            if concurrent_games > 1 {
+                run_pool(
+                    Arc::new(client),
+                    Arc::new(config),
+                    address,
+                    Arc::new(card_points),
+                    concurrent_games as usize,
+                );
+                return;
+            }
+
+            // The next game's deck, shuffled and proven while the current game plays
+            let mut next_deck: Option<std::thread::JoinHandle<(KeyPair, ShuffleResult)>> = None;
+            loop {
+                let deck = match next_deck.take().map(|handle| handle.join()) {
+                    Some(Ok(deck)) => deck,
+                    Some(Err(_)) => {
+                        tracing::warn!("Proof pre-generation panicked, shuffling now");
+                        prepare_deck(&card_points)
+                    }
+                    None => prepare_deck(&card_points),
+                };
+                let pregenerate = config.auto_create_game.then_some(card_points.as_slice());
+                match run_game(
+                    &client,
+                    &config,
+                    &address,
+                    deck,
+                    pregenerate,
+                    &mut next_deck,
+                ) {
+                    Ok(()) => tracing::info!("Game completed"),
+                    Err(e) => tracing::error!("Game failed: {e}"),
+                }
+                if let Err(e) = check_and_topup(&client, &config, &address) {
+                    tracing::error!("Auto top-up failed: {e}");
+                }
+
+                if !config.auto_create_game {
+                    tracing::info!("AUTO_CREATE_GAME=false, exiting");
+                    break;
+                }
+                tracing::info!("Starting next game...");
+            }
+        }
+        Command::Deposit {
+            contract_addr,
+            amount,
+            denom,
+        } => {
+            tracing::info!("Depositing {amount} {denom} to contract {contract_addr}...");
+            let msg_json = serde_json::json!({ "deposit_bankroll": {} });
+            let msg_bytes = serde_json::to_vec(&msg_json).unwrap();
+            let funds = vec![mob::Coin::new(&denom, amount.to_string())];
+            match execute_and_confirm(&client, contract_addr, msg_bytes, funds, "Deposit bankroll")
+            {
+                Ok(resp) => tracing::info!(txhash = %resp.txhash, "Deposit confirmed"),
+                Err(e) => {
+                    tracing::error!("Deposit failed: {e}");
                     std::process::exit(1);
                 }
             }
@@ -273,139 +884,1406 @@ fn main() {
                 contract_addr,
                 rpc_url: cli.rpc_url,
                 auto_create_game: false,
+                prover: Arc::new(RapidsnarkProver),
+                key_store: KeyStore::new(DATA_DIR),
+                key_password: key_password(&mnemonic),
+                verification_keys: None,
+                auto_topup: None,
+                notifier: None,
             };
             match withdraw_all_bankroll(&client, &config) {
-                Ok(()) => log::info!("Bankroll withdrawn successfully"),
+                Ok(()) => tracing::info!("Bankroll withdrawn successfully"),
+                Err(e) => {
+                    tracing::error!("Withdraw failed: {e}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::UpdateConfig {
+            contract_addr,
+            min_bet,
+            max_bet,
+            timeout_seconds,
+            surrender_allowed,
+            allow_negative_edge,
+        } => {
+            if let Err(e) = cmd_update_config(
+                &client,
+                &cli.rpc_url,
+                &contract_addr,
+                min_bet,
+                max_bet,
+                timeout_seconds,
+                surrender_allowed,
+                allow_negative_edge,
+            ) {
+                tracing::error!("Update config failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        Command::KillGame {
+            contract_addr,
+            game_id,
+            force,
+            dry_run,
+            i_understand_this_is_destructive,
+        } => {
+            if !i_understand_this_is_destructive {
+                tracing::error!(
+                    "kill-game settles the game on-chain; pass --i-understand-this-is-destructive to run it"
+                );
+                std::process::exit(1);
+            }
+            if let Err(e) = cmd_kill_game(
+                &client,
+                &cli.rpc_url,
+                &contract_addr,
+                game_id,
+                force,
+                dry_run,
+            ) {
+                tracing::error!("Kill game failed: {e}");
+                std::process::exit(1);
+            }
+        }
+        Command::VerifyDeck {
+            contract_addr,
+            game_id,
+            vk_override,
+            audit,
+        } => {
+            let password = key_password(&mnemonic);
+            match cmd_verify_deck(
+                &cli.rpc_url,
+                &contract_addr,
+                game_id,
+                &password,
+                vk_override.as_deref(),
+                audit,
+            ) {
+                Ok(true) => {}
+                Ok(false) => std::process::exit(1),
                 Err(e) => {
-                    log::error!("Withdraw failed: {e}");
+                    tracing::error!("Verify deck failed: {e}");
                     std::process::exit(1);
                 }
             }
         }
+        Command::Analyze { .. }
+        | Command::EstimateEdge { .. }
+        | Command::Export { .. }
+        | Command::TxHistory { .. }
+        | Command::GarbageCollect { .. }
+        | Command::InspectGame { .. } => {
+            unreachable!("handled before wallet setup")
+        }
+    }
+}
+
+/// Install the tracing subscriber: stderr plus an optional log file, filtered by
+/// RUST_LOG (default "info"). `log` records from dependencies are forwarded too.
+fn init_logging(format: LogFormat, log_file: Option<&std::path::Path>) -> Result<(), BoxErr> {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+
+    let file_layer = match log_file {
+        Some(path) => {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            Some(fmt_layer(format, std::sync::Mutex::new(file), false))
+        }
+        None => None,
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt_layer(format, std::io::stderr, true))
+        .with(file_layer)
+        .try_init()?;
+    Ok(())
+}
+
+fn fmt_layer<S, W>(
+    format: LogFormat,
+    writer: W,
+    ansi: bool,
+) -> Box<dyn tracing_subscriber::Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    W: for<'w> tracing_subscriber::fmt::MakeWriter<'w> + Send + Sync + 'static,
+{
+    use tracing_subscriber::Layer;
+
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(writer)
+        .with_ansi(ansi);
+    match format {
+        LogFormat::Json => layer.json().flatten_event(true).boxed(),
+        LogFormat::Pretty => layer.pretty().boxed(),
+        LogFormat::Compact => layer.compact().boxed(),
+    }
+}
+
+// ── Init subcommand ──
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_init(
+    client: &Client,
+    sender: &str,
+    code_id: u64,
+    bankroll: u128,
+    denom: &str,
+    min_bet: u128,
+    max_bet: u128,
+    blackjack_payout: &str,
+    dealer_hits_soft_17: bool,
+    dealer_peeks: bool,
+    double_restriction: &str,
+    max_splits: u32,
+    can_split_aces: bool,
+    can_hit_split_aces: bool,
+    double_after_split: bool,
+    surrender_allowed: bool,
+    shuffle_vk_id: &str,
+    reveal_vk_id: &str,
+    timeout_seconds: u64,
+    label: &str,
+    allow_negative_edge: bool,
+) -> Result<(), BoxErr> {
+    // Build InstantiateMsg
+    let bj_payout = parse_payout_ratio(blackjack_payout)?;
+    let double_res = parse_double_restriction(double_restriction)?;
+
+    // Edge check: compute house edge for this configuration
+    {
+        let rules = edge_rules(
+            &bj_payout,
+            dealer_hits_soft_17,
+            dealer_peeks,
+            &double_res,
+            max_splits,
+            can_split_aces,
+            can_hit_split_aces,
+            double_after_split,
+            surrender_allowed,
+        )?;
+
+        tracing::info!("Computing house edge for this configuration...");
+        let result = blackjack::compute_edge(&rules);
+        tracing::info!(
+            "House edge: {:+.4}% (player return: {:+.4}%)",
+            result.house_edge * 100.0,
+            result.expected_return * 100.0
+        );
+        if bankroll > 0 && max_bet > 0 {
+            tracing::info!(
+                "Risk of ruin at max bet {max_bet} {denom} with bankroll {bankroll} {denom}: {:.4}%",
+                house_risk_of_ruin(&result, bankroll, max_bet) * 100.0
+            );
+        }
+
+        refuse_negative_edge(result.house_edge, allow_negative_edge)?;
+    }
+
+    let instantiate_msg = InstantiateMsg {
+        denom: denom.to_string(),
+        min_bet: cosmwasm_std::Uint128::new(min_bet),
+        max_bet: cosmwasm_std::Uint128::new(max_bet),
+        blackjack_payout: bj_payout,
+        insurance_payout: PayoutRatio {
+            numerator: 2,
+            denominator: 1,
+        },
+        standard_payout: PayoutRatio {
+            numerator: 1,
+            denominator: 1,
+        },
+        dealer_hits_soft_17,
+        dealer_peeks,
+        double_restriction: double_res,
+        max_splits,
+        can_split_aces,
+        can_hit_split_aces,
+        double_after_split: Some(double_after_split),
+        resplit_aces: None,
+        debug_mode: None,
+        surrender_allowed,
+        shuffle_vk_id: shuffle_vk_id.to_string(),
+        reveal_vk_id: reveal_vk_id.to_string(),
+        timeout_seconds: Some(timeout_seconds),
+        retain_settled_seconds: None,
+        whitelist: None,
+        protocol_fee_bps: None,
+        protocol_fee_recipient: None,
+    };
+    let msg_bytes = serde_json::to_vec(&instantiate_msg)?;
+
+    // 4. Instantiate with bankroll funds
+    let funds = if bankroll > 0 {
+        vec![mob::Coin::new(denom, bankroll.to_string())]
+    } else {
+        vec![]
+    };
+
+    tracing::info!("Instantiating contract (code_id={code_id}, bankroll={bankroll} {denom})...");
+    let inst_response =
+        broadcast_and_confirm_instantiate(client, sender, code_id, label, msg_bytes, funds)?;
+    let contract_addr = extract_contract_address(&inst_response)?;
+    tracing::info!(txhash = %inst_response.txhash, contract = %contract_addr, "Contract instantiated");
+
+    // 5. Print for .env
+    println!("\nCONTRACT_ADDR={contract_addr}");
+    tracing::info!("Done. Add CONTRACT_ADDR to your .env file.");
+
+    Ok(())
+}
+
+// ── Analyze subcommand ──
+
+#[allow(clippy::too_many_arguments)]
+fn cmd_analyze(
+    rpc_url: &str,
+    contract_addr: Option<&str>,
+    bankroll: Option<u128>,
+    denom: &str,
+    min_bet: u128,
+    max_bet: u128,
+    blackjack_payout: &str,
+    dealer_hits_soft_17: bool,
+    dealer_peeks: bool,
+    double_restriction: &str,
+    max_splits: u32,
+    can_split_aces: bool,
+    can_hit_split_aces: bool,
+    double_after_split: bool,
+    surrender_allowed: bool,
+    allow_negative_edge: bool,
+) -> Result<(), BoxErr> {
+    let (config, bankroll) = if let Some(contract_addr) = contract_addr {
+        tracing::info!("Querying config of {contract_addr}...");
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let config = rt.block_on(query_config(rpc_url, contract_addr))?;
+        let balance = rt.block_on(query_dealer_balance(rpc_url, contract_addr))?;
+        (config, Some(balance.balance.u128()))
+    } else {
+        let config = flag_config(
+            denom,
+            min_bet,
+            max_bet,
+            blackjack_payout,
+            dealer_hits_soft_17,
+            dealer_peeks,
+            double_restriction,
+            max_splits,
+            can_split_aces,
+            can_hit_split_aces,
+            double_after_split,
+            surrender_allowed,
+        )?;
+        (config, bankroll)
+    };
+
+    tracing::info!("Computing house edge for this configuration...");
+    let result = config_edge(&config)?;
+    let house_edge = result.house_edge;
+    print_edge(&result);
+
+    // Flip one rule at a time and compare against the baseline edge
+    println!("\nRule impact:");
+    for (label, variant) in rule_variants(&config) {
+        let delta = config_edge(&variant)?.house_edge - house_edge;
+        let verb = if delta >= 0.0 { "saves" } else { "costs" };
+        println!("  {label} {verb} player {:.2}%", delta.abs() * 100.0);
+    }
+
+    print_expected_profit(&config, house_edge);
+
+    // Each game locks 10x max bet from the dealer balance (see execute_create_game)
+    let per_game = config.max_bet.u128().saturating_mul(10);
+    match bankroll {
+        Some(bankroll) if per_game > 0 => println!(
+            "\nSolvency: bankroll {bankroll} {} covers {} concurrent games ({per_game} {} locked per game)",
+            config.denom,
+            bankroll / per_game,
+            config.denom
+        ),
+        _ => println!("\nSolvency: pass --bankroll or --contract-addr for an estimate"),
+    }
+
+    print_kelly(&result, bankroll, &config.denom);
+
+    // Risk of ruin for the house if every hand were played at max bet
+    let max = config.max_bet.u128();
+    if let Some(bankroll) = bankroll.filter(|_| max > 0) {
+        println!(
+            "Risk of ruin:   {:.4}% at max bet ({:.0} max-bet units of bankroll)",
+            house_risk_of_ruin(&result, bankroll, max) * 100.0,
+            bankroll as f64 / max as f64
+        );
+    }
+
+    if house_edge < 0.0 {
+        println!();
+        println!("{}", "!".repeat(72));
+        println!(
+            "WARNING: NEGATIVE HOUSE EDGE ({:+.4}%) — this config favors the player",
+            house_edge * 100.0
+        );
+        println!("         and will lose the dealer money on average.");
+        println!("{}", "!".repeat(72));
+        if !allow_negative_edge {
+            return Err("Negative house edge (use --allow-negative-edge to ignore)".into());
+        }
+    }
+
+    Ok(())
+}
+
+// ── Estimate-edge subcommand ──
+
+/// Print the edge of `config` dealt from `num_decks` decks and return the house edge.
+fn cmd_estimate_edge(
+    config: &ContractConfig,
+    num_decks: u8,
+    bankroll: Option<u128>,
+    json: bool,
+) -> Result<f64, BoxErr> {
+    let rules = blackjack::GameRules {
+        num_decks: num_decks.max(1),
+        ..config_rules(config)?
+    };
+    let result = blackjack::compute_edge(&rules);
+    let house_edge = result.house_edge;
+
+    if json {
+        let report = serde_json::json!({
+            "edge_bps": (house_edge * 10_000.0).round() as i64,
+            "variance": result.variance_per_hand,
+            "kelly_fraction": house_kelly(&result).optimal_fraction(),
+        });
+        println!("{report}");
+    } else {
+        print_edge(&result);
+        print_expected_profit(config, house_edge);
+        println!();
+        print_kelly(&result, bankroll, &config.denom);
+        if house_edge <= 0.0 {
+            println!("\nHouse edge is not positive: this config favors the player");
+        }
+    }
+
+    Ok(house_edge)
+}
+
+/// Contract config built from the rule flags shared by `init`, `analyze` and `estimate-edge`.
+#[allow(clippy::too_many_arguments)]
+fn flag_config(
+    denom: &str,
+    min_bet: u128,
+    max_bet: u128,
+    blackjack_payout: &str,
+    dealer_hits_soft_17: bool,
+    dealer_peeks: bool,
+    double_restriction: &str,
+    max_splits: u32,
+    can_split_aces: bool,
+    can_hit_split_aces: bool,
+    double_after_split: bool,
+    surrender_allowed: bool,
+) -> Result<ContractConfig, BoxErr> {
+    Ok(ContractConfig {
+        denom: denom.to_string(),
+        min_bet: cosmwasm_std::Uint128::new(min_bet),
+        max_bet: cosmwasm_std::Uint128::new(max_bet),
+        blackjack_payout: parse_payout_ratio(blackjack_payout)?,
+        insurance_payout: PayoutRatio {
+            numerator: 2,
+            denominator: 1,
+        },
+        standard_payout: PayoutRatio {
+            numerator: 1,
+            denominator: 1,
+        },
+        dealer_hits_soft_17,
+        dealer_peeks,
+        double_restriction: parse_double_restriction(double_restriction)?,
+        max_splits,
+        can_split_aces,
+        can_hit_split_aces,
+        double_after_split,
+        resplit_aces: false,
+        debug_mode: false,
+        surrender_allowed,
+        shuffle_vk_id: String::new(),
+        reveal_vk_id: String::new(),
+        timeout_seconds: 0,
+        retain_settled_seconds: 0,
+        vk_version: 0,
+        version: 0,
+        protocol_fee_bps: 0,
+        protocol_fee_recipient: None,
+    })
+}
+
+fn print_edge(result: &blackjack::EdgeResult) {
+    println!("House edge:     {:+.4}%", result.house_edge * 100.0);
+    println!("Player return:  {:+.4}%", result.expected_return * 100.0);
+    println!("Std dev/hand:   {:.4} units", result.std_dev_per_hand);
+}
+
+fn print_expected_profit(config: &ContractConfig, house_edge: f64) {
+    let min = config.min_bet.u128() as f64;
+    let max = config.max_bet.u128() as f64;
+    println!("\nExpected dealer profit per 1000 hands:");
+    for (name, bet) in [("min", min), ("average", (min + max) / 2.0), ("max", max)] {
+        println!(
+            "  {name:>7} bet {bet:>12.0} {}: {:+.0} {}",
+            config.denom,
+            house_edge * bet * 1000.0,
+            config.denom
+        );
+    }
+}
+
+/// Kelly from the house side: the largest bet the bankroll should take per hand
+fn house_kelly(result: &blackjack::EdgeResult) -> blackjack::betting::KellyCriterion {
+    blackjack::betting::KellyCriterion {
+        edge_fraction: result.house_edge,
+        variance_per_hand: result.variance_per_hand,
+    }
+}
+
+/// Errors on a player-favourable edge unless `allow_negative_edge`, which only warns
+fn refuse_negative_edge(house_edge: f64, allow_negative_edge: bool) -> Result<(), BoxErr> {
+    if house_edge >= 0.0 {
+        return Ok(());
+    }
+    if allow_negative_edge {
+        tracing::warn!(
+            "Negative house edge ({:+.4}%) — dealer will lose money on average. Proceeding (--allow-negative-edge set).",
+            house_edge * 100.0
+        );
+        Ok(())
+    } else {
+        Err(format!(
+            "Negative house edge ({:+.4}%). This config favors the player and will lose the dealer money. \
+             Use --allow-negative-edge or ALLOW_NEGATIVE_EDGE=true to override.",
+            house_edge * 100.0
+        )
+        .into())
+    }
+}
+
+/// House risk of ruin if every hand were played at `max_bet`, with no profit target
+fn house_risk_of_ruin(result: &blackjack::EdgeResult, bankroll: u128, max_bet: u128) -> f64 {
+    blackjack::risk_of_ruin(blackjack::RiskConfig {
+        edge: result.house_edge,
+        variance: result.variance_per_hand,
+        bankroll_units: bankroll as f64 / max_bet as f64,
+        target_units: f64::INFINITY,
+    })
+    .p_ruin
+}
+
+fn print_kelly(result: &blackjack::EdgeResult, bankroll: Option<u128>, denom: &str) {
+    let kelly = house_kelly(result);
+    let kelly_fraction = kelly.optimal_fraction();
+    match bankroll {
+        Some(bankroll) if kelly_fraction > 0.0 => println!(
+            "Kelly max bet:  {:.4}% of bankroll ({} {denom} at current bankroll)",
+            kelly_fraction * 100.0,
+            kelly.bet_for_bankroll(bankroll, 1.0, 1),
+        ),
+        _ => println!("Kelly max bet:  {:.4}% of bankroll", kelly_fraction * 100.0),
+    }
+}
+
+fn config_rules(config: &ContractConfig) -> Result<blackjack::GameRules, BoxErr> {
+    edge_rules(
+        &config.blackjack_payout,
+        config.dealer_hits_soft_17,
+        config.dealer_peeks,
+        &config.double_restriction,
+        config.max_splits,
+        config.can_split_aces,
+        config.can_hit_split_aces,
+        config.double_after_split,
+        config.surrender_allowed,
+    )
+}
+
+fn config_edge(config: &ContractConfig) -> Result<blackjack::EdgeResult, BoxErr> {
+    Ok(blackjack::compute_edge(&config_rules(config)?))
+}
+
+/// The config with one rule changed, labelled by the current setting of that rule.
+fn rule_variants(config: &ContractConfig) -> Vec<(String, ContractConfig)> {
+    fn flag(name: &str, value: bool) -> String {
+        if value {
+            name.to_string()
+        } else {
+            format!("{name}=false")
+        }
+    }
+
+    let mut variants = Vec::new();
+
+    let mut v = config.clone();
+    v.surrender_allowed = !v.surrender_allowed;
+    variants.push((flag("surrender_allowed", config.surrender_allowed), v));
+
+    let mut v = config.clone();
+    v.dealer_hits_soft_17 = !v.dealer_hits_soft_17;
+    variants.push((flag("dealer_hits_soft_17", config.dealer_hits_soft_17), v));
+
+    let mut v = config.clone();
+    v.dealer_peeks = !v.dealer_peeks;
+    variants.push((flag("dealer_peeks", config.dealer_peeks), v));
+
+    let mut v = config.clone();
+    v.can_hit_split_aces = !v.can_hit_split_aces;
+    variants.push((flag("can_hit_split_aces", config.can_hit_split_aces), v));
+
+    let mut v = config.clone();
+    v.double_restriction = match config.double_restriction {
+        DoubleRestriction::Any => DoubleRestriction::Hard10_11,
+        _ => DoubleRestriction::Any,
+    };
+    let label = match config.double_restriction {
+        DoubleRestriction::Any => "double_restriction=any",
+        DoubleRestriction::Hard9_10_11 => "double_restriction=hard9_10_11",
+        DoubleRestriction::Hard10_11 => "double_restriction=hard10_11",
+    };
+    variants.push((label.to_string(), v));
+
+    let mut v = config.clone();
+    v.max_splits = if config.max_splits > 0 { 0 } else { 1 };
+    variants.push((format!("max_splits={}", config.max_splits), v));
+
+    let mut v = config.clone();
+    let bj = &config.blackjack_payout;
+    v.blackjack_payout = if (bj.numerator, bj.denominator) == (3, 2) {
+        PayoutRatio {
+            numerator: 6,
+            denominator: 5,
+        }
+    } else {
+        PayoutRatio {
+            numerator: 3,
+            denominator: 2,
+        }
+    };
+    variants.push((
+        format!("blackjack_payout={}:{}", bj.numerator, bj.denominator),
+        v,
+    ));
+
+    variants
+}
+
+// ── Export subcommand ──
+
+#[derive(serde::Serialize)]
+struct HandRecord {
+    game_id: u64,
+    settled_at: u64,
+    player: String,
+    hand_index: usize,
+    cards_dealt: String,
+    player_value: u8,
+    dealer_value: u8,
+    bet: u128,
+    outcome: &'static str,
+    payout: u128,
+    net: i128,
+    is_blackjack: bool,
+    was_doubled: bool,
+    was_split: bool,
+    was_surrendered: bool,
+}
+
+const CSV_HEADER: &str = "game_id,settled_at,player,hand_index,cards_dealt,player_value,dealer_value,bet,outcome,payout,net,is_blackjack,was_doubled,was_split,was_surrendered";
+
+impl HandRecord {
+    fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.game_id,
+            self.settled_at,
+            self.player,
+            self.hand_index,
+            self.cards_dealt,
+            self.player_value,
+            self.dealer_value,
+            self.bet,
+            self.outcome,
+            self.payout,
+            self.net,
+            self.is_blackjack,
+            self.was_doubled,
+            self.was_split,
+            self.was_surrendered
+        )
+    }
+}
+
+fn cmd_export(
+    rpc_url: &str,
+    contract_addr: &str,
+    output: &std::path::Path,
+    format: ExportFormat,
+    since_game_id: Option<u64>,
+    since: Option<u64>,
+) -> Result<(), BoxErr> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let config = rt.block_on(query_config(rpc_url, contract_addr))?;
+
+    // Collect IDs first so progress can report a total; details are fetched per batch
+    let mut game_ids = Vec::new();
+    let mut cursor = since_game_id;
+    loop {
+        let page = rt.block_on(query_list_games_page(
+            rpc_url,
+            contract_addr,
+            Some("Settled".into()),
+            EXPORT_BATCH_SIZE as u32,
+            cursor,
+        ))?;
+        game_ids.extend(page.games.iter().map(|g| g.game_id));
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    let total = game_ids.len();
+    tracing::info!("Found {total} settled games");
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(output)?);
+    if let ExportFormat::Csv = format {
+        writeln!(writer, "{CSV_HEADER}")?;
+    }
+
+    let mut processed = 0;
+    let mut exported = 0;
+    let mut hands = 0;
+    for batch in game_ids.chunks(EXPORT_BATCH_SIZE) {
+        for &game_id in batch {
+            let game = rt.block_on(query_game_by_id(rpc_url, contract_addr, game_id))?;
+            if since.is_some_and(|t| game.last_action_timestamp <= t) {
+                continue;
+            }
+            for record in hand_records(game_id, &game, &config) {
+                match format {
+                    ExportFormat::Csv => writeln!(writer, "{}", record.to_csv_row())?,
+                    ExportFormat::Jsonl => writeln!(writer, "{}", serde_json::to_string(&record)?)?,
+                }
+                hands += 1;
+            }
+            exported += 1;
+        }
+        writer.flush()?;
+        processed += batch.len();
+        tracing::info!("Exported {processed}/{total} games...");
+    }
+
+    tracing::info!(
+        "Export complete: {exported} games, {hands} hands written to {}",
+        output.display()
+    );
+    Ok(())
+}
+
+// ── TxHistory subcommand ──
+
+fn cmd_tx_history(output: Option<&std::path::Path>, game_id: Option<u64>) -> Result<(), BoxErr> {
+    let records = TransactionArchive::new(DATA_DIR).read_all()?;
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    let mut count = 0;
+    for record in records
+        .iter()
+        .filter(|r| game_id.is_none() || r.game_id == game_id)
+    {
+        writeln!(writer, "{}", serde_json::to_string(record)?)?;
+        count += 1;
+    }
+    writer.flush()?;
+    tracing::info!(count, "Transactions exported");
+    Ok(())
+}
+
+/// Key file password: the mnemonic storage key the TUI uses for its saved shuffles.
+fn key_password(mnemonic: &str) -> Vec<u8> {
+    storage_key_from_mnemonic(mnemonic).to_vec()
+}
+
+/// A key file eligible for deletion.
+struct StaleKeyFile {
+    game_id: u64,
+    path: PathBuf,
+    size: u64,
+}
+
+/// Delete key files, and the proofs saved with them, for games that settled and for
+/// orphans whose game no longer exists (swept, or a chain reset).
+fn cmd_garbage_collect(
+    rpc_url: &str,
+    contract_addr: &str,
+    dry_run: bool,
+    min_age_hours: Option<u64>,
+) -> Result<(), BoxErr> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let key_store = KeyStore::new(DATA_DIR);
+    let proof_log = ProofLog::new(DATA_DIR);
+    let min_age = std::time::Duration::from_secs(min_age_hours.unwrap_or(0) * 3600);
+
+    let mut settled = Vec::new();
+    let mut orphaned = Vec::new();
+    let mut active = Vec::new();
+    let mut too_recent = 0;
+    for game_id in key_store.list_game_ids()? {
+        let path = key_store.key_path(game_id);
+        let metadata = std::fs::metadata(&path)?;
+        // A clock skewed into the future counts as brand new
+        let age = metadata.modified()?.elapsed().unwrap_or_default();
+        if age < min_age {
+            too_recent += 1;
+            continue;
+        }
+
+        let file = StaleKeyFile {
+            game_id,
+            path,
+            size: metadata.len(),
+        };
+        match rt.block_on(query_game_by_id(rpc_url, contract_addr, game_id)) {
+            Ok(game) if is_finished(&game.status) => settled.push(file),
+            Ok(_) => active.push(game_id),
+            Err(e) if e.to_string().contains("not found") => orphaned.push(file),
+            Err(e) => return Err(format!("Query for game {game_id} failed: {e}").into()),
+        }
+    }
+
+    for file in settled.iter().chain(&orphaned) {
+        if dry_run {
+            println!(
+                "Would delete {} (game #{})",
+                file.path.display(),
+                file.game_id
+            );
+        } else {
+            key_store.delete_game_keys(file.game_id)?;
+            proof_log.delete(file.game_id)?;
+            tracing::debug!(game_id = file.game_id, "Deleted key file");
+        }
+    }
+
+    let verb = if dry_run {
+        "Would clean up"
+    } else {
+        "Cleaned up"
+    };
+    let active_ids: Vec<String> = active.iter().map(|id| format!("#{id}")).collect();
+    let kept = match active.len() {
+        0 => "kept 0".to_string(),
+        1 => format!("kept 1 for active game {}", active_ids[0]),
+        n => format!("kept {n} for active games {}", active_ids.join(", ")),
+    };
+    let mut summary = format!(
+        "{verb} {} settled key files ({}) and {} orphaned key files ({}), {kept}",
+        settled.len(),
+        format_size(settled.iter().map(|f| f.size).sum()),
+        orphaned.len(),
+        format_size(orphaned.iter().map(|f| f.size).sum()),
+    );
+    if too_recent > 0 {
+        summary.push_str(&format!(
+            ", skipped {too_recent} newer than {} hours",
+            min_age_hours.unwrap_or(0)
+        ));
+    }
+    println!("{summary}");
+    Ok(())
+}
+
+/// Send `UpdateConfig`, first checking the house edge under the new rules the same way
+/// `init` does. Bet limits and the timeout don't move the edge, so it is only
+/// recomputed when surrender changes.
+#[allow(clippy::too_many_arguments)]
+fn cmd_update_config(
+    client: &Client,
+    rpc_url: &str,
+    contract_addr: &str,
+    min_bet: Option<u128>,
+    max_bet: Option<u128>,
+    timeout_seconds: Option<u64>,
+    surrender_allowed: Option<bool>,
+    allow_negative_edge: bool,
+) -> Result<(), BoxErr> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let config = rt.block_on(query_config(rpc_url, contract_addr))?;
+
+    if let Some(surrender) = surrender_allowed.filter(|&s| s != config.surrender_allowed) {
+        let updated = ContractConfig {
+            surrender_allowed: surrender,
+            ..config.clone()
+        };
+        tracing::info!("Computing house edge with surrender_allowed={surrender}...");
+        let result = config_edge(&updated)?;
+        tracing::info!(
+            "House edge: {:+.4}% (was {:+.4}%)",
+            result.house_edge * 100.0,
+            config_edge(&config)?.house_edge * 100.0
+        );
+        refuse_negative_edge(result.house_edge, allow_negative_edge)?;
+    }
+
+    let msg_bytes = serde_json::to_vec(&serde_json::json!({
+        "update_config": {
+            "min_bet": min_bet.map(|v| v.to_string()),
+            "max_bet": max_bet.map(|v| v.to_string()),
+            "timeout_seconds": timeout_seconds,
+            "surrender_allowed": surrender_allowed,
+        }
+    }))?;
+    let tx = execute_and_confirm(
+        client,
+        contract_addr.to_string(),
+        msg_bytes,
+        vec![],
+        "Update config",
+    )?;
+    if tx.code != 0 {
+        return Err(format!("UpdateConfig failed: {}", tx.raw_log).into());
     }
-}
 
-// ── Init subcommand ──
+    let config = rt.block_on(query_config(rpc_url, contract_addr))?;
+    println!(
+        "Config v{}: bets {}-{} {}, timeout {}s, surrender {}",
+        config.version,
+        config.min_bet,
+        config.max_bet,
+        config.denom,
+        config.timeout_seconds,
+        config.surrender_allowed
+    );
+    println!("Games created before this keep the terms they started with");
+    Ok(())
+}
 
-#[allow(clippy::too_many_arguments)]
-fn cmd_init(
+/// Close a stuck game with `ClaimTimeout`. The contract has no admin override, so a
+/// game that isn't timeout-eligible yet can only be waited out.
+fn cmd_kill_game(
     client: &Client,
-    sender: &str,
-    code_id: u64,
-    bankroll: u128,
-    denom: &str,
-    min_bet: u128,
-    max_bet: u128,
-    blackjack_payout: &str,
-    dealer_hits_soft_17: bool,
-    dealer_peeks: bool,
-    double_restriction: &str,
-    max_splits: u32,
-    can_split_aces: bool,
-    can_hit_split_aces: bool,
-    surrender_allowed: bool,
-    shuffle_vk_id: &str,
-    reveal_vk_id: &str,
-    timeout_seconds: u64,
-    label: &str,
-    allow_negative_edge: bool,
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+    force: bool,
+    dry_run: bool,
 ) -> Result<(), BoxErr> {
-    // Build InstantiateMsg
-    let bj_payout = parse_payout_ratio(blackjack_payout)?;
-    let double_res = parse_double_restriction(double_restriction)?;
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let game = rt.block_on(query_game_by_id(rpc_url, contract_addr, game_id))?;
+    if is_finished(&game.status) {
+        println!("Game #{game_id} is already over: {}", game.status);
+        return Ok(());
+    }
+    let contract_config = rt.block_on(query_config(rpc_url, contract_addr))?;
+    let balance_before = rt
+        .block_on(query_dealer_balance(rpc_url, contract_addr))?
+        .balance;
+    println!(
+        "Game #{game_id}: {}, player {}, bet {}",
+        game.status, game.player, game.bet
+    );
 
-    // Edge check: compute house edge for this configuration
-    {
-        let edge_double_restriction = match &double_res {
-            DoubleRestriction::Any => blackjack::DoubleRestriction::Any,
-            DoubleRestriction::Hard9_10_11 => blackjack::DoubleRestriction::Hard9_10_11,
-            DoubleRestriction::Hard10_11 => blackjack::DoubleRestriction::Hard10_11,
+    if dry_run {
+        // Local clock; the contract compares against block time
+        let now = chrono::Utc::now().timestamp().max(0) as u64;
+        let elapsed = now.saturating_sub(game.last_action_timestamp);
+        let timeout_seconds = game
+            .terms
+            .as_ref()
+            .map_or(contract_config.timeout_seconds, |t| t.timeout_seconds);
+        match timeout_seconds.checked_sub(elapsed) {
+            Some(wait) if wait > 0 => {
+                println!("Would fail: timeout not yet eligible — wait {wait} more seconds")
+            }
+            _ => println!("Would send ClaimTimeout ({elapsed}s since the last action)"),
+        }
+        return Ok(());
+    }
+
+    if !force && !confirm(&format!("Claim timeout on game #{game_id}? [y/N] "))? {
+        println!("Aborted");
+        return Ok(());
+    }
+
+    let msg_bytes =
+        serde_json::to_vec(&serde_json::json!({ "claim_timeout": { "game_id": game_id } }))?;
+    let failure = match execute_and_confirm(
+        client,
+        contract_addr.to_string(),
+        msg_bytes,
+        vec![],
+        "Kill game",
+    ) {
+        Ok(tx) if tx.code == 0 => None,
+        Ok(tx) => Some(tx.raw_log),
+        Err(e) => Some(e.to_string()),
+    };
+    if let Some(log) = failure {
+        let advice = if let Some(wait) = timeout_wait(&log) {
+            format!("Timeout not yet eligible — wait {wait} more seconds")
+        } else if log.contains("No active turn") {
+            "Neither side is blocking the game, so there is no timeout to claim".to_string()
+        } else {
+            format!("ClaimTimeout failed: {log}")
         };
-        let edge_payout = blackjack::PayoutRatio::new(bj_payout.numerator, bj_payout.denominator)
-            .map_err(|e| format!("Invalid payout ratio: {e}"))?;
+        return Err(advice.into());
+    }
 
-        let rules = blackjack::GameRules {
-            num_decks: 1, // Always single deck (ZK shuffle architecture)
-            dealer_hits_soft_17,
-            allow_surrender: surrender_allowed,
-            late_surrender: surrender_allowed,
-            double_after_split: true,
-            double_restriction: edge_double_restriction,
-            allow_resplit: max_splits > 1,
-            max_splits: max_splits as u8,
-            resplit_aces: can_split_aces && can_hit_split_aces,
-            dealer_peeks,
-            blackjack_payout: edge_payout,
+    let game = rt.block_on(query_game_by_id(rpc_url, contract_addr, game_id))?;
+    let balance_after = rt
+        .block_on(query_dealer_balance(rpc_url, contract_addr))?
+        .balance;
+    let change = balance_after.u128() as i128 - balance_before.u128() as i128;
+    println!(
+        "Game #{game_id}: {}; dealer balance {balance_before} -> {balance_after} ({change:+} {})",
+        game.status, contract_config.denom
+    );
+    Ok(())
+}
+
+/// Seconds still to wait, from the contract's "Timeout not reached. Elapsed: Xs, Required: Ys".
+fn timeout_wait(log: &str) -> Option<u64> {
+    let (_, rest) = log.split_once("Elapsed: ")?;
+    let (elapsed, rest) = rest.split_once("s, Required: ")?;
+    let required = rest.split('s').next()?;
+    Some(
+        required
+            .parse::<u64>()
+            .ok()?
+            .saturating_sub(elapsed.parse().ok()?),
+    )
+}
+
+/// Check a game against our key file and the proofs `ProofLog` saved for it: each of
+/// our reveal proofs, the card value each fully revealed card decrypts to, and our
+/// shuffle proof. Prints one row per revealed card; returns false if any check failed.
+fn cmd_verify_deck(
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+    key_password: &[u8],
+    vk_override: Option<&std::path::Path>,
+    audit: bool,
+) -> Result<bool, BoxErr> {
+    let (sk, pk) = KeyStore::new(DATA_DIR)
+        .load_game_keys(game_id, key_password)
+        .map_err(|e| format!("No usable key file for game #{game_id}: {e}"))?;
+    let keys = VerificationKeys::load(vk_override)?;
+    let proofs = ProofLog::new(DATA_DIR).load(game_id)?;
+
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let game = rt.block_on(query_game_by_id(rpc_url, contract_addr, game_id))?;
+    let history = rt.block_on(query_reveal_history(rpc_url, contract_addr, game_id))?;
+
+    let card_scalars: Vec<Fr> = (1..=52u64).map(Fr::from).collect();
+    let card_map = CardMap::from_params(&Point::generator(), &card_scalars);
+    let point_from = |bytes: &[u8]| -> Result<Point, BoxErr> {
+        Point::deserialize_compressed(bytes).map_err(|e| format!("Bad point: {e}").into())
+    };
+
+    let mut all_valid = true;
+    println!(
+        "{:>5}  {:>7}  {:<8}  combined point",
+        "card", "claimed", "proof"
+    );
+    for record in &history {
+        let claimed = record
+            .revealed_value
+            .map_or("-".to_string(), |v| v.to_string());
+        let ciphertext = deck_ciphertext(&game, record.card_index)?;
+
+        // Our partial on-chain must be the one our key produces, under a proof that verifies
+        let player_partial = record
+            .player_partial
+            .as_ref()
+            .map(|p| point_from(p.as_slice()))
+            .transpose()?;
+        let (reveal, trail) =
+            reveal_card_with_audit(&sk, &ciphertext, &pk, player_partial, &card_map);
+        let status = match (
+            &record.dealer_partial,
+            proofs.reveals.get(&record.card_index),
+        ) {
+            (None, _) => "PENDING",
+            (Some(_), None) => "MISSING",
+            (Some(partial), Some(proof)) => {
+                let ours = serialize_point(&reveal.partial_decryption)?;
+                if partial.as_slice() == ours.as_slice()
+                    && verify_reveal_proof(&keys.reveal, proof, &reveal.public_inputs)?
+                {
+                    "VALID"
+                } else {
+                    "INVALID"
+                }
+            }
         };
 
-        log::info!("Computing house edge for this configuration...");
-        let result = blackjack::EdgeCalculator::new(rules).calculate();
-        log::info!(
-            "House edge: {:+.4}% (player return: {:+.4}%)",
-            result.house_edge * 100.0,
-            result.expected_return * 100.0
+        // A dealer partial other than ours is already INVALID, so ours stands in for it
+        let combined = (record.player_partial.is_some() && record.dealer_partial.is_some())
+            .then_some(trail.revealed_point);
+        let value_matches = match combined {
+            Some(_) => trail.verify_combination() && trail.matched_card == record.revealed_value,
+            None => record.revealed_value.is_none(),
+        };
+        let status = if value_matches { status } else { "INVALID" };
+        if status == "INVALID" || status == "MISSING" {
+            all_valid = false;
+        }
+
+        let combined_hex = match combined {
+            Some(point) => serialize_point(&point)?
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:>5}  {claimed:>7}  {status:<8}  {combined_hex}",
+            record.card_index
         );
+        if audit {
+            print_audit_trail(&trail)?;
+        }
+    }
 
-        if result.house_edge < 0.0 {
-            if allow_negative_edge {
-                log::warn!(
-                    "Negative house edge ({:+.4}%) — dealer will lose money on average. Proceeding (--allow-negative-edge set).",
-                    result.house_edge * 100.0
-                );
+    let shuffle_status = match &proofs.shuffle {
+        None => "MISSING",
+        Some(shuffle) => {
+            if verify_shuffle_proof(&keys.shuffle, &shuffle.proof, &shuffle.public_inputs)? {
+                "VALID"
             } else {
-                return Err(format!(
-                    "Negative house edge ({:+.4}%). This config favors the player and will lose the dealer money. \
-                     Use --allow-negative-edge or ALLOW_NEGATIVE_EDGE=true to override.",
-                    result.house_edge * 100.0
-                ).into());
+                "INVALID"
             }
         }
+    };
+    if shuffle_status != "VALID" {
+        all_valid = false;
     }
+    println!("Shuffle proof: {shuffle_status}");
+    Ok(all_valid)
+}
 
-    let instantiate_msg = InstantiateMsg {
-        denom: denom.to_string(),
-        min_bet: cosmwasm_std::Uint128::new(min_bet),
-        max_bet: cosmwasm_std::Uint128::new(max_bet),
-        blackjack_payout: bj_payout,
-        insurance_payout: PayoutRatio {
-            numerator: 2,
-            denominator: 1,
-        },
-        standard_payout: PayoutRatio {
-            numerator: 1,
-            denominator: 1,
-        },
-        dealer_hits_soft_17,
-        dealer_peeks,
-        double_restriction: double_res,
-        max_splits,
-        can_split_aces,
-        can_hit_split_aces,
-        surrender_allowed,
-        shuffle_vk_id: shuffle_vk_id.to_string(),
-        reveal_vk_id: reveal_vk_id.to_string(),
-        timeout_seconds: Some(timeout_seconds),
+/// One point per line, indented under its `verify-deck` row.
+fn print_audit_trail(trail: &RevealAuditTrail) -> Result<(), BoxErr> {
+    let hex = |point: &Point| -> Result<String, BoxErr> {
+        Ok(serialize_point(point)?
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect())
     };
-    let msg_bytes = serde_json::to_vec(&instantiate_msg)?;
+    println!("         c0        {}", hex(&trail.ciphertext.c0)?);
+    println!("         c1        {}", hex(&trail.ciphertext.c1)?);
+    println!("         dealer    {}", hex(&trail.my_partial)?);
+    println!("         player    {}", hex(&trail.their_partial)?);
+    println!("         combined  {}", hex(&trail.combined_partial)?);
+    println!("         revealed  {}", hex(&trail.revealed_point)?);
+    println!(
+        "         card      {}  (combination {})",
+        trail
+            .matched_card
+            .map_or("-".to_string(), |v| v.to_string()),
+        if trail.verify_combination() {
+            "consistent"
+        } else {
+            "INCONSISTENT"
+        }
+    );
+    Ok(())
+}
 
-    // 4. Instantiate with bankroll funds
-    let funds = if bankroll > 0 {
-        vec![mob::Coin::new(denom, bankroll.to_string())]
-    } else {
-        vec![]
+/// The encrypted card at `card_index` in the game's final deck.
+fn deck_ciphertext(game: &GameResponse, card_index: u32) -> Result<Ciphertext, BoxErr> {
+    let card = game
+        .deck
+        .get(card_index as usize)
+        .ok_or_else(|| format!("Card {card_index} is not in the deck"))?;
+    let mut cursor = card.as_slice();
+    Ok(Ciphertext {
+        c0: Point::deserialize_compressed(&mut cursor)
+            .map_err(|e| format!("Failed to deserialize card c0: {e}"))?,
+        c1: Point::deserialize_compressed(&mut cursor)
+            .map_err(|e| format!("Failed to deserialize card c1: {e}"))?,
+    })
+}
+
+fn cmd_inspect_game(
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+    key_password: Option<&[u8]>,
+) -> Result<bool, BoxErr> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let game = rt.block_on(query_game_by_id(rpc_url, contract_addr, game_id))?;
+    // The stored game, when the contract has debug_mode on
+    let state = rt
+        .block_on(query_contract_state(rpc_url, contract_addr, game_id))
+        .map_err(|e| tracing::info!("No internal state, GetContractState refused: {e}"))
+        .ok();
+    let history = match &state {
+        Some(state) => state.reveal_history.clone(),
+        None => rt.block_on(query_reveal_history(rpc_url, contract_addr, game_id))?,
     };
 
-    log::info!("Instantiating contract (code_id={code_id}, bankroll={bankroll} {denom})...");
-    let inst_response =
-        broadcast_and_confirm_instantiate(client, sender, code_id, label, msg_bytes, funds)?;
-    let contract_addr = extract_contract_address(&inst_response)?;
-    log::info!("Contract instantiated: tx={}", inst_response.txhash);
+    // A key file means this is one of our games; without one there is nothing to check
+    let keys = key_password.and_then(|password| {
+        KeyStore::new(DATA_DIR)
+            .load_game_keys(game_id, password)
+            .map_err(|e| tracing::info!("Partials not checked, no usable key file: {e}"))
+            .ok()
+    });
+    if key_password.is_none() {
+        tracing::info!("Partials not checked: pass the mnemonic to load the game's key file");
+    }
+    let mut consistent = true;
+    if let Some((_, pk)) = &keys {
+        if serialize_point(pk)?.as_slice() != game.dealer_pubkey.as_slice() {
+            println!("Key file public key differs from the game's dealer key: MISMATCH");
+            consistent = false;
+        }
+    }
 
-    // 5. Print for .env
-    println!("\nCONTRACT_ADDR={contract_addr}");
-    log::info!("Done. Add CONTRACT_ADDR to your .env file.");
+    let mut rows = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let pending = game.pending_reveals.iter().map(|p| RevealRecord {
+        card_index: p.card_index,
+        player_partial: p.player_partial.clone(),
+        dealer_partial: p.dealer_partial.clone(),
+        combined_result: None,
+        revealed_value: None,
+        block_height: 0,
+    });
+    for record in history.into_iter().chain(pending) {
+        if !seen.insert(record.card_index) {
+            continue;
+        }
+        let check = match (&keys, &record.dealer_partial) {
+            (None, _) => inspect::PartialCheck::Unchecked,
+            (Some(_), None) => inspect::PartialCheck::Pending,
+            (Some((sk, pk)), Some(stored)) => {
+                let ciphertext = deck_ciphertext(&game, record.card_index)?;
+                let ours = serialize_point(&reveal_card(sk, &ciphertext, pk).partial_decryption)?;
+                if ours.as_slice() == stored.as_slice() {
+                    inspect::PartialCheck::Match
+                } else {
+                    consistent = false;
+                    inspect::PartialCheck::Mismatch
+                }
+            }
+        };
+        rows.push(inspect::CardRow {
+            card_index: record.card_index,
+            value: record.revealed_value,
+            player_partial: record.player_partial.map(|b| b.to_vec()),
+            dealer_partial: record.dealer_partial.map(|b| b.to_vec()),
+            block_height: (record.block_height > 0).then_some(record.block_height),
+            check,
+        });
+    }
+    rows.sort_by_key(|row| row.card_index);
 
-    Ok(())
+    let txs: Vec<TxRecord> = TransactionArchive::new(DATA_DIR)
+        .read_all()?
+        .into_iter()
+        .filter(|tx| tx.game_id == Some(game_id))
+        .collect();
+    let now = chrono::Utc::now().timestamp().max(0) as u64;
+    let color = std::io::IsTerminal::is_terminal(&std::io::stdout());
+    print!(
+        "{}",
+        inspect::render(game_id, &game, &rows, &txs, now, color)
+    );
+    if let Some(state) = &state {
+        println!(
+            "\nInternal: turn {:?}, current hand {}, next card {}, dealer peeked {}, bankroll {}",
+            state.game.current_turn,
+            state.game.current_hand_index,
+            state.game.last_card_index,
+            state.game.dealer_peeked,
+            state.game.bankroll
+        );
+    }
+    Ok(consistent)
+}
+
+/// Settled, forfeited by the dealer or emergency-refunded: nothing more will happen
+/// in the game.
+fn is_finished(status: &str) -> bool {
+    status.starts_with("Settled")
+        || status.starts_with("Forfeited")
+        || status.starts_with("EmergencyRefunded")
+}
+
+/// Ask on stdin; anything but "y" or "yes" is a no.
+fn confirm(prompt: &str) -> Result<bool, BoxErr> {
+    print!("{prompt}");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+/// Human-readable file size, e.g. "3.2 KB".
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Flatten a settled game into one record per player hand.
+fn hand_records(game_id: u64, game: &GameResponse, config: &ContractConfig) -> Vec<HandRecord> {
+    let to_cards = |indices: &[u8]| -> Vec<blackjack::Card> {
+        indices
+            .iter()
+            .map(|&idx| blackjack::Card::from_index(idx as usize))
+            .collect()
+    };
+    let dealer_value = blackjack::calculate_hand_value(&to_cards(&game.dealer_hand)).total;
+    let was_split = game.hands.len() > 1;
+
+    game.hands
+        .iter()
+        .enumerate()
+        .map(|(hand_index, hand)| {
+            let cards = to_cards(&hand.cards);
+            let (outcome, payout) = hand_outcome(hand, &game.status, config);
+            let bet = hand.bet.u128();
+            HandRecord {
+                game_id,
+                settled_at: game.last_action_timestamp,
+                player: game.player.clone(),
+                hand_index,
+                cards_dealt: cards
+                    .iter()
+                    .map(|c| c.to_display())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                player_value: blackjack::calculate_hand_value(&cards).total,
+                dealer_value,
+                bet,
+                outcome,
+                payout,
+                net: payout as i128 - bet as i128,
+                is_blackjack: outcome == "player_blackjack",
+                was_doubled: hand.bet > game.bet,
+                was_split,
+                was_surrendered: outcome == "surrender",
+            }
+        })
+        .collect()
+}
+
+/// Outcome label and amount returned to the player for one hand, mirroring the
+/// contract's payout rules. Hands left unsettled by a timeout use the game winner.
+fn hand_outcome(
+    hand: &HandResponse,
+    game_status: &str,
+    config: &ContractConfig,
+) -> (&'static str, u128) {
+    let bet = hand.bet;
+    if hand.status == "Surrendered" {
+        return ("surrender", bet.u128() / 2);
+    }
+    if hand.status.starts_with("Settled") {
+        return if hand.status.contains("Player (Blackjack)") {
+            (
+                "player_blackjack",
+                (bet + config.blackjack_payout.calculate_payout(bet)).u128(),
+            )
+        } else if hand.status.contains("\"Player\"") {
+            (
+                "player",
+                (bet + config.standard_payout.calculate_payout(bet)).u128(),
+            )
+        } else if hand.status.contains("Push") {
+            ("push", bet.u128())
+        } else {
+            ("dealer", 0)
+        };
+    }
+    if game_status.contains("\"Player\"") {
+        ("timeout_player", bet.u128() * 2)
+    } else {
+        ("timeout_dealer", 0)
+    }
+}
+
+/// Map contract rule settings onto the edge calculator's `GameRules`.
+#[allow(clippy::too_many_arguments)]
+fn edge_rules(
+    blackjack_payout: &PayoutRatio,
+    dealer_hits_soft_17: bool,
+    dealer_peeks: bool,
+    double_restriction: &DoubleRestriction,
+    max_splits: u32,
+    can_split_aces: bool,
+    can_hit_split_aces: bool,
+    double_after_split: bool,
+    surrender_allowed: bool,
+) -> Result<blackjack::GameRules, BoxErr> {
+    let edge_double_restriction = match double_restriction {
+        DoubleRestriction::Any => blackjack::DoubleRestriction::Any,
+        DoubleRestriction::Hard9_10_11 => blackjack::DoubleRestriction::Hard9_10_11,
+        DoubleRestriction::Hard10_11 => blackjack::DoubleRestriction::Hard10_11,
+    };
+    let edge_payout =
+        blackjack::PayoutRatio::new(blackjack_payout.numerator, blackjack_payout.denominator)
+            .map_err(|e| format!("Invalid payout ratio: {e}"))?;
+
+    Ok(blackjack::GameRules {
+        num_decks: 1, // Always single deck (ZK shuffle architecture)
+        dealer_hits_soft_17,
+        allow_surrender: surrender_allowed,
+        late_surrender: surrender_allowed,
+        double_after_split,
+        double_restriction: edge_double_restriction,
+        allow_resplit: max_splits > 1,
+        max_splits: max_splits as u8,
+        resplit_aces: false, // Not set on instantiate, so the contract default
+        dealer_peeks,
+        blackjack_payout: edge_payout,
+        insurance_available: dealer_peeks, // Offered before the peek only
+        insurance_payout: blackjack::PayoutRatio::TWO_TO_ONE, // Fixed at instantiation
+    })
 }
 
 fn broadcast_and_confirm_instantiate(
@@ -428,7 +2306,7 @@ fn broadcast_and_confirm_instantiate(
     if broadcast.code != 0 {
         return Err(format!("Instantiate broadcast rejected: {}", broadcast.raw_log).into());
     }
-    log::info!("Instantiate TX broadcast: {}", broadcast.txhash);
+    tracing::info!(txhash = %broadcast.txhash, "Instantiate TX broadcast");
     poll_tx(client, &broadcast.txhash)
 }
 
@@ -480,6 +2358,19 @@ fn extract_contract_address(tx: &mob::TxResponse) -> Result<String, BoxErr> {
     .into())
 }
 
+/// The `game_id` attribute of a CreateGame tx's wasm event, if the raw_log carries events.
+fn extract_game_id(tx: &mob::TxResponse) -> Option<u64> {
+    let events: serde_json::Value = serde_json::from_str(&tx.raw_log).ok()?;
+    events
+        .as_array()?
+        .iter()
+        .filter(|event| event.get("type").and_then(|t| t.as_str()) == Some("wasm"))
+        .filter_map(|event| event.get("attributes")?.as_array())
+        .flatten()
+        .find(|attr| attr.get("key").and_then(|k| k.as_str()) == Some("game_id"))
+        .and_then(|attr| attr.get("value")?.as_str()?.parse().ok())
+}
+
 fn parse_payout_ratio(s: &str) -> Result<PayoutRatio, BoxErr> {
     let parts: Vec<&str> = s.split(':').collect();
     if parts.len() != 2 {
@@ -503,29 +2394,233 @@ fn parse_double_restriction(s: &str) -> Result<DoubleRestriction, BoxErr> {
     }
 }
 
-// ── Run subcommand helpers ──
-
-fn run_game(client: &Client, config: &DealerConfig, address: &str) -> Result<(), BoxErr> {
-    let (sk, pk, game_id) = create_game(client, config, address)?;
-
-    let key_path = format!("data/game_{game_id}_keys.bin");
-    save_keys(&key_path, &sk, &pk)?;
-    log::info!("Keys saved to {key_path}");
+// ── Run subcommand helpers ──
+
+/// How often `run_pool` reaps finished games and starts new ones
+const POOL_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// How often a game waiting to be created checks whether the contract is still paused
+const PAUSE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Up to N games at once, for `run --concurrent-games N`. Games are blocking tasks (the
+/// game code builds its own runtimes), each holding a semaphore permit until it ends.
+struct GamePool {
+    semaphore: Arc<tokio::sync::Semaphore>,
+    /// Keyed by task number, since a game's ID is only known once it's created
+    active: HashMap<u64, tokio::task::JoinHandle<Result<(), BoxErr>>>,
+    runtime: tokio::runtime::Runtime,
+    next_task: u64,
+}
+
+impl GamePool {
+    fn new(limit: usize) -> Result<Self, BoxErr> {
+        Ok(GamePool {
+            semaphore: Arc::new(tokio::sync::Semaphore::new(limit)),
+            active: HashMap::new(),
+            runtime: tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+            next_task: 0,
+        })
+    }
+
+    /// Starts `task` if fewer than N are running; false if not.
+    fn try_spawn<F>(&mut self, task: F) -> bool
+    where
+        F: FnOnce() -> Result<(), BoxErr> + Send + 'static,
+    {
+        let Ok(permit) = Arc::clone(&self.semaphore).try_acquire_owned() else {
+            return false;
+        };
+        let id = self.next_task;
+        self.next_task += 1;
+        let handle = self.runtime.spawn_blocking(move || {
+            let _permit = permit;
+            let _span = tracing::info_span!("task", id).entered();
+            task()
+        });
+        self.active.insert(id, handle);
+        true
+    }
+
+    /// Removes the tasks that have ended, with how each one ended.
+    fn reap(&mut self) -> Vec<(u64, Result<(), BoxErr>)> {
+        let done: Vec<u64> = self
+            .active
+            .iter()
+            .filter(|(_, handle)| handle.is_finished())
+            .map(|(&id, _)| id)
+            .collect();
+        done.into_iter()
+            .filter_map(|id| self.active.remove(&id).map(|handle| (id, handle)))
+            .map(|(id, handle)| match self.runtime.block_on(handle) {
+                Ok(result) => (id, result),
+                Err(e) => (id, Err(format!("game task panicked: {e}").into())),
+            })
+            .collect()
+    }
+
+    fn active_count(&self) -> usize {
+        self.active.len()
+    }
+}
+
+/// Keeps `limit` games going, each with its own deck and key file. Without
+/// auto-create, plays `limit` games and returns once they're all done.
+fn run_pool(
+    client: Arc<Client>,
+    config: Arc<DealerConfig>,
+    address: String,
+    card_points: Arc<Vec<Point>>,
+    limit: usize,
+) {
+    let mut pool = match GamePool::new(limit) {
+        Ok(pool) => pool,
+        Err(e) => {
+            tracing::error!("Failed to start the game pool: {e}");
+            std::process::exit(1);
+        }
+    };
+    tracing::info!(limit, "Running games concurrently");
+    let mut started = 0;
+    loop {
+        for (task, result) in pool.reap() {
+            match result {
+                Ok(()) => tracing::info!(task, "Game completed"),
+                Err(e) => tracing::error!(task, "Game failed: {e}"),
+            }
+            if let Err(e) = check_and_topup(&client, &config, &address) {
+                tracing::error!("Auto top-up failed: {e}");
+            }
+        }
+
+        while config.auto_create_game || started < limit {
+            let (client, config, address, card_points) = (
+                Arc::clone(&client),
+                Arc::clone(&config),
+                address.clone(),
+                Arc::clone(&card_points),
+            );
+            let spawned = pool.try_spawn(move || {
+                let deck = prepare_deck(&card_points);
+                run_game(&client, &config, &address, deck, None, &mut None)
+            });
+            if !spawned {
+                break;
+            }
+            started += 1;
+        }
+
+        if !config.auto_create_game && pool.active_count() == 0 {
+            tracing::info!("AUTO_CREATE_GAME=false, exiting");
+            return;
+        }
+        std::thread::sleep(POOL_POLL_INTERVAL);
+    }
+}
+
+/// Plays one game with `deck`. With `pregenerate`, the next game's deck is shuffled
+/// and proven into `next_deck` in the background once this one is created.
+fn run_game(
+    client: &Client,
+    config: &DealerConfig,
+    address: &str,
+    deck: (KeyPair, ShuffleResult),
+    pregenerate: Option<&[Point]>,
+    next_deck: &mut Option<std::thread::JoinHandle<(KeyPair, ShuffleResult)>>,
+) -> Result<(), BoxErr> {
+    wait_while_paused(config)?;
+    let (sk, pk, game_id) = create_game(client, config, address, deck)?;
+    if let Some(card_points) = pregenerate {
+        let prover = Arc::clone(&config.prover);
+        let card_points = card_points.to_vec();
+        *next_deck = Some(std::thread::spawn(move || {
+            let deck = prepare_deck(&card_points);
+            if let Err(e) = pregenerate_proof(prover.as_ref(), &deck.1) {
+                tracing::warn!("Proof pre-generation failed, the next game will prove: {e}");
+            }
+            deck
+        }));
+    }
+
+    save_keys(config, game_id, &sk, &pk)?;
+    tracing::info!(game_id, "Keys saved");
+
+    // Keys and proofs stay in DATA_DIR after the game so `verify-deck` can check it;
+    // `garbage-collect` removes them
+    game_loop(client, config, game_id, &sk, &pk)
+}
+
+/// Blocks until the contract accepts new games. Games already running keep playing;
+/// only creating the next one waits.
+fn wait_while_paused(config: &DealerConfig) -> Result<(), BoxErr> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let mut warned = false;
+    loop {
+        let status = rt.block_on(query_pause_status(&config.rpc_url, &config.contract_addr))?;
+        if !status.paused {
+            if warned {
+                tracing::info!("Contract resumed, creating the next game");
+            }
+            return Ok(());
+        }
+        if !warned {
+            tracing::warn!(
+                paused_at = ?status.paused_at,
+                "Contract is paused, waiting before creating a game"
+            );
+            warned = true;
+        }
+        std::thread::sleep(PAUSE_POLL_INTERVAL);
+    }
+}
+
+/// A fresh dealer keypair and the card points encrypted under it and shuffled.
+fn prepare_deck(card_points: &[Point]) -> (KeyPair, ShuffleResult) {
+    tracing::info!("Generating dealer keypair and shuffling deck...");
+    let mut rng = ChaCha8Rng::from_entropy();
+    let dealer_keys = KeyPair::generate(&mut rng);
+    let encrypted_deck = encrypt_batch_random(&mut rng, &dealer_keys.pk, card_points);
+    let dealer_shuffle = shuffle(&mut rng, &encrypted_deck, &dealer_keys.pk);
+    (dealer_keys, dealer_shuffle)
+}
 
-    let result = game_loop(client, config, game_id, &sk, &pk);
+fn proof_cache() -> ProofCache {
+    ProofCache::new(
+        std::path::Path::new(DATA_DIR).join(PROOF_CACHE_DIR),
+        PROOF_CACHE_ENTRIES,
+    )
+}
 
-    // Clean up key file after game completes (settled or timed out)
-    if std::fs::remove_file(&key_path).is_ok() {
-        log::debug!("Cleaned up {key_path}");
-    }
+/// Proves `dealer_shuffle` into the proof cache, where `create_game` picks it up.
+fn pregenerate_proof(prover: &dyn Prover, dealer_shuffle: &ShuffleResult) -> Result<(), BoxErr> {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let _rt_guard = rt.enter();
 
-    result
+    dealer_shuffle.public_inputs.validate()?;
+    let key = ProofCache::deck_key(&dealer_shuffle.deck)?;
+    tracing::info!("Pre-generating shuffle proof for the next game...");
+    let proof = prover.prove_shuffle(
+        &dealer_shuffle.public_inputs,
+        dealer_shuffle.private_inputs.clone(),
+    )?;
+    let inputs = ProofInputSerializer::fields_to_strings(
+        &dealer_shuffle.public_inputs.to_ark_public_inputs(),
+    );
+    proof_cache().store(&key, proof, inputs)?;
+    tracing::info!("Next game's shuffle proof is ready");
+    Ok(())
 }
 
 fn create_game(
     client: &Client,
     config: &DealerConfig,
     address: &str,
+    (dealer_keys, dealer_shuffle): (KeyPair, ShuffleResult),
 ) -> Result<(Fr, Point, u64), BoxErr> {
     // Build a local tokio runtime for proof generation (WASM calculator needs reactor)
     let rt = tokio::runtime::Builder::new_current_thread()
@@ -533,43 +2628,34 @@ fn create_game(
         .build()?;
     let _rt_guard = rt.enter();
 
-    log::info!("Generating dealer keypair and shuffling deck...");
-    let mut rng = ChaCha8Rng::from_entropy();
-    let dealer_keys = KeyPair::generate(&mut rng);
-
-    let g = Point::generator();
-    let mut encrypted_deck = Vec::new();
-    for i in 1..=52u64 {
-        let card_point = (g.into_group() * Fr::from(i)).into_affine();
-        let r = Fr::rand(&mut rng);
-        let ct = encrypt(&dealer_keys.pk, &card_point, &r);
-        encrypted_deck.push(ct);
+    let cached = proof_cache().get(&ProofCache::deck_key(&dealer_shuffle.deck)?);
+    let (dealer_proof, public_inputs_strs) = match cached {
+        Some(cached) => {
+            tracing::info!("Using pre-generated shuffle proof");
+            cached
+        }
+        None => {
+            dealer_shuffle.public_inputs.validate()?;
+            tracing::info!("Generating ZK shuffle proof (this may take ~1 minute)...");
+            let started = std::time::Instant::now();
+            let proof = config
+                .prover
+                .prove_shuffle(&dealer_shuffle.public_inputs, dealer_shuffle.private_inputs);
+            let proof = check_proof(config, "Shuffle proof", started, proof)?;
+            tracing::info!("Proof generated");
+            let inputs = ProofInputSerializer::fields_to_strings(
+                &dealer_shuffle.public_inputs.to_ark_public_inputs(),
+            );
+            (proof, inputs)
+        }
+    };
+    if let Some(keys) = &config.verification_keys {
+        if !keys.verify_shuffle(&dealer_proof, &dealer_shuffle.public_inputs)? {
+            return Err("Shuffle proof failed local verification; not submitting".into());
+        }
     }
 
-    log::info!("Shuffling deck...");
-    let dealer_shuffle = shuffle(&mut rng, &encrypted_deck, &dealer_keys.pk);
-
-    log::info!("Generating ZK shuffle proof (this may take ~1 minute)...");
-    let dealer_proof = generate_shuffle_proof_rapidsnark(
-        &dealer_shuffle.public_inputs,
-        dealer_shuffle.private_inputs,
-    )
-    .map_err(|e| -> BoxErr { e.to_string().into() })?;
-    log::info!("Proof generated");
-
     let proof_json = serde_json::to_string(&dealer_proof)?;
-    let public_inputs_strs: Vec<String> = dealer_shuffle
-        .public_inputs
-        .to_ark_public_inputs()
-        .iter()
-        .map(|f| {
-            let bigint = num_bigint::BigInt::from_bytes_le(
-                num_bigint::Sign::Plus,
-                &f.into_bigint().to_bytes_le(),
-            );
-            bigint.to_string()
-        })
-        .collect();
 
     let shuffled_deck: Vec<String> = dealer_shuffle
         .deck
@@ -587,7 +2673,7 @@ fn create_game(
     });
     let msg_bytes = serde_json::to_vec(&msg_json)?;
 
-    log::info!("Submitting CreateGame (using pre-deposited bankroll)...");
+    tracing::info!("Submitting CreateGame (using pre-deposited bankroll)...");
 
     // Drop the runtime guard before mob calls (mob creates its own runtime)
     drop(_rt_guard);
@@ -604,25 +2690,36 @@ fn create_game(
     if tx_response.code != 0 {
         return Err(format!("TX failed: {}", tx_response.raw_log).into());
     }
-    log::info!("TX confirmed: {}", tx_response.txhash);
-
-    // Find our game_id
-    let rt2 = tokio::runtime::Builder::new_current_thread()
-        .enable_all()
-        .build()?;
-    let games: Vec<GameListItem> = rt2.block_on(query_list_games(
-        &config.rpc_url,
-        &config.contract_addr,
-        Some("WaitingForPlayerJoin".into()),
-    ))?;
-
-    let game_id = games
-        .iter()
-        .find(|g| g.dealer == address)
-        .map(|g| g.game_id)
-        .ok_or("Could not find newly created game")?;
+    tracing::info!(txhash = %tx_response.txhash, "TX confirmed");
+
+    // Find our game_id. The tx's own event is exact even with concurrent games; the
+    // list lookup is only for nodes that don't return events in raw_log
+    let game_id = match extract_game_id(&tx_response) {
+        Some(game_id) => game_id,
+        None => {
+            let rt2 = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            let games: Vec<GameListItem> = rt2.block_on(query_list_games(
+                &config.rpc_url,
+                &config.contract_addr,
+                Some("WaitingForPlayerJoin".into()),
+            ))?;
+            games
+                .iter()
+                .find(|g| g.dealer == address)
+                .map(|g| g.game_id)
+                .ok_or("Could not find newly created game")?
+        }
+    };
 
-    log::info!("Game created: id={game_id}");
+    tracing::info!(game_id, dealer = %address, "Game created");
+    config.notify(NotificationLevel::Info, format!("Game {game_id} created"));
+    if let Err(e) =
+        ProofLog::new(DATA_DIR).record_shuffle(game_id, dealer_proof, dealer_shuffle.public_inputs)
+    {
+        tracing::warn!(game_id, "Failed to save shuffle proof: {e}");
+    }
     Ok((dealer_keys.sk, dealer_keys.pk, game_id))
 }
 
@@ -644,6 +2741,7 @@ fn game_loop(
     let claim_after = std::time::Duration::from_secs(contract_config.timeout_seconds * 2);
     let game_start = std::time::Instant::now();
     let mut consecutive_query_failures: u32 = 0;
+    let mut player_joined = false;
 
     loop {
         std::thread::sleep(std::time::Duration::from_secs(2));
@@ -659,8 +2757,12 @@ fn game_loop(
             }
             Err(e) => {
                 consecutive_query_failures += 1;
-                log::warn!("Query failed ({consecutive_query_failures}x): {e}");
+                tracing::warn!("Query failed ({consecutive_query_failures}x): {e}");
                 if consecutive_query_failures >= 30 {
+                    config.notify(
+                        NotificationLevel::Error,
+                        format!("Game {game_id}: RPC unreachable, {consecutive_query_failures} queries failed in a row: {e}"),
+                    );
                     return Err(format!(
                         "Game {game_id}: giving up after {consecutive_query_failures} consecutive query failures"
                     ).into());
@@ -674,53 +2776,76 @@ fn game_loop(
         if status.contains("WaitingForPlayerJoin") {
             // Check if we've waited too long for a player
             if game_start.elapsed() > claim_after {
-                log::warn!(
+                tracing::warn!(
                     "No player joined after {:?}, claiming timeout",
                     game_start.elapsed()
                 );
                 match claim_timeout(client, config, game_id) {
-                    Ok(_) => log::info!("Timeout claimed for game {game_id}"),
-                    Err(e) => log::error!("Failed to claim timeout: {e}"),
+                    Ok(_) => tracing::info!(game_id, "Timeout claimed"),
+                    Err(e) => tracing::error!("Failed to claim timeout: {e}"),
                 }
                 return Ok(());
             }
-            log::debug!("Waiting for player to join...");
+            tracing::debug!("Waiting for player to join...");
             continue;
         }
+        if !player_joined {
+            player_joined = true;
+            config.notify(
+                NotificationLevel::Info,
+                format!(
+                    "Game {game_id}: {} joined with a bet of {} {}",
+                    game.player, game.bet, contract_config.denom
+                ),
+            );
+        }
 
         if status.contains("WaitingForReveal") {
-            handle_reveals(client, config, game_id, &game, sk, pk);
+            match rt.block_on(query_reveal_requests(
+                &config.rpc_url,
+                &config.contract_addr,
+                game_id,
+            )) {
+                Ok(requests) => {
+                    handle_reveals(client, config, game_id, &game, &requests.pending, sk, pk)
+                }
+                Err(e) => tracing::warn!(game_id, "Reveal requests query failed: {e}"),
+            }
             continue;
         }
 
         if status.contains("OfferingInsurance") {
-            log::debug!("Waiting for player insurance decision...");
+            tracing::debug!("Waiting for player insurance decision...");
         } else if status.contains("PlayerTurn") {
-            log::debug!("Player's turn...");
+            tracing::debug!("Player's turn...");
         } else if status.contains("DealerTurn") {
-            log::debug!("Dealer turn (contract auto-processes)...");
-        } else if status.contains("Settled") {
-            log::info!("Game {game_id} settled: {status}");
+            tracing::debug!("Dealer turn (contract auto-processes)...");
+        } else if is_finished(&status) {
+            tracing::info!(game_id, status = %status, "Game settled");
             log_game_results(&game);
+            config.notify(
+                NotificationLevel::Info,
+                settled_message(game_id, &game, &contract_config),
+            );
             return Ok(());
         } else {
-            log::warn!("Unknown game status: {status}");
+            tracing::warn!("Unknown game status: {status}");
         }
 
         // For any non-settled, non-reveal status: check if player timed out
         if game_start.elapsed() > claim_after {
-            log::warn!(
+            tracing::warn!(
                 "Game {game_id} stale ({:?} elapsed), attempting timeout claim",
                 game_start.elapsed()
             );
             match claim_timeout(client, config, game_id) {
                 Ok(_) => {
-                    log::info!("Timeout claimed for game {game_id}");
+                    tracing::info!(game_id, "Timeout claimed");
                     return Ok(());
                 }
                 Err(e) => {
                     // Timeout claim may fail if not enough time passed on-chain yet
-                    log::debug!("Timeout claim failed (may not be eligible yet): {e}");
+                    tracing::debug!("Timeout claim failed (may not be eligible yet): {e}");
                 }
             }
         }
@@ -748,95 +2873,52 @@ fn handle_reveals(
     config: &DealerConfig,
     game_id: u64,
     game: &GameResponse,
+    pending: &[PendingReveal],
     sk: &Fr,
     pk: &Point,
 ) {
-    let reveal_requests = parse_reveal_requests(&game.status);
-
-    let already_submitted: Vec<u32> = game
-        .pending_reveals
+    let card_indices: Vec<u32> = pending
         .iter()
-        .filter(|pr| pr.dealer_partial.is_some())
-        .map(|pr| pr.card_index)
+        .filter(|p| !p.dealer_submitted)
+        .map(|p| p.card_index)
         .collect();
-
-    for &card_idx in &reveal_requests {
-        if !already_submitted.contains(&card_idx) {
-            if let Err(e) = submit_reveal(client, config, game_id, card_idx, game, sk, pk) {
-                log::error!("Reveal failed for card {card_idx}: {e}");
-            }
-        }
+    if card_indices.is_empty() {
+        return;
     }
-
-    for pr in &game.pending_reveals {
-        if pr.dealer_partial.is_none() && !reveal_requests.contains(&pr.card_index) {
-            if let Err(e) = submit_reveal(client, config, game_id, pr.card_index, game, sk, pk) {
-                log::error!("Reveal failed for card {}: {e}", pr.card_index);
-            }
-        }
+    if let Err(e) = submit_reveals(client, config, game_id, &card_indices, game, sk, pk) {
+        tracing::error!(game_id, ?card_indices, error = %e, "Reveal failed");
     }
 }
 
-fn submit_reveal(
+/// Prove and submit partial decryptions for every card in `card_indices` as one
+/// `SubmitBatchReveal` transaction
+fn submit_reveals(
     client: &Client,
     config: &DealerConfig,
     game_id: u64,
-    card_index: u32,
+    card_indices: &[u32],
     game: &GameResponse,
     sk: &Fr,
     pk: &Point,
 ) -> Result<(), BoxErr> {
-    if card_index as usize >= game.deck.len() {
-        return Err(format!("Invalid card_index: {card_index}").into());
-    }
-
     // Need tokio reactor for WASM proof generator
     let rt = tokio::runtime::Builder::new_current_thread()
         .enable_all()
         .build()?;
     let _rt_guard = rt.enter();
 
-    let card_binary = &game.deck[card_index as usize];
-    let mut cursor = card_binary.as_slice();
-    let c0 = Point::deserialize_compressed(&mut cursor)
-        .map_err(|e| format!("Failed to deserialize card c0: {e}"))?;
-    let c1 = Point::deserialize_compressed(&mut cursor)
-        .map_err(|e| format!("Failed to deserialize card c1: {e}"))?;
-    let encrypted_card = Ciphertext { c0, c1 };
-
-    log::info!("Revealing card {card_index}...");
-    let reveal = reveal_card(sk, &encrypted_card, pk);
-
-    log::info!("Generating reveal proof for card {card_index}...");
-    let reveal_proof = generate_reveal_proof_rapidsnark(&reveal.public_inputs, reveal.sk_p)
-        .map_err(|e| -> BoxErr { e.to_string().into() })?;
-
-    let mut partial_buf = Vec::new();
-    reveal
-        .partial_decryption
-        .serialize_compressed(&mut partial_buf)
-        .map_err(|e| format!("Failed to serialize partial decryption: {e}"))?;
-    let proof_json = serde_json::to_string(&reveal_proof)?;
-    let public_inputs_strs: Vec<String> = reveal
-        .public_inputs
-        .to_ark_public_inputs()
-        .iter()
-        .map(|f| {
-            let bigint = num_bigint::BigInt::from_bytes_le(
-                num_bigint::Sign::Plus,
-                &f.into_bigint().to_bytes_le(),
-            );
-            bigint.to_string()
-        })
-        .collect();
+    let mut items = Vec::with_capacity(card_indices.len());
+    let mut proofs = Vec::with_capacity(card_indices.len());
+    for &card_index in card_indices {
+        let (item, reveal_proof) = prepare_reveal(config, game_id, card_index, game, sk, pk)?;
+        items.push(item);
+        proofs.push((card_index, reveal_proof));
+    }
 
     let msg_json = serde_json::json!({
-        "submit_reveal": {
+        "submit_batch_reveal": {
             "game_id": game_id,
-            "card_index": card_index,
-            "partial_decryption": general_purpose::STANDARD.encode(&partial_buf),
-            "proof": general_purpose::STANDARD.encode(&proof_json),
-            "public_inputs": public_inputs_strs,
+            "reveals": items,
         }
     });
     let msg_bytes = serde_json::to_vec(&msg_json)?;
@@ -845,7 +2927,7 @@ fn submit_reveal(
     drop(_rt_guard);
     drop(rt);
 
-    log::info!("Submitting reveal TX for card {card_index}...");
+    tracing::info!(game_id, ?card_indices, "Submitting reveal TX");
     let tx_response = execute_and_confirm(
         client,
         config.contract_addr.clone(),
@@ -857,10 +2939,147 @@ fn submit_reveal(
     if tx_response.code != 0 {
         return Err(format!("Reveal TX failed: {}", tx_response.raw_log).into());
     }
-    log::info!(
-        "Reveal for card {card_index} confirmed: {}",
+    tracing::info!(
+        "Reveal for cards {card_indices:?} confirmed: {}",
         tx_response.txhash
     );
+    let proof_log = ProofLog::new(DATA_DIR);
+    for (card_index, reveal_proof) in proofs {
+        if let Err(e) = proof_log.record_reveal(game_id, card_index, reveal_proof) {
+            tracing::warn!(game_id, card_index, "Failed to save reveal proof: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// Partial decryption and proof for one card, as a `RevealItem` ready to submit.
+/// Must run inside a tokio runtime.
+fn prepare_reveal(
+    config: &DealerConfig,
+    game_id: u64,
+    card_index: u32,
+    game: &GameResponse,
+    sk: &Fr,
+    pk: &Point,
+) -> Result<(serde_json::Value, RevealProof), BoxErr> {
+    if card_index as usize >= game.deck.len() {
+        return Err(format!("Invalid card_index: {card_index}").into());
+    }
+
+    let card_binary = &game.deck[card_index as usize];
+    let mut cursor = card_binary.as_slice();
+    let c0 = Point::deserialize_compressed(&mut cursor)
+        .map_err(|e| format!("Failed to deserialize card c0: {e}"))?;
+    let c1 = Point::deserialize_compressed(&mut cursor)
+        .map_err(|e| format!("Failed to deserialize card c1: {e}"))?;
+    let encrypted_card = Ciphertext { c0, c1 };
+
+    tracing::info!(game_id, card_index, "Revealing card");
+    let reveal = reveal_card(sk, &encrypted_card, pk);
+
+    tracing::info!(game_id, card_index, "Generating reveal proof");
+    let started = std::time::Instant::now();
+    let reveal_proof = config
+        .prover
+        .prove_reveal(&reveal.public_inputs, reveal.sk_p);
+    let reveal_proof = check_proof(
+        config,
+        &format!("Game {game_id}: reveal proof for card {card_index}"),
+        started,
+        reveal_proof,
+    )?;
+    if let Some(keys) = &config.verification_keys {
+        if !keys.verify_reveal(&reveal_proof, &reveal.public_inputs)? {
+            return Err("Reveal proof failed local verification; not submitting".into());
+        }
+    }
+
+    let mut partial_buf = Vec::new();
+    reveal
+        .partial_decryption
+        .serialize_compressed(&mut partial_buf)
+        .map_err(|e| format!("Failed to serialize partial decryption: {e}"))?;
+    let proof_json = serde_json::to_string(&reveal_proof)?;
+    let public_inputs_strs =
+        ProofInputSerializer::fields_to_strings(&reveal.public_inputs.to_ark_public_inputs());
+
+    let item = serde_json::json!({
+        "card_index": card_index,
+        "partial_decryption": general_purpose::STANDARD.encode(&partial_buf),
+        "proof": general_purpose::STANDARD.encode(&proof_json),
+        "public_inputs": public_inputs_strs,
+    });
+    Ok((item, reveal_proof))
+}
+
+/// Deposit `auto_topup.amount` when the bankroll has fallen below the threshold and the
+/// dealer wallet can cover it. At most one top-up per `TOPUP_COOLDOWN`.
+fn check_and_topup(client: &Client, config: &DealerConfig, address: &str) -> Result<(), BoxErr> {
+    let Some(topup) = &config.auto_topup else {
+        return Ok(());
+    };
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let bankroll = rt
+        .block_on(query_dealer_balance(&config.rpc_url, &config.contract_addr))?
+        .balance
+        .u128();
+    if bankroll >= topup.threshold {
+        return Ok(());
+    }
+    let denom = rt
+        .block_on(query_config(&config.rpc_url, &config.contract_addr))?
+        .denom;
+    let wallet = rt.block_on(query_wallet_balance(&config.rpc_url, address, &denom))?;
+    drop(rt);
+
+    match topup.decide(bankroll, wallet, std::time::Instant::now()) {
+        TopupDecision::NotNeeded => {}
+        TopupDecision::TooSoon => {
+            tracing::debug!(
+                bankroll,
+                "Bankroll low, but topped up less than a minute ago"
+            )
+        }
+        TopupDecision::InsufficientFunds => {
+            tracing::warn!(
+                bankroll,
+                wallet,
+                amount = topup.amount,
+                "Bankroll below auto top-up threshold, but the dealer wallet can't cover it"
+            );
+            config.notify(
+                NotificationLevel::Warning,
+                format!(
+                    "Bankroll {bankroll} {denom} is below {}, and the wallet ({wallet}) can't cover a top-up",
+                    topup.threshold
+                ),
+            );
+        }
+        TopupDecision::Deposit => {
+            tracing::info!(bankroll, amount = topup.amount, "Topping up bankroll");
+            config.notify(
+                NotificationLevel::Warning,
+                format!(
+                    "Bankroll {bankroll} {denom} is below {}, topping up {}",
+                    topup.threshold, topup.amount
+                ),
+            );
+            let msg_bytes = serde_json::to_vec(&serde_json::json!({ "deposit_bankroll": {} }))?;
+            let funds = vec![mob::Coin::new(&denom, topup.amount.to_string())];
+            let tx = execute_and_confirm(
+                client,
+                config.contract_addr.clone(),
+                msg_bytes,
+                funds,
+                "Auto top-up bankroll",
+            )?;
+            *topup.last_topup.lock().unwrap_or_else(|e| e.into_inner()) =
+                Some(std::time::Instant::now());
+            tracing::info!(txhash = %tx.txhash, "Top-up confirmed");
+        }
+    }
     Ok(())
 }
 
@@ -873,11 +3092,11 @@ fn withdraw_all_bankroll(client: &Client, config: &DealerConfig) -> Result<(), B
     drop(rt);
 
     if balance.balance.is_zero() {
-        log::info!("Dealer balance is zero, nothing to withdraw");
+        tracing::info!("Dealer balance is zero, nothing to withdraw");
         return Ok(());
     }
 
-    log::info!("Withdrawing {} from bankroll...", balance.balance);
+    tracing::info!("Withdrawing {} from bankroll...", balance.balance);
     let msg_json = serde_json::json!({ "withdraw_bankroll": {} });
     let msg_bytes = serde_json::to_vec(&msg_json)?;
 
@@ -892,27 +3111,50 @@ fn withdraw_all_bankroll(client: &Client, config: &DealerConfig) -> Result<(), B
     if tx_response.code != 0 {
         return Err(format!("Withdraw TX failed: {}", tx_response.raw_log).into());
     }
-    log::info!("Withdraw confirmed: {}", tx_response.txhash);
+    tracing::info!(txhash = %tx_response.txhash, "Withdraw confirmed");
     Ok(())
 }
 
 // ── Helpers ──
 
-fn parse_reveal_requests(status: &str) -> Vec<u32> {
-    if !status.contains("reveal_requests:") {
-        return vec![];
-    }
-    status
-        .split("reveal_requests:")
-        .nth(1)
-        .and_then(|s| s.split(']').next())
-        .map(|s| s.trim_start_matches([' ', '[']))
-        .map(|s| {
-            s.split(',')
-                .filter_map(|n| n.trim().parse::<u32>().ok())
-                .collect()
-        })
-        .unwrap_or_default()
+/// Passes `proof` through, sending an error notification if it failed or took
+/// longer than `PROOF_ALERT_AFTER`.
+fn check_proof<T, E: std::fmt::Display>(
+    config: &DealerConfig,
+    what: &str,
+    started: std::time::Instant,
+    proof: Result<T, E>,
+) -> Result<T, E> {
+    let elapsed = started.elapsed();
+    match &proof {
+        Err(e) => config.notify(NotificationLevel::Error, format!("{what} failed: {e}")),
+        Ok(_) if elapsed > PROOF_ALERT_AFTER => config.notify(
+            NotificationLevel::Error,
+            format!("{what} took {}s", elapsed.as_secs()),
+        ),
+        Ok(_) => {}
+    }
+    proof
+}
+
+/// Dealer result for a settled game, from the same payout rules as `export`.
+fn settled_message(game_id: u64, game: &GameResponse, config: &ContractConfig) -> String {
+    let player_net: i128 = hand_records(game_id, game, config)
+        .iter()
+        .map(|record| record.net)
+        .sum();
+    let dealer_net = -player_net;
+    if dealer_net > 0 {
+        format!(
+            "🟢 Game {game_id} settled: dealer +{dealer_net} {}",
+            config.denom
+        )
+    } else {
+        format!(
+            "Game {game_id} settled: dealer {dealer_net} {}",
+            config.denom
+        )
+    }
 }
 
 fn log_game_results(game: &GameResponse) {
@@ -922,7 +3164,7 @@ fn log_game_results(game: &GameResponse) {
             .iter()
             .map(|&idx| blackjack::Card::from_index(idx as usize).to_display())
             .collect();
-        log::info!(
+        tracing::info!(
             "  Hand {}: [{}] - bet: {} - status: {}",
             i,
             cards.join(", "),
@@ -935,7 +3177,7 @@ fn log_game_results(game: &GameResponse) {
         .iter()
         .map(|&idx| blackjack::Card::from_index(idx as usize).to_display())
         .collect();
-    log::info!("  Dealer: [{}]", dealer_cards.join(", "));
+    tracing::info!("  Dealer: [{}]", dealer_cards.join(", "));
 }
 
 fn serialize_point(p: &Point) -> Result<Vec<u8>, BoxErr> {
@@ -956,13 +3198,14 @@ fn serialize_ciphertext(ct: &Ciphertext) -> Result<Vec<u8>, BoxErr> {
     Ok(buf)
 }
 
-fn save_keys(path: &str, sk: &Fr, pk: &Point) -> Result<(), BoxErr> {
-    let mut data = Vec::new();
-    sk.serialize_compressed(&mut data)
-        .map_err(|e| format!("Failed to serialize sk: {e}"))?;
-    pk.serialize_compressed(&mut data)
-        .map_err(|e| format!("Failed to serialize pk: {e}"))?;
-    std::fs::write(path, &data)?;
+fn save_keys(config: &DealerConfig, game_id: u64, sk: &Fr, pk: &Point) -> Result<(), BoxErr> {
+    // Never overwrite: with concurrent games, existing keys mean another task owns this game
+    if config.key_store.key_path(game_id).exists() {
+        return Err(format!("Keys for game {game_id} already exist; another task owns it").into());
+    }
+    config
+        .key_store
+        .save_game_keys(game_id, sk, pk, &config.key_password)?;
     Ok(())
 }
 
@@ -999,6 +3242,31 @@ async fn query_contract_raw(
     Ok(res_wrapper.data)
 }
 
+/// Dealer wallet balance in `denom`, from the bank module.
+async fn query_wallet_balance(rpc_url: &str, address: &str, denom: &str) -> Result<u128, BoxErr> {
+    use tendermint_rpc::{Client as TmClient, HttpClient};
+
+    let path = "/cosmos.bank.v1beta1.Query/Balance";
+    let data = xion_types::cosmos::bank::v1beta1::QueryBalanceRequest {
+        address: address.to_string(),
+        denom: denom.to_string(),
+    }
+    .encode_to_vec();
+
+    let tm_client = HttpClient::new(rpc_url)?;
+    let response = tm_client
+        .abci_query(Some(path.to_string()), data, None, false)
+        .await?;
+    if response.code.is_err() {
+        return Err(format!("Balance query failed: {}", response.log).into());
+    }
+
+    let balance =
+        xion_types::cosmos::bank::v1beta1::QueryBalanceResponse::decode(response.value.as_slice())?
+            .balance;
+    Ok(balance.map_or(Ok(0), |coin| coin.amount.parse())?)
+}
+
 async fn query_game_by_id(
     rpc_url: &str,
     contract_addr: &str,
@@ -1010,6 +3278,39 @@ async fn query_game_by_id(
     Ok(serde_json::from_slice(&response_bytes)?)
 }
 
+async fn query_reveal_history(
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+) -> Result<Vec<RevealRecord>, BoxErr> {
+    let query_bytes =
+        serde_json::to_vec(&serde_json::json!({ "get_reveal_history": { "game_id": game_id } }))?;
+    let response_bytes = query_contract_raw(rpc_url, contract_addr, &query_bytes).await?;
+    Ok(serde_json::from_slice(&response_bytes)?)
+}
+
+async fn query_contract_state(
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+) -> Result<ContractStateResponse, BoxErr> {
+    let query_bytes =
+        serde_json::to_vec(&serde_json::json!({ "get_contract_state": { "game_id": game_id } }))?;
+    let response_bytes = query_contract_raw(rpc_url, contract_addr, &query_bytes).await?;
+    Ok(serde_json::from_slice(&response_bytes)?)
+}
+
+async fn query_reveal_requests(
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+) -> Result<RevealRequestsResponse, BoxErr> {
+    let query_bytes =
+        serde_json::to_vec(&serde_json::json!({ "get_reveal_requests": { "game_id": game_id } }))?;
+    let response_bytes = query_contract_raw(rpc_url, contract_addr, &query_bytes).await?;
+    Ok(serde_json::from_slice(&response_bytes)?)
+}
+
 async fn query_dealer_balance(
     rpc_url: &str,
     contract_addr: &str,
@@ -1019,20 +3320,60 @@ async fn query_dealer_balance(
     Ok(serde_json::from_slice(&response_bytes)?)
 }
 
+async fn query_pause_status(
+    rpc_url: &str,
+    contract_addr: &str,
+) -> Result<PauseStatusResponse, BoxErr> {
+    let query_bytes = serde_json::to_vec(&serde_json::json!({ "get_pause_status": {} }))?;
+    let response_bytes = query_contract_raw(rpc_url, contract_addr, &query_bytes).await?;
+    Ok(serde_json::from_slice(&response_bytes)?)
+}
+
 async fn query_config(rpc_url: &str, contract_addr: &str) -> Result<ContractConfig, BoxErr> {
     let query_bytes = serde_json::to_vec(&serde_json::json!({ "get_config": {} }))?;
     let response_bytes = query_contract_raw(rpc_url, contract_addr, &query_bytes).await?;
     Ok(serde_json::from_slice(&response_bytes)?)
 }
 
+/// Every game matching `status_filter`, following `GetGamesByStatus` pages to the end
 async fn query_list_games(
     rpc_url: &str,
     contract_addr: &str,
     status_filter: Option<String>,
 ) -> Result<Vec<GameListItem>, BoxErr> {
-    let query_bytes = serde_json::to_vec(
-        &serde_json::json!({ "list_games": { "status_filter": status_filter } }),
-    )?;
+    let mut games = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = query_list_games_page(
+            rpc_url,
+            contract_addr,
+            status_filter.clone(),
+            MAX_GAMES_PAGE,
+            cursor,
+        )
+        .await?;
+        games.extend(page.games);
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => return Ok(games),
+        }
+    }
+}
+
+async fn query_list_games_page(
+    rpc_url: &str,
+    contract_addr: &str,
+    status_filter: Option<String>,
+    limit: u32,
+    start_after: Option<u64>,
+) -> Result<GamesPageResponse, BoxErr> {
+    let query_bytes = serde_json::to_vec(&serde_json::json!({
+        "get_games_by_status": {
+            "status_filter": status_filter,
+            "start_after": start_after,
+            "limit": limit,
+        }
+    }))?;
     let response_bytes = query_contract_raw(rpc_url, contract_addr, &query_bytes).await?;
     Ok(serde_json::from_slice(&response_bytes)?)
 }
@@ -1046,6 +3387,12 @@ fn execute_and_confirm(
     funds: Vec<mob::Coin>,
     memo: &str,
 ) -> Result<mob::TxResponse, BoxErr> {
+    // Concurrent games share the wallet's account sequence, so one tx at a time until
+    // it's confirmed
+    static TX_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _tx_guard = TX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+
+    let (action, game_id, card_index) = tx_archive::describe_execute_msg(&msg_bytes);
     let broadcast = client.execute_contract(
         contract_addr,
         msg_bytes,
@@ -1058,5 +3405,194 @@ fn execute_and_confirm(
         return Err(format!("Broadcast rejected: {}", broadcast.raw_log).into());
     }
 
-    poll_tx(client, &broadcast.txhash)
+    let tx = poll_tx(client, &broadcast.txhash)?;
+    let record = TxRecord {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        game_id,
+        action,
+        card_index,
+        txhash: tx.txhash.clone(),
+        code: tx.code,
+        gas_used: tx.gas_used,
+    };
+    // The tx is already on-chain; a failed archive write shouldn't fail the caller
+    if let Err(e) = TransactionArchive::new(DATA_DIR).append(&record) {
+        tracing::warn!(txhash = %tx.txhash, error = %e, "Failed to archive transaction");
+    }
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn topup() -> AutoTopup {
+        AutoTopup {
+            threshold: 1_000_000,
+            amount: 5_000_000,
+            last_topup: std::sync::Mutex::new(None),
+        }
+    }
+
+    #[test]
+    fn test_topup_triggers_below_threshold() {
+        let topup = topup();
+        let now = Instant::now();
+        assert_eq!(
+            topup.decide(1_000_000, 10_000_000, now),
+            TopupDecision::NotNeeded
+        );
+        assert_eq!(
+            topup.decide(999_999, 10_000_000, now),
+            TopupDecision::Deposit
+        );
+        assert_eq!(
+            topup.decide(999_999, 4_999_999, now),
+            TopupDecision::InsufficientFunds
+        );
+        assert_eq!(
+            topup.decide(999_999, 5_000_000, now),
+            TopupDecision::Deposit
+        );
+    }
+
+    #[test]
+    fn test_topup_at_most_once_per_minute() {
+        let topup = topup();
+        let start = Instant::now();
+        *topup.last_topup.lock().unwrap() = Some(start);
+        assert_eq!(
+            topup.decide(0, 10_000_000, start + Duration::from_secs(59)),
+            TopupDecision::TooSoon
+        );
+        assert_eq!(
+            topup.decide(0, 10_000_000, start + TOPUP_COOLDOWN),
+            TopupDecision::Deposit
+        );
+    }
+
+    #[test]
+    fn test_game_pool_limits_concurrent_games() {
+        let mut pool = GamePool::new(2).unwrap();
+        let (release, gate) = std::sync::mpsc::channel::<()>();
+        let gate = Arc::new(std::sync::Mutex::new(gate));
+        for fails in [false, true] {
+            let gate = Arc::clone(&gate);
+            assert!(pool.try_spawn(move || {
+                gate.lock().unwrap().recv()?;
+                if fails {
+                    return Err("game failed".into());
+                }
+                Ok(())
+            }));
+        }
+        assert!(!pool.try_spawn(|| Ok(())));
+        assert_eq!(pool.active_count(), 2);
+
+        release.send(()).unwrap();
+        release.send(()).unwrap();
+        let mut reaped = Vec::new();
+        while reaped.len() < 2 {
+            reaped.extend(pool.reap());
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(
+            reaped.iter().filter(|(_, result)| result.is_err()).count(),
+            1
+        );
+        assert_eq!(pool.active_count(), 0);
+        assert!(pool.try_spawn(|| Ok(())));
+    }
+
+    #[test]
+    fn test_notifications_rate_limited_per_level() {
+        use notify::{RateLimiter, NOTIFY_INTERVAL};
+
+        let limiter = RateLimiter::default();
+        let start = Instant::now();
+        assert!(limiter.allow(NotificationLevel::Info, start));
+        assert!(!limiter.allow(NotificationLevel::Info, start + Duration::from_secs(4)));
+        // Other levels have their own window
+        assert!(limiter.allow(NotificationLevel::Error, start + Duration::from_secs(1)));
+        assert!(limiter.allow(NotificationLevel::Info, start + NOTIFY_INTERVAL));
+    }
+
+    #[test]
+    fn test_version_flag() {
+        let err = Cli::try_parse_from(["juodzekas-dealer", "--version"])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), clap::error::ErrorKind::DisplayVersion);
+        let version = err.to_string();
+        let prefix = format!("juodzekas-dealer {} (git: ", env!("CARGO_PKG_VERSION"));
+        assert!(version.starts_with(&prefix));
+        assert!(version.contains("network: xion-testnet-2)"));
+    }
+
+    #[test]
+    fn test_inspect_marks_mismatched_partial() {
+        use cosmwasm_std::{Binary, Uint128};
+        use inspect::{CardRow, PartialCheck};
+
+        let hand = |cards: Vec<u8>, status: &str, split_depth| HandResponse {
+            cards,
+            bet: Uint128::new(1000),
+            status: status.to_string(),
+            split_depth,
+        };
+        // Hit then stood; a split hand doubled on 8♠ 3♠
+        assert_eq!(
+            inspect::hand_actions(&hand(vec![12, 1, 4], "Stood", 0)),
+            ["Hit", "Stand"]
+        );
+        assert_eq!(
+            inspect::hand_actions(&hand(vec![7, 2, 9], "Doubled", 1)),
+            ["Split", "Double"]
+        );
+
+        let game = GameResponse {
+            player: "player".to_string(),
+            dealer: "dealer".to_string(),
+            bet: Uint128::new(1000),
+            status: "PlayerTurn".to_string(),
+            hands: vec![hand(vec![12, 1], "Active", 0)],
+            dealer_hand: vec![5],
+            player_pubkey: Binary::default(),
+            dealer_pubkey: Binary::default(),
+            deck: vec![],
+            player_shuffled_deck: None,
+            deck_hash: Binary::default(),
+            player_deck_hash: None,
+            insurance: None,
+            pending_reveals: vec![],
+            last_action_timestamp: 1_000,
+            terms: None,
+        };
+        let row = |card_index, value, check| CardRow {
+            card_index,
+            value,
+            player_partial: Some(vec![0xab; 32]),
+            dealer_partial: Some(vec![0xcd; 32]),
+            block_height: Some(10),
+            check,
+        };
+        let rows = [
+            row(0, Some(12), PartialCheck::Match),
+            row(1, Some(1), PartialCheck::Mismatch),
+            row(2, Some(5), PartialCheck::Match),
+            row(3, None, PartialCheck::Pending),
+        ];
+        let report = inspect::render(7, &game, &rows, &[], 1_190, false);
+        assert!(report.contains("Phase: PlayerTurn (3m 10s since the last action)"));
+        assert!(report.contains("Dealer: [6♠ ??]"));
+        let mismatches: Vec<&str> = report.lines().filter(|l| l.contains("MISMATCH")).collect();
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].contains("2♠") && mismatches[0].contains("cdcdcdcd…"));
+        assert!(report.contains("Dealer (hole)"));
+
+        // Colored output puts the label in red
+        let colored = inspect::render(7, &game, &rows, &[], 1_190, true);
+        assert!(colored.contains("\x1b[31;1mMISMATCH\x1b[0m"));
+    }
 }
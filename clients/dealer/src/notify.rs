@@ -0,0 +1,114 @@
+//! Push notifications for events an operator should see without watching the logs.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Minimum time between two messages of the same level; extra ones are dropped
+pub const NOTIFY_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotificationLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+pub trait Notifier: Send + Sync {
+    /// Delivers `message` in the background. Failures are logged, never returned.
+    fn send(&self, level: NotificationLevel, message: String);
+}
+
+/// Allows one message per level per `NOTIFY_INTERVAL`.
+#[derive(Default)]
+pub struct RateLimiter {
+    last_sent: Mutex<HashMap<NotificationLevel, Instant>>,
+}
+
+impl RateLimiter {
+    /// Whether a message of `level` may go out at `now`; records it if so.
+    pub fn allow(&self, level: NotificationLevel, now: Instant) -> bool {
+        let mut last_sent = self.last_sent.lock().unwrap_or_else(|e| e.into_inner());
+        if last_sent
+            .get(&level)
+            .is_some_and(|&last| now.duration_since(last) < NOTIFY_INTERVAL)
+        {
+            return false;
+        }
+        last_sent.insert(level, now);
+        true
+    }
+}
+
+/// Sends through the Telegram Bot API's `sendMessage`.
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    token: String,
+    chat_id: String,
+    limiter: RateLimiter,
+}
+
+impl TelegramNotifier {
+    pub fn new(token: String, chat_id: String) -> Self {
+        TelegramNotifier {
+            client: reqwest::Client::new(),
+            token,
+            chat_id,
+            limiter: RateLimiter::default(),
+        }
+    }
+}
+
+impl Notifier for TelegramNotifier {
+    fn send(&self, level: NotificationLevel, message: String) {
+        if !self.limiter.allow(level, Instant::now()) {
+            tracing::debug!(?level, "Notification rate limited: {message}");
+            return;
+        }
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.token);
+        let body = serde_json::json!({
+            "chat_id": self.chat_id,
+            "text": match level_emoji(level) {
+                Some(emoji) => format!("{emoji} {message}"),
+                None => message,
+            },
+        });
+        let client = self.client.clone();
+        // Own thread and runtime: callers may be inside another runtime, and a slow
+        // Telegram must not hold up the game
+        std::thread::spawn(move || {
+            let rt = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(e) => {
+                    tracing::warn!("Telegram notification not sent: {e}");
+                    return;
+                }
+            };
+            let result = rt.block_on(async {
+                client
+                    .post(&url)
+                    .json(&body)
+                    .timeout(Duration::from_secs(10))
+                    .send()
+                    .await?
+                    .error_for_status()
+            });
+            if let Err(e) = result {
+                // The error text carries the URL, and with it the bot token
+                tracing::warn!("Telegram notification failed: {}", e.without_url());
+            }
+        });
+    }
+}
+
+/// Info messages pick their own emoji, if any
+fn level_emoji(level: NotificationLevel) -> Option<&'static str> {
+    match level {
+        NotificationLevel::Info => None,
+        NotificationLevel::Warning => Some("🟡"),
+        NotificationLevel::Error => Some("🔴"),
+    }
+}
@@ -0,0 +1,83 @@
+//! The dealer's own shuffle and reveal proofs per game. The contract verifies them but
+//! does not store them, so they are kept here for `verify-deck`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use zk_shuffle::proof::ShufflePublicInputs;
+use zk_shuffle::prover::{RevealProof, ShuffleProof};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShuffleRecord {
+    pub proof: ShuffleProof,
+    pub public_inputs: ShufflePublicInputs,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct GameProofs {
+    pub shuffle: Option<ShuffleRecord>,
+    /// Reveal proofs by card index. Their public inputs are rebuilt from the deck and keys.
+    pub reveals: BTreeMap<u32, RevealProof>,
+}
+
+pub struct ProofLog {
+    dir: PathBuf,
+}
+
+impl ProofLog {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        ProofLog { dir: dir.into() }
+    }
+
+    pub fn path(&self, game_id: u64) -> PathBuf {
+        self.dir.join(format!("game_{game_id}_proofs.json"))
+    }
+
+    /// Proofs recorded for `game_id`; empty if none were.
+    pub fn load(&self, game_id: u64) -> io::Result<GameProofs> {
+        match fs::read(self.path(game_id)) {
+            Ok(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(GameProofs::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn record_shuffle(
+        &self,
+        game_id: u64,
+        proof: ShuffleProof,
+        public_inputs: ShufflePublicInputs,
+    ) -> io::Result<()> {
+        let mut proofs = self.load(game_id)?;
+        proofs.shuffle = Some(ShuffleRecord {
+            proof,
+            public_inputs,
+        });
+        self.save(game_id, &proofs)
+    }
+
+    pub fn record_reveal(
+        &self,
+        game_id: u64,
+        card_index: u32,
+        proof: RevealProof,
+    ) -> io::Result<()> {
+        let mut proofs = self.load(game_id)?;
+        proofs.reveals.insert(card_index, proof);
+        self.save(game_id, &proofs)
+    }
+
+    pub fn delete(&self, game_id: u64) -> io::Result<()> {
+        match fs::remove_file(self.path(game_id)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    fn save(&self, game_id: u64, proofs: &GameProofs) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(game_id), serde_json::to_vec_pretty(proofs)?)
+    }
+}
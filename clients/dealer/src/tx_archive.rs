@@ -0,0 +1,141 @@
+//! Append-only JSONL archive of confirmed dealer transactions, for audits and disputes.
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+pub const ARCHIVE_FILE: &str = "tx_archive.jsonl";
+/// Size at which the live archive is compressed and a new one started
+pub const MAX_ARCHIVE_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxRecord {
+    pub timestamp: String,
+    pub game_id: Option<u64>,
+    pub action: String,
+    pub card_index: Option<u32>,
+    pub txhash: String,
+    pub code: u32,
+    pub gas_used: i64,
+}
+
+/// Action name and game/card fields taken from an execute message,
+/// e.g. `{"submit_reveal":{"game_id":3,"card_index":7,...}}` -> ("SubmitReveal", 3, 7).
+pub fn describe_execute_msg(msg_bytes: &[u8]) -> (String, Option<u64>, Option<u32>) {
+    let Ok(serde_json::Value::Object(msg)) = serde_json::from_slice(msg_bytes) else {
+        return ("Unknown".to_string(), None, None);
+    };
+    let Some((variant, body)) = msg.into_iter().next() else {
+        return ("Unknown".to_string(), None, None);
+    };
+    let action = variant
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+    let game_id = body.get("game_id").and_then(|v| v.as_u64());
+    let card_index = body
+        .get("card_index")
+        .and_then(|v| v.as_u64())
+        .map(|i| i as u32);
+    (action, game_id, card_index)
+}
+
+pub struct TransactionArchive {
+    dir: PathBuf,
+}
+
+impl TransactionArchive {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        TransactionArchive { dir: dir.into() }
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.dir.join(ARCHIVE_FILE)
+    }
+
+    /// Append one record. Existing lines are never rewritten; once the file
+    /// passes `MAX_ARCHIVE_BYTES` it is gzipped aside first.
+    pub fn append(&self, record: &TxRecord) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path();
+        if fs::metadata(&path).is_ok_and(|m| m.len() >= MAX_ARCHIVE_BYTES) {
+            self.rotate()?;
+        }
+
+        let mut line = serde_json::to_string(record).map_err(io::Error::other)?;
+        line.push('\n');
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()
+    }
+
+    /// Compress the live archive to `tx_archive_<date>.jsonl.gz` and remove it.
+    fn rotate(&self) -> io::Result<()> {
+        let date = chrono::Utc::now().format("%Y-%m-%d");
+        let mut target = self.dir.join(format!("tx_archive_{date}.jsonl.gz"));
+        let mut n = 1;
+        while target.exists() {
+            target = self.dir.join(format!("tx_archive_{date}_{n}.jsonl.gz"));
+            n += 1;
+        }
+
+        let mut encoder = GzEncoder::new(File::create(&target)?, Compression::default());
+        io::copy(&mut File::open(self.path())?, &mut encoder)?;
+        encoder.finish()?.sync_all()?;
+        fs::remove_file(self.path())?;
+        tracing::info!(path = %target.display(), "Rotated transaction archive");
+        Ok(())
+    }
+
+    /// All records, oldest first: rotated archives by name, then the live file.
+    pub fn read_all(&self) -> io::Result<Vec<TxRecord>> {
+        let mut rotated: Vec<PathBuf> = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("tx_archive_") && n.ends_with(".jsonl.gz"))
+                })
+                .collect(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        rotated.sort();
+
+        let mut records = Vec::new();
+        for path in rotated {
+            read_records(GzDecoder::new(File::open(&path)?), &path, &mut records)?;
+        }
+        let live = self.path();
+        if live.exists() {
+            read_records(File::open(&live)?, &live, &mut records)?;
+        }
+        Ok(records)
+    }
+}
+
+fn read_records(reader: impl Read, path: &Path, out: &mut Vec<TxRecord>) -> io::Result<()> {
+    for (n, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}:{}: {e}", path.display(), n + 1),
+            )
+        })?;
+        out.push(record);
+    }
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use std::process::Command;
+
+fn main() {
+    built::write_built_file().expect("Failed to acquire build-time information");
+
+    // Release artifacts built in CI must come from a committed tree
+    let release = std::env::var("PROFILE").is_ok_and(|p| p == "release");
+    if release && std::env::var_os("CI").is_some() {
+        let status = Command::new("git")
+            .args(["status", "--porcelain", "--untracked-files=no"])
+            .output();
+        if let Ok(output) = status {
+            if output.status.success() && !output.stdout.is_empty() {
+                panic!(
+                    "Refusing to build a release artifact from a dirty tree:\n{}",
+                    String::from_utf8_lossy(&output.stdout)
+                );
+            }
+        }
+    }
+}
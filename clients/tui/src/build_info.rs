@@ -0,0 +1,29 @@
+//! Version and build details, for `--version`, the title bar and saved files.
+
+mod built_info {
+    include!(concat!(env!("OUT_DIR"), "/built.rs"));
+}
+
+/// Network the TUI connects to unless configured otherwise
+pub const NETWORK: &str = "xion-testnet-2";
+
+pub const VERSION: &str = built_info::PKG_VERSION;
+
+/// "juodzekas-tui 0.1.0 (git: abc1234, built: ..., rustc: 1.75.0, network: xion-testnet-2)"
+pub fn version_string() -> String {
+    // RUSTC_VERSION reads "rustc 1.75.0 (82e1608df 2023-12-21)"
+    let rustc = built_info::RUSTC_VERSION
+        .split_whitespace()
+        .nth(1)
+        .unwrap_or(built_info::RUSTC_VERSION);
+    let git = match (built_info::GIT_COMMIT_HASH_SHORT, built_info::GIT_DIRTY) {
+        (Some(hash), Some(true)) => format!("{hash}-dirty"),
+        (Some(hash), _) => hash.to_string(),
+        (None, _) => "unknown".to_string(),
+    };
+    format!(
+        "{} {VERSION} (git: {git}, built: {}, rustc: {rustc}, network: {NETWORK})",
+        built_info::PKG_NAME,
+        built_info::BUILT_TIME_UTC,
+    )
+}
@@ -0,0 +1,169 @@
+//! Card rendering for the hand widgets: plain text, compact, or box-drawn card faces.
+
+use blackjack::Card;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Terminal width at which card faces are drawn by default
+pub const GRAPHIC_MIN_WIDTH: u16 = 140;
+/// Below this width cards are shown in compact form by default
+pub const COMPACT_MAX_WIDTH: u16 = 80;
+/// With more hands than this in play, compact mode shows each player hand as its value
+pub const COMPACT_MIN_HANDS: usize = 8;
+
+const GRAPHIC_WIDTH: u16 = 7;
+const GRAPHIC_HEIGHT: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CardDisplayMode {
+    Ascii,   // "A♥ " with a background highlight
+    Graphic, // 5x7 box-drawn card faces
+    Compact, // "A♥" with no padding, tens as "T"; past COMPACT_MIN_HANDS, hand values only
+}
+
+impl CardDisplayMode {
+    /// Mode picked from the terminal width and hands in play when the user hasn't chosen one.
+    pub fn auto(terminal_width: u16, total_hands: usize) -> Self {
+        if total_hands > COMPACT_MIN_HANDS || terminal_width < COMPACT_MAX_WIDTH {
+            CardDisplayMode::Compact
+        } else if terminal_width >= GRAPHIC_MIN_WIDTH {
+            CardDisplayMode::Graphic
+        } else {
+            CardDisplayMode::Ascii
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            CardDisplayMode::Ascii => CardDisplayMode::Graphic,
+            CardDisplayMode::Graphic => CardDisplayMode::Compact,
+            CardDisplayMode::Compact => CardDisplayMode::Ascii,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CardDisplayMode::Ascii => "ASCII",
+            CardDisplayMode::Graphic => "Graphic",
+            CardDisplayMode::Compact => "Compact",
+        }
+    }
+}
+
+pub fn card_color(card_str: &str) -> Color {
+    match card_str.chars().last() {
+        Some('♥') => Color::Red,
+        Some('♦') => Color::from_u32(0xFF_A5_00),
+        Some('♣') => Color::Magenta,
+        Some('♠') => Color::Black,
+        _ => Color::White,
+    }
+}
+
+fn card_style(card: Option<&Card>) -> Style {
+    let color = card
+        .map(|c| card_color(&c.to_display()))
+        .unwrap_or(Color::White);
+    Style::default().fg(color).bg(Color::Gray)
+}
+
+/// Split "10♥" into ("10", "♥").
+fn rank_and_suit(card: &Card) -> (String, String) {
+    let display = card.to_display();
+    let suit_start = display.char_indices().last().map(|(i, _)| i).unwrap_or(0);
+    (
+        display[..suit_start].to_string(),
+        display[suit_start..].to_string(),
+    )
+}
+
+/// Pad `s` with spaces to `width` display columns. `align` is 0 = left, 1 = center, 2 = right.
+fn pad(s: &str, width: usize, align: u8) -> String {
+    let gap = width.saturating_sub(s.width());
+    let (left, right) = match align {
+        0 => (0, gap),
+        1 => (gap / 2, gap - gap / 2),
+        _ => (gap, 0),
+    };
+    format!("{}{s}{}", " ".repeat(left), " ".repeat(right))
+}
+
+/// Renders a 5-line by 7-column card face with box-drawing borders.
+pub fn render_card_graphic(card: &Card, style: Style) -> Vec<Line<'static>> {
+    let inner = (GRAPHIC_WIDTH - 2) as usize;
+    let (rank, suit) = rank_and_suit(card);
+    let rows = [
+        format!("┌{}┐", "─".repeat(inner)),
+        format!("│{}│", pad(&rank, inner, 0)),
+        format!("│{}│", pad(&suit, inner, 1)),
+        format!("│{}│", pad(&rank, inner, 2)),
+        format!("└{}┘", "─".repeat(inner)),
+    ];
+    rows.into_iter()
+        .map(|row| Line::from(Span::styled(row, style)))
+        .collect()
+}
+
+/// Face-down card in the same footprint as `render_card_graphic`.
+fn render_card_back(style: Style) -> Vec<Line<'static>> {
+    let inner = (GRAPHIC_WIDTH - 2) as usize;
+    let mut rows = vec![format!("┌{}┐", "─".repeat(inner))];
+    rows.extend((0..GRAPHIC_HEIGHT - 2).map(|_| format!("│{}│", "░".repeat(inner))));
+    rows.push(format!("└{}┘", "─".repeat(inner)));
+    rows.into_iter()
+        .map(|row| Line::from(Span::styled(row, style)))
+        .collect()
+}
+
+fn text_span(card: Option<&Card>, mode: CardDisplayMode) -> Span<'static> {
+    let text = match (card, mode) {
+        (Some(c), CardDisplayMode::Compact) => {
+            let (rank, suit) = rank_and_suit(c);
+            let rank = if rank == "10" { "T".to_string() } else { rank };
+            format!("{rank}{suit}")
+        }
+        (Some(c), _) => format!("{} ", c.to_display()),
+        (None, CardDisplayMode::Compact) => "??".to_string(),
+        (None, _) => "?? ".to_string(),
+    };
+    Span::styled(text, card_style(card))
+}
+
+/// Lays out a hand (None = face down) in `width` columns, wrapping onto extra rows.
+pub fn hand_lines(cards: &[Option<Card>], mode: CardDisplayMode, width: u16) -> Vec<Line<'static>> {
+    if mode != CardDisplayMode::Graphic {
+        let spans: Vec<Span> = cards.iter().map(|c| text_span(c.as_ref(), mode)).collect();
+        let per_card = spans.iter().map(|s| s.width()).max().unwrap_or(1).max(1) as u16;
+        let cards_per_line = (width / per_card).max(1) as usize;
+        return spans
+            .chunks(cards_per_line)
+            .map(|chunk| Line::from(chunk.to_vec()))
+            .collect();
+    }
+
+    let cards_per_row = (width / (GRAPHIC_WIDTH + 1)).max(1) as usize;
+    let mut lines = Vec::new();
+    for row in cards.chunks(cards_per_row) {
+        let faces: Vec<Vec<Line>> = row
+            .iter()
+            .map(|c| match c {
+                Some(card) => render_card_graphic(card, card_style(Some(card))),
+                None => render_card_back(card_style(None)),
+            })
+            .collect();
+        for i in 0..GRAPHIC_HEIGHT {
+            let mut spans = Vec::new();
+            for (n, face) in faces.iter().enumerate() {
+                if n > 0 {
+                    spans.push(Span::raw(" "));
+                }
+                spans.extend(face[i].spans.iter().cloned());
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+    lines
+}
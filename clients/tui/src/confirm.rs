@@ -0,0 +1,117 @@
+//! Confirmation prompts for player actions that can't be undone.
+
+use ratatui::{
+    layout::{Alignment, Rect},
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// File in the working directory that overrides `AppConfig` defaults
+pub const KEYBINDINGS_FILE: &str = "keybindings.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AppAction {
+    Hit,
+    Stand,
+    DoubleDown,
+    Split,
+    Surrender,
+}
+
+impl AppAction {
+    fn prompt(self) -> String {
+        match self {
+            AppAction::Surrender => {
+                "Are you sure? Surrender returns half your bet. [Y]es / [N]o".to_string()
+            }
+            AppAction::Hit => "Are you sure you want to hit? [Y]es / [N]o".to_string(),
+            AppAction::Stand => "Are you sure you want to stand? [Y]es / [N]o".to_string(),
+            AppAction::DoubleDown => {
+                "Are you sure? Doubling down doubles your bet for one card. [Y]es / [N]o"
+                    .to_string()
+            }
+            AppAction::Split => "Are you sure you want to split? [Y]es / [N]o".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    pub confirmable_actions: HashSet<AppAction>,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            confirmable_actions: HashSet::from([AppAction::Surrender]),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Loads `keybindings.toml`, falling back to defaults if it is missing or invalid.
+    pub fn load() -> Self {
+        let Ok(contents) = std::fs::read_to_string(KEYBINDINGS_FILE) else {
+            return AppConfig::default();
+        };
+        match toml::from_str(&contents) {
+            Ok(config) => config,
+            Err(e) => {
+                log::warn!("Ignoring invalid {KEYBINDINGS_FILE}: {e}");
+                AppConfig::default()
+            }
+        }
+    }
+}
+
+pub struct ConfirmDialog {
+    pub action: AppAction,
+    pub prompt: String,
+    pub confirmed: bool,
+}
+
+impl ConfirmDialog {
+    pub fn new(action: AppAction) -> Self {
+        ConfirmDialog {
+            action,
+            prompt: action.prompt(),
+            confirmed: false,
+        }
+    }
+
+    /// Draws the dialog centered in `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let width = area.width.min(64);
+        let height = area.height.min(5);
+        let dialog_area = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        frame.render_widget(Clear, dialog_area);
+        let paragraph = Paragraph::new(Line::from(self.prompt.as_str()))
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .title(" Confirm ")
+                    .title_alignment(Alignment::Center),
+            )
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, dialog_area);
+    }
+}
@@ -1,17 +1,33 @@
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_std::UniformRand;
-use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::rand_core::{RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use zk_shuffle::babyjubjub::{Fr, Point};
-use zk_shuffle::decrypt::reveal_card;
+use zk_shuffle::decrypt::{reveal_card, reveal_card_with_audit};
 use zk_shuffle::elgamal::{encrypt, Ciphertext, KeyPair};
-use zk_shuffle::proof::{generate_shuffle_proof_rapidsnark, verify_shuffle_proof_rapidsnark};
+use zk_shuffle::proof::{
+    generate_shuffle_proof_rapidsnark, verify_shuffle_proof, vk_from_snarkjs_json,
+};
 use zk_shuffle::shuffle::shuffle;
+use zk_shuffle::vk::{self, VerificationKeys};
+use zk_shuffle::CardMap;
 
 // Re-export from blackjack package
 pub use blackjack::{Card, GameRules};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Fewest columns a hand gets before the spots wrap onto a second row
+pub const MIN_HAND_WIDTH: u16 = 8;
+/// Spots per row once they wrap
+pub const SPOTS_PER_ROW: usize = 4;
+
+/// Where a hand is drawn among the spots: `column` counts hands from the left of `row`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutPosition {
+    pub row: usize,
+    pub column: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum GameMode {
     Fast,      // No ZK proofs, instant gameplay
     Trustless, // Full ZK proofs, takes ~3-4 minutes to start
@@ -23,6 +39,7 @@ pub struct GameState {
     pub dealer_keys: KeyPair,
     pub aggregated_pk: Point,
     pub card_mapping: Vec<Point>, // Maps card index to elliptic curve point
+    pub card_map: CardMap,        // Reverse lookup: revealed point -> card index
     pub encrypted_deck: Vec<Ciphertext>,
     pub player_hands: Vec<Vec<Vec<Option<Card>>>>, // Spots -> Hands within spot (for splits) -> Cards
     pub dealer_hand: Vec<Option<Card>>,
@@ -36,7 +53,41 @@ pub struct GameState {
     pub hands_doubled: Vec<Vec<bool>>,     // Track which hands have doubled [spot][hand_in_spot]
     pub hands_stood: Vec<Vec<bool>>,       // Track which hands have stood [spot][hand_in_spot]
     pub hands_surrendered: Vec<Vec<bool>>, // Track which hands have surrendered [spot][hand_in_spot]
+    pub hands_split_depth: Vec<Vec<u8>>,   // Splits in each hand's history [spot][hand_in_spot]
     pub dealer_peeked: bool,               // Whether dealer has peeked for blackjack
+    // Hand each split hand came from, in the same spot [spot][hand_in_spot]
+    pub hands_split_from: Vec<Vec<Option<usize>>>,
+    pub vk_override: Option<std::path::PathBuf>, // Verifying keys dir replacing the embedded ones
+    pub dealt_cards: Vec<Card>,                  // Every revealed card, in deal order
+    pub history: blackjack::ActionHistory,
+}
+
+/// The encrypted deck as a `blackjack::DeckSource`. `draw` can only report running
+/// out, so the first reveal error is kept for the caller.
+struct EncryptedDeck<'a> {
+    game: &'a mut GameState,
+    error: Option<Box<dyn std::error::Error>>,
+}
+
+impl blackjack::DeckSource for EncryptedDeck<'_> {
+    fn draw(&mut self, _rng: &mut dyn RngCore) -> Option<Card> {
+        match self.game.reveal_next_card() {
+            Ok(card) => Some(card),
+            Err(e) => {
+                self.error.get_or_insert(e);
+                None
+            }
+        }
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(
+            self.game
+                .encrypted_deck
+                .len()
+                .saturating_sub(self.game.deck_position),
+        )
+    }
 }
 
 impl GameState {
@@ -68,24 +119,24 @@ impl GameState {
             (player_keys.pk.into_group() + dealer_keys.pk.into_group()).into_affine();
 
         // Create card mapping (52 cards)
-        let g = Point::generator();
-        let mut card_mapping = Vec::new();
-        for i in 1..=52 {
-            let card_point = (g.into_group() * Fr::from(i as u64)).into_affine();
-            card_mapping.push(card_point);
-        }
+        let card_scalars: Vec<Fr> = (1..=52u64).map(Fr::from).collect();
+        let card_map = CardMap::from_params(&Point::generator(), &card_scalars);
+        let card_mapping: Vec<Point> = card_map.all_cards().into_iter().map(|(_, p)| p).collect();
 
         // Initialize empty hands for each spot - each spot starts with one hand (index 0)
         let player_hands = vec![vec![Vec::new()]; num_spots];
         let hands_doubled = vec![vec![false]; num_spots];
         let hands_stood = vec![vec![false]; num_spots];
         let hands_surrendered = vec![vec![false]; num_spots];
+        let hands_split_from = vec![vec![None]; num_spots];
+        let hands_split_depth = vec![vec![0]; num_spots];
 
         Ok(GameState {
             player_keys,
             dealer_keys,
             aggregated_pk,
             card_mapping,
+            card_map,
             encrypted_deck: Vec::new(),
             player_hands,
             dealer_hand: Vec::new(),
@@ -99,7 +150,12 @@ impl GameState {
             hands_doubled,
             hands_stood,
             hands_surrendered,
+            hands_split_from,
+            hands_split_depth,
             dealer_peeked: false,
+            vk_override: None,
+            dealt_cards: Vec::new(),
+            history: blackjack::ActionHistory::default(),
         })
     }
 
@@ -143,7 +199,12 @@ impl GameState {
     }
 
     fn shuffle_deck_with_proofs(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let shuffle_vkey_path = "circuits/artifacts/shuffle_encrypt_vkey.json";
+        // Builds without embed-keys fall back to the snarkjs key unless overridden
+        let shuffle_vk = if self.vk_override.is_none() && !vk::EMBEDDED {
+            vk_from_snarkjs_json("circuits/artifacts/shuffle_encrypt_vkey.json")?
+        } else {
+            VerificationKeys::load(self.vk_override.as_deref())?.shuffle
+        };
 
         log::info!("Generating player shuffle proof");
         let player_start = std::time::Instant::now();
@@ -156,6 +217,7 @@ impl GameState {
             shuffle_start.elapsed().as_secs()
         );
 
+        player_shuffle.public_inputs.validate()?;
         let proof_gen_start = std::time::Instant::now();
         let player_proof = generate_shuffle_proof_rapidsnark(
             &player_shuffle.public_inputs,
@@ -168,11 +230,7 @@ impl GameState {
 
         // Verify player's shuffle proof
         let verify_start = std::time::Instant::now();
-        if !verify_shuffle_proof_rapidsnark(
-            shuffle_vkey_path,
-            &player_proof,
-            &player_shuffle.public_inputs,
-        )? {
+        if !verify_shuffle_proof(&shuffle_vk, &player_proof, &player_shuffle.public_inputs)? {
             return Err("Player shuffle proof verification failed".into());
         }
         log::info!(
@@ -197,6 +255,7 @@ impl GameState {
             shuffle_start.elapsed().as_secs()
         );
 
+        dealer_shuffle.public_inputs.validate()?;
         let proof_gen_start = std::time::Instant::now();
         let dealer_proof = generate_shuffle_proof_rapidsnark(
             &dealer_shuffle.public_inputs,
@@ -209,11 +268,7 @@ impl GameState {
 
         // Verify dealer's shuffle proof
         let verify_start = std::time::Instant::now();
-        if !verify_shuffle_proof_rapidsnark(
-            shuffle_vkey_path,
-            &dealer_proof,
-            &dealer_shuffle.public_inputs,
-        )? {
+        if !verify_shuffle_proof(&shuffle_vk, &dealer_proof, &dealer_shuffle.public_inputs)? {
             return Err("Dealer shuffle proof verification failed".into());
         }
         log::info!(
@@ -230,38 +285,36 @@ impl GameState {
         Ok(())
     }
 
+    /// Deal the round with `blackjack::GameState::deal_initial_cards`, revealing each card
+    /// off the encrypted deck, and copy the hands and the dealer's peek back.
+    pub fn deal_initial_cards(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut table = self.to_blackjack_state();
+        // The encrypted deck never reshuffles, so the deal has no use for randomness
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut deck = EncryptedDeck {
+            game: self,
+            error: None,
+        };
+        let dealt = table.deal_initial_cards(&mut deck, &mut rng);
+        if let Some(e) = deck.error {
+            return Err(e);
+        }
+        dealt?;
+
+        for (hands, spot) in self.player_hands.iter_mut().zip(&table.spots) {
+            hands[0] = spot.hands[0].cards.iter().copied().map(Some).collect();
+        }
+        self.dealer_hand = table.dealer_hand.iter().copied().map(Some).collect();
+        self.dealer_peeked = table.dealer_peeked;
+        Ok(())
+    }
+
     pub fn draw_card(
         &mut self,
         for_dealer: bool,
         spot_index: Option<usize>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        if self.deck_position >= self.encrypted_deck.len() {
-            return Err("No more cards in deck".into());
-        }
-
-        let card_to_reveal = &self.encrypted_deck[self.deck_position];
-        self.deck_position += 1;
-
-        // Both parties reveal
-        let player_reveal = reveal_card(&self.player_keys.sk, card_to_reveal, &self.player_keys.pk);
-        let dealer_reveal = reveal_card(&self.dealer_keys.sk, card_to_reveal, &self.dealer_keys.pk);
-
-        // Combine partial decryptions
-        let combined_reveal = (player_reveal.partial_decryption.into_group()
-            + dealer_reveal.partial_decryption.into_group())
-        .into_affine();
-        let revealed_card_point =
-            (card_to_reveal.c1.into_group() - combined_reveal.into_group()).into_affine();
-
-        // Find which card it is
-        let card_index = self
-            .card_mapping
-            .iter()
-            .position(|&point| point == revealed_card_point)
-            .ok_or("Card not found in mapping")?;
-
-        let card = Card::from_index(card_index);
-
+        let card = self.reveal_next_card()?;
         if for_dealer {
             self.dealer_hand.push(Some(card));
         } else {
@@ -280,7 +333,53 @@ impl GameState {
         Ok(())
     }
 
-    pub fn calculate_hand_value(hand: &[Option<Card>]) -> u8 {
+    /// Reveal the next card of the encrypted deck with both keys and add it to
+    /// `dealt_cards`.
+    fn reveal_next_card(&mut self) -> Result<Card, Box<dyn std::error::Error>> {
+        if self.deck_position >= self.encrypted_deck.len() {
+            return Err("No more cards in deck".into());
+        }
+
+        let card_to_reveal = &self.encrypted_deck[self.deck_position];
+        self.deck_position += 1;
+
+        // Both parties reveal; the player combines the partials and looks the card up
+        let dealer_reveal = reveal_card(&self.dealer_keys.sk, card_to_reveal, &self.dealer_keys.pk);
+        let (_, audit) = reveal_card_with_audit(
+            &self.player_keys.sk,
+            card_to_reveal,
+            &self.player_keys.pk,
+            Some(dealer_reveal.partial_decryption),
+            &self.card_map,
+        );
+
+        let Some(card_index) = audit.matched_card else {
+            log::warn!(
+                "Card {} not found in mapping (combination consistent: {}): {audit:?}",
+                self.deck_position - 1,
+                audit.verify_combination()
+            );
+            return Err("Card not found in mapping".into());
+        };
+
+        let card = Card::from_index(card_index as usize);
+        self.dealt_cards.push(card);
+        Ok(card)
+    }
+
+    /// Append `action` on the active hand to the history. The dealer's actions go
+    /// through here too; replay ignores their spot and hand.
+    pub fn record_action(&mut self, action: blackjack::Action, card_dealt: Option<Card>) {
+        self.history.entries.push(blackjack::ActionEntry {
+            action,
+            spot_index: self.active_spot,
+            hand_index: self.active_hand_in_spot,
+            card_dealt,
+            timestamp: None, // TUI doesn't track timestamps
+        });
+    }
+
+    pub fn calculate_hand_value(hand: &[Option<Card>]) -> blackjack::HandValue {
         // Filter out None values and call blackjack package's calculate_hand_value
         let cards: Vec<Card> = hand.iter().filter_map(|&c| c).collect();
         blackjack::calculate_hand_value(&cards)
@@ -301,6 +400,8 @@ impl GameState {
         self.hands_doubled.resize(num_spots, vec![false]);
         self.hands_stood.resize(num_spots, vec![false]);
         self.hands_surrendered.resize(num_spots, vec![false]);
+        self.hands_split_from.resize(num_spots, vec![None]);
+        self.hands_split_depth.resize(num_spots, vec![0]);
         self.active_spot = 0;
         self.active_hand_in_spot = 0;
         self.dealer_peeked = false;
@@ -308,8 +409,44 @@ impl GameState {
         Ok(())
     }
 
-    fn get_current_hand(&self) -> &Vec<Option<Card>> {
-        &self.player_hands[self.active_spot][self.active_hand_in_spot]
+    /// The hand being played, or an empty slice once every spot has been played.
+    fn active_hand(&self) -> &[Option<Card>] {
+        self.player_hands
+            .get(self.active_spot)
+            .and_then(|hands| hands.get(self.active_hand_in_spot))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    fn active_hand_flag(&self, flags: &[Vec<bool>]) -> bool {
+        flags
+            .get(self.active_spot)
+            .and_then(|hands| hands.get(self.active_hand_in_spot))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    pub fn active_hand_value(&self) -> u8 {
+        Self::calculate_hand_value(self.active_hand()).total
+    }
+
+    /// Two revealed cards of the same rank (whether the rules allow splitting them is `can_split`)
+    pub fn active_hand_is_pair(&self) -> bool {
+        matches!(
+            self.active_hand(),
+            [Some(a), Some(b)] if blackjack::can_split_cards(a, b)
+        )
+    }
+
+    pub fn active_hand_card_count(&self) -> usize {
+        self.active_hand().len()
+    }
+
+    pub fn active_hand_has_doubled(&self) -> bool {
+        self.active_hand_flag(&self.hands_doubled)
+    }
+
+    pub fn active_hand_has_surrendered(&self) -> bool {
+        self.active_hand_flag(&self.hands_surrendered)
     }
 
     pub fn can_double(&self) -> bool {
@@ -343,6 +480,17 @@ impl GameState {
         self.hands_stood[spot].insert(new_hand_idx, false);
         self.hands_surrendered[spot].insert(new_hand_idx, false);
 
+        // Both halves go one split deeper; parents after the insert point shift right
+        for parent in self.hands_split_from[spot].iter_mut().flatten() {
+            if *parent >= new_hand_idx {
+                *parent += 1;
+            }
+        }
+        self.hands_split_from[spot].insert(new_hand_idx, Some(hand_idx));
+        self.hands_split_depth[spot][hand_idx] += 1;
+        let depth = self.hands_split_depth[spot][hand_idx];
+        self.hands_split_depth[spot].insert(new_hand_idx, depth);
+
         // Deal one card to the original hand, one to the new hand
         self.active_hand_in_spot = hand_idx;
         self.draw_card(false, Some(spot))?;
@@ -351,6 +499,7 @@ impl GameState {
 
         // Reset to the original hand
         self.active_hand_in_spot = hand_idx;
+        self.record_action(blackjack::Action::Split, None);
 
         Ok(())
     }
@@ -370,135 +519,69 @@ impl GameState {
 
         self.hands_surrendered[self.active_spot][self.active_hand_in_spot] = true;
         self.hands_stood[self.active_spot][self.active_hand_in_spot] = true; // Also mark as stood to skip
+        self.record_action(blackjack::Action::Surrender, None);
 
         Ok(())
     }
 
+    /// Basic strategy for the active hand, from `blackjack::Hand::best_action`.
+    /// Surrender is only suggested while the game would still accept it.
     pub fn get_optimal_move(&self) -> &'static str {
-        let hand = self.get_current_hand();
-        let player_value = Self::calculate_hand_value(hand);
-
-        // Get dealer's up card value
         let dealer_up_card = match self.dealer_hand.first() {
             Some(Some(card)) => card.value(),
             _ => return "Stand", // No dealer card visible
         };
 
-        // Check for soft hand (Ace counted as 11)
-        let cards: Vec<Card> = hand.iter().filter_map(|&c| c).collect();
-        let is_soft = blackjack::is_soft_hand(&cards);
-
-        // Check for surrender (before split/double)
-        if self.can_surrender() {
-            // Surrender on hard 16 vs dealer 9, 10, A
-            // Surrender on hard 15 vs dealer 10
-            if !is_soft {
-                if player_value == 16
-                    && (dealer_up_card == 9 || dealer_up_card == 10 || dealer_up_card == 11)
-                {
-                    return "Surrender";
-                }
-                if player_value == 15 && dealer_up_card == 10 {
-                    return "Surrender";
-                }
-            }
+        let rules = GameRules {
+            allow_surrender: self.can_surrender(),
+            ..self.rules
+        };
+        let state = self.to_blackjack_state();
+        match state
+            .active_spot()
+            .active_hand()
+            .best_action(dealer_up_card, &rules)
+        {
+            blackjack::Action::Hit => "Hit",
+            blackjack::Action::Stand => "Stand",
+            blackjack::Action::Double => "Double",
+            blackjack::Action::Split => "Split",
+            blackjack::Action::Surrender => "Surrender",
         }
+    }
 
-        // Check if can split
-        if self.can_split() {
-            let card_rank = if let Some(card) = &hand[0] {
-                card.rank()
-            } else {
-                return "Stand";
-            };
-
-            // Always split Aces and 8s
-            if card_rank == 1 || card_rank == 8 {
-                return "Split";
-            }
-            // Never split 10s, 5s, 4s
-            if card_rank == 10
-                || card_rank == 11
-                || card_rank == 12
-                || card_rank == 13
-                || card_rank == 5
-                || card_rank == 4
-            {
-                // Fall through to regular strategy
-            } else if card_rank == 9 {
-                // Split 9s except against 7, 10, or Ace
-                if dealer_up_card != 7 && dealer_up_card != 10 && dealer_up_card != 11 {
-                    return "Split";
-                }
-            } else if card_rank == 7 || card_rank == 6 {
-                // Split 7s and 6s against 2-7
-                if (2..=7).contains(&dealer_up_card) {
-                    return "Split";
-                }
-            } else if card_rank == 3 || card_rank == 2 {
-                // Split 2s and 3s against 2-7
-                if (2..=7).contains(&dealer_up_card) {
-                    return "Split";
-                }
-            }
-        }
+    pub fn total_hands(&self) -> usize {
+        self.player_hands.iter().map(Vec::len).sum()
+    }
 
-        // Check if can double
-        if self.can_double() {
-            if is_soft {
-                // Soft doubling
-                if (player_value == 19 && dealer_up_card == 6)
-                    || (player_value == 18 && (2..=6).contains(&dealer_up_card))
-                    || (player_value == 17 && (3..=6).contains(&dealer_up_card))
-                    || ((15..=16).contains(&player_value) && (4..=6).contains(&dealer_up_card))
-                    || ((13..=14).contains(&player_value) && (5..=6).contains(&dealer_up_card))
-                {
-                    return "Double";
-                }
-            } else {
-                // Hard doubling
-                if player_value == 11
-                    || (player_value == 10 && dealer_up_card <= 9)
-                    || (player_value == 9 && (3..=6).contains(&dealer_up_card))
-                {
-                    return "Double";
-                }
-            }
+    /// Spots on each row of a player area `width` columns wide: one row while every hand
+    /// gets `MIN_HAND_WIDTH` columns, otherwise spots 1-4 above 5-8.
+    pub fn spot_rows(&self, width: u16) -> Vec<std::ops::Range<usize>> {
+        let spots = self.player_hands.len();
+        if spots <= SPOTS_PER_ROW || self.total_hands() * MIN_HAND_WIDTH as usize <= width as usize
+        {
+            vec![0..spots]
+        } else {
+            vec![0..SPOTS_PER_ROW, SPOTS_PER_ROW..spots]
         }
+    }
 
-        // Basic strategy for hitting/standing
-        if is_soft {
-            // Soft hands
-            if player_value >= 19 {
-                "Stand"
-            } else if player_value == 18 {
-                if dealer_up_card >= 9 {
-                    "Hit"
-                } else {
-                    "Stand"
-                }
-            } else {
-                "Hit"
-            }
-        } else {
-            // Hard hands
-            if player_value >= 17 {
-                "Stand"
-            } else if (13..=16).contains(&player_value) {
-                if (2..=6).contains(&dealer_up_card) {
-                    "Stand"
-                } else {
-                    "Hit"
-                }
-            } else if player_value == 12 {
-                if (4..=6).contains(&dealer_up_card) {
-                    "Stand"
-                } else {
-                    "Hit"
-                }
-            } else {
-                "Hit"
-            }
+    /// Row and column of the active hand when the spots are laid out in `terminal_width`
+    /// columns (the player area, not the whole screen, when the log is open).
+    pub fn active_hand_display_position(&self, terminal_width: u16) -> LayoutPosition {
+        let rows = self.spot_rows(terminal_width);
+        let row = rows
+            .iter()
+            .position(|r| r.contains(&self.active_spot))
+            .unwrap_or(rows.len() - 1);
+        let spot = self.active_spot.min(self.player_hands.len());
+        let hands_before: usize = self.player_hands[rows[row].start..spot]
+            .iter()
+            .map(Vec::len)
+            .sum();
+        LayoutPosition {
+            row,
+            column: hands_before + self.active_hand_in_spot,
         }
     }
 
@@ -524,7 +607,133 @@ impl GameState {
         self.hands_doubled[self.active_spot][self.active_hand_in_spot] = true;
         self.hands_stood[self.active_spot][self.active_hand_in_spot] = true; // Auto-stand after double
         self.draw_card(false, Some(self.active_spot))?; // Draw one more card
+        self.record_action(blackjack::Action::Double, self.dealt_cards.last().copied());
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game_with_hands(spots: Vec<Vec<Vec<Card>>>) -> GameState {
+        let mut game = GameState::new(GameMode::Fast, spots.len()).unwrap();
+        game.hands_doubled = spots.iter().map(|s| vec![false; s.len()]).collect();
+        game.hands_stood = game.hands_doubled.clone();
+        game.hands_surrendered = game.hands_doubled.clone();
+        game.hands_split_from = spots.iter().map(|s| vec![None; s.len()]).collect();
+        game.hands_split_depth = spots.iter().map(|s| vec![0; s.len()]).collect();
+        game.player_hands = spots
+            .into_iter()
+            .map(|s| {
+                s.into_iter()
+                    .map(|h| h.into_iter().map(Some).collect())
+                    .collect()
+            })
+            .collect();
+        game
+    }
+
+    fn is_soft(game: &GameState) -> bool {
+        GameState::calculate_hand_value(game.active_hand()).is_soft
+    }
+
+    #[test]
+    fn test_active_hand_empty() {
+        let game = GameState::new(GameMode::Fast, 1).unwrap();
+        assert_eq!(game.active_hand_value(), 0);
+        assert!(!is_soft(&game));
+        assert!(!game.active_hand_is_pair());
+        assert_eq!(game.active_hand_card_count(), 0);
+        assert!(!game.active_hand_has_doubled());
+        assert!(!game.active_hand_has_surrendered());
+    }
+
+    #[test]
+    fn test_active_hand_past_last_spot() {
+        let mut game = game_with_hands(vec![vec![vec![Card::TenSpades, Card::KingHearts]]]);
+        assert!(!game.move_to_next_hand_or_spot());
+        assert_eq!(game.active_hand_value(), 0);
+        assert_eq!(game.active_hand_card_count(), 0);
+        assert!(!game.active_hand_is_pair());
+        assert!(!game.active_hand_has_doubled());
+    }
+
+    #[test]
+    fn test_active_hand_value_soft_and_pair() {
+        let mut game = game_with_hands(vec![
+            vec![vec![Card::AceSpades, Card::SixHearts]],
+            vec![vec![Card::EightSpades, Card::EightDiamonds]],
+            vec![vec![Card::TenSpades, Card::KingHearts, Card::AceClubs]],
+        ]);
+
+        assert_eq!(game.active_hand_value(), 17);
+        assert!(is_soft(&game));
+        assert!(!game.active_hand_is_pair());
+
+        game.move_to_next_hand_or_spot();
+        assert_eq!(game.active_hand_value(), 16);
+        assert!(!is_soft(&game));
+        assert!(game.active_hand_is_pair());
+
+        // Ten and king are a pair only by value, and three cards are never a pair
+        game.move_to_next_hand_or_spot();
+        assert_eq!(game.active_hand_value(), 21);
+        assert!(!is_soft(&game));
+        assert!(!game.active_hand_is_pair());
+        assert_eq!(game.active_hand_card_count(), 3);
+    }
+
+    #[test]
+    fn test_active_hand_after_multiple_splits() {
+        let mut game = game_with_hands(vec![vec![
+            vec![Card::EightSpades, Card::ThreeHearts],
+            vec![Card::EightHearts, Card::EightClubs],
+            vec![Card::EightDiamonds, Card::TwoSpades, Card::FiveClubs],
+        ]]);
+        game.hands_doubled[0][0] = true;
+        game.hands_surrendered[0][2] = true;
+
+        assert_eq!(game.active_hand_value(), 11);
+        assert!(game.active_hand_has_doubled());
+        assert!(!game.active_hand_has_surrendered());
+
+        assert!(game.move_to_next_hand_or_spot());
+        assert!(game.active_hand_is_pair());
+        assert!(!game.active_hand_has_doubled());
+
+        assert!(game.move_to_next_hand_or_spot());
+        assert_eq!(game.active_hand_card_count(), 3);
+        assert_eq!(game.active_hand_value(), 15);
+        assert!(game.active_hand_has_surrendered());
+    }
+
+    #[test]
+    fn test_spots_wrap_when_hands_do_not_fit() {
+        let pair = || vec![Card::EightSpades, Card::EightHearts];
+        let mut spots: Vec<Vec<Vec<Card>>> = (0..8).map(|_| vec![pair()]).collect();
+        spots[5] = vec![pair(), pair(), pair()];
+        let mut game = game_with_hands(spots);
+        assert_eq!(game.total_hands(), 10);
+
+        // 10 hands need 80 columns
+        assert_eq!(game.spot_rows(80), vec![0..8]);
+        assert_eq!(game.spot_rows(79), vec![0..4, 4..8]);
+
+        game.active_spot = 5;
+        game.active_hand_in_spot = 2;
+        assert_eq!(
+            game.active_hand_display_position(120),
+            LayoutPosition { row: 0, column: 7 }
+        );
+        assert_eq!(
+            game.active_hand_display_position(60),
+            LayoutPosition { row: 1, column: 3 }
+        );
+
+        // A few spots never wrap, however narrow
+        let game = game_with_hands(vec![vec![pair(), pair(), pair()]; 4]);
+        assert_eq!(game.spot_rows(20), vec![0..4]);
+    }
+}
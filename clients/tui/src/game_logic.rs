@@ -6,7 +6,7 @@
 //! This module provides conversion functions to use blackjack package logic
 //! while maintaining TUI's cryptographic operations
 
-use crate::game::GameState as TuiGameState;
+use crate::game::{GameMode, GameState as TuiGameState};
 use blackjack::{Card, GamePhase, GameState as BlackjackState, Hand, Spot, TurnOwner};
 
 impl TuiGameState {
@@ -32,6 +32,9 @@ impl TuiGameState {
                 bj_hand.doubled = self.hands_doubled[spot_idx][hand_idx];
                 bj_hand.stood = self.hands_stood[spot_idx][hand_idx];
                 bj_hand.surrendered = self.hands_surrendered[spot_idx][hand_idx];
+                bj_hand.split_from =
+                    self.hands_split_from[spot_idx][hand_idx].map(|parent| (spot_idx, parent));
+                bj_hand.split_depth = self.hands_split_depth[spot_idx][hand_idx];
 
                 spot.hands.push(bj_hand);
             }
@@ -80,6 +83,11 @@ impl TuiGameState {
             dealer_peeked: self.dealer_peeked,
             rules: self.rules,
             last_action_timestamp: None, // TUI doesn't track timestamps
+            // Local games have no bets, so insurance is never offered
+            initial_bet: 0,
+            insurance_offered: true,
+            insurance_bet: None,
+            history: self.history.clone(),
         }
     }
 
@@ -89,12 +97,6 @@ impl TuiGameState {
         bj_state.dealer_should_hit()
     }
 
-    /// Use blackjack package logic to check if dealer should peek for blackjack
-    pub fn should_dealer_peek(&self) -> bool {
-        let bj_state = self.to_blackjack_state();
-        bj_state.should_dealer_peek()
-    }
-
     /// Use blackjack package logic to check if dealer has blackjack
     pub fn dealer_has_blackjack(&self) -> bool {
         let bj_state = self.to_blackjack_state();
@@ -119,12 +121,74 @@ impl TuiGameState {
         let spot = bj_state.active_spot();
         spot.can_split(&self.rules)
     }
+
+    /// Build a TUI state showing a blackjack state, for replays. The deck is left
+    /// empty, so it can only be displayed, not dealt from.
+    pub fn from_blackjack_state(
+        bj_state: &BlackjackState,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut tui_state = TuiGameState::new(GameMode::Fast, bj_state.spots.len())?;
+        let hand_flags = |flag: fn(&Hand) -> bool| -> Vec<Vec<bool>> {
+            bj_state
+                .spots
+                .iter()
+                .map(|spot| spot.hands.iter().map(flag).collect())
+                .collect()
+        };
+
+        tui_state.player_hands = bj_state
+            .spots
+            .iter()
+            .map(|spot| {
+                spot.hands
+                    .iter()
+                    .map(|hand| hand.cards.iter().copied().map(Some).collect())
+                    .collect()
+            })
+            .collect();
+        tui_state.hands_doubled = hand_flags(|hand| hand.doubled);
+        tui_state.hands_stood = hand_flags(|hand| hand.stood);
+        tui_state.hands_surrendered = hand_flags(|hand| hand.surrendered);
+        tui_state.hands_split_from = bj_state
+            .spots
+            .iter()
+            .map(|spot| {
+                spot.hands
+                    .iter()
+                    .map(|hand| hand.split_from.map(|(_, parent)| parent))
+                    .collect()
+            })
+            .collect();
+        tui_state.hands_split_depth = bj_state
+            .spots
+            .iter()
+            .map(|spot| spot.hands.iter().map(|hand| hand.split_depth).collect())
+            .collect();
+        tui_state.dealer_hand = bj_state.dealer_hand.iter().copied().map(Some).collect();
+        tui_state.rules = bj_state.rules;
+        tui_state.active_spot = bj_state.active_spot_index;
+        tui_state.active_hand_in_spot = bj_state
+            .spots
+            .get(bj_state.active_spot_index)
+            .map_or(0, |spot| spot.active_hand_index);
+        tui_state.dealer_peeked = bj_state.dealer_peeked;
+        tui_state.history = bj_state.history.clone();
+
+        Ok(tui_state)
+    }
+
+    /// Label for a hand in logs, e.g. "Spot 2" or "Spot 2.1" after a split
+    pub fn hand_label(&self, spot: usize, hand: usize) -> String {
+        self.to_blackjack_state().spots.get(spot).map_or_else(
+            || format!("Spot {}", spot + 1),
+            |s| s.hand_label(spot, hand),
+        )
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::game::GameMode;
 
     #[test]
     fn test_to_blackjack_state() {
@@ -135,4 +199,47 @@ mod tests {
         assert_eq!(bj_state.active_spot_index, 0);
         assert_eq!(bj_state.phase, GamePhase::NotStarted);
     }
+
+    #[test]
+    fn test_hand_label_follows_splits() {
+        let mut tui_state = TuiGameState::new(GameMode::Fast, 2).unwrap();
+        assert_eq!(tui_state.hand_label(1, 0), "Spot 2");
+
+        // Spot 2 split, then its second hand split again (inserted after its parent)
+        tui_state.player_hands[1] = vec![vec![]; 3];
+        tui_state.hands_doubled[1] = vec![false; 3];
+        tui_state.hands_stood[1] = vec![false; 3];
+        tui_state.hands_surrendered[1] = vec![false; 3];
+        tui_state.hands_split_from[1] = vec![None, Some(0), Some(1)];
+        tui_state.hands_split_depth[1] = vec![1, 2, 2];
+
+        assert_eq!(tui_state.hand_label(1, 0), "Spot 2.1");
+        assert_eq!(tui_state.hand_label(1, 1), "Spot 2.2.1");
+        assert_eq!(tui_state.hand_label(1, 2), "Spot 2.2.2");
+        assert_eq!(tui_state.hand_label(0, 0), "Spot 1");
+    }
+
+    #[test]
+    fn test_from_blackjack_state_round_trip() {
+        let mut bj_state = BlackjackState::new(2, blackjack::GameRules::default()).unwrap();
+        bj_state.spots[0].hands[0].add_card(Card::EightHearts);
+        bj_state.spots[0].hands[0].add_card(Card::EightSpades);
+        let rules = bj_state.rules;
+        bj_state.spots[0].split(&rules, 0).unwrap();
+        bj_state.spots[0].hands[1].stood = true;
+        bj_state.spots[1].hands[0].add_card(Card::KingClubs);
+        bj_state.dealer_hand = vec![Card::SixHearts, Card::TenSpades];
+
+        let tui_state = TuiGameState::from_blackjack_state(&bj_state).unwrap();
+        assert_eq!(tui_state.player_hands[0].len(), 2);
+        assert_eq!(tui_state.hands_stood[0], vec![false, true]);
+        assert_eq!(tui_state.hands_split_from[0], vec![None, Some(0)]);
+        assert_eq!(tui_state.dealer_hand.len(), 2);
+
+        let back = tui_state.to_blackjack_state();
+        assert_eq!(back.spots[0].hands[0].cards, vec![Card::EightHearts]);
+        assert_eq!(back.spots[0].hands[1].split_depth, 1);
+        assert_eq!(back.spots[1].hands[0].cards, vec![Card::KingClubs]);
+        assert_eq!(back.dealer_hand, bj_state.dealer_hand);
+    }
 }
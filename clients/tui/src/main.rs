@@ -1,4 +1,3 @@
-use ark_ff::{BigInteger, PrimeField};
 use base64::{engine::general_purpose, Engine as _};
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
@@ -10,23 +9,41 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
     Frame, Terminal,
 };
 use std::sync::mpsc as std_mpsc;
 use std::sync::{Arc, Mutex};
 use std::{error::Error, io};
 
+mod build_info;
+
 mod game;
-use game::{GameMode, GameState};
+use game::{GameMode, GameState, LayoutPosition};
 
 mod game_logic;
 
+mod card_view;
+use card_view::CardDisplayMode;
+
+mod confirm;
+use confirm::{AppAction, AppConfig, ConfirmDialog};
+
 mod contract_msg;
 
 mod tui_logger;
 use tui_logger::TuiLogger;
 
+use zk_shuffle::prover::{Prover, RapidsnarkProver};
+
+#[cfg(feature = "wallet")]
+mod replay;
+use replay::{Playback, ReplayFile};
+
+mod saved_shuffle;
+
+mod session;
+use session::SessionConfig;
 #[cfg(feature = "wallet")]
 mod wallet;
 #[cfg(feature = "wallet")]
@@ -63,11 +80,47 @@ enum InputMode {
     ContractAddress,
 }
 
+/// Panel that [Up]/[Down] act on, cycled with [Tab]. Letter keys always go to the game.
+#[derive(Clone, Copy, PartialEq)]
+enum FocusedPanel {
+    GameArea,
+    LogPanel,
+}
+
+impl FocusedPanel {
+    /// Nothing picked: arrows play the game
+    fn none() -> Self {
+        FocusedPanel::GameArea
+    }
+}
+
+/// Log entries kept for scrolling back
+const LOG_HISTORY: usize = 200;
+
+/// Joinable games fetched per page; one digit key per game
+#[cfg(feature = "wallet")]
+const GAMES_PAGE_SIZE: u32 = 10;
+
+/// How long an error overlay stays up unless dismissed with [Esc]
+const ERROR_OVERLAY_DURATION: std::time::Duration = std::time::Duration::from_secs(8);
+/// RPC queries older than this turn the connection dot yellow
+const CONNECTION_OK_AGE: std::time::Duration = std::time::Duration::from_secs(5);
+/// ... and older than this turn it red
+const CONNECTION_STALE_AGE: std::time::Duration = std::time::Duration::from_secs(15);
+
+struct ErrorOverlay {
+    message: String,
+    dismiss_at: std::time::Instant,
+}
+
 #[cfg(feature = "wallet")]
 enum Action {
     BalanceUpdated(String),
-    GamesListed(Vec<contract_msg::GameListItem>),
+    GamesListed(contract_msg::GamesPageResponse),
     GameStateUpdated(contract_msg::GameResponse),
+    TimeoutStatusUpdated(contract_msg::TimeoutStatusResponse),
+    PauseStatusUpdated(contract_msg::PauseStatusResponse),
+    RevealRequestsUpdated(contract_msg::RevealRequestsResponse),
     WalletConnected(mob::Client),
     GameJoined {
         client: mob::Client,
@@ -81,7 +134,7 @@ enum Action {
     },
     RevealSubmitted {
         client: mob::Client,
-        card_index: u32,
+        card_indices: Vec<u32>,
     },
     TxFailed {
         action_name: String,
@@ -110,10 +163,18 @@ struct App {
     next_game_task: Option<tokio::task::JoinHandle<Result<GameState, String>>>, // Background pre-shuffle for next game
     spot_outcomes: Vec<Vec<SpotOutcome>>, // Track outcome for each hand in each spot at end of round
     log_visible: bool,                    // Toggle for log visibility
+    focused_panel: FocusedPanel,          // Receives [Up]/[Down]; cycled with [Tab]
+    log_scroll: usize,                    // Entries scrolled back; 0 follows new ones
     help_visible: bool,                   // Toggle for help modal
+    card_display_mode: Option<CardDisplayMode>, // None = pick from terminal width
+    error_overlay: Option<ErrorOverlay>,  // Critical error shown over the game area
+    config: AppConfig,                    // User settings from keybindings.toml
+    confirm_dialog: Option<ConfirmDialog>, // Pending confirmation for an irreversible action
     // Contract mode fields
     #[cfg(feature = "wallet")]
     wallet: Option<Wallet>,
+    #[cfg(feature = "wallet")]
+    shuffle_storage_key: Option<[u8; 32]>, // Encrypts saved shuffles; derived from the mnemonic
     contract_address: Option<String>,
     game_id: Option<u64>, // Current game ID
     rpc_url: String,
@@ -121,10 +182,14 @@ struct App {
     contract_address_input: String, // Buffer for typing contract address
     mnemonic_input: String,         // Buffer for typing mnemonic phrase
     available_games: Vec<contract_msg::GameListItem>, // List of games player can join
+    games_next_cursor: Option<u64>, // Where the next page of joinable games starts, if any
     contract_game_state: Option<contract_msg::GameResponse>, // Current contract game state for display
+    timeout_status: Option<(contract_msg::TimeoutStatusResponse, std::time::Instant)>, // Latest GetTimeoutStatus and when it arrived
     zk_keys: Option<(zk_shuffle::babyjubjub::Fr, zk_shuffle::babyjubjub::Point)>, // (sk, pk) for contract mode reveals
+    prover: Arc<dyn Prover>, // Proof backend for contract mode shuffles and reveals
     wallet_balance: Option<String>, // Wallet balance (e.g., "1000uxion")
     last_balance_poll: Option<std::time::Instant>, // Last time balance was polled
+    contract_paused: bool,   // Latest GetPauseStatus; shows the "Contract paused" banner
     // Non-blocking action channel
     #[cfg(feature = "wallet")]
     action_tx: std_mpsc::Sender<Action>,
@@ -141,26 +206,39 @@ struct App {
     #[cfg(feature = "wallet")]
     game_poll_inflight: bool,
     last_game_poll: Option<std::time::Instant>,
+    last_successful_query: Option<std::time::Instant>, // Last balance or game query that reached the RPC
+    offline: bool, // --offline: Contract mode disabled, no network calls, env config ignored
+    vk_override: Option<std::path::PathBuf>, // --vk-override: verifying keys dir for Trustless mode
+    replay: Option<Playback>, // --replay: a saved round being stepped through
+    last_session: Option<SessionConfig>, // Offered on the mode screen until resumed or dismissed
+    resume_spots: Option<usize>, // Spots for a resumed game, applied once its deck is shuffling
+    save_session: bool, // Write session.toml on game start; off in tests
 }
 
 impl App {
-    fn new(log_buffer: Arc<Mutex<Vec<String>>>) -> App {
+    fn new(log_buffer: Arc<Mutex<Vec<String>>>, prover: Arc<dyn Prover>, offline: bool) -> App {
         #[cfg(feature = "wallet")]
         let (action_tx, action_rx) = std_mpsc::channel();
+        let mut logs = vec![
+            "Welcome to Juodžekas!".to_string(),
+            "Choose your game mode:".to_string(),
+            "[F] Fast - Instant gameplay, no proofs".to_string(),
+            "[T] Trustless - Full ZK proofs, ~1 min setup".to_string(),
+        ];
+        if offline {
+            logs.push("Offline: Contract mode and network calls are disabled".to_string());
+        } else {
+            logs.push("[C] Contract - On-chain with smart contract".to_string());
+        }
+        let status = mode_selection_status(offline);
         App {
             input_mode: InputMode::Normal,
             game_state: None,
             phase: GamePhase::ModeSelection,
             selected_mode: None,
             selected_spots: None,
-            status: "Select mode: [F]ast (instant), [T]rustless (~1 min, ZK proofs), or [C]ontract (on-chain)".to_string(),
-            logs: vec![
-                "Welcome to Juodžekas!".to_string(),
-                "Choose your game mode:".to_string(),
-                "[F] Fast - Instant gameplay, no proofs".to_string(),
-                "[T] Trustless - Full ZK proofs, ~1 min setup".to_string(),
-                "[C] Contract - On-chain with smart contract".to_string(),
-            ],
+            status: status.to_string(),
+            logs,
             log_buffer,
             loading_dots: 0,
             init_task: None,
@@ -169,20 +247,32 @@ impl App {
             next_game_task: None,
             spot_outcomes: Vec::new(),
             log_visible: true,
+            focused_panel: FocusedPanel::none(),
+            log_scroll: 0,
             help_visible: false,
+            card_display_mode: None,
+            error_overlay: None,
+            config: AppConfig::load(),
+            confirm_dialog: None,
             #[cfg(feature = "wallet")]
             wallet: None,
+            #[cfg(feature = "wallet")]
+            shuffle_storage_key: None,
             contract_address: None,
             game_id: None,
             rpc_url: "https://rpc.xion-testnet-2.burnt.com:443".to_string(),
-            chain_id: "xion-testnet-2".to_string(),
+            chain_id: build_info::NETWORK.to_string(),
             contract_address_input: String::new(),
             mnemonic_input: String::new(),
             available_games: Vec::new(),
+            games_next_cursor: None,
             contract_game_state: None,
+            timeout_status: None,
             zk_keys: None,
+            prover,
             wallet_balance: None,
             last_balance_poll: None,
+            contract_paused: false,
             #[cfg(feature = "wallet")]
             action_tx,
             #[cfg(feature = "wallet")]
@@ -198,6 +288,122 @@ impl App {
             #[cfg(feature = "wallet")]
             game_poll_inflight: false,
             last_game_poll: None,
+            last_successful_query: None,
+            offline,
+            vk_override: None,
+            replay: None,
+            last_session: None,
+            resume_spots: None,
+            save_session: false,
+        }
+    }
+
+    /// Offers `session` on the mode screen and pre-fills its contract address.
+    fn offer_last_session(&mut self, session: SessionConfig) {
+        if let Some(addr) = &session.last_contract_address {
+            self.contract_address_input = addr.clone();
+        }
+        let Some(summary) = session.summary() else {
+            return;
+        };
+        if self.offline && session.last_mode == Some(GameMode::Contract) {
+            return;
+        }
+        self.status = format!("[Enter] to resume last config ({summary}) or [C]hange");
+        self.last_session = Some(session);
+    }
+
+    /// [Enter] on the mode screen: start the offered session's mode and spots.
+    fn resume_last_session(&mut self) {
+        let Some(session) = self.last_session.take() else {
+            return;
+        };
+        match session.last_mode {
+            Some(GameMode::Contract) => self.select_contract_mode(),
+            Some(mode) => {
+                self.selected_mode = Some(mode);
+                self.phase = GamePhase::SpotSelection;
+                self.resume_spots = Some(session.last_spots as usize);
+                self.add_log(format!("Resuming last config: {mode:?} mode"));
+            }
+            None => {}
+        }
+    }
+
+    /// [C] while a session is offered: back to the plain mode choice.
+    fn dismiss_last_session(&mut self) {
+        self.last_session = None;
+        self.status = mode_selection_status(self.offline).to_string();
+    }
+
+    /// Saves the current mode, spots and contract so the next launch can resume them.
+    fn remember_session(&self) {
+        if !self.save_session {
+            return;
+        }
+        let Some(mode) = self.selected_mode else {
+            return;
+        };
+        #[cfg(feature = "wallet")]
+        let profile = self.wallet.as_ref().map(|w| w.address().to_string());
+        #[cfg(not(feature = "wallet"))]
+        let profile = None;
+        let session = SessionConfig {
+            last_mode: Some(mode),
+            last_spots: self.selected_spots.unwrap_or(1) as u8,
+            last_contract_address: self.contract_address.clone(),
+            last_profile_address: profile,
+        };
+        if let Err(e) = session.save() {
+            log::warn!("Could not save session: {e}");
+        }
+    }
+
+    /// Environment variable `name`, or None in offline mode so a `.env` meant for
+    /// contract play can't pull in network config.
+    #[cfg(feature = "wallet")]
+    fn env_var(&self, name: &str) -> Option<String> {
+        if self.offline {
+            return None;
+        }
+        std::env::var(name).ok()
+    }
+
+    /// [C] on the mode screen. Does nothing but log in offline mode.
+    fn select_contract_mode(&mut self) {
+        if self.offline {
+            self.add_log("Contract mode is disabled in offline mode".to_string());
+            return;
+        }
+        #[cfg(feature = "wallet")]
+        {
+            self.selected_mode = Some(GameMode::Contract);
+            self.phase = GamePhase::ContractSetup;
+            self.add_log("CONTRACT mode selected".to_string());
+            if self.wallet.is_none() {
+                if let Some(mnemonic) = self.env_var("PLAYER_MNEMONIC") {
+                    self.add_log("Loading wallet from PLAYER_MNEMONIC env...".to_string());
+                    if self.load_wallet_from_mnemonic(&mnemonic) {
+                        self.spawn_wallet_connect();
+                    }
+                } else {
+                    self.add_log("Enter mnemonic or press [G] to generate new wallet".to_string());
+                    self.status = "Enter mnemonic (press Enter to submit)".to_string();
+                    self.input_mode = InputMode::Mnemonic;
+                }
+            } else if self.contract_address.is_some() {
+                self.add_log("Press [L] to list games, or [J] to join after selecting".to_string());
+                self.status = "Press [L] to list available games".to_string();
+            } else {
+                self.add_log("Enter contract address".to_string());
+                self.status = "Enter contract address".to_string();
+                self.input_mode = InputMode::ContractAddress;
+            }
+        }
+        #[cfg(not(feature = "wallet"))]
+        {
+            self.add_log("CONTRACT mode requires wallet feature".to_string());
+            self.add_log("Build with --features wallet to enable".to_string());
         }
     }
 
@@ -209,9 +415,17 @@ impl App {
             Ok(wallet) => {
                 self.add_log(format!("Wallet loaded: {}", wallet.address()));
                 self.wallet = Some(wallet);
+                self.shuffle_storage_key =
+                    Some(zk_shuffle::sealed::storage_key_from_mnemonic(mnemonic));
                 self.input_mode = InputMode::Normal;
+                for (game_id, age) in saved_shuffle::resumable_games() {
+                    self.add_log(format!(
+                        "Saved shuffle for game {game_id} ({}s old): select it and press [J] to resume",
+                        age.as_secs()
+                    ));
+                }
 
-                if let Ok(addr) = std::env::var("CONTRACT_ADDR") {
+                if let Some(addr) = self.env_var("CONTRACT_ADDR") {
                     self.contract_address = Some(addr.clone());
                     self.add_log(format!("Contract: {addr}"));
                     self.add_log(
@@ -272,29 +486,76 @@ impl App {
 
     fn add_log(&mut self, message: String) {
         self.logs.push(message);
-        if self.logs.len() > 20 {
-            let excess = self.logs.len() - 20;
+        if self.logs.len() > LOG_HISTORY {
+            let excess = self.logs.len() - LOG_HISTORY;
             self.logs.drain(..excess);
         }
+        // Keep a scrolled-back view on the same entries
+        if self.log_scroll > 0 {
+            self.log_scroll = (self.log_scroll + 1).min(self.logs.len().saturating_sub(1));
+        }
     }
 
-    fn player_hit(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let (spot, hand_in_spot, player_value, num_hands) =
-            if let Some(ref mut game) = self.game_state {
-                let spot = game.active_spot;
-                let hand_in_spot = game.active_hand_in_spot;
-                let num_hands = game.player_hands[spot].len();
-                game.draw_card(false, Some(spot))?;
-                let value = GameState::calculate_hand_value(&game.player_hands[spot][hand_in_spot]);
-                (spot + 1, hand_in_spot + 1, value, num_hands)
-            } else {
-                return Ok(());
-            };
+    /// [Tab]: move focus to the next visible panel.
+    fn cycle_focus(&mut self) {
+        self.focused_panel = match self.focused_panel {
+            FocusedPanel::GameArea if self.log_visible => FocusedPanel::LogPanel,
+            _ => FocusedPanel::none(),
+        };
+    }
+
+    /// [Up]/[Down] while the log has focus.
+    fn scroll_log(&mut self, back: bool) {
+        self.log_scroll = if back {
+            (self.log_scroll + 1).min(self.logs.len().saturating_sub(1))
+        } else {
+            self.log_scroll.saturating_sub(1)
+        };
+    }
+
+    /// Log an error and show it in the overlay until `duration` passes or [Esc] is pressed.
+    fn show_error(&mut self, msg: String, duration: std::time::Duration) {
+        self.add_log(msg.clone());
+        self.error_overlay = Some(ErrorOverlay {
+            message: msg,
+            dismiss_at: std::time::Instant::now() + duration,
+        });
+    }
+
+    /// Player hands on the table across all spots, in either game.
+    fn total_hands(&self) -> usize {
+        if let Some(ref game) = self.game_state {
+            game.total_hands()
+        } else {
+            self.contract_game_state
+                .as_ref()
+                .map_or(0, |game| game.hands.len())
+        }
+    }
+
+    /// Color of the title bar connection dot, or None when not playing in contract mode.
+    fn connection_color(&self) -> Option<Color> {
+        if self.phase == GamePhase::ModeSelection || self.selected_mode != Some(GameMode::Contract)
+        {
+            return None;
+        }
+        let color = match self.last_successful_query.map(|t| t.elapsed()) {
+            Some(age) if age < CONNECTION_OK_AGE => Color::Green,
+            Some(age) if age <= CONNECTION_STALE_AGE => Color::Yellow,
+            _ => Color::Red,
+        };
+        Some(color)
+    }
 
-        let hand_label = if num_hands > 1 {
-            format!("Spot {spot}.{hand_in_spot}")
+    fn player_hit(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let (hand_label, player_value) = if let Some(ref mut game) = self.game_state {
+            let spot = game.active_spot;
+            let hand_label = game.hand_label(spot, game.active_hand_in_spot);
+            game.draw_card(false, Some(spot))?;
+            game.record_action(blackjack::Action::Hit, game.dealt_cards.last().copied());
+            (hand_label, game.active_hand_value())
         } else {
-            format!("Spot {spot}")
+            return Ok(());
         };
 
         self.add_log(format!("{hand_label} hits"));
@@ -317,13 +578,9 @@ impl App {
             let spot = game.active_spot;
             let hand_in_spot = game.active_hand_in_spot;
             game.hands_stood[spot][hand_in_spot] = true;
+            game.record_action(blackjack::Action::Stand, None);
 
-            let num_hands = game.player_hands[spot].len();
-            let hand_label = if num_hands > 1 {
-                format!("Spot {}.{}", spot + 1, hand_in_spot + 1)
-            } else {
-                format!("Spot {}", spot + 1)
-            };
+            let hand_label = game.hand_label(spot, hand_in_spot);
             self.add_log(format!("{hand_label} stands"));
         }
 
@@ -352,10 +609,7 @@ impl App {
                 return Ok(());
             };
             // More hands/spots to play - check if new hand has 21
-            let current_value = {
-                let game = self.game_state.as_ref().unwrap();
-                GameState::calculate_hand_value(&game.player_hands[active_spot][active_hand])
-            };
+            let current_value = self.game_state.as_ref().unwrap().active_hand_value();
 
             let hand_label = if num_hands_in_spot > 1 {
                 format!(
@@ -421,6 +675,7 @@ impl App {
 
             let dealer_value = if let Some(ref mut game) = self.game_state {
                 game.draw_card(true, None)?;
+                game.record_action(blackjack::Action::Hit, game.dealt_cards.last().copied());
                 GameState::calculate_hand_value(&game.dealer_hand)
             } else {
                 break;
@@ -428,11 +683,11 @@ impl App {
 
             self.add_log(format!("Dealer hits, has {dealer_value}"));
 
-            if dealer_value > 21 {
+            if dealer_value.is_busted() {
                 self.add_log(format!("Dealer busts with {dealer_value}!"));
 
                 // All non-busted, non-surrendered hands win
-                let hands_values: Vec<(usize, usize, u8, bool)> =
+                let hands_values: Vec<(usize, usize, String, blackjack::HandValue, bool)> =
                     if let Some(ref game) = self.game_state {
                         let mut values = Vec::new();
                         for (spot_idx, spot) in game.player_hands.iter().enumerate() {
@@ -441,6 +696,7 @@ impl App {
                                 values.push((
                                     spot_idx,
                                     hand_idx,
+                                    game.hand_label(spot_idx, hand_idx),
                                     GameState::calculate_hand_value(hand),
                                     surrendered,
                                 ));
@@ -465,18 +721,12 @@ impl App {
                         .collect();
                 }
 
-                for (spot_idx, hand_idx, player_value, surrendered) in hands_values {
-                    let hand_label = if self.spot_outcomes[spot_idx].len() > 1 {
-                        format!("Spot {}.{}", spot_idx + 1, hand_idx + 1)
-                    } else {
-                        format!("Spot {}", spot_idx + 1)
-                    };
-
+                for (spot_idx, hand_idx, hand_label, player_value, surrendered) in hands_values {
                     let outcome = if surrendered {
                         self.add_log(format!("{hand_label}: Surrendered (half loss)"));
                         surrenders += 1;
                         SpotOutcome::Surrender
-                    } else if player_value > 21 {
+                    } else if player_value.is_busted() {
                         self.add_log(format!("{hand_label}: Bust (loss)"));
                         losses += 1;
                         SpotOutcome::Loss
@@ -495,12 +745,14 @@ impl App {
                 };
                 self.status = status_msg;
                 self.phase = GamePhase::GameOver;
+                self.save_replay();
                 return Ok(());
             }
         }
 
         // Compare dealer hand against all player hands
-        let (dealer_value, hands_values) = if let Some(ref game) = self.game_state {
+        let (dealer_value, hands_values) = if let Some(ref mut game) = self.game_state {
+            game.record_action(blackjack::Action::Stand, None);
             let dealer_value = GameState::calculate_hand_value(&game.dealer_hand);
             let mut values = Vec::new();
             for (spot_idx, spot) in game.player_hands.iter().enumerate() {
@@ -509,6 +761,7 @@ impl App {
                     values.push((
                         spot_idx,
                         hand_idx,
+                        game.hand_label(spot_idx, hand_idx),
                         GameState::calculate_hand_value(hand),
                         surrendered,
                     ));
@@ -538,18 +791,12 @@ impl App {
                 .collect();
         }
 
-        for (spot_idx, hand_idx, player_value, surrendered) in hands_values {
-            let hand_label = if self.spot_outcomes[spot_idx].len() > 1 {
-                format!("Spot {}.{}", spot_idx + 1, hand_idx + 1)
-            } else {
-                format!("Spot {}", spot_idx + 1)
-            };
-
+        for (spot_idx, hand_idx, hand_label, player_value, surrendered) in hands_values {
             let outcome = if surrendered {
                 self.add_log(format!("{hand_label}: Surrendered (half loss)"));
                 surrenders += 1;
                 SpotOutcome::Surrender
-            } else if player_value > 21 {
+            } else if player_value.is_busted() {
                 self.add_log(format!("{hand_label}: Bust (loss)"));
                 losses += 1;
                 SpotOutcome::Loss
@@ -585,9 +832,77 @@ impl App {
         self.status = status_msg;
 
         self.phase = GamePhase::GameOver;
+        self.save_replay();
         Ok(())
     }
 
+    /// Save the finished local round so it can be watched again with `--replay`.
+    fn save_replay(&mut self) {
+        let Some(ref game) = self.game_state else {
+            return;
+        };
+        let file = ReplayFile {
+            num_spots: game.num_spots,
+            rules: game.rules,
+            deck: game.dealt_cards.clone(),
+            history: game.history.clone(),
+            client_version: build_info::version_string(),
+        };
+        match file.save() {
+            Ok(path) => self.add_log(format!("Replay saved to {}", path.display())),
+            Err(e) => self.add_log(format!("Could not save replay: {e}")),
+        }
+    }
+
+    /// Show `file` from its first action instead of starting a game.
+    fn start_replay(&mut self, file: ReplayFile) {
+        self.add_log(format!(
+            "Replaying {} actions: [Right]/[N] step forward, [Left]/[B] step back",
+            file.steps()
+        ));
+        self.replay = Some(Playback { file, step: 0 });
+        self.show_replay_step(0);
+    }
+
+    /// Rebuild the replayed round after `step` actions and show it.
+    fn show_replay_step(&mut self, step: usize) {
+        let Some(ref mut playback) = self.replay else {
+            return;
+        };
+        let step = step.min(playback.file.steps());
+        playback.step = step;
+        let total = playback.file.steps();
+        let state = match playback.file.state_at(step) {
+            Ok(state) => state,
+            Err(e) => {
+                self.add_log(format!("Replay error: {e}"));
+                return;
+            }
+        };
+        let action = step
+            .checked_sub(1)
+            .and_then(|i| state.history.entries.get(i))
+            .map(|entry| entry.action);
+
+        self.phase = match state.phase {
+            blackjack::GamePhase::DealerTurn => GamePhase::DealerTurn,
+            blackjack::GamePhase::Settled => GamePhase::GameOver,
+            _ => GamePhase::PlayerTurn,
+        };
+        match GameState::from_blackjack_state(&state) {
+            Ok(game) => self.game_state = Some(game),
+            Err(e) => {
+                self.add_log(format!("Replay error: {e}"));
+                return;
+            }
+        }
+        self.spot_outcomes.clear();
+        self.status = match action {
+            Some(action) => format!("Replay {step}/{total}: {action:?}"),
+            None => format!("Replay 0/{total}: initial deal"),
+        };
+    }
+
     /// Display contract game payouts (sync — just formats strings).
     #[cfg(feature = "wallet")]
     fn display_contract_payouts(&mut self, game: &contract_msg::GameResponse) {
@@ -641,7 +956,7 @@ impl App {
 
     #[cfg(feature = "wallet")]
     fn spawn_wallet_connect(&mut self) {
-        if self.pending_op.is_some() {
+        if self.offline || self.pending_op.is_some() {
             return;
         }
         let wallet = match self.wallet.as_mut() {
@@ -677,7 +992,7 @@ impl App {
 
     #[cfg(feature = "wallet")]
     fn spawn_query_balance(&mut self) {
-        if self.balance_poll_inflight {
+        if self.offline || self.balance_poll_inflight {
             return;
         }
         let wallet = match self.wallet.as_ref() {
@@ -689,6 +1004,7 @@ impl App {
         }
         let address = wallet.address().to_string();
         let rpc_url = wallet.client().unwrap().config().rpc_endpoint.clone();
+        let contract_addr = self.contract_address.clone();
         let tx = self.action_tx.clone();
         self.balance_poll_inflight = true;
 
@@ -702,12 +1018,28 @@ impl App {
                     let _ = tx.send(Action::BalanceUpdated(String::new()));
                 }
             }
+            // Rides along with the balance poll; contracts without the query never show the banner
+            if let Some(contract_addr) = contract_addr {
+                match query_pause_status_standalone(&rpc_url, &contract_addr).await {
+                    Ok(status) => {
+                        let _ = tx.send(Action::PauseStatusUpdated(status));
+                    }
+                    Err(e) => log::debug!("Pause status poll failed: {e}"),
+                }
+            }
         });
     }
 
     #[cfg(feature = "wallet")]
     fn spawn_list_games(&mut self) {
-        if self.pending_op.is_some() {
+        self.spawn_games_page(None);
+    }
+
+    /// Fetch a page of joinable games, `GAMES_PAGE_SIZE` at a time so each can be
+    /// picked with a digit key
+    #[cfg(feature = "wallet")]
+    fn spawn_games_page(&mut self, start_after: Option<u64>) {
+        if self.offline || self.pending_op.is_some() {
             return;
         }
         let rpc_url = self.rpc_url.clone();
@@ -724,11 +1056,12 @@ impl App {
                 &rpc_url,
                 &contract_addr,
                 Some("WaitingForPlayerJoin".to_string()),
+                start_after,
             )
             .await
             {
-                Ok(games) => {
-                    let _ = tx.send(Action::GamesListed(games));
+                Ok(page) => {
+                    let _ = tx.send(Action::GamesListed(page));
                 }
                 Err(e) => {
                     let _ = tx.send(Action::OpFailed {
@@ -742,7 +1075,7 @@ impl App {
 
     #[cfg(feature = "wallet")]
     fn spawn_query_game_state(&mut self) {
-        if self.game_poll_inflight {
+        if self.offline || self.game_poll_inflight {
             return;
         }
         let game_id = match self.game_id {
@@ -760,7 +1093,25 @@ impl App {
         tokio::spawn(async move {
             match query_game_by_id_standalone(&rpc_url, &contract_addr, game_id).await {
                 Ok(game) => {
+                    let waiting_for_reveal = game.status.contains("WaitingForReveal");
                     let _ = tx.send(Action::GameStateUpdated(game));
+                    if waiting_for_reveal {
+                        match query_reveal_requests_standalone(&rpc_url, &contract_addr, game_id)
+                            .await
+                        {
+                            Ok(requests) => {
+                                let _ = tx.send(Action::RevealRequestsUpdated(requests));
+                            }
+                            Err(e) => log::debug!("Reveal requests poll failed: {e}"),
+                        }
+                    }
+                    // Contracts without this query just never show the countdown
+                    match query_timeout_status_standalone(&rpc_url, &contract_addr, game_id).await {
+                        Ok(status) => {
+                            let _ = tx.send(Action::TimeoutStatusUpdated(status));
+                        }
+                        Err(e) => log::debug!("Timeout status poll failed: {e}"),
+                    }
                 }
                 Err(e) => {
                     log::debug!("Game poll failed: {e}");
@@ -775,7 +1126,7 @@ impl App {
 
     #[cfg(feature = "wallet")]
     fn spawn_join_game(&mut self) {
-        if self.pending_op.is_some() {
+        if self.offline || self.pending_op.is_some() {
             return;
         }
         if self.wallet.is_none() {
@@ -804,6 +1155,8 @@ impl App {
         let rpc_url = self.rpc_url.clone();
         let log_buffer = Arc::clone(&self.log_buffer);
         let tx = self.action_tx.clone();
+        let prover = Arc::clone(&self.prover);
+        let storage_key = self.shuffle_storage_key;
 
         self.pending_op = Some("Joining: generating keypair (~1s)".to_string());
         self.pending_op_start = Some(std::time::Instant::now());
@@ -820,79 +1173,122 @@ impl App {
                     .build()?;
                 let _rt_guard = rt.enter();
 
-                push_log(&log_buffer, "Generating player keypair...");
-
                 use ark_ec::{AffineRepr, CurveGroup};
                 use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
-                use zk_shuffle::babyjubjub::Point;
+                use zk_shuffle::babyjubjub::{Point, PointExt};
                 use zk_shuffle::elgamal::{Ciphertext, KeyPair};
                 use zk_shuffle::proof::{
-                    generate_shuffle_proof_rapidsnark, CanonicalDeserialize, CanonicalSerialize,
+                    CanonicalDeserialize, CanonicalSerialize, ProofInputSerializer,
                 };
                 use zk_shuffle::shuffle::shuffle;
 
                 let mut rng = ChaCha8Rng::from_entropy();
-                let player_keys = KeyPair::generate(&mut rng);
-
-                push_log(&log_buffer, &format!("Querying game {game_id}..."));
-                if let Ok(mut g) = op_override.lock() {
-                    *g = Some("Joining: querying game (~2s)".into());
-                }
-                let dealer_game: contract_msg::GameResponse = rt.block_on(
-                    query_game_by_id_standalone(&rpc_url, &contract_addr, game_id),
-                )?;
-
-                let dealer_shuffled = dealer_game
-                    .player_shuffled_deck
-                    .ok_or("Dealer hasn't shuffled deck yet")?;
-                push_log(
-                    &log_buffer,
-                    &format!(
-                        "Retrieved dealer's shuffled deck ({} cards)",
-                        dealer_shuffled.len()
-                    ),
-                );
 
-                let dealer_deck: Vec<Ciphertext> = dealer_shuffled
-                    .iter()
-                    .map(|binary| {
-                        let mut cursor = binary.as_slice();
-                        let c0 = Point::deserialize_compressed(&mut cursor)
-                            .map_err(|e| format!("Failed to deserialize c0: {e}"))?;
-                        let c1 = Point::deserialize_compressed(&mut cursor)
-                            .map_err(|e| format!("Failed to deserialize c1: {e}"))?;
-                        Ok(Ciphertext { c0, c1 })
-                    })
-                    .collect::<Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>>>()?;
+                // Reuse a shuffle saved by an earlier attempt at this join (and its proof, if done)
+                let resumed = storage_key
+                    .filter(|_| saved_shuffle::resumable_age(game_id).is_some())
+                    .and_then(|key| match saved_shuffle::load(game_id, &key) {
+                        Ok(saved) => Some(saved),
+                        Err(e) => {
+                            push_log(&log_buffer, &format!("Ignoring saved shuffle: {e}"));
+                            None
+                        }
+                    });
 
-                let dealer_pk =
-                    Point::deserialize_compressed(&mut dealer_game.dealer_pubkey.as_slice())
-                        .map_err(|e| format!("Failed to deserialize dealer pubkey: {e}"))?;
+                let (player_keys, player_shuffle, saved_proof) = match resumed {
+                    Some(saved) => {
+                        push_log(
+                            &log_buffer,
+                            &format!("Resuming saved shuffle for game {game_id}"),
+                        );
+                        (saved.keys, saved.shuffle, saved.proof)
+                    }
+                    None => {
+                        push_log(&log_buffer, "Generating player keypair...");
+                        let player_keys = KeyPair::generate(&mut rng);
 
-                let aggregated_pk =
-                    (player_keys.pk.into_group() + dealer_pk.into_group()).into_affine();
+                        push_log(&log_buffer, &format!("Querying game {game_id}..."));
+                        if let Ok(mut g) = op_override.lock() {
+                            *g = Some("Joining: querying game (~2s)".into());
+                        }
+                        let dealer_game: contract_msg::GameResponse = rt.block_on(
+                            query_game_by_id_standalone(&rpc_url, &contract_addr, game_id),
+                        )?;
+
+                        let dealer_shuffled = dealer_game
+                            .player_shuffled_deck
+                            .ok_or("Dealer hasn't shuffled deck yet")?;
+                        push_log(
+                            &log_buffer,
+                            &format!(
+                                "Retrieved dealer's shuffled deck ({} cards)",
+                                dealer_shuffled.len()
+                            ),
+                        );
 
-                push_log(&log_buffer, "Shuffling deck...");
-                if let Ok(mut g) = op_override.lock() {
-                    *g = Some("Joining: shuffling deck (~2s)".into());
-                }
-                let player_shuffle = shuffle(&mut rng, &dealer_deck, &aggregated_pk);
+                        let dealer_deck: Vec<Ciphertext> = dealer_shuffled
+                            .iter()
+                            .map(|binary| {
+                                let mut cursor = binary.as_slice();
+                                let c0 = Point::deserialize_compressed(&mut cursor)
+                                    .map_err(|e| format!("Failed to deserialize c0: {e}"))?;
+                                let c1 = Point::deserialize_compressed(&mut cursor)
+                                    .map_err(|e| format!("Failed to deserialize c1: {e}"))?;
+                                Ok(Ciphertext { c0, c1 })
+                            })
+                            .collect::<Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>>>(
+                            )?;
+
+                        let dealer_pk =
+                            Point::from_bytes_validated(dealer_game.dealer_pubkey.as_slice())
+                                .map_err(|e| format!("Invalid dealer pubkey: {e}"))?;
+
+                        let aggregated_pk =
+                            (player_keys.pk.into_group() + dealer_pk.into_group()).into_affine();
+
+                        push_log(&log_buffer, "Shuffling deck...");
+                        if let Ok(mut g) = op_override.lock() {
+                            *g = Some("Joining: shuffling deck (~2s)".into());
+                        }
+                        let player_shuffle = shuffle(&mut rng, &dealer_deck, &aggregated_pk);
+                        if let Some(key) = storage_key {
+                            if let Err(e) =
+                                saved_shuffle::save(game_id, &key, &player_keys, &player_shuffle)
+                            {
+                                push_log(&log_buffer, &format!("Could not save shuffle: {e}"));
+                            }
+                        }
+                        (player_keys, player_shuffle, None)
+                    }
+                };
 
-                push_log(
-                    &log_buffer,
-                    "Generating ZK proof (this may take ~1 minute)...",
-                );
-                if let Ok(mut g) = op_override.lock() {
-                    *g = Some("Joining: generating proof (~60s)".into());
-                }
-                let player_proof = generate_shuffle_proof_rapidsnark(
-                    &player_shuffle.public_inputs,
-                    player_shuffle.private_inputs,
-                )
-                .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
-                    e.to_string().into()
-                })?;
-                push_log(&log_buffer, "Proof generated!");
+                let player_proof = match saved_proof {
+                    Some(proof) => {
+                        push_log(&log_buffer, "Using saved shuffle proof");
+                        proof
+                    }
+                    None => {
+                        player_shuffle.public_inputs.validate()?;
+                        push_log(
+                            &log_buffer,
+                            "Generating ZK proof (this may take ~1 minute)...",
+                        );
+                        if let Ok(mut g) = op_override.lock() {
+                            *g = Some("Joining: generating proof (~60s)".into());
+                        }
+                        let proof = prover.prove_shuffle(
+                            &player_shuffle.public_inputs,
+                            player_shuffle.private_inputs,
+                        )?;
+                        if storage_key.is_some() {
+                            if let Err(e) = saved_shuffle::save_proof(game_id, &proof) {
+                                push_log(&log_buffer, &format!("Could not save proof: {e}"));
+                            }
+                        }
+                        push_log(&log_buffer, "Proof generated!");
+                        proof
+                    }
+                };
 
                 let serialize_point =
                     |p: &Point| -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
@@ -911,18 +1307,9 @@ impl App {
                 };
 
                 let proof_json = serde_json::to_string(&player_proof)?;
-                let public_inputs_strs: Vec<String> = player_shuffle
-                    .public_inputs
-                    .to_ark_public_inputs()
-                    .iter()
-                    .map(|f| {
-                        let bigint = num_bigint::BigInt::from_bytes_le(
-                            num_bigint::Sign::Plus,
-                            &f.into_bigint().to_bytes_le(),
-                        );
-                        bigint.to_string()
-                    })
-                    .collect();
+                let public_inputs_strs = ProofInputSerializer::fields_to_strings(
+                    &player_shuffle.public_inputs.to_ark_public_inputs(),
+                );
 
                 let config: contract_msg::Config =
                     rt.block_on(query_config_standalone(&rpc_url, &contract_addr))?;
@@ -975,6 +1362,7 @@ impl App {
                     &log_buffer,
                     &format!("Transaction confirmed! Hash: {}", tx_response.txhash),
                 );
+                saved_shuffle::remove(game_id);
                 Ok((player_keys.sk, player_keys.pk))
             })();
 
@@ -996,7 +1384,7 @@ impl App {
     /// Spawn a simple contract TX (hit, stand, surrender, claim_timeout).
     #[cfg(feature = "wallet")]
     fn spawn_simple_tx(&mut self, action_name: &str, msg_json: serde_json::Value) {
-        if self.pending_op.is_some() {
+        if self.offline || self.pending_op.is_some() {
             return;
         }
         let wallet = match self.wallet.as_mut() {
@@ -1065,7 +1453,7 @@ impl App {
     /// Spawn double_down or split (needs funds query).
     #[cfg(feature = "wallet")]
     fn spawn_funded_tx(&mut self, action_name: &str, msg_json: serde_json::Value) {
-        if self.pending_op.is_some() {
+        if self.offline || self.pending_op.is_some() {
             return;
         }
         let wallet = match self.wallet.as_mut() {
@@ -1224,7 +1612,7 @@ impl App {
 
     #[cfg(feature = "wallet")]
     fn spawn_insurance(&mut self) {
-        if self.pending_op.is_some() {
+        if self.offline || self.pending_op.is_some() {
             return;
         }
         let wallet = match self.wallet.as_mut() {
@@ -1327,8 +1715,8 @@ impl App {
     }
 
     #[cfg(feature = "wallet")]
-    fn spawn_submit_reveal(&mut self, card_index: u32) {
-        if self.pending_op.is_some() {
+    fn spawn_submit_reveal(&mut self, card_indices: Vec<u32>) {
+        if self.offline || self.pending_op.is_some() || card_indices.is_empty() {
             return;
         }
         if self.wallet.is_none() {
@@ -1362,10 +1750,12 @@ impl App {
         let rpc_url = self.rpc_url.clone();
         let log_buffer = Arc::clone(&self.log_buffer);
         let tx = self.action_tx.clone();
+        let prover = Arc::clone(&self.prover);
 
-        self.pending_op = Some(format!("Reveal card {card_index}: decrypting (~1s)"));
+        let cards = format!("{card_indices:?}");
+        self.pending_op = Some(format!("Reveal cards {cards}: decrypting (~1s)"));
         self.pending_op_start = Some(std::time::Instant::now());
-        self.add_log(format!("Submitting reveal for card {card_index}..."));
+        self.add_log(format!("Submitting reveal for cards {cards}..."));
         let op_override = Arc::clone(&self.pending_op_override);
 
         std::thread::spawn(move || {
@@ -1382,70 +1772,65 @@ impl App {
                     game_id,
                 ))?;
 
-                if card_index as usize >= game.deck.len() {
-                    return Err(format!("Invalid card_index: {card_index}").into());
-                }
-
                 use zk_shuffle::babyjubjub::Point;
                 use zk_shuffle::decrypt::reveal_card;
                 use zk_shuffle::elgamal::Ciphertext;
                 use zk_shuffle::proof::{
-                    generate_reveal_proof_rapidsnark, CanonicalDeserialize, CanonicalSerialize,
+                    CanonicalDeserialize, CanonicalSerialize, ProofInputSerializer,
                 };
 
-                let card_binary = &game.deck[card_index as usize];
-                let mut cursor = card_binary.as_slice();
-                let c0 = Point::deserialize_compressed(&mut cursor)
-                    .map_err(|e| format!("Failed to deserialize card c0: {e}"))?;
-                let c1 = Point::deserialize_compressed(&mut cursor)
-                    .map_err(|e| format!("Failed to deserialize card c1: {e}"))?;
-                let encrypted_card = Ciphertext { c0, c1 };
-
-                push_log(&log_buffer, &format!("Revealing card {card_index}..."));
-                let reveal = reveal_card(&sk, &encrypted_card, &pk);
+                // Every pending card goes into one SubmitBatchReveal transaction
+                let mut items = Vec::with_capacity(card_indices.len());
+                for &card_index in &card_indices {
+                    if card_index as usize >= game.deck.len() {
+                        return Err(format!("Invalid card_index: {card_index}").into());
+                    }
 
-                push_log(&log_buffer, "Generating reveal proof...");
-                if let Ok(mut g) = op_override.lock() {
-                    *g = Some(format!("Reveal card {card_index}: generating proof (~10s)"));
-                }
-                let reveal_proof =
-                    generate_reveal_proof_rapidsnark(&reveal.public_inputs, reveal.sk_p).map_err(
-                        |e| -> Box<dyn std::error::Error + Send + Sync> { e.to_string().into() },
-                    )?;
+                    let card_binary = &game.deck[card_index as usize];
+                    let mut cursor = card_binary.as_slice();
+                    let c0 = Point::deserialize_compressed(&mut cursor)
+                        .map_err(|e| format!("Failed to deserialize card c0: {e}"))?;
+                    let c1 = Point::deserialize_compressed(&mut cursor)
+                        .map_err(|e| format!("Failed to deserialize card c1: {e}"))?;
+                    let encrypted_card = Ciphertext { c0, c1 };
 
-                let mut partial_buf = Vec::new();
-                reveal
-                    .partial_decryption
-                    .serialize_compressed(&mut partial_buf)
-                    .map_err(|e| format!("Failed to serialize partial decryption: {e}"))?;
-                let proof_json = serde_json::to_string(&reveal_proof)?;
-                let public_inputs_strs: Vec<String> = reveal
-                    .public_inputs
-                    .to_ark_public_inputs()
-                    .iter()
-                    .map(|f| {
-                        let bigint = num_bigint::BigInt::from_bytes_le(
-                            num_bigint::Sign::Plus,
-                            &f.into_bigint().to_bytes_le(),
-                        );
-                        bigint.to_string()
-                    })
-                    .collect();
+                    push_log(&log_buffer, &format!("Revealing card {card_index}..."));
+                    let reveal = reveal_card(&sk, &encrypted_card, &pk);
 
-                let msg_json = serde_json::json!({
-                    "submit_reveal": {
-                        "game_id": game_id,
+                    push_log(&log_buffer, "Generating reveal proof...");
+                    if let Ok(mut g) = op_override.lock() {
+                        *g = Some(format!("Reveal card {card_index}: generating proof (~10s)"));
+                    }
+                    let reveal_proof = prover.prove_reveal(&reveal.public_inputs, reveal.sk_p)?;
+
+                    let mut partial_buf = Vec::new();
+                    reveal
+                        .partial_decryption
+                        .serialize_compressed(&mut partial_buf)
+                        .map_err(|e| format!("Failed to serialize partial decryption: {e}"))?;
+                    let proof_json = serde_json::to_string(&reveal_proof)?;
+                    let public_inputs_strs = ProofInputSerializer::fields_to_strings(
+                        &reveal.public_inputs.to_ark_public_inputs(),
+                    );
+                    items.push(serde_json::json!({
                         "card_index": card_index,
                         "partial_decryption": general_purpose::STANDARD.encode(&partial_buf),
                         "proof": general_purpose::STANDARD.encode(&proof_json),
                         "public_inputs": public_inputs_strs,
+                    }));
+                }
+
+                let msg_json = serde_json::json!({
+                    "submit_batch_reveal": {
+                        "game_id": game_id,
+                        "reveals": items,
                     }
                 });
                 let msg_bytes = serde_json::to_vec(&msg_json)?;
 
                 push_log(&log_buffer, "Submitting reveal to contract...");
                 if let Ok(mut g) = op_override.lock() {
-                    *g = Some(format!("Reveal card {card_index}: broadcasting TX (~5s)"));
+                    *g = Some(format!("Reveal cards {cards}: broadcasting TX (~5s)"));
                 }
                 let tx_response = execute_and_confirm_standalone(
                     &client,
@@ -1468,7 +1853,10 @@ impl App {
 
             match result {
                 Ok(()) => {
-                    let _ = tx.send(Action::RevealSubmitted { client, card_index });
+                    let _ = tx.send(Action::RevealSubmitted {
+                        client,
+                        card_indices,
+                    });
                 }
                 Err(e) => {
                     let _ = tx.send(Action::TxFailed {
@@ -1538,17 +1926,10 @@ impl App {
             self.add_log("Cannot double down now".to_string());
             return;
         }
-        let (spot, hand, num_hands, player_value) = if let Some(ref mut game) = self.game_state {
-            let spot = game.active_spot;
-            let hand = game.active_hand_in_spot;
-            let num_hands = game.player_hands[spot].len();
+        let (hand_label, player_value) = if let Some(ref mut game) = self.game_state {
+            let hand_label = game.hand_label(game.active_spot, game.active_hand_in_spot);
             match game.double_down() {
-                Ok(_) => (
-                    spot + 1,
-                    hand + 1,
-                    num_hands,
-                    GameState::calculate_hand_value(&game.player_hands[spot][hand]),
-                ),
+                Ok(_) => (hand_label, game.active_hand_value()),
                 Err(e) => {
                     self.add_log(format!("Error: {e}"));
                     return;
@@ -1557,11 +1938,6 @@ impl App {
         } else {
             return;
         };
-        let hand_label = if num_hands > 1 {
-            format!("Spot {spot}.{hand}")
-        } else {
-            format!("Spot {spot}")
-        };
         self.add_log(format!("{hand_label} doubles down!"));
         if player_value > 21 {
             self.add_log(format!("{hand_label} busts with {player_value}!"));
@@ -1580,22 +1956,19 @@ impl App {
             self.spawn_split();
             return;
         }
-        let (split_result, spot, can_double) = if let Some(ref mut game) = self.game_state {
-            if game.can_split() {
+        let (split_result, split_label, active_label, can_double) = match self.game_state {
+            Some(ref mut game) if game.can_split() => {
+                let split_label = game.hand_label(game.active_spot, game.active_hand_in_spot);
                 let result = game.split();
-                let spot = game.active_spot + 1;
-                let can_double = game.can_double();
-                (Some(result), spot, can_double)
-            } else {
-                (None, 0, false)
+                let active_label = game.hand_label(game.active_spot, game.active_hand_in_spot);
+                (Some(result), split_label, active_label, game.can_double())
             }
-        } else {
-            (None, 0, false)
+            _ => (None, String::new(), String::new(), false),
         };
         if let Some(result) = split_result {
             match result {
                 Ok(_) => {
-                    self.add_log(format!("Spot {spot} splits!"));
+                    self.add_log(format!("{split_label} splits!"));
                     let can_surrender = self
                         .game_state
                         .as_ref()
@@ -1608,7 +1981,7 @@ impl App {
                     if can_surrender {
                         options.push("Su[r]render");
                     }
-                    self.status = format!("Spot {}.1/2: {}", spot, options.join(" or "));
+                    self.status = format!("{active_label}: {}", options.join(" or "));
                 }
                 Err(e) => {
                     self.add_log(format!("Error: {e}"));
@@ -1628,28 +2001,16 @@ impl App {
             self.spawn_surrender();
             return;
         }
-        let (surrender_result, spot, hand, num_hands) = if let Some(ref mut game) = self.game_state
-        {
-            if game.can_surrender() {
-                let spot = game.active_spot;
-                let hand = game.active_hand_in_spot;
-                let num_hands = game.player_hands[spot].len();
-                let result = game.surrender();
-                (Some(result), spot + 1, hand + 1, num_hands)
-            } else {
-                (None, 0, 0, 1)
+        let (surrender_result, hand_label) = match self.game_state {
+            Some(ref mut game) if game.can_surrender() => {
+                let hand_label = game.hand_label(game.active_spot, game.active_hand_in_spot);
+                (Some(game.surrender()), hand_label)
             }
-        } else {
-            (None, 0, 0, 1)
+            _ => (None, String::new()),
         };
         if let Some(result) = surrender_result {
             match result {
                 Ok(_) => {
-                    let hand_label = if num_hands > 1 {
-                        format!("Spot {spot}.{hand}")
-                    } else {
-                        format!("Spot {spot}")
-                    };
                     self.add_log(format!("{hand_label} surrenders!"));
                     if let Err(e) = self.move_to_next_spot_or_dealer() {
                         self.add_log(format!("Error: {e}"));
@@ -1664,6 +2025,31 @@ impl App {
         }
     }
 
+    fn requires_confirmation(&self, action: AppAction) -> bool {
+        // Surrender can't be taken back once it is on-chain
+        (action == AppAction::Surrender && self.selected_mode == Some(GameMode::Contract))
+            || self.config.confirmable_actions.contains(&action)
+    }
+
+    /// Entry point for gameplay keys: runs the action or asks for confirmation first.
+    fn request_action(&mut self, action: AppAction) {
+        if matches!(self.phase, GamePhase::PlayerTurn) && self.requires_confirmation(action) {
+            self.confirm_dialog = Some(ConfirmDialog::new(action));
+        } else {
+            self.run_action(action);
+        }
+    }
+
+    fn run_action(&mut self, action: AppAction) {
+        match action {
+            AppAction::Hit => self.handle_hit(),
+            AppAction::Stand => self.handle_stand(),
+            AppAction::DoubleDown => self.handle_double(),
+            AppAction::Split => self.handle_split(),
+            AppAction::Surrender => self.handle_surrender(),
+        }
+    }
+
     // ── Action handling ──
 
     #[cfg(feature = "wallet")]
@@ -1683,11 +2069,14 @@ impl App {
                     self.balance_poll_inflight = false;
                     if !balance_str.is_empty() {
                         self.wallet_balance = Some(balance_str);
+                        self.last_successful_query = Some(std::time::Instant::now());
                     }
                     self.last_balance_poll = Some(std::time::Instant::now());
                 }
-                Action::GamesListed(games) => {
+                Action::GamesListed(page) => {
                     self.clear_pending_op();
+                    let games = page.games;
+                    self.games_next_cursor = page.next_cursor;
                     if games.is_empty() {
                         self.add_log("No games available to join".into());
                         self.status = "No games available. Press [L] to refresh".into();
@@ -1702,13 +2091,34 @@ impl App {
                         self.available_games = games;
                         self.add_log(format!("Found {game_count} available games"));
                         self.add_log("Press number key to select game, then [J] to join".into());
+                        if self.games_next_cursor.is_some() {
+                            self.add_log("Press [Down] for more games".into());
+                        }
                         self.status = "Select game by number".into();
                     }
                 }
                 Action::GameStateUpdated(game) => {
                     self.game_poll_inflight = false;
+                    self.last_successful_query = Some(std::time::Instant::now());
                     self.process_game_state_update(game);
                 }
+                Action::RevealRequestsUpdated(requests) => {
+                    self.process_reveal_requests(requests.pending);
+                }
+                Action::PauseStatusUpdated(status) => {
+                    if status.paused && !self.contract_paused {
+                        self.add_log(
+                            "Contract paused: no new games can be created or joined".into(),
+                        );
+                    }
+                    self.contract_paused = status.paused;
+                }
+                Action::TimeoutStatusUpdated(status) => {
+                    // A late reply for a game we've already left
+                    if self.game_id.is_some() {
+                        self.timeout_status = Some((status, std::time::Instant::now()));
+                    }
+                }
                 Action::GameJoined { client, sk, pk } => {
                     self.clear_pending_op();
                     if let Some(ref mut wallet) = self.wallet {
@@ -1717,6 +2127,7 @@ impl App {
                     self.zk_keys = Some((sk, pk));
                     self.phase = GamePhase::WaitingForReveal;
                     self.status = "Game joined! Waiting for initial card reveals (~30s)".into();
+                    self.remember_session();
                 }
                 Action::TxCompleted {
                     action_name,
@@ -1751,12 +2162,15 @@ impl App {
                         _ => {}
                     }
                 }
-                Action::RevealSubmitted { client, card_index } => {
+                Action::RevealSubmitted {
+                    client,
+                    card_indices,
+                } => {
                     self.clear_pending_op();
                     if let Some(ref mut wallet) = self.wallet {
                         wallet.set_client(client);
                     }
-                    self.add_log(format!("Reveal for card {card_index} submitted"));
+                    self.add_log(format!("Reveal for cards {card_indices:?} submitted"));
                 }
                 Action::TxFailed {
                     action_name,
@@ -1767,15 +2181,23 @@ impl App {
                     if let Some(ref mut wallet) = self.wallet {
                         wallet.set_client(client);
                     }
-                    self.add_log(format!("{action_name} failed: {error}"));
+                    self.show_error(
+                        format!("{action_name} failed: {error}"),
+                        ERROR_OVERLAY_DURATION,
+                    );
                 }
                 Action::OpFailed { op_name, error } => {
                     if op_name == "game_poll" {
+                        // Background poll, retried in 2s; not worth interrupting the player
                         self.game_poll_inflight = false;
+                        self.add_log(format!("{op_name} failed: {error}"));
                     } else {
                         self.clear_pending_op();
+                        self.show_error(
+                            format!("{op_name} failed: {error}"),
+                            ERROR_OVERLAY_DURATION,
+                        );
                     }
-                    self.add_log(format!("{op_name} failed: {error}"));
                 }
             }
         }
@@ -1792,6 +2214,29 @@ impl App {
         }
     }
 
+    /// Submit our partials for every requested card still missing one, in a single TX.
+    #[cfg(feature = "wallet")]
+    fn process_reveal_requests(&mut self, pending: Vec<contract_msg::PendingReveal>) {
+        if self.phase != GamePhase::WaitingForReveal {
+            return;
+        }
+        if self.pending_op.is_none() {
+            let card_indices: Vec<u32> = pending
+                .iter()
+                .filter(|p| !p.player_submitted)
+                .map(|p| p.card_index)
+                .collect();
+            self.spawn_submit_reveal(card_indices);
+        }
+
+        let total = pending.len();
+        let done = pending
+            .iter()
+            .filter(|p| p.player_submitted && p.dealer_submitted)
+            .count();
+        self.status = format!("Waiting for dealer reveal ({done}/{total} complete, ~10s per card)");
+    }
+
     #[cfg(feature = "wallet")]
     fn process_game_state_update(&mut self, game: contract_msg::GameResponse) {
         self.contract_game_state = Some(game.clone());
@@ -1810,75 +2255,20 @@ impl App {
                 } else if game.status.contains("DealerTurn") {
                     self.phase = GamePhase::DealerTurn;
                     self.status = "Dealer's turn — waiting for dealer action (~10s)".into();
-                } else if game.status.contains("Settled") {
+                } else if contract_game_over(&game.status) {
                     self.phase = GamePhase::GameOver;
                     self.display_contract_payouts(&game);
                 }
                 return;
             }
 
-            // Parse reveal_requests from status string (e.g. "WaitingForReveal { reveal_requests: [0, 1, 2], ... }")
-            let reveal_requests: Vec<u32> = if game.status.contains("reveal_requests:") {
-                game.status
-                    .split("reveal_requests:")
-                    .nth(1)
-                    .and_then(|s| s.split(']').next())
-                    .map(|s| s.trim_start_matches([' ', '[']))
-                    .map(|s| {
-                        s.split(',')
-                            .filter_map(|n| n.trim().parse::<u32>().ok())
-                            .collect()
-                    })
-                    .unwrap_or_default()
-            } else {
-                vec![]
-            };
-
-            // Submit one missing reveal at a time (only if no pending op)
-            if self.pending_op.is_none() {
-                // First: check reveal_requests for cards we haven't submitted to yet
-                // (pending_reveals only has entries after the first party submits)
-                let already_submitted: Vec<u32> = game
-                    .pending_reveals
-                    .iter()
-                    .filter(|pr| pr.player_partial.is_some())
-                    .map(|pr| pr.card_index)
-                    .collect();
-
-                let mut submitted = false;
-                for &card_idx in &reveal_requests {
-                    if !already_submitted.contains(&card_idx) {
-                        self.spawn_submit_reveal(card_idx);
-                        submitted = true;
-                        break;
-                    }
-                }
-
-                // Fallback: check pending_reveals for cards still missing our partial
-                if !submitted {
-                    for pending in &game.pending_reveals {
-                        if pending.player_partial.is_none() {
-                            self.spawn_submit_reveal(pending.card_index);
-                            break;
-                        }
-                    }
-                }
-            }
-
-            let total = reveal_requests.len();
-            let done = game
-                .pending_reveals
-                .iter()
-                .filter(|pr| pr.player_partial.is_some() && pr.dealer_partial.is_some())
-                .count();
-            self.status =
-                format!("Waiting for dealer reveal ({done}/{total} complete, ~10s per card)");
-        }
+            // Reveals are submitted once the matching GetRevealRequests reply arrives
+        }
 
         // DealerTurn in contract mode — just poll
         if self.phase == GamePhase::DealerTurn && self.selected_mode == Some(GameMode::Contract) {
             self.status = "Dealer's turn — processing (~10s)".into();
-            if game.status.contains("Settled") {
+            if contract_game_over(&game.status) {
                 self.phase = GamePhase::GameOver;
                 self.display_contract_payouts(&game);
             }
@@ -1886,7 +2276,7 @@ impl App {
 
         // InsuranceOffer — handle transitions (e.g., timeout settling game)
         if self.phase == GamePhase::InsuranceOffer {
-            if game.status.contains("Settled") {
+            if contract_game_over(&game.status) {
                 self.phase = GamePhase::GameOver;
                 self.display_contract_payouts(&game);
             } else if game.status.contains("PlayerTurn") {
@@ -2009,37 +2399,78 @@ async fn query_game_by_id_standalone(
     Ok(serde_json::from_slice(&response_bytes)?)
 }
 
+#[cfg(feature = "wallet")]
+async fn query_pause_status_standalone(
+    rpc_url: &str,
+    contract_addr: &str,
+) -> Result<contract_msg::PauseStatusResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let query_bytes = serde_json::to_vec(&serde_json::json!({ "get_pause_status": {} }))?;
+    let response_bytes =
+        query_contract_raw_standalone(rpc_url, contract_addr, &query_bytes).await?;
+    Ok(serde_json::from_slice(&response_bytes)?)
+}
+
+#[cfg(feature = "wallet")]
+async fn query_timeout_status_standalone(
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+) -> Result<contract_msg::TimeoutStatusResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let query_bytes =
+        serde_json::to_vec(&serde_json::json!({ "get_timeout_status": { "game_id": game_id } }))?;
+    let response_bytes =
+        query_contract_raw_standalone(rpc_url, contract_addr, &query_bytes).await?;
+    Ok(serde_json::from_slice(&response_bytes)?)
+}
+
+#[cfg(feature = "wallet")]
+async fn query_reveal_requests_standalone(
+    rpc_url: &str,
+    contract_addr: &str,
+    game_id: u64,
+) -> Result<contract_msg::RevealRequestsResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let query_bytes =
+        serde_json::to_vec(&serde_json::json!({ "get_reveal_requests": { "game_id": game_id } }))?;
+    let response_bytes =
+        query_contract_raw_standalone(rpc_url, contract_addr, &query_bytes).await?;
+    Ok(serde_json::from_slice(&response_bytes)?)
+}
+
 #[cfg(feature = "wallet")]
 async fn query_list_games_standalone(
     rpc_url: &str,
     contract_addr: &str,
     status_filter: Option<String>,
-) -> Result<Vec<contract_msg::GameListItem>, Box<dyn std::error::Error + Send + Sync>> {
-    let query_bytes = serde_json::to_vec(
-        &serde_json::json!({ "list_games": { "status_filter": status_filter } }),
-    )?;
+    start_after: Option<u64>,
+) -> Result<contract_msg::GamesPageResponse, Box<dyn std::error::Error + Send + Sync>> {
+    let query_bytes = serde_json::to_vec(&serde_json::json!({
+        "get_games_by_status": {
+            "status_filter": status_filter,
+            "start_after": start_after,
+            "limit": GAMES_PAGE_SIZE,
+        }
+    }))?;
     let response_bytes =
         query_contract_raw_standalone(rpc_url, contract_addr, &query_bytes).await?;
     Ok(serde_json::from_slice(&response_bytes)?)
 }
 
+/// A contract game status after which nothing more happens: settled, forfeited
+/// by the dealer, or refunded by `EmergencyRefundAll`.
+fn contract_game_over(status: &str) -> bool {
+    status.contains("Settled")
+        || status.contains("Forfeited")
+        || status.contains("EmergencyRefunded")
+}
+
 /// Convert 0-51 card indices to blackjack hand value (with ace soft/hard logic).
+/// Hands the contract hasn't dealt into yet are empty and count 0.
 fn calculate_hand_value_from_indices(indices: &[u8]) -> u8 {
-    let mut total: u8 = 0;
-    let mut aces: u8 = 0;
-    for &idx in indices {
-        let card = blackjack::Card::from_index(idx as usize);
-        let v = card.value();
-        total = total.saturating_add(v);
-        if v == 11 {
-            aces += 1;
-        }
-    }
-    while total > 21 && aces > 0 {
-        total -= 10;
-        aces -= 1;
-    }
-    total
+    let cards: Vec<blackjack::Card> = indices
+        .iter()
+        .map(|&idx| blackjack::Card::from_index(idx as usize))
+        .collect();
+    blackjack::calculate_hand_value(&cards).total
 }
 
 /// Execute a contract message via mob Client + poll for confirmation.
@@ -2079,9 +2510,78 @@ fn execute_and_confirm_standalone(
     Err("Tx not confirmed after 30s".into())
 }
 
+fn mode_selection_status(offline: bool) -> &'static str {
+    if offline {
+        "Select mode: [F]ast (instant) or [T]rustless (~1 min, ZK proofs)"
+    } else {
+        "Select mode: [F]ast (instant), [T]rustless (~1 min, ZK proofs), or [C]ontract (on-chain)"
+    }
+}
+
+/// `--fresh`: ignore the saved session and start from the mode screen.
+/// `--version` or `-V`: print build details and exit.
+fn version_flag(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter()
+        .skip(1)
+        .any(|arg| arg == "--version" || arg == "-V")
+}
+
+fn fresh_flag(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter().skip(1).any(|arg| arg == "--fresh")
+}
+
+/// `--offline`, or `--headless` (which never talks to a node), keeps the TUI off the network.
+fn offline_flag(args: impl IntoIterator<Item = String>) -> bool {
+    args.into_iter()
+        .skip(1)
+        .any(|arg| arg == "--offline" || arg == "--headless")
+}
+
+/// `--replay <file>` (or `--replay=<file>`): a saved round to step through instead of playing.
+fn replay_arg(args: impl IntoIterator<Item = String>) -> Option<std::path::PathBuf> {
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--replay" {
+            return args.next().map(Into::into);
+        }
+        if let Some(file) = arg.strip_prefix("--replay=") {
+            return Some(file.into());
+        }
+    }
+    None
+}
+
+/// `--vk-override <dir>` (or `--vk-override=<dir>`): verifying keys to use instead
+/// of the embedded ones, for circuit development.
+fn vk_override_arg(args: impl IntoIterator<Item = String>) -> Option<std::path::PathBuf> {
+    let mut args = args.into_iter().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--vk-override" {
+            return args.next().map(Into::into);
+        }
+        if let Some(dir) = arg.strip_prefix("--vk-override=") {
+            return Some(dir.into());
+        }
+    }
+    None
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
+    if version_flag(std::env::args()) {
+        println!("{}", build_info::version_string());
+        return Ok(());
+    }
     dotenvy::dotenv().ok();
+    let offline = offline_flag(std::env::args());
+    // Load before taking over the terminal so a bad file is reported plainly
+    let replay = match replay_arg(std::env::args()) {
+        Some(path) => Some(
+            ReplayFile::load(&path)
+                .map_err(|e| format!("Could not load replay {}: {e}", path.display()))?,
+        ),
+        None => None,
+    };
 
     // Initialize custom logger
     let (logger, log_buffer) = TuiLogger::new();
@@ -2097,7 +2597,14 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let mut terminal = Terminal::new(backend)?;
 
     // create app and run it
-    let app = App::new(log_buffer);
+    let mut app = App::new(log_buffer, Arc::new(RapidsnarkProver), offline);
+    app.vk_override = vk_override_arg(std::env::args());
+    app.save_session = true;
+    if let Some(file) = replay {
+        app.start_replay(file);
+    } else if !fresh_flag(std::env::args()) {
+        app.offer_last_session(SessionConfig::load());
+    }
     let res = run_app(&mut terminal, app).await;
 
     // restore terminal
@@ -2128,6 +2635,14 @@ where
         #[cfg(feature = "wallet")]
         app.handle_actions();
 
+        if app
+            .error_overlay
+            .as_ref()
+            .is_some_and(|o| std::time::Instant::now() >= o.dismiss_at)
+        {
+            app.error_overlay = None;
+        }
+
         // Render UI
         terminal.draw(|f| ui(f, &app))?;
 
@@ -2252,10 +2767,12 @@ where
             app.init_start_time = Some(std::time::Instant::now());
             app.current_init_stage = "Initializing".to_string();
             let mode = app.selected_mode.unwrap();
+            let vk_override = app.vk_override.clone();
 
             let task = tokio::task::spawn(async move {
                 let mut game_state =
                     GameState::new_uninitialized(mode).map_err(|e| e.to_string())?;
+                game_state.vk_override = vk_override;
 
                 game_state.initialize_deck().map_err(|e| e.to_string())?;
                 game_state.shuffle_deck().map_err(|e| e.to_string())?;
@@ -2263,6 +2780,14 @@ where
                 Ok(game_state)
             });
             app.init_task = Some(task);
+
+            // A resumed session already knows its spots
+            if let Some(num_spots) = app.resume_spots.take() {
+                app.selected_spots = Some(num_spots);
+                app.phase = GamePhase::Initializing;
+                app.loading_dots = 0;
+                app.add_log(format!("Starting game with {num_spots} spot(s)"));
+            }
         }
 
         // After spots selected, resize game state and deal cards
@@ -2287,20 +2812,7 @@ where
                                 app.init_start_time = None;
                                 app.current_init_stage.clear();
                             } else {
-                                for spot in 0..num_spots {
-                                    if let Err(e) = game_state.draw_card(false, Some(spot)) {
-                                        app.add_log(format!("Error dealing: {e}"));
-                                    }
-                                }
-                                if let Err(e) = game_state.draw_card(true, None) {
-                                    app.add_log(format!("Error dealing: {e}"));
-                                }
-                                for spot in 0..num_spots {
-                                    if let Err(e) = game_state.draw_card(false, Some(spot)) {
-                                        app.add_log(format!("Error dealing: {e}"));
-                                    }
-                                }
-                                if let Err(e) = game_state.draw_card(true, None) {
+                                if let Err(e) = game_state.deal_initial_cards() {
                                     app.add_log(format!("Error dealing: {e}"));
                                 }
 
@@ -2308,16 +2820,11 @@ where
                                 app.phase = GamePhase::PlayerTurn;
                                 app.init_start_time = None;
                                 app.current_init_stage.clear();
+                                app.remember_session();
 
-                                let should_peek = app
-                                    .game_state
-                                    .as_ref()
-                                    .map(|g| g.should_dealer_peek())
-                                    .unwrap_or(false);
-                                if should_peek {
-                                    if let Some(ref mut game) = app.game_state {
-                                        game.dealer_peeked = true;
-                                    }
+                                let peeked =
+                                    app.game_state.as_ref().is_some_and(|g| g.dealer_peeked);
+                                if peeked {
                                     let has_blackjack = app
                                         .game_state
                                         .as_ref()
@@ -2342,7 +2849,7 @@ where
                                         GameState::calculate_hand_value(&game.player_hands[0][0])
                                     };
 
-                                    if first_spot_value == 21 {
+                                    if first_spot_value.total == 21 {
                                         app.add_log(format!(
                                             "Game started! Spot 1/{num_spots} has Blackjack!"
                                         ));
@@ -2397,7 +2904,7 @@ where
                             }
                         }
                         Ok(Err(e)) => {
-                            app.add_log(format!("ERROR: {e}"));
+                            app.show_error(format!("ERROR: {e}"), ERROR_OVERLAY_DURATION);
                             app.status =
                                 "Error starting game. Press [F] or [T] to try again".to_string();
                             app.phase = GamePhase::ModeSelection;
@@ -2407,7 +2914,7 @@ where
                             app.current_init_stage.clear();
                         }
                         Err(e) => {
-                            app.add_log(format!("Task error: {e}"));
+                            app.show_error(format!("Task error: {e}"), ERROR_OVERLAY_DURATION);
                             app.status =
                                 "Error starting game. Press [F] or [T] to try again".to_string();
                             app.phase = GamePhase::ModeSelection;
@@ -2424,6 +2931,25 @@ where
         // Use poll with timeout so UI can refresh even during long operations
         if event::poll(std::time::Duration::from_millis(100))? {
             if let Event::Key(key) = event::read()? {
+                // A confirmation dialog swallows the next key: only [Y] goes ahead
+                if let Some(mut dialog) = app.confirm_dialog.take() {
+                    if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                        dialog.confirmed = true;
+                    }
+                    if dialog.confirmed {
+                        app.run_action(dialog.action);
+                    } else {
+                        app.add_log("Cancelled".to_string());
+                    }
+                    continue;
+                }
+
+                // Esc dismisses an error overlay first, otherwise quits
+                if matches!(key.code, KeyCode::Esc) && app.error_overlay.is_some() {
+                    app.error_overlay = None;
+                    continue;
+                }
+
                 // Handle Esc to quit globally
                 if matches!(key.code, KeyCode::Esc) {
                     if let Some(task) = app.init_task.take() {
@@ -2452,6 +2978,20 @@ where
                     return Ok(());
                 }
 
+                // A replay only steps; nothing is dealt or sent
+                if let Some(step) = app.replay.as_ref().map(|p| p.step) {
+                    match key.code {
+                        KeyCode::Right | KeyCode::Char('n') | KeyCode::Char('N') => {
+                            app.show_replay_step(step + 1)
+                        }
+                        KeyCode::Left | KeyCode::Char('b') | KeyCode::Char('B') => {
+                            app.show_replay_step(step.saturating_sub(1))
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
                 // Block action keys while pending_op is active (except input modes)
                 #[cfg(feature = "wallet")]
                 if app.pending_op.is_some() && app.input_mode == InputMode::Normal {
@@ -2500,8 +3040,15 @@ where
                         _ => {}
                     },
                     InputMode::Normal => match key.code {
+                        KeyCode::Enter
+                            if app.phase == GamePhase::ModeSelection
+                                && app.last_session.is_some() =>
+                        {
+                            app.resume_last_session();
+                        }
                         KeyCode::Char('f') | KeyCode::Char('F') => {
                             if matches!(app.phase, GamePhase::ModeSelection | GamePhase::GameOver) {
+                                app.last_session = None;
                                 app.selected_mode = Some(GameMode::Fast);
                                 app.phase = GamePhase::SpotSelection;
                                 app.add_log("FAST mode selected".to_string());
@@ -2511,6 +3058,7 @@ where
                         }
                         KeyCode::Char('t') | KeyCode::Char('T') => {
                             if matches!(app.phase, GamePhase::ModeSelection | GamePhase::GameOver) {
+                                app.last_session = None;
                                 app.selected_mode = Some(GameMode::Trustless);
                                 app.phase = GamePhase::SpotSelection;
                                 app.add_log("TRUSTLESS mode selected".to_string());
@@ -2534,50 +3082,21 @@ where
                             }
                         }
                         KeyCode::Char('c') | KeyCode::Char('C') => {
-                            if matches!(app.phase, GamePhase::ModeSelection | GamePhase::GameOver) {
-                                #[cfg(feature = "wallet")]
-                                {
-                                    app.selected_mode = Some(GameMode::Contract);
-                                    app.phase = GamePhase::ContractSetup;
-                                    app.add_log("CONTRACT mode selected".to_string());
-                                    if app.wallet.is_none() {
-                                        if let Ok(mnemonic) = std::env::var("PLAYER_MNEMONIC") {
-                                            app.add_log(
-                                                "Loading wallet from PLAYER_MNEMONIC env..."
-                                                    .to_string(),
-                                            );
-                                            if app.load_wallet_from_mnemonic(&mnemonic) {
-                                                app.spawn_wallet_connect();
-                                            }
-                                        } else {
-                                            app.add_log("Enter mnemonic or press [G] to generate new wallet".to_string());
-                                            app.status = "Enter mnemonic (press Enter to submit)"
-                                                .to_string();
-                                            app.input_mode = InputMode::Mnemonic;
-                                        }
-                                    } else if app.contract_address.is_some() {
-                                        app.add_log("Press [L] to list games, or [J] to join after selecting".to_string());
-                                        app.status =
-                                            "Press [L] to list available games".to_string();
-                                    } else {
-                                        app.add_log("Enter contract address".to_string());
-                                        app.status = "Enter contract address".to_string();
-                                        app.input_mode = InputMode::ContractAddress;
-                                    }
-                                }
-                                #[cfg(not(feature = "wallet"))]
-                                {
-                                    app.add_log(
-                                        "CONTRACT mode requires wallet feature".to_string(),
-                                    );
-                                    app.add_log(
-                                        "Build with --features wallet to enable".to_string(),
-                                    );
-                                }
+                            if app.phase == GamePhase::ModeSelection && app.last_session.is_some() {
+                                app.dismiss_last_session();
+                            } else if matches!(
+                                app.phase,
+                                GamePhase::ModeSelection | GamePhase::GameOver
+                            ) {
+                                app.select_contract_mode();
                             }
                         }
-                        KeyCode::Char('d') | KeyCode::Char('D') => app.handle_double(),
-                        KeyCode::Char('p') | KeyCode::Char('P') => app.handle_split(),
+                        KeyCode::Char('d') | KeyCode::Char('D') => {
+                            app.request_action(AppAction::DoubleDown)
+                        }
+                        KeyCode::Char('p') | KeyCode::Char('P') => {
+                            app.request_action(AppAction::Split)
+                        }
                         KeyCode::Char('g') | KeyCode::Char('G') => {
                             #[cfg(feature = "wallet")]
                             if app.phase == GamePhase::ContractSetup && app.wallet.is_none() {
@@ -2587,9 +3106,13 @@ where
                                         app.add_log(format!("Mnemonic: {mnemonic}"));
                                         app.add_log("IMPORTANT: Save this mnemonic!".to_string());
                                         app.wallet = Some(wallet);
+                                        app.shuffle_storage_key =
+                                            Some(zk_shuffle::sealed::storage_key_from_mnemonic(
+                                                &mnemonic,
+                                            ));
                                         app.input_mode = InputMode::Normal;
 
-                                        if let Ok(addr) = std::env::var("CONTRACT_ADDR") {
+                                        if let Some(addr) = app.env_var("CONTRACT_ADDR") {
                                             app.contract_address = Some(addr.clone());
                                             app.add_log(format!("Contract: {addr}"));
                                             app.add_log("Press [L] to list games".to_string());
@@ -2631,13 +3154,39 @@ where
                                     app.add_log(format!(
                                         "Selected game {game_id} by dealer {dealer}"
                                     ));
-                                    app.add_log("Press [J] to join this game".to_string());
-                                    app.status = format!("Press [J] to join game {game_id}");
+                                    if let Some(age) = saved_shuffle::resumable_age(game_id) {
+                                        app.add_log(format!(
+                                            "Found a saved shuffle for this game ({}s old)",
+                                            age.as_secs()
+                                        ));
+                                        app.add_log(
+                                            "Press [J] to resume it instead of re-shuffling"
+                                                .to_string(),
+                                        );
+                                        app.status =
+                                            format!("Press [J] to resume joining game {game_id}");
+                                    } else {
+                                        app.add_log("Press [J] to join this game".to_string());
+                                        app.status = format!("Press [J] to join game {game_id}");
+                                    }
                                 }
                             }
                         }
-                        KeyCode::Char('h') | KeyCode::Char('H') => app.handle_hit(),
-                        KeyCode::Char('s') | KeyCode::Char('S') => app.handle_stand(),
+                        KeyCode::Char('v') | KeyCode::Char('V') => {
+                            let width = crossterm::terminal::size().map(|(w, _)| w).unwrap_or(0);
+                            let next = app
+                                .card_display_mode
+                                .unwrap_or_else(|| CardDisplayMode::auto(width, app.total_hands()))
+                                .next();
+                            app.card_display_mode = Some(next);
+                            app.add_log(format!("Card display: {}", next.label()));
+                        }
+                        KeyCode::Char('h') | KeyCode::Char('H') => {
+                            app.request_action(AppAction::Hit)
+                        }
+                        KeyCode::Char('s') | KeyCode::Char('S') => {
+                            app.request_action(AppAction::Stand)
+                        }
                         KeyCode::Char('j') | KeyCode::Char('J') => {
                             #[cfg(feature = "wallet")]
                             if app.phase == GamePhase::ContractSetup
@@ -2653,7 +3202,9 @@ where
                                 }
                             }
                         }
-                        KeyCode::Char('r') | KeyCode::Char('R') => app.handle_surrender(),
+                        KeyCode::Char('r') | KeyCode::Char('R') => {
+                            app.request_action(AppAction::Surrender)
+                        }
                         KeyCode::Char('y') | KeyCode::Char('Y') => {
                             #[cfg(feature = "wallet")]
                             if matches!(app.phase, GamePhase::InsuranceOffer)
@@ -2676,6 +3227,7 @@ where
                                     app.game_id = None;
                                     app.zk_keys = None;
                                     app.contract_game_state = None;
+                                    app.timeout_status = None;
                                     app.spot_outcomes.clear();
                                     app.phase = GamePhase::ContractSetup;
                                     app.add_log("--- Returning to game selection ---".to_string());
@@ -2697,55 +3249,19 @@ where
                                                         "--- New Game (pre-shuffled) ---"
                                                             .to_string(),
                                                     );
-                                                    let num_spots = next_game.num_spots;
-                                                    for spot in 0..num_spots {
-                                                        if let Err(e) =
-                                                            next_game.draw_card(false, Some(spot))
-                                                        {
-                                                            app.add_log(format!(
-                                                                "Error dealing to spot {}: {}",
-                                                                spot + 1,
-                                                                e
-                                                            ));
-                                                        }
-                                                    }
-                                                    if let Err(e) = next_game.draw_card(true, None)
-                                                    {
-                                                        app.add_log(format!(
-                                                            "Error dealing to dealer: {e}"
-                                                        ));
-                                                    }
-                                                    for spot in 0..num_spots {
-                                                        if let Err(e) =
-                                                            next_game.draw_card(false, Some(spot))
-                                                        {
-                                                            app.add_log(format!(
-                                                                "Error dealing to spot {}: {}",
-                                                                spot + 1,
-                                                                e
-                                                            ));
-                                                        }
-                                                    }
-                                                    if let Err(e) = next_game.draw_card(true, None)
-                                                    {
-                                                        app.add_log(format!(
-                                                            "Error dealing to dealer: {e}"
-                                                        ));
+                                                    if let Err(e) = next_game.deal_initial_cards() {
+                                                        app.add_log(format!("Error dealing: {e}"));
                                                     }
 
                                                     app.game_state = Some(next_game);
                                                     app.phase = GamePhase::PlayerTurn;
                                                     app.spot_outcomes.clear();
 
-                                                    let should_peek = app
+                                                    let peeked = app
                                                         .game_state
                                                         .as_ref()
-                                                        .map(|g| g.should_dealer_peek())
-                                                        .unwrap_or(false);
-                                                    if should_peek {
-                                                        if let Some(ref mut game) = app.game_state {
-                                                            game.dealer_peeked = true;
-                                                        }
+                                                        .is_some_and(|g| g.dealer_peeked);
+                                                    if peeked {
                                                         let has_blackjack = app
                                                             .game_state
                                                             .as_ref()
@@ -2818,12 +3334,12 @@ where
                                                     } // end if not GameOver from dealer blackjack
                                                 }
                                                 Ok(Err(e)) => {
-                                                    app.add_log(format!("Next game ERROR: {e}. Press [F] or [T] to restart"));
+                                                    app.show_error(format!("Next game ERROR: {e}. Press [F] or [T] to restart"), ERROR_OVERLAY_DURATION);
                                                     app.status = "Next game failed. Press [F] or [T] to restart".to_string();
                                                     app.phase = GamePhase::ModeSelection;
                                                 }
                                                 Err(e) => {
-                                                    app.add_log(format!("Next game task error: {e}. Press [F] or [T] to restart"));
+                                                    app.show_error(format!("Next game task error: {e}. Press [F] or [T] to restart"), ERROR_OVERLAY_DURATION);
                                                     app.status = "Next game failed. Press [F] or [T] to restart".to_string();
                                                     app.phase = GamePhase::ModeSelection;
                                                 }
@@ -2853,12 +3369,29 @@ where
                                 }
                             } else {
                                 app.log_visible = !app.log_visible;
+                                if !app.log_visible {
+                                    app.focused_panel = FocusedPanel::none();
+                                }
                             }
                         }
-                        KeyCode::Up => app.handle_hit(),
-                        KeyCode::Down => app.handle_stand(),
-                        KeyCode::Right => app.handle_double(),
-                        KeyCode::Left => app.handle_split(),
+                        KeyCode::Tab => app.cycle_focus(),
+                        KeyCode::Up if app.focused_panel == FocusedPanel::LogPanel => {
+                            app.scroll_log(true)
+                        }
+                        KeyCode::Down if app.focused_panel == FocusedPanel::LogPanel => {
+                            app.scroll_log(false)
+                        }
+                        KeyCode::Down
+                            if app.phase == GamePhase::ContractSetup
+                                && app.games_next_cursor.is_some() =>
+                        {
+                            #[cfg(feature = "wallet")]
+                            app.spawn_games_page(app.games_next_cursor);
+                        }
+                        KeyCode::Up => app.request_action(AppAction::Hit),
+                        KeyCode::Down => app.request_action(AppAction::Stand),
+                        KeyCode::Right => app.request_action(AppAction::DoubleDown),
+                        KeyCode::Left => app.request_action(AppAction::Split),
                         KeyCode::Char(c) =>
                         {
                             #[cfg(feature = "wallet")]
@@ -2917,16 +3450,6 @@ where
     }
 }
 
-fn card_color(card_str: &str) -> Color {
-    match card_str.chars().last() {
-        Some('♥') => Color::Red,
-        Some('♦') => Color::from_u32(0xFF_A5_00),
-        Some('♣') => Color::Magenta,
-        Some('♠') => Color::Black,
-        _ => Color::White,
-    }
-}
-
 fn ui(f: &mut Frame, app: &App) {
     // Main layout: Top section and bottom section
     let main_chunks = Layout::default()
@@ -2943,7 +3466,7 @@ fn ui(f: &mut Frame, app: &App) {
         .split(f.area());
 
     // Title bar with game mode
-    let title_text = if let Some(mode) = app.selected_mode {
+    let mut title_text = if let Some(mode) = app.selected_mode {
         match mode {
             GameMode::Fast => "Juodžekas - Fast Mode (No Proofs)".to_string(),
             GameMode::Trustless => "Juodžekas - Trustless Mode (ZK Proofs)".to_string(),
@@ -2952,14 +3475,41 @@ fn ui(f: &mut Frame, app: &App) {
     } else {
         "Juodžekas - Trustless Blackjack".to_string()
     };
+    if let (Some(GameMode::Contract), Some(game_id)) = (app.selected_mode, app.game_id) {
+        title_text.push_str(&format!(" (Game #{game_id})"));
+    }
+    if app.offline {
+        title_text.push_str(" [OFFLINE]");
+    }
 
-    let title = Paragraph::new(title_text)
+    let connection_color = app.connection_color();
+    let mut title_line = match connection_color {
+        Some(color) => Line::from(vec![
+            Span::styled(" ● ", Style::default().fg(color)),
+            Span::raw(format!("{title_text} ")),
+        ]),
+        None => Line::from(title_text),
+    };
+    if app.selected_mode == Some(GameMode::Contract) && app.contract_paused {
+        title_line.push_span(Span::styled(
+            " Contract paused ",
+            Style::default().fg(Color::White).bg(Color::Red),
+        ));
+    }
+
+    let title = Paragraph::new(title_line)
         .style(
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
         )
-        .block(Block::default().borders(Borders::ALL));
+        .block(
+            Block::default().borders(Borders::ALL).title_bottom(
+                Line::from(format!(" v{} ", build_info::VERSION))
+                    .right_aligned()
+                    .style(Style::default().fg(Color::DarkGray)),
+            ),
+        );
     f.render_widget(title, main_chunks[0]);
 
     // Split main area: left (game) and right (logs if visible)
@@ -2979,47 +3529,45 @@ fn ui(f: &mut Frame, app: &App) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
         .split(game_container);
 
+    let card_mode = app
+        .card_display_mode
+        .unwrap_or_else(|| CardDisplayMode::auto(f.area().width, app.total_hands()));
+    // Crowded tables show each player hand as its value, so more fit across
+    let values_only =
+        card_mode == CardDisplayMode::Compact && app.total_hands() > card_view::COMPACT_MIN_HANDS;
+
     // Dealer hand
-    let dealer_cards: Vec<Span> = if let Some(ref game) = app.game_state {
-        game.dealer_hand
-            .iter()
-            .enumerate()
-            .map(|(idx, card_opt)| {
-                // Hide dealer's second card until dealer's turn
-                let card_str =
+    let dealer_hand: Option<Vec<Option<blackjack::Card>>> = if let Some(ref game) = app.game_state {
+        Some(
+            game.dealer_hand
+                .iter()
+                .enumerate()
+                .map(|(idx, card_opt)| {
+                    // Hide dealer's second card until dealer's turn
                     if matches!(app.phase, GamePhase::PlayerTurn | GamePhase::Initializing)
                         && idx == 1
                     {
-                        "??".to_string()
-                    } else if let Some(card) = card_opt {
-                        card.to_display()
+                        None
                     } else {
-                        "??".to_string()
-                    };
-
-                let color = card_color(&card_str);
-                Span::styled(
-                    format!("{card_str} "),
-                    Style::default().fg(color).bg(Color::Gray),
-                )
-            })
-            .collect()
-    } else if let Some(ref contract_game) = app.contract_game_state {
-        contract_game
-            .dealer_hand
-            .iter()
-            .map(|&card_idx| {
-                let card = blackjack::Card::from_index(card_idx as usize);
-                let card_str = card.to_display();
-                let color = card_color(&card_str);
-                Span::styled(
-                    format!("{card_str} "),
-                    Style::default().fg(color).bg(Color::Gray),
-                )
-            })
-            .collect()
+                        *card_opt
+                    }
+                })
+                .collect(),
+        )
     } else {
-        vec![Span::raw("No game started")]
+        app.contract_game_state.as_ref().map(|contract_game| {
+            contract_game
+                .dealer_hand
+                .iter()
+                .map(|&card_idx| Some(blackjack::Card::from_index(card_idx as usize)))
+                .collect()
+        })
+    };
+    let dealer_card_lines: Vec<Line> = match dealer_hand {
+        Some(cards) => {
+            card_view::hand_lines(&cards, card_mode, game_area[0].width.saturating_sub(2))
+        }
+        None => vec![Line::from("No game started")],
     };
 
     let dealer_value = if let Some(ref game) = app.game_state {
@@ -3112,79 +3660,72 @@ fn ui(f: &mut Frame, app: &App) {
         }
 
         let instructions = vec![Line::from(instruction_spans)];
-        let content_height = 1 + instructions.len() + 1; // 1 card line + instructions + spacing
+        let content_height = dealer_card_lines.len() + instructions.len() + 1; // cards + instructions + spacing
         let padding_top = dealer_block_height.saturating_sub(content_height as u16) / 2;
 
         dealer_lines.extend(vec![Line::from(""); padding_top as usize]);
-        dealer_lines.push(Line::from(dealer_cards));
+        dealer_lines.extend(dealer_card_lines);
         dealer_lines.push(Line::from("")); // Spacing
         dealer_lines.extend(instructions);
     } else {
-        let padding_lines = dealer_block_height / 2;
+        let padding_lines = dealer_block_height.saturating_sub(dealer_card_lines.len() as u16) / 2;
         dealer_lines.extend(vec![Line::from(""); padding_lines as usize]);
-        dealer_lines.push(Line::from(dealer_cards));
+        dealer_lines.extend(dealer_card_lines);
     }
 
+    // Only worth marking focus when the log is there to take it
+    let game_border = if app.log_visible && app.focused_panel == FocusedPanel::GameArea {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default()
+    };
     let dealer_block = Paragraph::new(dealer_lines)
         .block(
             Block::default()
                 .title(format!(" Dealer Hand{dealer_value} "))
-                .borders(Borders::ALL),
+                .borders(Borders::ALL)
+                .border_style(game_border),
         )
         .alignment(Alignment::Center);
     f.render_widget(dealer_block, game_area[0]); // Dealer on top
 
-    // Player spots (horizontal layout)
+    // Player spots: one row, or spots 1-4 above 5-8 when the hands don't fit across
     if let Some(ref game) = app.game_state {
-        // Create horizontal layout for player spots
-        let num_spots = game.num_spots;
-        let spot_constraints: Vec<Constraint> =
-            vec![Constraint::Ratio(1, num_spots as u32); num_spots];
-
-        let spot_areas = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(spot_constraints)
+        let rows = game.spot_rows(game_area[1].width);
+        let row_areas = Layout::vertical(vec![Constraint::Ratio(1, rows.len() as u32); rows.len()])
             .split(game_area[1]);
+        let active = game.active_hand_display_position(game_area[1].width);
+
+        for (row, spots) in rows.iter().enumerate() {
+            let spot_constraints: Vec<Constraint> =
+                vec![Constraint::Ratio(1, spots.len() as u32); spots.len()];
+            let spot_areas = Layout::horizontal(spot_constraints).split(row_areas[row]);
+            let mut column = 0;
 
-        // Render each spot
-        for (i, spot_hands) in game.player_hands.iter().enumerate() {
-            let num_hands_in_spot = spot_hands.len();
+            for (i, spot_area) in spots.clone().zip(spot_areas.iter()) {
+                let spot_hands = &game.player_hands[i];
+                let num_hands_in_spot = spot_hands.len();
 
-            // If spot is split, subdivide horizontally
-            if num_hands_in_spot > 1 {
+                // If spot is split, subdivide horizontally
                 let hand_constraints: Vec<Constraint> =
                     vec![Constraint::Ratio(1, num_hands_in_spot as u32); num_hands_in_spot];
-                let hand_areas = Layout::default()
-                    .direction(Direction::Horizontal)
-                    .constraints(hand_constraints)
-                    .split(spot_areas[i]);
+                let hand_areas = Layout::horizontal(hand_constraints).split(*spot_area);
 
-                // Render each hand within the split spot
                 for (j, hand) in spot_hands.iter().enumerate() {
-                    let player_cards: Vec<Span> = hand
-                        .iter()
-                        .map(|card_opt| {
-                            let card_str = if let Some(card) = card_opt {
-                                card.to_display()
-                            } else {
-                                "??".to_string()
-                            };
-
-                            let color = card_color(&card_str);
-                            Span::styled(
-                                format!("{card_str} "),
-                                Style::default().fg(color).bg(Color::Gray),
-                            )
-                        })
-                        .collect();
-
                     let player_value = GameState::calculate_hand_value(hand);
-
-                    // Highlight active hand within split spot during play, or outcome at game over
-                    let border_style = if i == game.active_spot
-                        && j == game.active_hand_in_spot
-                        && matches!(app.phase, GamePhase::PlayerTurn)
-                    {
+                    let is_active = matches!(app.phase, GamePhase::PlayerTurn)
+                        && active == LayoutPosition { row, column };
+                    column += 1;
+
+                    // Highlight active hand during play, or outcome at game over
+                    let mut border_type = BorderType::Plain;
+                    let border_style = if is_active && values_only {
+                        // No cards to pick out, so the border carries it
+                        border_type = BorderType::Thick;
+                        Style::default()
+                            .fg(Color::LightYellow)
+                            .add_modifier(Modifier::BOLD)
+                    } else if is_active {
                         Style::default()
                             .fg(Color::Yellow)
                             .add_modifier(Modifier::BOLD)
@@ -3208,110 +3749,44 @@ fn ui(f: &mut Frame, app: &App) {
                         Style::default()
                     };
 
-                    // Calculate wrapping based on available width
-                    let hand_width = hand_areas[j].width.saturating_sub(2); // Subtract borders
-                    let card_width = 4; // Each card is roughly "Ah♥ " = 4 chars
-                    let cards_per_line = (hand_width / card_width).max(1) as usize;
-
-                    // Wrap cards into multiple lines if needed
-                    let mut wrapped_lines: Vec<Line> = Vec::new();
-                    for chunk in player_cards.chunks(cards_per_line) {
-                        wrapped_lines.push(Line::from(chunk.to_vec()));
-                    }
+                    let label = if num_hands_in_spot > 1 {
+                        format!("{}.{}", i + 1, j + 1)
+                    } else {
+                        (i + 1).to_string()
+                    };
+                    let (title, content) = if values_only {
+                        let value = Line::from(Span::styled(
+                            player_value.to_string(),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ));
+                        (format!(" {label} "), vec![value])
+                    } else {
+                        // Wrap cards into multiple lines based on available width
+                        let hand_width = hand_areas[j].width.saturating_sub(2); // Subtract borders
+                        (
+                            format!(" {label} ({player_value}) "),
+                            card_view::hand_lines(hand, card_mode, hand_width),
+                        )
+                    };
 
                     // Calculate vertical centering
                     let hand_block_height = hand_areas[j].height.saturating_sub(2);
-                    let content_lines = wrapped_lines.len();
-                    let padding_top = (hand_block_height.saturating_sub(content_lines as u16)) / 2;
+                    let padding_top = hand_block_height.saturating_sub(content.len() as u16) / 2;
 
                     let mut hand_lines: Vec<Line> = vec![Line::from(""); padding_top as usize];
-                    hand_lines.extend(wrapped_lines);
+                    hand_lines.extend(content);
 
                     let hand_block = Paragraph::new(hand_lines)
                         .block(
                             Block::default()
-                                .title(format!(" {}.{} ({}) ", i + 1, j + 1, player_value))
+                                .title(title)
                                 .borders(Borders::ALL)
+                                .border_type(border_type)
                                 .border_style(border_style),
                         )
                         .alignment(Alignment::Center);
                     f.render_widget(hand_block, hand_areas[j]);
                 }
-            } else {
-                // Single hand (not split)
-                let hand = &spot_hands[0];
-                let player_cards: Vec<Span> = hand
-                    .iter()
-                    .map(|card_opt| {
-                        let card_str = if let Some(card) = card_opt {
-                            card.to_display()
-                        } else {
-                            "??".to_string()
-                        };
-
-                        let color = card_color(&card_str);
-                        Span::styled(
-                            format!("{card_str} "),
-                            Style::default().fg(color).bg(Color::Gray),
-                        )
-                    })
-                    .collect();
-
-                let player_value = GameState::calculate_hand_value(hand);
-
-                // Highlight active spot during play, or outcome at game over
-                let border_style = if i == game.active_spot
-                    && matches!(app.phase, GamePhase::PlayerTurn)
-                {
-                    Style::default()
-                        .fg(Color::Yellow)
-                        .add_modifier(Modifier::BOLD)
-                } else if app.phase == GamePhase::GameOver
-                    && i < app.spot_outcomes.len()
-                    && !app.spot_outcomes[i].is_empty()
-                {
-                    match app.spot_outcomes[i][0] {
-                        SpotOutcome::Win => Style::default()
-                            .fg(Color::Green)
-                            .add_modifier(Modifier::BOLD),
-                        SpotOutcome::Loss => {
-                            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
-                        }
-                        SpotOutcome::Push => Style::default().fg(Color::DarkGray),
-                        SpotOutcome::Surrender => Style::default().fg(Color::from_u32(0xFF_A5_00)), // Orange
-                    }
-                } else {
-                    Style::default()
-                };
-
-                // Calculate wrapping based on available width
-                let spot_width = spot_areas[i].width.saturating_sub(2); // Subtract borders
-                let card_width = 4; // Each card is roughly "Ah♥ " = 4 chars
-                let cards_per_line = (spot_width / card_width).max(1) as usize;
-
-                // Wrap cards into multiple lines if needed
-                let mut wrapped_lines: Vec<Line> = Vec::new();
-                for chunk in player_cards.chunks(cards_per_line) {
-                    wrapped_lines.push(Line::from(chunk.to_vec()));
-                }
-
-                // Calculate vertical centering
-                let spot_block_height = spot_areas[i].height.saturating_sub(2);
-                let content_lines = wrapped_lines.len();
-                let padding_top = (spot_block_height.saturating_sub(content_lines as u16)) / 2;
-
-                let mut spot_lines: Vec<Line> = vec![Line::from(""); padding_top as usize];
-                spot_lines.extend(wrapped_lines);
-
-                let spot_block = Paragraph::new(spot_lines)
-                    .block(
-                        Block::default()
-                            .title(format!(" {} ({}) ", i + 1, player_value))
-                            .borders(Borders::ALL)
-                            .border_style(border_style),
-                    )
-                    .alignment(Alignment::Center);
-                f.render_widget(spot_block, spot_areas[i]);
             }
         }
     } else if let Some(ref contract_game) = app.contract_game_state {
@@ -3326,18 +3801,10 @@ fn ui(f: &mut Frame, app: &App) {
             .split(game_area[1]);
 
         for (i, hand) in contract_game.hands.iter().enumerate() {
-            let player_cards: Vec<Span> = hand
+            let cards: Vec<Option<blackjack::Card>> = hand
                 .cards
                 .iter()
-                .map(|&card_idx| {
-                    let card = blackjack::Card::from_index(card_idx as usize);
-                    let card_str = card.to_display();
-                    let color = card_color(&card_str);
-                    Span::styled(
-                        format!("{card_str} "),
-                        Style::default().fg(color).bg(Color::Gray),
-                    )
-                })
+                .map(|&card_idx| Some(blackjack::Card::from_index(card_idx as usize)))
                 .collect();
 
             let player_value = calculate_hand_value_from_indices(&hand.cards);
@@ -3356,11 +3823,14 @@ fn ui(f: &mut Frame, app: &App) {
                 Style::default()
             };
 
+            let card_lines =
+                card_view::hand_lines(&cards, card_mode, hand_areas[i].width.saturating_sub(2));
+
             // Vertical centering
             let hand_block_height = hand_areas[i].height.saturating_sub(2);
-            let padding_top = hand_block_height / 2;
+            let padding_top = hand_block_height.saturating_sub(card_lines.len() as u16) / 2;
             let mut hand_lines: Vec<Line> = vec![Line::from(""); padding_top as usize];
-            hand_lines.push(Line::from(player_cards));
+            hand_lines.extend(card_lines);
 
             let hand_block = Paragraph::new(hand_lines)
                 .block(
@@ -3397,6 +3867,12 @@ fn ui(f: &mut Frame, app: &App) {
         #[cfg(not(feature = "wallet"))]
         let log_title = " Game Log ".to_string();
 
+        let log_title = if app.log_scroll > 0 {
+            format!("{log_title}↑{} ", app.log_scroll)
+        } else {
+            log_title
+        };
+
         // Estimate how many log entries will fit (accounting for wrapping)
         let log_frame_height = log_area.height.saturating_sub(2) as usize; // Subtract borders
         let log_width = log_area.width.saturating_sub(4) as usize; // Subtract borders and bullet
@@ -3406,7 +3882,7 @@ fn ui(f: &mut Frame, app: &App) {
         let mut logs_to_show = Vec::new();
 
         // Work backwards from the end to collect enough logs to fill the screen
-        for log in app.logs.iter().rev() {
+        for log in app.logs.iter().rev().skip(app.log_scroll) {
             let log_len = log.len() + 2; // Add bullet and space
             let wrapped_lines = (log_len / log_width.max(1)) + 1;
 
@@ -3435,7 +3911,13 @@ fn ui(f: &mut Frame, app: &App) {
                 Block::default()
                     .title(log_title)
                     .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Green)),
+                    .border_style(Style::default().fg(
+                        if app.focused_panel == FocusedPanel::LogPanel {
+                            Color::Yellow
+                        } else {
+                            Color::Green
+                        },
+                    )),
             )
             .style(Style::default().fg(Color::Gray))
             .wrap(Wrap { trim: true });
@@ -3465,18 +3947,117 @@ fn ui(f: &mut Frame, app: &App) {
     #[cfg(not(feature = "wallet"))]
     let status_text = app.status.clone();
 
-    let status_bar = Paragraph::new(status_text.as_str())
+    let status_text =
+        if app.phase == GamePhase::WaitingForReveal && connection_color == Some(Color::Red) {
+            format!("{status_text} | Connection issues — game updates may be delayed")
+        } else {
+            status_text
+        };
+
+    let mut status_spans = vec![Span::raw(status_text)];
+    if let Some(span) = timeout_span(app) {
+        status_spans.push(Span::raw(" | "));
+        status_spans.push(span);
+    }
+    let status_bar = Paragraph::new(Line::from(status_spans))
         .style(Style::default().fg(Color::White))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(status_bar, main_chunks[2]);
 
+    if let Some(ref dialog) = app.confirm_dialog {
+        dialog.render(f, main_chunks[1]);
+    }
+
+    if let Some(ref overlay) = app.error_overlay {
+        render_error_overlay(f, overlay, main_chunks[1]);
+    }
+
     // Render help modal if visible
     if app.help_visible {
-        render_help_modal(f);
+        render_help_modal(f, app);
+    }
+}
+
+/// Status bar countdown from the last `GetTimeoutStatus` poll: shown in the final
+/// 30 seconds, flashing red in the last 10, then the claim prompt once it runs out.
+fn timeout_span(app: &App) -> Option<Span<'static>> {
+    let (status, received) = app.timeout_status.as_ref()?;
+    let party = status.timed_out_party.as_deref()?;
+    let since_poll = received.elapsed();
+    let remaining = status
+        .seconds_until_eligible
+        .map_or(0, |secs| secs.saturating_sub(since_poll.as_secs()));
+
+    if status.can_claim_timeout || remaining == 0 {
+        // Anyone can claim, but only a stalled dealer is worth claiming against
+        return Some(if party == "dealer" {
+            Span::styled(
+                " [X] Claim timeout ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else {
+            Span::styled(
+                "Timed out: the dealer can claim this game",
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )
+        });
     }
+    if remaining >= 30 {
+        return None;
+    }
+
+    let style = if remaining >= 10 {
+        Style::default().fg(Color::Yellow)
+    } else if since_poll.as_millis() / 500 % 2 == 0 {
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let whose = if party == "player" {
+        "your move"
+    } else {
+        "dealer reveal"
+    };
+    Some(Span::styled(
+        format!("Timeout in {remaining}s ({whose})"),
+        style,
+    ))
 }
 
-fn render_help_modal(f: &mut Frame) {
+fn render_error_overlay(f: &mut Frame, overlay: &ErrorOverlay, game_area: ratatui::layout::Rect) {
+    use ratatui::widgets::Clear;
+
+    // Pinned to the top of the game area, 80% wide
+    let width = (game_area.width * 80) / 100;
+    let area = ratatui::layout::Rect {
+        x: game_area.x + (game_area.width - width) / 2,
+        y: game_area.y,
+        width,
+        height: game_area.height.min(5),
+    };
+
+    f.render_widget(Clear, area);
+    let paragraph = Paragraph::new(overlay.message.as_str())
+        .style(Style::default().fg(Color::White).bg(Color::Red))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(
+                    Style::default()
+                        .fg(Color::LightRed)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .title(" Error - [Esc] to dismiss ")
+                .title_alignment(Alignment::Center),
+        )
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, area);
+}
+
+fn render_help_modal(f: &mut Frame, app: &App) {
     use ratatui::widgets::Clear;
 
     // Center the help modal - 80% width, 80% height
@@ -3516,7 +4097,11 @@ fn render_help_modal(f: &mut Frame) {
         )]),
         Line::from("  [F] - Fast Mode: Instant gameplay, no ZK proofs"),
         Line::from("  [T] - Trustless Mode: Full ZK proofs (~1 min setup)"),
-        Line::from("  [C] - Contract Mode: On-chain with smart contract"),
+        Line::from(if app.offline {
+            "  [C] - Contract Mode: disabled (--offline)"
+        } else {
+            "  [C] - Contract Mode: On-chain with smart contract"
+        }),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Gameplay Keys:",
@@ -3541,8 +4126,9 @@ fn render_help_modal(f: &mut Frame) {
         Line::from("  [G] - Generate new wallet"),
         Line::from("  [J] - Join selected game"),
         Line::from("  [L] - List available games (in setup)"),
-        Line::from("  [X] - Claim timeout (if opponent doesn't respond)"),
+        Line::from("  [X] - Claim timeout (status bar counts down the last 30s)"),
         Line::from("  [0-9] - Select game from list"),
+        Line::from("  [Down] - Next page of games (in setup)"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "Other Keys:",
@@ -3551,6 +4137,8 @@ fn render_help_modal(f: &mut Frame) {
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from("  [L] - Toggle log visibility (during gameplay)"),
+        Line::from("  [Tab] - Focus game / log; [↑]/[↓] scroll the focused log"),
+        Line::from("  [V] - Cycle card display (ASCII / Graphic / Compact)"),
         Line::from("  [?] - Show/hide this help"),
         Line::from("  [Q] - Quit"),
         Line::from(""),
@@ -3583,3 +4171,295 @@ fn render_help_modal(f: &mut Frame) {
 
     f.render_widget(help_paragraph, modal_area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    fn offline_app() -> App {
+        App::new(
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RapidsnarkProver),
+            true,
+        )
+    }
+
+    fn render(app: &App) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        terminal
+            .backend()
+            .buffer()
+            .content
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_offline_flag() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(offline_flag(args(&["juodzekas-tui", "--offline"])));
+        assert!(offline_flag(args(&["juodzekas-tui", "--headless"])));
+        assert!(!offline_flag(args(&["juodzekas-tui"])));
+        // The program name is not a flag
+        assert!(!offline_flag(args(&["--offline"])));
+    }
+
+    #[test]
+    fn test_version_flag() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(version_flag(args(&["juodzekas-tui", "--version"])));
+        assert!(version_flag(args(&["juodzekas-tui", "-V"])));
+        assert!(!version_flag(args(&["juodzekas-tui", "--offline"])));
+
+        let version = build_info::version_string();
+        assert!(version.starts_with(&format!("juodzekas-tui {} (git: ", build_info::VERSION)));
+        assert!(version.ends_with(", network: xion-testnet-2)"));
+    }
+
+    #[test]
+    fn test_vk_override_arg() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let dev_keys = Some(std::path::PathBuf::from("dev/keys"));
+        let split = args(&["juodzekas-tui", "--offline", "--vk-override", "dev/keys"]);
+        assert_eq!(vk_override_arg(split), dev_keys);
+        assert_eq!(
+            vk_override_arg(args(&["juodzekas-tui", "--vk-override=dev/keys"])),
+            dev_keys
+        );
+        // A trailing flag without a directory is ignored
+        let missing_dir = args(&["juodzekas-tui", "--vk-override"]);
+        assert_eq!(vk_override_arg(missing_dir), None);
+        assert_eq!(vk_override_arg(args(&["juodzekas-tui"])), None);
+    }
+
+    #[test]
+    fn test_replay_arg() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let file = Some(std::path::PathBuf::from("data/replay_1.json"));
+        let split = args(&["juodzekas-tui", "--replay", "data/replay_1.json"]);
+        assert_eq!(replay_arg(split), file);
+        assert_eq!(
+            replay_arg(args(&["juodzekas-tui", "--replay=data/replay_1.json"])),
+            file
+        );
+        assert_eq!(replay_arg(args(&["juodzekas-tui", "--replay"])), None);
+        assert_eq!(replay_arg(args(&["juodzekas-tui", "--offline"])), None);
+    }
+
+    #[test]
+    fn test_replay_steps_through_round() {
+        use blackjack::{Action, ActionEntry, ActionHistory, Card};
+        let entry = |action, card_dealt| ActionEntry {
+            action,
+            spot_index: 0,
+            hand_index: 0,
+            card_dealt,
+            timestamp: None,
+        };
+        let mut app = offline_app();
+        app.start_replay(ReplayFile {
+            num_spots: 1,
+            rules: blackjack::GameRules::default(),
+            deck: vec![
+                Card::TenHearts,
+                Card::SixSpades,
+                Card::TwoClubs,
+                Card::TenDiamonds,
+                Card::FiveHearts,
+                Card::FourSpades,
+            ],
+            history: ActionHistory {
+                entries: vec![
+                    entry(Action::Hit, Some(Card::FiveHearts)),
+                    entry(Action::Stand, None),
+                    entry(Action::Hit, Some(Card::FourSpades)),
+                    entry(Action::Stand, None),
+                ],
+            },
+            client_version: String::new(),
+        });
+        assert!(app.phase == GamePhase::PlayerTurn);
+        assert_eq!(app.status, "Replay 0/4: initial deal");
+        assert_eq!(app.game_state.as_ref().unwrap().player_hands[0][0].len(), 2);
+
+        app.show_replay_step(1);
+        assert_eq!(app.status, "Replay 1/4: Hit");
+        assert_eq!(app.game_state.as_ref().unwrap().active_hand_value(), 17);
+
+        // Stepping past the end stays on the settled round
+        app.show_replay_step(10);
+        assert!(app.phase == GamePhase::GameOver);
+        assert_eq!(app.status, "Replay 4/4: Stand");
+        assert_eq!(app.game_state.as_ref().unwrap().dealer_hand.len(), 3);
+    }
+
+    #[test]
+    fn test_offline_disables_contract_mode() {
+        let mut app = offline_app();
+        assert!(!app.status.contains("[C]"));
+        assert!(!app.logs.iter().any(|line| line.starts_with("[C]")));
+
+        app.select_contract_mode();
+        assert!(app.phase == GamePhase::ModeSelection);
+        assert!(app.selected_mode.is_none());
+
+        let screen = render(&app);
+        assert!(screen.contains("[OFFLINE]"));
+        assert!(!screen.contains("[C]ontract"));
+    }
+
+    #[test]
+    fn test_resume_last_session() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(fresh_flag(args(&["juodzekas-tui", "--fresh"])));
+        assert!(!fresh_flag(args(&["juodzekas-tui"])));
+
+        let mut app = offline_app();
+        app.offer_last_session(SessionConfig {
+            last_mode: Some(GameMode::Fast),
+            last_spots: 2,
+            last_contract_address: Some("xion1contract".to_string()),
+            last_profile_address: None,
+        });
+        assert_eq!(
+            app.status,
+            "[Enter] to resume last config ([F]ast, 2 spots) or [C]hange"
+        );
+        assert_eq!(app.contract_address_input, "xion1contract");
+
+        app.resume_last_session();
+        assert!(app.phase == GamePhase::SpotSelection);
+        assert_eq!(app.selected_mode, Some(GameMode::Fast));
+        assert_eq!(app.resume_spots, Some(2));
+
+        // [C]hange drops the offer; offline, a contract session is not offered at all
+        let mut app = offline_app();
+        app.offer_last_session(SessionConfig {
+            last_mode: Some(GameMode::Trustless),
+            last_spots: 1,
+            ..Default::default()
+        });
+        app.dismiss_last_session();
+        assert!(app.last_session.is_none());
+        assert!(app.status.starts_with("Select mode"));
+        app.offer_last_session(SessionConfig {
+            last_mode: Some(GameMode::Contract),
+            ..Default::default()
+        });
+        assert!(app.last_session.is_none());
+    }
+
+    #[test]
+    fn test_online_title_has_no_offline_tag() {
+        let app = App::new(
+            Arc::new(Mutex::new(Vec::new())),
+            Arc::new(RapidsnarkProver),
+            false,
+        );
+        assert!(app.status.contains("[C]ontract"));
+        assert!(!render(&app).contains("[OFFLINE]"));
+    }
+
+    #[test]
+    fn test_tab_focus_and_log_scroll() {
+        let mut app = offline_app();
+        assert!(app.focused_panel == FocusedPanel::GameArea);
+        app.cycle_focus();
+        assert!(app.focused_panel == FocusedPanel::LogPanel);
+        app.cycle_focus();
+        assert!(app.focused_panel == FocusedPanel::none());
+
+        // A hidden log can't take focus
+        app.log_visible = false;
+        app.cycle_focus();
+        assert!(app.focused_panel == FocusedPanel::GameArea);
+        app.log_visible = true;
+
+        for i in 0..5 {
+            app.add_log(format!("entry {i}"));
+        }
+        app.scroll_log(false);
+        assert_eq!(app.log_scroll, 0);
+        app.scroll_log(true);
+        app.scroll_log(true);
+        assert_eq!(app.log_scroll, 2);
+        // New messages don't move a scrolled-back view
+        app.add_log("entry 5".to_string());
+        assert_eq!(app.log_scroll, 3);
+        for _ in 0..LOG_HISTORY {
+            app.scroll_log(true);
+        }
+        assert_eq!(app.log_scroll, app.logs.len() - 1);
+    }
+
+    #[test]
+    fn test_timeout_countdown_in_status_bar() {
+        let mut app = offline_app();
+        let status = |can_claim_timeout, seconds_until_eligible: Option<u64>, party: &str| {
+            contract_msg::TimeoutStatusResponse {
+                can_claim_timeout,
+                seconds_until_eligible,
+                timed_out_party: Some(party.to_string()),
+            }
+        };
+
+        app.timeout_status = Some((status(false, Some(45), "player"), std::time::Instant::now()));
+        assert!(!render(&app).contains("Timeout in"));
+
+        app.timeout_status = Some((status(false, Some(20), "player"), std::time::Instant::now()));
+        assert!(render(&app).contains("Timeout in 20s (your move)"));
+
+        app.timeout_status = Some((status(true, None, "dealer"), std::time::Instant::now()));
+        assert!(render(&app).contains("[X] Claim timeout"));
+
+        // Settled games have nobody to blame
+        app.timeout_status = Some((
+            contract_msg::TimeoutStatusResponse {
+                can_claim_timeout: false,
+                seconds_until_eligible: None,
+                timed_out_party: None,
+            },
+            std::time::Instant::now(),
+        ));
+        assert!(timeout_span(&app).is_none());
+    }
+
+    #[cfg(feature = "wallet")]
+    #[test]
+    fn test_offline_ignores_env_and_skips_network() {
+        std::env::set_var("CONTRACT_ADDR", "xion1offlinetest");
+        let mut app = offline_app();
+        let mnemonic = "abandon abandon abandon abandon abandon abandon \
+                        abandon abandon abandon abandon abandon about";
+        assert!(app.load_wallet_from_mnemonic(mnemonic));
+        assert_eq!(app.contract_address, None);
+        assert!(app.input_mode == InputMode::ContractAddress);
+
+        // Every network task is a no-op, even with everything it needs in place
+        app.contract_address = Some("xion1offlinetest".to_string());
+        app.game_id = Some(1);
+        app.spawn_wallet_connect();
+        app.spawn_list_games();
+        app.spawn_join_game();
+        app.spawn_hit();
+        app.spawn_submit_reveal(vec![0]);
+        assert_eq!(app.pending_op, None);
+        app.spawn_query_balance();
+        app.spawn_query_game_state();
+        assert!(!app.balance_poll_inflight);
+        assert!(!app.game_poll_inflight);
+        assert!(app.action_rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_hand_value_from_indices() {
+        // Indices are 0-51 with rank = index % 13: 0 is an Ace, 12 a King
+        assert_eq!(calculate_hand_value_from_indices(&[]), 0);
+        assert_eq!(calculate_hand_value_from_indices(&[0]), 11);
+        assert_eq!(calculate_hand_value_from_indices(&[0, 12]), 21);
+        assert_eq!(calculate_hand_value_from_indices(&[9, 5, 0]), 17);
+    }
+}
@@ -0,0 +1,104 @@
+//! Replay files for local games. When a round ends its dealt cards and action
+//! history are written to `data/` as JSON; `--replay <file>` steps back through them.
+
+use blackjack::{ActionHistory, Card, GameRules, GameState as BlackjackState};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory (relative to the working directory) replays are saved to
+pub const REPLAY_DIR: &str = "data";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayFile {
+    pub num_spots: usize,
+    pub rules: GameRules,
+    /// Cards in the order they were dealt
+    pub deck: Vec<Card>,
+    pub history: ActionHistory,
+    /// `--version` string of the TUI that saved the round
+    #[serde(default)]
+    pub client_version: String,
+}
+
+impl ReplayFile {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let json = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Write to `REPLAY_DIR`, named after the current time, and return the path.
+    pub fn save(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)?
+            .as_secs();
+        std::fs::create_dir_all(REPLAY_DIR)?;
+        let path = PathBuf::from(REPLAY_DIR).join(format!("replay_{secs}.json"));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+
+    /// Number of actions that can be stepped through
+    pub fn steps(&self) -> usize {
+        self.history.entries.len()
+    }
+
+    /// The round after its first `steps` actions.
+    pub fn state_at(&self, steps: usize) -> Result<BlackjackState, &'static str> {
+        let history = ActionHistory {
+            entries: self.history.entries[..steps.min(self.steps())].to_vec(),
+        };
+        BlackjackState::replay(self.num_spots, self.rules, &self.deck, &history)
+    }
+}
+
+/// A replay being shown, and how many of its actions have been applied
+pub struct Playback {
+    pub file: ReplayFile,
+    pub step: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use blackjack::{Action, ActionEntry, GamePhase};
+
+    #[test]
+    fn test_replay_file_json_round_trip() {
+        let file = ReplayFile {
+            num_spots: 1,
+            rules: GameRules::default(),
+            deck: vec![
+                Card::TenHearts,
+                Card::SixSpades,
+                Card::NineClubs,
+                Card::TenDiamonds,
+            ],
+            history: ActionHistory {
+                entries: vec![ActionEntry {
+                    action: Action::Stand,
+                    spot_index: 0,
+                    hand_index: 0,
+                    card_dealt: None,
+                    timestamp: None,
+                }],
+            },
+            client_version: crate::build_info::version_string(),
+        };
+
+        let json = serde_json::to_string_pretty(&file).unwrap();
+        let loaded: ReplayFile = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, file);
+
+        // Replays saved before the version was recorded still load
+        let mut old: serde_json::Value = serde_json::from_str(&json).unwrap();
+        old.as_object_mut().unwrap().remove("client_version");
+        let loaded: ReplayFile = serde_json::from_value(old).unwrap();
+        assert_eq!(loaded.client_version, "");
+
+        assert_eq!(file.state_at(0).unwrap().phase, GamePhase::PlayerTurn);
+        // The history ends with the dealer still to hit 16
+        let end = file.state_at(file.steps()).unwrap();
+        assert_eq!(end.phase, GamePhase::DealerTurn);
+        assert!(end.dealer_should_hit());
+    }
+}
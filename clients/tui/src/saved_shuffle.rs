@@ -0,0 +1,95 @@
+//! Crash recovery for contract-mode joins. The player's shuffle, keys and proof are
+//! written to disk before the join transaction, so a restart can resume instead of
+//! paying for another shuffle proof.
+
+use std::path::PathBuf;
+use std::time::Duration;
+use zk_shuffle::elgamal::KeyPair;
+use zk_shuffle::keypair::KeyStore;
+use zk_shuffle::prover::ShuffleProof;
+use zk_shuffle::shuffle::ShuffleResult;
+
+/// Directory (relative to the working directory) holding saved shuffles
+pub const SAVED_SHUFFLE_DIR: &str = "data";
+/// Saved shuffles older than this are ignored; the dealer may have moved on
+pub const MAX_RESUME_AGE: Duration = Duration::from_secs(10 * 60);
+
+pub struct SavedShuffle {
+    pub keys: KeyPair,
+    pub shuffle: ShuffleResult,
+    /// Present if the proof finished before the crash
+    pub proof: Option<ShuffleProof>,
+}
+
+fn shuffle_path(game_id: u64) -> PathBuf {
+    PathBuf::from(SAVED_SHUFFLE_DIR).join(format!("shuffle_game_{game_id}.bin"))
+}
+
+fn proof_path(game_id: u64) -> PathBuf {
+    PathBuf::from(SAVED_SHUFFLE_DIR).join(format!("shuffle_game_{game_id}_proof.json"))
+}
+
+/// Age of the saved shuffle for `game_id`, if there is one young enough to resume.
+pub fn resumable_age(game_id: u64) -> Option<Duration> {
+    let modified = std::fs::metadata(shuffle_path(game_id))
+        .and_then(|m| m.modified())
+        .ok()?;
+    let age = modified.elapsed().unwrap_or_default();
+    (age <= MAX_RESUME_AGE).then_some(age)
+}
+
+/// Game IDs with a resumable saved shuffle.
+pub fn resumable_games() -> Vec<(u64, Duration)> {
+    let Ok(entries) = std::fs::read_dir(SAVED_SHUFFLE_DIR) else {
+        return Vec::new();
+    };
+    let mut games: Vec<(u64, Duration)> = entries
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            let game_id = name
+                .strip_prefix("shuffle_game_")?
+                .strip_suffix(".bin")?
+                .parse()
+                .ok()?;
+            Some((game_id, resumable_age(game_id)?))
+        })
+        .collect();
+    games.sort_unstable();
+    games
+}
+
+/// Save the shuffle (encrypted with `key`) and the keys it was made for.
+pub fn save(
+    game_id: u64,
+    key: &[u8; 32],
+    keys: &KeyPair,
+    shuffle: &ShuffleResult,
+) -> Result<(), zk_shuffle::Error> {
+    KeyStore::new(SAVED_SHUFFLE_DIR).save_game_keys(game_id, &keys.sk, &keys.pk, key)?;
+    shuffle.save_to_file(&shuffle_path(game_id), key)
+}
+
+/// The proof only contains public data, so it is stored as plain JSON.
+pub fn save_proof(game_id: u64, proof: &ShuffleProof) -> std::io::Result<()> {
+    std::fs::write(proof_path(game_id), serde_json::to_vec(proof)?)
+}
+
+pub fn load(game_id: u64, key: &[u8; 32]) -> Result<SavedShuffle, zk_shuffle::Error> {
+    let (sk, pk) = KeyStore::new(SAVED_SHUFFLE_DIR).load_game_keys(game_id, key)?;
+    let shuffle = ShuffleResult::load_from_file(&shuffle_path(game_id), key)?;
+    let proof = std::fs::read(proof_path(game_id))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    Ok(SavedShuffle {
+        keys: KeyPair { sk, pk },
+        shuffle,
+        proof,
+    })
+}
+
+/// Remove everything saved for `game_id` once the join has landed on-chain.
+pub fn remove(game_id: u64) {
+    let _ = std::fs::remove_file(shuffle_path(game_id));
+    let _ = std::fs::remove_file(proof_path(game_id));
+    let _ = KeyStore::new(SAVED_SHUFFLE_DIR).delete_game_keys(game_id);
+}
@@ -0,0 +1,74 @@
+//! Settings from the last game start, offered on the next launch so a restart is one key.
+
+use crate::game::GameMode;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::PathBuf;
+
+/// Under the home directory
+const SESSION_FILE: &str = ".juodzekas/session.toml";
+
+/// Never holds secrets: the file is plain text in the home directory. A field that
+/// could carry one (mnemonic, secret key) must be `#[serde(skip)]`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    pub last_mode: Option<GameMode>,
+    pub last_spots: u8,
+    pub last_contract_address: Option<String>,
+    /// Wallet address used in contract mode
+    pub last_profile_address: Option<String>,
+}
+
+impl SessionConfig {
+    pub fn path() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join(SESSION_FILE))
+    }
+
+    /// The saved session, or defaults if there is none or it can't be read.
+    pub fn load() -> Self {
+        let Some(contents) = Self::path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+            return SessionConfig::default();
+        };
+        match toml::from_str(&contents) {
+            Ok(session) => session,
+            Err(e) => {
+                log::warn!("Ignoring invalid {SESSION_FILE}: {e}");
+                SessionConfig::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "HOME is not set"))?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string(self).map_err(io::Error::other)?;
+        std::fs::write(path, contents)
+    }
+
+    /// "[F]ast, 2 spots" for the resume prompt; None if there is nothing to resume.
+    pub fn summary(&self) -> Option<String> {
+        let spot_count = (1..=8)
+            .contains(&self.last_spots)
+            .then(|| spots(self.last_spots));
+        match self.last_mode? {
+            GameMode::Fast => Some(format!("[F]ast, {}", spot_count?)),
+            GameMode::Trustless => Some(format!("[T]rustless, {}", spot_count?)),
+            GameMode::Contract => Some(match &self.last_contract_address {
+                Some(addr) => format!("[C]ontract, {addr}"),
+                None => "[C]ontract".to_string(),
+            }),
+        }
+    }
+}
+
+fn spots(n: u8) -> String {
+    if n == 1 {
+        "1 spot".to_string()
+    } else {
+        format!("{n} spots")
+    }
+}
@@ -1,12 +1,28 @@
+use crate::contract::instantiate::validate_terms;
+use crate::contract::{
+    active_games_indexed, awaits_emergency_refund, backfill_active_games, backfill_player_stats,
+    count_pending_refunds, credit_dealer_settlement, deck_hash, game_config, is_split_ace,
+    is_whitelisted, max_win_amount, record_config_changes, timeout_blocker,
+};
 use crate::error::ContractError;
 use crate::game_logic::{config_to_rules, to_blackjack_state};
 use crate::msg::ExecuteMsg;
 use crate::state::{
-    GameSession, GameStatus, Hand, HandStatus, TurnOwner, CONFIG, DEALER, DEALER_BALANCE, GAMES,
-    GAME_COUNTER,
+    GameOutcome, GameSession, GameStatus, GameTerms, Hand, HandStatus, InsuranceBet, SettledGame,
+    TurnOwner, ACTIVE_GAMES, CONFIG, DEALER, DEALER_BALANCE, GAMES, GAME_COUNTER, PAUSED,
+    PAUSED_AT, PLAYER_SETTLED_GAMES, PROTOCOL_FEES, REVEAL_HISTORY, WAITING_GAMES, WHITELIST,
+    WHITELIST_ENABLED,
+};
+use crate::zk::{xion_zk_verify, xion_zk_vkey_registered, Point, PointExt};
+use cosmwasm_std::{
+    Addr, Binary, DepsMut, Empty, Env, Event, MessageInfo, Order, Response, StdError, StdResult,
+    Storage, Uint128,
 };
-use crate::zk::xion_zk_verify;
-use cosmwasm_std::{Addr, Binary, DepsMut, Env, MessageInfo, Order, Response, StdError, Uint128};
+
+/// Longest `ForfeitGame` reason, in characters
+const MAX_FORFEIT_REASON_LEN: usize = 256;
+/// Games refunded per `EmergencyRefundAll` call, to stay within the gas limit
+const EMERGENCY_REFUND_BATCH: usize = 20;
 
 /// Reject messages that send unexpected funds
 fn no_funds(info: &MessageInfo) -> Result<(), ContractError> {
@@ -19,10 +35,11 @@ fn no_funds(info: &MessageInfo) -> Result<(), ContractError> {
 /// Reject messages that include coins in any denom other than the expected one.
 /// Prevents tokens from getting permanently stuck in the contract.
 fn only_denom(info: &MessageInfo, denom: &str) -> Result<(), ContractError> {
-    if info.funds.iter().any(|c| c.denom != denom) {
-        return Err(ContractError::Std(StdError::msg(format!(
-            "Only {denom} accepted; other denoms would be permanently locked"
-        ))));
+    if let Some(coin) = info.funds.iter().find(|c| c.denom != denom) {
+        return Err(ContractError::InvalidDenom {
+            expected: denom.to_string(),
+            received: coin.denom.clone(),
+        });
     }
     Ok(())
 }
@@ -89,11 +106,51 @@ pub fn execute(
             proof,
             public_inputs,
         ),
+        ExecuteMsg::SubmitBatchReveal { game_id, reveals } => {
+            execute_submit_batch_reveal(deps, _env, info, game_id, reveals)
+        }
         ExecuteMsg::CancelGame { game_id } => execute_cancel_game(deps, info, game_id),
         ExecuteMsg::ClaimTimeout { game_id } => execute_claim_timeout(deps, _env, info, game_id),
         ExecuteMsg::SweepSettled { game_ids } => execute_sweep_settled(deps, _env, game_ids),
+        ExecuteMsg::BurnExpiredGames {
+            start_after,
+            before_game_id,
+            limit,
+        } => execute_burn_expired_games(deps, _env, info, start_after, before_game_id, limit),
         ExecuteMsg::DepositBankroll {} => execute_deposit_bankroll(deps, info),
         ExecuteMsg::WithdrawBankroll { amount } => execute_withdraw_bankroll(deps, info, amount),
+        ExecuteMsg::ClaimProtocolFee {} => execute_claim_protocol_fee(deps, info),
+        ExecuteMsg::AddToWhitelist { addresses } => execute_add_to_whitelist(deps, info, addresses),
+        ExecuteMsg::RemoveFromWhitelist { addresses } => {
+            execute_remove_from_whitelist(deps, info, addresses)
+        }
+        ExecuteMsg::ClearWhitelist {} => execute_clear_whitelist(deps, info),
+        ExecuteMsg::UpdateVerificationKeys {
+            shuffle_vk_id,
+            reveal_vk_id,
+        } => execute_update_verification_keys(deps, _env, info, shuffle_vk_id, reveal_vk_id),
+        ExecuteMsg::UpdateConfig {
+            min_bet,
+            max_bet,
+            timeout_seconds,
+            surrender_allowed,
+        } => execute_update_config(
+            deps,
+            _env,
+            info,
+            min_bet,
+            max_bet,
+            timeout_seconds,
+            surrender_allowed,
+        ),
+        ExecuteMsg::Pause {} => execute_set_paused(deps, _env, info, true),
+        ExecuteMsg::Unpause {} => execute_set_paused(deps, _env, info, false),
+        ExecuteMsg::EmergencyRefundAll {} => execute_emergency_refund_all(deps, _env, info),
+        ExecuteMsg::BackfillPlayerStats {} => execute_backfill_player_stats(deps, info),
+        ExecuteMsg::BackfillActiveGames {} => execute_backfill_active_games(deps, info),
+        ExecuteMsg::ForfeitGame { game_id, reason } => {
+            execute_forfeit_game(deps, _env, info, game_id, reason)
+        }
     }
 }
 
@@ -108,6 +165,7 @@ pub fn execute_create_game(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     only_denom(&info, &config.denom)?;
+    not_paused(&deps)?;
     let dealer = DEALER.load(deps.storage)?;
 
     // Only the contract's dealer can create games
@@ -122,13 +180,14 @@ pub fn execute_create_game(
             "Deck must contain exactly 52 cards",
         )));
     }
+    Point::from_bytes_validated(&public_key)?;
 
     // Verify dealer's shuffle proof
     let verified = xion_zk_verify(deps.as_ref(), &config.shuffle_vk_id, proof, public_inputs)?;
     if !verified {
-        return Err(ContractError::Std(StdError::msg(
-            "Invalid dealer shuffle proof",
-        )));
+        return Err(ContractError::ProofVerificationFailed {
+            proof: "dealer shuffle".to_string(),
+        });
     }
 
     // Dealer must have sufficient bankroll (10x max bet)
@@ -171,6 +230,7 @@ pub fn execute_create_game(
     GAME_COUNTER.save(deps.storage, &next_id)?;
 
     // Create game session waiting for player
+    let deck_hash = deck_hash(&shuffled_deck);
     let game = GameSession {
         player: Addr::unchecked("pending"), // Placeholder until player joins
         dealer: info.sender.clone(),
@@ -189,17 +249,22 @@ pub fn execute_create_game(
         last_card_index: 0,
         pending_reveals: vec![],
         dealer_peeked: false,
-        insurance_bet: None,
+        insurance: None,
+        deck_hash: deck_hash.clone(),
+        player_deck_hash: None,
+        terms: Some(GameTerms::from_config(&config)),
     };
 
     // Store game by ID
     GAMES.save(deps.storage, game_id, &game)?;
+    WAITING_GAMES.save(deps.storage, game_id, &Empty {})?;
 
     Ok(Response::new()
         .add_attribute("action", "create_game")
         .add_attribute("game_id", game_id.to_string())
         .add_attribute("dealer", info.sender)
-        .add_attribute("bankroll", required_bankroll))
+        .add_attribute("bankroll", required_bankroll)
+        .add_attribute("deck_hash", deck_hash.to_hex()))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -215,13 +280,10 @@ pub fn execute_join_game(
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     only_denom(&info, &config.denom)?;
+    not_paused(&deps)?;
 
-    // Validate bet
-    if bet < config.min_bet {
-        return Err(ContractError::Std(StdError::msg("Bet too low")));
-    }
-    if bet > config.max_bet {
-        return Err(ContractError::Std(StdError::msg("Bet too high")));
+    if bet.is_zero() {
+        return Err(ContractError::ZeroBetNotAllowed {});
     }
 
     if shuffled_deck.len() != 52 {
@@ -229,6 +291,7 @@ pub fn execute_join_game(
             "Deck must contain exactly 52 cards",
         )));
     }
+    Point::from_bytes_validated(&public_key)?;
 
     // Auto-find first WaitingForPlayerJoin game
     let (game_id, mut game) = GAMES
@@ -243,6 +306,16 @@ pub fn execute_join_game(
         })
         .ok_or_else(|| ContractError::Std(StdError::msg("No game available for joining")))?;
 
+    // Bet limits are the ones the game was created (and its bankroll locked) under
+    let config = game_config(deps.storage, &game)?;
+    if bet < config.min_bet || bet > config.max_bet {
+        return Err(ContractError::BetOutOfRange {
+            min: config.min_bet,
+            max: config.max_bet,
+            provided: bet,
+        });
+    }
+
     // Dealer cannot join own game (self-play)
     if info.sender == game.dealer {
         return Err(ContractError::Std(StdError::msg(
@@ -250,12 +323,18 @@ pub fn execute_join_game(
         )));
     }
 
+    if !is_whitelisted(deps.storage, &info.sender)? {
+        return Err(ContractError::NotWhitelisted {
+            address: info.sender.to_string(),
+        });
+    }
+
     // Verify player's re-shuffle proof
     let verified = xion_zk_verify(deps.as_ref(), &config.shuffle_vk_id, proof, public_inputs)?;
     if !verified {
-        return Err(ContractError::Std(StdError::msg(
-            "Invalid player shuffle proof",
-        )));
+        return Err(ContractError::ProofVerificationFailed {
+            proof: "player shuffle".to_string(),
+        });
     }
 
     // Player must deposit exact bet in correct denom
@@ -277,12 +356,15 @@ pub fn execute_join_game(
     game.player = info.sender.clone();
     game.bet = bet;
     game.player_pubkey = public_key;
+    let player_deck_hash = deck_hash(&shuffled_deck);
+    game.player_deck_hash = Some(player_deck_hash.clone());
     game.deck = shuffled_deck; // Player's re-shuffle becomes final deck
     game.player_shuffled_deck = None; // Dealer's initial shuffle no longer needed on-chain
     game.hands = vec![Hand {
         cards: vec![],
         bet,
         status: HandStatus::Active,
+        split_depth: 0,
     }];
     game.status = GameStatus::WaitingForReveal {
         reveal_requests: vec![0, 1, 2], // First 3 cards: player card 1, player card 2, dealer upcard
@@ -294,13 +376,16 @@ pub fn execute_join_game(
 
     // Save updated game
     GAMES.save(deps.storage, game_id, &game)?;
+    WAITING_GAMES.remove(deps.storage, game_id);
+    ACTIVE_GAMES.save(deps.storage, game_id, &Empty {})?;
 
     Ok(Response::new()
         .add_attribute("action", "join_game")
         .add_attribute("game_id", game_id.to_string())
         .add_attribute("player", info.sender)
         .add_attribute("dealer", game.dealer)
-        .add_attribute("bet", bet))
+        .add_attribute("bet", bet)
+        .add_attribute("player_deck_hash", player_deck_hash.to_hex()))
 }
 
 pub fn execute_hit(
@@ -310,8 +395,8 @@ pub fn execute_hit(
     game_id: u64,
 ) -> Result<Response, ContractError> {
     no_funds(&info)?;
-    let config = CONFIG.load(deps.storage)?;
     let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
 
     // Verify sender is player
     if game.player != info.sender {
@@ -335,16 +420,9 @@ pub fn execute_hit(
         return Err(ContractError::Std(StdError::msg("Hand is not active")));
     }
 
-    // Block hitting on split aces when config disallows it
-    if !config.can_hit_split_aces && game.hands.len() > 1 {
-        if let Some(&first_card) = hand.cards.first() {
-            let rank = (first_card % 13) + 1;
-            if rank == 1 && hand.cards.len() == 2 {
-                return Err(ContractError::Std(StdError::msg(
-                    "Cannot hit on split aces",
-                )));
-            }
-        }
+    // Split aces only wait for the player when a second ace could be resplit
+    if !config.can_hit_split_aces && is_split_ace(hand) {
+        return Err(ContractError::HitNotAllowed {});
     }
 
     if game.last_card_index >= 52 {
@@ -374,8 +452,8 @@ pub fn execute_stand(
     game_id: u64,
 ) -> Result<Response, ContractError> {
     no_funds(&info)?;
-    let config = CONFIG.load(deps.storage)?;
     let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
     if game.player != info.sender {
         return Err(ContractError::Std(StdError::msg("Not authorized")));
     }
@@ -403,7 +481,14 @@ pub fn execute_stand(
 
     // If dealer peeked and all hands done, process_dealer_turn may have settled
     if matches!(game.status, GameStatus::Settled { .. }) {
-        response = super::reveal::execute_payouts(deps.storage, &game, &config, response)?;
+        response = super::reveal::execute_payouts(
+            deps.storage,
+            &game,
+            game_id,
+            &config,
+            env.block.height,
+            response,
+        )?;
     }
 
     GAMES.save(deps.storage, game_id, &game)?;
@@ -417,9 +502,9 @@ pub fn execute_double_down(
     info: MessageInfo,
     game_id: u64,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    only_denom(&info, &config.denom)?;
     let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
+    only_denom(&info, &config.denom)?;
 
     if game.player != info.sender {
         return Err(ContractError::Std(StdError::msg("Not authorized")));
@@ -428,18 +513,23 @@ pub fn execute_double_down(
         return Err(ContractError::Std(StdError::msg("Not player turn")));
     }
 
-    // Use blackjack package to validate all double rules (including restriction)
-    let rules = config_to_rules(&config);
-    let bj_state = to_blackjack_state(&game, rules);
-    if !bj_state.can_double_current_hand() {
-        return Err(ContractError::Std(StdError::msg("Double down not allowed")));
-    }
-
     // Player must send exact additional bet equal to original hand bet
     let hand_idx = game.current_hand_index as usize;
     if hand_idx >= game.hands.len() {
         return Err(ContractError::Std(StdError::msg("Invalid hand index")));
     }
+
+    // Doubling draws a card, which split aces can't take unless hits are allowed
+    if !config.can_hit_split_aces && is_split_ace(&game.hands[hand_idx]) {
+        return Err(ContractError::DoubleNotAllowed {});
+    }
+
+    // Use blackjack package to validate all double rules (including restriction)
+    let rules = config_to_rules(&config);
+    let bj_state = to_blackjack_state(&game, rules);
+    if !bj_state.can_double_current_hand() {
+        return Err(ContractError::DoubleNotAllowed {});
+    }
     let hand = &mut game.hands[hand_idx];
     let additional_bet = hand.bet;
     let deposited: Uint128 = info
@@ -491,7 +581,7 @@ pub fn execute_surrender(
 ) -> Result<Response, ContractError> {
     no_funds(&info)?;
     let mut game = GAMES.load(deps.storage, game_id)?;
-    let config = CONFIG.load(deps.storage)?;
+    let config = game_config(deps.storage, &game)?;
 
     if game.player != info.sender {
         return Err(ContractError::Std(StdError::msg("Not authorized")));
@@ -511,9 +601,10 @@ pub fn execute_surrender(
     let rules = config_to_rules(&config);
     let bj_state = to_blackjack_state(&game, rules);
     if !bj_state.can_surrender_current_hand() {
-        return Err(ContractError::Std(StdError::msg("Surrender not allowed")));
+        return Err(ContractError::SurrenderNotAllowed {});
     }
 
+    let insurance_bet = game.insurance_stake();
     let hand = &mut game.hands[game.current_hand_index as usize];
 
     // Settlement: return half the bet to player
@@ -523,7 +614,6 @@ pub fn execute_surrender(
         .map_err(|e| StdError::msg(e.to_string()))?;
 
     // Credit dealer: bankroll + player's bet + lost insurance - player's refund
-    let insurance_bet = game.insurance_bet.unwrap_or(Uint128::zero());
     let dealer_credit = game
         .bankroll
         .checked_add(hand.bet)
@@ -532,17 +622,19 @@ pub fn execute_surrender(
         .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?
         .checked_sub(refund_amount)
         .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-    let mut dealer_balance = DEALER_BALANCE.load(deps.storage)?;
-    dealer_balance = dealer_balance
-        .checked_add(dealer_credit)
-        .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-    DEALER_BALANCE.save(deps.storage, &dealer_balance)?;
 
     hand.status = HandStatus::Surrendered;
     game.current_turn = crate::state::TurnOwner::None;
     game.status = GameStatus::Settled {
         winner: "Surrendered".to_string(),
     };
+    credit_dealer_settlement(
+        deps.storage,
+        &game,
+        game_id,
+        dealer_credit,
+        env.block.height,
+    )?;
 
     game.last_action_timestamp = env.block.time.seconds();
     GAMES.save(deps.storage, game_id, &game)?;
@@ -571,9 +663,9 @@ pub fn execute_split(
     info: MessageInfo,
     game_id: u64,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    only_denom(&info, &config.denom)?;
     let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
+    only_denom(&info, &config.denom)?;
 
     if game.player != info.sender {
         return Err(ContractError::Std(StdError::msg("Not authorized")));
@@ -582,6 +674,22 @@ pub fn execute_split(
         return Err(ContractError::Std(StdError::msg("Not player turn")));
     }
 
+    let hand_index = game.current_hand_index as usize;
+    if hand_index >= game.hands.len() {
+        return Err(ContractError::Std(StdError::msg("Invalid hand index")));
+    }
+
+    // Aces get their own errors; the package check below would only say "not allowed"
+    let hand = &game.hands[hand_index];
+    if hand.cards.len() == 2 && hand.cards.iter().all(|&c| c % 13 == 0) {
+        if !config.can_split_aces {
+            return Err(ContractError::CannotSplitAces {});
+        }
+        if hand.split_depth > 0 && !config.resplit_aces {
+            return Err(ContractError::ResplitAcesNotAllowed {});
+        }
+    }
+
     // Use blackjack package to validate if split is allowed
     let rules = config_to_rules(&config);
     let bj_state = to_blackjack_state(&game, rules);
@@ -589,7 +697,6 @@ pub fn execute_split(
         return Err(ContractError::Std(StdError::msg("Split not allowed")));
     }
 
-    let hand_index = game.current_hand_index as usize;
     let original_bet = game.hands[hand_index].bet;
 
     // Player must send exact additional bet equal to original hand bet for the new split hand
@@ -610,12 +717,15 @@ pub fn execute_split(
         (hand.cards[0], hand.cards[1])
     };
 
-    // Split the hand
+    // Split the hand; both halves are one split deeper
+    let split_depth = game.hands[hand_index].split_depth + 1;
     game.hands[hand_index].cards = vec![card0];
+    game.hands[hand_index].split_depth = split_depth;
     game.hands.push(Hand {
         cards: vec![card1],
         bet: original_bet,
         status: HandStatus::Active,
+        split_depth,
     });
 
     // Request two cards, one for each hand
@@ -649,39 +759,38 @@ pub fn execute_insurance(
     info: MessageInfo,
     game_id: u64,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    only_denom(&info, &config.denom)?;
     let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
+    only_denom(&info, &config.denom)?;
 
     if game.player != info.sender {
         return Err(ContractError::Std(StdError::msg("Not authorized")));
     }
-    if game.status != GameStatus::OfferingInsurance {
-        return Err(ContractError::Std(StdError::msg(
-            "Insurance not being offered",
-        )));
-    }
+    let insurance = open_insurance(&game)?;
 
-    let insurance_amount = game
+    // Any stake up to half the original bet
+    let max_insurance = game
         .bet
         .checked_div(Uint128::new(2))
         .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
 
-    let deposited = info
+    let insurance_amount: Uint128 = info
         .funds
         .iter()
         .find(|c| c.denom == config.denom)
-        .map(|c| c.amount)
-        .unwrap_or(cosmwasm_std::Uint256::zero());
-
-    let required = cosmwasm_std::Uint256::from(insurance_amount);
-    if deposited != required {
+        .map(|c| Uint128::try_from(c.amount).unwrap_or(Uint128::MAX))
+        .unwrap_or(Uint128::zero());
+    if insurance_amount.is_zero() || insurance_amount > max_insurance {
         return Err(ContractError::Std(StdError::msg(format!(
-            "Must send exact insurance amount. Required: {required}, Got: {deposited}"
+            "Invalid insurance amount. Allowed: 1 to {max_insurance}, Got: {insurance_amount}"
         ))));
     }
 
-    game.insurance_bet = Some(insurance_amount);
+    game.insurance = Some(InsuranceBet {
+        accepted: true,
+        bet: insurance_amount,
+        ..insurance
+    });
     game.status = GameStatus::WaitingForReveal {
         reveal_requests: vec![3],
         next_status: Box::new(GameStatus::PlayerTurn),
@@ -695,6 +804,18 @@ pub fn execute_insurance(
         .add_attribute("insurance_amount", insurance_amount))
 }
 
+/// The insurance offer awaiting the player's answer.
+fn open_insurance(game: &GameSession) -> Result<InsuranceBet, ContractError> {
+    let insurance = game.insurance.clone().unwrap_or_default();
+    if insurance.resolved {
+        return Err(ContractError::InsuranceAlreadyResolved {});
+    }
+    if game.status != GameStatus::OfferingInsurance {
+        return Err(ContractError::InsuranceNotOffered {});
+    }
+    Ok(insurance)
+}
+
 pub fn execute_decline_insurance(
     deps: DepsMut,
     env: Env,
@@ -707,12 +828,12 @@ pub fn execute_decline_insurance(
     if game.player != info.sender {
         return Err(ContractError::Std(StdError::msg("Not authorized")));
     }
-    if game.status != GameStatus::OfferingInsurance {
-        return Err(ContractError::Std(StdError::msg(
-            "Insurance not being offered",
-        )));
-    }
+    let insurance = open_insurance(&game)?;
 
+    game.insurance = Some(InsuranceBet {
+        accepted: false,
+        ..insurance
+    });
     game.status = GameStatus::WaitingForReveal {
         reveal_requests: vec![3],
         next_status: Box::new(GameStatus::PlayerTurn),
@@ -726,7 +847,7 @@ pub fn execute_decline_insurance(
 }
 
 // Import from reveal module
-use super::reveal::execute_submit_reveal;
+use super::reveal::{execute_submit_batch_reveal, execute_submit_reveal};
 
 pub fn execute_cancel_game(
     deps: DepsMut,
@@ -756,6 +877,7 @@ pub fn execute_cancel_game(
     DEALER_BALANCE.save(deps.storage, &dealer_balance)?;
 
     GAMES.remove(deps.storage, game_id);
+    WAITING_GAMES.remove(deps.storage, game_id);
 
     Ok(Response::new()
         .add_attribute("action", "cancel_game")
@@ -770,11 +892,11 @@ pub fn execute_claim_timeout(
     game_id: u64,
 ) -> Result<Response, ContractError> {
     no_funds(&_info)?;
-    let config = CONFIG.load(deps.storage)?;
     let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
 
     // Reject if game is already settled
-    if matches!(game.status, GameStatus::Settled { .. }) {
+    if game.status.is_finished() {
         return Err(ContractError::Std(StdError::msg("Game is already settled")));
     }
 
@@ -788,54 +910,49 @@ pub fn execute_claim_timeout(
         ))));
     }
 
-    let total_bets: Uint128 = game.hands.iter().map(|h| h.bet).sum();
-    let insurance_bet = game.insurance_bet.unwrap_or(Uint128::zero());
-
-    // Determine who is blocking progress.
-    // During WaitingForReveal, both parties must submit — check pending_reveals to
-    // see who submitted more partials; the lagging party is the blocker.
-    let blocker = if matches!(&game.status, GameStatus::WaitingForReveal { .. }) {
-        let player_count = game
-            .pending_reveals
-            .iter()
-            .filter(|pr| pr.player_partial.is_some())
-            .count();
-        let dealer_count = game
-            .pending_reveals
-            .iter()
-            .filter(|pr| pr.dealer_partial.is_some())
-            .count();
-        if player_count > dealer_count {
-            crate::state::TurnOwner::Dealer // dealer is lagging
-        } else if dealer_count > player_count {
-            crate::state::TurnOwner::Player // player is lagging
-        } else {
-            game.current_turn.clone() // equal, fall back
-        }
+    let overflow = |e: cosmwasm_std::OverflowError| StdError::msg(e.to_string());
+    let total_bets = game
+        .hands
+        .iter()
+        .try_fold(Uint128::zero(), |sum, h| sum.checked_add(h.bet))
+        .map_err(overflow)?;
+    let insurance_bet = game.insurance_stake();
+    // Once the hole card has resolved insurance without a dealer blackjack, it's the dealer's
+    let insurance_resolved = game.insurance.as_ref().is_some_and(|ins| ins.resolved);
+    let insurance_refund = if insurance_resolved {
+        Uint128::zero()
     } else {
-        game.current_turn.clone()
+        insurance_bet
     };
 
-    let (player_payout, dealer_credit, winner) = match blocker {
+    let (player_payout, dealer_credit, winner) = match timeout_blocker(&game) {
         crate::state::TurnOwner::Player => {
             // Player failed to act, dealer wins: gets bankroll + all player bets + insurance
             let credit = game
                 .bankroll
                 .checked_add(total_bets)
-                .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?
+                .map_err(overflow)?
                 .checked_add(insurance_bet)
-                .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
+                .map_err(overflow)?;
             (Uint128::zero(), credit, "Dealer")
         }
         crate::state::TurnOwner::Dealer => {
-            // Dealer failed to act, player wins 2x bets + insurance back
+            // Dealer failed to act, player wins 2x bets + unresolved insurance back
             let payout = total_bets
                 .checked_mul(Uint128::new(2))
-                .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?
-                .checked_add(insurance_bet)
-                .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-            // Dealer gets back bankroll minus what player takes from it
-            let credit = game.bankroll.saturating_sub(total_bets);
+                .map_err(overflow)?
+                .checked_add(insurance_refund)
+                .map_err(overflow)?;
+            // Dealer gets back bankroll minus what player takes from it, plus lost insurance
+            let lost_insurance = insurance_bet
+                .checked_sub(insurance_refund)
+                .map_err(overflow)?;
+            let credit = game
+                .bankroll
+                .checked_sub(total_bets)
+                .map_err(overflow)?
+                .checked_add(lost_insurance)
+                .map_err(overflow)?;
             (payout, credit, "Player")
         }
         crate::state::TurnOwner::None => {
@@ -845,17 +962,17 @@ pub fn execute_claim_timeout(
         }
     };
 
-    // Credit dealer balance
-    let mut dealer_balance = DEALER_BALANCE.load(deps.storage)?;
-    dealer_balance = dealer_balance
-        .checked_add(dealer_credit)
-        .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-    DEALER_BALANCE.save(deps.storage, &dealer_balance)?;
-
     // Mark game as settled instead of removing
     game.status = GameStatus::Settled {
         winner: winner.to_string(),
     };
+    credit_dealer_settlement(
+        deps.storage,
+        &game,
+        game_id,
+        dealer_credit,
+        env.block.height,
+    )?;
     game.current_turn = crate::state::TurnOwner::None;
     game.last_action_timestamp = current_time;
     GAMES.save(deps.storage, game_id, &game)?;
@@ -898,7 +1015,7 @@ pub fn execute_sweep_settled(
             Some(g) => g,
             None => continue,
         };
-        if !matches!(game.status, GameStatus::Settled { .. }) {
+        if !game.status.is_finished() {
             continue;
         }
         if game
@@ -908,7 +1025,7 @@ pub fn execute_sweep_settled(
         {
             continue;
         }
-        GAMES.remove(deps.storage, *game_id);
+        remove_game(deps.storage, *game_id)?;
         removed += 1;
     }
 
@@ -917,6 +1034,101 @@ pub fn execute_sweep_settled(
         .add_attribute("removed", removed.to_string()))
 }
 
+/// Deletes a game and its reveal history. The player's `PLAYER_SETTLED_GAMES` entry stays.
+fn remove_game(storage: &mut dyn Storage, game_id: u64) -> StdResult<()> {
+    GAMES.remove(storage, game_id);
+    let revealed: Vec<u32> = REVEAL_HISTORY
+        .prefix(game_id)
+        .keys(storage, None, None, Order::Ascending)
+        .collect::<Result<_, _>>()?;
+    for card_index in revealed {
+        REVEAL_HISTORY.remove(storage, (game_id, card_index));
+    }
+    Ok(())
+}
+
+/// Games `BurnExpiredGames` reads per call, burnable or not, so a run of old games
+/// still in play can't push a call out of gas
+const BURN_SCAN_LIMIT: usize = 100;
+
+/// Deletes finished games whose last action is older than `retain_settled_seconds`,
+/// lowest id first. Game ids follow creation order, so the oldest games come first
+/// without a settlement-time index. Each deleted game gets a `game_burned` event
+/// with its final status and, from `PLAYER_SETTLED_GAMES`, the player's stake and
+/// payout, since `GetGame` stops answering for it.
+///
+/// When the call stops at `limit` or `BURN_SCAN_LIMIT` rather than at the end of the
+/// range, the last id it read is returned as `last_scanned`, to pass as the next
+/// call's `start_after`.
+pub fn execute_burn_expired_games(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    start_after: Option<u64>,
+    before_game_id: Option<u64>,
+    limit: u32,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+    if limit == 0 || limit > 50 {
+        return Err(ContractError::Std(StdError::msg(
+            "limit must be between 1 and 50",
+        )));
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let cutoff = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(config.retain_settled_seconds);
+
+    let mut expired = Vec::new();
+    let mut scanned = 0;
+    let mut last_scanned = None;
+    let start = start_after.map(cw_storage_plus::Bound::exclusive);
+    let end = before_game_id.map(cw_storage_plus::Bound::exclusive);
+    for item in GAMES
+        .range(deps.storage, start, end, Order::Ascending)
+        .take(BURN_SCAN_LIMIT)
+    {
+        let (game_id, game) = item?;
+        scanned += 1;
+        last_scanned = Some(game_id);
+        if game.status.is_finished() && game.last_action_timestamp <= cutoff {
+            expired.push((game_id, game));
+            if expired.len() == limit as usize {
+                break;
+            }
+        }
+    }
+
+    let stopped_early = expired.len() == limit as usize || scanned == BURN_SCAN_LIMIT;
+    let mut response = Response::new();
+    if let Some(game_id) = last_scanned.filter(|_| stopped_early) {
+        response = response.add_attribute("last_scanned", game_id.to_string());
+    }
+    for (game_id, game) in &expired {
+        let mut event = Event::new("game_burned")
+            .add_attribute("game_id", game_id.to_string())
+            .add_attribute("player", game.player.to_string())
+            .add_attribute("status", format!("{:?}", game.status));
+        if let Some(settled) =
+            PLAYER_SETTLED_GAMES.may_load(deps.storage, (&game.player, *game_id))?
+        {
+            event = event
+                .add_attribute("bet", settled.bet)
+                .add_attribute("payout", settled.payout)
+                .add_attribute("outcome", format!("{:?}", settled.outcome));
+        }
+        remove_game(deps.storage, *game_id)?;
+        response = response.add_event(event);
+    }
+
+    Ok(response
+        .add_attribute("action", "burn_expired_games")
+        .add_attribute("burned", expired.len().to_string()))
+}
+
 pub fn execute_deposit_bankroll(
     deps: DepsMut,
     info: MessageInfo,
@@ -974,7 +1186,7 @@ pub fn execute_withdraw_bankroll(
         .range(deps.storage, None, None, Order::Ascending)
         .any(|item| {
             if let Ok((_, g)) = item {
-                !matches!(g.status, GameStatus::Settled { .. })
+                !g.status.is_finished()
             } else {
                 false
             }
@@ -1015,3 +1227,440 @@ pub fn execute_withdraw_bankroll(
         .add_attribute("amount", withdraw_amount)
         .add_attribute("remaining", new_balance))
 }
+
+pub fn execute_claim_protocol_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    let config = CONFIG.load(deps.storage)?;
+    if config.protocol_fee_recipient.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let amount = PROTOCOL_FEES.may_load(deps.storage)?.unwrap_or_default();
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::msg(
+            "No protocol fees to claim",
+        )));
+    }
+    PROTOCOL_FEES.save(deps.storage, &Uint128::zero())?;
+
+    Ok(Response::new()
+        .add_message(cosmwasm_std::BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![cosmwasm_std::Coin {
+                denom: config.denom,
+                amount: amount.into(),
+            }],
+        })
+        .add_attribute("action", "claim_protocol_fee")
+        .add_attribute("recipient", info.sender)
+        .add_attribute("amount", amount))
+}
+
+fn only_dealer(deps: &DepsMut, info: &MessageInfo) -> Result<(), ContractError> {
+    if info.sender != DEALER.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+fn is_paused(deps: &DepsMut) -> StdResult<bool> {
+    Ok(PAUSED.may_load(deps.storage)?.unwrap_or(false))
+}
+
+fn not_paused(deps: &DepsMut) -> Result<(), ContractError> {
+    if is_paused(deps)? {
+        return Err(ContractError::ContractPaused {});
+    }
+    Ok(())
+}
+
+pub fn execute_add_to_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+
+    for address in &addresses {
+        let addr = deps.api.addr_validate(address)?;
+        WHITELIST.save(deps.storage, &addr, &Empty {})?;
+    }
+    WHITELIST_ENABLED.save(deps.storage, &true)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "add_to_whitelist")
+        .add_attribute("count", addresses.len().to_string()))
+}
+
+pub fn execute_remove_from_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+    addresses: Vec<String>,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+
+    for address in &addresses {
+        let addr = deps.api.addr_validate(address)?;
+        WHITELIST.remove(deps.storage, &addr);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "remove_from_whitelist")
+        .add_attribute("count", addresses.len().to_string()))
+}
+
+pub fn execute_clear_whitelist(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+
+    let entries: Vec<Addr> = WHITELIST
+        .keys(deps.storage, None, None, Order::Ascending)
+        .collect::<Result<_, _>>()?;
+    for addr in &entries {
+        WHITELIST.remove(deps.storage, addr);
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "clear_whitelist")
+        .add_attribute("removed", entries.len().to_string()))
+}
+
+/// Swap the shuffle and reveal verification keys, e.g. after a circuit fix.
+/// Games waiting for a player are allowed: the player's join is verified
+/// against the new shuffle key. Anything further along must settle first.
+pub fn execute_update_verification_keys(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    shuffle_vk_id: String,
+    reveal_vk_id: String,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+    active_games_indexed(deps.storage)?;
+
+    let in_progress = ACTIVE_GAMES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .count() as u32;
+    if in_progress > 0 {
+        return Err(ContractError::GamesInProgress { count: in_progress });
+    }
+
+    for vk_id in [&shuffle_vk_id, &reveal_vk_id] {
+        if !xion_zk_vkey_registered(deps.as_ref(), vk_id)? {
+            return Err(ContractError::VerificationKeyNotRegistered {
+                vk_id: vk_id.clone(),
+            });
+        }
+    }
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let old_shuffle_vk = std::mem::replace(&mut config.shuffle_vk_id, shuffle_vk_id);
+    let old_reveal_vk = std::mem::replace(&mut config.reveal_vk_id, reveal_vk_id);
+    config.vk_version += 1;
+    record_config_changes(
+        deps.storage,
+        &mut config,
+        &info.sender,
+        env.block.time.seconds(),
+        &[
+            (
+                "shuffle_vk_id",
+                old_shuffle_vk.clone(),
+                config.shuffle_vk_id.clone(),
+            ),
+            (
+                "reveal_vk_id",
+                old_reveal_vk.clone(),
+                config.reveal_vk_id.clone(),
+            ),
+        ],
+    )?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_verification_keys")
+        .add_attribute("old_shuffle_vk", old_shuffle_vk)
+        .add_attribute("new_shuffle_vk", &config.shuffle_vk_id)
+        .add_attribute("old_reveal_vk", old_reveal_vk)
+        .add_attribute("new_reveal_vk", &config.reveal_vk_id)
+        .add_attribute("vk_version", config.vk_version.to_string()))
+}
+
+/// Changes the terms new games are created under. Each game keeps the `terms` it was
+/// created with, so games already waiting or in play are unaffected.
+pub fn execute_update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    min_bet: Option<Uint128>,
+    max_bet: Option<Uint128>,
+    timeout_seconds: Option<u64>,
+    surrender_allowed: Option<bool>,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+
+    let mut config = CONFIG.load(deps.storage)?;
+    let old = GameTerms::from_config(&config);
+    config.min_bet = min_bet.unwrap_or(config.min_bet);
+    config.max_bet = max_bet.unwrap_or(config.max_bet);
+    config.timeout_seconds = timeout_seconds.unwrap_or(config.timeout_seconds);
+    config.surrender_allowed = surrender_allowed.unwrap_or(config.surrender_allowed);
+    validate_terms(config.min_bet, config.max_bet, config.timeout_seconds)?;
+
+    record_config_changes(
+        deps.storage,
+        &mut config,
+        &info.sender,
+        env.block.time.seconds(),
+        &[
+            (
+                "min_bet",
+                old.min_bet.to_string(),
+                config.min_bet.to_string(),
+            ),
+            (
+                "max_bet",
+                old.max_bet.to_string(),
+                config.max_bet.to_string(),
+            ),
+            (
+                "timeout_seconds",
+                old.timeout_seconds.to_string(),
+                config.timeout_seconds.to_string(),
+            ),
+            (
+                "surrender_allowed",
+                old.surrender_allowed.to_string(),
+                config.surrender_allowed.to_string(),
+            ),
+        ],
+    )?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_config")
+        .add_attribute("min_bet", config.min_bet)
+        .add_attribute("max_bet", config.max_bet)
+        .add_attribute("timeout_seconds", config.timeout_seconds.to_string())
+        .add_attribute("surrender_allowed", config.surrender_allowed.to_string())
+        .add_attribute("version", config.version.to_string()))
+}
+
+/// Stops or allows new games. Games already created play on and can still time out.
+pub fn execute_set_paused(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+    if !paused {
+        PAUSED_AT.remove(deps.storage);
+    } else if !is_paused(&deps)? {
+        // Pausing again keeps the original time
+        PAUSED_AT.save(deps.storage, &env.block.time.seconds())?;
+    }
+    PAUSED.save(deps.storage, &paused)?;
+
+    Ok(Response::new().add_attribute("action", if paused { "pause" } else { "unpause" }))
+}
+
+/// Voids joined games: the player gets back everything they staked (hand bets,
+/// including doubles and splits, plus insurance) and the game's locked bankroll
+/// returns to the dealer balance. Nothing is added to `HOUSE_STATS`. Games are taken
+/// from `ACTIVE_GAMES`, which refunded games leave, so calling again moves on to the
+/// next batch.
+pub fn execute_emergency_refund_all(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+    if !is_paused(&deps)? {
+        return Err(ContractError::ContractNotPaused {});
+    }
+    active_games_indexed(deps.storage)?;
+    let config = CONFIG.load(deps.storage)?;
+    let overflow = |e: cosmwasm_std::OverflowError| StdError::msg(e.to_string());
+
+    let game_ids: Vec<u64> = ACTIVE_GAMES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .take(EMERGENCY_REFUND_BATCH)
+        .collect::<StdResult<_>>()?;
+
+    let mut response = Response::new();
+    let mut returned_bankroll = Uint128::zero();
+    for game_id in game_ids {
+        let mut game = GAMES.load(deps.storage, game_id)?;
+        let amount = game
+            .hands
+            .iter()
+            .map(|h| h.bet)
+            .sum::<Uint128>()
+            .checked_add(game.insurance_stake())
+            .map_err(overflow)?;
+        returned_bankroll = returned_bankroll
+            .checked_add(game.bankroll)
+            .map_err(overflow)?;
+
+        game.status = GameStatus::EmergencyRefunded;
+        game.current_turn = TurnOwner::None;
+        game.pending_reveals.clear();
+        game.last_action_timestamp = env.block.time.seconds();
+        GAMES.save(deps.storage, game_id, &game)?;
+        ACTIVE_GAMES.remove(deps.storage, game_id);
+        PLAYER_SETTLED_GAMES.save(
+            deps.storage,
+            (&game.player, game_id),
+            &SettledGame {
+                bet: amount,
+                payout: amount,
+                outcome: GameOutcome::Refunded,
+                block_height: env.block.height,
+                forfeit_reason: None,
+            },
+        )?;
+
+        if !amount.is_zero() {
+            response = response.add_message(cosmwasm_std::BankMsg::Send {
+                to_address: game.player.to_string(),
+                amount: vec![cosmwasm_std::Coin {
+                    denom: config.denom.clone(),
+                    amount: amount.into(),
+                }],
+            });
+        }
+        response = response.add_event(
+            Event::new("emergency_refund")
+                .add_attribute("game_id", game_id.to_string())
+                .add_attribute("player", game.player.to_string())
+                .add_attribute("amount", amount),
+        );
+    }
+
+    let balance = DEALER_BALANCE
+        .load(deps.storage)?
+        .checked_add(returned_bankroll)
+        .map_err(overflow)?;
+    DEALER_BALANCE.save(deps.storage, &balance)?;
+
+    let refunded = response.events.len();
+    let remaining = count_pending_refunds(deps.storage)?;
+    Ok(response
+        .add_attribute("action", "emergency_refund_all")
+        .add_attribute("refunded", refunded.to_string())
+        .add_attribute("remaining", remaining.to_string()))
+}
+
+/// Continues the player stats rebuild a migration started. A no-op once it is done.
+pub fn execute_backfill_player_stats(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+    let stats_rebuilt = backfill_player_stats(deps.storage)?;
+    Ok(Response::new()
+        .add_attribute("action", "backfill_player_stats")
+        .add_attribute("stats_rebuilt", stats_rebuilt.to_string()))
+}
+
+/// Continues the `ACTIVE_GAMES` and `WAITING_GAMES` rebuild a migration started. A no-op once it is done.
+pub fn execute_backfill_active_games(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+    let active_games_rebuilt = backfill_active_games(deps.storage)?;
+    Ok(Response::new()
+        .add_attribute("action", "backfill_active_games")
+        .add_attribute("active_games_rebuilt", active_games_rebuilt.to_string()))
+}
+
+/// Lets the dealer give up a joined game it can't finish fairly, e.g. after deploying
+/// the wrong verification keys. The player gets their stake (hand bets plus insurance)
+/// and `max_win_amount` out of the game's bankroll; the rest of the bankroll returns
+/// to the dealer balance.
+pub fn execute_forfeit_game(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    reason: Option<String>,
+) -> Result<Response, ContractError> {
+    no_funds(&info)?;
+    only_dealer(&deps, &info)?;
+    if let Some(length) = reason.as_ref().map(|r| r.chars().count()) {
+        if length > MAX_FORFEIT_REASON_LEN {
+            return Err(ContractError::ForfeitReasonTooLong {
+                length,
+                max: MAX_FORFEIT_REASON_LEN,
+            });
+        }
+    }
+    let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
+    if !awaits_emergency_refund(&game) {
+        return Err(ContractError::Std(StdError::msg(
+            "Can only forfeit a game in progress",
+        )));
+    }
+
+    let overflow = |e: cosmwasm_std::OverflowError| StdError::msg(e.to_string());
+    let max_win = max_win_amount(&game, &config);
+    let amount_paid = game
+        .hands
+        .iter()
+        .map(|h| h.bet)
+        .sum::<Uint128>()
+        .checked_add(game.insurance_stake())
+        .and_then(|stake| stake.checked_add(max_win))
+        .map_err(overflow)?;
+    let dealer_credit = game.bankroll.checked_sub(max_win).map_err(overflow)?;
+
+    game.status = GameStatus::Forfeited {
+        reason: reason.clone(),
+    };
+    game.current_turn = TurnOwner::None;
+    game.pending_reveals.clear();
+    game.last_action_timestamp = env.block.time.seconds();
+    credit_dealer_settlement(
+        deps.storage,
+        &game,
+        game_id,
+        dealer_credit,
+        env.block.height,
+    )?;
+    GAMES.save(deps.storage, game_id, &game)?;
+
+    let mut response = Response::new()
+        .add_message(cosmwasm_std::BankMsg::Send {
+            to_address: game.player.to_string(),
+            amount: vec![cosmwasm_std::Coin {
+                denom: config.denom,
+                amount: amount_paid.into(),
+            }],
+        })
+        .add_attribute("action", "forfeit_game")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("game_forfeited", "true")
+        .add_attribute("amount_paid", amount_paid);
+    // Attribute values can't be empty
+    if let Some(reason) = reason.filter(|r| !r.trim().is_empty()) {
+        response = response.add_attribute("reason", reason);
+    }
+    Ok(response)
+}
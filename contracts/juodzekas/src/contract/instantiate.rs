@@ -1,12 +1,39 @@
 use crate::error::ContractError;
 use crate::msg::InstantiateMsg;
-use crate::state::{Config, CONFIG, DEALER, DEALER_BALANCE, GAME_COUNTER};
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdError};
+use crate::state::{
+    default_retain_settled_seconds, Config, CONFIG, DEALER, DEALER_BALANCE, GAME_COUNTER,
+    WHITELIST, WHITELIST_ENABLED,
+};
+use cosmwasm_std::{DepsMut, Empty, Env, MessageInfo, Response, StdError, Uint128};
 use cw2::set_contract_version;
 
-const CONTRACT_NAME: &str = "crates.io:juodzekas";
+pub(crate) const CONTRACT_NAME: &str = "crates.io:juodzekas";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// Bet limits and timeout checks shared by `instantiate` and `UpdateConfig`
+pub(crate) fn validate_terms(
+    min_bet: Uint128,
+    max_bet: Uint128,
+    timeout_seconds: u64,
+) -> Result<(), ContractError> {
+    if min_bet.is_zero() {
+        return Err(ContractError::Std(StdError::msg(
+            "min_bet must be greater than zero",
+        )));
+    }
+    if min_bet > max_bet {
+        return Err(ContractError::Std(StdError::msg(
+            "min_bet cannot exceed max_bet",
+        )));
+    }
+    if timeout_seconds == 0 {
+        return Err(ContractError::Std(StdError::msg(
+            "timeout_seconds must be greater than zero",
+        )));
+    }
+    Ok(())
+}
+
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
@@ -31,17 +58,8 @@ pub fn instantiate(
         )));
     }
 
-    // Validate bet limits
-    if msg.min_bet.is_zero() {
-        return Err(ContractError::Std(StdError::msg(
-            "min_bet must be greater than zero",
-        )));
-    }
-    if msg.min_bet > msg.max_bet {
-        return Err(ContractError::Std(StdError::msg(
-            "min_bet cannot exceed max_bet",
-        )));
-    }
+    let timeout = msg.timeout_seconds.unwrap_or(3600);
+    validate_terms(msg.min_bet, msg.max_bet, timeout)?;
 
     // With bankroll = 10 * max_bet, dealer timeout pays player 2*total_bets.
     // Max total_bets = (max_splits+1) * max_bet. Solvency requires max_splits <= 4.
@@ -51,10 +69,20 @@ pub fn instantiate(
         )));
     }
 
-    let timeout = msg.timeout_seconds.unwrap_or(3600);
-    if timeout == 0 {
+    if msg.protocol_fee_bps.is_some_and(|bps| bps > 10_000) {
         return Err(ContractError::Std(StdError::msg(
-            "timeout_seconds must be greater than zero",
+            "protocol_fee_bps cannot exceed 10000",
+        )));
+    }
+    let protocol_fee_recipient = msg
+        .protocol_fee_recipient
+        .as_deref()
+        .map(|addr| deps.api.addr_validate(addr))
+        .transpose()?;
+    let protocol_fee_bps = msg.protocol_fee_bps.unwrap_or(0);
+    if protocol_fee_bps > 0 && protocol_fee_recipient.is_none() {
+        return Err(ContractError::Std(StdError::msg(
+            "protocol_fee_recipient is required with a protocol fee",
         )));
     }
 
@@ -71,10 +99,20 @@ pub fn instantiate(
         max_splits: msg.max_splits,
         can_split_aces: msg.can_split_aces,
         can_hit_split_aces: msg.can_hit_split_aces,
+        double_after_split: msg.double_after_split.unwrap_or(true),
+        resplit_aces: msg.resplit_aces.unwrap_or(false),
+        debug_mode: msg.debug_mode.unwrap_or(false),
         surrender_allowed: msg.surrender_allowed,
         shuffle_vk_id: msg.shuffle_vk_id.clone(),
         reveal_vk_id: msg.reveal_vk_id.clone(),
         timeout_seconds: timeout,
+        retain_settled_seconds: msg
+            .retain_settled_seconds
+            .unwrap_or_else(default_retain_settled_seconds),
+        vk_version: 0,
+        version: 0,
+        protocol_fee_bps,
+        protocol_fee_recipient,
     };
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     CONFIG.save(deps.storage, &config)?;
@@ -83,6 +121,13 @@ pub fn instantiate(
     // Single-dealer: the instantiator is the dealer
     DEALER.save(deps.storage, &info.sender)?;
 
+    let whitelist = msg.whitelist.unwrap_or_default();
+    for address in &whitelist {
+        let addr = deps.api.addr_validate(address)?;
+        WHITELIST.save(deps.storage, &addr, &Empty {})?;
+    }
+    WHITELIST_ENABLED.save(deps.storage, &!whitelist.is_empty())?;
+
     // Extract initial bankroll from sent funds
     let initial_balance = info
         .funds
@@ -98,5 +143,7 @@ pub fn instantiate(
         .add_attribute("denom", msg.denom)
         .add_attribute("min_bet", msg.min_bet)
         .add_attribute("max_bet", msg.max_bet)
-        .add_attribute("initial_balance", initial_balance))
+        .add_attribute("initial_balance", initial_balance)
+        .add_attribute("protocol_fee_bps", protocol_fee_bps.to_string())
+        .add_attribute("whitelist_size", whitelist.len().to_string()))
 }
@@ -0,0 +1,81 @@
+use crate::contract::instantiate::CONTRACT_NAME;
+use crate::contract::{backfill_active_games, backfill_player_stats};
+use crate::error::ContractError;
+use crate::msg::MigrateMsg;
+use crate::state::{
+    player_stats, ActiveGamesBackfill, StatsBackfill, ACTIVE_GAMES, ACTIVE_GAMES_BACKFILL, CONFIG,
+    HOUSE_STATS, PAUSED, STATS_BACKFILL, WAITING_GAMES, WHITELIST_ENABLED,
+};
+use cosmwasm_std::{DepsMut, Env, Order, Response, StdError};
+use cw2::{get_contract_version, set_contract_version};
+
+/// Brings storage written by any earlier version up to this code and records
+/// `new_version`. Every step only fills in what is missing, so running it again
+/// leaves the state unchanged.
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::Std(StdError::msg(format!(
+            "Cannot migrate from {} to {CONTRACT_NAME}",
+            stored.contract
+        ))));
+    }
+    if msg.new_version.is_empty() {
+        return Err(ContractError::Std(StdError::msg(
+            "new_version must not be empty",
+        )));
+    }
+
+    // Loading fills fields added since with their serde defaults; saving stores them
+    let config = CONFIG.load(deps.storage)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    // Games are left as stored: fields added since load with their serde defaults.
+    // Versions before ACTIVE_GAMES and WAITING_GAMES never indexed the open games; find
+    // them a batch here and the rest through `BackfillActiveGames`
+    let unindexed = ACTIVE_GAMES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .chain(WAITING_GAMES.keys(deps.storage, None, None, Order::Ascending))
+        .next()
+        .is_none();
+    if unindexed && !ACTIVE_GAMES_BACKFILL.exists(deps.storage) {
+        ACTIVE_GAMES_BACKFILL.save(deps.storage, &ActiveGamesBackfill { last: None })?;
+    }
+    let active_games_rebuilt = backfill_active_games(deps.storage)?;
+
+    // Items older versions never wrote
+    if HOUSE_STATS.may_load(deps.storage)?.is_none() {
+        HOUSE_STATS.save(deps.storage, &Default::default())?;
+    }
+    if PAUSED.may_load(deps.storage)?.is_none() {
+        PAUSED.save(deps.storage, &false)?;
+    }
+    if WHITELIST_ENABLED.may_load(deps.storage)?.is_none() {
+        WHITELIST_ENABLED.save(deps.storage, &false)?;
+    }
+    // Player stats start out empty; rebuild them from the settled games kept so far,
+    // a batch here and the rest through `BackfillPlayerStats`
+    let no_stats = player_stats()
+        .keys(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_none();
+    if no_stats && STATS_BACKFILL.may_load(deps.storage)?.is_none() {
+        STATS_BACKFILL.save(
+            deps.storage,
+            &StatsBackfill {
+                before_height: env.block.height,
+                last: None,
+            },
+        )?;
+    }
+    let stats_rebuilt = backfill_player_stats(deps.storage)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, &msg.new_version)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("migrated_from", stored.version)
+        .add_attribute("migrated_to", msg.new_version)
+        .add_attribute("active_games_rebuilt", active_games_rebuilt.to_string())
+        .add_attribute("stats_rebuilt", stats_rebuilt.to_string()))
+}
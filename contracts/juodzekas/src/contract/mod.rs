@@ -1,5 +1,6 @@
 pub mod execute;
 pub mod instantiate;
+pub mod migrate;
 pub mod query;
 pub mod reveal;
 
@@ -8,11 +9,336 @@ pub mod reveal;
 
 pub use crate::contract::execute::execute;
 pub use crate::contract::instantiate::instantiate;
+pub use crate::contract::migrate::migrate;
 pub use crate::contract::query::query;
 
+use crate::error::ContractError;
+use crate::state::{
+    player_stats, Config, ConfigChange, GameOutcome, GameSession, GameStatus, Hand, HandStatus,
+    SettledGame, TurnOwner, ACTIVE_GAMES, ACTIVE_GAMES_BACKFILL, CONFIG, CONFIG_HISTORY,
+    CONFIG_HISTORY_LIMIT, DEALER_BALANCE, GAMES, HOUSE_STATS, PLAYER_SETTLED_GAMES, PROTOCOL_FEES,
+    STATS_BACKFILL, WAITING_GAMES, WHITELIST, WHITELIST_ENABLED,
+};
+use cosmwasm_std::{Addr, Binary, Int128, Order, StdError, StdResult, Storage, Uint128};
+use sha2::{Digest, Sha256};
+
+/// Fingerprint of a submitted deck: SHA-256 over the ciphertexts concatenated
+/// in order. Stored on-chain so neither side can later claim a different deck.
+pub fn deck_hash(deck: &[Binary]) -> Binary {
+    let mut hasher = Sha256::new();
+    for card in deck {
+        hasher.update(card.as_slice());
+    }
+    Binary::from(hasher.finalize().as_slice())
+}
+
+/// True if `player` may join games: no whitelist is enforced, or they are on it.
+pub fn is_whitelisted(storage: &dyn Storage, player: &Addr) -> StdResult<bool> {
+    if !WHITELIST_ENABLED.may_load(storage)?.unwrap_or(false) {
+        return Ok(true);
+    }
+    Ok(WHITELIST.has(storage, player))
+}
+
+/// The party holding up the game, who loses it if a timeout is claimed.
+/// During WaitingForReveal both parties must submit, so whoever has submitted
+/// fewer partials is lagging; on a tie it falls back to `current_turn`.
+pub fn timeout_blocker(game: &GameSession) -> TurnOwner {
+    if !matches!(game.status, GameStatus::WaitingForReveal { .. }) {
+        return game.current_turn.clone();
+    }
+    let player_count = game
+        .pending_reveals
+        .iter()
+        .filter(|pr| pr.player_partial.is_some())
+        .count();
+    let dealer_count = game
+        .pending_reveals
+        .iter()
+        .filter(|pr| pr.dealer_partial.is_some())
+        .count();
+    match player_count.cmp(&dealer_count) {
+        std::cmp::Ordering::Greater => TurnOwner::Dealer,
+        std::cmp::Ordering::Less => TurnOwner::Player,
+        std::cmp::Ordering::Equal => game.current_turn.clone(),
+    }
+}
+
+/// Joined, unsettled games hold the player's stake until `EmergencyRefundAll` returns it.
+pub fn awaits_emergency_refund(game: &GameSession) -> bool {
+    game.status != GameStatus::WaitingForPlayerJoin && !game.status.is_finished()
+}
+
+/// Games `backfill_active_games` reads per call
+pub const ACTIVE_GAMES_BACKFILL_BATCH: usize = 200;
+
+/// Indexes the next `ACTIVE_GAMES_BACKFILL_BATCH` games of an `ACTIVE_GAMES_BACKFILL`
+/// in progress: those awaiting an emergency refund in `ACTIVE_GAMES`, those waiting
+/// for a player in `WAITING_GAMES`. Returns whether the rebuild is done, removing
+/// `ACTIVE_GAMES_BACKFILL` once it is.
+pub fn backfill_active_games(storage: &mut dyn Storage) -> StdResult<bool> {
+    let Some(mut backfill) = ACTIVE_GAMES_BACKFILL.may_load(storage)? else {
+        return Ok(true);
+    };
+    let start = backfill.last.map(cw_storage_plus::Bound::exclusive);
+    let games: Vec<(u64, GameSession)> = GAMES
+        .range(storage, start, None, Order::Ascending)
+        .take(ACTIVE_GAMES_BACKFILL_BATCH)
+        .collect::<StdResult<_>>()?;
+    // Games joined or settled since the migration already keep their entries up to date
+    for (game_id, game) in &games {
+        if awaits_emergency_refund(game) {
+            ACTIVE_GAMES.save(storage, *game_id, &cosmwasm_std::Empty {})?;
+        } else if game.status == GameStatus::WaitingForPlayerJoin {
+            WAITING_GAMES.save(storage, *game_id, &cosmwasm_std::Empty {})?;
+        }
+    }
+
+    if games.len() < ACTIVE_GAMES_BACKFILL_BATCH {
+        ACTIVE_GAMES_BACKFILL.remove(storage);
+        return Ok(true);
+    }
+    backfill.last = games.last().map(|(game_id, _)| *game_id);
+    ACTIVE_GAMES_BACKFILL.save(storage, &backfill)?;
+    Ok(false)
+}
+
+/// Fails while `ACTIVE_GAMES` may still be missing games a migration hasn't indexed yet
+pub fn active_games_indexed(storage: &dyn Storage) -> Result<(), ContractError> {
+    if ACTIVE_GAMES_BACKFILL.exists(storage) {
+        return Err(ContractError::ActiveGamesBackfillPending {});
+    }
+    Ok(())
+}
+
+/// Joined games not yet finished, which `EmergencyRefundAll` would refund
+pub fn count_pending_refunds(storage: &dyn Storage) -> StdResult<u32> {
+    let mut pending = 0u32;
+    for game_id in ACTIVE_GAMES.keys(storage, None, None, Order::Ascending) {
+        game_id?;
+        pending += 1;
+    }
+    Ok(pending)
+}
+
+/// The most the player could still win on `game` beyond their stake: each hand at the
+/// better of `blackjack_payout` and `standard_payout`, plus accepted insurance at
+/// `insurance_payout`. Never more than the bankroll locked for the game.
+pub fn max_win_amount(game: &GameSession, config: &Config) -> Uint128 {
+    let hands: Uint128 = game
+        .hands
+        .iter()
+        .map(|h| {
+            config
+                .blackjack_payout
+                .calculate_payout(h.bet)
+                .max(config.standard_payout.calculate_payout(h.bet))
+        })
+        .sum();
+    let insurance = config
+        .insurance_payout
+        .calculate_payout(game.insurance_stake());
+    hands.saturating_add(insurance).min(game.bankroll)
+}
+
+/// The config `game` plays under: the live config with the game's own `terms`, so an
+/// `UpdateConfig` after the game was created doesn't reach it.
+pub fn game_config(storage: &dyn Storage, game: &GameSession) -> StdResult<Config> {
+    let mut config = CONFIG.load(storage)?;
+    if let Some(terms) = &game.terms {
+        config.min_bet = terms.min_bet;
+        config.max_bet = terms.max_bet;
+        config.timeout_seconds = terms.timeout_seconds;
+        config.surrender_allowed = terms.surrender_allowed;
+    }
+    Ok(config)
+}
+
+/// Bumps `config.version` and appends one `CONFIG_HISTORY` entry per field whose
+/// value differs. `changes` holds `(field, old_value, new_value)`; the caller saves `config`.
+pub fn record_config_changes(
+    storage: &mut dyn Storage,
+    config: &mut Config,
+    changed_by: &Addr,
+    timestamp: u64,
+    changes: &[(&str, String, String)],
+) -> StdResult<()> {
+    config.version += 1;
+    let mut history = CONFIG_HISTORY.may_load(storage)?.unwrap_or_default();
+    history.extend(
+        changes
+            .iter()
+            .filter(|(_, old, new)| old != new)
+            .map(|(field, old, new)| ConfigChange {
+                version: config.version,
+                changed_by: changed_by.clone(),
+                timestamp,
+                field: field.to_string(),
+                old_value: old.clone(),
+                new_value: new.clone(),
+            }),
+    );
+    let overflow = history.len().saturating_sub(CONFIG_HISTORY_LIMIT);
+    history.drain(..overflow);
+    CONFIG_HISTORY.save(storage, &history)
+}
+
+/// Pays a settling game's `dealer_credit` (its bankroll plus whatever the dealer
+/// won from it) into the dealer balance, less the protocol fee on any profit, adds
+/// the game to `HOUSE_STATS` and records it in the player's `PLAYER_SETTLED_GAMES`
+/// and stats, and drops it from `ACTIVE_GAMES`. `game.status` must already
+/// be settled.
+pub fn credit_dealer_settlement(
+    storage: &mut dyn Storage,
+    game: &GameSession,
+    game_id: u64,
+    dealer_credit: Uint128,
+    block_height: u64,
+) -> StdResult<()> {
+    let overflow = |e: cosmwasm_std::OverflowError| StdError::msg(e.to_string());
+    // Taken from the profit only, so the dealer always gets its bankroll back
+    let fee = dealer_credit
+        .saturating_sub(game.bankroll)
+        .multiply_ratio(CONFIG.load(storage)?.protocol_fee_bps, 10_000u128);
+    if !fee.is_zero() {
+        let fees = PROTOCOL_FEES
+            .may_load(storage)?
+            .unwrap_or_default()
+            .checked_add(fee)
+            .map_err(overflow)?;
+        PROTOCOL_FEES.save(storage, &fees)?;
+    }
+    let dealer_kept = dealer_credit - fee;
+    let balance = DEALER_BALANCE
+        .load(storage)?
+        .checked_add(dealer_kept)
+        .map_err(overflow)?;
+    DEALER_BALANCE.save(storage, &balance)?;
+
+    let wagered: Uint128 = game.hands.iter().map(|h| h.bet).sum();
+    let as_signed =
+        |amount: Uint128| Int128::try_from(amount).map_err(|e| StdError::msg(e.to_string()));
+    let profit = as_signed(dealer_kept)?
+        .checked_sub(as_signed(game.bankroll)?)
+        .map_err(overflow)?;
+
+    let mut stats = HOUSE_STATS.may_load(storage)?.unwrap_or_default();
+    stats.total_volume = stats
+        .total_volume
+        .checked_add(wagered)
+        .and_then(|v| v.checked_add(game.insurance_stake()))
+        .map_err(overflow)?;
+    stats.house_profit = stats.house_profit.checked_add(profit).map_err(overflow)?;
+    HOUSE_STATS.save(storage, &stats)?;
+
+    // What the dealer keeps beyond its bankroll came out of the player's stake
+    let bet = wagered
+        .checked_add(game.insurance_stake())
+        .map_err(overflow)?;
+    let payout = bet
+        .checked_add(game.bankroll)
+        .and_then(|v| v.checked_sub(dealer_credit))
+        .map_err(overflow)?;
+    let settled = SettledGame {
+        bet,
+        payout,
+        outcome: settled_outcome(game, payout.cmp(&bet)),
+        block_height,
+        forfeit_reason: match &game.status {
+            GameStatus::Forfeited { reason } => reason.clone(),
+            _ => None,
+        },
+    };
+    PLAYER_SETTLED_GAMES.save(storage, (&game.player, game_id), &settled)?;
+    ACTIVE_GAMES.remove(storage, game_id);
+    record_player_stats(storage, &game.player, &settled)
+}
+
+/// Adds a settled game to the player's stats.
+pub fn record_player_stats(
+    storage: &mut dyn Storage,
+    player: &Addr,
+    game: &SettledGame,
+) -> StdResult<()> {
+    let stats = player_stats();
+    let mut totals = stats.may_load(storage, player)?.unwrap_or_default();
+    totals.record(game)?;
+    stats.save(storage, player, &totals)
+}
+
+/// Settled games `backfill_player_stats` reads per call
+pub const STATS_BACKFILL_BATCH: usize = 200;
+
+/// Counts the next `STATS_BACKFILL_BATCH` settled games of a `STATS_BACKFILL` in
+/// progress into the player stats. Returns whether the rebuild is done, removing
+/// `STATS_BACKFILL` once it is.
+pub fn backfill_player_stats(storage: &mut dyn Storage) -> StdResult<bool> {
+    let Some(mut backfill) = STATS_BACKFILL.may_load(storage)? else {
+        return Ok(true);
+    };
+    let start = backfill
+        .last
+        .as_ref()
+        .map(|(player, game_id)| cw_storage_plus::Bound::exclusive((player, *game_id)));
+    let settled: Vec<((Addr, u64), SettledGame)> = PLAYER_SETTLED_GAMES
+        .range(storage, start, None, Order::Ascending)
+        .take(STATS_BACKFILL_BATCH)
+        .collect::<StdResult<_>>()?;
+    for ((player, _), game) in &settled {
+        if game.block_height < backfill.before_height {
+            record_player_stats(storage, player, game)?;
+        }
+    }
+
+    if settled.len() < STATS_BACKFILL_BATCH {
+        STATS_BACKFILL.remove(storage);
+        return Ok(true);
+    }
+    backfill.last = settled.last().map(|(key, _)| key.clone());
+    STATS_BACKFILL.save(storage, &backfill)?;
+    Ok(false)
+}
+
+/// Outcome of a settled game from its final status and whether the player came out
+/// ahead, even or behind.
+fn settled_outcome(game: &GameSession, net: std::cmp::Ordering) -> GameOutcome {
+    use std::cmp::Ordering;
+    if matches!(game.status, GameStatus::Forfeited { .. }) {
+        return GameOutcome::Forfeit;
+    }
+    if matches!(&game.status, GameStatus::Settled { winner } if winner == "Surrendered") {
+        return GameOutcome::Surrender;
+    }
+    let natural = game.hands.iter().any(
+        |h| matches!(&h.status, HandStatus::Settled { winner } if winner == "Player (Blackjack)"),
+    );
+    match net {
+        Ordering::Greater if natural => GameOutcome::Blackjack,
+        Ordering::Greater => GameOutcome::Win,
+        Ordering::Equal => GameOutcome::Push,
+        Ordering::Less => GameOutcome::Loss,
+    }
+}
+
+/// A hand that came from splitting aces: one card each unless `can_hit_split_aces`.
+pub fn is_split_ace(hand: &Hand) -> bool {
+    hand.split_depth > 0 && hand.cards.first().is_some_and(|&c| c % 13 == 0)
+}
+
 /// Calculates the Blackjack score for a hand.
 /// Handles Aces as 1 or 11 to maximize the score without busting.
 pub fn calculate_score(hand: &[u8]) -> u8 {
+    let (score, _) = score_with_soft_aces(hand);
+    score.min(u8::MAX as u16) as u8
+}
+
+/// True if the best score counts an Ace as 11 (e.g. A+6 is soft 17).
+pub fn is_soft_hand(hand: &[u8]) -> bool {
+    score_with_soft_aces(hand).1 > 0
+}
+
+/// Best score, and how many Aces in it still count as 11.
+fn score_with_soft_aces(hand: &[u8]) -> (u16, u16) {
     let mut score: u16 = 0;
     let mut aces: u16 = 0;
     for &card in hand {
@@ -30,5 +356,5 @@ pub fn calculate_score(hand: &[u8]) -> u8 {
         score -= 10;
         aces -= 1;
     }
-    score.min(u8::MAX as u16) as u8
+    (score, aces)
 }
@@ -1,21 +1,111 @@
+use crate::contract::{
+    calculate_score, count_pending_refunds, game_config, is_soft_hand, is_whitelisted,
+    timeout_blocker,
+};
+use crate::error::ContractError;
+use crate::game_logic::{card_value_to_card, config_to_rules, to_blackjack_state};
 use crate::msg::{
-    DealerBalanceResponse, DealerResponse, GameListItem, GameResponse, PendingRevealResponse,
-    QueryMsg,
+    AggregatedKeyResponse, CompletedReveal, ContractStateResponse, DealerBalanceResponse,
+    DealerDashboardResponse, DealerResponse, GameListItem, GameResponse, GameSummary,
+    GamesPageResponse, HandValueResponse, InsuranceBetResponse, OptimalActionResponse,
+    PauseStatusResponse, PendingReveal, PendingRevealResponse, PlayerStatsResponse,
+    ProtocolFeesResponse, QueryMsg, RevealRecord, RevealRequestsResponse, RevealStatusResponse,
+    SettledGameSummary, SettledGamesResponse, TimeoutStatusResponse, TopPlayersSort,
+};
+use crate::state::{
+    player_stats, Config, GameSession, GameStatus, HandStatus, PlayerStats, TurnOwner,
+    ACTIVE_GAMES, CONFIG, CONFIG_HISTORY, DEALER, DEALER_BALANCE, GAMES, GAME_COUNTER, HOUSE_STATS,
+    PAUSED, PAUSED_AT, PLAYER_SETTLED_GAMES, PROTOCOL_FEES, REVEAL_HISTORY, WAITING_GAMES,
 };
-use crate::state::{Config, CONFIG, DEALER, DEALER_BALANCE, GAMES};
-use cosmwasm_std::{to_json_binary, Binary, Deps, Env, Order, StdResult};
+use crate::zk::aggregate_public_keys;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, Env, Int128, Order, StdError, StdResult, Uint128,
+};
+
+/// Unsettled games listed in `GetDealerDashboard`
+const DASHBOARD_ACTIVE_GAMES: usize = 20;
 
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetConfig {} => to_json_binary(&query_config(deps)?),
+        QueryMsg::GetConfigHistory {} => {
+            to_json_binary(&CONFIG_HISTORY.may_load(deps.storage)?.unwrap_or_default())
+        }
         QueryMsg::GetGame { game_id } => to_json_binary(&query_game(deps, game_id)?),
         QueryMsg::ListGames {
             status_filter,
             limit,
             start_after,
         } => to_json_binary(&query_list_games(deps, status_filter, limit, start_after)?),
+        QueryMsg::GetGamesByStatus {
+            status_filter,
+            start_after,
+            limit,
+        } => to_json_binary(&query_games_by_status(
+            deps,
+            status_filter,
+            start_after,
+            limit,
+        )?),
         QueryMsg::GetDealerBalance {} => to_json_binary(&query_dealer_balance(deps)?),
         QueryMsg::GetDealer {} => to_json_binary(&query_dealer(deps)?),
+        QueryMsg::GetRevealHistory { game_id } => {
+            to_json_binary(&query_reveal_history(deps, game_id)?)
+        }
+        QueryMsg::GetRevealRequests { game_id } => {
+            to_json_binary(&query_reveal_requests(deps, game_id)?)
+        }
+        QueryMsg::GetRevealStatus { game_id } => {
+            to_json_binary(&query_reveal_status(deps, game_id)?)
+        }
+        QueryMsg::GetHandValue {
+            game_id,
+            hand_index,
+        } => to_json_binary(&query_hand_value(deps, game_id, hand_index)?),
+        QueryMsg::CalculateOptimalAction {
+            game_id,
+            hand_index,
+        } => to_json_binary(&query_optimal_action(deps, game_id, hand_index)?),
+        QueryMsg::IsWhitelisted { player } => {
+            let player = deps.api.addr_validate(&player)?;
+            to_json_binary(&is_whitelisted(deps.storage, &player)?)
+        }
+        QueryMsg::GetTimeoutStatus { game_id } => {
+            to_json_binary(&query_timeout_status(deps, env, game_id)?)
+        }
+        QueryMsg::GetDealerDashboard {} => to_json_binary(&query_dealer_dashboard(deps, env)?),
+        QueryMsg::GetAggregatedPublicKey { game_id } => {
+            to_json_binary(&query_aggregated_public_key(deps, game_id)?)
+        }
+        QueryMsg::GetPendingRefunds {} => to_json_binary(&count_pending_refunds(deps.storage)?),
+        QueryMsg::GetProtocolFees {} => to_json_binary(&query_protocol_fees(deps)?),
+        QueryMsg::GetPauseStatus {} => to_json_binary(&query_pause_status(deps)?),
+        QueryMsg::GetSettledGamesForPlayer {
+            player,
+            start_after,
+            limit,
+        } => {
+            let player = deps.api.addr_validate(&player)?;
+            to_json_binary(&query_settled_games_for_player(
+                deps,
+                &player,
+                start_after,
+                limit,
+            )?)
+        }
+        QueryMsg::GetPlayerStats { player } => {
+            let player = deps.api.addr_validate(&player)?;
+            let stats = player_stats()
+                .may_load(deps.storage, &player)?
+                .unwrap_or_default();
+            to_json_binary(&player_stats_response(&player, stats))
+        }
+        QueryMsg::GetTopPlayers { by, limit } => {
+            to_json_binary(&query_top_players(deps, by, limit)?)
+        }
+        QueryMsg::GetContractState { game_id } => {
+            to_json_binary(&query_contract_state(deps, game_id)?)
+        }
     }
 }
 
@@ -34,6 +124,7 @@ fn query_game(deps: Deps, game_id: u64) -> StdResult<GameResponse> {
             cards: h.cards,
             bet: h.bet,
             status: format!("{:?}", h.status),
+            split_depth: h.split_depth,
         })
         .collect();
 
@@ -58,10 +149,59 @@ fn query_game(deps: Deps, game_id: u64) -> StdResult<GameResponse> {
         dealer_pubkey: game.dealer_pubkey,
         deck: game.deck,
         player_shuffled_deck: game.player_shuffled_deck,
+        deck_hash: game.deck_hash,
+        player_deck_hash: game.player_deck_hash,
+        insurance: game.insurance.map(|ins| InsuranceBetResponse {
+            offered: ins.offered,
+            accepted: ins.accepted,
+            bet: ins.bet,
+            resolved: ins.resolved,
+        }),
         pending_reveals,
+        last_action_timestamp: game.last_action_timestamp,
+        terms: game.terms,
+    })
+}
+
+/// Games `GetGamesByStatus` reads per call, matching or not, so a filter few games
+/// match can't push the query past the gas limit
+const GAMES_SCAN_LIMIT: usize = 500;
+
+/// The game as a list item, if its status contains `status_filter`
+fn game_list_item(
+    game_id: u64,
+    game: &GameSession,
+    status_filter: Option<&str>,
+) -> Option<GameListItem> {
+    let status_str = format!("{:?}", game.status);
+    if status_filter.is_some_and(|filter| !status_str.contains(filter)) {
+        return None;
+    }
+    Some(GameListItem {
+        game_id,
+        dealer: game.dealer.to_string(),
+        player: game.player.to_string(),
+        status: status_str,
+        bet: game.bet,
     })
 }
 
+/// Games after `start_after` by ascending id, keeping those whose status contains
+/// `status_filter`
+fn game_list_items<'a>(
+    deps: Deps<'a>,
+    status_filter: Option<String>,
+    start_after: Option<u64>,
+) -> impl Iterator<Item = StdResult<GameListItem>> + 'a {
+    let start = start_after.map(cw_storage_plus::Bound::exclusive);
+    GAMES
+        .range(deps.storage, start, None, Order::Ascending)
+        .filter_map(move |item| match item {
+            Ok((game_id, game)) => game_list_item(game_id, &game, status_filter.as_deref()).map(Ok),
+            Err(e) => Some(Err(e)),
+        })
+}
+
 fn query_list_games(
     deps: Deps,
     status_filter: Option<String>,
@@ -69,32 +209,131 @@ fn query_list_games(
     start_after: Option<u64>,
 ) -> StdResult<Vec<GameListItem>> {
     let max_limit = limit.unwrap_or(30).min(100) as usize;
+    game_list_items(deps, status_filter, start_after)
+        .take(max_limit)
+        .collect()
+}
+
+fn query_games_by_status(
+    deps: Deps,
+    status_filter: Option<String>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<GamesPageResponse> {
+    let limit = limit.unwrap_or(20).clamp(1, 100) as usize;
     let start = start_after.map(cw_storage_plus::Bound::exclusive);
 
-    let games: Result<Vec<GameListItem>, _> = GAMES
-        .range(deps.storage, start, None, Order::Ascending)
-        .filter_map(|item| match item {
-            Ok((game_id, game)) => {
-                let status_str = format!("{:?}", game.status);
-                if let Some(ref filter) = status_filter {
-                    if !status_str.contains(filter) {
-                        return None;
-                    }
-                }
-                Some(Ok(GameListItem {
-                    game_id,
-                    dealer: game.dealer.to_string(),
-                    player: game.player.to_string(),
-                    status: status_str,
-                    bet: game.bet,
-                }))
+    let mut games: Vec<GameListItem> = Vec::new();
+    let mut scanned = 0;
+    let mut last_scanned = None;
+    for item in GAMES.range(deps.storage, start, None, Order::Ascending) {
+        let (game_id, game) = item?;
+        // Out of reads with games left: carry on after the last one read
+        if scanned == GAMES_SCAN_LIMIT {
+            return Ok(GamesPageResponse {
+                games,
+                next_cursor: last_scanned,
+            });
+        }
+        scanned += 1;
+        if let Some(listed) = game_list_item(game_id, &game, status_filter.as_deref()) {
+            // A full page and another match: carry on after the page
+            if games.len() == limit {
+                return Ok(GamesPageResponse {
+                    next_cursor: games.last().map(|g| g.game_id),
+                    games,
+                });
             }
-            Err(e) => Some(Err(e)),
+            games.push(listed);
+        }
+        last_scanned = Some(game_id);
+    }
+    Ok(GamesPageResponse {
+        games,
+        next_cursor: None,
+    })
+}
+
+fn query_settled_games_for_player(
+    deps: Deps,
+    player: &cosmwasm_std::Addr,
+    start_after: Option<u64>,
+    limit: u32,
+) -> StdResult<SettledGamesResponse> {
+    let limit = limit.min(100) as usize;
+    let start = start_after.map(cw_storage_plus::Bound::exclusive);
+    let as_signed =
+        |amount: Uint128| Int128::try_from(amount).map_err(|e| StdError::msg(e.to_string()));
+
+    // Take one extra to tell whether another page follows
+    let mut games = PLAYER_SETTLED_GAMES
+        .prefix(player)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit + 1)
+        .map(|item| {
+            let (game_id, settled) = item?;
+            let net = as_signed(settled.payout)?
+                .checked_sub(as_signed(settled.bet)?)
+                .map_err(|e| StdError::msg(e.to_string()))?;
+            Ok(SettledGameSummary {
+                game_id,
+                bet: settled.bet,
+                payout: settled.payout,
+                net,
+                outcome: settled.outcome,
+                block_height: settled.block_height,
+                forfeit_reason: settled.forfeit_reason,
+            })
         })
-        .take(max_limit)
-        .collect();
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next_cursor = if games.len() > limit {
+        games.truncate(limit);
+        games.last().map(|g| g.game_id)
+    } else {
+        None
+    };
+    Ok(SettledGamesResponse { games, next_cursor })
+}
+
+fn player_stats_response(player: &Addr, stats: PlayerStats) -> PlayerStatsResponse {
+    PlayerStatsResponse {
+        player: player.to_string(),
+        games_played: stats.games_played,
+        wins: stats.wins,
+        losses: stats.losses,
+        pushes: stats.pushes,
+        surrenders: stats.surrenders,
+        blackjacks: stats.blackjacks,
+        total_wagered: stats.total_wagered,
+        net_profit: stats.net_profit,
+    }
+}
 
-    games
+fn query_top_players(
+    deps: Deps,
+    by: TopPlayersSort,
+    limit: u32,
+) -> StdResult<Vec<PlayerStatsResponse>> {
+    let stats = player_stats();
+    let ranked = match by {
+        TopPlayersSort::ByNetProfit => {
+            stats
+                .idx
+                .net_profit
+                .range(deps.storage, None, None, Order::Descending)
+        }
+        TopPlayersSort::ByGamesPlayed => {
+            stats
+                .idx
+                .games_played
+                .range(deps.storage, None, None, Order::Descending)
+        }
+    };
+    ranked
+        .take(limit.min(100) as usize)
+        .map(|item| item.map(|(player, totals)| player_stats_response(&player, totals)))
+        .collect()
 }
 
 fn query_dealer_balance(deps: Deps) -> StdResult<DealerBalanceResponse> {
@@ -108,3 +347,264 @@ fn query_dealer(deps: Deps) -> StdResult<DealerResponse> {
         dealer: dealer.to_string(),
     })
 }
+
+fn query_protocol_fees(deps: Deps) -> StdResult<ProtocolFeesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(ProtocolFeesResponse {
+        collected: PROTOCOL_FEES.may_load(deps.storage)?.unwrap_or_default(),
+        fee_bps: config.protocol_fee_bps,
+        recipient: config.protocol_fee_recipient.map(|addr| addr.to_string()),
+    })
+}
+
+fn query_pause_status(deps: Deps) -> StdResult<PauseStatusResponse> {
+    Ok(PauseStatusResponse {
+        paused: PAUSED.may_load(deps.storage)?.unwrap_or(false),
+        paused_at: PAUSED_AT.may_load(deps.storage)?,
+    })
+}
+
+fn query_reveal_history(deps: Deps, game_id: u64) -> StdResult<Vec<RevealRecord>> {
+    // Unknown games error like GetGame instead of returning an empty history
+    GAMES.load(deps.storage, game_id)?;
+    REVEAL_HISTORY
+        .prefix(game_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, record)| record))
+        .collect()
+}
+
+fn query_contract_state(deps: Deps, game_id: u64) -> Result<ContractStateResponse, ContractError> {
+    if !CONFIG.load(deps.storage)?.debug_mode {
+        return Err(ContractError::DebugModeDisabled {});
+    }
+    let game = GAMES.load(deps.storage, game_id)?;
+
+    let status = format!("{:?}", game.status);
+    let phase = status
+        .split([' ', '{'])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    Ok(ContractStateResponse {
+        game,
+        phase,
+        reveal_history: query_reveal_history(deps, game_id)?,
+    })
+}
+
+fn query_reveal_requests(deps: Deps, game_id: u64) -> StdResult<RevealRequestsResponse> {
+    let game = GAMES.load(deps.storage, game_id)?;
+    let GameStatus::WaitingForReveal {
+        reveal_requests, ..
+    } = &game.status
+    else {
+        return Ok(RevealRequestsResponse { pending: vec![] });
+    };
+
+    let pending = reveal_requests
+        .iter()
+        .map(|&card_index| {
+            let submitted = game
+                .pending_reveals
+                .iter()
+                .find(|pr| pr.card_index == card_index);
+            PendingReveal {
+                card_index,
+                player_submitted: submitted.is_some_and(|pr| pr.player_partial.is_some()),
+                dealer_submitted: submitted.is_some_and(|pr| pr.dealer_partial.is_some()),
+            }
+        })
+        .collect();
+    Ok(RevealRequestsResponse { pending })
+}
+
+fn query_reveal_status(deps: Deps, game_id: u64) -> StdResult<RevealStatusResponse> {
+    let pending = query_reveal_requests(deps, game_id)?.pending;
+    let completed = REVEAL_HISTORY
+        .prefix(game_id)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((card_index, record)) => record.revealed_value.map(|card_value| {
+                Ok(CompletedReveal {
+                    card_index,
+                    card_value,
+                })
+            }),
+            Err(e) => Some(Err(e)),
+        })
+        .collect::<StdResult<_>>()?;
+    Ok(RevealStatusResponse { pending, completed })
+}
+
+/// Cards are only added to a hand once revealed, so this is the value of what
+/// the player can see so far.
+fn query_hand_value(
+    deps: Deps,
+    game_id: u64,
+    hand_index: u32,
+) -> Result<HandValueResponse, ContractError> {
+    let game = GAMES.load(deps.storage, game_id)?;
+    let hand = game
+        .hands
+        .get(hand_index as usize)
+        .ok_or(ContractError::InvalidHandIndex { hand_index })?;
+
+    let value = calculate_score(&hand.cards);
+    Ok(HandValueResponse {
+        value,
+        is_soft: is_soft_hand(&hand.cards),
+        is_busted: value > 21,
+        // Split hands never count as blackjack (see settle_game)
+        is_blackjack: value == 21 && hand.cards.len() == 2 && game.hands.len() == 1,
+        total_cards: hand.cards.len() as u8,
+        is_stood: hand.status == HandStatus::Stood,
+        is_surrendered: hand.status == HandStatus::Surrendered,
+    })
+}
+
+/// Basic strategy from `blackjack::StrategyTable` for the configured rules. Split and
+/// surrender are offered to the table only when `Split`/`Surrender` would accept them.
+fn query_optimal_action(
+    deps: Deps,
+    game_id: u64,
+    hand_index: u32,
+) -> Result<OptimalActionResponse, ContractError> {
+    let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
+    let hand = game
+        .hands
+        .get(hand_index as usize)
+        .ok_or(ContractError::InvalidHandIndex { hand_index })?;
+    if hand.cards.len() < 2 {
+        return Err(ContractError::Std(StdError::msg(
+            "Hand has not been dealt yet",
+        )));
+    }
+    let dealer_upcard = game
+        .dealer_hand
+        .first()
+        .map(|&card| card_value_to_card(card).value())
+        .ok_or_else(|| StdError::msg("Dealer upcard has not been dealt yet"))?;
+    let cards: Vec<_> = hand.cards.iter().map(|&c| card_value_to_card(c)).collect();
+    let player_value = calculate_score(&hand.cards);
+    let is_soft = is_soft_hand(&hand.cards);
+
+    let rules = config_to_rules(&config);
+    game.current_hand_index = hand_index;
+    let state = to_blackjack_state(&game, rules);
+    let options = blackjack::HandOptions {
+        from_split: game.hands.len() > 1,
+        can_split: state.can_split_current_hand(),
+        can_surrender: game.hands.len() == 1 && state.can_surrender_current_hand(),
+    };
+    let action = blackjack::StrategyTable::new(&rules).action(&cards, dealer_upcard, options);
+
+    Ok(OptimalActionResponse {
+        optimal_action: action.into(),
+        player_value,
+        is_soft,
+        dealer_upcard,
+        ev_hit: None,
+        ev_stand: None,
+    })
+}
+
+/// Mirrors the checks in `execute_claim_timeout`: the claim is allowed once
+/// `timeout_seconds` have passed since the last action and someone is blocking.
+fn query_timeout_status(deps: Deps, env: Env, game_id: u64) -> StdResult<TimeoutStatusResponse> {
+    let game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
+
+    let timed_out_party = match (&game.status, timeout_blocker(&game)) {
+        (status, _) if status.is_finished() => None,
+        (_, TurnOwner::None) => None,
+        (_, TurnOwner::Player) => Some("player".to_string()),
+        (_, TurnOwner::Dealer) => Some("dealer".to_string()),
+    };
+    if timed_out_party.is_none() {
+        return Ok(TimeoutStatusResponse {
+            can_claim_timeout: false,
+            seconds_until_eligible: None,
+            timed_out_party,
+        });
+    }
+
+    let elapsed = env
+        .block
+        .time
+        .seconds()
+        .saturating_sub(game.last_action_timestamp);
+    let remaining = config.timeout_seconds.saturating_sub(elapsed);
+    Ok(TimeoutStatusResponse {
+        can_claim_timeout: remaining == 0,
+        seconds_until_eligible: (remaining > 0).then_some(remaining),
+        timed_out_party,
+    })
+}
+
+/// Walks every stored game, so its cost grows until settled games are swept.
+fn query_dealer_dashboard(deps: Deps, env: Env) -> StdResult<DealerDashboardResponse> {
+    let available = DEALER_BALANCE.load(deps.storage)?;
+    let stats = HOUSE_STATS.may_load(deps.storage)?.unwrap_or_default();
+    let now = env.block.time.seconds();
+
+    // Only the open games, from the indexes, newest first
+    let mut open_ids = ACTIVE_GAMES
+        .keys(deps.storage, None, None, Order::Ascending)
+        .chain(WAITING_GAMES.keys(deps.storage, None, None, Order::Ascending))
+        .collect::<StdResult<Vec<u64>>>()?;
+    open_ids.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut locked = Uint128::zero();
+    let mut open_games = 0u32;
+    let mut active_games = Vec::new();
+    for game_id in open_ids {
+        let game = GAMES.load(deps.storage, game_id)?;
+        locked = locked
+            .checked_add(game.bankroll)
+            .map_err(|e| StdError::msg(e.to_string()))?;
+        open_games += 1;
+        if active_games.len() < DASHBOARD_ACTIVE_GAMES {
+            active_games.push(GameSummary {
+                game_id,
+                status: format!("{:?}", game.status),
+                player: game.player.to_string(),
+                bet: game.bet,
+                seconds_since_action: now.saturating_sub(game.last_action_timestamp),
+            });
+        }
+    }
+
+    Ok(DealerDashboardResponse {
+        balance: available
+            .checked_add(locked)
+            .map_err(|e| StdError::msg(e.to_string()))?,
+        locked,
+        available,
+        open_games,
+        total_games: GAME_COUNTER.load(deps.storage)?,
+        total_volume: stats.total_volume,
+        house_profit: stats.house_profit,
+        active_games,
+    })
+}
+
+/// The player's key is stored empty until `JoinGame`.
+fn query_aggregated_public_key(deps: Deps, game_id: u64) -> StdResult<AggregatedKeyResponse> {
+    let game = GAMES.load(deps.storage, game_id)?;
+    if game.player_pubkey.is_empty() {
+        return Ok(AggregatedKeyResponse {
+            dealer_pk: game.dealer_pubkey,
+            player_pk: None,
+            aggregated_pk: None,
+        });
+    }
+
+    let aggregated_pk = aggregate_public_keys(&game.dealer_pubkey, &game.player_pubkey)?;
+    Ok(AggregatedKeyResponse {
+        dealer_pk: game.dealer_pubkey,
+        player_pk: Some(game.player_pubkey),
+        aggregated_pk: Some(aggregated_pk),
+    })
+}
@@ -1,10 +1,15 @@
+use crate::contract::game_config;
 use crate::error::ContractError;
+use crate::game_logic::{config_to_rules, to_blackjack_state};
+use crate::msg::RevealItem;
 use crate::state::{
-    GameSession, GameStatus, HandStatus, PendingReveal, CONFIG, DEALER_BALANCE, GAMES,
+    GameSession, GameStatus, HandStatus, InsuranceBet, PendingReveal, RevealRecord, GAMES,
+    REVEAL_HISTORY,
 };
 use crate::zk::xion_zk_verify;
+use blackjack::HandOutcome;
 use cosmwasm_std::{
-    BankMsg, Binary, Coin, DepsMut, Env, MessageInfo, Response, StdError, Storage, Uint128,
+    BankMsg, Binary, Coin, DepsMut, Env, Event, MessageInfo, Response, StdError, Storage, Uint128,
 };
 
 /// Handle submission of partial decryption from player or dealer
@@ -23,10 +28,9 @@ pub fn execute_submit_reveal(
     if !info.funds.is_empty() {
         return Err(ContractError::Std(StdError::msg("Unexpected funds sent")));
     }
-    let config = CONFIG.load(deps.storage)?;
-
     // Load game by ID
     let mut game = GAMES.load(deps.storage, game_id)?;
+    let config = game_config(deps.storage, &game)?;
 
     // Determine if sender is player or dealer
     let is_player = info.sender == game.player;
@@ -62,7 +66,9 @@ pub fn execute_submit_reveal(
     // Verify the ZK proof
     let verified = xion_zk_verify(deps.as_ref(), &config.reveal_vk_id, proof, public_inputs)?;
     if !verified {
-        return Err(ContractError::Std(StdError::msg("Invalid reveal proof")));
+        return Err(ContractError::ProofVerificationFailed {
+            proof: "reveal".to_string(),
+        });
     }
 
     // Find or create pending reveal for this card
@@ -105,6 +111,15 @@ pub fn execute_submit_reveal(
         pending_reveal.dealer_partial = Some(partial_decryption.clone());
     }
 
+    let mut record = RevealRecord {
+        card_index,
+        player_partial: pending_reveal.player_partial.clone(),
+        dealer_partial: pending_reveal.dealer_partial.clone(),
+        combined_result: None,
+        revealed_value: None,
+        block_height: env.block.height,
+    };
+
     // Check if both parties have submitted
     let both_revealed =
         pending_reveal.player_partial.is_some() && pending_reveal.dealer_partial.is_some();
@@ -117,7 +132,11 @@ pub fn execute_submit_reveal(
         let dealer_bytes = pending_reveal.dealer_partial.as_ref().unwrap().as_slice();
 
         // Simple combination: XOR first bytes and mod 52
-        let card_value = (player_bytes[0] ^ dealer_bytes[0]) % 52;
+        let combined = player_bytes[0] ^ dealer_bytes[0];
+        let card_value = combined % 52;
+        record.combined_result = Some(Binary::from(vec![combined]));
+        record.revealed_value = Some(card_value);
+        REVEAL_HISTORY.save(deps.storage, (game_id, card_index), &record)?;
 
         // Add card to appropriate hand/dealer based on card_index
         let for_dealer = matches!(*next_status, GameStatus::DealerTurn);
@@ -153,13 +172,21 @@ pub fn execute_submit_reveal(
             .add_attribute("both_revealed", "true");
 
         if matches!(game.status, GameStatus::Settled { .. }) {
-            response = execute_payouts(deps.storage, &game, &config, response)?;
+            response = execute_payouts(
+                deps.storage,
+                &game,
+                game_id,
+                &config,
+                env.block.height,
+                response,
+            )?;
         }
         GAMES.save(deps.storage, game_id, &game)?;
 
         Ok(response)
     } else {
         // Only one party has submitted, wait for the other
+        REVEAL_HISTORY.save(deps.storage, (game_id, card_index), &record)?;
         game.pending_reveals.push(pending_reveal);
         game.last_action_timestamp = env.block.time.seconds();
         GAMES.save(deps.storage, game_id, &game)?;
@@ -173,6 +200,46 @@ pub fn execute_submit_reveal(
     }
 }
 
+/// Submit several partial decryptions in one transaction. Items are applied in
+/// order exactly as individual `SubmitReveal` calls; the first failure aborts the
+/// transaction, so either every item lands or none does.
+pub fn execute_submit_batch_reveal(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    game_id: u64,
+    reveals: Vec<RevealItem>,
+) -> Result<Response, ContractError> {
+    if reveals.is_empty() {
+        return Err(ContractError::Std(StdError::msg(
+            "Batch reveal needs at least one item",
+        )));
+    }
+    let count = reveals.len();
+    let mut response = Response::new()
+        .add_attribute("action", "submit_batch_reveal")
+        .add_attribute("game_id", game_id.to_string())
+        .add_attribute("revealed", count.to_string());
+    for item in reveals {
+        let sub = execute_submit_reveal(
+            deps.branch(),
+            env.clone(),
+            info.clone(),
+            game_id,
+            item.card_index,
+            item.partial_decryption,
+            item.proof,
+            item.public_inputs,
+        )?;
+        // Each item's attributes become a `reveal` event so callers can read them per card
+        response = response
+            .add_submessages(sub.messages)
+            .add_events(sub.events)
+            .add_event(Event::new("reveal").add_attributes(sub.attributes));
+    }
+    Ok(response)
+}
+
 /// Add revealed card to the appropriate hand
 fn add_card_to_game(
     game: &mut GameSession,
@@ -228,7 +295,12 @@ fn determine_next_status(
                 if let Some(&upcard) = game.dealer_hand.first() {
                     let rank = (upcard % 13) + 1;
                     // Offer insurance for Ace upcard before peek
-                    if rank == 1 && game.dealer_hand.len() == 1 && game.insurance_bet.is_none() {
+                    let mut bj_state = to_blackjack_state(game, config_to_rules(config));
+                    if bj_state.offer_insurance().is_ok() {
+                        game.insurance = Some(InsuranceBet {
+                            offered: true,
+                            ..InsuranceBet::default()
+                        });
                         game.current_turn = crate::state::TurnOwner::Player;
                         return Ok(GameStatus::OfferingInsurance);
                     }
@@ -240,8 +312,12 @@ fn determine_next_status(
                                 next_status: Box::new(GameStatus::PlayerTurn),
                             });
                         } else {
-                            // Post-peek: hole card just revealed
+                            // Post-peek: hole card just revealed, which decides insurance.
+                            // The bet itself is paid or kept by the dealer on settlement.
                             game.dealer_peeked = true;
+                            if let Some(insurance) = game.insurance.as_mut() {
+                                insurance.resolved = true;
+                            }
                             let d_score = crate::contract::calculate_score(&game.dealer_hand);
                             if d_score == 21 {
                                 return settle_game(game, d_score);
@@ -271,6 +347,10 @@ fn determine_next_status(
             } else if matches!(hand.status, HandStatus::Doubled) {
                 // Doubled - auto-stand after one card
                 advance_or_dealer_turn(game, config)
+            } else if split_ace_must_stand(game, config) {
+                // Split aces - auto-stand after one card
+                game.hands[hand_idx].status = HandStatus::Stood;
+                advance_or_dealer_turn(game, config)
             } else {
                 game.current_turn = crate::state::TurnOwner::Player;
                 Ok(GameStatus::PlayerTurn)
@@ -290,6 +370,11 @@ pub(crate) fn advance_or_dealer_turn(
     let hand_idx = game.current_hand_index as usize;
     if hand_idx + 1 < game.hands.len() {
         game.current_hand_index = (hand_idx + 1) as u32;
+        // The next hand got its second card on the split, so it may already be done
+        if split_ace_must_stand(game, config) {
+            game.hands[hand_idx + 1].status = HandStatus::Stood;
+            return advance_or_dealer_turn(game, config);
+        }
         game.current_turn = crate::state::TurnOwner::Player;
         Ok(GameStatus::PlayerTurn)
     } else {
@@ -313,6 +398,19 @@ pub(crate) fn advance_or_dealer_turn(
     }
 }
 
+/// The current hand is a split ace holding its one card, with no choice left: hits
+/// are off and it isn't a pair of aces that may be resplit.
+fn split_ace_must_stand(game: &GameSession, config: &crate::state::Config) -> bool {
+    let Some(hand) = game.hands.get(game.current_hand_index as usize) else {
+        return false;
+    };
+    !config.can_hit_split_aces
+        && crate::contract::is_split_ace(hand)
+        && hand.cards.len() >= 2
+        && hand.status == HandStatus::Active
+        && !to_blackjack_state(game, config_to_rules(config)).can_split_current_hand()
+}
+
 /// Process dealer turn logic: hit, stand, or settle.
 pub(crate) fn process_dealer_turn(
     game: &mut GameSession,
@@ -331,25 +429,7 @@ pub(crate) fn process_dealer_turn(
             next_status: Box::new(GameStatus::DealerTurn),
         })
     } else if d_score == 17 && config.dealer_hits_soft_17 {
-        // Check if soft 17 (at least one ace counted as 11)
-        let mut score: u16 = 0;
-        let mut aces: u16 = 0;
-        for &card in &game.dealer_hand {
-            let val = (card % 13) + 1;
-            if val == 1 {
-                aces += 1;
-                score += 11;
-            } else if val > 10 {
-                score += 10;
-            } else {
-                score += val as u16;
-            }
-        }
-        while score > 21 && aces > 0 {
-            score -= 10;
-            aces -= 1;
-        }
-        if aces > 0 && score == 17 {
+        if crate::contract::is_soft_hand(&game.dealer_hand) {
             if game.last_card_index >= 52 {
                 return Err(ContractError::Std(StdError::msg("Deck exhausted")));
             }
@@ -424,46 +504,100 @@ fn settle_game(game: &mut GameSession, d_score: u8) -> Result<GameStatus, Contra
     })
 }
 
+/// Cross-checks what a settled hand returns to the player. The expected amount is
+/// worked out again from the game's base bet, the hand's stake and its cards, so a
+/// settlement string that disagrees with the cards, or a stake that is neither the bet
+/// nor a double of it, fails the settlement instead of paying out.
+fn check_payout(
+    game: &GameSession,
+    hand_index: usize,
+    outcome: HandOutcome,
+    returned: Uint128,
+    config: &crate::state::Config,
+) -> Result<(), ContractError> {
+    let overflow = |e: cosmwasm_std::OverflowError| StdError::msg(e.to_string());
+    let hand = &game.hands[hand_index];
+    let base = game.bet;
+    let double = base.checked_mul(Uint128::new(2)).map_err(overflow)?;
+    let doubled = hand.bet == double;
+    let outcome = match (outcome, doubled) {
+        (HandOutcome::Win, true) => HandOutcome::DoubleWin,
+        (HandOutcome::Loss, true) => HandOutcome::DoubleLoss,
+        (outcome, _) => outcome,
+    };
+
+    let score = crate::contract::calculate_score(&hand.cards);
+    let cards_agree = match outcome {
+        HandOutcome::Blackjack => {
+            game.hands.len() == 1 && hand.cards.len() == 2 && score == 21 && !doubled
+        }
+        HandOutcome::Win | HandOutcome::DoubleWin | HandOutcome::Push => score <= 21,
+        _ => true,
+    };
+    let with_winnings = |stake: Uint128, ratio: &crate::state::PayoutRatio| {
+        stake
+            .checked_multiply_ratio(ratio.numerator, ratio.denominator)
+            .map_err(|e| StdError::msg(e.to_string()))
+            .and_then(|won| stake.checked_add(won).map_err(overflow))
+    };
+    let expected = match outcome {
+        HandOutcome::Win => with_winnings(base, &config.standard_payout)?,
+        HandOutcome::DoubleWin => with_winnings(double, &config.standard_payout)?,
+        HandOutcome::Blackjack => with_winnings(base, &config.blackjack_payout)?,
+        HandOutcome::Push => hand.bet,
+        HandOutcome::Loss | HandOutcome::DoubleLoss => Uint128::zero(),
+        // Surrender and insurance are settled outside execute_payouts
+        _ => return Ok(()),
+    };
+
+    if (hand.bet != base && !doubled) || !cards_agree || returned != expected {
+        return Err(ContractError::PayoutMismatch {
+            hand_index: hand_index as u32,
+            outcome: format!("{outcome:?}"),
+            expected,
+            actual: returned,
+        });
+    }
+    Ok(())
+}
+
 /// Execute payouts based on game results
 pub(crate) fn execute_payouts(
     storage: &mut dyn Storage,
     game: &GameSession,
+    game_id: u64,
     config: &crate::state::Config,
+    block_height: u64,
     mut response: Response,
 ) -> Result<Response, ContractError> {
     let mut player_winnings = Uint128::zero();
     let total_player_bets: Uint128 = game.hands.iter().map(|h| h.bet).sum();
-    let insurance_bet = game.insurance_bet.unwrap_or(Uint128::zero());
+    let insurance_bet = game.insurance_stake();
 
-    for hand in &game.hands {
+    for (hand_index, hand) in game.hands.iter().enumerate() {
         let winner = match &hand.status {
             HandStatus::Settled { winner } => winner.as_str(),
             _ => continue,
         };
 
-        match winner {
-            "Player (Blackjack)" => {
-                let payout = config.blackjack_payout.calculate_payout(hand.bet);
-                player_winnings = player_winnings
-                    .checked_add(hand.bet + payout)
-                    .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-            }
-            "Player" => {
-                let payout = config.standard_payout.calculate_payout(hand.bet);
-                player_winnings = player_winnings
-                    .checked_add(hand.bet + payout)
-                    .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-            }
-            "Push" => {
-                player_winnings = player_winnings
-                    .checked_add(hand.bet)
-                    .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-            }
-            "Dealer" => {
-                // Dealer wins, player loses bet
-            }
-            _ => {}
-        }
+        let (returned, outcome) = match winner {
+            "Player (Blackjack)" => (
+                hand.bet + config.blackjack_payout.calculate_payout(hand.bet),
+                HandOutcome::Blackjack,
+            ),
+            "Player" => (
+                hand.bet + config.standard_payout.calculate_payout(hand.bet),
+                HandOutcome::Win,
+            ),
+            "Push" => (hand.bet, HandOutcome::Push),
+            // Dealer wins, player loses bet
+            "Dealer" => (Uint128::zero(), HandOutcome::Loss),
+            _ => continue,
+        };
+        check_payout(game, hand_index, outcome, returned, config)?;
+        player_winnings = player_winnings
+            .checked_add(returned)
+            .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
     }
 
     // Insurance side bet settlement
@@ -489,11 +623,7 @@ pub(crate) fn execute_payouts(
         .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?
         .checked_sub(player_winnings)
         .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-    let mut dealer_balance = DEALER_BALANCE.load(storage)?;
-    dealer_balance = dealer_balance
-        .checked_add(dealer_credit)
-        .map_err(|e| ContractError::Std(StdError::msg(e.to_string())))?;
-    DEALER_BALANCE.save(storage, &dealer_balance)?;
+    crate::contract::credit_dealer_settlement(storage, game, game_id, dealer_credit, block_height)?;
 
     // Send winnings to player if any
     if player_winnings > Uint128::zero() {
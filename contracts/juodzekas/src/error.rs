@@ -1,4 +1,4 @@
-use cosmwasm_std::StdError;
+use cosmwasm_std::{StdError, Uint128};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -9,6 +9,82 @@ pub enum ContractError {
     #[error("Unauthorized")]
     Unauthorized {},
 
+    /// `proof` names what was proven, e.g. "player shuffle"
+    #[error("Invalid {proof} proof")]
+    ProofVerificationFailed { proof: String },
+
+    #[error("Surrender not allowed")]
+    SurrenderNotAllowed {},
+
+    #[error("Double down not allowed")]
+    DoubleNotAllowed {},
+
+    #[error("Hit not allowed")]
+    HitNotAllowed {},
+
+    #[error("Splitting aces not allowed")]
+    CannotSplitAces {},
+
+    #[error("Resplitting aces not allowed")]
+    ResplitAcesNotAllowed {},
+
+    #[error("Only {expected} accepted, got {received}; other denoms would be permanently locked")]
+    InvalidDenom { expected: String, received: String },
+
+    #[error("Bet out of range: provided {provided}, min {min}, max {max}")]
+    BetOutOfRange {
+        min: Uint128,
+        max: Uint128,
+        provided: Uint128,
+    },
+
+    #[error("Zero bet not allowed")]
+    ZeroBetNotAllowed {},
+
+    #[error("Insurance not being offered")]
+    InsuranceNotOffered {},
+
+    #[error("Insurance already resolved")]
+    InsuranceAlreadyResolved {},
+
+    #[error("{address} is not whitelisted")]
+    NotWhitelisted { address: String },
+
+    #[error("{count} game(s) still in progress")]
+    GamesInProgress { count: u32 },
+
+    #[error("Verification key {vk_id} is not registered in the ZK module")]
+    VerificationKeyNotRegistered { vk_id: String },
+
+    #[error("Contract is paused")]
+    ContractPaused {},
+
+    #[error("Contract must be paused first")]
+    ContractNotPaused {},
+
+    #[error("Forfeit reason is {length} characters, max {max}")]
+    ForfeitReasonTooLong { length: usize, max: usize },
+
+    #[error("Invalid public key: {reason}")]
+    InvalidPublicKey { reason: String },
+
+    #[error("Invalid hand index: {hand_index}")]
+    InvalidHandIndex { hand_index: u32 },
+
+    #[error("Games in progress are still being indexed; call BackfillActiveGames first")]
+    ActiveGamesBackfillPending {},
+
+    #[error("Debug queries are disabled (debug_mode is off)")]
+    DebugModeDisabled {},
+
+    #[error("Hand {hand_index} settled as {outcome} should return {expected}, not {actual}")]
+    PayoutMismatch {
+        hand_index: u32,
+        outcome: String,
+        expected: Uint128,
+        actual: Uint128,
+    },
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
 }
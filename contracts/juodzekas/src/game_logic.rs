@@ -1,6 +1,6 @@
 use crate::state::{Config, GameSession, GameStatus, HandStatus, TurnOwner};
 use blackjack::{
-    Card, GamePhase, GameRules, GameState, Hand as BjHand, PayoutRatio, Spot,
+    ActionHistory, Card, GamePhase, GameRules, GameState, Hand as BjHand, PayoutRatio, Spot,
     TurnOwner as BjTurnOwner,
 };
 
@@ -16,6 +16,7 @@ pub fn to_blackjack_state(session: &GameSession, rules: GameRules) -> GameState
         for &card_value in &hand.cards {
             bj_hand.add_card(card_value_to_card(card_value));
         }
+        bj_hand.split_depth = hand.split_depth;
 
         match hand.status {
             HandStatus::Doubled => bj_hand.doubled = true,
@@ -46,11 +47,22 @@ pub fn to_blackjack_state(session: &GameSession, rules: GameRules) -> GameState
         GameStatus::WaitingForReveal { .. } if session.dealer_hand.is_empty() => {
             GamePhase::InitialDeal
         }
+        // Upcard shown, hole card not yet peeked, no card past the initial four dealt
+        GameStatus::WaitingForReveal { next_status, .. }
+            if **next_status == GameStatus::PlayerTurn
+                && !session.dealer_peeked
+                && session.dealer_hand.len() == 1
+                && session.last_card_index <= 4 =>
+        {
+            GamePhase::InitialDeal
+        }
         GameStatus::WaitingForReveal { .. } => GamePhase::DealerTurn, // Card reveals during game
-        GameStatus::OfferingInsurance => GamePhase::PlayerTurn, // Insurance is a player decision
+        GameStatus::OfferingInsurance => GamePhase::InsuranceOffered,
         GameStatus::PlayerTurn => GamePhase::PlayerTurn,
         GameStatus::DealerTurn => GamePhase::DealerTurn,
-        GameStatus::Settled { .. } => GamePhase::Settled,
+        GameStatus::Settled { .. }
+        | GameStatus::Forfeited { .. }
+        | GameStatus::EmergencyRefunded => GamePhase::Settled,
     };
 
     // Convert turn owner
@@ -69,6 +81,23 @@ pub fn to_blackjack_state(session: &GameSession, rules: GameRules) -> GameState
         dealer_peeked: session.dealer_peeked,
         rules,
         last_action_timestamp: Some(session.last_action_timestamp),
+        initial_bet: session.bet.u128(),
+        // The offer comes before the hole card (index 3) is requested
+        insurance_offered: session.insurance.is_some()
+            || session.dealer_hand.len() > 1
+            || match &session.status {
+                GameStatus::OfferingInsurance => true,
+                GameStatus::WaitingForReveal {
+                    reveal_requests, ..
+                } => reveal_requests.contains(&3),
+                _ => false,
+            },
+        insurance_bet: session
+            .insurance
+            .as_ref()
+            .filter(|ins| ins.accepted)
+            .map(|ins| ins.bet.u128()),
+        history: ActionHistory::default(),
     }
 }
 
@@ -80,6 +109,11 @@ pub fn config_to_rules(config: &Config) -> GameRules {
         config.blackjack_payout.denominator,
     )
     .unwrap_or(PayoutRatio::THREE_TO_TWO);
+    let insurance_payout = PayoutRatio::new(
+        config.insurance_payout.numerator,
+        config.insurance_payout.denominator,
+    )
+    .unwrap_or(PayoutRatio::TWO_TO_ONE);
 
     // Convert contract DoubleRestriction to blackjack DoubleRestriction
     let double_restriction = match config.double_restriction {
@@ -91,21 +125,23 @@ pub fn config_to_rules(config: &Config) -> GameRules {
     GameRules {
         dealer_hits_soft_17: config.dealer_hits_soft_17,
         allow_surrender: config.surrender_allowed,
-        late_surrender: true,     // Assume late surrender if surrender allowed
-        double_after_split: true, // Would need to add to Config if different
+        late_surrender: true, // Assume late surrender if surrender allowed
+        double_after_split: config.double_after_split,
         double_restriction,
         allow_resplit: config.max_splits > 0,
         max_splits: config.max_splits as u8,
-        resplit_aces: config.can_split_aces,
+        resplit_aces: config.can_split_aces && config.resplit_aces,
         dealer_peeks: config.dealer_peeks,
         blackjack_payout,
-        num_decks: 1, // Would need to add to Config
+        num_decks: 1,                             // Would need to add to Config
+        insurance_available: config.dealer_peeks, // Offered before the peek only
+        insurance_payout,
     }
 }
 
 /// Convert u8 card value (0-51) to Card enum
 /// Contract uses 0-51 indexing where rank = (value % 13) and suit = (value / 13)
-fn card_value_to_card(value: u8) -> Card {
+pub(crate) fn card_value_to_card(value: u8) -> Card {
     let rank = value % 13; // 0-12 (Ace through King)
     let suit = value / 13; // 0-3 (Spades, Hearts, Diamonds, Clubs)
 
@@ -206,10 +242,18 @@ mod tests {
             max_splits: 3,
             can_split_aces: false,
             can_hit_split_aces: false,
+            double_after_split: true,
+            resplit_aces: false,
+            debug_mode: false,
             surrender_allowed: true,
             shuffle_vk_id: "test".to_string(),
             reveal_vk_id: "test".to_string(),
             timeout_seconds: 3600,
+            retain_settled_seconds: 604_800,
+            vk_version: 0,
+            version: 0,
+            protocol_fee_bps: 0,
+            protocol_fee_recipient: None,
         };
 
         let rules = config_to_rules(&config);
@@ -235,6 +279,7 @@ mod tests {
                 cards: vec![1, 23], // Two of Spades, King of Hearts
                 bet: Uint128::new(100),
                 status: HandStatus::Active,
+                split_depth: 0,
             }],
             current_hand_index: 0,
             dealer_hand: vec![10], // Jack of Spades
@@ -244,7 +289,10 @@ mod tests {
             last_card_index: 2,
             pending_reveals: vec![],
             dealer_peeked: false,
-            insurance_bet: None,
+            insurance: None,
+            deck_hash: Binary::default(),
+            player_deck_hash: None,
+            terms: None,
         };
 
         let rules = GameRules::default();
@@ -259,6 +307,71 @@ mod tests {
         assert_eq!(state.last_action_timestamp, Some(1000));
     }
 
+    #[test]
+    fn test_to_blackjack_state_insurance() {
+        let mut session = GameSession {
+            player: Addr::unchecked("player"),
+            dealer: Addr::unchecked("dealer"),
+            bet: Uint128::new(100),
+            bankroll: Uint128::new(10000),
+            player_pubkey: Binary::default(),
+            dealer_pubkey: Binary::default(),
+            deck: vec![],
+            player_shuffled_deck: None,
+            hands: vec![crate::state::Hand {
+                cards: vec![1, 23],
+                bet: Uint128::new(100),
+                status: HandStatus::Active,
+                split_depth: 0,
+            }],
+            current_hand_index: 0,
+            dealer_hand: vec![0], // Ace of Spades
+            status: GameStatus::WaitingForReveal {
+                reveal_requests: vec![2],
+                next_status: Box::new(GameStatus::PlayerTurn),
+            },
+            current_turn: TurnOwner::Dealer,
+            last_action_timestamp: 1000,
+            last_card_index: 4,
+            pending_reveals: vec![],
+            dealer_peeked: false,
+            insurance: None,
+            deck_hash: Binary::default(),
+            player_deck_hash: None,
+            terms: None,
+        };
+        let rules = GameRules::default();
+
+        // Initial deal just revealed an ace: insurance is due
+        let state = to_blackjack_state(&session, rules);
+        assert_eq!(state.phase, GamePhase::InitialDeal);
+        assert_eq!(state.initial_bet, 100);
+        assert!(state.can_take_insurance());
+
+        session.status = GameStatus::OfferingInsurance;
+        let state = to_blackjack_state(&session, rules);
+        assert_eq!(state.phase, GamePhase::InsuranceOffered);
+        assert!(!state.can_take_insurance());
+
+        // Declined: waiting on the hole card, not offered again
+        session.status = GameStatus::WaitingForReveal {
+            reveal_requests: vec![3],
+            next_status: Box::new(GameStatus::PlayerTurn),
+        };
+        assert!(!to_blackjack_state(&session, rules).can_take_insurance());
+
+        // A hit in a no-peek game is not part of the initial deal
+        session.last_card_index = 5;
+        session.status = GameStatus::WaitingForReveal {
+            reveal_requests: vec![4],
+            next_status: Box::new(GameStatus::PlayerTurn),
+        };
+        assert_eq!(
+            to_blackjack_state(&session, rules).phase,
+            GamePhase::DealerTurn
+        );
+    }
+
     /// After fix: double down requires additional funds, so total_player_bets matches deposits.
     #[test]
     fn test_double_down_accounting_balanced() {
@@ -303,4 +416,20 @@ mod tests {
 
         assert_eq!(dealer_credit, contract_balance);
     }
+
+    /// Hands fill in as cards are revealed, so scores are taken of empty and partial hands.
+    #[test]
+    fn test_calculate_score_hand_sizes() {
+        use crate::contract::{calculate_score, is_soft_hand};
+
+        assert_eq!(calculate_score(&[]), 0);
+        assert!(!is_soft_hand(&[]));
+        // Card values are 0-51 with rank = value % 13: 0 is an Ace, 12 a King
+        assert_eq!(calculate_score(&[0]), 11);
+        assert!(is_soft_hand(&[0]));
+        assert_eq!(calculate_score(&[0, 12]), 21);
+        assert_eq!(calculate_score(&[9, 5, 0]), 17);
+        assert!(!is_soft_hand(&[9, 5, 0]));
+        assert_eq!(calculate_score(&[12, 11, 10]), 30);
+    }
 }
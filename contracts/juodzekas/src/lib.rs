@@ -8,7 +8,7 @@ pub mod zk;
 #[cfg(not(feature = "library"))]
 use crate::error::ContractError;
 #[cfg(not(feature = "library"))]
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::{entry_point, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
 
@@ -40,6 +40,12 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     contract::query(deps, env, msg)
 }
 
+#[cfg(not(feature = "library"))]
+#[entry_point]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    contract::migrate(deps, env, msg)
+}
+
 // the random function must be disabled in cosmwasm
 use core::num::NonZeroU32;
 use getrandom::Error;
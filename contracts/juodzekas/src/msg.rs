@@ -1,7 +1,10 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Binary, Uint128};
+use cosmwasm_std::{Binary, Int128, SignedDecimal, Uint128};
 
-pub use crate::state::{Config, DoubleRestriction, PayoutRatio};
+pub use crate::state::{
+    Config, ConfigChange, DoubleRestriction, GameOutcome, GameSession, GameTerms, PayoutRatio,
+    RevealRecord,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
@@ -17,11 +20,27 @@ pub struct InstantiateMsg {
     pub max_splits: u32,
     pub can_split_aces: bool,
     pub can_hit_split_aces: bool,
+    /// Allow doubling a hand that came from a split. Defaults to true.
+    pub double_after_split: Option<bool>,
+    /// Allow splitting a second ace drawn to a split ace. Defaults to false.
+    pub resplit_aces: Option<bool>,
+    /// Enable the `GetContractState` debug query. Defaults to false.
+    pub debug_mode: Option<bool>,
     pub surrender_allowed: bool,
     pub shuffle_vk_id: String,
     pub reveal_vk_id: String,
     /// Timeout in seconds for inactivity claims and settled game cleanup. Defaults to 3600 (1 hour).
     pub timeout_seconds: Option<u64>,
+    /// How long a finished game is kept before `BurnExpiredGames` may delete it.
+    /// Defaults to 604800 (7 days).
+    pub retain_settled_seconds: Option<u64>,
+    /// Restrict `JoinGame` to these addresses. `None` or empty lets anyone join.
+    pub whitelist: Option<Vec<String>>,
+    /// Cut of the dealer's profit on each winning game, in basis points (at most 10000),
+    /// held for `protocol_fee_recipient`. Defaults to 0.
+    pub protocol_fee_bps: Option<u16>,
+    /// Required with a non-zero `protocol_fee_bps`
+    pub protocol_fee_recipient: Option<String>,
 }
 
 #[cw_serde]
@@ -58,6 +77,7 @@ pub enum ExecuteMsg {
     Surrender {
         game_id: u64,
     },
+    // Player, while insurance is offered; stake up to half the original bet, sent as funds
     Insurance {
         game_id: u64,
     },
@@ -72,6 +92,11 @@ pub enum ExecuteMsg {
         proof: Binary,
         public_inputs: Vec<String>,
     },
+    // Several SubmitReveal items in one transaction; any failing item rolls back the batch
+    SubmitBatchReveal {
+        game_id: u64,
+        reveals: Vec<RevealItem>,
+    },
     // Timeout claim: if opponent doesn't act, claim funds
     ClaimTimeout {
         game_id: u64,
@@ -84,12 +109,76 @@ pub enum ExecuteMsg {
     SweepSettled {
         game_ids: Vec<u64>,
     },
+    // Dealer-only; delete up to `limit` (at most 50) finished games older than
+    // `retain_settled_seconds`, oldest first, among ids above `start_after` and below
+    // `before_game_id`. Pass the `last_scanned` attribute as `start_after` to page.
+    BurnExpiredGames {
+        start_after: Option<u64>,
+        before_game_id: Option<u64>,
+        limit: u32,
+    },
     // Deposit additional bankroll
     DepositBankroll {},
     // Withdraw dealer bankroll balance
     WithdrawBankroll {
         amount: Option<Uint128>,
     },
+    // Protocol fee recipient only; sends all collected protocol fees
+    ClaimProtocolFee {},
+    // Dealer-only player whitelist management
+    AddToWhitelist {
+        addresses: Vec<String>,
+    },
+    RemoveFromWhitelist {
+        addresses: Vec<String>,
+    },
+    ClearWhitelist {},
+    // Dealer-only; point the contract at new ZK verification keys while no game is in play
+    UpdateVerificationKeys {
+        shuffle_vk_id: String,
+        reveal_vk_id: String,
+    },
+    // Dealer-only; change table terms for games created from now on. Omitted fields
+    // keep their value, and games already created keep the terms they started with.
+    UpdateConfig {
+        min_bet: Option<Uint128>,
+        max_bet: Option<Uint128>,
+        timeout_seconds: Option<u64>,
+        surrender_allowed: Option<bool>,
+    },
+    // Dealer-only; stop or allow new games
+    Pause {},
+    Unpause {},
+    // Dealer-only, while paused; refund the player's stake in up to 20 joined games per call
+    EmergencyRefundAll {},
+    // Dealer-only; count the next batch of settled games into the player stats migrate
+    // started rebuilding. Call again until `stats_rebuilt` is true.
+    BackfillPlayerStats {},
+    // Dealer-only; index the next batch of games migrate started scanning for open
+    // ones. Key rotation and emergency refunds wait until `active_games_rebuilt` is
+    // true.
+    BackfillActiveGames {},
+    // Dealer-only; give up a joined game, paying the player their stake plus the most
+    // they could still win. `reason` is at most 256 characters.
+    ForfeitGame {
+        game_id: u64,
+        reason: Option<String>,
+    },
+}
+
+/// One card's partial decryption inside `SubmitBatchReveal`
+#[cw_serde]
+pub struct RevealItem {
+    pub card_index: u32,
+    pub partial_decryption: Binary,
+    pub proof: Binary,
+    pub public_inputs: Vec<String>,
+}
+
+/// Sent with a code upgrade; the stored cw2 version becomes `new_version`
+#[cw_serde]
+pub struct MigrateMsg {
+    pub new_version: String,
 }
 
 #[cw_serde]
@@ -97,6 +186,9 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(Config)]
     GetConfig {},
+    /// The latest config changes (at most 100), oldest first
+    #[returns(Vec<ConfigChange>)]
+    GetConfigHistory {},
     #[returns(GameResponse)]
     GetGame { game_id: u64 },
     #[returns(Vec<GameListItem>)]
@@ -105,10 +197,76 @@ pub enum QueryMsg {
         limit: Option<u32>,
         start_after: Option<u64>,
     },
+    /// Games by ascending id whose status contains `status_filter`, a page at a time.
+    /// `limit` defaults to 20 and is kept between 1 and 100. At most 500 games are read
+    /// per call, so a page can come back short, even empty, with a `next_cursor`.
+    #[returns(GamesPageResponse)]
+    GetGamesByStatus {
+        status_filter: Option<String>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
     #[returns(DealerBalanceResponse)]
     GetDealerBalance {},
     #[returns(DealerResponse)]
     GetDealer {},
+    /// Every reveal submitted for a game, ordered by card index
+    #[returns(Vec<RevealRecord>)]
+    GetRevealHistory { game_id: u64 },
+    /// Cards the game is waiting on and who has submitted a partial for each;
+    /// empty outside `WaitingForReveal`
+    #[returns(RevealRequestsResponse)]
+    GetRevealRequests { game_id: u64 },
+    /// `GetRevealRequests` plus every card of the game revealed so far
+    #[returns(RevealStatusResponse)]
+    GetRevealStatus { game_id: u64 },
+    /// Score of one of the player's hands, from its revealed cards only
+    #[returns(HandValueResponse)]
+    GetHandValue { game_id: u64, hand_index: u32 },
+    /// Basic strategy play for one of the player's hands under the configured rules
+    #[returns(OptimalActionResponse)]
+    CalculateOptimalAction { game_id: u64, hand_index: u32 },
+    /// Whether `player` may join games; always true while no whitelist is enforced
+    #[returns(bool)]
+    IsWhitelisted { player: String },
+    /// Whether `ClaimTimeout` would go through now, and if not, how long until it would
+    #[returns(TimeoutStatusResponse)]
+    GetTimeoutStatus { game_id: u64 },
+    /// Balances, lifetime totals and the newest unsettled games in one call, for monitoring
+    #[returns(DealerDashboardResponse)]
+    GetDealerDashboard {},
+    /// Dealer and player public keys and their sum, so clients need no curve arithmetic
+    #[returns(AggregatedKeyResponse)]
+    GetAggregatedPublicKey { game_id: u64 },
+    /// Joined, unsettled games `EmergencyRefundAll` has yet to refund
+    #[returns(u32)]
+    GetPendingRefunds {},
+    /// Protocol fees collected and not yet claimed, with the fee settings
+    #[returns(ProtocolFeesResponse)]
+    GetProtocolFees {},
+    /// Whether the dealer has paused new games, and since when
+    #[returns(PauseStatusResponse)]
+    GetPauseStatus {},
+    /// A player's settled games by ascending id, including swept ones.
+    /// `limit` is capped at 100.
+    #[returns(SettledGamesResponse)]
+    GetSettledGamesForPlayer {
+        player: String,
+        start_after: Option<u64>,
+        limit: u32,
+    },
+    /// A player's lifetime totals; all zeros for one with no settled games
+    #[returns(PlayerStatsResponse)]
+    GetPlayerStats { player: String },
+    /// Players ranked by `by`, highest first; ties in descending address order.
+    /// `limit` is capped at 100.
+    #[returns(Vec<PlayerStatsResponse>)]
+    GetTopPlayers { by: TopPlayersSort, limit: u32 },
+    /// Everything stored for a game, for debugging stuck ones. Only answered with
+    /// `debug_mode` on. Queries carry no sender, so it can't be limited to the dealer;
+    /// everything in it is readable from contract storage anyway.
+    #[returns(ContractStateResponse)]
+    GetContractState { game_id: u64 },
 }
 
 #[cw_serde]
@@ -118,6 +276,56 @@ pub struct PendingRevealResponse {
     pub dealer_partial: Option<Binary>,
 }
 
+#[cw_serde]
+pub struct PendingReveal {
+    pub card_index: u32,
+    pub player_submitted: bool,
+    pub dealer_submitted: bool,
+}
+
+#[cw_serde]
+pub struct RevealRequestsResponse {
+    /// In the order the contract requested them
+    pub pending: Vec<PendingReveal>,
+}
+
+#[cw_serde]
+pub struct CompletedReveal {
+    pub card_index: u32,
+    pub card_value: u8,
+}
+
+#[cw_serde]
+pub struct RevealStatusResponse {
+    /// Same as `GetRevealRequests`
+    pub pending: Vec<PendingReveal>,
+    /// Ordered by card index
+    pub completed: Vec<CompletedReveal>,
+}
+
+#[cw_serde]
+pub struct SettledGameSummary {
+    pub game_id: u64,
+    /// Everything the player staked: hand bets, including doubles and splits, plus insurance
+    pub bet: Uint128,
+    /// Everything paid back to the player
+    pub payout: Uint128,
+    /// `payout - bet`
+    pub net: Int128,
+    pub outcome: GameOutcome,
+    /// Height at which the game settled
+    pub block_height: u64,
+    /// The dealer's reason, for a forfeited game that gave one
+    pub forfeit_reason: Option<String>,
+}
+
+#[cw_serde]
+pub struct SettledGamesResponse {
+    pub games: Vec<SettledGameSummary>,
+    /// `start_after` for the next page; `None` once there are no more games
+    pub next_cursor: Option<u64>,
+}
+
 #[cw_serde]
 pub struct GameResponse {
     pub player: String,
@@ -130,7 +338,18 @@ pub struct GameResponse {
     pub dealer_pubkey: Binary,
     pub deck: Vec<Binary>,
     pub player_shuffled_deck: Option<Vec<Binary>>,
+    /// SHA-256 of the dealer's shuffled deck as submitted in `CreateGame`
+    pub deck_hash: Binary,
+    /// SHA-256 of the player's re-shuffle, once joined
+    pub player_deck_hash: Option<Binary>,
+    /// Present once insurance has been offered
+    pub insurance: Option<InsuranceBetResponse>,
     pub pending_reveals: Vec<PendingRevealResponse>,
+    /// Block time (seconds) of the last action; for settled games, when they settled
+    pub last_action_timestamp: u64,
+    /// Bet limits, timeout and surrender rule the game plays under; `None` for games
+    /// created before these were fixed per game, which follow the live config
+    pub terms: Option<GameTerms>,
 }
 
 #[cw_serde]
@@ -138,6 +357,151 @@ pub struct HandResponse {
     pub cards: Vec<u8>,
     pub bet: Uint128,
     pub status: String,
+    /// Number of splits in this hand's history; 0 if never split
+    pub split_depth: u8,
+}
+
+#[cw_serde]
+pub struct InsuranceBetResponse {
+    pub offered: bool,
+    pub accepted: bool,
+    pub bet: Uint128,
+    /// The hole card has been revealed and the bet paid or forfeited
+    pub resolved: bool,
+}
+
+#[cw_serde]
+pub struct DealerDashboardResponse {
+    /// Dealer funds held by the contract: `available` plus `locked`
+    pub balance: Uint128,
+    /// Bankroll reserved by unsettled games, i.e. the most they can still pay out
+    pub locked: Uint128,
+    /// Free for new games or withdrawal; the same as `GetDealerBalance`
+    pub available: Uint128,
+    /// Games not yet settled, including ones waiting for a player
+    pub open_games: u32,
+    /// Games ever created
+    pub total_games: u64,
+    /// Total wagered on settled games
+    pub total_volume: Uint128,
+    /// Dealer's net result over settled games; negative when players are ahead
+    pub house_profit: Int128,
+    /// Up to 20 unsettled games, newest first
+    pub active_games: Vec<GameSummary>,
+}
+
+#[cw_serde]
+pub struct GameSummary {
+    pub game_id: u64,
+    pub status: String,
+    pub player: String,
+    pub bet: Uint128,
+    pub seconds_since_action: u64,
+}
+
+#[cw_serde]
+pub enum TopPlayersSort {
+    ByNetProfit,
+    ByGamesPlayed,
+}
+
+/// See `state::PlayerStats`
+#[cw_serde]
+pub struct PlayerStatsResponse {
+    pub player: String,
+    pub games_played: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    pub surrenders: u64,
+    pub blackjacks: u64,
+    pub total_wagered: Uint128,
+    pub net_profit: Int128,
+}
+
+#[cw_serde]
+pub struct ProtocolFeesResponse {
+    pub collected: Uint128,
+    pub fee_bps: u16,
+    pub recipient: Option<String>,
+}
+
+#[cw_serde]
+pub struct PauseStatusResponse {
+    pub paused: bool,
+    /// Block time of the `Pause`; None while not paused
+    pub paused_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct TimeoutStatusResponse {
+    pub can_claim_timeout: bool,
+    /// Seconds left before the timeout can be claimed; None once it can be, or if it never can
+    pub seconds_until_eligible: Option<u64>,
+    /// "player" or "dealer": whoever is holding the game up and loses it on a timeout claim
+    pub timed_out_party: Option<String>,
+}
+
+#[cw_serde]
+pub struct HandValueResponse {
+    pub value: u8,
+    /// An Ace is counted as 11
+    pub is_soft: bool,
+    pub is_busted: bool,
+    /// Two-card 21 on an unsplit hand
+    pub is_blackjack: bool,
+    pub total_cards: u8,
+    pub is_stood: bool,
+    pub is_surrendered: bool,
+}
+
+/// A player decision, as recommended by `CalculateOptimalAction`
+#[cw_serde]
+#[derive(Copy)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl From<blackjack::Action> for Action {
+    fn from(action: blackjack::Action) -> Self {
+        match action {
+            blackjack::Action::Hit => Action::Hit,
+            blackjack::Action::Stand => Action::Stand,
+            blackjack::Action::Double => Action::Double,
+            blackjack::Action::Split => Action::Split,
+            blackjack::Action::Surrender => Action::Surrender,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct OptimalActionResponse {
+    /// Split and surrender are only recommended when the game would accept them
+    pub optimal_action: Action,
+    pub player_value: u8,
+    pub is_soft: bool,
+    /// Card value of the upcard; an Ace is 11
+    pub dealer_upcard: u8,
+    /// Expected value of hitting, in units of the bet. Always null for now: the
+    /// contract only has the strategy table, and f64 can't be used because the wasm
+    /// build rejects float instructions.
+    pub ev_hit: Option<SignedDecimal>,
+    /// Expected value of standing, in units of the bet. Always null, as `ev_hit`.
+    pub ev_stand: Option<SignedDecimal>,
+}
+
+#[cw_serde]
+pub struct ContractStateResponse {
+    /// The game exactly as stored, including the raw deck and every pending partial
+    pub game: GameSession,
+    /// `GameStatus` variant name, without its fields
+    pub phase: String,
+    /// Every card revealed or partially revealed so far, ordered by card index
+    pub reveal_history: Vec<RevealRecord>,
 }
 
 #[cw_serde]
@@ -158,3 +522,19 @@ pub struct GameListItem {
     pub status: String,
     pub bet: Uint128,
 }
+
+#[cw_serde]
+pub struct GamesPageResponse {
+    pub games: Vec<GameListItem>,
+    /// `start_after` for the next page; `None` once there are no more games
+    pub next_cursor: Option<u64>,
+}
+
+#[cw_serde]
+pub struct AggregatedKeyResponse {
+    pub dealer_pk: Binary,
+    /// Present once the player has joined
+    pub player_pk: Option<Binary>,
+    /// `dealer_pk + player_pk`, the key the deck is encrypted under; present once the player has joined
+    pub aggregated_pk: Option<Binary>,
+}
@@ -1,6 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Binary, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Binary, Empty, Int128, StdError, StdResult, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
 #[cw_serde]
 pub struct PayoutRatio {
@@ -41,10 +41,64 @@ pub struct Config {
     pub max_splits: u32,
     pub can_split_aces: bool,
     pub can_hit_split_aces: bool,
+    /// Configs stored before this was configurable allowed it
+    #[serde(default = "default_true")]
+    pub double_after_split: bool,
+    /// Split a second ace drawn to a split ace. Configs stored before this was
+    /// configurable could not resplit anything.
+    #[serde(default)]
+    pub resplit_aces: bool,
+    /// Enables the `GetContractState` debug query
+    #[serde(default)]
+    pub debug_mode: bool,
     pub surrender_allowed: bool,
     pub shuffle_vk_id: String,
     pub reveal_vk_id: String,
     pub timeout_seconds: u64,
+    /// Finished games older than this can be deleted by `BurnExpiredGames`
+    #[serde(default = "default_retain_settled_seconds")]
+    pub retain_settled_seconds: u64,
+    /// Bumped on every UpdateVerificationKeys
+    #[serde(default)]
+    pub vk_version: u32,
+    /// Bumped on every config update; see `CONFIG_HISTORY`
+    #[serde(default)]
+    pub version: u32,
+    /// Basis points of the dealer's profit on each game that go to
+    /// `protocol_fee_recipient`. Configs stored before fees existed charge none.
+    #[serde(default)]
+    pub protocol_fee_bps: u16,
+    #[serde(default)]
+    pub protocol_fee_recipient: Option<Addr>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+pub(crate) fn default_retain_settled_seconds() -> u64 {
+    7 * 24 * 60 * 60
+}
+
+/// The config fields `UpdateConfig` can change, as they stood when a game was created.
+/// The game plays out under these whatever the config says later.
+#[cw_serde]
+pub struct GameTerms {
+    pub min_bet: Uint128,
+    pub max_bet: Uint128,
+    pub timeout_seconds: u64,
+    pub surrender_allowed: bool,
+}
+
+impl GameTerms {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            min_bet: config.min_bet,
+            max_bet: config.max_bet,
+            timeout_seconds: config.timeout_seconds,
+            surrender_allowed: config.surrender_allowed,
+        }
+    }
 }
 
 #[cw_serde]
@@ -67,6 +121,25 @@ pub enum GameStatus {
     Settled {
         winner: String,
     },
+    /// Given up by the dealer with `ForfeitGame`; the player was paid their stake
+    /// plus the most they could have won
+    Forfeited {
+        reason: Option<String>,
+    },
+    /// Voided by `EmergencyRefundAll`; the player got back everything they staked
+    EmergencyRefunded,
+}
+
+impl GameStatus {
+    /// Settled, forfeited or refunded: the game is over and its funds are paid out.
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            GameStatus::Settled { .. }
+                | GameStatus::Forfeited { .. }
+                | GameStatus::EmergencyRefunded
+        )
+    }
 }
 
 #[cw_serde]
@@ -88,7 +161,36 @@ pub struct GameSession {
     pub last_card_index: u32,
     pub pending_reveals: Vec<PendingReveal>, // Track partial decryptions from both parties
     pub dealer_peeked: bool,
-    pub insurance_bet: Option<Uint128>,
+    /// Set when insurance is offered
+    pub insurance: Option<InsuranceBet>,
+    /// SHA-256 of the dealer's submitted shuffle (see `contract::deck_hash`)
+    pub deck_hash: Binary,
+    /// SHA-256 of the player's re-shuffle, set on join
+    pub player_deck_hash: Option<Binary>,
+    /// Set on creation; games stored before `UpdateConfig` existed follow the live config
+    #[serde(default)]
+    pub terms: Option<GameTerms>,
+}
+
+impl GameSession {
+    /// Insurance the player paid in; zero if it was never offered or was declined.
+    pub fn insurance_stake(&self) -> Uint128 {
+        self.insurance
+            .as_ref()
+            .filter(|ins| ins.accepted)
+            .map_or(Uint128::zero(), |ins| ins.bet)
+    }
+}
+
+/// Insurance side bet. It is resolved by the dealer's hole card: paid at
+/// `insurance_payout` on a dealer blackjack, otherwise forfeited to the dealer.
+#[cw_serde]
+#[derive(Default)]
+pub struct InsuranceBet {
+    pub offered: bool,
+    pub accepted: bool,
+    pub bet: Uint128,
+    pub resolved: bool,
 }
 
 #[cw_serde]
@@ -96,6 +198,9 @@ pub struct Hand {
     pub cards: Vec<u8>,
     pub bet: Uint128,
     pub status: HandStatus,
+    /// Number of splits in this hand's history
+    #[serde(default)]
+    pub split_depth: u8,
 }
 
 #[cw_serde]
@@ -115,8 +220,202 @@ pub struct PendingReveal {
     pub dealer_partial: Option<Binary>,
 }
 
+/// Audit record for one revealed (or partially revealed) card
+#[cw_serde]
+pub struct RevealRecord {
+    pub card_index: u32,
+    pub player_partial: Option<Binary>,
+    pub dealer_partial: Option<Binary>,
+    /// Combination of both partials as computed by the contract. The contract currently
+    /// combines the first byte of each partial, so this is that single byte.
+    pub combined_result: Option<Binary>,
+    /// Card index (0-51) derived from `combined_result`
+    pub revealed_value: Option<u8>,
+    /// Height of the latest partial submission for this card
+    pub block_height: u64,
+}
+
+/// How a settled game went for the player, as a whole
+#[cw_serde]
+#[derive(Copy)]
+pub enum GameOutcome {
+    Win,
+    Loss,
+    Push,
+    /// A net win with at least one hand settled as a natural
+    Blackjack,
+    Surrender,
+    /// Voided by `EmergencyRefundAll`
+    Refunded,
+    /// Paid out by the dealer's `ForfeitGame`; a win, but not one the player played out
+    Forfeit,
+}
+
+/// A player's settled game in `PLAYER_SETTLED_GAMES`
+#[cw_serde]
+pub struct SettledGame {
+    /// Everything the player staked: hand bets, including doubles and splits, plus insurance
+    pub bet: Uint128,
+    /// Everything paid back to the player
+    pub payout: Uint128,
+    pub outcome: GameOutcome,
+    /// Height at which the game settled
+    pub block_height: u64,
+    /// The dealer's reason, for a forfeited game that gave one
+    #[serde(default)]
+    pub forfeit_reason: Option<String>,
+}
+
+/// One config field changed by an update
+#[cw_serde]
+pub struct ConfigChange {
+    /// `Config::version` after the update
+    pub version: u32,
+    pub changed_by: Addr,
+    pub timestamp: u64,
+    pub field: String,
+    pub old_value: String,
+    pub new_value: String,
+}
+
+/// Lifetime totals over settled games
+#[cw_serde]
+#[derive(Default)]
+pub struct HouseStats {
+    /// Everything players have wagered: hand bets, including doubles and splits, plus insurance
+    pub total_volume: Uint128,
+    /// What the dealer got back, after protocol fees, minus the bankroll it locked,
+    /// summed over games
+    pub house_profit: Int128,
+}
+
+/// Lifetime totals for one player over their settled games. Every game counts once
+/// in `wins`, `losses`, `pushes` or `surrenders`; `blackjacks` is the part of `wins`
+/// won with a natural. Games voided by `EmergencyRefundAll` aren't counted.
+#[cw_serde]
+#[derive(Default)]
+pub struct PlayerStats {
+    pub games_played: u64,
+    /// Includes blackjacks and forfeits paid out by the dealer
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    pub surrenders: u64,
+    pub blackjacks: u64,
+    /// Hand bets, including doubles and splits, plus insurance
+    pub total_wagered: Uint128,
+    /// Everything paid back minus `total_wagered`
+    pub net_profit: Int128,
+}
+
+impl PlayerStats {
+    /// Adds one settled game. Refunded games leave the stats unchanged.
+    pub fn record(&mut self, game: &SettledGame) -> StdResult<()> {
+        let counter = match game.outcome {
+            GameOutcome::Win | GameOutcome::Forfeit => &mut self.wins,
+            GameOutcome::Blackjack => {
+                self.blackjacks += 1;
+                &mut self.wins
+            }
+            GameOutcome::Loss => &mut self.losses,
+            GameOutcome::Push => &mut self.pushes,
+            GameOutcome::Surrender => &mut self.surrenders,
+            GameOutcome::Refunded => return Ok(()),
+        };
+        *counter += 1;
+        self.games_played += 1;
+
+        let overflow = |e: cosmwasm_std::OverflowError| StdError::msg(e.to_string());
+        let as_signed =
+            |amount: Uint128| Int128::try_from(amount).map_err(|e| StdError::msg(e.to_string()));
+        self.total_wagered = self.total_wagered.checked_add(game.bet).map_err(overflow)?;
+        let net = as_signed(game.payout)?
+            .checked_sub(as_signed(game.bet)?)
+            .map_err(overflow)?;
+        self.net_profit = self.net_profit.checked_add(net).map_err(overflow)?;
+        Ok(())
+    }
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
+/// The latest `CONFIG_HISTORY_LIMIT` config changes, oldest first
+pub const CONFIG_HISTORY: Item<Vec<ConfigChange>> = Item::new("config_history");
+pub const CONFIG_HISTORY_LIMIT: usize = 100;
 pub const GAME_COUNTER: Item<u64> = Item::new("game_counter");
 pub const GAMES: Map<u64, GameSession> = Map::new("games");
+/// Joined games that have not finished yet. Added on join, removed at settlement, so
+/// checks for games in progress don't have to read every game ever created.
+pub const ACTIVE_GAMES: Map<u64, Empty> = Map::new("active_games");
+/// Games waiting for a player. Added on create, removed on join or cancel.
+pub const WAITING_GAMES: Map<u64, Empty> = Map::new("waiting_games");
 pub const DEALER: Item<Addr> = Item::new("dealer");
 pub const DEALER_BALANCE: Item<Uint128> = Item::new("dealer_balance");
+/// Missing on contracts instantiated before it existed; treat that as all zeros
+pub const HOUSE_STATS: Item<HouseStats> = Item::new("house_stats");
+/// Players allowed to join games while `WHITELIST_ENABLED` is set
+pub const WHITELIST: Map<&Addr, Empty> = Map::new("whitelist");
+/// Turned on by a non-empty `whitelist` at instantiation or by `AddToWhitelist`.
+/// Stays on after `ClearWhitelist`, which then locks every player out.
+pub const WHITELIST_ENABLED: Item<bool> = Item::new("whitelist_enabled");
+/// Set by the dealer's `Pause`; no games can be created or joined while true
+pub const PAUSED: Item<bool> = Item::new("paused");
+/// Block time of the `Pause` that started the current pause; removed by `Unpause`
+pub const PAUSED_AT: Item<u64> = Item::new("paused_at");
+/// Reveal history keyed by (game_id, card_index); removed together with the game
+pub const REVEAL_HISTORY: Map<(u64, u32), RevealRecord> = Map::new("reveal_history");
+/// Settled games by (player, game_id). Kept when `SweepSettled` removes the game itself.
+pub const PLAYER_SETTLED_GAMES: Map<(&Addr, u64), SettledGame> = Map::new("player_settled_games");
+/// Protocol fees taken at settlement and not yet claimed; missing means zero
+pub const PROTOCOL_FEES: Item<Uint128> = Item::new("protocol_fees_collected");
+
+/// Orderings `GetTopPlayers` ranks by
+pub struct PlayerStatsIndexes<'a> {
+    pub net_profit: MultiIndex<'a, i128, PlayerStats, &'a Addr>,
+    pub games_played: MultiIndex<'a, u64, PlayerStats, &'a Addr>,
+}
+
+impl IndexList<PlayerStats> for PlayerStatsIndexes<'_> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<PlayerStats>> + '_> {
+        let v: Vec<&dyn Index<PlayerStats>> = vec![&self.net_profit, &self.games_played];
+        Box::new(v.into_iter())
+    }
+}
+
+/// Per-player totals, updated with `PLAYER_SETTLED_GAMES` at settlement
+pub fn player_stats<'a>() -> IndexedMap<&'a Addr, PlayerStats, PlayerStatsIndexes<'a>> {
+    let indexes = PlayerStatsIndexes {
+        net_profit: MultiIndex::new(
+            |_, stats| stats.net_profit.i128(),
+            "player_stats",
+            "player_stats__net_profit",
+        ),
+        games_played: MultiIndex::new(
+            |_, stats| stats.games_played,
+            "player_stats",
+            "player_stats__games_played",
+        ),
+    };
+    IndexedMap::new("player_stats", indexes)
+}
+
+/// How far migrate's rebuild of player stats from `PLAYER_SETTLED_GAMES` has got.
+/// Removed once every game has been counted.
+#[cw_serde]
+pub struct StatsBackfill {
+    /// Games settled from this height on were counted at settlement
+    pub before_height: u64,
+    /// Last settled game read so far
+    pub last: Option<(Addr, u64)>,
+}
+
+pub const STATS_BACKFILL: Item<StatsBackfill> = Item::new("stats_backfill");
+
+/// How far migrate's rebuild of `ACTIVE_GAMES` and `WAITING_GAMES` from `GAMES` has
+/// got. Removed once every game has been read.
+#[cw_serde]
+pub struct ActiveGamesBackfill {
+    /// Last game id read so far
+    pub last: Option<u64>,
+}
+
+pub const ACTIVE_GAMES_BACKFILL: Item<ActiveGamesBackfill> = Item::new("active_games_backfill");
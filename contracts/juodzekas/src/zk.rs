@@ -1,8 +1,20 @@
-use cosmwasm_std::{Binary, Deps, StdResult};
+use crate::error::ContractError;
+use ark_ec::twisted_edwards::Affine;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use cosmwasm_std::{Binary, Deps, StdError, StdResult};
 use prost::Message;
+use taceo_ark_babyjubjub::EdwardsConfig;
 use xion_types::traits::MessageExt;
 use xion_types::xion::zk::v1::{ProofVerifyResponse, QueryVerifyRequest};
 
+/// `xion.zk.v1.QueryVKeyByNameRequest`
+#[derive(Clone, PartialEq, prost::Message)]
+struct QueryVKeyByNameRequest {
+    #[prost(string, tag = "1")]
+    name: String,
+}
+
 pub fn xion_zk_verify(
     deps: Deps,
     vkey_name: &str,
@@ -24,3 +36,70 @@ pub fn xion_zk_verify(
 
     Ok(verify_response.verified)
 }
+
+/// Whether `vkey_name` is registered in the ZK module. The lookup fails with a
+/// "not found" error for unknown names; any other failure is returned as is.
+pub fn xion_zk_vkey_registered(deps: Deps, vkey_name: &str) -> StdResult<bool> {
+    let request = QueryVKeyByNameRequest {
+        name: vkey_name.to_string(),
+    };
+    match deps.querier.query_grpc(
+        String::from("/xion.zk.v1.Query/VKeyByName"),
+        Binary::new(request.encode_to_vec()),
+    ) {
+        Ok(_) => Ok(true),
+        Err(e) if e.to_string().to_lowercase().contains("not found") => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// BabyJubJub point in the circuits' (circom) form, as used by zk-shuffle
+pub type Point = Affine<EdwardsConfig>;
+
+/// Validation for points received in messages. Mirrors `zk_shuffle::babyjubjub::PointExt`,
+/// which can't be linked into the contract since zk-shuffle pulls in the prover.
+pub trait PointExt: Sized {
+    /// Deserializes a compressed point and rejects it unless it lies on the curve and
+    /// in the prime-order subgroup, with no bytes left over.
+    fn from_bytes_validated(bytes: &[u8]) -> Result<Self, ContractError>;
+}
+
+impl PointExt for Point {
+    fn from_bytes_validated(bytes: &[u8]) -> Result<Self, ContractError> {
+        let invalid = |reason: String| ContractError::InvalidPublicKey { reason };
+        let mut cursor = bytes;
+        let point = Point::deserialize_compressed_unchecked(&mut cursor)
+            .map_err(|e| invalid(e.to_string()))?;
+        if !cursor.is_empty() {
+            return Err(invalid(format!(
+                "{} trailing bytes after point",
+                cursor.len()
+            )));
+        }
+        if !point.is_on_curve() {
+            return Err(invalid("point is not on the BabyJubJub curve".to_string()));
+        }
+        if !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(invalid(
+                "point is not in the prime-order subgroup".to_string(),
+            ));
+        }
+        Ok(point)
+    }
+}
+
+/// `dealer_pk + player_pk`, the key the deck is encrypted under. Both keys and
+/// the result are compressed points.
+pub fn aggregate_public_keys(dealer_pk: &[u8], player_pk: &[u8]) -> StdResult<Binary> {
+    let aggregated =
+        (decode_point(dealer_pk)?.into_group() + decode_point(player_pk)?).into_affine();
+    let mut bytes = Vec::new();
+    aggregated
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| StdError::msg(format!("Failed to serialize aggregated key: {e}")))?;
+    Ok(Binary::new(bytes))
+}
+
+fn decode_point(bytes: &[u8]) -> StdResult<Point> {
+    Point::from_bytes_validated(bytes).map_err(|e| StdError::msg(e.to_string()))
+}
@@ -1,3 +1,4 @@
+use ark_serialize::CanonicalSerialize;
 use cosmwasm_std::testing::{MockApi, MockStorage};
 use cosmwasm_std::{Addr, AnyMsg, Binary, Coin, Empty, GrpcQuery, Uint128};
 use cw_multi_test::{
@@ -7,6 +8,17 @@ use cw_multi_test::{
 use juodzekas::msg::{ExecuteMsg, InstantiateMsg};
 use juodzekas::state::{DoubleRestriction, PayoutRatio};
 use prost::Message;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use zk_shuffle::elgamal::KeyPair;
+
+/// Compressed public key of the keypair drawn from `seed`
+fn public_key(seed: u64) -> Binary {
+    let keys = KeyPair::generate(&mut ChaCha8Rng::seed_from_u64(seed));
+    let mut bytes = Vec::new();
+    keys.pk.serialize_compressed(&mut bytes).unwrap();
+    Binary::new(bytes)
+}
 
 type TestApp = App<
     BankKeeper,
@@ -182,10 +194,17 @@ fn test_two_party_basic_game() {
                 max_splits: 3,
                 can_split_aces: true,
                 can_hit_split_aces: false,
+                double_after_split: None,
+                resplit_aces: None,
+                debug_mode: None,
                 surrender_allowed: true,
                 shuffle_vk_id: "test_shuffle".to_string(),
                 reveal_vk_id: "test_reveal".to_string(),
                 timeout_seconds: None,
+                retain_settled_seconds: None,
+                whitelist: None,
+                protocol_fee_bps: None,
+                protocol_fee_recipient: None,
             },
             &[],
             "juodzekas",
@@ -201,7 +220,7 @@ fn test_two_party_basic_game() {
             dealer.clone(),
             contract_addr.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dealer_pubkey"),
+                public_key: public_key(1),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"dealer_shuffle_proof"),
                 public_inputs: vec![],
@@ -225,7 +244,7 @@ fn test_two_party_basic_game() {
         contract_addr.clone(),
         &ExecuteMsg::JoinGame {
             bet: Uint128::new(1000),
-            public_key: Binary::from(b"player_pubkey"),
+            public_key: public_key(2),
             shuffled_deck: game.player_shuffled_deck(),
             proof: Binary::from(b"player_shuffle_proof"),
             public_inputs: vec![],
@@ -397,10 +416,17 @@ fn test_two_party_player_busts() {
                 max_splits: 3,
                 can_split_aces: true,
                 can_hit_split_aces: false,
+                double_after_split: None,
+                resplit_aces: None,
+                debug_mode: None,
                 surrender_allowed: true,
                 shuffle_vk_id: "test_shuffle".to_string(),
                 reveal_vk_id: "test_reveal".to_string(),
                 timeout_seconds: None,
+                retain_settled_seconds: None,
+                whitelist: None,
+                protocol_fee_bps: None,
+                protocol_fee_recipient: None,
             },
             &[],
             "juodzekas",
@@ -416,7 +442,7 @@ fn test_two_party_player_busts() {
             dealer.clone(),
             contract_addr.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dealer_pubkey"),
+                public_key: public_key(1),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"dealer_shuffle_proof"),
                 public_inputs: vec![],
@@ -440,7 +466,7 @@ fn test_two_party_player_busts() {
         contract_addr.clone(),
         &ExecuteMsg::JoinGame {
             bet: Uint128::new(500),
-            public_key: Binary::from(b"player_pubkey"),
+            public_key: public_key(2),
             shuffled_deck: game.player_shuffled_deck(),
             proof: Binary::from(b"player_shuffle_proof"),
             public_inputs: vec![],
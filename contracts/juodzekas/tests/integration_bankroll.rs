@@ -1,17 +1,33 @@
 //! Integration tests for bankroll management, settlement accounting, and game action flows.
 //! Uses cw-multi-test with mocked ZK verification (always passes).
 
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::CanonicalSerialize;
 use cosmwasm_std::testing::{MockApi, MockStorage};
-use cosmwasm_std::{Addr, AnyMsg, Binary, Coin, Empty, GrpcQuery, Uint128};
+use cosmwasm_std::{
+    Addr, AnyMsg, Binary, Coin, Empty, GrpcQuery, Int128, Order, StdResult, Uint128,
+};
 use cw_multi_test::{
     App, AppBuilder, AppResponse, BankKeeper, ContractWrapper, DistributionKeeper, Executor,
     FailingModule, GovFailingModule, IbcFailingModule, StakeKeeper, Stargate, WasmKeeper,
 };
+use juodzekas::contract::deck_hash;
+use juodzekas::game_logic::config_to_rules;
 use juodzekas::msg::{
-    DealerBalanceResponse, DealerResponse, ExecuteMsg, GameResponse, InstantiateMsg, QueryMsg,
+    Action, AggregatedKeyResponse, CompletedReveal, ContractStateResponse, DealerBalanceResponse,
+    DealerDashboardResponse, DealerResponse, ExecuteMsg, GameResponse, GamesPageResponse,
+    HandValueResponse, InstantiateMsg, MigrateMsg, OptimalActionResponse, PauseStatusResponse,
+    PendingReveal, PlayerStatsResponse, ProtocolFeesResponse, QueryMsg, RevealItem, RevealRecord,
+    RevealRequestsResponse, RevealStatusResponse, SettledGamesResponse, TimeoutStatusResponse,
+    TopPlayersSort,
 };
-use juodzekas::state::{DoubleRestriction, PayoutRatio};
+use juodzekas::state::{Config, ConfigChange, DoubleRestriction, GameOutcome, PayoutRatio};
 use prost::Message;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::cell::Cell;
+use std::rc::Rc;
+use zk_shuffle::elgamal::KeyPair;
 
 // ---------------------------------------------------------------------------
 // Test infrastructure (ZK mock, helpers)
@@ -27,7 +43,7 @@ type TestApp = App<
     DistributionKeeper,
     IbcFailingModule,
     GovFailingModule,
-    ZkMockStargate,
+    ZkConditionalStargate,
 >;
 
 #[derive(Clone, Copy, PartialEq, prost::Message)]
@@ -36,9 +52,92 @@ struct ProofVerifyResponse {
     verified: bool,
 }
 
-struct ZkMockStargate;
+#[derive(Clone, PartialEq, prost::Message)]
+struct QueryVerifyRequest {
+    #[prost(bytes = "vec", tag = "1")]
+    proof: Vec<u8>,
+    #[prost(string, repeated, tag = "2")]
+    public_inputs: Vec<String>,
+    #[prost(string, tag = "3")]
+    vkey_name: String,
+    #[prost(uint64, tag = "4")]
+    vkey_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct QueryVKeyByNameRequest {
+    #[prost(string, tag = "1")]
+    name: String,
+}
+
+const SHUFFLE_VK_ID: &str = "shuffle_encrypt";
+const REVEAL_VK_ID: &str = "decrypt";
+/// Keys the mock ZK module knows besides the two above
+const V2_SHUFFLE_VK_ID: &str = "shuffle_encrypt_v2";
+const V2_REVEAL_VK_ID: &str = "decrypt_v2";
+
+/// ZK verifier mock. Accepts every proof by default; shuffle or reveal proofs
+/// (told apart by verification key name) can be set to fail, and key lookups can be
+/// made to fail as if the module were unreachable. The flags are shared with clones,
+/// so a test can keep a handle and flip them mid-game.
+#[derive(Clone, Default)]
+struct ZkConditionalStargate {
+    reject_shuffle: Rc<Cell<bool>>,
+    reject_reveal: Rc<Cell<bool>>,
+    vkey_lookup_down: Rc<Cell<bool>>,
+}
+
+impl ZkConditionalStargate {
+    /// Verifier that rejects every proof
+    fn rejecting() -> Self {
+        let verifier = Self::default();
+        verifier.reject_shuffle.set(true);
+        verifier.reject_reveal.set(true);
+        verifier
+    }
+
+    fn respond(&self, path: &str, data: &Binary) -> cosmwasm_std::StdResult<Binary> {
+        if path == "/xion.zk.v1.Query/VKeyByName" {
+            if self.vkey_lookup_down.get() {
+                return Err(cosmwasm_std::StdError::msg("zk module unavailable"));
+            }
+            return Self::vkey_response(data);
+        }
+        self.verify_response(data)
+    }
+
+    fn vkey_response(data: &Binary) -> cosmwasm_std::StdResult<Binary> {
+        let request = QueryVKeyByNameRequest::decode(data.as_slice())
+            .map_err(|e| cosmwasm_std::StdError::msg(e.to_string()))?;
+        match request.name.as_str() {
+            SHUFFLE_VK_ID | REVEAL_VK_ID | V2_SHUFFLE_VK_ID | V2_REVEAL_VK_ID => {
+                Ok(Binary::default())
+            }
+            name => Err(cosmwasm_std::StdError::msg(format!(
+                "vkey {name} not found"
+            ))),
+        }
+    }
+
+    fn verify_response(&self, data: &Binary) -> cosmwasm_std::StdResult<Binary> {
+        let request = QueryVerifyRequest::decode(data.as_slice())
+            .map_err(|e| cosmwasm_std::StdError::msg(e.to_string()))?;
+        let rejected = match request.vkey_name.as_str() {
+            SHUFFLE_VK_ID => self.reject_shuffle.get(),
+            REVEAL_VK_ID => self.reject_reveal.get(),
+            _ => false,
+        };
+        let mut buf = Vec::new();
+        ProofVerifyResponse {
+            verified: !rejected,
+        }
+        .encode(&mut buf)
+        .unwrap();
+        Ok(Binary::from(buf))
+    }
+}
 
-impl Stargate for ZkMockStargate {
+impl Stargate for ZkConditionalStargate {
     fn execute_stargate<ExecC, QueryC>(
         &self,
         _api: &dyn cosmwasm_std::Api,
@@ -62,14 +161,10 @@ impl Stargate for ZkMockStargate {
         _storage: &dyn cosmwasm_std::Storage,
         _querier: &dyn cosmwasm_std::Querier,
         _block: &cosmwasm_std::BlockInfo,
-        _path: String,
-        _data: Binary,
+        path: String,
+        data: Binary,
     ) -> cosmwasm_std::StdResult<Binary> {
-        let mut buf = Vec::new();
-        ProofVerifyResponse { verified: true }
-            .encode(&mut buf)
-            .unwrap();
-        Ok(Binary::from(buf))
+        self.respond(&path, &data)
     }
 
     fn execute_any<ExecC, QueryC>(
@@ -94,13 +189,9 @@ impl Stargate for ZkMockStargate {
         _storage: &dyn cosmwasm_std::Storage,
         _querier: &dyn cosmwasm_std::Querier,
         _block: &cosmwasm_std::BlockInfo,
-        _request: GrpcQuery,
+        request: GrpcQuery,
     ) -> cosmwasm_std::StdResult<Binary> {
-        let mut buf = Vec::new();
-        ProofVerifyResponse { verified: true }
-            .encode(&mut buf)
-            .unwrap();
-        Ok(Binary::from(buf))
+        self.respond(&request.path, &request.data)
     }
 }
 
@@ -132,6 +223,26 @@ impl SeededGame {
     }
 }
 
+fn point_bytes(point: &zk_shuffle::babyjubjub::Point) -> Binary {
+    let mut bytes = Vec::new();
+    point.serialize_compressed(&mut bytes).unwrap();
+    Binary::new(bytes)
+}
+
+/// Compressed public key of the keypair drawn from `seed`. Create and join reject
+/// anything that is not a valid BabyJubJub point.
+fn public_key(seed: u64) -> Binary {
+    point_bytes(&KeyPair::generate(&mut ChaCha8Rng::seed_from_u64(seed)).pk)
+}
+
+fn dealer_pk() -> Binary {
+    public_key(1)
+}
+
+fn player_pk() -> Binary {
+    public_key(2)
+}
+
 struct TestEnv {
     app: TestApp,
     contract: Addr,
@@ -162,10 +273,17 @@ fn default_instantiate_msg() -> InstantiateMsg {
         max_splits: 3,
         can_split_aces: true,
         can_hit_split_aces: false,
+        double_after_split: None,
+        resplit_aces: None,
+        debug_mode: None,
         surrender_allowed: true,
-        shuffle_vk_id: "test".to_string(),
-        reveal_vk_id: "test".to_string(),
+        shuffle_vk_id: SHUFFLE_VK_ID.to_string(),
+        reveal_vk_id: REVEAL_VK_ID.to_string(),
         timeout_seconds: Some(60),
+        retain_settled_seconds: None,
+        whitelist: None,
+        protocol_fee_bps: None,
+        protocol_fee_recipient: None,
     }
 }
 
@@ -175,31 +293,48 @@ fn setup() -> TestEnv {
 }
 
 fn setup_with_bankroll(initial_bankroll: u128) -> TestEnv {
+    setup_with_msg(default_instantiate_msg(), initial_bankroll)
+}
+
+fn setup_with_msg(msg: InstantiateMsg, initial_bankroll: u128) -> TestEnv {
+    setup_with_verifier(msg, initial_bankroll, ZkConditionalStargate::default())
+}
+
+fn setup_with_verifier(
+    msg: InstantiateMsg,
+    initial_bankroll: u128,
+    verifier: ZkConditionalStargate,
+) -> TestEnv {
     let api = MockApi::default();
     let dealer = api.addr_make("dealer");
     let player = api.addr_make("player");
-
-    let mut app: TestApp = AppBuilder::new_custom()
-        .with_stargate(ZkMockStargate)
-        .build(|router, _api, storage| {
-            router
-                .bank
-                .init_balance(storage, &dealer, vec![Coin::new(10_000_000u128, "utoken")])
-                .unwrap();
-            router
-                .bank
-                .init_balance(storage, &player, vec![Coin::new(1_000_000u128, "utoken")])
-                .unwrap();
-        });
-
-    let code_id = app.store_code(Box::new(ContractWrapper::new(
-        juodzekas::contract::execute,
-        juodzekas::contract::instantiate,
-        juodzekas::contract::query,
-    )));
+    let denom = msg.denom.clone();
+
+    let mut app: TestApp =
+        AppBuilder::new_custom()
+            .with_stargate(verifier)
+            .build(|router, _api, storage| {
+                router
+                    .bank
+                    .init_balance(storage, &dealer, vec![Coin::new(10_000_000u128, &denom)])
+                    .unwrap();
+                router
+                    .bank
+                    .init_balance(storage, &player, vec![Coin::new(1_000_000u128, &denom)])
+                    .unwrap();
+            });
+
+    let code_id = app.store_code(Box::new(
+        ContractWrapper::new(
+            juodzekas::contract::execute,
+            juodzekas::contract::instantiate,
+            juodzekas::contract::query,
+        )
+        .with_migrate(juodzekas::contract::migrate),
+    ));
 
     let funds: Vec<Coin> = if initial_bankroll > 0 {
-        vec![Coin::new(initial_bankroll, "utoken")]
+        vec![Coin::new(initial_bankroll, &denom)]
     } else {
         vec![]
     };
@@ -208,7 +343,7 @@ fn setup_with_bankroll(initial_bankroll: u128) -> TestEnv {
         .instantiate_contract(
             code_id,
             dealer.clone(),
-            &default_instantiate_msg(),
+            &msg,
             &funds,
             "juodzekas",
             Some(dealer.to_string()),
@@ -265,7 +400,7 @@ fn create_and_deal(
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -282,7 +417,7 @@ fn create_and_deal(
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(bet),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -481,6 +616,68 @@ fn test_blackjack_payout() {
     assert_eq!(bal, Uint128::new(98_500));
 }
 
+// ===== Settlement rejects a payout its stake doesn't account for =====
+#[test]
+fn test_payout_check_rejects_bad_stake() {
+    let mut env = setup();
+    let game = SeededGame::new(1055);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 8, 5);
+
+    // A stake that is neither the bet nor a double of it
+    let mut key = b"\x00\x05games".to_vec();
+    key.extend_from_slice(&game_id.to_be_bytes());
+    let mut stored = raw_state(&env, &key).unwrap();
+    stored["hands"][0]["bet"] = "1500".into();
+    env.app
+        .contract_storage_mut(&env.contract)
+        .set(&key, &serde_json::to_vec(&stored).unwrap());
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap();
+    reveal_card(&mut env, &game, game_id, 3, 9);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 4,
+                partial_decryption: game.player_partial(4),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    // Dealer busts on 26, which settles the hand as a win on the wrong stake
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 4,
+                partial_decryption: game.dealer_partial(4, 9),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Hand 0 settled as Win should return 2000, not 3000"),
+        "{err}"
+    );
+}
+
 // ===== Surrender =====
 #[test]
 fn test_surrender() {
@@ -856,7 +1053,7 @@ fn test_create_game_with_predeposited_balance() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game2.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -949,7 +1146,7 @@ fn test_create_game_insufficient_bankroll() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -981,7 +1178,7 @@ fn test_multiple_games_balance_accumulation() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game2.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -999,7 +1196,7 @@ fn test_multiple_games_balance_accumulation() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(bet),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game2.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1122,7 +1319,7 @@ fn test_authorization_wrong_sender() {
             stranger.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1189,7 +1386,7 @@ fn test_multiple_concurrent_games() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game1.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1206,7 +1403,7 @@ fn test_multiple_concurrent_games() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game2.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1236,7 +1433,7 @@ fn test_multiple_concurrent_games() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(bet),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game1.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1252,7 +1449,7 @@ fn test_multiple_concurrent_games() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(bet),
-                public_key: Binary::from(b"ppk2"),
+                public_key: public_key(3),
                 shuffled_deck: game2.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1380,7 +1577,7 @@ fn test_auto_join_picks_first() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game1.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1397,7 +1594,7 @@ fn test_auto_join_picks_first() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game2.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1415,7 +1612,7 @@ fn test_auto_join_picks_first() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(1000),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game1.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1511,7 +1708,7 @@ fn test_cancel_game_success() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1575,7 +1772,7 @@ fn test_cancel_game_not_dealer() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1611,7 +1808,7 @@ fn test_cancel_then_create() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game1.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1629,7 +1826,7 @@ fn test_cancel_then_create() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game2.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1655,7 +1852,7 @@ fn test_cancel_then_create() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game2.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1733,7 +1930,7 @@ fn test_withdraw_blocked_with_waiting_games() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1868,7 +2065,7 @@ fn test_create_game_invalid_deck_length() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: short_deck,
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1891,7 +2088,7 @@ fn test_join_game_invalid_deck_length() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1911,7 +2108,7 @@ fn test_join_game_invalid_deck_length() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(1000),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: short_deck,
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -1929,7 +2126,7 @@ fn test_instantiate_zero_denominator_rejected() {
     let dealer = api.addr_make("dealer");
 
     let mut app: TestApp = AppBuilder::new_custom()
-        .with_stargate(ZkMockStargate)
+        .with_stargate(ZkConditionalStargate::default())
         .build(|router, _api, storage| {
             router
                 .bank
@@ -1969,7 +2166,7 @@ fn test_instantiate_min_exceeds_max_rejected() {
     let dealer = api.addr_make("dealer");
 
     let mut app: TestApp = AppBuilder::new_custom()
-        .with_stargate(ZkMockStargate)
+        .with_stargate(ZkConditionalStargate::default())
         .build(|router, _api, storage| {
             router
                 .bank
@@ -2099,6 +2296,7 @@ fn test_split_cards_route_correctly() {
 
     // Player: 8+8 (pair), Dealer shows 6
     let game_id = create_and_deal(&mut env, &game, bet, 7, 7, 5);
+    assert_eq!(query_game(&env, game_id).hands[0].split_depth, 0);
 
     // Split
     env.app
@@ -2119,6 +2317,9 @@ fn test_split_cards_route_correctly() {
     assert_eq!(g.hands[0].cards.len(), 2, "Hand 0 should have 2 cards");
     // Hand 1: [8, 2] = 2 cards
     assert_eq!(g.hands[1].cards.len(), 2, "Hand 1 should have 2 cards");
+    // Both halves record one split
+    assert_eq!(g.hands[0].split_depth, 1);
+    assert_eq!(g.hands[1].split_depth, 1);
 }
 
 // ===== Busted hand stays busted in split (not overwritten by Stand) =====
@@ -2404,7 +2605,7 @@ fn test_join_game_rejects_excess_funds() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -2421,7 +2622,7 @@ fn test_join_game_rejects_excess_funds() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(1000),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -2504,19 +2705,10 @@ fn test_split_hand_21_not_blackjack() {
 
     // Reveal split cards: hand 0 gets 10 (Ace+10=21), hand 1 gets 10 (Ace+10=21)
     // 10 = card value 9 (rank 9%13+1 = 10)
-    reveal_card(&mut env, &game, game_id, 4, 9); // hand 0: A+10=21, auto-advances to hand 1
+    reveal_card(&mut env, &game, game_id, 4, 9); // hand 0: A+10=21
     reveal_card(&mut env, &game, game_id, 5, 9); // hand 1: A+10=21
 
-    // Hand 0 auto-stood at 21. Hand 1 also has 21 but needs explicit stand.
-    env.app
-        .execute_contract(
-            env.player.clone(),
-            env.contract.clone(),
-            &ExecuteMsg::Stand { game_id },
-            &[],
-        )
-        .unwrap();
-
+    // Hand 0 auto-stood at 21, hand 1 as a split ace; straight to the dealer
     // Dealer turn: 6 + hole(10) = 16, hits, gets 5 = 21
     reveal_card(&mut env, &game, game_id, 3, 9); // hole=10, total 16
     reveal_card(&mut env, &game, game_id, 6, 4); // hit=5, total 21
@@ -2557,6 +2749,10 @@ fn test_stand_updates_timestamp() {
             &[],
         )
         .unwrap();
+    assert_eq!(
+        query_game(&env, game_id).last_action_timestamp,
+        env.app.block_info().time.seconds()
+    );
 
     // Check that the timeout window starts from AFTER stand, not from the initial deal
     // Advance by 50s (less than 60s timeout) — timeout should NOT be claimable
@@ -3150,7 +3346,7 @@ fn test_player_shuffled_deck_cleared_after_join() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3174,7 +3370,7 @@ fn test_player_shuffled_deck_cleared_after_join() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(bet),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3207,7 +3403,7 @@ fn test_dealer_cannot_join_own_game() {
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3224,7 +3420,7 @@ fn test_dealer_cannot_join_own_game() {
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(1000),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3246,7 +3442,7 @@ fn test_instantiate_zero_timeout_rejected() {
     let dealer = api.addr_make("dealer");
 
     let mut app: TestApp = AppBuilder::new_custom()
-        .with_stargate(ZkMockStargate)
+        .with_stargate(ZkConditionalStargate::default())
         .build(|router, _api, storage| {
             router
                 .bank
@@ -3346,7 +3542,7 @@ fn setup_with_peek() -> TestEnv {
     let player = api.addr_make("player");
 
     let mut app: TestApp = AppBuilder::new_custom()
-        .with_stargate(ZkMockStargate)
+        .with_stargate(ZkConditionalStargate::default())
         .build(|router, _api, storage| {
             router
                 .bank
@@ -3403,7 +3599,7 @@ fn create_and_deal_with_peek(
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3419,7 +3615,7 @@ fn create_and_deal_with_peek(
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(bet),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3667,7 +3863,7 @@ fn create_and_deal_to_insurance(
             env.dealer.clone(),
             env.contract.clone(),
             &ExecuteMsg::CreateGame {
-                public_key: Binary::from(b"dpk"),
+                public_key: dealer_pk(),
                 shuffled_deck: game.dealer_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3683,7 +3879,7 @@ fn create_and_deal_to_insurance(
             env.contract.clone(),
             &ExecuteMsg::JoinGame {
                 bet: Uint128::new(bet),
-                public_key: Binary::from(b"ppk"),
+                public_key: player_pk(),
                 shuffled_deck: game.player_shuffled_deck(),
                 proof: Binary::from(b"proof"),
                 public_inputs: vec![],
@@ -3726,6 +3922,10 @@ fn test_insurance_taken_dealer_bj() {
         )
         .unwrap();
 
+    let insurance = query_game(&env, game_id).insurance.unwrap();
+    assert!(insurance.offered && insurance.accepted && !insurance.resolved);
+    assert_eq!(insurance.bet, Uint128::new(500));
+
     // Reveal hole card (Ten=9) → dealer BJ → settled
     reveal_card(&mut env, &game, game_id, 3, 9);
 
@@ -3740,6 +3940,7 @@ fn test_insurance_taken_dealer_bj() {
         "Expected dealer win, got: {}",
         g.status
     );
+    assert!(g.insurance.unwrap().resolved);
 
     // Insurance pays: 500 + 500*2/1 = 1500. Main bet lost.
     // dealer_credit = 100000 + 1000 + 500 - 1500 = 100000
@@ -3775,6 +3976,24 @@ fn test_insurance_taken_no_dealer_bj() {
         "Expected PlayerTurn, got: {}",
         g.status
     );
+    let insurance = g.insurance.unwrap();
+    assert!(insurance.accepted && insurance.resolved);
+
+    // The hole card has settled insurance, it can't be bought again
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Insurance { game_id },
+            &[Coin::new(500u128, "utoken")],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Insurance already resolved"),
+        "Expected 'Insurance already resolved', got: {}",
+        err
+    );
 
     // Player stands with 19, dealer has 18 (peeked). Settles inline.
     env.app
@@ -3825,6 +4044,10 @@ fn test_insurance_declined() {
         )
         .unwrap();
 
+    let insurance = query_game(&env, game_id).insurance.unwrap();
+    assert!(insurance.offered && !insurance.accepted && !insurance.resolved);
+    assert!(insurance.bet.is_zero());
+
     // Reveal hole card → no BJ → PlayerTurn
     reveal_card(&mut env, &game, game_id, 3, 6);
 
@@ -3850,6 +4073,41 @@ fn test_insurance_declined() {
     assert_eq!(bal, Uint128::new(99_000));
 }
 
+#[test]
+fn test_insurance_declined_dealer_bj() {
+    let mut env = setup_with_peek();
+    let game = SeededGame::new(207);
+    let bet = 1000u128;
+
+    // Player: 8+7=15, Dealer: Ace+Ten=21 BJ
+    let game_id = create_and_deal_to_insurance(&mut env, &game, bet, 7, 6);
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::DeclineInsurance { game_id },
+            &[],
+        )
+        .unwrap();
+
+    // Reveal hole card (Ten=9) → dealer BJ → settled
+    reveal_card(&mut env, &game, game_id, 3, 9);
+
+    let g = query_game(&env, game_id);
+    assert!(
+        g.status.contains("Dealer"),
+        "Expected dealer win, got: {}",
+        g.status
+    );
+    let insurance = g.insurance.unwrap();
+    assert!(!insurance.accepted && insurance.resolved);
+
+    // Nothing insured, main bet lost. dealer_credit = 100000 + 1000 = 101000
+    let bal = query_dealer_balance(&env);
+    assert_eq!(bal, Uint128::new(101_000));
+}
+
 #[test]
 fn test_insurance_wrong_amount() {
     let mut env = setup_with_peek();
@@ -3858,14 +4116,14 @@ fn test_insurance_wrong_amount() {
 
     let game_id = create_and_deal_to_insurance(&mut env, &game, bet, 7, 6);
 
-    // Send wrong amount (300 instead of 500)
+    // More than half the bet (600 > 500)
     let err = env
         .app
         .execute_contract(
             env.player.clone(),
             env.contract.clone(),
             &ExecuteMsg::Insurance { game_id },
-            &[Coin::new(300u128, "utoken")],
+            &[Coin::new(600u128, "utoken")],
         )
         .unwrap_err();
     assert!(
@@ -3891,6 +4149,37 @@ fn test_insurance_wrong_amount() {
     );
 }
 
+#[test]
+fn test_insurance_partial_stake() {
+    let mut env = setup_with_peek();
+    let game = SeededGame::new(917);
+    let bet = 1000u128;
+
+    // Player: 8+7=15, Dealer: Ace+Ten=21 BJ
+    let game_id = create_and_deal_to_insurance(&mut env, &game, bet, 7, 6);
+
+    // Less than the full half-bet is fine
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Insurance { game_id },
+            &[Coin::new(300u128, "utoken")],
+        )
+        .unwrap();
+    assert_eq!(
+        query_game(&env, game_id).insurance.unwrap().bet,
+        Uint128::new(300)
+    );
+
+    reveal_card(&mut env, &game, game_id, 3, 9);
+    assert!(query_game(&env, game_id).status.contains("Dealer"));
+
+    // Insurance pays 300 + 300*2/1 = 900. Main bet lost.
+    // dealer_credit = 100000 + 1000 + 300 - 900 = 100400
+    assert_eq!(query_dealer_balance(&env), Uint128::new(100_400));
+}
+
 #[test]
 fn test_insurance_not_offered_ten_upcard() {
     let mut env = setup_with_peek();
@@ -3907,6 +4196,8 @@ fn test_insurance_not_offered_ten_upcard() {
         g.status
     );
 
+    assert!(query_game(&env, game_id).insurance.is_none());
+
     // Trying insurance should fail (not OfferingInsurance)
     let err = env
         .app
@@ -3924,6 +4215,53 @@ fn test_insurance_not_offered_ten_upcard() {
     );
 }
 
+#[test]
+fn test_insurance_not_offered_without_peek() {
+    // Default config has dealer_peeks: false
+    let mut env = setup();
+    let game = SeededGame::new(206);
+    let bet = 1000u128;
+
+    // Player: 10+9=19, Dealer upcard: Ace(0)
+    let game_id = create_and_deal(&mut env, &game, bet, 9, 8, 0);
+
+    let g = query_game(&env, game_id);
+    assert!(
+        g.status.contains("PlayerTurn"),
+        "Expected PlayerTurn, got: {}",
+        g.status
+    );
+    assert!(g.insurance.is_none());
+
+    for (msg, funds) in [
+        (
+            ExecuteMsg::Insurance { game_id },
+            vec![Coin::new(500u128, "utoken")],
+        ),
+        (ExecuteMsg::DeclineInsurance { game_id }, vec![]),
+    ] {
+        let err = env
+            .app
+            .execute_contract(env.player.clone(), env.contract.clone(), &msg, &funds)
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("Insurance not being offered"),
+            "Expected 'Insurance not being offered', got: {}",
+            err
+        );
+    }
+
+    // Nothing was charged and the hand plays on
+    stand_and_finish(&mut env, &game, game_id, 6, &[]);
+    let g = query_game(&env, game_id);
+    assert!(
+        g.status.contains("Player"),
+        "Expected player win (19 vs 17), got: {}",
+        g.status
+    );
+    assert!(g.insurance.is_none());
+}
+
 #[test]
 fn test_insurance_player_bj_dealer_bj() {
     let mut env = setup_with_peek();
@@ -3983,3 +4321,3530 @@ fn test_insurance_player_bj_dealer_bj() {
         .unwrap();
     assert_eq!(net, Uint128::new(1_000), "Player net gain should be 1000");
 }
+
+// ===== Reveal history records every partial and the combined card =====
+#[test]
+fn test_reveal_history() {
+    let mut env = setup();
+    let game = SeededGame::new(206);
+
+    // Player: 10+9=19, Dealer upcard 7
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 8, 6);
+
+    let query_history = |env: &TestEnv| -> Vec<RevealRecord> {
+        env.app
+            .wrap()
+            .query_wasm_smart(&env.contract, &QueryMsg::GetRevealHistory { game_id })
+            .unwrap()
+    };
+
+    let history = query_history(&env);
+    assert_eq!(history.len(), 3);
+    for (record, (idx, val)) in history.iter().zip([(0u32, 9u8), (1, 8), (2, 6)]) {
+        assert_eq!(record.card_index, idx);
+        assert_eq!(record.player_partial, Some(game.player_partial(idx)));
+        assert_eq!(record.dealer_partial, Some(game.dealer_partial(idx, val)));
+        assert_eq!(record.combined_result, Some(Binary::from(vec![val])));
+        assert_eq!(record.revealed_value, Some(val));
+        assert_eq!(record.block_height, env.app.block_info().height);
+    }
+
+    // Only the player's half of the hole card: recorded, but not combined yet
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 3,
+                partial_decryption: game.player_partial(3),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    let hole = &query_history(&env)[3];
+    assert_eq!(hole.card_index, 3);
+    assert!(hole.player_partial.is_some());
+    assert!(hole.dealer_partial.is_none());
+    assert!(hole.combined_result.is_none());
+    assert!(hole.revealed_value.is_none());
+
+    // Dealer completes it in a later block: hole card 10 → dealer 17, stands
+    env.app.update_block(|b| b.height += 1);
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 3,
+                partial_decryption: game.dealer_partial(3, 9),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    let hole = &query_history(&env)[3];
+    assert_eq!(hole.revealed_value, Some(9));
+    assert_eq!(hole.block_height, env.app.block_info().height);
+    assert!(query_game(&env, game_id).status.contains("Settled"));
+
+    // Sweeping the settled game drops its history along with it
+    env.app.update_block(|b| b.time = b.time.plus_seconds(61));
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SweepSettled {
+                game_ids: vec![game_id],
+            },
+            &[],
+        )
+        .unwrap();
+    let gone: Result<Vec<RevealRecord>, _> = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetRevealHistory { game_id });
+    assert!(gone.is_err());
+}
+
+// ===== Soft 17 timing: with H17 the dealer's hit is requested right after the hole card =====
+#[test]
+fn test_dealer_hits_soft_17_forced() {
+    let mut env = setup();
+    let game = SeededGame::new(207);
+    let bet = 1000u128;
+
+    // Player: 10+8=18, Dealer upcard: Ace(0)
+    let game_id = create_and_deal(&mut env, &game, bet, 9, 7, 0);
+    stand_and_finish(&mut env, &game, game_id, 5, &[]); // hole=6 → A+6 = soft 17
+
+    // Not settled: the contract must ask for card 4 before the dealer can stand
+    let g = query_game(&env, game_id);
+    assert_eq!(g.dealer_hand, vec![0, 5]);
+    assert!(
+        g.status.contains("WaitingForReveal") && g.status.contains("reveal_requests: [4]"),
+        "Dealer must hit soft 17, got: {}",
+        g.status
+    );
+
+    // Ten → A+6+10 = hard 17, dealer stands; player's 18 wins
+    reveal_card(&mut env, &game, game_id, 4, 9);
+
+    let g = query_game(&env, game_id);
+    assert_eq!(g.dealer_hand, vec![0, 5, 9]);
+    assert!(
+        g.status.contains("Settled") && g.status.contains("Player"),
+        "Expected player win 18 vs 17, got: {}",
+        g.status
+    );
+    assert_eq!(query_dealer_balance(&env), Uint128::new(99_000));
+}
+
+// ===== Soft 17 timing: with S17 the game settles on the hole card =====
+#[test]
+fn test_dealer_stands_hard_17() {
+    let mut msg = default_instantiate_msg();
+    msg.dealer_hits_soft_17 = false;
+    let mut env = setup_with_msg(msg, 100_000);
+    let game = SeededGame::new(208);
+    let bet = 1000u128;
+
+    // Player: 10+8=18, Dealer: Ace + 6 = soft 17, stands
+    let game_id = create_and_deal(&mut env, &game, bet, 9, 7, 0);
+    stand_and_finish(&mut env, &game, game_id, 5, &[]);
+
+    let g = query_game(&env, game_id);
+    assert_eq!(g.dealer_hand, vec![0, 5]);
+    assert!(
+        g.status.contains("Settled") && g.status.contains("Player"),
+        "Expected player win 18 vs soft 17 without another card, got: {}",
+        g.status
+    );
+    assert_eq!(query_dealer_balance(&env), Uint128::new(99_000));
+}
+
+// ===== Early surrender: without a peek, surrender is open right after the deal =====
+#[test]
+fn test_early_surrender_no_peek() {
+    let mut env = setup();
+    let game = SeededGame::new(209);
+    let bet = 1000u128;
+
+    // Player: 10+6=16, Dealer shows Ace; hole card is never revealed
+    let game_id = create_and_deal(&mut env, &game, bet, 9, 5, 0);
+    assert!(query_game(&env, game_id).status.contains("PlayerTurn"));
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap();
+
+    let g = query_game(&env, game_id);
+    assert!(
+        g.status.contains("Surrendered"),
+        "Expected surrendered, got: {}",
+        g.status
+    );
+    assert_eq!(g.dealer_hand, vec![0]);
+    // Dealer keeps half the bet: 100000 + 1000 / 2
+    assert_eq!(query_dealer_balance(&env), Uint128::new(100_500));
+}
+
+// ===== Surrender is gone once the player has drawn a card =====
+#[test]
+fn test_surrender_only_before_action() {
+    let mut env = setup();
+    let game = SeededGame::new(210);
+
+    // Player: 10+2=12, Dealer shows 10
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 1, 9);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Hit { game_id },
+            &[],
+        )
+        .unwrap();
+    reveal_card(&mut env, &game, game_id, 4, 2); // Three → 15
+    assert!(query_game(&env, game_id).status.contains("PlayerTurn"));
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap_err();
+    // Multi-test wraps contract errors, so match on the SurrenderNotAllowed message
+    assert!(
+        err.to_string().contains("Surrender not allowed"),
+        "Expected SurrenderNotAllowed, got: {err}"
+    );
+}
+
+// ===== Late surrender: with a peek, surrender opens only after the hole card clears =====
+#[test]
+fn test_late_surrender_timing() {
+    let mut env = setup_with_peek();
+    let game = SeededGame::new(211);
+    let bet = 1000u128;
+
+    // Player: 10+6=16, Dealer shows 10 → peek pending
+    let game_id = create_and_deal(&mut env, &game, bet, 9, 5, 9);
+    let g = query_game(&env, game_id);
+    assert!(
+        g.status.contains("WaitingForReveal"),
+        "Expected peek reveal, got: {}",
+        g.status
+    );
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Not player turn"));
+
+    // Hole card 7: no dealer blackjack, surrender is now allowed
+    reveal_card(&mut env, &game, game_id, 3, 6);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap();
+
+    let g = query_game(&env, game_id);
+    assert!(
+        g.status.contains("Surrendered"),
+        "Expected surrendered, got: {}",
+        g.status
+    );
+    assert_eq!(query_dealer_balance(&env), Uint128::new(100_500));
+}
+
+// ===========================================================================
+// Denomination checks
+// ===========================================================================
+
+/// Table in "uxion" with the game created; the player also holds "uatom".
+fn setup_uxion_game(game: &SeededGame) -> TestEnv {
+    let mut msg = default_instantiate_msg();
+    msg.denom = "uxion".to_string();
+    let mut env = setup_with_msg(msg, 100_000);
+
+    let player = env.player.clone();
+    env.app.init_modules(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(
+                storage,
+                &player,
+                vec![
+                    Coin::new(1_000_000u128, "uxion"),
+                    Coin::new(1_000_000u128, "uatom"),
+                ],
+            )
+            .unwrap();
+    });
+
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: dealer_pk(),
+                shuffled_deck: game.dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    env
+}
+
+fn join_with_funds(
+    env: &mut TestEnv,
+    game: &SeededGame,
+    bet: u128,
+    funds: &[Coin],
+) -> Result<AppResponse, cosmwasm_std::StdError> {
+    env.app.execute_contract(
+        env.player.clone(),
+        env.contract.clone(),
+        &ExecuteMsg::JoinGame {
+            bet: Uint128::new(bet),
+            public_key: player_pk(),
+            shuffled_deck: game.player_shuffled_deck(),
+            proof: Binary::from(b"proof"),
+            public_inputs: vec![],
+        },
+        funds,
+    )
+}
+
+// ===== JoinGame paid in another denom is rejected =====
+#[test]
+fn test_wrong_denom_rejected() {
+    let game = SeededGame::new(212);
+    let mut env = setup_uxion_game(&game);
+
+    let err = join_with_funds(&mut env, &game, 1000, &[Coin::new(1000u128, "uatom")]).unwrap_err();
+    assert!(
+        err.to_string().contains("Only uxion accepted, got uatom"),
+        "Expected InvalidDenom, got: {err}"
+    );
+}
+
+// ===== Mixing the right denom with another one is rejected =====
+#[test]
+fn test_multiple_denoms_rejected() {
+    let game = SeededGame::new(213);
+    let mut env = setup_uxion_game(&game);
+
+    // Half the bet in uxion, half in uatom: must not count as a full bet
+    let err = join_with_funds(
+        &mut env,
+        &game,
+        1000,
+        &[Coin::new(500u128, "uxion"), Coin::new(500u128, "uatom")],
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("Only uxion accepted, got uatom"),
+        "Expected InvalidDenom, got: {err}"
+    );
+
+    // The game is still open and the player kept their funds
+    join_with_funds(&mut env, &game, 1000, &[Coin::new(1000u128, "uxion")]).unwrap();
+}
+
+// ===== DepositBankroll in another denom is rejected =====
+#[test]
+fn test_wrong_denom_deposit() {
+    let game = SeededGame::new(214);
+    let mut env = setup_uxion_game(&game);
+
+    let dealer = env.dealer.clone();
+    env.app.init_modules(|router, _api, storage| {
+        router
+            .bank
+            .init_balance(storage, &dealer, vec![Coin::new(1_000_000u128, "uatom")])
+            .unwrap();
+    });
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::DepositBankroll {},
+            &[Coin::new(50_000u128, "uatom")],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Only uxion accepted, got uatom"),
+        "Expected InvalidDenom, got: {err}"
+    );
+}
+
+// ===== A bet of 999 against a 1000 minimum is rejected =====
+#[test]
+fn test_exact_amount_required() {
+    let game = SeededGame::new(215);
+    let mut msg = default_instantiate_msg();
+    msg.denom = "uxion".to_string();
+    msg.min_bet = Uint128::new(1000);
+    let mut env = setup_with_msg(msg, 100_000);
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: dealer_pk(),
+                shuffled_deck: game.dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let err = join_with_funds(&mut env, &game, 999, &[Coin::new(999u128, "uxion")]).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Bet out of range: provided 999, min 1000, max 10000"),
+        "Expected BetOutOfRange, got: {err}"
+    );
+}
+
+/// Joins a game with min_bet 1000 and max_bet 10000, betting `bet`.
+fn join_with_bet_limits(seed: u64, bet: u128) -> cosmwasm_std::StdError {
+    let game = SeededGame::new(seed);
+    let mut msg = default_instantiate_msg();
+    msg.denom = "uxion".to_string();
+    msg.min_bet = Uint128::new(1000);
+    msg.max_bet = Uint128::new(10_000);
+    let mut env = setup_with_msg(msg, 100_000);
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: dealer_pk(),
+                shuffled_deck: game.dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    let funds: Vec<Coin> = if bet == 0 {
+        vec![]
+    } else {
+        vec![Coin::new(bet, "uxion")]
+    };
+    join_with_funds(&mut env, &game, bet, &funds).unwrap_err()
+}
+
+#[test]
+fn test_bet_below_minimum_fails() {
+    let err = join_with_bet_limits(219, 500);
+    assert!(
+        err.to_string()
+            .contains("Bet out of range: provided 500, min 1000, max 10000"),
+        "Expected BetOutOfRange, got: {err}"
+    );
+}
+
+#[test]
+fn test_bet_above_maximum_fails() {
+    let err = join_with_bet_limits(220, 10_001);
+    assert!(
+        err.to_string()
+            .contains("Bet out of range: provided 10001, min 1000, max 10000"),
+        "Expected BetOutOfRange, got: {err}"
+    );
+}
+
+#[test]
+fn test_zero_bet_fails() {
+    let err = join_with_bet_limits(221, 0);
+    assert!(
+        err.to_string().contains("Zero bet not allowed"),
+        "Expected ZeroBetNotAllowed, got: {err}"
+    );
+}
+
+// ===========================================================================
+// Proof rejection
+// ===========================================================================
+
+fn create_game(
+    env: &mut TestEnv,
+    game: &SeededGame,
+) -> Result<AppResponse, cosmwasm_std::StdError> {
+    env.app.execute_contract(
+        env.dealer.clone(),
+        env.contract.clone(),
+        &ExecuteMsg::CreateGame {
+            public_key: dealer_pk(),
+            shuffled_deck: game.dealer_shuffled_deck(),
+            proof: Binary::from(b"proof"),
+            public_inputs: vec![],
+        },
+        &[],
+    )
+}
+
+// ===== CreateGame with a rejected dealer shuffle proof =====
+#[test]
+fn test_dealer_shuffle_proof_rejected() {
+    let mut env = setup_with_verifier(
+        default_instantiate_msg(),
+        100_000,
+        ZkConditionalStargate::rejecting(),
+    );
+    let game = SeededGame::new(216);
+
+    let err = create_game(&mut env, &game).unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid dealer shuffle proof"),
+        "Expected ProofVerificationFailed, got: {err}"
+    );
+    // Bankroll was not committed to a game
+    assert_eq!(query_dealer_balance(&env), Uint128::new(100_000));
+}
+
+// ===== JoinGame with a rejected player shuffle proof =====
+#[test]
+fn test_shuffle_proof_rejected_at_join() {
+    let verifier = ZkConditionalStargate::default();
+    let mut env = setup_with_verifier(default_instantiate_msg(), 100_000, verifier.clone());
+    let game = SeededGame::new(217);
+    let game_id = extract_game_id(&create_game(&mut env, &game).unwrap());
+
+    verifier.reject_shuffle.set(true);
+    let before = query_game(&env, game_id);
+    let player_before = env
+        .app
+        .wrap()
+        .query_balance(&env.player, "utoken")
+        .unwrap()
+        .amount;
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::JoinGame {
+                bet: Uint128::new(1000),
+                public_key: player_pk(),
+                shuffled_deck: game.player_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[Coin::new(1000u128, "utoken")],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid player shuffle proof"),
+        "Expected ProofVerificationFailed, got: {err}"
+    );
+
+    // Game still open and untouched, and the bet was not taken
+    let after = query_game(&env, game_id);
+    assert_eq!(after, before);
+    assert!(
+        after.status.contains("WaitingForPlayerJoin"),
+        "{}",
+        after.status
+    );
+    let player_after = env
+        .app
+        .wrap()
+        .query_balance(&env.player, "utoken")
+        .unwrap()
+        .amount;
+    assert_eq!(player_after, player_before);
+}
+
+// ===== SubmitReveal with a rejected reveal proof =====
+#[test]
+fn test_reveal_proof_rejected_at_submit() {
+    let verifier = ZkConditionalStargate::default();
+    let mut env = setup_with_verifier(default_instantiate_msg(), 100_000, verifier.clone());
+    let game = SeededGame::new(218);
+
+    // Player: 10+8=18, Dealer shows 7
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 7, 6);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap();
+    let before = query_game(&env, game_id);
+
+    verifier.reject_reveal.set(true);
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 3,
+                partial_decryption: game.player_partial(3),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid reveal proof"),
+        "Expected ProofVerificationFailed, got: {err}"
+    );
+
+    // No partial stored, no phase change, nothing in the reveal history
+    let after = query_game(&env, game_id);
+    assert_eq!(after, before);
+    assert!(after.pending_reveals.is_empty());
+    let history: Vec<RevealRecord> = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetRevealHistory { game_id })
+        .unwrap();
+    assert_eq!(history.len(), 3);
+
+    // Once proofs verify again the reveal goes through
+    verifier.reject_reveal.set(false);
+    reveal_card(&mut env, &game, game_id, 3, 9);
+    assert!(query_game(&env, game_id).status.contains("Settled"));
+}
+
+// ===== GetHandValue query =====
+fn query_hand_value(env: &TestEnv, game_id: u64, hand_index: u32) -> HandValueResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(
+            &env.contract,
+            &QueryMsg::GetHandValue {
+                game_id,
+                hand_index,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_hand_value_soft_hand() {
+    let mut env = setup();
+    let game = SeededGame::new(401);
+
+    // Player: A+6 = soft 17, Dealer shows 6
+    let game_id = create_and_deal(&mut env, &game, 1000, 0, 5, 5);
+    let hv = query_hand_value(&env, game_id, 0);
+    assert_eq!(hv.value, 17);
+    assert!(hv.is_soft);
+    assert!(!hv.is_busted);
+    assert!(!hv.is_blackjack);
+    assert_eq!(hv.total_cards, 2);
+    assert!(!hv.is_stood);
+    assert!(!hv.is_surrendered);
+
+    stand_and_finish(&mut env, &game, game_id, 9, &[]);
+    assert!(query_hand_value(&env, game_id, 0).is_stood);
+}
+
+#[test]
+fn test_hand_value_blackjack() {
+    let mut env = setup();
+    let game = SeededGame::new(402);
+
+    // Player: A+K, Dealer shows 6
+    let game_id = create_and_deal(&mut env, &game, 1000, 0, 12, 5);
+    let hv = query_hand_value(&env, game_id, 0);
+    assert_eq!(hv.value, 21);
+    assert!(hv.is_blackjack);
+    assert!(hv.is_soft);
+    assert_eq!(hv.total_cards, 2);
+}
+
+#[test]
+fn test_hand_value_busted() {
+    let mut env = setup();
+    let game = SeededGame::new(403);
+
+    // Player: 10+6, hits a 10
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 5, 5);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Hit { game_id },
+            &[],
+        )
+        .unwrap();
+    reveal_card(&mut env, &game, game_id, 4, 9);
+
+    let hv = query_hand_value(&env, game_id, 0);
+    assert_eq!(hv.value, 26);
+    assert!(hv.is_busted);
+    assert!(!hv.is_soft);
+    assert_eq!(hv.total_cards, 3);
+}
+
+#[test]
+fn test_hand_value_invalid_index() {
+    let mut env = setup();
+    let game = SeededGame::new(404);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 5, 5);
+
+    let err = env
+        .app
+        .wrap()
+        .query_wasm_smart::<HandValueResponse>(
+            &env.contract,
+            &QueryMsg::GetHandValue {
+                game_id,
+                hand_index: 1,
+            },
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Invalid hand index: 1"),
+        "Expected InvalidHandIndex, got: {err}"
+    );
+}
+
+// ===== CalculateOptimalAction query =====
+#[test]
+fn test_optimal_action_matches_strategy_table() {
+    use blackjack::{Card, HandOptions, StrategyTable};
+
+    let all = HandOptions {
+        from_split: false,
+        can_split: true,
+        can_surrender: true,
+    };
+    // Player cards and dealer upcard as card indices, and the expected play
+    let cases = [
+        (0, 5, 5, Action::Double),    // soft 17 vs 6
+        (9, 5, 9, Action::Surrender), // 16 vs 10
+        (7, 20, 5, Action::Split),    // 8-8 vs 6
+        (9, 1, 3, Action::Stand),     // 12 vs 4
+        (4, 3, 9, Action::Hit),       // 9 vs 10
+    ];
+    for (seed, (p0, p1, d_up, expected)) in (405..).zip(cases) {
+        let mut env = setup();
+        let game = SeededGame::new(seed);
+        let game_id = create_and_deal(&mut env, &game, 1000, p0, p1, d_up);
+
+        let resp: OptimalActionResponse = env
+            .app
+            .wrap()
+            .query_wasm_smart(
+                &env.contract,
+                &QueryMsg::CalculateOptimalAction {
+                    game_id,
+                    hand_index: 0,
+                },
+            )
+            .unwrap();
+        let cards = [Card::from_index(p0 as usize), Card::from_index(p1 as usize)];
+        let up_value = Card::from_index(d_up as usize).value();
+        let table = StrategyTable::new(&config_to_rules(&query_config(&env)));
+        assert_eq!(
+            resp.optimal_action,
+            Action::from(table.action(&cards, up_value, all)),
+            "{cards:?} vs {up_value}"
+        );
+        assert_eq!(resp.optimal_action, expected, "{cards:?} vs {up_value}");
+        assert_eq!(resp.dealer_upcard, up_value);
+        assert_eq!(resp.player_value, query_hand_value(&env, game_id, 0).value);
+        assert_eq!((resp.ev_hit, resp.ev_stand), (None, None));
+    }
+}
+
+#[test]
+fn test_optimal_action_after_split() {
+    let mut env = setup();
+    let game = SeededGame::new(410);
+    let game_id = create_and_deal(&mut env, &game, 1000, 7, 20, 5);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Split { game_id },
+            &[Coin::new(1000u128, "utoken")],
+        )
+        .unwrap();
+    reveal_card(&mut env, &game, game_id, 4, 2);
+
+    // 8+3 against a 6 doubles; surrender is no longer on offer after a split
+    let resp: OptimalActionResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.contract,
+            &QueryMsg::CalculateOptimalAction {
+                game_id,
+                hand_index: 0,
+            },
+        )
+        .unwrap();
+    assert_eq!(resp.player_value, 11);
+    assert!(!resp.is_soft);
+    assert_eq!(resp.optimal_action, Action::Double);
+
+    // The second hand has only its eight so far
+    let err = env
+        .app
+        .wrap()
+        .query_wasm_smart::<OptimalActionResponse>(
+            &env.contract,
+            &QueryMsg::CalculateOptimalAction {
+                game_id,
+                hand_index: 1,
+            },
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Hand has not been dealt yet"),
+        "Expected an undealt hand error, got: {err}"
+    );
+}
+
+// ===== Deck hash fingerprints =====
+fn create_game_with_deck(env: &mut TestEnv, deck: Vec<Binary>) -> u64 {
+    // Each open game locks 10x max bet, so fund the bankroll for this one
+    let resp = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: dealer_pk(),
+                shuffled_deck: deck,
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[Coin::new(100_000u128, "utoken")],
+        )
+        .unwrap();
+    extract_game_id(&resp)
+}
+
+#[test]
+fn test_deck_hashes_in_game_response() {
+    let mut env = setup();
+    let game = SeededGame::new(501);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 5, 5);
+
+    let g = query_game(&env, game_id);
+    assert_eq!(g.deck_hash.len(), 32);
+    assert_eq!(g.deck_hash, deck_hash(&game.dealer_shuffled_deck()));
+    assert_eq!(
+        g.player_deck_hash,
+        Some(deck_hash(&game.player_shuffled_deck()))
+    );
+    assert_ne!(g.player_deck_hash, Some(g.deck_hash));
+}
+
+#[test]
+fn test_deck_hash_deterministic_and_tamper_evident() {
+    let mut env = setup();
+    let game = SeededGame::new(502);
+    let deck = game.dealer_shuffled_deck();
+
+    let first = create_game_with_deck(&mut env, deck.clone());
+    let second = create_game_with_deck(&mut env, deck.clone());
+    let first_hash = query_game(&env, first).deck_hash;
+    assert_eq!(first_hash, query_game(&env, second).deck_hash);
+    // Not joined yet
+    assert_eq!(query_game(&env, first).player_deck_hash, None);
+
+    // Flipping a single bit in any card changes the fingerprint
+    for idx in [0, 51] {
+        let mut tampered = deck.clone();
+        let mut bytes = tampered[idx].to_vec();
+        bytes[0] ^= 1;
+        tampered[idx] = Binary::from(bytes);
+        let game_id = create_game_with_deck(&mut env, tampered);
+        assert_ne!(query_game(&env, game_id).deck_hash, first_hash);
+    }
+}
+
+// ===== Player whitelist =====
+fn try_join(env: &mut TestEnv, game: &SeededGame) -> StdResult<AppResponse> {
+    env.app.execute_contract(
+        env.player.clone(),
+        env.contract.clone(),
+        &ExecuteMsg::JoinGame {
+            bet: Uint128::new(1000),
+            public_key: player_pk(),
+            shuffled_deck: game.player_shuffled_deck(),
+            proof: Binary::from(b"proof"),
+            public_inputs: vec![],
+        },
+        &[Coin::new(1000u128, "utoken")],
+    )
+}
+
+fn query_is_whitelisted(env: &TestEnv, player: &Addr) -> bool {
+    env.app
+        .wrap()
+        .query_wasm_smart(
+            &env.contract,
+            &QueryMsg::IsWhitelisted {
+                player: player.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+fn whitelist_msg(addresses: Vec<String>) -> InstantiateMsg {
+    InstantiateMsg {
+        whitelist: Some(addresses),
+        ..default_instantiate_msg()
+    }
+}
+
+#[test]
+fn test_whitelist_add_and_remove() {
+    let someone_else = MockApi::default().addr_make("someone_else");
+    let mut env = setup_with_msg(whitelist_msg(vec![someone_else.to_string()]), 100_000);
+    let game = SeededGame::new(601);
+
+    create_game_with_deck(&mut env, game.dealer_shuffled_deck());
+    assert!(!query_is_whitelisted(&env, &env.player));
+    let err = try_join(&mut env, &game).unwrap_err();
+    assert!(
+        err.to_string().contains("is not whitelisted"),
+        "Expected NotWhitelisted, got: {}",
+        err
+    );
+
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::AddToWhitelist {
+                addresses: vec![env.player.to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(query_is_whitelisted(&env, &env.player));
+    try_join(&mut env, &game).unwrap();
+
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::RemoveFromWhitelist {
+                addresses: vec![env.player.to_string()],
+            },
+            &[],
+        )
+        .unwrap();
+    assert!(!query_is_whitelisted(&env, &env.player));
+    assert!(query_is_whitelisted(&env, &someone_else));
+
+    create_game_with_deck(&mut env, game.dealer_shuffled_deck());
+    assert!(try_join(&mut env, &game).is_err());
+}
+
+#[test]
+fn test_whitelist_clear_rejects_everyone() {
+    let player = MockApi::default().addr_make("player");
+    let mut env = setup_with_msg(whitelist_msg(vec![player.to_string()]), 100_000);
+    let game = SeededGame::new(602);
+
+    create_game_with_deck(&mut env, game.dealer_shuffled_deck());
+    try_join(&mut env, &game).unwrap();
+
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::ClearWhitelist {},
+            &[],
+        )
+        .unwrap();
+    assert!(!query_is_whitelisted(&env, &env.player));
+
+    create_game_with_deck(&mut env, game.dealer_shuffled_deck());
+    let err = try_join(&mut env, &game).unwrap_err();
+    assert!(err.to_string().contains("is not whitelisted"));
+}
+
+#[test]
+fn test_no_whitelist_allows_everyone() {
+    let mut env = setup();
+    let game = SeededGame::new(603);
+
+    let anyone = MockApi::default().addr_make("anyone");
+    assert!(query_is_whitelisted(&env, &anyone));
+    assert!(query_is_whitelisted(&env, &env.player));
+
+    create_game_with_deck(&mut env, game.dealer_shuffled_deck());
+    try_join(&mut env, &game).unwrap();
+}
+
+#[test]
+fn test_whitelist_dealer_only() {
+    let mut env = setup();
+
+    for msg in [
+        ExecuteMsg::AddToWhitelist {
+            addresses: vec![env.player.to_string()],
+        },
+        ExecuteMsg::RemoveFromWhitelist {
+            addresses: vec![env.player.to_string()],
+        },
+        ExecuteMsg::ClearWhitelist {},
+    ] {
+        let err = env
+            .app
+            .execute_contract(env.player.clone(), env.contract.clone(), &msg, &[])
+            .unwrap_err();
+        assert!(err.to_string().contains("Unauthorized"));
+    }
+    // Nothing was enforced by the failed attempts
+    assert!(query_is_whitelisted(&env, &env.player));
+}
+
+// ===== GetTimeoutStatus query =====
+fn query_timeout_status(env: &TestEnv, game_id: u64) -> TimeoutStatusResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetTimeoutStatus { game_id })
+        .unwrap()
+}
+
+#[test]
+fn test_timeout_status_countdown() {
+    let mut env = setup();
+    let game = SeededGame::new(420);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 8, 5);
+
+    let status = query_timeout_status(&env, game_id);
+    assert!(!status.can_claim_timeout);
+    assert_eq!(status.seconds_until_eligible, Some(60));
+    assert_eq!(status.timed_out_party.as_deref(), Some("player"));
+
+    env.app.update_block(|b| b.time = b.time.plus_seconds(50));
+    assert_eq!(
+        query_timeout_status(&env, game_id).seconds_until_eligible,
+        Some(10)
+    );
+
+    // Eligible at exactly timeout_seconds, same as ClaimTimeout
+    env.app.update_block(|b| b.time = b.time.plus_seconds(10));
+    let status = query_timeout_status(&env, game_id);
+    assert!(status.can_claim_timeout);
+    assert_eq!(status.seconds_until_eligible, None);
+    assert_eq!(status.timed_out_party.as_deref(), Some("player"));
+
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::ClaimTimeout { game_id },
+            &[],
+        )
+        .unwrap();
+    let status = query_timeout_status(&env, game_id);
+    assert!(!status.can_claim_timeout);
+    assert_eq!(status.seconds_until_eligible, None);
+    assert_eq!(status.timed_out_party, None);
+}
+
+#[test]
+fn test_timeout_status_blames_lagging_revealer() {
+    let mut env = setup();
+    let game = SeededGame::new(421);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 8, 5);
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 3,
+                partial_decryption: game.player_partial(3),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(
+        query_timeout_status(&env, game_id)
+            .timed_out_party
+            .as_deref(),
+        Some("dealer")
+    );
+}
+
+// ===== GetRevealRequests query =====
+fn query_reveal_requests(env: &TestEnv, game_id: u64) -> Vec<PendingReveal> {
+    let resp: RevealRequestsResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetRevealRequests { game_id })
+        .unwrap();
+    resp.pending
+}
+
+#[test]
+fn test_reveal_requests_track_submissions() {
+    let mut env = setup();
+    let game = SeededGame::new(422);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 8, 5);
+    assert!(query_reveal_requests(&env, game_id).is_empty());
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        query_reveal_requests(&env, game_id),
+        vec![PendingReveal {
+            card_index: 3,
+            player_submitted: false,
+            dealer_submitted: false,
+        }]
+    );
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 3,
+                partial_decryption: game.player_partial(3),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(
+        query_reveal_requests(&env, game_id),
+        vec![PendingReveal {
+            card_index: 3,
+            player_submitted: true,
+            dealer_submitted: false,
+        }]
+    );
+}
+
+// ===== GetSettledGamesForPlayer query =====
+fn query_settled_games(
+    env: &TestEnv,
+    start_after: Option<u64>,
+    limit: u32,
+) -> SettledGamesResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(
+            &env.contract,
+            &QueryMsg::GetSettledGamesForPlayer {
+                player: env.player.to_string(),
+                start_after,
+                limit,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_settled_games_for_player() {
+    let mut env = setup();
+    let bet = 1000u128;
+
+    // Player 19 vs dealer bust: +1000
+    let game = SeededGame::new(430);
+    let won = create_and_deal(&mut env, &game, bet, 9, 8, 5);
+    stand_and_finish(&mut env, &game, won, 9, &[9]);
+
+    // Player 16 vs dealer 18: -1000
+    let game = SeededGame::new(431);
+    let lost = create_and_deal(&mut env, &game, bet, 9, 5, 9);
+    stand_and_finish(&mut env, &game, lost, 7, &[]);
+
+    // Surrender: -500
+    let game = SeededGame::new(432);
+    let surrendered = create_and_deal(&mut env, &game, bet, 9, 5, 9);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender {
+                game_id: surrendered,
+            },
+            &[],
+        )
+        .unwrap();
+
+    // Still in play, so not listed
+    let game = SeededGame::new(433);
+    let active = create_and_deal(&mut env, &game, bet, 9, 8, 5);
+
+    let first = query_settled_games(&env, None, 2);
+    assert_eq!(
+        first
+            .games
+            .iter()
+            .map(|g| (g.game_id, g.net, g.outcome))
+            .collect::<Vec<_>>(),
+        vec![
+            (won, Int128::new(1000), GameOutcome::Win),
+            (lost, Int128::new(-1000), GameOutcome::Loss),
+        ]
+    );
+    assert_eq!(first.games[0].payout, Uint128::new(2000));
+    assert_eq!(first.games[1].payout, Uint128::zero());
+    assert!(first.games.iter().all(|g| g.bet == Uint128::new(bet)));
+    assert_eq!(first.next_cursor, Some(lost));
+
+    let second = query_settled_games(&env, first.next_cursor, 2);
+    assert_eq!(second.games.len(), 1);
+    assert_eq!(second.games[0].game_id, surrendered);
+    assert_eq!(second.games[0].net, Int128::new(-500));
+    assert_eq!(second.games[0].outcome, GameOutcome::Surrender);
+    assert_eq!(second.next_cursor, None);
+    assert!(!second.games.iter().any(|g| g.game_id == active));
+
+    // Another player has no history
+    let other: SettledGamesResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(
+            &env.contract,
+            &QueryMsg::GetSettledGamesForPlayer {
+                player: env.dealer.to_string(),
+                start_after: None,
+                limit: 10,
+            },
+        )
+        .unwrap();
+    assert!(other.games.is_empty());
+}
+
+// ===== GetPlayerStats / GetTopPlayers queries =====
+fn query_player_stats(env: &TestEnv, player: &Addr) -> PlayerStatsResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(
+            &env.contract,
+            &QueryMsg::GetPlayerStats {
+                player: player.to_string(),
+            },
+        )
+        .unwrap()
+}
+
+fn query_top_players(env: &TestEnv, by: TopPlayersSort, limit: u32) -> Vec<String> {
+    let players: Vec<PlayerStatsResponse> = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetTopPlayers { by, limit })
+        .unwrap();
+    players.into_iter().map(|p| p.player).collect()
+}
+
+#[test]
+fn test_player_stats_accumulate() {
+    // Room for the game left in play to keep its bankroll locked
+    let mut env = setup_with_bankroll(300_000);
+    let bet = 1000u128;
+    let player = env.player.clone();
+    assert_eq!(query_player_stats(&env, &player).games_played, 0);
+
+    let mut seed = 918;
+    let mut next_game = || {
+        seed += 1;
+        SeededGame::new(seed)
+    };
+    for _ in 0..3 {
+        // Player 19 vs dealer bust: +1000
+        let game = next_game();
+        let id = create_and_deal(&mut env, &game, bet, 9, 8, 5);
+        stand_and_finish(&mut env, &game, id, 9, &[9]);
+    }
+    for _ in 0..2 {
+        // Natural vs dealer 18: +1500
+        let game = next_game();
+        let id = create_and_deal(&mut env, &game, bet, 0, 12, 9);
+        reveal_card(&mut env, &game, id, 3, 7);
+    }
+    for _ in 0..2 {
+        // Player 16 vs dealer 18: -1000
+        let game = next_game();
+        let id = create_and_deal(&mut env, &game, bet, 9, 5, 9);
+        stand_and_finish(&mut env, &game, id, 7, &[]);
+    }
+    // Player 18 vs dealer 18: push
+    let game = next_game();
+    let id = create_and_deal(&mut env, &game, bet, 9, 7, 9);
+    stand_and_finish(&mut env, &game, id, 7, &[]);
+    for _ in 0..2 {
+        // -500
+        let game = next_game();
+        let game_id = create_and_deal(&mut env, &game, bet, 9, 5, 9);
+        env.app
+            .execute_contract(
+                env.player.clone(),
+                env.contract.clone(),
+                &ExecuteMsg::Surrender { game_id },
+                &[],
+            )
+            .unwrap();
+    }
+    // Still in play, so not counted
+    create_and_deal(&mut env, &next_game(), bet, 9, 8, 5);
+
+    let stats = query_player_stats(&env, &player);
+    assert_eq!(stats.player, player.to_string());
+    assert_eq!(stats.games_played, 10);
+    assert_eq!(
+        (stats.wins, stats.losses, stats.pushes, stats.surrenders),
+        (5, 2, 1, 2)
+    );
+    assert_eq!(stats.blackjacks, 2);
+    assert_eq!(stats.total_wagered, Uint128::new(10_000));
+    assert_eq!(stats.net_profit, Int128::new(3000 + 3000 - 2000 - 1000));
+
+    // A second player who has lost more, but in more games
+    let player2 = MockApi::default().addr_make("player2");
+    env.app
+        .send_tokens(
+            player.clone(),
+            player2.clone(),
+            &[Coin::new(100_000u128, "utoken")],
+        )
+        .unwrap();
+    env.player = player2.clone();
+    for _ in 0..11 {
+        let game = next_game();
+        let id = create_and_deal(&mut env, &game, bet, 9, 5, 9);
+        stand_and_finish(&mut env, &game, id, 7, &[]);
+    }
+    env.player = player.clone();
+    let stats2 = query_player_stats(&env, &player2);
+    assert_eq!((stats2.games_played, stats2.losses), (11, 11));
+    assert_eq!(stats2.net_profit, Int128::new(-11_000));
+
+    assert_eq!(
+        query_top_players(&env, TopPlayersSort::ByNetProfit, 10),
+        vec![player.to_string(), player2.to_string()]
+    );
+    assert_eq!(
+        query_top_players(&env, TopPlayersSort::ByGamesPlayed, 10),
+        vec![player2.to_string(), player.to_string()]
+    );
+    assert_eq!(
+        query_top_players(&env, TopPlayersSort::ByGamesPlayed, 1),
+        vec![player2.to_string()]
+    );
+}
+
+// ===== Protocol fees =====
+fn query_protocol_fees(env: &TestEnv) -> ProtocolFeesResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetProtocolFees {})
+        .unwrap()
+}
+
+fn fee_msg(fee_bps: Option<u16>, recipient: Option<&Addr>) -> InstantiateMsg {
+    InstantiateMsg {
+        protocol_fee_bps: fee_bps,
+        protocol_fee_recipient: recipient.map(Addr::to_string),
+        ..default_instantiate_msg()
+    }
+}
+
+#[test]
+fn test_protocol_fee_instantiate_validation() {
+    let mut env = setup();
+    let treasury = MockApi::default().addr_make("treasury");
+    let code_id = env.app.store_code(Box::new(ContractWrapper::new(
+        juodzekas::contract::execute,
+        juodzekas::contract::instantiate,
+        juodzekas::contract::query,
+    )));
+    let mut instantiate = |msg: InstantiateMsg| {
+        env.app
+            .instantiate_contract(code_id, env.dealer.clone(), &msg, &[], "juodzekas", None)
+            .map_err(|e| e.to_string())
+    };
+
+    let err = instantiate(fee_msg(Some(10_001), Some(&treasury))).unwrap_err();
+    assert!(err.contains("cannot exceed 10000"), "got: {err}");
+    let err = instantiate(fee_msg(Some(100), None)).unwrap_err();
+    assert!(
+        err.contains("protocol_fee_recipient is required"),
+        "got: {err}"
+    );
+    instantiate(fee_msg(Some(10_000), Some(&treasury))).unwrap();
+    instantiate(fee_msg(None, None)).unwrap();
+
+    // The default config charges nothing
+    assert_eq!(
+        query_protocol_fees(&env),
+        ProtocolFeesResponse {
+            collected: Uint128::zero(),
+            fee_bps: 0,
+            recipient: None
+        }
+    );
+}
+
+#[test]
+fn test_protocol_fee_accumulates_and_claims() {
+    let treasury = MockApi::default().addr_make("treasury");
+    let mut env = setup_with_msg(fee_msg(Some(1000), Some(&treasury)), 100_000);
+    let bet = 1000u128;
+
+    // Player 16 vs dealer 18: dealer profit 1000, fee 100
+    let game = SeededGame::new(942);
+    let lost = create_and_deal(&mut env, &game, bet, 9, 5, 9);
+    stand_and_finish(&mut env, &game, lost, 7, &[]);
+    assert_eq!(query_protocol_fees(&env).collected, Uint128::new(100));
+    assert_eq!(query_dealer_balance(&env), Uint128::new(100_900));
+
+    // Surrender: dealer profit 500, fee 50
+    let game_id = create_and_deal(&mut env, &SeededGame::new(943), bet, 9, 5, 9);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap();
+    assert_eq!(query_protocol_fees(&env).collected, Uint128::new(150));
+    assert_eq!(query_dealer_balance(&env), Uint128::new(101_350));
+
+    // Player 19 vs dealer bust: the dealer loses, so no fee and the full bankroll back
+    let game = SeededGame::new(944);
+    let won = create_and_deal(&mut env, &game, bet, 9, 8, 5);
+    stand_and_finish(&mut env, &game, won, 9, &[9]);
+    assert_eq!(query_protocol_fees(&env).collected, Uint128::new(150));
+    assert_eq!(query_dealer_balance(&env), Uint128::new(100_350));
+
+    // Only the recipient can claim
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::ClaimProtocolFee {},
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"));
+
+    let claim = |env: &mut TestEnv| {
+        env.app.execute_contract(
+            treasury.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::ClaimProtocolFee {},
+            &[],
+        )
+    };
+    claim(&mut env).unwrap();
+    assert_eq!(
+        env.app.wrap().query_balance(&treasury, "utoken").unwrap(),
+        Coin::new(150u128, "utoken")
+    );
+    assert_eq!(query_protocol_fees(&env).collected, Uint128::zero());
+    // The dealer's balance is untouched by the claim
+    assert_eq!(query_dealer_balance(&env), Uint128::new(100_350));
+
+    let err = claim(&mut env).unwrap_err();
+    assert!(err.to_string().contains("No protocol fees to claim"));
+}
+
+// ===== GetGamesByStatus query =====
+fn query_games_by_status(
+    env: &TestEnv,
+    status_filter: Option<&str>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> GamesPageResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(
+            &env.contract,
+            &QueryMsg::GetGamesByStatus {
+                status_filter: status_filter.map(str::to_string),
+                start_after,
+                limit,
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn test_games_by_status_pagination() {
+    let mut env = setup_with_bankroll(400_000);
+
+    let game = SeededGame::new(909);
+    let dealt = create_and_deal(&mut env, &game, 1000, 9, 8, 5);
+    let waiting: Vec<u64> = (910..913)
+        .map(|seed| {
+            let resp = env
+                .app
+                .execute_contract(
+                    env.dealer.clone(),
+                    env.contract.clone(),
+                    &ExecuteMsg::CreateGame {
+                        public_key: dealer_pk(),
+                        shuffled_deck: SeededGame::new(seed).dealer_shuffled_deck(),
+                        proof: Binary::from(b"proof"),
+                        public_inputs: vec![],
+                    },
+                    &[],
+                )
+                .unwrap();
+            extract_game_id(&resp)
+        })
+        .collect();
+
+    let ids = |page: &GamesPageResponse| page.games.iter().map(|g| g.game_id).collect::<Vec<_>>();
+
+    // The dealt game is skipped by the filter and doesn't count towards the limit
+    let first = query_games_by_status(&env, Some("WaitingForPlayerJoin"), None, Some(2));
+    assert_eq!(ids(&first), &waiting[..2]);
+    assert_eq!(first.next_cursor, Some(waiting[1]));
+
+    let second = query_games_by_status(
+        &env,
+        Some("WaitingForPlayerJoin"),
+        first.next_cursor,
+        Some(2),
+    );
+    assert_eq!(ids(&second), &waiting[2..]);
+    assert_eq!(second.next_cursor, None);
+
+    // No filter and the default limit: everything on one page
+    let all = query_games_by_status(&env, None, None, None);
+    assert_eq!(ids(&all)[0], dealt);
+    assert_eq!(all.games.len(), 4);
+    assert_eq!(all.next_cursor, None);
+
+    // A zero limit still returns a page
+    let one = query_games_by_status(&env, None, None, Some(0));
+    assert_eq!(ids(&one), [dealt]);
+    assert_eq!(one.next_cursor, Some(dealt));
+}
+
+#[test]
+fn test_games_by_status_caps_reads() {
+    let mut env = setup();
+    let dealt = create_and_deal(&mut env, &SeededGame::new(1052), 1000, 9, 8, 5);
+    let waiting = create_game_with_deck(&mut env, SeededGame::new(1053).dealer_shuffled_deck());
+
+    // 600 more in-play games, copied from the dealt one
+    let game_key = |game_id: u64| {
+        let mut key = b"\x00\x05games".to_vec();
+        key.extend_from_slice(&game_id.to_be_bytes());
+        key
+    };
+    let mut storage = env.app.contract_storage_mut(&env.contract);
+    let dealt_game = storage.get(&game_key(dealt)).unwrap();
+    for game_id in (waiting + 1)..=(waiting + 600) {
+        storage.set(&game_key(game_id), &dealt_game);
+    }
+    // Next id after the copies
+    storage.set(b"game_counter", (waiting + 601).to_string().as_bytes());
+    drop(storage);
+    let waiting_after =
+        create_game_with_deck(&mut env, SeededGame::new(1054).dealer_shuffled_deck());
+    assert_eq!(waiting_after, waiting + 601);
+
+    let filter = Some("WaitingForPlayerJoin");
+    // 500 reads reach the 498th copy; the page is short and says where to go on
+    let first = query_games_by_status(&env, filter, None, Some(10));
+    assert_eq!(
+        first.games.iter().map(|g| g.game_id).collect::<Vec<_>>(),
+        [waiting]
+    );
+    assert_eq!(first.next_cursor, Some(waiting + 498));
+    let second = query_games_by_status(&env, filter, first.next_cursor, Some(10));
+    assert_eq!(
+        second.games.iter().map(|g| g.game_id).collect::<Vec<_>>(),
+        [waiting_after]
+    );
+    assert_eq!(second.next_cursor, None);
+}
+
+// ===== Migration =====
+fn migrate(env: &mut TestEnv, new_version: &str) -> AppResponse {
+    let code_id = env
+        .app
+        .wrap()
+        .query_wasm_contract_info(&env.contract)
+        .unwrap()
+        .code_id;
+    env.app
+        .migrate_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &MigrateMsg {
+                new_version: new_version.to_string(),
+            },
+            code_id,
+        )
+        .unwrap()
+}
+
+fn raw_state(env: &TestEnv, key: &[u8]) -> Option<serde_json::Value> {
+    env.app
+        .wrap()
+        .query_wasm_raw(&env.contract, key)
+        .unwrap()
+        .map(|bytes| serde_json::from_slice(&bytes).unwrap())
+}
+
+#[test]
+fn test_migrate_preserves_games_and_fills_defaults() {
+    let mut env = setup();
+    let game = SeededGame::new(450);
+    let settled = create_and_deal(&mut env, &game, 1000, 9, 8, 5);
+    stand_and_finish(&mut env, &game, settled, 9, &[9]);
+    let game = SeededGame::new(451);
+    let active = create_and_deal(&mut env, &game, 1000, 9, 5, 9);
+    let games_before = [query_game(&env, settled), query_game(&env, active)];
+
+    // Store config and stats the way a contract from before those fields would have
+    let mut config = raw_state(&env, b"config").unwrap();
+    for field in [
+        "double_after_split",
+        "resplit_aces",
+        "debug_mode",
+        "vk_version",
+        "version",
+    ] {
+        config.as_object_mut().unwrap().remove(field);
+    }
+    let mut storage = env.app.contract_storage_mut(&env.contract);
+    storage.set(b"config", &serde_json::to_vec(&config).unwrap());
+    storage.remove(b"house_stats");
+    // Player stats and their indexes, all under namespaces starting with player_stats
+    let stats_keys: Vec<Vec<u8>> = storage
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| key)
+        .filter(|key| key.windows(12).any(|w| w == b"player_stats"))
+        .collect();
+    assert_eq!(stats_keys.len(), 3);
+    for key in &stats_keys {
+        storage.remove(key);
+    }
+    // The in-progress game's ACTIVE_GAMES entry
+    let mut active_key = b"\x00\x0cactive_games".to_vec();
+    active_key.extend_from_slice(&active.to_be_bytes());
+    assert!(storage.get(&active_key).is_some());
+    storage.remove(&active_key);
+    drop(storage);
+    // Stats are rebuilt from games settled before the migration's block
+    env.app.update_block(|b| b.height += 1);
+
+    let resp = migrate(&mut env, "0.2.0");
+    let wasm = resp.events.iter().find(|e| e.ty == "wasm").unwrap();
+    let attr = |key: &str| {
+        wasm.attributes
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+    };
+    assert_eq!(attr("migrated_from").as_deref(), Some("0.1.0"));
+    assert_eq!(attr("migrated_to").as_deref(), Some("0.2.0"));
+    assert_eq!(attr("stats_rebuilt").as_deref(), Some("true"));
+
+    let config = raw_state(&env, b"config").unwrap();
+    assert_eq!(config["double_after_split"], true);
+    assert_eq!(config["resplit_aces"], false);
+    assert_eq!(config["debug_mode"], false);
+    assert_eq!(config["vk_version"], 0);
+    assert_eq!(config["version"], 0);
+    assert!(raw_state(&env, b"house_stats").is_some());
+    // Rebuilt from the settled game
+    let stats = query_player_stats(&env, &env.player);
+    assert_eq!((stats.games_played, stats.wins), (1, 1));
+    assert_eq!(stats.net_profit, Int128::new(1000));
+    assert_eq!(
+        raw_state(&env, b"contract_info").unwrap()["version"],
+        "0.2.0"
+    );
+    assert_eq!(
+        [query_game(&env, settled), query_game(&env, active)],
+        games_before
+    );
+    // Back in ACTIVE_GAMES, so the key rotation guard sees it
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &update_vks_msg(V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID),
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("1 game(s) still in progress"),
+        "{err}"
+    );
+
+    // Running it again changes nothing but the recorded source version
+    let resp = migrate(&mut env, "0.2.0");
+    let wasm = resp.events.iter().find(|e| e.ty == "wasm").unwrap();
+    assert!(wasm
+        .attributes
+        .iter()
+        .any(|a| a.key == "migrated_from" && a.value == "0.2.0"));
+    assert_eq!(raw_state(&env, b"config").unwrap(), config);
+    assert_eq!(query_player_stats(&env, &env.player), stats);
+    assert_eq!(
+        [query_game(&env, settled), query_game(&env, active)],
+        games_before
+    );
+}
+
+#[test]
+fn test_player_stats_backfill_in_batches() {
+    let mut env = setup();
+    let height = env.app.block_info().height;
+    let player2 = MockApi::default().addr_make("player2");
+    // Settled games as an older version stored them, without any player stats
+    let mut storage = env.app.contract_storage_mut(&env.contract);
+    let mut store_settled = |player: &Addr, game_id: u64, block_height: u64| {
+        let mut key = b"\x00\x14player_settled_games".to_vec();
+        key.extend_from_slice(&(player.as_bytes().len() as u16).to_be_bytes());
+        key.extend_from_slice(player.as_bytes());
+        key.extend_from_slice(&game_id.to_be_bytes());
+        let game = serde_json::json!({
+            "bet": "100",
+            "payout": "200",
+            "outcome": "win",
+            "block_height": block_height,
+            "forfeit_reason": null,
+        });
+        storage.set(&key, &serde_json::to_vec(&game).unwrap());
+    };
+    for game_id in 1..=250 {
+        store_settled(&env.player, game_id, height);
+    }
+    // Settled from the migration's block on, so already counted at settlement
+    store_settled(&player2, 251, height + 1);
+    drop(storage);
+    env.app.update_block(|b| b.height += 1);
+
+    let rebuilt = |resp: &AppResponse| {
+        resp.events
+            .iter()
+            .flat_map(|e| &e.attributes)
+            .find(|a| a.key == "stats_rebuilt")
+            .map(|a| a.value.clone())
+    };
+    let resp = migrate(&mut env, "0.2.0");
+    assert_eq!(rebuilt(&resp).as_deref(), Some("false"));
+    assert_eq!(query_player_stats(&env, &env.player).games_played, 200);
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::BackfillPlayerStats {},
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"));
+
+    let resp = dealer_exec(&mut env, &ExecuteMsg::BackfillPlayerStats {}).unwrap();
+    assert_eq!(rebuilt(&resp).as_deref(), Some("true"));
+    let stats = query_player_stats(&env, &env.player);
+    assert_eq!((stats.games_played, stats.wins), (250, 250));
+    assert_eq!(stats.net_profit, Int128::new(25_000));
+    assert_eq!(query_player_stats(&env, &player2).games_played, 0);
+    assert_eq!(
+        query_top_players(&env, TopPlayersSort::ByNetProfit, 10),
+        vec![env.player.to_string()]
+    );
+
+    // Done: further calls and migrations leave the stats alone
+    let resp = dealer_exec(&mut env, &ExecuteMsg::BackfillPlayerStats {}).unwrap();
+    assert_eq!(rebuilt(&resp).as_deref(), Some("true"));
+    migrate(&mut env, "0.2.1");
+    assert_eq!(query_player_stats(&env, &env.player), stats);
+}
+
+#[test]
+fn test_active_games_backfill_in_batches() {
+    let mut env = setup();
+    let game = SeededGame::new(452);
+    let active = create_and_deal(&mut env, &game, 1000, 9, 5, 9);
+    // 250 more games in progress, stored before ACTIVE_GAMES existed
+    let game_key = |game_id: u64| {
+        let mut key = b"\x00\x05games".to_vec();
+        key.extend_from_slice(&game_id.to_be_bytes());
+        key
+    };
+    let mut active_key = b"\x00\x0cactive_games".to_vec();
+    active_key.extend_from_slice(&active.to_be_bytes());
+    let mut storage = env.app.contract_storage_mut(&env.contract);
+    let stored = storage.get(&game_key(active)).unwrap();
+    for game_id in 1000..1250 {
+        storage.set(&game_key(game_id), &stored);
+    }
+    storage.remove(&active_key);
+    drop(storage);
+
+    let rebuilt = |resp: &AppResponse| {
+        resp.events
+            .iter()
+            .flat_map(|e| &e.attributes)
+            .find(|a| a.key == "active_games_rebuilt")
+            .map(|a| a.value.clone())
+    };
+    let resp = migrate(&mut env, "0.2.0");
+    assert_eq!(rebuilt(&resp).as_deref(), Some("false"));
+
+    // Until every game is indexed, the in-progress guard can't be trusted
+    let err =
+        dealer_exec(&mut env, &update_vks_msg(V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID)).unwrap_err();
+    assert!(
+        err.to_string().contains("call BackfillActiveGames first"),
+        "{err}"
+    );
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::BackfillActiveGames {},
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"));
+
+    let resp = dealer_exec(&mut env, &ExecuteMsg::BackfillActiveGames {}).unwrap();
+    assert_eq!(rebuilt(&resp).as_deref(), Some("true"));
+    let pending: u32 = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetPendingRefunds {})
+        .unwrap();
+    assert_eq!(pending, 251);
+    let err =
+        dealer_exec(&mut env, &update_vks_msg(V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID)).unwrap_err();
+    assert!(
+        err.to_string().contains("251 game(s) still in progress"),
+        "{err}"
+    );
+
+    // Indexed games are kept up to date from here on, so migrating again starts no scan
+    let resp = migrate(&mut env, "0.2.1");
+    assert_eq!(rebuilt(&resp).as_deref(), Some("true"));
+}
+
+// ===== GetDealerDashboard query =====
+fn query_dashboard(env: &TestEnv) -> DealerDashboardResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetDealerDashboard {})
+        .unwrap()
+}
+
+#[test]
+fn test_dealer_dashboard() {
+    let mut env = setup();
+    let empty = query_dashboard(&env);
+    assert_eq!(empty.open_games, 0);
+    assert_eq!(empty.total_games, 0);
+    assert!(empty.locked.is_zero());
+    assert_eq!(empty.balance, Uint128::new(100_000));
+
+    let game = SeededGame::new(430);
+    // Player: 10+6=16, Dealer shows 10
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 5, 9);
+    env.app.update_block(|b| b.time = b.time.plus_seconds(15));
+
+    let dash = query_dashboard(&env);
+    assert_eq!(dash.open_games, 1);
+    assert_eq!(dash.total_games, 1);
+    assert_eq!(dash.available, query_dealer_balance(&env));
+    assert_eq!(dash.locked, Uint128::new(100_000) - dash.available);
+    assert_eq!(dash.balance, dash.available + dash.locked);
+    assert_eq!(dash.active_games.len(), 1);
+    let summary = &dash.active_games[0];
+    assert_eq!(summary.game_id, game_id);
+    assert_eq!(summary.player, env.player.to_string());
+    assert_eq!(summary.bet, Uint128::new(1000));
+    assert_eq!(summary.seconds_since_action, 15);
+    assert!(summary.status.contains("PlayerTurn"));
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap();
+
+    // Dealer keeps half the surrendered bet
+    let dash = query_dashboard(&env);
+    assert_eq!(dash.open_games, 0);
+    assert_eq!(dash.total_games, 1);
+    assert!(dash.locked.is_zero());
+    assert!(dash.active_games.is_empty());
+    assert_eq!(dash.total_volume, Uint128::new(1000));
+    assert_eq!(dash.house_profit, Int128::new(500));
+    assert_eq!(dash.balance, Uint128::new(100_500));
+
+    // A game waiting for a player locks its bankroll too
+    let waiting = create_game_with_deck(&mut env, SeededGame::new(431).dealer_shuffled_deck());
+    let dash = query_dashboard(&env);
+    assert_eq!(dash.open_games, 1);
+    assert_eq!(dash.total_games, 2);
+    assert!(!dash.locked.is_zero());
+    assert_eq!(dash.available, query_dealer_balance(&env));
+    assert_eq!(dash.balance, Uint128::new(200_500));
+    assert_eq!(dash.active_games[0].game_id, waiting);
+    assert_eq!(dash.active_games[0].status, "WaitingForPlayerJoin");
+
+    dealer_exec(&mut env, &ExecuteMsg::CancelGame { game_id: waiting }).unwrap();
+    let dash = query_dashboard(&env);
+    assert_eq!(dash.open_games, 0);
+    assert!(dash.locked.is_zero());
+}
+
+// ===== UpdateVerificationKeys =====
+fn query_config(env: &TestEnv) -> Config {
+    env.app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetConfig {})
+        .unwrap()
+}
+
+fn update_vks_msg(shuffle_vk_id: &str, reveal_vk_id: &str) -> ExecuteMsg {
+    ExecuteMsg::UpdateVerificationKeys {
+        shuffle_vk_id: shuffle_vk_id.to_string(),
+        reveal_vk_id: reveal_vk_id.to_string(),
+    }
+}
+
+#[test]
+fn test_update_verification_keys_rejected_with_open_games() {
+    let mut env = setup();
+    let game = SeededGame::new(440);
+    // Player: 10+6=16, Dealer shows 10
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 5, 9);
+
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &update_vks_msg(V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID),
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("1 game(s) still in progress"),
+        "Expected GamesInProgress, got: {err}"
+    );
+    assert_eq!(query_config(&env).shuffle_vk_id, SHUFFLE_VK_ID);
+
+    // Once the game settles the keys can change
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap();
+    let resp = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &update_vks_msg(V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID),
+            &[],
+        )
+        .unwrap();
+    let wasm = resp.events.iter().find(|e| e.ty == "wasm").unwrap();
+    let attr = |key: &str| {
+        wasm.attributes
+            .iter()
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+    };
+    assert_eq!(attr("old_shuffle_vk").as_deref(), Some(SHUFFLE_VK_ID));
+    assert_eq!(attr("new_reveal_vk").as_deref(), Some(V2_REVEAL_VK_ID));
+
+    let config = query_config(&env);
+    assert_eq!(config.shuffle_vk_id, V2_SHUFFLE_VK_ID);
+    assert_eq!(config.reveal_vk_id, V2_REVEAL_VK_ID);
+    assert_eq!(config.vk_version, 1);
+}
+
+#[test]
+fn test_config_history_tracks_updates() {
+    let mut env = setup();
+    assert_eq!(query_config(&env).version, 0);
+
+    let dealer_update = |env: &mut TestEnv, shuffle_vk_id: &str, reveal_vk_id: &str| {
+        env.app
+            .execute_contract(
+                env.dealer.clone(),
+                env.contract.clone(),
+                &update_vks_msg(shuffle_vk_id, reveal_vk_id),
+                &[],
+            )
+            .unwrap();
+    };
+    dealer_update(&mut env, V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID);
+    // Only the shuffle key changes back
+    dealer_update(&mut env, SHUFFLE_VK_ID, V2_REVEAL_VK_ID);
+
+    assert_eq!(query_config(&env).version, 2);
+    let history: Vec<ConfigChange> = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetConfigHistory {})
+        .unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|c| (
+                c.version,
+                c.field.as_str(),
+                c.old_value.as_str(),
+                c.new_value.as_str()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            (1, "shuffle_vk_id", SHUFFLE_VK_ID, V2_SHUFFLE_VK_ID),
+            (1, "reveal_vk_id", REVEAL_VK_ID, V2_REVEAL_VK_ID),
+            (2, "shuffle_vk_id", V2_SHUFFLE_VK_ID, SHUFFLE_VK_ID),
+        ]
+    );
+    assert!(history.iter().all(|c| c.changed_by == env.dealer));
+    assert!(history
+        .iter()
+        .all(|c| c.timestamp == env.app.block_info().time.seconds()));
+}
+
+#[test]
+fn test_update_verification_keys_checks_registration_and_sender() {
+    let mut env = setup();
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &update_vks_msg(V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID),
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"));
+
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &update_vks_msg(V2_SHUFFLE_VK_ID, "decrypt_typo"),
+            &[],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("decrypt_typo is not registered"),
+        "Expected VerificationKeyNotRegistered, got: {err}"
+    );
+
+    let config = query_config(&env);
+    assert_eq!(config.shuffle_vk_id, SHUFFLE_VK_ID);
+    assert_eq!(config.reveal_vk_id, REVEAL_VK_ID);
+    assert_eq!(config.vk_version, 0);
+}
+
+#[test]
+fn test_update_verification_keys_lookup_failure_is_not_unregistered() {
+    let verifier = ZkConditionalStargate::default();
+    let mut env = setup_with_verifier(default_instantiate_msg(), 100_000, verifier.clone());
+    verifier.vkey_lookup_down.set(true);
+
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &update_vks_msg(V2_SHUFFLE_VK_ID, V2_REVEAL_VK_ID),
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("zk module unavailable"), "{err}");
+    assert!(!err.to_string().contains("is not registered"), "{err}");
+    assert_eq!(query_config(&env).vk_version, 0);
+}
+
+// ===== UpdateConfig =====
+fn update_config(
+    env: &mut TestEnv,
+    sender: &Addr,
+    max_bet: Option<u128>,
+    timeout_seconds: Option<u64>,
+    surrender_allowed: Option<bool>,
+) -> StdResult<AppResponse> {
+    env.app.execute_contract(
+        sender.clone(),
+        env.contract.clone(),
+        &ExecuteMsg::UpdateConfig {
+            min_bet: None,
+            max_bet: max_bet.map(Uint128::new),
+            timeout_seconds,
+            surrender_allowed,
+        },
+        &[],
+    )
+}
+
+#[test]
+fn test_update_config_dealer_only_and_validated() {
+    let mut env = setup();
+
+    let player = env.player.clone();
+    let err = update_config(&mut env, &player, Some(5_000), None, None).unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"), "{err}");
+
+    // min_bet is 100, so a max_bet below it is rejected, as is a zero timeout
+    let dealer = env.dealer.clone();
+    let err = update_config(&mut env, &dealer, Some(50), None, None).unwrap_err();
+    assert!(
+        err.to_string().contains("min_bet cannot exceed max_bet"),
+        "{err}"
+    );
+    let err = update_config(&mut env, &dealer, None, Some(0), None).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("timeout_seconds must be greater than zero"),
+        "{err}"
+    );
+    assert_eq!(query_config(&env).version, 0);
+
+    // Omitted fields keep their value
+    update_config(&mut env, &dealer, None, Some(600), None).unwrap();
+    let config = query_config(&env);
+    assert_eq!(config.timeout_seconds, 600);
+    assert_eq!(config.max_bet, Uint128::new(10_000));
+    assert!(config.surrender_allowed);
+    assert_eq!(config.version, 1);
+}
+
+#[test]
+fn test_update_config_leaves_existing_games_alone() {
+    let mut env = setup_with_bankroll(300_000);
+
+    // One game in play, one waiting for a player
+    let playing = SeededGame::new(913);
+    let in_play = create_and_deal(&mut env, &playing, 5_000, 9, 5, 9);
+    let waiting_deck = SeededGame::new(914);
+    let resp = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: dealer_pk(),
+                shuffled_deck: waiting_deck.dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    let waiting = extract_game_id(&resp);
+
+    let dealer = env.dealer.clone();
+    update_config(&mut env, &dealer, Some(2_000), Some(600), Some(false)).unwrap();
+
+    let history: Vec<ConfigChange> = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetConfigHistory {})
+        .unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|c| (c.field.as_str(), c.new_value.as_str()))
+            .collect::<Vec<_>>(),
+        vec![
+            ("max_bet", "2000"),
+            ("timeout_seconds", "600"),
+            ("surrender_allowed", "false"),
+        ]
+    );
+
+    // The game in play can still surrender
+    let terms = query_game(&env, in_play).terms.unwrap();
+    assert!(terms.surrender_allowed);
+    assert_eq!(terms.timeout_seconds, 60);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id: in_play },
+            &[],
+        )
+        .unwrap();
+
+    // The waiting game takes a bet above the new maximum and times out after the old 60s
+    join_with_funds(
+        &mut env,
+        &waiting_deck,
+        5_000,
+        &[Coin::new(5_000u128, "utoken")],
+    )
+    .unwrap();
+    assert_eq!(
+        query_game(&env, waiting).terms.unwrap().max_bet,
+        Uint128::new(10_000)
+    );
+    env.app.update_block(|b| b.time = b.time.plus_seconds(61));
+    env.app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::ClaimTimeout { game_id: waiting },
+            &[],
+        )
+        .unwrap();
+
+    // New games are created under the new terms, locking 10x the new max bet
+    let balance = query_dealer_balance(&env);
+    let resp = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: dealer_pk(),
+                shuffled_deck: SeededGame::new(915).dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    let terms = query_game(&env, extract_game_id(&resp)).terms.unwrap();
+    assert_eq!(terms.max_bet, Uint128::new(2_000));
+    assert_eq!(terms.timeout_seconds, 600);
+    assert!(!terms.surrender_allowed);
+    assert_eq!(balance - query_dealer_balance(&env), Uint128::new(20_000));
+}
+
+// ===== GetAggregatedPublicKey query =====
+fn query_aggregated_key(env: &TestEnv, game_id: u64) -> StdResult<AggregatedKeyResponse> {
+    env.app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetAggregatedPublicKey { game_id })
+}
+
+#[test]
+fn test_aggregated_public_key() {
+    let mut env = setup();
+    let game = SeededGame::new(440);
+    let mut rng = ChaCha8Rng::seed_from_u64(440);
+    let dealer_keys = KeyPair::generate(&mut rng);
+    let player_keys = KeyPair::generate(&mut rng);
+
+    let resp = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: point_bytes(&dealer_keys.pk),
+                shuffled_deck: game.dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+    let game_id = extract_game_id(&resp);
+
+    let keys = query_aggregated_key(&env, game_id).unwrap();
+    assert_eq!(keys.dealer_pk, point_bytes(&dealer_keys.pk));
+    assert_eq!(keys.player_pk, None);
+    assert_eq!(keys.aggregated_pk, None);
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::JoinGame {
+                bet: Uint128::new(1000),
+                public_key: point_bytes(&player_keys.pk),
+                shuffled_deck: game.player_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[Coin::new(1000u128, "utoken")],
+        )
+        .unwrap();
+
+    let aggregated = (dealer_keys.pk.into_group() + player_keys.pk).into_affine();
+    let keys = query_aggregated_key(&env, game_id).unwrap();
+    assert_eq!(keys.dealer_pk, point_bytes(&dealer_keys.pk));
+    assert_eq!(keys.player_pk, Some(point_bytes(&player_keys.pk)));
+    assert_eq!(keys.aggregated_pk, Some(point_bytes(&aggregated)));
+}
+
+#[test]
+fn test_invalid_public_keys_rejected() {
+    let mut env = setup();
+    let game = SeededGame::new(441);
+    let create = |env: &mut TestEnv, public_key: Binary| {
+        env.app.execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key,
+                shuffled_deck: game.dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+    };
+    let join = |env: &mut TestEnv, public_key: Binary| {
+        env.app.execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::JoinGame {
+                bet: Uint128::new(1000),
+                public_key,
+                shuffled_deck: game.player_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[Coin::new(1000u128, "utoken")],
+        )
+    };
+
+    let mut trailing = player_pk().to_vec();
+    trailing.push(0);
+    let invalid = [
+        Binary::from(b"ppk"),
+        Binary::new(vec![0xff; 32]),
+        Binary::new(trailing),
+    ];
+    for key in &invalid {
+        let err = create(&mut env, key.clone()).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid public key"),
+            "Expected invalid key error, got: {err}"
+        );
+    }
+
+    let game_id = extract_game_id(&create(&mut env, dealer_pk()).unwrap());
+    for key in &invalid {
+        let err = join(&mut env, key.clone()).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid public key"),
+            "Expected invalid key error, got: {err}"
+        );
+    }
+    assert!(query_game(&env, game_id).player_pubkey.is_empty());
+
+    join(&mut env, player_pk()).unwrap();
+    assert!(query_aggregated_key(&env, game_id).is_ok());
+    assert!(query_aggregated_key(&env, game_id + 1).is_err());
+}
+
+// ===== Double after split =====
+/// Player 5+5 splits against a dealer 6; hand 1 draws a 6 (11), hand 2 a 3 (8).
+fn split_fives(env: &mut TestEnv, game: &SeededGame, bet: u128) -> u64 {
+    let game_id = create_and_deal(env, game, bet, 4, 4, 5);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Split { game_id },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap();
+    reveal_card(env, game, game_id, 4, 5);
+    reveal_card(env, game, game_id, 5, 2);
+    game_id
+}
+
+#[test]
+fn test_split_then_double_with_das() {
+    let mut env = setup();
+    let game = SeededGame::new(450);
+    let bet = 1000u128;
+    let game_id = split_fives(&mut env, &game, bet);
+
+    // Double hand 1 (11): draws 8 → 19 and stands
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::DoubleDown { game_id },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap();
+    reveal_card(&mut env, &game, game_id, 6, 7);
+
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands[0].bet, Uint128::new(2 * bet));
+    assert_eq!(g.hands[0].status, "Doubled");
+
+    // Hand 2 stands on 8
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap();
+
+    // Dealer: 6 + 9 = 15, hits a 10 → 25
+    reveal_card(&mut env, &game, game_id, 3, 8);
+    reveal_card(&mut env, &game, game_id, 7, 9);
+
+    let g = query_game(&env, game_id);
+    assert!(
+        g.status.contains("Player"),
+        "Expected player win on both hands, got: {}",
+        g.status
+    );
+    let total_bet: Uint128 = g.hands.iter().map(|h| h.bet).sum();
+    assert_eq!(total_bet, Uint128::new(bet * 3));
+
+    // Player wagered 3000 and is paid 6000: 100000 + 3000 - 6000
+    assert_eq!(query_dealer_balance(&env), Uint128::new(97_000));
+}
+
+#[test]
+fn test_split_no_double_after_split() {
+    let msg = InstantiateMsg {
+        double_after_split: Some(false),
+        ..default_instantiate_msg()
+    };
+    let mut env = setup_with_msg(msg, 100_000);
+    let game = SeededGame::new(451);
+    let bet = 1000u128;
+    let game_id = split_fives(&mut env, &game, bet);
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::DoubleDown { game_id },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Double down not allowed"),
+        "Expected DoubleNotAllowed, got: {err}"
+    );
+
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands[0].bet, Uint128::new(bet));
+    assert!(g.status.contains("PlayerTurn"));
+}
+
+// ===== Split aces =====
+/// Deals A♠ A♥ against a 6 and splits them
+fn split_aces(env: &mut TestEnv, game: &SeededGame, bet: u128) -> u64 {
+    let game_id = create_and_deal(env, game, bet, 0, 13, 5);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Split { game_id },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap();
+    game_id
+}
+
+fn player_exec(env: &mut TestEnv, msg: &ExecuteMsg, bet: u128) -> StdResult<AppResponse> {
+    let funds = if bet > 0 {
+        vec![Coin::new(bet, "utoken")]
+    } else {
+        vec![]
+    };
+    env.app
+        .execute_contract(env.player.clone(), env.contract.clone(), msg, &funds)
+}
+
+#[test]
+fn test_split_aces_disabled() {
+    let msg = InstantiateMsg {
+        can_split_aces: false,
+        ..default_instantiate_msg()
+    };
+    let mut env = setup_with_msg(msg, 100_000);
+    let game = SeededGame::new(903);
+    let game_id = create_and_deal(&mut env, &game, 1000, 0, 13, 5);
+
+    let err = player_exec(&mut env, &ExecuteMsg::Split { game_id }, 1000).unwrap_err();
+    assert!(
+        err.to_string().contains("Splitting aces not allowed"),
+        "Expected CannotSplitAces, got: {err}"
+    );
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands.len(), 1);
+    assert!(g.status.contains("PlayerTurn"));
+}
+
+#[test]
+fn test_split_aces_one_card_only() {
+    let mut env = setup();
+    let game = SeededGame::new(904);
+    let game_id = split_aces(&mut env, &game, 1000);
+
+    // Hand 0 draws a third ace it may not resplit, hand 1 an 8: both stand unasked
+    reveal_card(&mut env, &game, game_id, 4, 26);
+    reveal_card(&mut env, &game, game_id, 5, 7);
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands[0].status, "Stood");
+    assert_eq!(g.hands[1].status, "Stood");
+    assert!(
+        g.status.contains("reveal_requests: [3]"),
+        "Expected the hole card reveal, got: {}",
+        g.status
+    );
+
+    // Dealer: 6 + 10 = 16, hits a 10 and busts; both hands win
+    reveal_card(&mut env, &game, game_id, 3, 9);
+    reveal_card(&mut env, &game, game_id, 6, 9);
+    assert!(query_game(&env, game_id).status.contains("Settled"));
+    assert_eq!(query_dealer_balance(&env), Uint128::new(98_000));
+}
+
+#[test]
+fn test_split_aces_pair_can_only_resplit_or_stand() {
+    let msg = InstantiateMsg {
+        resplit_aces: Some(true),
+        ..default_instantiate_msg()
+    };
+    let mut env = setup_with_msg(msg, 100_000);
+    let game = SeededGame::new(905);
+    let bet = 1000u128;
+    let game_id = split_aces(&mut env, &game, bet);
+
+    // Hand 0 draws another ace, so it waits for the player to decide on a resplit
+    reveal_card(&mut env, &game, game_id, 4, 26);
+    reveal_card(&mut env, &game, game_id, 5, 9);
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands[0].status, "Active");
+    assert!(g.status.contains("PlayerTurn"));
+
+    let err = player_exec(&mut env, &ExecuteMsg::Hit { game_id }, 0).unwrap_err();
+    assert!(
+        err.to_string().contains("Hit not allowed"),
+        "Expected HitNotAllowed, got: {err}"
+    );
+    let err = player_exec(&mut env, &ExecuteMsg::DoubleDown { game_id }, bet).unwrap_err();
+    assert!(
+        err.to_string().contains("Double down not allowed"),
+        "Expected DoubleNotAllowed, got: {err}"
+    );
+
+    // Resplit: hand 0 draws a 5 and the new hand 2 an 8; every hand then stands,
+    // including hand 1 on its 21
+    player_exec(&mut env, &ExecuteMsg::Split { game_id }, bet).unwrap();
+    reveal_card(&mut env, &game, game_id, 6, 4);
+    reveal_card(&mut env, &game, game_id, 7, 7);
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands.len(), 3);
+    assert!(g.hands.iter().all(|h| h.status == "Stood"));
+    assert!(g.hands.iter().all(|h| h.bet == Uint128::new(bet)));
+    assert!(
+        g.status.contains("reveal_requests: [3]"),
+        "Expected the hole card reveal, got: {}",
+        g.status
+    );
+}
+
+#[test]
+fn test_resplit_aces_disabled() {
+    let msg = InstantiateMsg {
+        can_hit_split_aces: true,
+        ..default_instantiate_msg()
+    };
+    let mut env = setup_with_msg(msg, 100_000);
+    let game = SeededGame::new(906);
+    let game_id = split_aces(&mut env, &game, 1000);
+    reveal_card(&mut env, &game, game_id, 4, 26);
+    reveal_card(&mut env, &game, game_id, 5, 9);
+
+    let err = player_exec(&mut env, &ExecuteMsg::Split { game_id }, 1000).unwrap_err();
+    assert!(
+        err.to_string().contains("Resplitting aces not allowed"),
+        "Expected ResplitAcesNotAllowed, got: {err}"
+    );
+
+    // Hits are allowed here, so the split ace doesn't stand on its own
+    player_exec(&mut env, &ExecuteMsg::Hit { game_id }, 0).unwrap();
+    reveal_card(&mut env, &game, game_id, 6, 4);
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands[0].cards, vec![0, 26, 4]);
+    assert!(g.status.contains("PlayerTurn"));
+}
+
+// ===== Emergency refund =====
+fn query_pending_refunds(env: &TestEnv) -> u32 {
+    env.app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetPendingRefunds {})
+        .unwrap()
+}
+
+fn player_balance(env: &TestEnv) -> Uint128 {
+    let amount = env
+        .app
+        .wrap()
+        .query_balance(&env.player, "utoken")
+        .unwrap()
+        .amount;
+    Uint128::try_from(amount).unwrap()
+}
+
+fn dealer_exec(env: &mut TestEnv, msg: &ExecuteMsg) -> StdResult<AppResponse> {
+    env.app
+        .execute_contract(env.dealer.clone(), env.contract.clone(), msg, &[])
+}
+
+#[test]
+fn test_emergency_refund_requires_pause() {
+    let mut env = setup();
+    let game = SeededGame::new(460);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 5, 9);
+
+    let err = dealer_exec(&mut env, &ExecuteMsg::EmergencyRefundAll {}).unwrap_err();
+    assert!(
+        err.to_string().contains("must be paused first"),
+        "Expected ContractNotPaused, got: {err}"
+    );
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Pause {},
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"));
+
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id },
+            &[],
+        )
+        .unwrap();
+    let waiting = SeededGame::new(461);
+    create_game_with_deck(&mut env, waiting.dealer_shuffled_deck());
+
+    // Paused: no new games, and waiting games can't be joined
+    dealer_exec(&mut env, &ExecuteMsg::Pause {}).unwrap();
+    let err = try_join(&mut env, &waiting).unwrap_err();
+    assert!(err.to_string().contains("Contract is paused"));
+    let err = env
+        .app
+        .execute_contract(
+            env.dealer.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::CreateGame {
+                public_key: dealer_pk(),
+                shuffled_deck: SeededGame::new(462).dealer_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[Coin::new(100_000u128, "utoken")],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Contract is paused"));
+
+    dealer_exec(&mut env, &ExecuteMsg::Unpause {}).unwrap();
+    try_join(&mut env, &waiting).unwrap();
+}
+
+fn query_pause_status(env: &TestEnv) -> PauseStatusResponse {
+    env.app
+        .wrap()
+        .query_wasm_smart(env.contract.clone(), &QueryMsg::GetPauseStatus {})
+        .unwrap()
+}
+
+#[test]
+fn test_pause_leaves_in_flight_games_running() {
+    let mut env = setup();
+    let game_id = create_and_deal(&mut env, &SeededGame::new(916), 1000, 4, 4, 5);
+    assert_eq!(
+        query_pause_status(&env),
+        PauseStatusResponse {
+            paused: false,
+            paused_at: None
+        }
+    );
+
+    dealer_exec(&mut env, &ExecuteMsg::Pause {}).unwrap();
+    let paused_at = env.app.block_info().time.seconds();
+    assert_eq!(query_pause_status(&env).paused_at, Some(paused_at));
+
+    // Pausing again doesn't move the pause time
+    env.app.update_block(|b| b.time = b.time.plus_seconds(10));
+    dealer_exec(&mut env, &ExecuteMsg::Pause {}).unwrap();
+    assert_eq!(query_pause_status(&env).paused_at, Some(paused_at));
+
+    // The game already dealt plays on and times out as usual
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Hit { game_id },
+            &[],
+        )
+        .unwrap();
+    env.app.update_block(|b| b.time = b.time.plus_seconds(61));
+    dealer_exec(&mut env, &ExecuteMsg::ClaimTimeout { game_id }).unwrap();
+    let g = query_game(&env, game_id);
+    assert!(g.status.contains("Dealer"), "got: {}", g.status);
+
+    dealer_exec(&mut env, &ExecuteMsg::Unpause {}).unwrap();
+    assert_eq!(
+        query_pause_status(&env),
+        PauseStatusResponse {
+            paused: false,
+            paused_at: None
+        }
+    );
+}
+
+#[test]
+fn test_emergency_refund_all() {
+    let mut env = setup();
+    let bet = 1000u128;
+
+    // Settled before the emergency: not refunded
+    let settled = create_and_deal(&mut env, &SeededGame::new(465), bet, 9, 5, 9);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Surrender { game_id: settled },
+            &[],
+        )
+        .unwrap();
+
+    // Doubled, waiting on the double card (2000 staked)
+    let game1 = SeededGame::new(466);
+    let doubled = create_and_deal(&mut env, &game1, bet, 4, 5, 5);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::DoubleDown { game_id: doubled },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap();
+
+    // Joined, only the player's partial for card 0 in (1000 staked)
+    let game2 = SeededGame::new(467);
+    let joined = create_game_with_deck(&mut env, game2.dealer_shuffled_deck());
+    try_join(&mut env, &game2).unwrap();
+    batch_reveal(&mut env, &game2, joined, true, &[(0, 9)]).unwrap();
+
+    // Waiting for a player, nothing to refund
+    let waiting = create_game_with_deck(&mut env, SeededGame::new(468).dealer_shuffled_deck());
+
+    assert_eq!(query_pending_refunds(&env), 2);
+    let dealer_before = query_dealer_balance(&env);
+    let player_before = player_balance(&env);
+
+    dealer_exec(&mut env, &ExecuteMsg::Pause {}).unwrap();
+    let resp = dealer_exec(&mut env, &ExecuteMsg::EmergencyRefundAll {}).unwrap();
+
+    let refunds: Vec<(String, String)> = resp
+        .events
+        .iter()
+        .filter(|e| e.ty == "wasm-emergency_refund")
+        .map(|e| {
+            let attr = |key: &str| {
+                e.attributes
+                    .iter()
+                    .find(|a| a.key == key)
+                    .unwrap()
+                    .value
+                    .clone()
+            };
+            assert_eq!(attr("player"), env.player.to_string());
+            (attr("game_id"), attr("amount"))
+        })
+        .collect();
+    assert_eq!(
+        refunds,
+        vec![
+            (doubled.to_string(), "2000".to_string()),
+            (joined.to_string(), "1000".to_string()),
+        ]
+    );
+
+    assert_eq!(player_balance(&env), player_before + Uint128::new(3000));
+    // Both games' locked bankroll is back with the dealer
+    assert_eq!(
+        query_dealer_balance(&env),
+        dealer_before + Uint128::new(200_000)
+    );
+    for game_id in [doubled, joined] {
+        let g = query_game(&env, game_id);
+        assert_eq!(g.status, "EmergencyRefunded");
+        assert!(g.pending_reveals.is_empty());
+        assert!(query_reveal_requests(&env, game_id).is_empty());
+    }
+    assert!(query_game(&env, waiting)
+        .status
+        .contains("WaitingForPlayerJoin"));
+    assert_eq!(query_pending_refunds(&env), 0);
+
+    // Calling again refunds nothing
+    let resp = dealer_exec(&mut env, &ExecuteMsg::EmergencyRefundAll {}).unwrap();
+    assert!(!resp.events.iter().any(|e| e.ty == "wasm-emergency_refund"));
+    assert_eq!(player_balance(&env), player_before + Uint128::new(3000));
+}
+
+#[test]
+fn test_emergency_refund_all_batches() {
+    let mut env = setup();
+    for seed in 0..21 {
+        let game = SeededGame::new(470 + seed);
+        create_game_with_deck(&mut env, game.dealer_shuffled_deck());
+        try_join(&mut env, &game).unwrap();
+    }
+    let player_before = player_balance(&env);
+
+    dealer_exec(&mut env, &ExecuteMsg::Pause {}).unwrap();
+    dealer_exec(&mut env, &ExecuteMsg::EmergencyRefundAll {}).unwrap();
+    assert_eq!(query_pending_refunds(&env), 1);
+    assert_eq!(player_balance(&env), player_before + Uint128::new(20_000));
+
+    dealer_exec(&mut env, &ExecuteMsg::EmergencyRefundAll {}).unwrap();
+    assert_eq!(query_pending_refunds(&env), 0);
+    assert_eq!(player_balance(&env), player_before + Uint128::new(21_000));
+}
+
+// ===== ForfeitGame =====
+#[test]
+fn test_forfeit_game_pays_max_win() {
+    let mut env = setup();
+    let bet = 1000u128;
+
+    // Doubled, waiting on the double card: 2000 staked
+    let game = SeededGame::new(900);
+    let game_id = create_and_deal(&mut env, &game, bet, 4, 5, 5);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::DoubleDown { game_id },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap();
+
+    let dealer_before = query_dealer_balance(&env);
+    let player_before = player_balance(&env);
+    let reason = "Wrong reveal VK ID deployed";
+    let resp = dealer_exec(
+        &mut env,
+        &ExecuteMsg::ForfeitGame {
+            game_id,
+            reason: Some(reason.to_string()),
+        },
+    )
+    .unwrap();
+
+    // Stake back plus the best possible win on it, a 3:2 natural
+    let max_win = 3000u128;
+    let attr = |key: &str| {
+        resp.events
+            .iter()
+            .flat_map(|e| &e.attributes)
+            .find(|a| a.key == key)
+            .map(|a| a.value.clone())
+    };
+    assert_eq!(attr("game_forfeited").as_deref(), Some("true"));
+    assert_eq!(attr("reason").as_deref(), Some(reason));
+    assert_eq!(attr("amount_paid"), Some((2000 + max_win).to_string()));
+    assert_eq!(
+        player_balance(&env),
+        player_before + Uint128::new(2000 + max_win)
+    );
+    // The rest of the locked bankroll is back with the dealer
+    assert_eq!(
+        query_dealer_balance(&env),
+        dealer_before + Uint128::new(100_000 - max_win)
+    );
+
+    assert_eq!(
+        query_game(&env, game_id).status,
+        format!("Forfeited {{ reason: Some({reason:?}) }}")
+    );
+    let settled = query_settled_games(&env, None, 10).games;
+    assert_eq!(settled.len(), 1);
+    assert_eq!(settled[0].outcome, GameOutcome::Forfeit);
+    assert_eq!(settled[0].bet, Uint128::new(2000));
+    assert_eq!(settled[0].payout, Uint128::new(2000 + max_win));
+    assert_eq!(settled[0].forfeit_reason.as_deref(), Some(reason));
+
+    // Over: no more moves, and no second forfeit
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap_err();
+    assert!(!err.to_string().is_empty());
+    let err = dealer_exec(
+        &mut env,
+        &ExecuteMsg::ForfeitGame {
+            game_id,
+            reason: None,
+        },
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("in progress"), "got: {err}");
+}
+
+#[test]
+fn test_forfeit_game_rejections() {
+    let mut env = setup();
+    let game = SeededGame::new(901);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 5, 9);
+    let waiting = create_game_with_deck(&mut env, SeededGame::new(902).dealer_shuffled_deck());
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::ForfeitGame {
+                game_id,
+                reason: None,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"));
+
+    let err = dealer_exec(
+        &mut env,
+        &ExecuteMsg::ForfeitGame {
+            game_id,
+            reason: Some("x".repeat(257)),
+        },
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Forfeit reason is 257 characters, max 256"),
+        "got: {err}"
+    );
+
+    // Nobody to pay yet; the dealer cancels instead
+    let err = dealer_exec(
+        &mut env,
+        &ExecuteMsg::ForfeitGame {
+            game_id: waiting,
+            reason: None,
+        },
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("in progress"), "got: {err}");
+
+    // Without a reason there is no reason attribute
+    let resp = dealer_exec(
+        &mut env,
+        &ExecuteMsg::ForfeitGame {
+            game_id,
+            reason: None,
+        },
+    )
+    .unwrap();
+    assert!(!resp
+        .events
+        .iter()
+        .flat_map(|e| &e.attributes)
+        .any(|a| a.key == "reason"));
+    assert_eq!(
+        query_game(&env, game_id).status,
+        "Forfeited { reason: None }"
+    );
+}
+
+// ===== GetContractState dumps a game's internal state in debug mode =====
+#[test]
+fn test_contract_state_query() {
+    let msg = InstantiateMsg {
+        debug_mode: Some(true),
+        ..default_instantiate_msg()
+    };
+    let mut env = setup_with_msg(msg, 100_000);
+    let game = SeededGame::new(907);
+    let game_id = create_and_deal(&mut env, &game, 1000, 9, 4, 6);
+
+    // Hit, with only the player's partial in for the new card (3 is the hole card)
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Hit { game_id },
+            &[],
+        )
+        .unwrap();
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::SubmitReveal {
+                game_id,
+                card_index: 4,
+                partial_decryption: game.player_partial(4),
+                proof: Binary::from(b"p"),
+                public_inputs: vec![],
+            },
+            &[],
+        )
+        .unwrap();
+
+    let state: ContractStateResponse = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetContractState { game_id })
+        .unwrap();
+    assert_eq!(state.phase, "WaitingForReveal");
+    assert_eq!(state.game.hands[0].cards, [9, 4]);
+    assert_eq!(state.game.dealer_hand, [6]);
+    assert_eq!(state.game.last_card_index, 5);
+    assert_eq!(state.game.deck, query_game(&env, game_id).deck);
+    assert_eq!(state.game.pending_reveals.len(), 1);
+    let pending = &state.game.pending_reveals[0];
+    assert_eq!(pending.card_index, 4);
+    assert_eq!(pending.player_partial, Some(game.player_partial(4)));
+    assert_eq!(pending.dealer_partial, None);
+    assert_eq!(
+        state.game.last_action_timestamp,
+        env.app.block_info().time.seconds()
+    );
+    let revealed: Vec<_> = state
+        .reveal_history
+        .iter()
+        .map(|r| (r.card_index, r.revealed_value))
+        .collect();
+    assert_eq!(
+        revealed,
+        [(0, Some(9)), (1, Some(4)), (2, Some(6)), (4, None)]
+    );
+
+    // Binary fields go out base64 encoded
+    let raw: serde_json::Value = env
+        .app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetContractState { game_id })
+        .unwrap();
+    assert_eq!(
+        raw["game"]["pending_reveals"][0]["player_partial"],
+        game.player_partial(4).to_base64()
+    );
+
+    // Off by default
+    let mut env = setup();
+    let game_id = create_and_deal(&mut env, &SeededGame::new(908), 1000, 9, 4, 6);
+    let err = env
+        .app
+        .wrap()
+        .query_wasm_smart::<ContractStateResponse>(
+            &env.contract,
+            &QueryMsg::GetContractState { game_id },
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("debug_mode is off"), "{err}");
+}
+
+// ===== BurnExpiredGames =====
+/// `game_id` and `outcome` of each `game_burned` event
+fn burned_games(resp: &AppResponse) -> Vec<(u64, String)> {
+    resp.events
+        .iter()
+        .filter(|e| e.ty == "wasm-game_burned")
+        .map(|e| {
+            let attr = |key: &str| {
+                e.attributes
+                    .iter()
+                    .find(|a| a.key == key)
+                    .unwrap()
+                    .value
+                    .clone()
+            };
+            (attr("game_id").parse().unwrap(), attr("outcome"))
+        })
+        .collect()
+}
+
+#[test]
+fn test_burn_expired_games() {
+    // Room for the game left in play and the recent one
+    let mut env = setup_with_bankroll(200_000);
+    let surrendered_game = |env: &mut TestEnv, seed| {
+        let game_id = create_and_deal(env, &SeededGame::new(seed), 1000, 9, 5, 9);
+        env.app
+            .execute_contract(
+                env.player.clone(),
+                env.contract.clone(),
+                &ExecuteMsg::Surrender { game_id },
+                &[],
+            )
+            .unwrap();
+        game_id
+    };
+    let old: Vec<u64> = (945..1045)
+        .map(|seed| surrendered_game(&mut env, seed))
+        .collect();
+    let active = create_and_deal(&mut env, &SeededGame::new(1045), 1000, 9, 8, 5);
+    env.app
+        .update_block(|b| b.time = b.time.plus_seconds(7 * 24 * 60 * 60));
+    let recent = surrendered_game(&mut env, 1046);
+    let burn_after = |env: &mut TestEnv, start_after, before_game_id, limit| {
+        dealer_exec(
+            env,
+            &ExecuteMsg::BurnExpiredGames {
+                start_after,
+                before_game_id,
+                limit,
+            },
+        )
+    };
+    let burn =
+        |env: &mut TestEnv, before_game_id, limit| burn_after(env, None, before_game_id, limit);
+    let last_scanned = |resp: &AppResponse| -> Option<u64> {
+        resp.events
+            .iter()
+            .flat_map(|e| &e.attributes)
+            .find(|a| a.key == "last_scanned")
+            .map(|a| a.value.parse().unwrap())
+    };
+
+    let err = env
+        .app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::BurnExpiredGames {
+                start_after: None,
+                before_game_id: None,
+                limit: 10,
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Unauthorized"));
+    let err = burn(&mut env, None, 51).unwrap_err();
+    assert!(err.to_string().contains("limit must be between 1 and 50"));
+
+    // Only ids between start_after and before_game_id, oldest first
+    let resp = burn_after(&mut env, Some(old[0]), Some(old[10]), 50).unwrap();
+    assert_eq!(
+        burned_games(&resp),
+        old[1..10]
+            .iter()
+            .map(|&id| (id, "Surrender".to_string()))
+            .collect::<Vec<_>>()
+    );
+    // The range ran out before the limit: nothing left to page through
+    assert_eq!(last_scanned(&resp), None);
+    assert_eq!(
+        burned_games(&burn(&mut env, None, 1).unwrap()),
+        vec![(old[0], "Surrender".to_string())]
+    );
+
+    // Never more than limit per call; the cursor picks up after the last game read
+    let resp = burn(&mut env, None, 50).unwrap();
+    let burned = burned_games(&resp);
+    assert_eq!(burned.first().map(|b| b.0), Some(old[10]));
+    assert_eq!(burned.len(), 50);
+    assert_eq!(last_scanned(&resp), Some(old[59]));
+    // Reads the 40 remaining old games, then the two that are not burnable
+    let resp = burn_after(&mut env, last_scanned(&resp), None, 50).unwrap();
+    let burned = burned_games(&resp);
+    assert_eq!(burned.len(), 40);
+    assert_eq!(burned.last().map(|b| b.0), old.last().copied());
+    assert_eq!(last_scanned(&resp), None);
+    let resp = burn_after(&mut env, Some(old[99]), None, 50).unwrap();
+    assert!(burned_games(&resp).is_empty());
+
+    for game_id in &old {
+        assert!(env
+            .app
+            .wrap()
+            .query_wasm_smart::<GameResponse>(
+                &env.contract,
+                &QueryMsg::GetGame { game_id: *game_id }
+            )
+            .is_err());
+    }
+    assert!(query_game(&env, active).status.contains("PlayerTurn"));
+    assert!(query_game(&env, recent).status.contains("Surrendered"));
+    // Player history outlives the game
+    assert_eq!(query_player_stats(&env, &env.player).surrenders, 101);
+}
+
+// ===== SubmitBatchReveal =====
+/// Create and join a game, leaving the initial three cards unrevealed
+fn create_and_join(env: &mut TestEnv, game: &SeededGame, bet: u128) -> u64 {
+    let game_id = extract_game_id(&create_game(env, game).unwrap());
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::JoinGame {
+                bet: Uint128::new(bet),
+                public_key: player_pk(),
+                shuffled_deck: game.player_shuffled_deck(),
+                proof: Binary::from(b"proof"),
+                public_inputs: vec![],
+            },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap();
+    game_id
+}
+
+/// Submit one party's partials for every `(card_index, card_value)` in one transaction
+fn batch_reveal(
+    env: &mut TestEnv,
+    game: &SeededGame,
+    game_id: u64,
+    as_player: bool,
+    cards: &[(u32, u8)],
+) -> StdResult<AppResponse> {
+    let reveals = cards
+        .iter()
+        .map(|&(card_index, card_value)| RevealItem {
+            card_index,
+            partial_decryption: if as_player {
+                game.player_partial(card_index)
+            } else {
+                game.dealer_partial(card_index, card_value)
+            },
+            proof: Binary::from(b"p"),
+            public_inputs: vec![],
+        })
+        .collect();
+    let sender = if as_player {
+        env.player.clone()
+    } else {
+        env.dealer.clone()
+    };
+    env.app.execute_contract(
+        sender,
+        env.contract.clone(),
+        &ExecuteMsg::SubmitBatchReveal { game_id, reveals },
+        &[],
+    )
+}
+
+fn revealed_values(resp: &AppResponse) -> Vec<(String, String)> {
+    resp.events
+        .iter()
+        .filter(|e| e.ty == "wasm-reveal")
+        .filter_map(|e| {
+            let attr = |key: &str| {
+                e.attributes
+                    .iter()
+                    .find(|a| a.key == key)
+                    .map(|a| a.value.clone())
+            };
+            Some((attr("card_index")?, attr("card_value")?))
+        })
+        .collect()
+}
+
+#[test]
+fn test_batch_reveal_initial_deal() {
+    let mut env = setup();
+    let game = SeededGame::new(1047);
+    let game_id = create_and_join(&mut env, &game, 1000);
+    let cards = [(0u32, 9u8), (1, 8), (2, 5)];
+
+    let resp = batch_reveal(&mut env, &game, game_id, true, &cards).unwrap();
+    assert!(revealed_values(&resp).is_empty());
+    assert_eq!(
+        query_reveal_requests(&env, game_id),
+        (0..3)
+            .map(|card_index| PendingReveal {
+                card_index,
+                player_submitted: true,
+                dealer_submitted: false,
+            })
+            .collect::<Vec<_>>()
+    );
+
+    let resp = batch_reveal(&mut env, &game, game_id, false, &cards).unwrap();
+    assert_eq!(
+        revealed_values(&resp),
+        vec![
+            ("0".to_string(), "9".to_string()),
+            ("1".to_string(), "8".to_string()),
+            ("2".to_string(), "5".to_string()),
+        ]
+    );
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands[0].cards, vec![9, 8]);
+    assert_eq!(g.dealer_hand, vec![5]);
+    assert!(g.status.contains("PlayerTurn"), "{}", g.status);
+
+    // Same end state as revealing the cards one by one
+    let single = SeededGame::new(1048);
+    let single_id = create_and_deal(&mut env, &single, 1000, 9, 8, 5);
+    let s = query_game(&env, single_id);
+    assert_eq!(s.hands[0].cards, g.hands[0].cards);
+    assert_eq!(s.dealer_hand, g.dealer_hand);
+    assert_eq!(s.status, g.status);
+}
+
+#[test]
+fn test_batch_reveal_split_cards_and_settle() {
+    let mut env = setup();
+    let game = SeededGame::new(1049);
+    let bet = 1000u128;
+    let game_id = create_and_deal(&mut env, &game, bet, 7, 7, 5);
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Split { game_id },
+            &[Coin::new(bet, "utoken")],
+        )
+        .unwrap();
+
+    let cards = [(4u32, 9u8), (5, 9)];
+    batch_reveal(&mut env, &game, game_id, true, &cards).unwrap();
+    batch_reveal(&mut env, &game, game_id, false, &cards).unwrap();
+    let g = query_game(&env, game_id);
+    assert_eq!(g.hands[0].cards, vec![7, 9]);
+    assert_eq!(g.hands[1].cards, vec![7, 9]);
+
+    for _ in 0..2 {
+        env.app
+            .execute_contract(
+                env.player.clone(),
+                env.contract.clone(),
+                &ExecuteMsg::Stand { game_id },
+                &[],
+            )
+            .unwrap();
+    }
+    // A single-item batch settles the game just like SubmitReveal: hole 10 → 16, hit 10 → bust
+    reveal_card(&mut env, &game, game_id, 3, 9);
+    batch_reveal(&mut env, &game, game_id, true, &[(6, 9)]).unwrap();
+    let resp = batch_reveal(&mut env, &game, game_id, false, &[(6, 9)]).unwrap();
+    assert!(resp.events.iter().any(|e| e.ty == "transfer"));
+    let g = query_game(&env, game_id);
+    assert!(g.status.contains("Settled"), "{}", g.status);
+}
+
+#[test]
+fn test_batch_reveal_is_atomic() {
+    let verifier = ZkConditionalStargate::default();
+    let mut env = setup_with_verifier(default_instantiate_msg(), 100_000, verifier.clone());
+    let game = SeededGame::new(1050);
+    let game_id = create_and_join(&mut env, &game, 1000);
+    let untouched = query_reveal_requests(&env, game_id);
+
+    let err = batch_reveal(&mut env, &game, game_id, true, &[]).unwrap_err();
+    assert!(err.to_string().contains("at least one item"));
+
+    // Card 3 is not pending, so the three valid items before it are dropped too
+    let err = batch_reveal(
+        &mut env,
+        &game,
+        game_id,
+        true,
+        &[(0, 9), (1, 8), (2, 5), (3, 6)],
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("Card 3 not in pending reveals"),
+        "{err}"
+    );
+    assert_eq!(query_reveal_requests(&env, game_id), untouched);
+
+    verifier.reject_reveal.set(true);
+    let err = batch_reveal(&mut env, &game, game_id, true, &[(0, 9), (1, 8), (2, 5)]).unwrap_err();
+    assert!(err.to_string().contains("Invalid reveal proof"), "{err}");
+    assert_eq!(query_reveal_requests(&env, game_id), untouched);
+
+    // A repeated index fails on the second copy
+    verifier.reject_reveal.set(false);
+    let err = batch_reveal(&mut env, &game, game_id, true, &[(0, 9), (0, 9)]).unwrap_err();
+    assert!(err.to_string().contains("Player already revealed"), "{err}");
+    assert_eq!(query_reveal_requests(&env, game_id), untouched);
+
+    // Only the game's player and dealer may submit
+    let stranger = MockApi::default().addr_make("stranger");
+    let err = env
+        .app
+        .execute_contract(
+            stranger,
+            env.contract.clone(),
+            &ExecuteMsg::SubmitBatchReveal {
+                game_id,
+                reveals: vec![RevealItem {
+                    card_index: 0,
+                    partial_decryption: game.player_partial(0),
+                    proof: Binary::from(b"p"),
+                    public_inputs: vec![],
+                }],
+            },
+            &[],
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("Sender is not part of this game"));
+}
+
+// ===== GetRevealStatus query =====
+fn query_reveal_status(env: &TestEnv, game_id: u64) -> StdResult<RevealStatusResponse> {
+    env.app
+        .wrap()
+        .query_wasm_smart(&env.contract, &QueryMsg::GetRevealStatus { game_id })
+}
+
+#[test]
+fn test_reveal_status_tracks_progress() {
+    let mut env = setup();
+    let game = SeededGame::new(1051);
+    let game_id = create_and_join(&mut env, &game, 1000);
+    let completed = |cards: &[(u32, u8)]| {
+        cards
+            .iter()
+            .map(|&(card_index, card_value)| CompletedReveal {
+                card_index,
+                card_value,
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let status = query_reveal_status(&env, game_id).unwrap();
+    assert_eq!(status.pending, query_reveal_requests(&env, game_id));
+    assert_eq!(status.pending.len(), 3);
+    assert!(status.completed.is_empty());
+
+    // Player partials alone reveal nothing
+    let cards = [(0u32, 9u8), (1, 8), (2, 5)];
+    batch_reveal(&mut env, &game, game_id, true, &cards).unwrap();
+    let status = query_reveal_status(&env, game_id).unwrap();
+    assert!(status.pending.iter().all(|p| p.player_submitted));
+    assert!(status.completed.is_empty());
+
+    batch_reveal(&mut env, &game, game_id, false, &cards[..2]).unwrap();
+    let status = query_reveal_status(&env, game_id).unwrap();
+    assert_eq!(
+        status.pending,
+        vec![PendingReveal {
+            card_index: 2,
+            player_submitted: true,
+            dealer_submitted: false,
+        }]
+    );
+    assert_eq!(status.completed, completed(&cards[..2]));
+
+    batch_reveal(&mut env, &game, game_id, false, &cards[2..]).unwrap();
+    let status = query_reveal_status(&env, game_id).unwrap();
+    assert!(status.pending.is_empty());
+    assert_eq!(status.completed, completed(&cards));
+
+    // The next round adds to what is already complete
+    env.app
+        .execute_contract(
+            env.player.clone(),
+            env.contract.clone(),
+            &ExecuteMsg::Stand { game_id },
+            &[],
+        )
+        .unwrap();
+    let status = query_reveal_status(&env, game_id).unwrap();
+    assert_eq!(status.pending.len(), 1);
+    assert_eq!(status.pending[0].card_index, 3);
+    assert_eq!(status.completed, completed(&cards));
+
+    assert!(query_reveal_status(&env, game_id + 1).is_err());
+}
@@ -276,10 +276,17 @@ async fn test_two_party_with_real_zk_proofs() {
                 max_splits: 3,
                 can_split_aces: true,
                 can_hit_split_aces: false,
+                double_after_split: None,
+                resplit_aces: None,
+                debug_mode: None,
                 surrender_allowed: true,
                 shuffle_vk_id: "test_shuffle".to_string(),
                 reveal_vk_id: "test_reveal".to_string(),
                 timeout_seconds: None,
+                retain_settled_seconds: None,
+                whitelist: None,
+                protocol_fee_bps: None,
+                protocol_fee_recipient: None,
             },
             &[],
             "juodzekas",
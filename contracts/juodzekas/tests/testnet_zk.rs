@@ -703,10 +703,17 @@ fn deploy_contract() {
         max_splits: 3,
         can_split_aces: true,
         can_hit_split_aces: false,
+        double_after_split: None,
+        resplit_aces: None,
+        debug_mode: None,
         surrender_allowed: true,
         shuffle_vk_id: "shuffle_encrypt".to_string(),
         reveal_vk_id: "decrypt".to_string(),
         timeout_seconds: Some(3600),
+        retain_settled_seconds: None,
+        whitelist: None,
+        protocol_fee_bps: None,
+        protocol_fee_recipient: None,
     };
     let msg_bytes = serde_json::to_vec(&msg).unwrap();
 
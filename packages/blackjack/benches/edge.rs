@@ -0,0 +1,22 @@
+//! Six-deck house edge. Compare the two builds to see what the rayon split
+//! of `aggregate_ev` buys on this machine:
+//!
+//! cargo bench -p blackjack --features edge --bench edge
+//! cargo bench -p blackjack --features parallel --bench edge
+
+use blackjack::{compute_edge, GameRules};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+fn bench_six_decks(c: &mut Criterion) {
+    // Vegas defaults: six decks, S17, DAS, late surrender
+    let rules = GameRules::default();
+    let mut group = c.benchmark_group("edge");
+    group.sample_size(10);
+    group.bench_function("compute_edge(6 decks)", |b| {
+        b.iter(|| compute_edge(black_box(&rules)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_six_decks);
+criterion_main!(benches);
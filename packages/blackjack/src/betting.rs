@@ -0,0 +1,83 @@
+#[cfg(feature = "edge")]
+use crate::EdgeResult;
+
+/// Kelly criterion bet sizing for a game with a known edge and variance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KellyCriterion {
+    /// Expected return per unit bet for the bettor (negative = disadvantage)
+    pub edge_fraction: f64,
+    /// Variance of the result per hand, in squared bet units
+    pub variance_per_hand: f64,
+}
+
+impl KellyCriterion {
+    /// Sizing from the player's point of view for a calculated rule set.
+    #[cfg(feature = "edge")]
+    pub fn from_edge_result(result: &EdgeResult) -> Self {
+        Self {
+            edge_fraction: result.expected_return,
+            variance_per_hand: result.variance_per_hand,
+        }
+    }
+
+    /// Fraction of bankroll to bet per hand (`edge / variance`).
+    /// Zero when there is no positive edge, i.e. bet the table minimum.
+    pub fn optimal_fraction(&self) -> f64 {
+        if self.edge_fraction <= 0.0 || self.variance_per_hand <= 0.0 {
+            return 0.0;
+        }
+        self.edge_fraction / self.variance_per_hand
+    }
+
+    /// A fraction of full Kelly, e.g. 0.5 for half-Kelly.
+    pub fn fractional_kelly(&self, f: f64) -> f64 {
+        self.optimal_fraction() * f
+    }
+
+    /// Bet for `bankroll` at `fraction` of full Kelly, rounded to the nearest `unit`.
+    pub fn bet_for_bankroll(&self, bankroll: u128, fraction: f64, unit: u128) -> u128 {
+        let bet = bankroll as f64 * self.fractional_kelly(fraction);
+        if unit == 0 {
+            return bet.round() as u128;
+        }
+        (bet / unit as f64).round() as u128 * unit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kelly_optimal_fraction() {
+        let kelly = KellyCriterion {
+            edge_fraction: 0.01,
+            variance_per_hand: 1.3,
+        };
+        assert!((kelly.optimal_fraction() - 0.01 / 1.3).abs() < 1e-12);
+        assert!((kelly.fractional_kelly(0.5) - 0.005 / 1.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kelly_negative_edge_bets_nothing() {
+        let kelly = KellyCriterion {
+            edge_fraction: -0.005,
+            variance_per_hand: 1.3,
+        };
+        assert_eq!(kelly.optimal_fraction(), 0.0);
+        assert_eq!(kelly.bet_for_bankroll(1_000_000, 1.0, 100), 0);
+    }
+
+    #[test]
+    fn test_kelly_bet_rounds_to_unit() {
+        let kelly = KellyCriterion {
+            edge_fraction: 0.01,
+            variance_per_hand: 1.0,
+        };
+        // 1% of 123_456 = 1234.56
+        assert_eq!(kelly.bet_for_bankroll(123_456, 1.0, 100), 1200);
+        assert_eq!(kelly.bet_for_bankroll(123_456, 1.0, 25), 1225);
+        assert_eq!(kelly.bet_for_bankroll(123_456, 1.0, 0), 1235);
+        assert_eq!(kelly.bet_for_bankroll(123_456, 0.5, 1), 617);
+    }
+}
@@ -1,4 +1,4 @@
-use blackjack::{DoubleRestriction, EdgeCalculator, GameRules, PayoutRatio};
+use blackjack::{compute_edge, DoubleRestriction, GameRules, PayoutRatio};
 use clap::Parser;
 
 #[derive(Parser)]
@@ -76,6 +76,8 @@ fn main() {
             resplit_aces: false,
             dealer_peeks: args.dealer_peeks,
             blackjack_payout: payout,
+            insurance_available: true,
+            insurance_payout: PayoutRatio::TWO_TO_ONE,
         }
     };
 
@@ -107,10 +109,11 @@ fn main() {
     );
     eprintln!("Calculating...");
 
-    let result = EdgeCalculator::new(rules).calculate();
+    let result = compute_edge(&rules);
 
     println!("House edge:     {:+.4}%", result.house_edge * 100.0);
     println!("Player return:  {:+.4}%", result.expected_return * 100.0);
+    println!("Std dev/hand:   {:.4}", result.std_dev_per_hand);
 
     if result.house_edge > 0.0 {
         println!("Result: House advantage");
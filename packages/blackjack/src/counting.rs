@@ -0,0 +1,319 @@
+//! Monte Carlo simulation of a Hi-Lo card counter.
+
+use crate::simulation::{play_round, Deal, SplitMix64};
+use crate::strategy::{Action, HandOptions, StrategyTable};
+use crate::{calculate_hand_value, Card, GameRules, HandValue};
+
+/// Fraction of the shoe dealt before the cut card forces a reshuffle.
+pub const PENETRATION: f64 = 0.75;
+
+/// Seed used by [`HiLoSimulation::run`], so results are reproducible.
+pub const DEFAULT_SEED: u64 = 0x6a75_6f64_7a65_6b61;
+
+/// Betting spread keyed on the true count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BetRamp {
+    /// Largest bet as a multiple of `min_bet` (e.g. 16 for a 1-16 spread).
+    pub spread: u8,
+    pub min_bet: u32,
+}
+
+impl BetRamp {
+    /// One unit at a true count of +1 or lower, then one more unit per
+    /// true count up to `spread` units.
+    pub fn bet_for(&self, true_count: f64) -> u32 {
+        let units = true_count.floor().clamp(1.0, self.spread.max(1) as f64) as u32;
+        units * self.min_bet
+    }
+}
+
+/// Outcome of a simulation run. Amounts are in the same chips as `BetRamp::min_bet`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationResult {
+    /// Mean net result per round.
+    pub ev_per_hand: f64,
+    /// Standard deviation of the net result per round.
+    pub std_dev: f64,
+    /// Rounds played (a split round counts once).
+    pub hands_simulated: u64,
+    /// 95% confidence interval for `ev_per_hand`.
+    pub ci_95: (f64, f64),
+}
+
+/// A single player counting Hi-Lo through `num_shoes` shoes, playing basic
+/// strategy with count-based deviations and sizing bets by `bet_ramp`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HiLoSimulation {
+    pub rules: GameRules,
+    pub num_shoes: u64,
+    pub bet_ramp: BetRamp,
+}
+
+impl HiLoSimulation {
+    pub fn run(&self) -> SimulationResult {
+        self.run_with_seed(DEFAULT_SEED)
+    }
+
+    pub fn run_with_seed(&self, seed: u64) -> SimulationResult {
+        let mut rng = SplitMix64(seed);
+        let table = StrategyTable::new(&self.rules);
+        let mut shoe = Shoe::new(self.rules.num_decks);
+        let mut n = 0u64;
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+
+        for _ in 0..self.num_shoes {
+            shoe.shuffle(&mut rng);
+            while !shoe.past_cut_card() {
+                let bet = self.bet_ramp.bet_for(shoe.true_count());
+                let Some(round) = play_round(&self.rules, &mut shoe, |cards, up, options, shoe| {
+                    decide(&table, cards, up, shoe.true_count(), options)
+                }) else {
+                    break;
+                };
+                let net = round.net * bet as f64;
+                n += 1;
+                sum += net;
+                sum_sq += net * net;
+            }
+        }
+
+        if n == 0 {
+            return SimulationResult {
+                ev_per_hand: 0.0,
+                std_dev: 0.0,
+                hands_simulated: 0,
+                ci_95: (0.0, 0.0),
+            };
+        }
+        let mean = sum / n as f64;
+        let variance = (sum_sq / n as f64 - mean * mean).max(0.0);
+        let std_dev = variance.sqrt();
+        let half_width = 1.96 * std_dev / (n as f64).sqrt();
+        SimulationResult {
+            ev_per_hand: mean,
+            std_dev,
+            hands_simulated: n,
+            ci_95: (mean - half_width, mean + half_width),
+        }
+    }
+}
+
+/// Hi-Lo tag: +1 for 2-6, 0 for 7-9, -1 for tens and aces.
+fn hi_lo_tag(card: Card) -> i32 {
+    match card.value() {
+        2..=6 => 1,
+        7..=9 => 0,
+        _ => -1,
+    }
+}
+
+struct Shoe {
+    cards: Vec<Card>,
+    next: usize,
+    cut: usize,
+    running_count: i32,
+}
+
+impl Shoe {
+    fn new(num_decks: u8) -> Self {
+        let cards: Vec<Card> = (0..num_decks.max(1) as usize * 52)
+            .map(|i| Card::from_index(i % 52))
+            .collect();
+        let cut = (cards.len() as f64 * PENETRATION) as usize;
+        Shoe {
+            cards,
+            next: 0,
+            cut,
+            running_count: 0,
+        }
+    }
+
+    fn shuffle(&mut self, rng: &mut SplitMix64) {
+        for i in (1..self.cards.len()).rev() {
+            let j = rng.below(i + 1);
+            self.cards.swap(i, j);
+        }
+        self.next = 0;
+        self.running_count = 0;
+    }
+
+    fn past_cut_card(&self) -> bool {
+        self.next >= self.cut
+    }
+
+    fn true_count(&self) -> f64 {
+        let decks_remaining = (self.cards.len() - self.next) as f64 / 52.0;
+        if decks_remaining <= 0.0 {
+            return 0.0;
+        }
+        self.running_count as f64 / decks_remaining
+    }
+}
+
+impl Deal for Shoe {
+    /// Deal a card face up, updating the running count.
+    fn deal(&mut self) -> Option<Card> {
+        let card = self.deal_hidden()?;
+        self.running_count += hi_lo_tag(card);
+        Some(card)
+    }
+
+    fn deal_hidden(&mut self) -> Option<Card> {
+        let card = *self.cards.get(self.next)?;
+        self.next += 1;
+        Some(card)
+    }
+
+    fn reveal(&mut self, card: Card) {
+        self.running_count += hi_lo_tag(card);
+    }
+}
+
+/// Basic strategy from `table`, adjusted by the Hi-Lo deviations below.
+fn decide(
+    table: &StrategyTable,
+    cards: &[Card],
+    up: u8,
+    true_count: f64,
+    options: HandOptions,
+) -> Action {
+    let basic = table.action(cards, up, options);
+    if basic == Action::Surrender {
+        return basic;
+    }
+
+    let HandValue {
+        total,
+        is_soft: soft,
+    } = calculate_hand_value(cards);
+    match deviation(total, soft, options.can_split, up, true_count) {
+        Some(Action::Surrender) if options.can_surrender && cards.len() == 2 && !soft => {
+            Action::Surrender
+        }
+        Some(Action::Double) if table.can_double(cards, options.from_split) => Action::Double,
+        Some(action @ (Action::Split | Action::Hit | Action::Stand)) => action,
+        _ => basic,
+    }
+}
+
+/// Hi-Lo index plays (the Illustrious 18 less insurance, plus the Fab 4
+/// surrenders). Returns the deviation that applies at this count, if any;
+/// `decide` checks surrender before the other index plays.
+fn deviation(total: u8, soft: bool, pair: bool, up: u8, tc: f64) -> Option<Action> {
+    use Action::*;
+    // Other pairs follow the split table regardless of count.
+    if soft || (pair && total != 20) {
+        return None;
+    }
+    let (action, applies) = match (total, up) {
+        (20, 5) if pair => (Split, tc >= 5.0),
+        (20, 6) if pair => (Split, tc >= 4.0),
+        (16, 10) => (Stand, tc >= 0.0),
+        (16, 9) => (Stand, tc >= 5.0),
+        (15, 10) => (Stand, tc >= 4.0),
+        (15, 9) => (Surrender, tc >= 2.0),
+        (15, 11) => (Surrender, tc >= 1.0),
+        (14, 10) => (Surrender, tc >= 3.0),
+        (13, 2) => (Hit, tc <= -1.0),
+        (13, 3) => (Hit, tc <= -2.0),
+        (12, 2) => (Stand, tc >= 3.0),
+        (12, 3) => (Stand, tc >= 2.0),
+        (12, 4) => (Hit, tc < 0.0),
+        (12, 5) => (Hit, tc <= -2.0),
+        (12, 6) => (Hit, tc <= -1.0),
+        (11, 11) => (Double, tc >= 1.0),
+        (10, 10) | (10, 11) => (Double, tc >= 4.0),
+        (9, 2) => (Double, tc >= 1.0),
+        (9, 7) => (Double, tc >= 3.0),
+        _ => return None,
+    };
+    applies.then_some(action)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bet_ramp() {
+        let ramp = BetRamp {
+            spread: 8,
+            min_bet: 10,
+        };
+        assert_eq!(ramp.bet_for(-3.0), 10);
+        assert_eq!(ramp.bet_for(1.9), 10);
+        assert_eq!(ramp.bet_for(3.2), 30);
+        assert_eq!(ramp.bet_for(12.0), 80);
+    }
+
+    #[test]
+    fn test_deviation_16_vs_10() {
+        let table = StrategyTable::new(&GameRules::default());
+        let hand = [Card::TenSpades, Card::SixHearts];
+        let surrender = HandOptions {
+            can_surrender: true,
+            ..HandOptions::default()
+        };
+        // Hard 16 vs 10 surrenders by basic strategy...
+        assert_eq!(
+            decide(&table, &hand, 10, -1.0, surrender),
+            Action::Surrender
+        );
+        // ...and stands from a true count of 0 when surrender isn't possible.
+        let no_surrender = HandOptions::default();
+        assert_eq!(decide(&table, &hand, 10, -1.0, no_surrender), Action::Hit);
+        assert_eq!(decide(&table, &hand, 10, 0.5, no_surrender), Action::Stand);
+    }
+
+    #[test]
+    fn test_flat_bet_matches_analytic_edge() {
+        // 6 decks, S17, DAS, late surrender, 3:2: edge-calc gives a player
+        // return of -0.328%. Deviations with a flat bet gain only a few
+        // hundredths of a percent, well inside the interval.
+        let sim = HiLoSimulation {
+            rules: GameRules::default(),
+            num_shoes: 23_000,
+            bet_ramp: BetRamp {
+                spread: 1,
+                min_bet: 1,
+            },
+        };
+        let result = sim.run();
+        assert!(result.hands_simulated >= 1_000_000);
+        let analytic = -0.003278;
+        assert!(
+            result.ci_95.0 <= analytic && analytic <= result.ci_95.1,
+            "EV {:.5} CI {:?}",
+            result.ev_per_hand,
+            result.ci_95
+        );
+        assert!((result.std_dev - 1.15).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_spread_gains_edge() {
+        let rules = GameRules::default();
+        let flat = HiLoSimulation {
+            rules,
+            num_shoes: 5_000,
+            bet_ramp: BetRamp {
+                spread: 1,
+                min_bet: 1,
+            },
+        }
+        .run();
+        let spread = HiLoSimulation {
+            rules,
+            num_shoes: 5_000,
+            bet_ramp: BetRamp {
+                spread: 16,
+                min_bet: 1,
+            },
+        }
+        .run();
+        assert_eq!(flat.hands_simulated, spread.hands_simulated);
+        assert!(spread.ev_per_hand > 0.0, "{spread:?}");
+        assert!(spread.ev_per_hand > flat.ev_per_hand);
+    }
+}
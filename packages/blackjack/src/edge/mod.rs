@@ -1,16 +1,81 @@
 use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::SubAssign;
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
 
-use crate::{DoubleRestriction, GameRules};
+#[cfg(feature = "parallel")]
+use dashmap::DashMap;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::{DoubleRestriction, GameRules, PayoutRatio};
 
 /// Card counts by blackjack value index.
 /// Index 0=Ace, 1=Two, 2=Three, ..., 8=Nine, 9=Ten/J/Q/K.
 /// Single deck: [4,4,4,4,4,4,4,4,4,16].
-type Shoe = [u8; 10];
+type Shoe<C> = [C; 10];
+
+/// Integer type for the per-value card counts in a shoe. `u8` keeps the cache
+/// keys small but only fits 15 decks of ten-value cards; `u16` fits any shoe.
+pub trait ShoeCount:
+    Copy + Eq + Hash + From<u8> + Into<u16> + TryFrom<u16> + SubAssign + Send + Sync
+{
+}
+
+impl ShoeCount for u8 {}
+impl ShoeCount for u16 {}
+
+/// A house edge calculation that can be run through `compute_edge` or boxed.
+pub trait EdgeApi {
+    fn calculate(&mut self) -> EdgeResult;
+}
+
+/// The original calculator, with a compact `[u8; 10]` shoe. It holds up to 15 decks
+/// (240 ten-value cards), so every `num_decks` from 1 to 8 fits.
+pub type EdgeCalculator = ShoeEdgeCalculator<u8>;
+
+/// The same calculator with a `[u16; 10]` shoe, for multi-deck games.
+pub type MultiDecksEdgeCalculator = ShoeEdgeCalculator<u16>;
+
+/// House edge for `rules`, using the calculator suited to its deck count.
+pub fn compute_edge(rules: &GameRules) -> EdgeResult {
+    let mut calculator: Box<dyn EdgeApi> = if rules.num_decks <= 1 {
+        Box::new(EdgeCalculator::new(*rules))
+    } else {
+        Box::new(MultiDecksEdgeCalculator::new(*rules))
+    };
+    calculator.calculate()
+}
+
+/// Whether insurance is a positive-EV bet against an ace. `shoe` is the unseen
+/// composition that still counts the dealer's ace, so the hole card is drawn from
+/// `total - 1` cards. Insurance pays when tens make up more than
+/// `1 / (payout + 1)` of those: a third at 2:1.
+pub fn should_take_insurance(shoe: &[u16; 10], insurance_payout: &PayoutRatio) -> bool {
+    let total: u64 = shoe.iter().map(|&c| u64::from(c)).sum();
+    if total <= 1 {
+        return false;
+    }
+    let tens = u64::from(shoe[9]);
+    let num = u64::from(insurance_payout.numerator);
+    let den = u64::from(insurance_payout.denominator);
+    // tens / (total - 1) > den / (num + den), kept in integers so the break-even
+    // point is exact.
+    tens * (num + den) > (total - 1) * den
+}
 
 /// Dealer outcome probability distribution.
 /// [P(bust), P(17), P(18), P(19), P(20), P(21)]
 type DealerProbs = [f64; 6];
 
+/// Memoized `dealer_probs` results. With the `parallel` feature the cache is
+/// shared by the per-first-card workers in `aggregate_ev`.
+#[cfg(feature = "parallel")]
+type DealerCache<C> = Arc<DashMap<(Shoe<C>, u8, bool), DealerProbs>>;
+#[cfg(not(feature = "parallel"))]
+type DealerCache<C> = HashMap<(Shoe<C>, u8, bool), DealerProbs>;
+
 /// Result of house edge calculation.
 #[derive(Debug, Clone, Copy)]
 pub struct EdgeResult {
@@ -19,6 +84,64 @@ pub struct EdgeResult {
     pub house_edge: f64,
     /// Expected return per unit bet for the player.
     pub expected_return: f64,
+    /// Variance of the player's result per hand, in squared units of the initial bet.
+    pub variance_per_hand: f64,
+    /// Standard deviation per hand (`variance_per_hand.sqrt()`).
+    pub std_dev_per_hand: f64,
+}
+
+/// First and second moments (E[X], E[X²]) of a hand's result in units of the initial bet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Moments {
+    ev: f64,
+    m2: f64,
+}
+
+impl Moments {
+    const LOSE: Moments = Moments { ev: -1.0, m2: 1.0 };
+    /// An action the rules don't allow here; never chosen by `best`.
+    const UNAVAILABLE: Moments = Moments {
+        ev: f64::NEG_INFINITY,
+        m2: 0.0,
+    };
+
+    /// Result scaled by a constant factor (e.g. 2x for a doubled bet).
+    fn scale(self, k: f64) -> Moments {
+        Moments {
+            ev: k * self.ev,
+            m2: k * k * self.m2,
+        }
+    }
+
+    /// `p * self`, for accumulating a probability-weighted mixture.
+    fn weighted(self, p: f64) -> Moments {
+        Moments {
+            ev: p * self.ev,
+            m2: p * self.m2,
+        }
+    }
+
+    fn add(self, other: Moments) -> Moments {
+        Moments {
+            ev: self.ev + other.ev,
+            m2: self.m2 + other.m2,
+        }
+    }
+
+    /// The option with the higher EV (the player's choice).
+    fn best(self, other: Moments) -> Moments {
+        if other.ev > self.ev {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl Default for Moments {
+    fn default() -> Self {
+        Moments { ev: 0.0, m2: 0.0 }
+    }
 }
 
 /// Combinatorial blackjack house edge calculator.
@@ -26,24 +149,45 @@ pub struct EdgeResult {
 /// Computes exact house edge via enumeration of all possible deals and
 /// composition-dependent optimal player decisions.
 /// Uses split approximation (play one hand, multiply by 2).
-pub struct EdgeCalculator {
+/// Use it through `EdgeCalculator` or `MultiDecksEdgeCalculator`.
+pub struct ShoeEdgeCalculator<C: ShoeCount> {
     rules: GameRules,
-    shoe: Shoe,
+    shoe: Shoe<C>,
     total_cards: u16,
-    dealer_cache: HashMap<(Shoe, u8, bool), DealerProbs>,
-    dealer_upcard_cache: HashMap<(Shoe, u8), DealerProbs>,
-    player_cache: HashMap<(Shoe, u8, bool, u8), f64>,
+    dealer_cache: DealerCache<C>,
+    dealer_upcard_cache: HashMap<(Shoe<C>, u8), DealerProbs>,
+    player_cache: HashMap<(Shoe<C>, u8, bool, u8), Moments>,
 }
 
-impl EdgeCalculator {
+impl<C: ShoeCount> EdgeApi for ShoeEdgeCalculator<C> {
+    fn calculate(&mut self) -> EdgeResult {
+        ShoeEdgeCalculator::calculate(self)
+    }
+}
+
+impl<C: ShoeCount> ShoeEdgeCalculator<C> {
     pub fn new(rules: GameRules) -> Self {
         let shoe = Self::initial_shoe(rules.num_decks);
-        let total_cards = shoe.iter().map(|&c| c as u16).sum();
+        let total_cards = Self::shoe_total(&shoe);
         Self {
             rules,
             shoe,
             total_cards,
-            dealer_cache: HashMap::new(),
+            dealer_cache: DealerCache::default(),
+            dealer_upcard_cache: HashMap::new(),
+            player_cache: HashMap::new(),
+        }
+    }
+
+    /// A calculator for one branch of `aggregate_ev`, sharing the dealer cache
+    /// but keeping its own upcard and player caches.
+    #[cfg(feature = "parallel")]
+    fn worker(&self) -> Self {
+        Self {
+            rules: self.rules,
+            shoe: self.shoe,
+            total_cards: self.total_cards,
+            dealer_cache: Arc::clone(&self.dealer_cache),
             dealer_upcard_cache: HashMap::new(),
             player_cache: HashMap::new(),
         }
@@ -53,39 +197,42 @@ impl EdgeCalculator {
         self.dealer_cache.clear();
         self.dealer_upcard_cache.clear();
         self.player_cache.clear();
-        let expected_return = self.aggregate_ev();
+        let moments = self.aggregate_ev();
+        let expected_return = moments.ev;
+        let variance_per_hand = (moments.m2 - expected_return * expected_return).max(0.0);
         EdgeResult {
             house_edge: -expected_return,
             expected_return,
+            variance_per_hand,
+            std_dev_per_hand: variance_per_hand.sqrt(),
         }
     }
 
     // ── Shoe helpers ──
 
-    fn initial_shoe(num_decks: u8) -> Shoe {
-        let n = num_decks;
-        [
-            4 * n,
-            4 * n,
-            4 * n,
-            4 * n,
-            4 * n,
-            4 * n,
-            4 * n,
-            4 * n,
-            4 * n,
-            16 * n,
-        ]
+    fn initial_shoe(num_decks: u8) -> Shoe<C> {
+        let count = |per_deck: u16| {
+            C::try_from(per_deck * num_decks as u16)
+                .unwrap_or_else(|_| panic!("{num_decks} decks overflow the shoe count type"))
+        };
+        let mut shoe = [count(4); 10];
+        shoe[9] = count(16);
+        shoe
+    }
+
+    fn shoe_total(shoe: &Shoe<C>) -> u16 {
+        shoe.iter().map(|&c| c.into()).sum()
     }
 
-    fn shoe_total(shoe: &Shoe) -> u16 {
-        shoe.iter().map(|&c| c as u16).sum()
+    /// Cards left at value index `idx`.
+    fn count(shoe: &Shoe<C>, idx: usize) -> u16 {
+        shoe[idx].into()
     }
 
-    fn remove_card(shoe: &Shoe, idx: usize) -> Shoe {
+    fn remove_card(shoe: &Shoe<C>, idx: usize) -> Shoe<C> {
         let mut s = *shoe;
-        debug_assert!(s[idx] > 0);
-        s[idx] -= 1;
+        debug_assert!(Self::count(&s, idx) > 0);
+        s[idx] -= C::from(1);
         s
     }
 
@@ -120,9 +267,9 @@ impl EdgeCalculator {
     // ── Dealer outcome probabilities ──
 
     /// Recursive dealer probs from a given hand state (used for subsequent draws).
-    fn dealer_probs(&mut self, shoe: Shoe, value: u8, is_soft: bool) -> DealerProbs {
-        if let Some(&cached) = self.dealer_cache.get(&(shoe, value, is_soft)) {
-            return cached;
+    fn dealer_probs(&mut self, shoe: Shoe<C>, value: u8, is_soft: bool) -> DealerProbs {
+        if let Some(cached) = self.dealer_cache.get(&(shoe, value, is_soft)) {
+            return *cached;
         }
 
         let must_stand = if value >= 18 || value > 21 {
@@ -154,10 +301,10 @@ impl EdgeCalculator {
             }
             let mut r = [0.0; 6];
             for i in 0..10 {
-                if shoe[i] == 0 {
+                if Self::count(&shoe, i) == 0 {
                     continue;
                 }
-                let p = shoe[i] as f64 / total as f64;
+                let p = Self::count(&shoe, i) as f64 / total as f64;
                 let cv = Self::card_value(i);
                 let (nv, ns) = Self::add_to_hand(value, is_soft, cv);
                 let new_shoe = Self::remove_card(&shoe, i);
@@ -180,7 +327,7 @@ impl EdgeCalculator {
     /// Dealer probs starting from just the upcard.
     /// For peek games with BJ-possible upcard, conditions on the hole card
     /// NOT completing a blackjack (since peek already confirmed no BJ).
-    fn dealer_probs_from_upcard(&mut self, shoe: Shoe, upcard_idx: u8) -> DealerProbs {
+    fn dealer_probs_from_upcard(&mut self, shoe: Shoe<C>, upcard_idx: u8) -> DealerProbs {
         let key = (shoe, upcard_idx);
         if let Some(&cached) = self.dealer_upcard_cache.get(&key) {
             return cached;
@@ -197,7 +344,7 @@ impl EdgeCalculator {
             // 10 up → hole card can't be Ace (idx 0)
             let forbidden = if cv == 1 { 9 } else { 0 };
             let total = Self::shoe_total(&shoe);
-            let forbidden_count = shoe[forbidden] as u16;
+            let forbidden_count = Self::count(&shoe, forbidden);
             let adj_total = total - forbidden_count;
 
             if adj_total == 0 {
@@ -212,10 +359,10 @@ impl EdgeCalculator {
 
             let mut r = [0.0; 6];
             for i in 0..10 {
-                if shoe[i] == 0 || i == forbidden {
+                if Self::count(&shoe, i) == 0 || i == forbidden {
                     continue;
                 }
-                let p = shoe[i] as f64 / adj_total as f64;
+                let p = Self::count(&shoe, i) as f64 / adj_total as f64;
                 let hcv = Self::card_value(i);
                 let (nv, ns) = Self::add_to_hand(d_val, d_soft, hcv);
                 let new_shoe = Self::remove_card(&shoe, i);
@@ -254,43 +401,77 @@ impl EdgeCalculator {
         ev
     }
 
+    /// Standing wins or loses one unit unless the dealer pushes.
+    fn stand_moments(player_value: u8, dp: &DealerProbs) -> Moments {
+        let p_push = if (17..=21).contains(&player_value) {
+            dp[(player_value - 16) as usize]
+        } else {
+            0.0
+        };
+        Moments {
+            ev: Self::stand_ev(player_value, dp),
+            m2: 1.0 - p_push,
+        }
+    }
+
+    // ── Insurance EV ──
+
+    /// EV per unit of insurance bet, given the shoe left after the player's two
+    /// cards and the dealer's upcard. Insurance wins `insurance_payout` when the
+    /// hole card is a ten and loses the stake otherwise. It is only offered
+    /// against an ace, so any other upcard returns `f64::NEG_INFINITY`.
+    pub fn insurance_ev(&self, shoe_after_deal: Shoe<C>, dealer_up_is_ace: bool) -> f64 {
+        if !dealer_up_is_ace {
+            return f64::NEG_INFINITY;
+        }
+        let total = Self::shoe_total(&shoe_after_deal);
+        if total == 0 {
+            return -1.0;
+        }
+        let payout = self.rules.insurance_payout.numerator as f64
+            / self.rules.insurance_payout.denominator as f64;
+        let p_bj = Self::count(&shoe_after_deal, 9) as f64 / total as f64;
+        p_bj * payout - (1.0 - p_bj)
+    }
+
     // ── Hit-or-stand EV ──
 
     fn hit_or_stand_ev(
         &mut self,
-        shoe: Shoe,
+        shoe: Shoe<C>,
         player_value: u8,
         is_soft: bool,
         dealer_up: u8,
-    ) -> f64 {
+    ) -> Moments {
         let key = (shoe, player_value, is_soft, dealer_up);
         if let Some(&cached) = self.player_cache.get(&key) {
             return cached;
         }
 
         let dp = self.dealer_probs_from_upcard(shoe, dealer_up);
-        let s_ev = Self::stand_ev(player_value, &dp);
+        let stand = Self::stand_moments(player_value, &dp);
 
         let total = Self::shoe_total(&shoe);
-        let mut h_ev = 0.0;
+        let mut hit = Moments::default();
         if total > 0 {
             for i in 0..10 {
-                if shoe[i] == 0 {
+                if Self::count(&shoe, i) == 0 {
                     continue;
                 }
-                let p = shoe[i] as f64 / total as f64;
+                let p = Self::count(&shoe, i) as f64 / total as f64;
                 let cv = Self::card_value(i);
                 let (nv, ns) = Self::add_to_hand(player_value, is_soft, cv);
                 let new_shoe = Self::remove_card(&shoe, i);
-                if nv > 21 {
-                    h_ev += -p;
+                let next = if nv > 21 {
+                    Moments::LOSE
                 } else {
-                    h_ev += p * self.hit_or_stand_ev(new_shoe, nv, ns, dealer_up);
-                }
+                    self.hit_or_stand_ev(new_shoe, nv, ns, dealer_up)
+                };
+                hit = hit.add(next.weighted(p));
             }
         }
 
-        let best = s_ev.max(h_ev);
+        let best = stand.best(hit);
         self.player_cache.insert(key, best);
         best
     }
@@ -311,7 +492,7 @@ impl EdgeCalculator {
     #[allow(clippy::too_many_arguments)]
     fn initial_hand_ev(
         &mut self,
-        shoe: Shoe,
+        shoe: Shoe<C>,
         p1_idx: usize,
         p2_idx: usize,
         p_value: u8,
@@ -319,67 +500,69 @@ impl EdgeCalculator {
         d_idx: usize,
         split_depth: u8,
         can_surrender: bool,
-    ) -> f64 {
+    ) -> Moments {
         let dp = self.dealer_probs_from_upcard(shoe, d_idx as u8);
 
         // Stand
-        let s_ev = Self::stand_ev(p_value, &dp);
+        let stand = Self::stand_moments(p_value, &dp);
 
         // Hit
         let total = Self::shoe_total(&shoe);
-        let mut h_ev = 0.0;
+        let mut hit = Moments::default();
         if total > 0 {
             for i in 0..10 {
-                if shoe[i] == 0 {
+                if Self::count(&shoe, i) == 0 {
                     continue;
                 }
-                let p = shoe[i] as f64 / total as f64;
+                let p = Self::count(&shoe, i) as f64 / total as f64;
                 let cv = Self::card_value(i);
                 let (nv, ns) = Self::add_to_hand(p_value, p_soft, cv);
                 let new_shoe = Self::remove_card(&shoe, i);
-                if nv > 21 {
-                    h_ev += -p;
+                let next = if nv > 21 {
+                    Moments::LOSE
                 } else {
-                    h_ev += p * self.hit_or_stand_ev(new_shoe, nv, ns, d_idx as u8);
-                }
+                    self.hit_or_stand_ev(new_shoe, nv, ns, d_idx as u8)
+                };
+                hit = hit.add(next.weighted(p));
             }
         }
 
         // Double
-        let dbl_ev = if self.can_double(p_value, p_soft, split_depth > 0) && total > 0 {
-            let mut ev = 0.0;
+        let double = if self.can_double(p_value, p_soft, split_depth > 0) && total > 0 {
+            let mut one_card = Moments::default();
             for i in 0..10 {
-                if shoe[i] == 0 {
+                if Self::count(&shoe, i) == 0 {
                     continue;
                 }
-                let p = shoe[i] as f64 / total as f64;
+                let p = Self::count(&shoe, i) as f64 / total as f64;
                 let cv = Self::card_value(i);
                 let (nv, _ns) = Self::add_to_hand(p_value, p_soft, cv);
                 let new_shoe = Self::remove_card(&shoe, i);
-                if nv > 21 {
-                    ev += -p;
+                let next = if nv > 21 {
+                    Moments::LOSE
                 } else {
                     let dp2 = self.dealer_probs_from_upcard(new_shoe, d_idx as u8);
-                    ev += p * Self::stand_ev(nv, &dp2);
-                }
+                    Self::stand_moments(nv, &dp2)
+                };
+                one_card = one_card.add(next.weighted(p));
             }
-            2.0 * ev
+            one_card.scale(2.0)
         } else {
-            f64::NEG_INFINITY
+            Moments::UNAVAILABLE
         };
 
         // Surrender
-        let sur_ev = if can_surrender && split_depth == 0 {
-            -0.5
+        let surrender = if can_surrender && split_depth == 0 {
+            Moments { ev: -0.5, m2: 0.25 }
         } else {
-            f64::NEG_INFINITY
+            Moments::UNAVAILABLE
         };
 
-        let best_no_split = s_ev.max(h_ev).max(dbl_ev).max(sur_ev);
+        let best_no_split = stand.best(hit).best(double).best(surrender);
 
         // Split
         if p1_idx == p2_idx && split_depth < self.rules.max_splits {
-            let sp_ev = self.split_ev(shoe, p1_idx, d_idx, split_depth);
+            let split = self.split_ev(shoe, p1_idx, d_idx, split_depth);
             if p1_idx == 9 {
                 // 10-value cards split by rank only.
                 let n = self.rules.num_decks as f64;
@@ -387,9 +570,12 @@ impl EdgeCalculator {
                 let total_10 = 16.0 * n;
                 let same_rank_frac =
                     4.0 * (per_rank * (per_rank - 1.0)) / (total_10 * (total_10 - 1.0));
-                same_rank_frac * best_no_split.max(sp_ev) + (1.0 - same_rank_frac) * best_no_split
+                best_no_split
+                    .best(split)
+                    .weighted(same_rank_frac)
+                    .add(best_no_split.weighted(1.0 - same_rank_frac))
             } else {
-                best_no_split.max(sp_ev)
+                best_no_split.best(split)
             }
         } else {
             best_no_split
@@ -398,38 +584,44 @@ impl EdgeCalculator {
 
     // ── Split EV (approximation) ──
 
-    fn split_ev(&mut self, shoe: Shoe, pair_idx: usize, d_idx: usize, split_depth: u8) -> f64 {
+    fn split_ev(
+        &mut self,
+        shoe: Shoe<C>,
+        pair_idx: usize,
+        d_idx: usize,
+        split_depth: u8,
+    ) -> Moments {
         let is_ace_split = pair_idx == 0;
         let card_val = Self::card_value(pair_idx);
         let (base_val, base_soft) = Self::add_to_hand(0, false, card_val);
 
         let total = Self::shoe_total(&shoe);
         if total == 0 {
-            return 0.0;
+            return Moments::default();
         }
 
-        let mut one_hand_ev = 0.0;
+        let mut one_hand = Moments::default();
 
         for i in 0..10 {
-            if shoe[i] == 0 {
+            if Self::count(&shoe, i) == 0 {
                 continue;
             }
-            let p = shoe[i] as f64 / total as f64;
+            let p = Self::count(&shoe, i) as f64 / total as f64;
             let cv = Self::card_value(i);
             let (hand_val, hand_soft) = Self::add_to_hand(base_val, base_soft, cv);
             let new_shoe = Self::remove_card(&shoe, i);
 
-            if is_ace_split {
+            let next = if is_ace_split {
                 if i == 0 && self.rules.resplit_aces && split_depth + 1 < self.rules.max_splits {
                     // Drew another ace — resplit
-                    one_hand_ev += p * self.split_ev(new_shoe, 0, d_idx, split_depth + 1);
+                    self.split_ev(new_shoe, 0, d_idx, split_depth + 1)
                 } else {
                     // Split aces: one card only, must stand. Not blackjack even if 21.
                     let dp = self.dealer_probs_from_upcard(new_shoe, d_idx as u8);
-                    one_hand_ev += p * Self::stand_ev(hand_val, &dp);
+                    Self::stand_moments(hand_val, &dp)
                 }
             } else {
-                one_hand_ev += p * self.initial_hand_ev(
+                self.initial_hand_ev(
                     new_shoe,
                     pair_idx,
                     i,
@@ -438,112 +630,144 @@ impl EdgeCalculator {
                     d_idx,
                     split_depth + 1,
                     false,
-                );
-            }
+                )
+            };
+            one_hand = one_hand.add(next.weighted(p));
         }
 
-        2.0 * one_hand_ev
+        // Two hands treated as independent: E[(X1+X2)²] = 2E[X²] + 2E[X]²
+        Moments {
+            ev: 2.0 * one_hand.ev,
+            m2: 2.0 * one_hand.m2 + 2.0 * one_hand.ev * one_hand.ev,
+        }
     }
 
     // ── Aggregate ──
 
-    fn aggregate_ev(&mut self) -> f64 {
+    /// EV over every deal. The deals for each player first card are
+    /// independent, so with the `parallel` feature they run on rayon workers.
+    fn aggregate_ev(&mut self) -> Moments {
+        #[cfg(feature = "parallel")]
+        {
+            let this = &*self;
+            (0..10)
+                .into_par_iter()
+                .map(|p1| this.worker().first_card_ev(p1))
+                .reduce(Moments::default, Moments::add)
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            (0..10)
+                .map(|p1| self.first_card_ev(p1))
+                .fold(Moments::default(), Moments::add)
+        }
+    }
+
+    /// Probability-weighted EV of all deals where the player's first card is `p1`.
+    fn first_card_ev(&mut self, p1: usize) -> Moments {
         let shoe = self.shoe;
         let total = self.total_cards;
-        let mut ev_sum = 0.0;
+        let mut sum = Moments::default();
 
         let bj_payout = self.rules.blackjack_payout.numerator as f64
             / self.rules.blackjack_payout.denominator as f64;
+        let blackjack = Moments {
+            ev: bj_payout,
+            m2: bj_payout * bj_payout,
+        };
+
+        if Self::count(&shoe, p1) == 0 {
+            return sum;
+        }
+        let shoe1 = Self::remove_card(&shoe, p1);
+        let total1 = total - 1;
 
-        for p1 in 0..10 {
-            if shoe[p1] == 0 {
+        for p2 in 0..10 {
+            if Self::count(&shoe1, p2) == 0 {
                 continue;
             }
-            let shoe1 = Self::remove_card(&shoe, p1);
-            let total1 = total - 1;
+            let shoe2 = Self::remove_card(&shoe1, p2);
+            let total2 = total1 - 1;
 
-            for p2 in 0..10 {
-                if shoe1[p2] == 0 {
+            let cv1 = Self::card_value(p1);
+            let cv2 = Self::card_value(p2);
+            let (v1, s1) = Self::add_to_hand(0, false, cv1);
+            let (p_val, p_soft) = Self::add_to_hand(v1, s1, cv2);
+            let player_bj = p_val == 21;
+
+            for d in 0..10 {
+                if Self::count(&shoe2, d) == 0 {
                     continue;
                 }
-                let shoe2 = Self::remove_card(&shoe1, p2);
-                let total2 = total1 - 1;
-
-                let cv1 = Self::card_value(p1);
-                let cv2 = Self::card_value(p2);
-                let (v1, s1) = Self::add_to_hand(0, false, cv1);
-                let (p_val, p_soft) = Self::add_to_hand(v1, s1, cv2);
-                let player_bj = p_val == 21;
-
-                for d in 0..10 {
-                    if shoe2[d] == 0 {
-                        continue;
-                    }
-                    let shoe3 = Self::remove_card(&shoe2, d);
-
-                    let prob = (shoe[p1] as f64 / total as f64)
-                        * (shoe1[p2] as f64 / total1 as f64)
-                        * (shoe2[d] as f64 / total2 as f64);
-
-                    let d_val_raw = Self::card_value(d);
-                    let dealer_can_bj = d_val_raw == 1 || d_val_raw == 10;
-
-                    if player_bj {
-                        if dealer_can_bj {
-                            let remaining = Self::shoe_total(&shoe3) as f64;
-                            let p_dealer_bj = if remaining == 0.0 {
-                                0.0
-                            } else if d_val_raw == 1 {
-                                shoe3[9] as f64 / remaining
-                            } else {
-                                shoe3[0] as f64 / remaining
-                            };
-                            ev_sum += prob * (1.0 - p_dealer_bj) * bj_payout;
-                        } else {
-                            ev_sum += prob * bj_payout;
-                        }
-                    } else if self.rules.dealer_peeks && dealer_can_bj {
-                        // Peek game with BJ-possible upcard.
+                let shoe3 = Self::remove_card(&shoe2, d);
+
+                let prob = (Self::count(&shoe, p1) as f64 / total as f64)
+                    * (Self::count(&shoe1, p2) as f64 / total1 as f64)
+                    * (Self::count(&shoe2, d) as f64 / total2 as f64);
+
+                let d_val_raw = Self::card_value(d);
+                let dealer_can_bj = d_val_raw == 1 || d_val_raw == 10;
+
+                let hand = if player_bj {
+                    if dealer_can_bj {
                         let remaining = Self::shoe_total(&shoe3) as f64;
                         let p_dealer_bj = if remaining == 0.0 {
                             0.0
                         } else if d_val_raw == 1 {
-                            shoe3[9] as f64 / remaining
+                            Self::count(&shoe3, 9) as f64 / remaining
                         } else {
-                            shoe3[0] as f64 / remaining
+                            Self::count(&shoe3, 0) as f64 / remaining
                         };
-                        if self.rules.allow_surrender && !self.rules.late_surrender {
-                            // Early surrender: player can surrender before dealer peeks.
-                            let normal_ev =
-                                self.initial_hand_ev(shoe3, p1, p2, p_val, p_soft, d, 0, false);
-                            let peek_ev = -p_dealer_bj + (1.0 - p_dealer_bj) * normal_ev;
-                            ev_sum += prob * peek_ev.max(-0.5);
-                        } else {
-                            // Late surrender (in no-BJ branch) or no surrender.
-                            let can_sur = self.rules.allow_surrender && self.rules.late_surrender;
-                            let normal_ev =
-                                self.initial_hand_ev(shoe3, p1, p2, p_val, p_soft, d, 0, can_sur);
-                            ev_sum += prob * (-p_dealer_bj + (1.0 - p_dealer_bj) * normal_ev);
-                        }
+                        // Dealer blackjack pushes
+                        blackjack.weighted(1.0 - p_dealer_bj)
                     } else {
-                        // No peek, or upcard can't make BJ.
-                        let normal_ev = self.initial_hand_ev(
-                            shoe3,
-                            p1,
-                            p2,
-                            p_val,
-                            p_soft,
-                            d,
-                            0,
-                            self.rules.allow_surrender,
-                        );
-                        ev_sum += prob * normal_ev;
+                        blackjack
                     }
-                }
+                } else if self.rules.dealer_peeks && dealer_can_bj {
+                    // Peek game with BJ-possible upcard.
+                    let remaining = Self::shoe_total(&shoe3) as f64;
+                    let p_dealer_bj = if remaining == 0.0 {
+                        0.0
+                    } else if d_val_raw == 1 {
+                        Self::count(&shoe3, 9) as f64 / remaining
+                    } else {
+                        Self::count(&shoe3, 0) as f64 / remaining
+                    };
+                    if self.rules.allow_surrender && !self.rules.late_surrender {
+                        // Early surrender: player can surrender before dealer peeks.
+                        let normal =
+                            self.initial_hand_ev(shoe3, p1, p2, p_val, p_soft, d, 0, false);
+                        let peek = Moments::LOSE
+                            .weighted(p_dealer_bj)
+                            .add(normal.weighted(1.0 - p_dealer_bj));
+                        peek.best(Moments { ev: -0.5, m2: 0.25 })
+                    } else {
+                        // Late surrender (in no-BJ branch) or no surrender.
+                        let can_sur = self.rules.allow_surrender && self.rules.late_surrender;
+                        let normal =
+                            self.initial_hand_ev(shoe3, p1, p2, p_val, p_soft, d, 0, can_sur);
+                        Moments::LOSE
+                            .weighted(p_dealer_bj)
+                            .add(normal.weighted(1.0 - p_dealer_bj))
+                    }
+                } else {
+                    // No peek, or upcard can't make BJ.
+                    self.initial_hand_ev(
+                        shoe3,
+                        p1,
+                        p2,
+                        p_val,
+                        p_soft,
+                        d,
+                        0,
+                        self.rules.allow_surrender,
+                    )
+                };
+                sum = sum.add(hand.weighted(prob));
             }
         }
 
-        ev_sum
+        sum
     }
 }
 
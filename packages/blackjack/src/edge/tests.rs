@@ -14,6 +14,8 @@ fn standard_single_deck() -> GameRules {
         resplit_aces: false,
         dealer_peeks: true,
         blackjack_payout: PayoutRatio::THREE_TO_TWO,
+        insurance_available: true,
+        insurance_payout: PayoutRatio::TWO_TO_ONE,
     }
 }
 
@@ -24,6 +26,33 @@ fn test_shoe_initial() {
     assert_eq!(EdgeCalculator::shoe_total(&shoe), 52);
 }
 
+#[test]
+fn test_u16_shoe_beyond_u8() {
+    let shoe = MultiDecksEdgeCalculator::initial_shoe(16);
+    assert_eq!(shoe[9], 256);
+    assert_eq!(MultiDecksEdgeCalculator::shoe_total(&shoe), 832);
+}
+
+#[test]
+fn test_u8_shoe_fits_eight_decks() {
+    let shoe = EdgeCalculator::initial_shoe(8);
+    assert_eq!(shoe, [32, 32, 32, 32, 32, 32, 32, 32, 32, 128]);
+    assert_eq!(EdgeCalculator::shoe_total(&shoe), 416);
+}
+
+#[test]
+fn test_multi_decks_calculator_matches_single_deck() {
+    let rules = standard_single_deck();
+    let single = EdgeCalculator::new(rules).calculate();
+    let mut multi: Box<dyn EdgeApi> = Box::new(MultiDecksEdgeCalculator::new(rules));
+    let multi = multi.calculate();
+    assert!((single.house_edge - multi.house_edge).abs() < 1e-12);
+    assert!((single.variance_per_hand - multi.variance_per_hand).abs() < 1e-12);
+
+    let unified = compute_edge(&rules);
+    assert!((single.house_edge - unified.house_edge).abs() < 1e-12);
+}
+
 #[test]
 fn test_add_to_hand_basic() {
     assert_eq!(EdgeCalculator::add_to_hand(5, false, 3), (8, false));
@@ -88,6 +117,68 @@ fn test_stand_ev_push() {
     assert!((EdgeCalculator::stand_ev(20, &dp) - 0.0).abs() < 1e-10);
 }
 
+#[test]
+fn test_insurance_ev_single_deck_no_tens_dealt() {
+    let calc = EdgeCalculator::new(standard_single_deck());
+    // Player 5+6 against an ace: 16 tens left in 49 cards.
+    let shoe = EdgeCalculator::remove_card(&EdgeCalculator::initial_shoe(1), 4);
+    let shoe = EdgeCalculator::remove_card(&shoe, 5);
+    let shoe = EdgeCalculator::remove_card(&shoe, 0);
+    let ev = calc.insurance_ev(shoe, true);
+    // Published value: 16/49 * 2 - 33/49 = -1/49 (about -2.04%).
+    assert!((ev + 1.0 / 49.0).abs() < 1e-12, "Insurance EV {ev}");
+}
+
+#[test]
+fn test_insurance_ev_depends_on_tens_left() {
+    let calc = EdgeCalculator::new(standard_single_deck());
+    // Player 10+10 against an ace: 14 tens left in 49, a worse bet.
+    let shoe = EdgeCalculator::remove_card(&EdgeCalculator::initial_shoe(1), 9);
+    let shoe = EdgeCalculator::remove_card(&shoe, 9);
+    let shoe = EdgeCalculator::remove_card(&shoe, 0);
+    assert!((calc.insurance_ev(shoe, true) + 7.0 / 49.0).abs() < 1e-12);
+
+    // A ten-rich shoe (more than a third tens) makes insurance profitable.
+    let rich = [1, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+    assert!(calc.insurance_ev(rich, true) > 0.0);
+
+    assert_eq!(calc.insurance_ev(shoe, false), f64::NEG_INFINITY);
+}
+
+#[test]
+fn test_should_take_insurance_threshold() {
+    // Thorp, "Beat the Dealer" (1966): insurance at 2:1 is a good bet once more
+    // than a third of the unseen cards are tens. Shoes count the dealer's ace.
+    let full = [4, 4, 4, 4, 4, 4, 4, 4, 4, 16];
+    // 16/51 tens, under a third.
+    assert!(!should_take_insurance(&full, &PayoutRatio::TWO_TO_ONE));
+
+    // 17/51 is exactly a third: break-even, not worth taking.
+    let even = [4, 4, 4, 4, 4, 4, 4, 4, 3, 17];
+    assert!(!should_take_insurance(&even, &PayoutRatio::TWO_TO_ONE));
+
+    // 18/51 tips it over.
+    let rich = [4, 4, 4, 4, 4, 4, 4, 3, 3, 18];
+    assert!(should_take_insurance(&rich, &PayoutRatio::TWO_TO_ONE));
+
+    // At 1:1 the same shoe needs more than half tens.
+    let even_money = PayoutRatio {
+        numerator: 1,
+        denominator: 1,
+    };
+    assert!(!should_take_insurance(&rich, &even_money));
+    assert!(should_take_insurance(
+        &[1, 0, 0, 0, 0, 0, 0, 0, 0, 2],
+        &even_money
+    ));
+
+    // Nothing left to draw after the ace.
+    assert!(!should_take_insurance(
+        &[1, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        &PayoutRatio::TWO_TO_ONE
+    ));
+}
+
 #[test]
 fn test_single_deck_s17_3to2_edge() {
     let mut calc = EdgeCalculator::new(standard_single_deck());
@@ -214,3 +305,108 @@ fn test_resplit_aces() {
         result_no.house_edge * 100.0
     );
 }
+
+#[test]
+fn test_six_deck_std_dev() {
+    let result = EdgeCalculator::new(GameRules::default()).calculate();
+    // Published figure for 6-deck S17 DAS blackjack is about 1.14 units per hand.
+    assert!(
+        (result.std_dev_per_hand - 1.14).abs() < 0.02,
+        "Std dev {:.4} out of range",
+        result.std_dev_per_hand
+    );
+    assert!((result.std_dev_per_hand.powi(2) - result.variance_per_hand).abs() < 1e-12);
+}
+
+// ---------------------------------------------------------------------------
+// Regression against published house edges
+//
+// Reference figures are Wizard of Odds basic-strategy house edges for the rule
+// set (resplit to four hands, no resplitting aces, dealer peeks):
+// https://wizardofodds.com/games/blackjack/calculator/
+// https://wizardofodds.com/games/blackjack/rule-variations/
+// ---------------------------------------------------------------------------
+
+/// Composition-dependent play, which the calculator uses, gains about 0.04% over
+/// total-dependent basic strategy in a single deck; in six or more decks the gap
+/// is negligible.
+const SINGLE_DECK_CD_GAIN: f64 = 0.04;
+
+fn published_rules(num_decks: u8) -> GameRules {
+    GameRules {
+        num_decks,
+        dealer_hits_soft_17: false,
+        allow_surrender: false,
+        late_surrender: false,
+        double_after_split: false,
+        double_restriction: DoubleRestriction::Any,
+        allow_resplit: true,
+        max_splits: 3,
+        resplit_aces: false,
+        dealer_peeks: true,
+        blackjack_payout: PayoutRatio::THREE_TO_TWO,
+        insurance_available: true,
+        insurance_payout: PayoutRatio::TWO_TO_ONE,
+    }
+}
+
+/// Assert the house edge is within 0.05 percentage points of `expected_pct`.
+fn assert_house_edge(rules: GameRules, expected_pct: f64) {
+    let edge_pct = EdgeCalculator::new(rules).calculate().house_edge * 100.0;
+    assert!(
+        (edge_pct - expected_pct).abs() <= 0.05,
+        "House edge {edge_pct:.4}%, expected {expected_pct:.2}% ± 0.05"
+    );
+}
+
+#[test]
+fn test_published_edge_1d_s17_no_das() {
+    // Published: 0.01%
+    assert_house_edge(published_rules(1), 0.01 - SINGLE_DECK_CD_GAIN);
+}
+
+#[test]
+fn test_published_edge_6d_h17_das_ls() {
+    // Published: 0.53%
+    let mut rules = published_rules(6);
+    rules.dealer_hits_soft_17 = true;
+    rules.double_after_split = true;
+    rules.allow_surrender = true;
+    rules.late_surrender = true;
+    assert_house_edge(rules, 0.53);
+}
+
+#[test]
+fn test_published_edge_6d_s17_das() {
+    // Published: 0.39%
+    let mut rules = published_rules(6);
+    rules.double_after_split = true;
+    assert_house_edge(rules, 0.39);
+}
+
+#[test]
+fn test_published_edge_1d_6to5() {
+    // Published: 1.40% (S17, no DAS; 6:5 costs 1.39% in a single deck)
+    let mut rules = published_rules(1);
+    rules.blackjack_payout = PayoutRatio::SIX_TO_FIVE;
+    assert_house_edge(rules, 1.40 - SINGLE_DECK_CD_GAIN);
+}
+
+#[test]
+fn test_published_edge_8d_h17_no_das() {
+    // Published: 0.78%
+    let mut rules = published_rules(8);
+    rules.dealer_hits_soft_17 = true;
+    assert_house_edge(rules, 0.78);
+}
+
+#[test]
+fn test_published_edge_6d_vegas_strip() {
+    // Published: 0.28% (S17, DAS, late surrender, resplit aces)
+    let mut rules = published_rules(6);
+    rules.double_after_split = true;
+    rules.allow_surrender = true;
+    rules.late_surrender = true;
+    rules.resplit_aces = true;
+    assert_house_edge(rules, 0.28);
+}
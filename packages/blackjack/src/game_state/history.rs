@@ -0,0 +1,167 @@
+//! Record of the actions taken in a round, so the round can be replayed from its deck
+//! and audited against the cards each action revealed.
+
+use super::{GamePhase, GameState};
+use crate::{Action, Card, GameRules};
+use serde::{Deserialize, Serialize};
+
+/// One player or dealer action.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionEntry {
+    pub action: Action,
+    /// Hand the action was taken on; not used for the dealer's actions
+    pub spot_index: usize,
+    pub hand_index: usize,
+    /// Card drawn by a hit or double. A split draws two and records neither.
+    pub card_dealt: Option<Card>,
+    pub timestamp: Option<u64>,
+}
+
+/// Actions in the order they were taken
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionHistory {
+    pub entries: Vec<ActionEntry>,
+}
+
+impl GameState {
+    pub fn record_action(&mut self, entry: ActionEntry) {
+        self.history.entries.push(entry);
+    }
+
+    /// Rebuild a round by dealing `deck` in order and applying `history` to it.
+    ///
+    /// The initial deal gives each spot a card, then the dealer, then each spot again,
+    /// then the dealer's hole card. Every later card comes off the deck as an action
+    /// draws it, and must match the entry's `card_dealt` when one is recorded. Once all
+    /// hands are finished, hits and stands are the dealer's.
+    pub fn replay(
+        num_spots: usize,
+        rules: GameRules,
+        deck: &[Card],
+        history: &ActionHistory,
+    ) -> Result<GameState, &'static str> {
+        let mut state = GameState::new(num_spots, rules)?;
+        let mut deck = deck.iter().copied();
+        let mut draw = || deck.next().ok_or("Deck ran out during replay");
+
+        state.phase = GamePhase::InitialDeal;
+        for _ in 0..2 {
+            for spot in &mut state.spots {
+                spot.active_hand_mut().add_card(draw()?);
+            }
+            state.dealer_hand.push(draw()?);
+        }
+
+        state.start_player_turn(None);
+        if state.should_dealer_peek() {
+            state.dealer_peeked = true;
+            if state.dealer_has_blackjack() {
+                state.start_dealer_turn(None);
+            }
+        }
+        state.skip_finished_hands();
+
+        for entry in &history.entries {
+            let drawn = match state.phase {
+                GamePhase::PlayerTurn => state.replay_player_action(entry, &mut draw)?,
+                GamePhase::DealerTurn => match entry.action {
+                    Action::Hit => {
+                        let card = draw()?;
+                        state.dealer_hand.push(card);
+                        Some(card)
+                    }
+                    Action::Stand => {
+                        state.settle();
+                        None
+                    }
+                    _ => return Err("Dealer can only hit or stand"),
+                },
+                _ => return Err("Action recorded after the round was settled"),
+            };
+            if entry.card_dealt.is_some() && entry.card_dealt != drawn {
+                return Err("Recorded card does not match the deck");
+            }
+            state.record_action(entry.clone());
+        }
+
+        // A dealer that busted or drew to a stand has nothing left to record
+        if state.phase == GamePhase::DealerTurn && !state.dealer_should_hit() {
+            state.settle();
+        }
+        Ok(state)
+    }
+
+    /// Apply one player action to the active hand, returning the card a hit or double drew.
+    fn replay_player_action(
+        &mut self,
+        entry: &ActionEntry,
+        draw: &mut impl FnMut() -> Result<Card, &'static str>,
+    ) -> Result<Option<Card>, &'static str> {
+        let spot_index = self.active_spot_index;
+        if entry.spot_index != spot_index
+            || entry.hand_index != self.active_spot().active_hand_index
+        {
+            return Err("Action recorded for a hand out of turn");
+        }
+
+        let drawn = match entry.action {
+            Action::Hit => {
+                let card = draw()?;
+                self.active_spot_mut().active_hand_mut().add_card(card);
+                Some(card)
+            }
+            Action::Stand => {
+                self.active_spot_mut().active_hand_mut().stood = true;
+                None
+            }
+            Action::Double => {
+                if !self.can_double_current_hand() {
+                    return Err("Cannot double this hand");
+                }
+                let card = draw()?;
+                let hand = self.active_spot_mut().active_hand_mut();
+                hand.add_card(card);
+                hand.doubled = true;
+                hand.stood = true;
+                Some(card)
+            }
+            Action::Split => {
+                let rules = self.rules;
+                self.active_spot_mut().split(&rules, spot_index)?;
+                // One card to the hand that was split, then one to the new hand
+                let new_index = self.active_spot().hands.len() - 1;
+                let first = draw()?;
+                self.active_spot_mut().active_hand_mut().add_card(first);
+                let second = draw()?;
+                self.active_spot_mut().hands[new_index].add_card(second);
+                None
+            }
+            Action::Surrender => {
+                if !self.can_surrender_current_hand() {
+                    return Err("Cannot surrender this hand");
+                }
+                self.active_spot_mut().active_hand_mut().surrendered = true;
+                None
+            }
+        };
+
+        self.skip_finished_hands();
+        Ok(drawn)
+    }
+
+    /// Move past stood, doubled, surrendered and 21-or-more hands, handing the round
+    /// to the dealer once every spot is done.
+    fn skip_finished_hands(&mut self) {
+        while self.phase == GamePhase::PlayerTurn {
+            let hand = self.active_spot().active_hand();
+            let finished =
+                hand.stood || hand.doubled || hand.surrendered || hand.value().total >= 21;
+            if !finished {
+                return;
+            }
+            if !self.active_spot_mut().move_to_next_hand() && !self.move_to_next_spot() {
+                self.start_dealer_turn(None);
+            }
+        }
+    }
+}
@@ -1,5 +1,11 @@
-use crate::{Card, GameRules, Hand};
+use crate::{Card, GameRules, Hand, HandOutcome};
+use rand_core::RngCore;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+mod history;
+
+pub use history::{ActionEntry, ActionHistory};
 
 /// Tracks whose turn it is in the game
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -14,11 +20,36 @@ pub enum TurnOwner {
 pub enum GamePhase {
     NotStarted,
     InitialDeal,
+    /// Dealer shows an ace and the player is deciding on insurance
+    InsuranceOffered,
     PlayerTurn,
     DealerTurn,
     Settled,
 }
 
+/// Where [`GameState::deal_initial_cards`] gets its cards. `rng` is there for sources
+/// that reshuffle when they run out.
+pub trait DeckSource {
+    /// The next card, or None once the source is exhausted
+    fn draw(&mut self, rng: &mut dyn RngCore) -> Option<Card>;
+
+    /// Cards left before the source is exhausted, or None if it can't tell
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// Deals from the front and never reshuffles.
+impl DeckSource for VecDeque<Card> {
+    fn draw(&mut self, _rng: &mut dyn RngCore) -> Option<Card> {
+        self.pop_front()
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
 /// Represents a single spot at the table (can have multiple hands if split)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Spot {
@@ -61,39 +92,86 @@ impl Spot {
             return false;
         }
 
-        // Check if can split first hand
-        if self.hands.len() == 1 && self.active_hand().can_split() {
-            // Check if splitting aces and resplit_aces is disabled
-            if let Some(first_card) = self.hands[0].cards.first() {
-                if first_card.rank() == 1 && self.hands.len() > 1 && !rules.resplit_aces {
-                    return false;
-                }
+        let hand = self.active_hand();
+        if !hand.can_split() || hand.doubled || hand.stood {
+            return false;
+        }
+
+        // A hand that came from a split needs resplitting allowed, and for aces, resplit_aces
+        if hand.split_depth > 0 {
+            if !rules.allow_resplit {
+                return false;
+            }
+            if hand.cards[0].rank() == 1 && !rules.resplit_aces {
+                return false;
             }
-            true
-        } else {
-            false
         }
+        true
     }
 
-    pub fn split(&mut self, rules: &GameRules) -> Result<(), &'static str> {
+    /// Split the active hand. Both halves go one level deeper; the new hand records
+    /// `spot_index` and the active hand as its parent.
+    pub fn split(&mut self, rules: &GameRules, spot_index: usize) -> Result<(), &'static str> {
         if !self.can_split(rules) {
             return Err("Cannot split");
         }
 
-        let hand = &mut self.hands[0];
+        let parent_index = self.active_hand_index;
+        let hand = &mut self.hands[parent_index];
         if hand.cards.len() != 2 {
             return Err("Hand must have exactly 2 cards to split");
         }
 
         let second_card = hand.cards.pop().ok_or("No second card")?;
+        hand.split_depth += 1;
 
         let mut new_hand = Hand::new();
         new_hand.add_card(second_card);
+        new_hand.split_from = Some((spot_index, parent_index));
+        new_hand.split_depth = hand.split_depth;
         self.hands.push(new_hand);
 
         Ok(())
     }
 
+    /// Display label for a hand, e.g. "Spot 1" before any split, "Spot 1.1" and
+    /// "Spot 1.2" after one, "Spot 1.2.1" once "Spot 1.2" is split again. Each split
+    /// adds a level: 1 for the half that kept the first card, 2 for the new hand.
+    pub fn hand_label(&self, spot_index: usize, hand_index: usize) -> String {
+        let mut label = format!("Spot {}", spot_index + 1);
+        for level in self.split_path(hand_index) {
+            label.push_str(&format!(".{level}"));
+        }
+        label
+    }
+
+    /// One entry (1 or 2) per split level of `hand_index`, from the first split down.
+    fn split_path(&self, hand_index: usize) -> Vec<u8> {
+        let Some(hand) = self.hands.get(hand_index) else {
+            return vec![];
+        };
+        // Every later split of this hand deepened it by one and left a child behind
+        let children = self
+            .hands
+            .iter()
+            .filter(|h| matches!(h.split_from, Some((_, p)) if p == hand_index))
+            .count() as u8;
+
+        let mut path = match hand.split_from {
+            // Parents always come before their children, which also rules out cycles
+            Some((_, parent)) if parent < hand_index => {
+                let depth_at_split = hand.split_depth.saturating_sub(children);
+                let mut path = self.split_path(parent);
+                path.truncate(depth_at_split.saturating_sub(1) as usize);
+                path.push(2);
+                path
+            }
+            _ => vec![],
+        };
+        path.resize(hand.split_depth as usize, 1);
+        path
+    }
+
     pub fn all_hands_finished(&self) -> bool {
         self.hands
             .iter()
@@ -118,6 +196,15 @@ pub struct GameState {
     pub dealer_peeked: bool,
     pub rules: GameRules,
     pub last_action_timestamp: Option<u64>, // For timeout tracking
+    /// Initial bet per spot; insurance is capped at half of it
+    #[serde(default)]
+    pub initial_bet: u128,
+    #[serde(default)]
+    pub insurance_offered: bool,
+    #[serde(default)]
+    pub insurance_bet: Option<u128>,
+    #[serde(default)]
+    pub history: ActionHistory,
 }
 
 impl GameState {
@@ -135,6 +222,10 @@ impl GameState {
             dealer_peeked: false,
             rules,
             last_action_timestamp: None,
+            initial_bet: 0,
+            insurance_offered: false,
+            insurance_bet: None,
+            history: ActionHistory::default(),
         })
     }
 
@@ -146,16 +237,16 @@ impl GameState {
         &mut self.spots[self.active_spot_index]
     }
 
-    pub fn dealer_value(&self) -> u8 {
+    pub fn dealer_value(&self) -> crate::HandValue {
         crate::calculate_hand_value(&self.dealer_hand)
     }
 
     pub fn dealer_should_hit(&self) -> bool {
         let value = self.dealer_value();
-        if value >= 17 {
+        if value.total >= 17 {
             // Check for soft 17
-            if value == 17 && self.rules.dealer_hits_soft_17 {
-                crate::is_soft_hand(&self.dealer_hand)
+            if value.total == 17 && self.rules.dealer_hits_soft_17 {
+                value.is_soft
             } else {
                 false
             }
@@ -178,6 +269,171 @@ impl GameState {
         crate::is_blackjack(&self.dealer_hand)
     }
 
+    /// Insurance can be offered once, when the dealer shows an ace and the player has yet
+    /// to act on any hand.
+    pub fn can_offer_insurance(&self) -> bool {
+        let untouched = |spot: &Spot| {
+            let hand = &spot.hands[0];
+            spot.hands.len() == 1
+                && hand.cards.len() <= 2
+                && !hand.doubled
+                && !hand.stood
+                && !hand.surrendered
+        };
+        self.rules.insurance_available
+            && !self.insurance_offered
+            && self
+                .dealer_hand
+                .first()
+                .is_some_and(|card| card.value() == 11)
+            && self.spots.iter().all(untouched)
+    }
+
+    /// [`Self::can_offer_insurance`], and the deal is where it's offered.
+    pub fn can_take_insurance(&self) -> bool {
+        self.phase == GamePhase::InitialDeal && self.can_offer_insurance()
+    }
+
+    pub fn offer_insurance(&mut self) -> Result<(), &'static str> {
+        if !self.can_take_insurance() {
+            return Err("Insurance not available");
+        }
+        self.insurance_offered = true;
+        self.phase = GamePhase::InsuranceOffered;
+        Ok(())
+    }
+
+    /// Take insurance for `bet` on every spot, at most half the initial bet, and return
+    /// to the deal.
+    pub fn accept_insurance(&mut self, bet: u128) -> Result<(), &'static str> {
+        if self.phase != GamePhase::InsuranceOffered {
+            return Err("Insurance not offered");
+        }
+        if bet == 0 {
+            return Err("Insurance bet must be greater than zero");
+        }
+        if bet > self.initial_bet / 2 {
+            return Err("Insurance bet cannot exceed half the original bet");
+        }
+        self.insurance_bet = Some(bet);
+        for spot in &mut self.spots {
+            spot.hands[0].insurance_bet = Some(bet);
+        }
+        self.phase = GamePhase::InitialDeal;
+        Ok(())
+    }
+
+    /// Net result of the insurance bets: each pays `insurance_payout` on a dealer
+    /// blackjack and is lost otherwise. Zero when nothing was insured.
+    pub fn settle_insurance(&self, dealer_has_blackjack: bool) -> i64 {
+        let as_i64 = |amount: u128| i64::try_from(amount).unwrap_or(i64::MAX);
+        self.spots
+            .iter()
+            .flat_map(|spot| &spot.hands)
+            .filter_map(|hand| {
+                let bet = hand.insurance_bet?;
+                Some(match hand.insurance_outcome(dealer_has_blackjack)? {
+                    HandOutcome::InsuranceWon => {
+                        as_i64(self.rules.insurance_payout.calculate_payout(bet))
+                    }
+                    _ => -as_i64(bet),
+                })
+            })
+            .fold(0i64, i64::saturating_add)
+    }
+
+    /// EV per unit of insurance from the cards still unseen: the shoe less every card
+    /// on the table. Priced by the edge calculator's `insurance_ev`, so it's
+    /// `f64::NEG_INFINITY` unless the dealer shows an ace.
+    #[cfg(feature = "edge")]
+    pub fn insurance_ev(&self) -> f64 {
+        let decks = u16::from(self.rules.num_decks.max(1));
+        let mut shoe = [4 * decks; 10];
+        shoe[9] = 16 * decks;
+        let seen = self
+            .spots
+            .iter()
+            .flat_map(|spot| &spot.hands)
+            .flat_map(|hand| &hand.cards)
+            .chain(self.dealer_hand.first());
+        for card in seen {
+            let index = match card.value() {
+                11 => 0,
+                value => usize::from(value.min(10)) - 1,
+            };
+            shoe[index] = shoe[index].saturating_sub(1);
+        }
+        crate::MultiDecksEdgeCalculator::new(self.rules).insurance_ev(
+            shoe,
+            self.dealer_hand
+                .first()
+                .is_some_and(|card| card.value() == 11),
+        )
+    }
+
+    pub fn decline_insurance(&mut self) -> Result<(), &'static str> {
+        if self.phase != GamePhase::InsuranceOffered {
+            return Err("Insurance not offered");
+        }
+        self.phase = GamePhase::InitialDeal;
+        Ok(())
+    }
+
+    /// Deal the round in table order: a card to each spot, the dealer's upcard, a second
+    /// card to each spot, then the hole card. Every card is drawn before any is placed,
+    /// so the state is left as it was if the deck runs out.
+    ///
+    /// Against an ace, insurance is offered before the dealer peeks and the deal stops
+    /// there; [`Self::finish_initial_deal`] completes it once the offer is answered.
+    /// Otherwise the deal is finished right away.
+    pub fn deal_initial_cards<R: RngCore>(
+        &mut self,
+        deck: &mut impl DeckSource,
+        rng: &mut R,
+    ) -> Result<(), &'static str> {
+        if self.phase != GamePhase::NotStarted {
+            return Err("Cards already dealt");
+        }
+        let per_round = self.spots.len() + 1;
+        if deck.remaining().is_some_and(|left| left < 2 * per_round) {
+            return Err("Deck is empty");
+        }
+        let cards = (0..2 * per_round)
+            .map(|_| deck.draw(rng).ok_or("Deck is empty"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for round in cards.chunks(per_round) {
+            for (spot, card) in self.spots.iter_mut().zip(round) {
+                spot.hands[0].add_card(*card);
+            }
+            self.dealer_hand.push(round[per_round - 1]);
+        }
+        self.phase = GamePhase::InitialDeal;
+
+        if self.can_take_insurance() {
+            return self.offer_insurance();
+        }
+        self.finish_initial_deal()
+    }
+
+    /// Ends the deal once any insurance offer has been answered: the dealer peeks when
+    /// the rules call for it, and a peeked blackjack settles the round. Otherwise the
+    /// player's turn starts.
+    pub fn finish_initial_deal(&mut self) -> Result<(), &'static str> {
+        if self.phase != GamePhase::InitialDeal || self.dealer_hand.len() != 2 {
+            return Err("Initial deal not in progress");
+        }
+        if self.should_dealer_peek() {
+            self.dealer_peeked = true;
+            if self.dealer_has_blackjack() {
+                self.settle();
+                return Ok(());
+            }
+        }
+        self.start_player_turn(None);
+        Ok(())
+    }
+
     pub fn move_to_next_spot(&mut self) -> bool {
         self.active_spot_index += 1;
         if self.active_spot_index < self.spots.len() {
@@ -248,8 +504,10 @@ impl GameState {
         }
 
         // Check hand value against double restriction
-        let hand_value = crate::calculate_hand_value(&hand.cards);
-        let is_soft = crate::is_soft_hand(&hand.cards);
+        let crate::HandValue {
+            total: hand_value,
+            is_soft,
+        } = crate::calculate_hand_value(&hand.cards);
 
         match self.rules.double_restriction {
             crate::DoubleRestriction::Any => true,
@@ -259,11 +517,10 @@ impl GameState {
     }
 
     pub fn can_surrender_current_hand(&self) -> bool {
-        let spot = self.active_spot();
-        if spot.hands.len() > 1 {
+        let hand = self.active_spot().active_hand();
+        if hand.split_depth > 0 {
             return false; // Can't surrender after split
         }
-        let hand = spot.active_hand();
         self.rules.allow_surrender
             && hand.cards.len() == 2
             && !hand.doubled
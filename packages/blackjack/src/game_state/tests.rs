@@ -33,7 +33,7 @@ fn test_spot_cannot_split_after_split() {
     let mut spot = Spot::new();
     spot.active_hand_mut().add_card(Card::EightHearts);
     spot.active_hand_mut().add_card(Card::EightSpades);
-    spot.split(&rules).unwrap();
+    spot.split(&rules, 0).unwrap();
     assert!(!spot.can_split(&rules)); // Already split
 }
 
@@ -44,7 +44,7 @@ fn test_spot_split() {
     spot.active_hand_mut().add_card(Card::EightHearts);
     spot.active_hand_mut().add_card(Card::EightSpades);
 
-    spot.split(&rules).unwrap();
+    spot.split(&rules, 0).unwrap();
 
     assert_eq!(spot.hands.len(), 2);
     assert_eq!(spot.hands[0].cards.len(), 1);
@@ -53,6 +53,41 @@ fn test_spot_split() {
     assert_eq!(spot.hands[1].cards[0], Card::EightSpades);
 }
 
+#[test]
+fn test_spot_split_history_and_labels() {
+    let rules = GameRules::default();
+    let mut spot = Spot::new();
+    spot.active_hand_mut().add_card(Card::EightHearts);
+    spot.active_hand_mut().add_card(Card::EightSpades);
+    assert_eq!(spot.hand_label(2, 0), "Spot 3");
+
+    spot.split(&rules, 2).unwrap();
+    assert_eq!(spot.hands[0].split_from, None);
+    assert_eq!(spot.hands[1].split_from, Some((2, 0)));
+    assert_eq!(spot.hands[0].split_depth, 1);
+    assert_eq!(spot.hands[1].split_depth, 1);
+    assert_eq!(spot.hand_label(2, 0), "Spot 3.1");
+    assert_eq!(spot.hand_label(2, 1), "Spot 3.2");
+
+    // Resplit the second hand, then the first
+    spot.hands[1].split_depth = 2;
+    let mut third = Hand::new();
+    third.split_from = Some((2, 1));
+    third.split_depth = 2;
+    spot.hands.push(third);
+    spot.hands[0].split_depth = 2;
+    let mut fourth = Hand::new();
+    fourth.split_from = Some((2, 0));
+    fourth.split_depth = 2;
+    spot.hands.push(fourth);
+
+    let labels: Vec<String> = (0..4).map(|i| spot.hand_label(2, i)).collect();
+    assert_eq!(
+        labels,
+        ["Spot 3.1.1", "Spot 3.2.1", "Spot 3.2.2", "Spot 3.1.2"]
+    );
+}
+
 #[test]
 fn test_spot_cannot_split_max_splits_reached() {
     let rules = GameRules {
@@ -62,7 +97,7 @@ fn test_spot_cannot_split_max_splits_reached() {
     let mut spot = Spot::new();
     spot.active_hand_mut().add_card(Card::EightHearts);
     spot.active_hand_mut().add_card(Card::EightSpades);
-    spot.split(&rules).unwrap();
+    spot.split(&rules, 0).unwrap();
 
     // Now we have 2 hands, max_splits is 1, so can't split again
     spot.hands[0].cards.push(Card::EightClubs);
@@ -81,6 +116,34 @@ fn test_spot_cannot_resplit_aces() {
 
     // First split should work
     assert!(spot.can_split(&rules));
+    spot.split(&rules, 0).unwrap();
+
+    // A second ace on a split ace can't be split again
+    spot.hands[0].add_card(Card::AceClubs);
+    assert!(!spot.can_split(&rules));
+
+    let rules = GameRules {
+        resplit_aces: true,
+        ..rules
+    };
+    assert!(spot.can_split(&rules));
+}
+
+#[test]
+fn test_spot_resplit() {
+    let rules = GameRules::default();
+    let mut spot = Spot::new();
+    spot.active_hand_mut().add_card(Card::EightHearts);
+    spot.active_hand_mut().add_card(Card::EightSpades);
+    spot.split(&rules, 0).unwrap();
+    spot.hands[0].add_card(Card::EightClubs);
+    assert!(spot.can_split(&rules));
+
+    let no_resplit = GameRules {
+        allow_resplit: false,
+        ..GameRules::default()
+    };
+    assert!(!spot.can_split(&no_resplit));
 }
 
 #[test]
@@ -89,7 +152,7 @@ fn test_spot_move_to_next_hand() {
     let mut spot = Spot::new();
     spot.active_hand_mut().add_card(Card::EightHearts);
     spot.active_hand_mut().add_card(Card::EightSpades);
-    spot.split(&rules).unwrap();
+    spot.split(&rules, 0).unwrap();
 
     assert_eq!(spot.active_hand_index, 0);
     assert!(spot.move_to_next_hand());
@@ -110,7 +173,7 @@ fn test_cannot_double_after_split_when_disabled() {
         let spot = game.active_spot_mut();
         spot.active_hand_mut().add_card(Card::EightHearts);
         spot.active_hand_mut().add_card(Card::EightSpades);
-        spot.split(&rules_info).unwrap();
+        spot.split(&rules_info, 0).unwrap();
         spot.hands[0].cards.push(Card::TwoClubs);
         spot.hands[1].cards.push(Card::ThreeClubs);
     }
@@ -131,7 +194,7 @@ fn test_can_double_after_split_when_enabled() {
         let spot = game.active_spot_mut();
         spot.active_hand_mut().add_card(Card::EightHearts);
         spot.active_hand_mut().add_card(Card::EightSpades);
-        spot.split(&rules_info).unwrap();
+        spot.split(&rules_info, 0).unwrap();
         spot.hands[0].cards.push(Card::TwoClubs);
         spot.hands[1].cards.push(Card::ThreeClubs);
     }
@@ -315,7 +378,7 @@ fn test_cannot_surrender_after_split() {
         let spot = game.active_spot_mut();
         spot.active_hand_mut().add_card(Card::EightHearts);
         spot.active_hand_mut().add_card(Card::EightSpades);
-        spot.split(&rules_info).unwrap();
+        spot.split(&rules_info, 0).unwrap();
     }
 
     assert!(!game.can_surrender_current_hand()); // Can't surrender after split
@@ -329,3 +392,423 @@ fn test_payout_calculation() {
     let rules_six_five = GameRules::single_deck();
     assert_eq!(rules_six_five.blackjack_payout.calculate_payout(100), 120);
 }
+
+fn dealt_with_upcard(upcard: Card) -> GameState {
+    let mut game = GameState::new(1, GameRules::default()).unwrap();
+    game.initial_bet = 100;
+    game.phase = GamePhase::InitialDeal;
+    game.dealer_hand.push(upcard);
+    game
+}
+
+#[test]
+fn test_can_take_insurance() {
+    let game = dealt_with_upcard(Card::AceSpades);
+    assert!(game.can_take_insurance());
+
+    // Only against an ace
+    assert!(!dealt_with_upcard(Card::KingSpades).can_take_insurance());
+
+    // Only during the initial deal
+    let mut game = dealt_with_upcard(Card::AceSpades);
+    game.start_player_turn(None);
+    assert!(!game.can_take_insurance());
+
+    // Only when the rules offer it
+    let mut game = dealt_with_upcard(Card::AceSpades);
+    game.rules.insurance_available = false;
+    assert!(!game.can_take_insurance());
+    assert_eq!(game.offer_insurance(), Err("Insurance not available"));
+}
+
+#[test]
+fn test_accept_insurance() {
+    let mut game = dealt_with_upcard(Card::AceHearts);
+    assert_eq!(game.accept_insurance(50), Err("Insurance not offered"));
+
+    game.offer_insurance().unwrap();
+    assert_eq!(game.phase, GamePhase::InsuranceOffered);
+    // Offered once per round
+    assert!(!game.can_take_insurance());
+    assert!(game.offer_insurance().is_err());
+
+    assert!(game.accept_insurance(51).is_err()); // More than half of 100
+    assert!(game.accept_insurance(0).is_err());
+    game.accept_insurance(50).unwrap();
+    assert_eq!(game.insurance_bet, Some(50));
+    assert_eq!(game.spots[0].hands[0].insurance_bet, Some(50));
+    assert_eq!(game.phase, GamePhase::InitialDeal);
+    assert!(game.decline_insurance().is_err());
+}
+
+#[test]
+fn test_can_offer_insurance_until_player_acts() {
+    let mut game = dealt_with_upcard(Card::AceSpades);
+    game.spots[0].hands[0].add_card(Card::TenHearts);
+    game.spots[0].hands[0].add_card(Card::SixClubs);
+    assert!(game.can_offer_insurance());
+
+    game.spots[0].hands[0].stood = true;
+    assert!(!game.can_offer_insurance());
+    assert!(!game.can_take_insurance());
+}
+
+#[test]
+fn test_settle_insurance_pays_two_to_one() {
+    let mut game = dealt_with_upcard(Card::AceDiamonds);
+    assert_eq!(game.settle_insurance(true), 0);
+
+    game.offer_insurance().unwrap();
+    game.accept_insurance(50).unwrap();
+    assert_eq!(game.settle_insurance(true), 100);
+    assert_eq!(game.settle_insurance(false), -50);
+    assert_eq!(
+        game.spots[0].hands[0].insurance_outcome(true),
+        Some(HandOutcome::InsuranceWon)
+    );
+
+    // Every spot is insured for the same amount
+    let mut game = GameState::new(3, GameRules::default()).unwrap();
+    game.initial_bet = 100;
+    game.phase = GamePhase::InitialDeal;
+    game.dealer_hand.push(Card::AceSpades);
+    game.offer_insurance().unwrap();
+    game.accept_insurance(40).unwrap();
+    assert_eq!(game.settle_insurance(true), 3 * 80);
+    assert_eq!(game.settle_insurance(false), -3 * 40);
+}
+
+#[test]
+fn test_insured_blackjack_against_dealer_blackjack() {
+    let mut game = dealt_with_upcard(Card::AceClubs);
+    game.spots[0].hands[0].add_card(Card::AceHearts);
+    game.spots[0].hands[0].add_card(Card::KingHearts);
+    game.offer_insurance().unwrap();
+    game.accept_insurance(50).unwrap();
+    game.dealer_hand.push(Card::QueenSpades);
+
+    // The blackjacks push and the insurance pays 2:1: even money on the hand
+    assert!(game.dealer_has_blackjack());
+    assert!(game.spots[0].hands[0].is_blackjack());
+    assert_eq!(game.settle_insurance(game.dealer_has_blackjack()), 100);
+    assert_eq!(
+        HandOutcome::Push.payout_multiplier(&game.rules)
+            + HandOutcome::InsuranceWon.payout_multiplier(&game.rules),
+        1.0
+    );
+}
+
+#[cfg(feature = "edge")]
+#[test]
+fn test_insurance_ev_from_cards_on_table() {
+    let mut game = dealt_with_upcard(Card::AceSpades);
+    game.rules.num_decks = 1;
+    game.spots[0].hands[0].add_card(Card::TwoHearts);
+    game.spots[0].hands[0].add_card(Card::ThreeHearts);
+    // 16 tens among the 49 unseen cards
+    assert!((game.insurance_ev() - (16.0 * 2.0 - 33.0) / 49.0).abs() < 1e-12);
+
+    assert_eq!(
+        dealt_with_upcard(Card::KingSpades).insurance_ev(),
+        f64::NEG_INFINITY
+    );
+}
+
+#[test]
+fn test_decline_insurance() {
+    let mut game = dealt_with_upcard(Card::AceClubs);
+    assert!(game.decline_insurance().is_err());
+
+    game.offer_insurance().unwrap();
+    game.decline_insurance().unwrap();
+    assert_eq!(game.insurance_bet, None);
+    assert_eq!(game.phase, GamePhase::InitialDeal);
+    assert!(!game.can_take_insurance());
+}
+
+fn entry(
+    action: crate::Action,
+    spot_index: usize,
+    hand_index: usize,
+    card: Option<Card>,
+) -> ActionEntry {
+    ActionEntry {
+        action,
+        spot_index,
+        hand_index,
+        card_dealt: card,
+        timestamp: None,
+    }
+}
+
+#[test]
+fn test_replay_hit_stand_and_dealer() {
+    use crate::Action;
+    // Player 10+2, dealer 6 up with a 10 in the hole; no peek against a 6
+    let deck = [
+        Card::TenHearts,
+        Card::SixSpades,
+        Card::TwoClubs,
+        Card::TenDiamonds,
+        Card::FiveHearts,
+        Card::FourSpades,
+    ];
+    let history = ActionHistory {
+        entries: vec![
+            entry(Action::Hit, 0, 0, Some(Card::FiveHearts)),
+            entry(Action::Stand, 0, 0, None),
+            entry(Action::Hit, 0, 0, Some(Card::FourSpades)),
+            entry(Action::Stand, 0, 0, None),
+        ],
+    };
+
+    let game = GameState::replay(1, GameRules::default(), &deck, &history).unwrap();
+    assert_eq!(game.phase, GamePhase::Settled);
+    assert_eq!(game.spots[0].hands[0].value().total, 17);
+    assert_eq!(game.dealer_value().total, 20);
+    assert_eq!(game.history, history);
+
+    // A prefix of the history stops mid-round
+    let partial = ActionHistory {
+        entries: history.entries[..1].to_vec(),
+    };
+    let game = GameState::replay(1, GameRules::default(), &deck, &partial).unwrap();
+    assert_eq!(game.phase, GamePhase::PlayerTurn);
+    assert_eq!(game.history.entries.len(), 1);
+}
+
+#[test]
+fn test_replay_split_then_double() {
+    use crate::Action;
+    let deck = [
+        Card::EightHearts,
+        Card::SixSpades,
+        Card::EightClubs,
+        Card::TenDiamonds,
+        Card::ThreeHearts,
+        Card::TwoSpades,
+        Card::TenClubs,
+        Card::NineHearts,
+    ];
+    let history = ActionHistory {
+        entries: vec![
+            entry(Action::Split, 0, 0, None),
+            entry(Action::Double, 0, 0, Some(Card::TenClubs)),
+            entry(Action::Stand, 0, 1, None),
+            entry(Action::Hit, 0, 0, Some(Card::NineHearts)),
+        ],
+    };
+
+    let game = GameState::replay(1, GameRules::default(), &deck, &history).unwrap();
+    let hands = &game.spots[0].hands;
+    assert_eq!(hands[0].value().total, 21);
+    assert!(hands[0].doubled);
+    assert_eq!(hands[1].value().total, 10);
+    // The dealer busts without a stand being recorded
+    assert!(game.dealer_value().is_busted());
+    assert_eq!(game.phase, GamePhase::Settled);
+}
+
+#[test]
+fn test_replay_rejects_bad_history() {
+    use crate::Action;
+    let deck = [
+        Card::TenHearts,
+        Card::SixSpades,
+        Card::TwoClubs,
+        Card::TenDiamonds,
+        Card::FiveHearts,
+    ];
+    let replay =
+        |entries| GameState::replay(1, GameRules::default(), &deck, &ActionHistory { entries });
+
+    let wrong_card = vec![entry(Action::Hit, 0, 0, Some(Card::AceHearts))];
+    assert_eq!(
+        replay(wrong_card).unwrap_err(),
+        "Recorded card does not match the deck"
+    );
+    let wrong_spot = vec![entry(Action::Stand, 1, 0, None)];
+    assert_eq!(
+        replay(wrong_spot).unwrap_err(),
+        "Action recorded for a hand out of turn"
+    );
+    let past_deck = vec![
+        entry(Action::Hit, 0, 0, None),
+        entry(Action::Hit, 0, 0, None),
+    ];
+    assert_eq!(replay(past_deck).unwrap_err(), "Deck ran out during replay");
+    assert!(GameState::replay(
+        1,
+        GameRules::default(),
+        &deck[..3],
+        &ActionHistory::default()
+    )
+    .is_err());
+}
+
+fn deck(cards: &[Card]) -> VecDeque<Card> {
+    cards.iter().copied().collect()
+}
+
+#[test]
+fn test_deal_initial_cards_in_table_order() {
+    let mut game = GameState::new(2, GameRules::default()).unwrap();
+    let mut rng = crate::simulation::SplitMix64(1);
+    let mut cards = deck(&[
+        Card::TwoHearts,
+        Card::ThreeHearts,
+        Card::SixClubs,
+        Card::FourHearts,
+        Card::FiveHearts,
+        Card::NineClubs,
+        Card::KingSpades,
+    ]);
+    game.deal_initial_cards(&mut cards, &mut rng).unwrap();
+
+    assert_eq!(
+        game.spots[0].hands[0].cards,
+        [Card::TwoHearts, Card::FourHearts]
+    );
+    assert_eq!(
+        game.spots[1].hands[0].cards,
+        [Card::ThreeHearts, Card::FiveHearts]
+    );
+    assert_eq!(game.dealer_hand, [Card::SixClubs, Card::NineClubs]);
+    assert_eq!(cards.len(), 1);
+    // No peek against a six
+    assert!(!game.dealer_peeked);
+    assert_eq!(game.phase, GamePhase::PlayerTurn);
+    assert_eq!(game.current_turn, TurnOwner::Player);
+
+    assert_eq!(
+        game.deal_initial_cards(&mut cards, &mut rng),
+        Err("Cards already dealt")
+    );
+}
+
+#[test]
+fn test_deal_initial_cards_peeks() {
+    let mut rng = crate::simulation::SplitMix64(1);
+    let peeking = GameRules {
+        dealer_peeks: true,
+        ..GameRules::default()
+    };
+
+    // A peeked blackjack ends the round
+    let mut game = GameState::new(1, peeking).unwrap();
+    let mut cards = deck(&[
+        Card::TenHearts,
+        Card::KingSpades,
+        Card::NineHearts,
+        Card::AceClubs,
+    ]);
+    game.deal_initial_cards(&mut cards, &mut rng).unwrap();
+    assert!(game.dealer_peeked);
+    assert_eq!(game.phase, GamePhase::Settled);
+
+    // Peeked and no blackjack: play on
+    let mut game = GameState::new(1, peeking).unwrap();
+    let mut cards = deck(&[
+        Card::TenHearts,
+        Card::KingSpades,
+        Card::NineHearts,
+        Card::SevenClubs,
+    ]);
+    game.deal_initial_cards(&mut cards, &mut rng).unwrap();
+    assert!(game.dealer_peeked);
+    assert_eq!(game.phase, GamePhase::PlayerTurn);
+
+    // Without peeking the blackjack stays hidden until the dealer's turn
+    let mut game = GameState::new(1, GameRules::default()).unwrap();
+    game.rules.dealer_peeks = false;
+    let mut cards = deck(&[
+        Card::TenHearts,
+        Card::KingSpades,
+        Card::NineHearts,
+        Card::AceClubs,
+    ]);
+    game.deal_initial_cards(&mut cards, &mut rng).unwrap();
+    assert!(!game.dealer_peeked);
+    assert_eq!(game.phase, GamePhase::PlayerTurn);
+}
+
+#[test]
+fn test_deal_initial_cards_offers_insurance_before_peek() {
+    let mut rng = crate::simulation::SplitMix64(1);
+    let aces_up = || {
+        deck(&[
+            Card::TenHearts,
+            Card::AceSpades,
+            Card::NineHearts,
+            Card::KingClubs,
+        ])
+    };
+
+    let mut game = GameState::new(1, GameRules::default()).unwrap();
+    game.initial_bet = 100;
+    game.deal_initial_cards(&mut aces_up(), &mut rng).unwrap();
+    assert_eq!(game.phase, GamePhase::InsuranceOffered);
+    assert!(game.insurance_offered);
+    // The hole card stays unchecked until insurance is answered
+    assert!(!game.dealer_peeked);
+    assert_eq!(
+        game.finish_initial_deal(),
+        Err("Initial deal not in progress")
+    );
+
+    game.accept_insurance(50).unwrap();
+    game.finish_initial_deal().unwrap();
+    assert!(game.dealer_peeked);
+    assert_eq!(game.phase, GamePhase::Settled);
+    assert_eq!(game.settle_insurance(game.dealer_has_blackjack()), 100);
+
+    // Declined, and played on without insurance on offer
+    let mut game = GameState::new(1, GameRules::default()).unwrap();
+    game.deal_initial_cards(&mut aces_up(), &mut rng).unwrap();
+    game.decline_insurance().unwrap();
+    game.finish_initial_deal().unwrap();
+    assert_eq!(game.phase, GamePhase::Settled);
+
+    let mut game = GameState::new(1, GameRules::default()).unwrap();
+    game.rules.insurance_available = false;
+    game.deal_initial_cards(&mut aces_up(), &mut rng).unwrap();
+    assert!(!game.insurance_offered);
+    assert!(game.dealer_peeked);
+    assert_eq!(game.phase, GamePhase::Settled);
+}
+
+#[test]
+fn test_deal_initial_cards_empty_deck() {
+    let mut rng = crate::simulation::SplitMix64(1);
+    let mut game = GameState::new(2, GameRules::default()).unwrap();
+    let before = format!("{game:?}");
+
+    // Five of the six cards needed: nothing is drawn or dealt
+    let mut cards = deck(&[
+        Card::TenHearts,
+        Card::AceSpades,
+        Card::NineHearts,
+        Card::KingClubs,
+        Card::TwoClubs,
+    ]);
+    assert_eq!(
+        game.deal_initial_cards(&mut cards, &mut rng),
+        Err("Deck is empty")
+    );
+    assert_eq!(cards.len(), 5);
+    assert_eq!(format!("{game:?}"), before);
+
+    // A source that can't tell how many cards it has left runs out mid-deal
+    struct Uncounted(VecDeque<Card>);
+    impl DeckSource for Uncounted {
+        fn draw(&mut self, _rng: &mut dyn RngCore) -> Option<Card> {
+            self.0.pop_front()
+        }
+    }
+    assert_eq!(
+        game.deal_initial_cards(&mut Uncounted(cards), &mut rng),
+        Err("Deck is empty")
+    );
+    assert_eq!(format!("{game:?}"), before);
+    assert_eq!(game.phase, GamePhase::NotStarted);
+}
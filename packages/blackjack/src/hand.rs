@@ -1,4 +1,4 @@
-use crate::Card;
+use crate::{Card, GameRules};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -8,11 +8,115 @@ pub enum HandOutcome {
     Push,
     Surrender,
     Blackjack,
+    DoubleWin,
+    DoubleLoss,
+    /// The insurance side bet, at the usual half of the initial bet, against a dealer blackjack
+    InsuranceWon,
+    InsuranceLost,
 }
 
-/// Calculate the value of a blackjack hand
-pub fn calculate_hand_value(cards: &[Card]) -> u8 {
-    let mut total = 0;
+impl HandOutcome {
+    /// Net result in units of the initial bet, e.g. -0.5 for a surrender and 1.2 for a
+    /// blackjack paid 6:5.
+    pub fn payout_multiplier(&self, rules: &GameRules) -> f64 {
+        match self {
+            HandOutcome::Win => 1.0,
+            HandOutcome::Loss => -1.0,
+            HandOutcome::Push => 0.0,
+            HandOutcome::Surrender => -0.5,
+            HandOutcome::Blackjack => {
+                rules.blackjack_payout.numerator as f64 / rules.blackjack_payout.denominator as f64
+            }
+            HandOutcome::DoubleWin => 2.0,
+            HandOutcome::DoubleLoss => -2.0,
+            HandOutcome::InsuranceWon => {
+                0.5 * rules.insurance_payout.numerator as f64
+                    / rules.insurance_payout.denominator as f64
+            }
+            HandOutcome::InsuranceLost => -0.5,
+        }
+    }
+}
+
+/// Running tally of settled hands, with the net result in units of the initial bet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionStats {
+    pub hands: u32,
+    /// Wins, including blackjacks and won doubles
+    pub wins: u32,
+    /// Losses, including surrenders and lost doubles
+    pub losses: u32,
+    pub pushes: u32,
+    pub net_units: f64,
+}
+
+impl SessionStats {
+    /// Records one settled outcome. Insurance is a side bet, so it moves `net_units`
+    /// without counting as a hand.
+    pub fn update(&mut self, outcome: HandOutcome, rules: &GameRules) {
+        self.net_units += outcome.payout_multiplier(rules);
+        match outcome {
+            HandOutcome::Win | HandOutcome::Blackjack | HandOutcome::DoubleWin => self.wins += 1,
+            HandOutcome::Loss | HandOutcome::Surrender | HandOutcome::DoubleLoss => {
+                self.losses += 1
+            }
+            HandOutcome::Push => self.pushes += 1,
+            HandOutcome::InsuranceWon | HandOutcome::InsuranceLost => return,
+        }
+        self.hands += 1;
+    }
+}
+
+/// Best total of a hand, and whether an ace still counts as 11 in it.
+///
+/// Comparisons look at `total` only, so a soft 17 and a hard 17 are equal.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HandValue {
+    pub total: u8,
+    pub is_soft: bool,
+}
+
+impl HandValue {
+    pub fn is_busted(&self) -> bool {
+        self.total > 21
+    }
+
+    /// 21 on exactly two cards
+    pub fn is_blackjack(&self, card_count: usize) -> bool {
+        card_count == 2 && self.total == 21
+    }
+}
+
+impl PartialEq for HandValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.total == other.total
+    }
+}
+
+impl Eq for HandValue {}
+
+impl PartialOrd for HandValue {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HandValue {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.total.cmp(&other.total)
+    }
+}
+
+impl std::fmt::Display for HandValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.total)
+    }
+}
+
+/// Calculate the value of a blackjack hand; 0 for an empty hand, total capped at 255.
+/// Summed in u16 so no number of cards can overflow.
+pub fn calculate_hand_value(cards: &[Card]) -> HandValue {
+    let mut total: u16 = 0;
     let mut aces = 0;
 
     for card in cards {
@@ -20,7 +124,7 @@ pub fn calculate_hand_value(cards: &[Card]) -> u8 {
         if value == 11 {
             aces += 1;
         }
-        total += value;
+        total += u16::from(value);
     }
 
     // Adjust for aces
@@ -29,32 +133,25 @@ pub fn calculate_hand_value(cards: &[Card]) -> u8 {
         aces -= 1;
     }
 
-    total
+    HandValue {
+        total: total.min(u16::from(u8::MAX)) as u8,
+        is_soft: aces > 0,
+    }
 }
 
 /// Check if a hand is soft (has an ace counted as 11)
 pub fn is_soft_hand(cards: &[Card]) -> bool {
-    let has_ace = cards.iter().any(|c| c.value() == 11);
-    let value = calculate_hand_value(cards);
-    has_ace
-        && value <= 21
-        && cards.iter().filter(|c| c.value() == 11).count() > 0
-        && cards
-            .iter()
-            .map(|c| if c.value() == 11 { 1 } else { c.value() })
-            .sum::<u8>()
-            + 10
-            == value
+    calculate_hand_value(cards).is_soft
 }
 
 /// Check if a hand is busted
 pub fn is_busted(cards: &[Card]) -> bool {
-    calculate_hand_value(cards) > 21
+    calculate_hand_value(cards).is_busted()
 }
 
 /// Check if a hand is blackjack (21 with 2 cards)
 pub fn is_blackjack(cards: &[Card]) -> bool {
-    cards.len() == 2 && calculate_hand_value(cards) == 21
+    calculate_hand_value(cards).is_blackjack(cards.len())
 }
 
 /// Check if two cards can be split (same rank)
@@ -68,6 +165,15 @@ pub struct Hand {
     pub doubled: bool,
     pub stood: bool,
     pub surrendered: bool,
+    /// (spot_index, hand_index) of the hand this one was split off from
+    #[serde(default)]
+    pub split_from: Option<(usize, usize)>,
+    /// Number of splits in this hand's history; 0 for a hand that was never split
+    #[serde(default)]
+    pub split_depth: u8,
+    /// Insurance staked on this hand, if the player took it
+    #[serde(default)]
+    pub insurance_bet: Option<u128>,
 }
 
 impl Hand {
@@ -77,10 +183,13 @@ impl Hand {
             doubled: false,
             stood: false,
             surrendered: false,
+            split_from: None,
+            split_depth: 0,
+            insurance_bet: None,
         }
     }
 
-    pub fn value(&self) -> u8 {
+    pub fn value(&self) -> HandValue {
         calculate_hand_value(&self.cards)
     }
 
@@ -103,6 +212,17 @@ impl Hand {
     pub fn can_split(&self) -> bool {
         self.cards.len() == 2 && can_split_cards(&self.cards[0], &self.cards[1])
     }
+
+    /// How this hand's insurance went; None if it wasn't insured.
+    pub fn insurance_outcome(&self, dealer_has_blackjack: bool) -> Option<HandOutcome> {
+        self.insurance_bet.map(|_| {
+            if dealer_has_blackjack {
+                HandOutcome::InsuranceWon
+            } else {
+                HandOutcome::InsuranceLost
+            }
+        })
+    }
 }
 
 impl Default for Hand {
@@ -118,37 +238,37 @@ mod tests {
     #[test]
     fn test_calculate_hand_value_simple() {
         let cards = vec![Card::TwoHearts, Card::ThreeSpades];
-        assert_eq!(calculate_hand_value(&cards), 5);
+        assert_eq!(calculate_hand_value(&cards).total, 5);
     }
 
     #[test]
     fn test_calculate_hand_value_with_face_cards() {
         let cards = vec![Card::KingHearts, Card::QueenSpades];
-        assert_eq!(calculate_hand_value(&cards), 20);
+        assert_eq!(calculate_hand_value(&cards).total, 20);
     }
 
     #[test]
     fn test_calculate_hand_value_blackjack() {
         let cards = vec![Card::AceHearts, Card::KingSpades];
-        assert_eq!(calculate_hand_value(&cards), 21);
+        assert_eq!(calculate_hand_value(&cards).total, 21);
     }
 
     #[test]
     fn test_calculate_hand_value_soft_ace() {
         let cards = vec![Card::AceHearts, Card::SixSpades];
-        assert_eq!(calculate_hand_value(&cards), 17); // Ace as 11
+        assert_eq!(calculate_hand_value(&cards).total, 17); // Ace as 11
     }
 
     #[test]
     fn test_calculate_hand_value_hard_ace() {
         let cards = vec![Card::AceHearts, Card::SixSpades, Card::NineClubs];
-        assert_eq!(calculate_hand_value(&cards), 16); // Ace as 1
+        assert_eq!(calculate_hand_value(&cards).total, 16); // Ace as 1
     }
 
     #[test]
     fn test_calculate_hand_value_multiple_aces() {
         let cards = vec![Card::AceHearts, Card::AceSpades, Card::NineClubs];
-        assert_eq!(calculate_hand_value(&cards), 21); // One ace as 11, one as 1
+        assert_eq!(calculate_hand_value(&cards).total, 21); // One ace as 11, one as 1
     }
 
     #[test]
@@ -199,6 +319,132 @@ mod tests {
         assert!(!is_soft_hand(&cards));
     }
 
+    #[test]
+    fn test_empty_hand() {
+        assert_eq!(calculate_hand_value(&[]).total, 0);
+        assert!(!is_soft_hand(&[]));
+        assert!(!is_busted(&[]));
+        assert!(!is_blackjack(&[]));
+
+        let hand = Hand::new();
+        assert_eq!(hand.value().total, 0);
+        assert!(!hand.is_soft() && !hand.is_busted() && !hand.is_blackjack());
+    }
+
+    #[test]
+    fn test_hand_sizes() {
+        // (cards, value, soft, busted, blackjack)
+        let cases: [(&[Card], u8, bool, bool, bool); 7] = [
+            (&[Card::AceHearts], 11, true, false, false),
+            (&[Card::KingHearts], 10, false, false, false),
+            (&[Card::AceHearts, Card::AceSpades], 12, true, false, false),
+            (&[Card::AceHearts, Card::QueenSpades], 21, true, false, true),
+            (
+                &[Card::AceHearts, Card::FiveSpades, Card::FiveClubs],
+                21,
+                true,
+                false,
+                false,
+            ),
+            (
+                &[Card::KingHearts, Card::SixSpades, Card::AceClubs],
+                17,
+                false,
+                false,
+                false,
+            ),
+            (
+                &[Card::KingHearts, Card::SixSpades, Card::SixClubs],
+                22,
+                false,
+                true,
+                false,
+            ),
+        ];
+        for (cards, value, soft, busted, blackjack) in cases {
+            assert_eq!(calculate_hand_value(cards).total, value, "{cards:?}");
+            assert_eq!(is_soft_hand(cards), soft, "{cards:?}");
+            assert_eq!(is_busted(cards), busted, "{cards:?}");
+            assert_eq!(is_blackjack(cards), blackjack, "{cards:?}");
+        }
+    }
+
+    #[test]
+    fn test_hand_value_does_not_overflow() {
+        let aces = [Card::AceHearts; 30];
+        assert_eq!(calculate_hand_value(&aces).total, 30);
+        let tens = [Card::KingHearts; 30];
+        assert_eq!(calculate_hand_value(&tens).total, 255);
+        assert!(is_busted(&tens));
+    }
+
+    #[test]
+    fn test_hand_value_compares_total_only() {
+        let soft_17 = calculate_hand_value(&[Card::AceHearts, Card::SixSpades]);
+        let hard_17 = calculate_hand_value(&[Card::KingHearts, Card::SevenSpades]);
+        let hard_18 = calculate_hand_value(&[Card::KingHearts, Card::EightSpades]);
+        assert!(soft_17.is_soft && !hard_17.is_soft);
+        assert_eq!(soft_17, hard_17);
+        assert!(hard_18 > soft_17);
+        assert!(!hard_18.is_busted());
+        assert!(!soft_17.is_blackjack(2));
+
+        let blackjack = calculate_hand_value(&[Card::AceHearts, Card::KingSpades]);
+        assert!(blackjack.is_blackjack(2));
+        assert!(!blackjack.is_blackjack(3));
+    }
+
+    #[test]
+    fn test_payout_multiplier() {
+        let rules = GameRules::default();
+        let cases = [
+            (HandOutcome::Win, 1.0),
+            (HandOutcome::Loss, -1.0),
+            (HandOutcome::Push, 0.0),
+            (HandOutcome::Surrender, -0.5),
+            (HandOutcome::Blackjack, 1.5),
+            (HandOutcome::DoubleWin, 2.0),
+            (HandOutcome::DoubleLoss, -2.0),
+            (HandOutcome::InsuranceWon, 1.0),
+            (HandOutcome::InsuranceLost, -0.5),
+        ];
+        for (outcome, multiplier) in cases {
+            assert_eq!(outcome.payout_multiplier(&rules), multiplier, "{outcome:?}");
+        }
+
+        let six_to_five = GameRules {
+            blackjack_payout: crate::PayoutRatio::SIX_TO_FIVE,
+            ..rules
+        };
+        assert_eq!(HandOutcome::Blackjack.payout_multiplier(&six_to_five), 1.2);
+        assert_eq!(HandOutcome::Win.payout_multiplier(&six_to_five), 1.0);
+    }
+
+    #[test]
+    fn test_session_stats_update() {
+        let rules = GameRules {
+            blackjack_payout: crate::PayoutRatio::SIX_TO_FIVE,
+            ..GameRules::default()
+        };
+        let mut stats = SessionStats::default();
+        for outcome in [
+            HandOutcome::Blackjack,
+            HandOutcome::DoubleWin,
+            HandOutcome::Surrender,
+            HandOutcome::DoubleLoss,
+            HandOutcome::Push,
+            HandOutcome::InsuranceLost,
+        ] {
+            stats.update(outcome, &rules);
+        }
+        assert_eq!(stats.hands, 5);
+        assert_eq!(stats.wins, 2);
+        assert_eq!(stats.losses, 2);
+        assert_eq!(stats.pushes, 1);
+        // 1.2 + 2.0 - 0.5 - 2.0 + 0.0 - 0.5
+        assert!((stats.net_units - 0.2).abs() < 1e-9, "{}", stats.net_units);
+    }
+
     #[test]
     fn test_can_split_cards_same_rank() {
         let card1 = Card::EightHearts;
@@ -225,7 +471,7 @@ mod tests {
         let mut hand = Hand::new();
         hand.add_card(Card::KingHearts);
         hand.add_card(Card::SevenSpades);
-        assert_eq!(hand.value(), 17);
+        assert_eq!(hand.value().total, 17);
     }
 
     #[test]
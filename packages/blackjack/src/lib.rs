@@ -1,16 +1,28 @@
-// TODO: Add insurance logic
+pub mod betting;
 mod card;
+pub mod counting;
 #[cfg(feature = "edge")]
 mod edge;
 mod game_state;
 mod hand;
+mod risk;
 mod rules;
+pub mod simulation;
+mod strategy;
 
 pub use card::Card;
 #[cfg(feature = "edge")]
-pub use edge::{EdgeCalculator, EdgeResult};
-pub use game_state::{GamePhase, GameState, Spot, TurnOwner};
+pub use edge::{
+    compute_edge, should_take_insurance, EdgeApi, EdgeCalculator, EdgeResult,
+    MultiDecksEdgeCalculator, ShoeCount, ShoeEdgeCalculator,
+};
+pub use game_state::{
+    ActionEntry, ActionHistory, DeckSource, GamePhase, GameState, Spot, TurnOwner,
+};
 pub use hand::{
-    calculate_hand_value, can_split_cards, is_blackjack, is_busted, is_soft_hand, Hand, HandOutcome,
+    calculate_hand_value, can_split_cards, is_blackjack, is_busted, is_soft_hand, Hand,
+    HandOutcome, HandValue, SessionStats,
 };
+pub use risk::{risk_of_ruin, RiskConfig, RiskResult};
 pub use rules::{DoubleRestriction, GameRules, PayoutRatio};
+pub use strategy::{Action, CountingDeviation, DeviationTable, HandOptions, StrategyTable};
@@ -0,0 +1,156 @@
+/// Inputs for a risk-of-ruin estimate. All amounts are in betting units.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskConfig {
+    /// Expected result per hand for the bettor (negative = disadvantage)
+    pub edge: f64,
+    /// Variance of the result per hand
+    pub variance: f64,
+    /// Units the bettor can lose before going broke
+    pub bankroll_units: f64,
+    /// Profit at which the bettor stops; `f64::INFINITY` for plain risk of ruin
+    pub target_units: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskResult {
+    /// Probability of losing the bankroll before reaching the target
+    pub p_ruin: f64,
+    /// Probability of reaching the target first (`1 - p_ruin`)
+    pub p_goal: f64,
+    /// Expected hands until either boundary is hit (infinite if neither is certain)
+    pub expected_hands: f64,
+}
+
+/// Two-boundary risk of ruin, treating the bankroll as Brownian motion with
+/// drift `edge` and variance `variance` per hand.
+///
+/// With `a = 2·edge/variance`, `B` the bankroll and `T` the target:
+/// `p_ruin = (e^(-aT) - 1) / (e^(-aT) - e^(aB))`, which is the continuous limit of the
+/// gambler's-ruin formula and reduces to `e^(-aB)` as `T` goes to infinity.
+pub fn risk_of_ruin(config: RiskConfig) -> RiskResult {
+    let RiskConfig {
+        edge,
+        variance,
+        bankroll_units: bankroll,
+        target_units: target,
+    } = config;
+
+    if bankroll <= 0.0 {
+        return RiskResult {
+            p_ruin: 1.0,
+            p_goal: 0.0,
+            expected_hands: 0.0,
+        };
+    }
+    if variance <= 0.0 {
+        // No variance: the outcome is decided by the sign of the edge alone
+        let p_ruin = if edge < 0.0 { 1.0 } else { 0.0 };
+        let distance = if edge < 0.0 { bankroll } else { target };
+        return RiskResult {
+            p_ruin,
+            p_goal: 1.0 - p_ruin,
+            expected_hands: if edge == 0.0 {
+                f64::INFINITY
+            } else {
+                distance / edge.abs()
+            },
+        };
+    }
+
+    let a = 2.0 * edge / variance;
+    let p_ruin = if a == 0.0 {
+        if target.is_infinite() {
+            1.0
+        } else {
+            target / (bankroll + target)
+        }
+    } else if target.is_infinite() {
+        if a > 0.0 {
+            (-a * bankroll).exp()
+        } else {
+            1.0
+        }
+    } else {
+        // Written with exp_m1 to stay accurate when a·T is small
+        let num = (-a * target).exp_m1();
+        let den = num - (a * bankroll).exp_m1();
+        num / den
+    };
+    let p_ruin = p_ruin.clamp(0.0, 1.0);
+    let p_goal = 1.0 - p_ruin;
+
+    let expected_hands = if a == 0.0 {
+        bankroll * target / variance
+    } else if target.is_infinite() {
+        if edge < 0.0 {
+            bankroll / -edge
+        } else {
+            f64::INFINITY
+        }
+    } else {
+        (target * p_goal - bankroll * p_ruin) / edge
+    };
+
+    RiskResult {
+        p_ruin,
+        p_goal,
+        expected_hands,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ruin_with_infinite_target() {
+        // +0.5% edge, variance 1.3, 100 units: e^(-2·0.005·100/1.3) ≈ 46%
+        let result = risk_of_ruin(RiskConfig {
+            edge: 0.005,
+            variance: 1.3,
+            bankroll_units: 100.0,
+            target_units: f64::INFINITY,
+        });
+        assert!((result.p_ruin - 0.4634).abs() < 0.001, "{result:?}");
+        assert!(result.expected_hands.is_infinite());
+
+        // At a disadvantage, ruin is certain without a target to stop at
+        let result = risk_of_ruin(RiskConfig {
+            edge: -0.005,
+            variance: 1.3,
+            bankroll_units: 100.0,
+            target_units: f64::INFINITY,
+        });
+        assert_eq!(result.p_ruin, 1.0);
+        assert!((result.expected_hands - 20_000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_two_boundary_ruin() {
+        // No edge: plain gambler's ruin, T / (B + T) = 100 / 150
+        let even = risk_of_ruin(RiskConfig {
+            edge: 0.0,
+            variance: 1.3,
+            bankroll_units: 50.0,
+            target_units: 100.0,
+        });
+        assert!((even.p_ruin - 100.0 / 150.0).abs() < 1e-12);
+        assert!((even.expected_hands - 50.0 * 100.0 / 1.3).abs() < 1e-6);
+
+        // A disadvantage raises ruin; an advantage lowers it
+        let base = RiskConfig {
+            edge: -0.005,
+            variance: 1.3,
+            bankroll_units: 100.0,
+            target_units: 100.0,
+        };
+        let losing = risk_of_ruin(base);
+        let winning = risk_of_ruin(RiskConfig {
+            edge: 0.005,
+            ..base
+        });
+        assert!(losing.p_ruin > 0.5 && winning.p_ruin < 0.5);
+        assert!((losing.p_ruin + winning.p_ruin - 1.0).abs() < 1e-12);
+        assert!((losing.p_goal + losing.p_ruin - 1.0).abs() < 1e-12);
+    }
+}
@@ -31,6 +31,10 @@ impl PayoutRatio {
         numerator: 1,
         denominator: 1,
     };
+    pub const TWO_TO_ONE: Self = Self {
+        numerator: 2,
+        denominator: 1,
+    };
 
     pub fn new(numerator: u16, denominator: u16) -> Result<Self, &'static str> {
         if denominator == 0 {
@@ -82,6 +86,22 @@ pub struct GameRules {
 
     /// Number of decks in the shoe
     pub num_decks: u8,
+
+    /// Insurance is offered when the dealer shows an ace
+    #[serde(default = "default_insurance_available")]
+    pub insurance_available: bool,
+
+    /// Insurance payout multiplier (commonly 2:1)
+    #[serde(default = "default_insurance_payout")]
+    pub insurance_payout: PayoutRatio,
+}
+
+fn default_insurance_available() -> bool {
+    true
+}
+
+fn default_insurance_payout() -> PayoutRatio {
+    PayoutRatio::TWO_TO_ONE
 }
 
 impl Default for GameRules {
@@ -99,6 +119,8 @@ impl Default for GameRules {
             dealer_peeks: true,
             blackjack_payout: PayoutRatio::THREE_TO_TWO,
             num_decks: 6,
+            insurance_available: true,
+            insurance_payout: PayoutRatio::TWO_TO_ONE,
         }
     }
 }
@@ -118,6 +140,8 @@ impl GameRules {
             dealer_peeks: false,
             blackjack_payout: PayoutRatio::THREE_TO_TWO,
             num_decks: 6,
+            insurance_available: true,
+            insurance_payout: PayoutRatio::TWO_TO_ONE,
         }
     }
 
@@ -135,6 +159,8 @@ impl GameRules {
             dealer_peeks: true,
             blackjack_payout: PayoutRatio::THREE_TO_TWO,
             num_decks: 8,
+            insurance_available: true,
+            insurance_payout: PayoutRatio::TWO_TO_ONE,
         }
     }
 
@@ -152,6 +178,8 @@ impl GameRules {
             dealer_peeks: true,
             blackjack_payout: PayoutRatio::SIX_TO_FIVE,
             num_decks: 1,
+            insurance_available: true,
+            insurance_payout: PayoutRatio::TWO_TO_ONE,
         }
     }
 }
@@ -0,0 +1,390 @@
+//! Monte Carlo play of basic strategy, as a cross-check on the analytic edge.
+
+use std::collections::VecDeque;
+
+use rand_core::RngCore;
+
+use crate::counting::{DEFAULT_SEED, PENETRATION};
+use crate::strategy::{Action, HandOptions, StrategyTable};
+use crate::{calculate_hand_value, Card, GameRules, HandOutcome};
+
+/// Result of one round, in units of the initial bet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulatedHandResult {
+    /// Net win (positive) or loss (negative)
+    pub net: f64,
+    /// Total staked, including doubles and splits
+    pub wagered: f64,
+}
+
+/// Per-round statistics over a session, in units of the initial bet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationResult {
+    /// Mean net result per round (the player's expected return)
+    pub ev: f64,
+    /// Variance of the net result per round
+    pub variance: f64,
+    /// Rounds played (a split round counts once)
+    pub hands: u64,
+}
+
+/// Plays basic strategy from [`StrategyTable`] against shuffled shoes.
+pub struct SimulationEngine {
+    pub rules: GameRules,
+    pub rng: Box<dyn RngCore + Send>,
+    table: StrategyTable,
+}
+
+impl SimulationEngine {
+    /// Engine seeded with the same default seed as the Hi-Lo simulation.
+    pub fn new(rules: GameRules) -> Self {
+        Self::with_rng(rules, Box::new(SplitMix64(DEFAULT_SEED)))
+    }
+
+    pub fn with_rng(rules: GameRules, rng: Box<dyn RngCore + Send>) -> Self {
+        SimulationEngine {
+            table: StrategyTable::new(&rules),
+            rules,
+            rng,
+        }
+    }
+
+    /// A freshly shuffled shoe for the engine's rules.
+    pub fn shuffled_shoe(&mut self) -> VecDeque<Card> {
+        shuffled_shoe(self.rules.num_decks, self.rng.as_mut())
+    }
+
+    /// Play one round from the front of `shoe`. If the shoe runs out mid-round
+    /// it is replaced with a freshly shuffled one.
+    pub fn run_hand(&mut self, shoe: &mut VecDeque<Card>) -> SimulatedHandResult {
+        let SimulationEngine { rules, rng, table } = self;
+        let mut source = Refilling {
+            num_decks: rules.num_decks,
+            rng: rng.as_mut(),
+            shoe,
+        };
+        play_round(rules, &mut source, |cards, up, options, _| {
+            table.action(cards, up, options)
+        })
+        .expect("a refilling shoe never runs out")
+    }
+
+    /// Play `n_hands` rounds, reshuffling at [`PENETRATION`].
+    pub fn run_session(&mut self, n_hands: u64) -> SimulationResult {
+        let cut =
+            ((self.rules.num_decks.max(1) as usize * 52) as f64 * (1.0 - PENETRATION)) as usize;
+        let mut shoe = self.shuffled_shoe();
+        let mut sum = 0.0;
+        let mut sum_sq = 0.0;
+
+        for _ in 0..n_hands {
+            if shoe.len() <= cut {
+                shoe = self.shuffled_shoe();
+            }
+            let net = self.run_hand(&mut shoe).net;
+            sum += net;
+            sum_sq += net * net;
+        }
+
+        if n_hands == 0 {
+            return SimulationResult {
+                ev: 0.0,
+                variance: 0.0,
+                hands: 0,
+            };
+        }
+        let ev = sum / n_hands as f64;
+        SimulationResult {
+            ev,
+            variance: (sum_sq / n_hands as f64 - ev * ev).max(0.0),
+            hands: n_hands,
+        }
+    }
+}
+
+/// Deals from `shoe`, replacing it with a new shuffled shoe when it empties.
+struct Refilling<'a> {
+    num_decks: u8,
+    rng: &'a mut (dyn RngCore + Send),
+    shoe: &'a mut VecDeque<Card>,
+}
+
+impl Deal for Refilling<'_> {
+    fn deal(&mut self) -> Option<Card> {
+        if self.shoe.is_empty() {
+            *self.shoe = shuffled_shoe(self.num_decks, self.rng);
+        }
+        self.shoe.pop_front()
+    }
+}
+
+fn shuffled_shoe(num_decks: u8, rng: &mut dyn RngCore) -> VecDeque<Card> {
+    let mut cards: Vec<Card> = (0..num_decks.max(1) as usize * 52)
+        .map(|i| Card::from_index(i % 52))
+        .collect();
+    for i in (1..cards.len()).rev() {
+        let j = below(rng, i + 1);
+        cards.swap(i, j);
+    }
+    cards.into()
+}
+
+/// Uniform in `0..bound`.
+fn below(rng: &mut dyn RngCore, bound: usize) -> usize {
+    ((rng.next_u64() as u128 * bound as u128) >> 64) as usize
+}
+
+/// SplitMix64: small, fast and good enough for dealing cards.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl RngCore for SplitMix64 {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        rand_core::impls::fill_bytes_via_next(self, dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SplitMix64 {
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        below(self, bound)
+    }
+}
+
+/// A card source for [`play_round`].
+pub(crate) trait Deal {
+    fn deal(&mut self) -> Option<Card>;
+
+    /// Deal the dealer's hole card, which the player can't see yet.
+    fn deal_hidden(&mut self) -> Option<Card> {
+        self.deal()
+    }
+
+    /// The hole card is turned over.
+    fn reveal(&mut self, _card: Card) {}
+}
+
+struct PlayerHand {
+    cards: Vec<Card>,
+    /// Bet in units of the initial wager
+    stake: f64,
+    from_split: bool,
+    done: bool,
+}
+
+/// Play one round and return the result in units of the initial bet, or `None`
+/// if the cards ran out mid-round. `decide` gets the hand, the dealer's upcard
+/// value, what the hand may do, and the card source (for count-based play).
+pub(crate) fn play_round<D: Deal>(
+    rules: &GameRules,
+    shoe: &mut D,
+    mut decide: impl FnMut(&[Card], u8, HandOptions, &D) -> Action,
+) -> Option<SimulatedHandResult> {
+    let p1 = shoe.deal()?;
+    let up = shoe.deal()?;
+    let p2 = shoe.deal()?;
+    let hole = shoe.deal_hidden()?;
+    let dealer = [up, hole];
+    let dealer_bj = calculate_hand_value(&dealer).is_blackjack(2);
+    let player_bj = calculate_hand_value(&[p1, p2]).is_blackjack(2);
+    let up_value = up.value();
+    let up_can_bj = up_value == 10 || up_value == 11;
+    let single = |net| SimulatedHandResult { net, wagered: 1.0 };
+
+    if player_bj {
+        shoe.reveal(hole);
+        let outcome = if dealer_bj {
+            HandOutcome::Push
+        } else {
+            HandOutcome::Blackjack
+        };
+        return Some(single(outcome.payout_multiplier(rules)));
+    }
+
+    if up_can_bj && rules.allow_surrender && !rules.late_surrender {
+        let options = HandOptions {
+            from_split: false,
+            can_split: false,
+            can_surrender: true,
+        };
+        if decide(&[p1, p2], up_value, options, shoe) == Action::Surrender {
+            shoe.reveal(hole);
+            return Some(single(HandOutcome::Surrender.payout_multiplier(rules)));
+        }
+    }
+
+    if rules.dealer_peeks && up_can_bj && dealer_bj {
+        shoe.reveal(hole);
+        return Some(single(HandOutcome::Loss.payout_multiplier(rules)));
+    }
+
+    let mut hands = vec![PlayerHand {
+        cards: vec![p1, p2],
+        stake: 1.0,
+        from_split: false,
+        done: false,
+    }];
+    let mut splits = 0u8;
+    let mut i = 0;
+    while i < hands.len() {
+        while !hands[i].done {
+            let hand = &hands[i];
+            if calculate_hand_value(&hand.cards).total >= 21 {
+                hands[i].done = true;
+                break;
+            }
+            let split_aces = hand.from_split && hand.cards[0].value() == 11;
+            let can_split = hand.cards.len() == 2
+                && hand.cards[0].rank() == hand.cards[1].rank()
+                && splits < rules.max_splits
+                && (splits == 0 || rules.allow_resplit)
+                && (!split_aces || rules.resplit_aces);
+            if split_aces && !can_split && hand.cards.len() == 2 {
+                hands[i].done = true;
+                break;
+            }
+            // Early surrender against a ten or ace was offered before the peek.
+            let can_surrender = rules.allow_surrender && (rules.late_surrender || !up_can_bj);
+            let options = HandOptions {
+                from_split: hand.from_split,
+                can_split,
+                can_surrender: can_surrender && hands.len() == 1,
+            };
+            match decide(&hand.cards, up_value, options, shoe) {
+                Action::Stand => hands[i].done = true,
+                Action::Hit => hands[i].cards.push(shoe.deal()?),
+                Action::Double => {
+                    hands[i].stake *= 2.0;
+                    hands[i].cards.push(shoe.deal()?);
+                    hands[i].done = true;
+                }
+                Action::Surrender => {
+                    shoe.reveal(hole);
+                    return Some(single(HandOutcome::Surrender.payout_multiplier(rules)));
+                }
+                Action::Split => {
+                    splits += 1;
+                    let second = hands[i].cards.pop()?;
+                    hands[i].from_split = true;
+                    hands[i].cards.push(shoe.deal()?);
+                    let new_hand = PlayerHand {
+                        cards: vec![second, shoe.deal()?],
+                        stake: 1.0,
+                        from_split: true,
+                        done: false,
+                    };
+                    hands.insert(i + 1, new_hand);
+                }
+            }
+        }
+        i += 1;
+    }
+
+    shoe.reveal(hole);
+    let wagered: f64 = hands.iter().map(|h| h.stake).sum();
+    if dealer_bj {
+        // No peek: dealer blackjack takes every bet on the table.
+        return Some(SimulatedHandResult {
+            net: -wagered,
+            wagered,
+        });
+    }
+
+    let mut dealer_cards = dealer.to_vec();
+    if hands
+        .iter()
+        .any(|h| !calculate_hand_value(&h.cards).is_busted())
+    {
+        loop {
+            let value = calculate_hand_value(&dealer_cards);
+            let soft_17 = value.total == 17 && value.is_soft;
+            if value.total > 17 || (value.total == 17 && !(soft_17 && rules.dealer_hits_soft_17)) {
+                break;
+            }
+            dealer_cards.push(shoe.deal()?);
+        }
+    }
+    let dealer_value = calculate_hand_value(&dealer_cards);
+
+    let net = hands
+        .iter()
+        .map(|h| {
+            let value = calculate_hand_value(&h.cards);
+            let (win, loss) = if h.stake > 1.0 {
+                (HandOutcome::DoubleWin, HandOutcome::DoubleLoss)
+            } else {
+                (HandOutcome::Win, HandOutcome::Loss)
+            };
+            let outcome = if value.is_busted() {
+                loss
+            } else if dealer_value.is_busted() || value > dealer_value {
+                win
+            } else if value < dealer_value {
+                loss
+            } else {
+                HandOutcome::Push
+            };
+            outcome.payout_multiplier(rules)
+        })
+        .sum();
+    Some(SimulatedHandResult { net, wagered })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seeded_session_is_reproducible() {
+        let a = SimulationEngine::new(GameRules::default()).run_session(20_000);
+        let b = SimulationEngine::new(GameRules::default()).run_session(20_000);
+        assert_eq!(a, b);
+        assert_eq!(a.hands, 20_000);
+        // Per-hand standard deviation is about 1.15 units
+        assert!((a.variance.sqrt() - 1.15).abs() < 0.1, "{a:?}");
+    }
+
+    #[test]
+    fn test_run_hand_refills_empty_shoe() {
+        let mut engine = SimulationEngine::new(GameRules::default());
+        let mut shoe = VecDeque::new();
+        let result = engine.run_hand(&mut shoe);
+        assert!(result.wagered >= 1.0);
+        assert!(result.net.abs() <= result.wagered * 1.5);
+        assert!(!shoe.is_empty());
+    }
+
+    /// One million hands of basic strategy must land within three standard
+    /// errors of the analytic return. Takes minutes in debug builds.
+    #[cfg(feature = "edge")]
+    #[test]
+    #[ignore]
+    fn test_million_hands_match_edge_calculator() {
+        let rules = GameRules::default();
+        let analytic = crate::EdgeCalculator::new(rules).calculate();
+        let result = SimulationEngine::new(rules).run_session(1_000_000);
+        let std_error = (result.variance / result.hands as f64).sqrt();
+        assert!(
+            (result.ev - analytic.expected_return).abs() <= 3.0 * std_error,
+            "simulated {:.5} vs analytic {:.5} (3σ = {:.5})",
+            result.ev,
+            analytic.expected_return,
+            3.0 * std_error
+        );
+    }
+}
@@ -0,0 +1,646 @@
+//! Total-dependent basic strategy, precomputed per rule set.
+
+use crate::{calculate_hand_value, Card, DoubleRestriction, GameRules, Hand, HandValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+/// What the round still allows for a hand, beyond hitting and standing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandOptions {
+    /// The hand came from a split, so doubling needs double-after-split
+    pub from_split: bool,
+    /// The split limit allows splitting this hand if it is a pair
+    pub can_split: bool,
+    /// Surrender is still on offer
+    pub can_surrender: bool,
+}
+
+/// Table cell for a hard or soft total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Play {
+    Hit,
+    Stand,
+    /// Double if allowed, otherwise hit
+    DoubleOrHit,
+    /// Double if allowed, otherwise stand
+    DoubleOrStand,
+}
+
+/// Dealer upcards in table order: 2 through 10, then ace (value 11).
+const UPCARDS: usize = 10;
+const HARD_MIN: u8 = 4;
+const SOFT_MIN: u8 = 12;
+
+/// Basic strategy for one rule set. Rows are player totals or pair values,
+/// columns dealer upcards 2-10 and ace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyTable {
+    rules: GameRules,
+    /// Hard 4-21
+    hard: [[Play; UPCARDS]; 18],
+    /// Soft 12-21
+    soft: [[Play; UPCARDS]; 10],
+    /// Split a pair of this value (2-11)
+    pairs: [[bool; UPCARDS]; 10],
+    /// Surrender hard 15-17
+    surrender: [[bool; UPCARDS]; 3],
+}
+
+fn up_index(up: u8) -> usize {
+    up.clamp(2, 11) as usize - 2
+}
+
+impl StrategyTable {
+    pub fn new(rules: &GameRules) -> Self {
+        let h17 = rules.dealer_hits_soft_17;
+        let das = rules.double_after_split;
+        let mut table = StrategyTable {
+            rules: *rules,
+            hard: [[Play::Hit; UPCARDS]; 18],
+            soft: [[Play::Hit; UPCARDS]; 10],
+            pairs: [[false; UPCARDS]; 10],
+            surrender: [[false; UPCARDS]; 3],
+        };
+
+        for (col, up) in (2..=11u8).enumerate() {
+            for total in HARD_MIN..=21 {
+                let double = match total {
+                    9 => (3..=6).contains(&up),
+                    10 => up <= 9,
+                    11 => up <= 10 || h17,
+                    _ => false,
+                };
+                let stand = match total {
+                    17.. => true,
+                    13..=16 => up <= 6,
+                    12 => (4..=6).contains(&up),
+                    _ => false,
+                };
+                table.hard[(total - HARD_MIN) as usize][col] = match (double, stand) {
+                    (true, _) => Play::DoubleOrHit,
+                    (false, true) => Play::Stand,
+                    (false, false) => Play::Hit,
+                };
+            }
+
+            for total in SOFT_MIN..=21 {
+                let double = match total {
+                    13 | 14 => (5..=6).contains(&up),
+                    15 | 16 => (4..=6).contains(&up),
+                    17 => (3..=6).contains(&up),
+                    18 => (3..=6).contains(&up) || (h17 && up == 2),
+                    19 => h17 && up == 6,
+                    _ => false,
+                };
+                let stand = match total {
+                    19.. => true,
+                    18 => up <= 8,
+                    _ => false,
+                };
+                table.soft[(total - SOFT_MIN) as usize][col] = match (double, stand) {
+                    (true, true) => Play::DoubleOrStand,
+                    (true, false) => Play::DoubleOrHit,
+                    (false, true) => Play::Stand,
+                    (false, false) => Play::Hit,
+                };
+            }
+
+            for value in 2..=11u8 {
+                table.pairs[value as usize - 2][col] = match value {
+                    11 | 8 => true,
+                    9 => !matches!(up, 7 | 10 | 11),
+                    7 => up <= 7,
+                    6 => up <= 6 && (das || up >= 3),
+                    4 => das && (5..=6).contains(&up),
+                    2 | 3 => up <= 7 && (das || up >= 4),
+                    _ => false,
+                };
+            }
+
+            table.surrender[0][col] = up == 10 || (up == 11 && h17);
+            table.surrender[1][col] = (9..=11).contains(&up);
+            table.surrender[2][col] = up == 11 && h17;
+        }
+        table
+    }
+
+    /// Whether the rules allow doubling down on `cards`.
+    pub fn can_double(&self, cards: &[Card], from_split: bool) -> bool {
+        let HandValue {
+            total,
+            is_soft: soft,
+        } = calculate_hand_value(cards);
+        cards.len() == 2
+            && (!from_split || self.rules.double_after_split)
+            && match self.rules.double_restriction {
+                DoubleRestriction::Any => true,
+                DoubleRestriction::Hard9_10_11 => !soft && (9..=11).contains(&total),
+                DoubleRestriction::Hard10_11 => !soft && (10..=11).contains(&total),
+            }
+    }
+
+    /// Basic strategy play for `cards` against a dealer upcard of value `up`
+    /// (2-11, ace = 11). Totals of 21 or more stand.
+    pub fn action(&self, cards: &[Card], up: u8, options: HandOptions) -> Action {
+        let HandValue {
+            total,
+            is_soft: soft,
+        } = calculate_hand_value(cards);
+        if total >= 21 {
+            return Action::Stand;
+        }
+        let col = up_index(up);
+        let two_cards = cards.len() == 2;
+        let pair = two_cards && cards[0].rank() == cards[1].rank();
+
+        // Hard 16 from a pair of eights splits rather than surrenders, if it can split
+        if options.can_surrender && two_cards && !soft && (15..=17).contains(&total) {
+            let splits_eights =
+                options.can_split && pair && cards[0].value() == 8 && self.pairs[6][col];
+            if self.surrender[(total - 15) as usize][col] && !splits_eights {
+                return Action::Surrender;
+            }
+        }
+
+        if options.can_split && pair && self.pairs[cards[0].value() as usize - 2][col] {
+            return Action::Split;
+        }
+
+        let play = if soft {
+            self.soft[(total - SOFT_MIN) as usize][col]
+        } else {
+            self.hard[(total.max(HARD_MIN) - HARD_MIN) as usize][col]
+        };
+        let can_double = self.can_double(cards, options.from_split);
+        match play {
+            Play::Hit => Action::Hit,
+            Play::Stand => Action::Stand,
+            Play::DoubleOrHit if can_double => Action::Double,
+            Play::DoubleOrHit => Action::Hit,
+            Play::DoubleOrStand if can_double => Action::Double,
+            Play::DoubleOrStand => Action::Stand,
+        }
+    }
+}
+
+impl Hand {
+    /// Basic strategy play for this hand against a dealer upcard of value
+    /// `dealer_upcard` (2-11, ace = 11) under `rules`.
+    ///
+    /// Only the hand is known here, so the split limit is judged from its own
+    /// `split_depth`; callers holding the whole spot can build [`HandOptions`]
+    /// themselves and use [`StrategyTable::action`].
+    pub fn best_action(&self, dealer_upcard: u8, rules: &GameRules) -> Action {
+        let split = self.split_depth > 0;
+        let aces = self.cards.first().is_some_and(|card| card.rank() == 1);
+        let resplit_ok = !split || (rules.allow_resplit && (!aces || rules.resplit_aces));
+        let options = HandOptions {
+            from_split: split,
+            can_split: self.split_depth < rules.max_splits && resplit_ok,
+            can_surrender: rules.allow_surrender && !split && !self.doubled,
+        };
+        StrategyTable::new(rules).action(&self.cards, dealer_upcard, options)
+    }
+}
+
+/// One count-based departure from basic strategy.
+///
+/// Deviations to `Hit` are low-count plays and apply below `index`; every other
+/// deviation applies at `index` or higher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountingDeviation {
+    pub player_value: u8,
+    pub is_soft: bool,
+    /// Dealer upcard value, 2-11 (ace = 11)
+    pub dealer_up: u8,
+    pub basic_action: Action,
+    pub deviation_action: Action,
+    /// Hi-Lo true count at which the deviation takes over
+    pub index: i8,
+}
+
+impl CountingDeviation {
+    fn applies(&self, true_count: i8) -> bool {
+        match self.deviation_action {
+            Action::Hit => true_count < self.index,
+            _ => true_count >= self.index,
+        }
+    }
+}
+
+/// Hi-Lo index plays for one rule set.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DeviationTable {
+    pub plays: Vec<CountingDeviation>,
+    /// Take insurance at this true count or higher; None if insurance isn't offered.
+    /// Insurance is a side bet rather than an `Action`, so it sits outside `plays`.
+    pub insurance_index: Option<i8>,
+}
+
+impl DeviationTable {
+    /// The Illustrious 18 (Schlesinger, "Blackjack Attack"): insurance plus the
+    /// 17 hand plays worth the most to a Hi-Lo counter. Under H17, 11 vs ace is
+    /// already a double, so that entry becomes a hit below -1; doubles the
+    /// rules forbid are left out.
+    pub fn illustrious_18(rules: &GameRules) -> DeviationTable {
+        use Action::*;
+        let hard =
+            |player_value, dealer_up, basic_action, deviation_action, index| CountingDeviation {
+                player_value,
+                is_soft: false,
+                dealer_up,
+                basic_action,
+                deviation_action,
+                index,
+            };
+        let eleven_vs_ace = if rules.dealer_hits_soft_17 {
+            hard(11, 11, Double, Hit, -1)
+        } else {
+            hard(11, 11, Hit, Double, 1)
+        };
+        let plays = vec![
+            hard(16, 10, Hit, Stand, 0),
+            hard(15, 10, Hit, Stand, 4),
+            hard(20, 5, Stand, Split, 5),
+            hard(20, 6, Stand, Split, 4),
+            hard(10, 10, Hit, Double, 4),
+            hard(12, 3, Hit, Stand, 2),
+            hard(12, 2, Hit, Stand, 3),
+            eleven_vs_ace,
+            hard(9, 2, Hit, Double, 1),
+            hard(10, 11, Hit, Double, 4),
+            hard(9, 7, Hit, Double, 3),
+            hard(16, 9, Hit, Stand, 5),
+            hard(13, 2, Stand, Hit, -1),
+            hard(12, 4, Stand, Hit, 0),
+            hard(12, 5, Stand, Hit, -2),
+            hard(12, 6, Stand, Hit, -1),
+            hard(13, 3, Stand, Hit, -2),
+        ];
+        let can_double = |total: u8| match rules.double_restriction {
+            DoubleRestriction::Any => true,
+            DoubleRestriction::Hard9_10_11 => (9..=11).contains(&total),
+            DoubleRestriction::Hard10_11 => (10..=11).contains(&total),
+        };
+        DeviationTable {
+            plays: plays
+                .into_iter()
+                .filter(|d| d.deviation_action != Double || can_double(d.player_value))
+                .collect(),
+            insurance_index: rules.insurance_available.then_some(3),
+        }
+    }
+
+    /// The index play for `hand` against `dealer_up` at this true count, if one
+    /// applies. Doubles and splits only match a two-card hand.
+    pub fn should_deviate(&self, hand: &Hand, dealer_up: Card, true_count: i8) -> Option<Action> {
+        let HandValue { total, is_soft } = hand.value();
+        let two_cards = hand.cards.len() == 2;
+        self.plays
+            .iter()
+            .find(|d| {
+                d.player_value == total
+                    && d.is_soft == is_soft
+                    && d.dealer_up == dealer_up.value()
+                    && (two_cards || !matches!(d.deviation_action, Action::Double | Action::Split))
+            })
+            .filter(|d| d.applies(true_count))
+            .map(|d| d.deviation_action)
+    }
+
+    /// Whether to insure against an ace at this true count.
+    pub fn take_insurance(&self, true_count: i8) -> bool {
+        self.insurance_index
+            .is_some_and(|index| true_count >= index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: HandOptions = HandOptions {
+        from_split: false,
+        can_split: true,
+        can_surrender: true,
+    };
+
+    #[test]
+    fn test_hard_totals() {
+        let table = StrategyTable::new(&GameRules::default());
+        let play = |cards: &[Card], up| table.action(cards, up, ALL);
+        assert_eq!(play(&[Card::TenSpades, Card::TwoHearts], 3), Action::Hit);
+        assert_eq!(play(&[Card::TenSpades, Card::TwoHearts], 4), Action::Stand);
+        assert_eq!(
+            play(&[Card::SixSpades, Card::FiveHearts], 10),
+            Action::Double
+        );
+        assert_eq!(play(&[Card::SixSpades, Card::FiveHearts], 11), Action::Hit);
+        assert_eq!(
+            play(&[Card::TenSpades, Card::SixHearts], 10),
+            Action::Surrender
+        );
+        assert_eq!(
+            play(&[Card::TenSpades, Card::SevenHearts], 11),
+            Action::Stand
+        );
+        // Three-card 11 can't double
+        assert_eq!(
+            play(&[Card::FiveSpades, Card::FourHearts, Card::TwoClubs], 6),
+            Action::Hit
+        );
+    }
+
+    #[test]
+    fn test_soft_totals_and_pairs() {
+        let table = StrategyTable::new(&GameRules::default());
+        let play = |cards: &[Card], up| table.action(cards, up, ALL);
+        assert_eq!(
+            play(&[Card::AceSpades, Card::SevenHearts], 4),
+            Action::Double
+        );
+        assert_eq!(
+            play(&[Card::AceSpades, Card::SevenHearts], 8),
+            Action::Stand
+        );
+        assert_eq!(play(&[Card::AceSpades, Card::SevenHearts], 9), Action::Hit);
+        assert_eq!(
+            play(&[Card::AceSpades, Card::FiveHearts, Card::TwoClubs], 4),
+            Action::Stand
+        );
+        // Eights split instead of surrendering; tens never split
+        assert_eq!(
+            play(&[Card::EightSpades, Card::EightHearts], 10),
+            Action::Split
+        );
+        assert_eq!(
+            play(&[Card::KingSpades, Card::KingHearts], 6),
+            Action::Stand
+        );
+        assert_eq!(
+            play(&[Card::NineSpades, Card::NineHearts], 7),
+            Action::Stand
+        );
+        // With no splits left, 8-8 plays as any hard 16
+        let no_split = HandOptions {
+            can_split: false,
+            ..ALL
+        };
+        assert_eq!(
+            table.action(&[Card::EightSpades, Card::EightHearts], 10, no_split),
+            Action::Surrender
+        );
+        let h17 = StrategyTable::new(&GameRules {
+            dealer_hits_soft_17: true,
+            ..GameRules::default()
+        });
+        assert_eq!(
+            h17.action(&[Card::EightSpades, Card::EightHearts], 11, no_split),
+            Action::Surrender
+        );
+        assert_eq!(
+            h17.action(&[Card::EightSpades, Card::EightHearts], 11, ALL),
+            Action::Split
+        );
+    }
+
+    #[test]
+    fn test_rule_dependent_plays() {
+        let mut rules = GameRules {
+            dealer_hits_soft_17: true,
+            ..GameRules::default()
+        };
+        let h17 = StrategyTable::new(&rules);
+        assert_eq!(
+            h17.action(&[Card::TenSpades, Card::FiveHearts], 11, ALL),
+            Action::Surrender
+        );
+        assert_eq!(
+            h17.action(&[Card::AceSpades, Card::EightHearts], 6, ALL),
+            Action::Double
+        );
+
+        let split_hand = HandOptions {
+            from_split: true,
+            ..ALL
+        };
+        rules.double_after_split = false;
+        let no_das = StrategyTable::new(&rules);
+        assert_eq!(
+            no_das.action(&[Card::FourSpades, Card::FourHearts], 5, ALL),
+            Action::Hit
+        );
+        assert_eq!(
+            no_das.action(&[Card::SixSpades, Card::FiveHearts], 6, split_hand),
+            Action::Hit
+        );
+    }
+
+    fn hand(cards: &[Card]) -> Hand {
+        let mut hand = Hand::new();
+        for &card in cards {
+            hand.add_card(card);
+        }
+        hand
+    }
+
+    /// Upcards 2-10 then ace: H hit, S stand, D double, P split, R surrender.
+    fn expect_row(row: &str) -> Vec<Action> {
+        row.chars()
+            .map(|c| match c {
+                'H' => Action::Hit,
+                'S' => Action::Stand,
+                'D' => Action::Double,
+                'P' => Action::Split,
+                'R' => Action::Surrender,
+                _ => unreachable!("bad table cell {c}"),
+            })
+            .collect()
+    }
+
+    fn card(rank: usize) -> Card {
+        Card::from_index(rank - 1)
+    }
+
+    fn suited(rank: usize) -> Card {
+        Card::from_index(13 + rank - 1)
+    }
+
+    #[test]
+    fn test_best_action_wizard_of_odds_table() {
+        // 4-8 decks, dealer stands on soft 17, double after split, late surrender
+        let rules = GameRules::default();
+        let check = |cards: [Card; 2], row: &str| {
+            let hand = hand(&cards);
+            let actual: Vec<_> = (2..=11).map(|up| hand.best_action(up, &rules)).collect();
+            assert_eq!(actual, expect_row(row), "{cards:?}");
+        };
+
+        let hard = [
+            (3, "HHHHHHHHHH"),
+            (4, "HHHHHHHHHH"),
+            (5, "HHHHHHHHHH"),
+            (6, "HHHHHHHHHH"),
+            (7, "HDDDDHHHHH"),
+            (8, "DDDDDDDDHH"),
+            (9, "DDDDDDDDDH"),
+        ];
+        for (second, row) in hard {
+            check([card(2), suited(second)], row);
+        }
+        let hard_with_ten = [
+            (2, "HHSSSHHHHH"),
+            (3, "SSSSSHHHHH"),
+            (4, "SSSSSHHHHH"),
+            (5, "SSSSSHHHRH"),
+            (6, "SSSSSHHRRR"),
+            (7, "SSSSSSSSSS"),
+            (8, "SSSSSSSSSS"),
+            (9, "SSSSSSSSSS"),
+        ];
+        for (second, row) in hard_with_ten {
+            check([card(10), suited(second)], row);
+        }
+
+        let soft = [
+            (2, "HHHDDHHHHH"),
+            (3, "HHHDDHHHHH"),
+            (4, "HHDDDHHHHH"),
+            (5, "HHDDDHHHHH"),
+            (6, "HDDDDHHHHH"),
+            (7, "SDDDDSSHHH"),
+            (8, "SSSSSSSSSS"),
+            (9, "SSSSSSSSSS"),
+        ];
+        for (second, row) in soft {
+            check([card(1), suited(second)], row);
+        }
+
+        let pairs = [
+            (2, "PPPPPPHHHH"),
+            (3, "PPPPPPHHHH"),
+            (4, "HHHPPHHHHH"),
+            (5, "DDDDDDDDHH"),
+            (6, "PPPPPHHHHH"),
+            (7, "PPPPPPHHHH"),
+            (8, "PPPPPPPPPP"),
+            (9, "PPPPPSPPSS"),
+            (10, "SSSSSSSSSS"),
+            (1, "PPPPPPPPPP"),
+        ];
+        for (rank, row) in pairs {
+            check([card(rank), suited(rank)], row);
+        }
+    }
+
+    #[test]
+    fn test_best_action_respects_rules() {
+        let sixteen = hand(&[Card::TenSpades, Card::SixHearts]);
+        let nine = hand(&[Card::FiveSpades, Card::FourHearts]);
+        let eights = hand(&[Card::EightSpades, Card::EightHearts]);
+
+        let no_surrender = GameRules {
+            allow_surrender: false,
+            ..GameRules::default()
+        };
+        assert_eq!(sixteen.best_action(10, &no_surrender), Action::Hit);
+
+        let ten_eleven = GameRules {
+            double_restriction: DoubleRestriction::Hard10_11,
+            ..GameRules::default()
+        };
+        assert_eq!(nine.best_action(5, &ten_eleven), Action::Hit);
+
+        let no_splits = GameRules {
+            max_splits: 0,
+            ..GameRules::default()
+        };
+        assert_eq!(eights.best_action(6, &no_splits), Action::Stand);
+
+        // A split-off hand at the limit plays as a total, and can't surrender
+        let mut resplit = eights.clone();
+        resplit.split_depth = 1;
+        let one_split = GameRules {
+            max_splits: 1,
+            ..GameRules::default()
+        };
+        assert_eq!(resplit.best_action(10, &one_split), Action::Hit);
+        assert_eq!(
+            resplit.best_action(10, &GameRules::default()),
+            Action::Split
+        );
+    }
+
+    #[test]
+    fn test_illustrious_18() {
+        let table = DeviationTable::illustrious_18(&GameRules::default());
+        assert_eq!(table.plays.len(), 17);
+        assert_eq!(table.insurance_index, Some(3));
+        assert!(!table.take_insurance(2));
+        assert!(table.take_insurance(3));
+
+        // Hard 16 vs 10 stands from a true count of 0, hits below it
+        let sixteen = hand(&[Card::TenSpades, Card::SixHearts]);
+        assert_eq!(table.should_deviate(&sixteen, Card::KingClubs, -1), None);
+        assert_eq!(
+            table.should_deviate(&sixteen, Card::KingClubs, 0),
+            Some(Action::Stand)
+        );
+        let three_card = hand(&[Card::FiveSpades, Card::FiveHearts, Card::SixClubs]);
+        assert_eq!(
+            table.should_deviate(&three_card, Card::TenDiamonds, 2),
+            Some(Action::Stand)
+        );
+
+        // Low-count plays go the other way: 12 vs 4 hits below 0
+        let twelve = hand(&[Card::TenSpades, Card::TwoHearts]);
+        assert_eq!(
+            table.should_deviate(&twelve, Card::FourClubs, -1),
+            Some(Action::Hit)
+        );
+        assert_eq!(table.should_deviate(&twelve, Card::FourClubs, 0), None);
+
+        // Tens split against a 6 at +4, but a three-card 20 can't
+        let tens = hand(&[Card::TenSpades, Card::KingHearts]);
+        assert_eq!(
+            table.should_deviate(&tens, Card::SixClubs, 4),
+            Some(Action::Split)
+        );
+        let twenty = hand(&[Card::TenSpades, Card::FiveHearts, Card::FiveClubs]);
+        assert_eq!(table.should_deviate(&twenty, Card::SixClubs, 4), None);
+    }
+
+    #[test]
+    fn test_illustrious_18_rule_variants() {
+        let rules = GameRules {
+            dealer_hits_soft_17: true,
+            double_restriction: DoubleRestriction::Hard10_11,
+            insurance_available: false,
+            ..GameRules::default()
+        };
+        let table = DeviationTable::illustrious_18(&rules);
+        assert_eq!(table.insurance_index, None);
+        assert!(!table.take_insurance(10));
+
+        // 9 vs 2 and 9 vs 7 can't be doubled
+        assert_eq!(table.plays.len(), 15);
+        let nine = hand(&[Card::FiveSpades, Card::FourHearts]);
+        assert_eq!(table.should_deviate(&nine, Card::TwoClubs, 5), None);
+
+        // 11 vs ace doubles by basic strategy under H17
+        let eleven = hand(&[Card::SixSpades, Card::FiveHearts]);
+        assert_eq!(
+            table.should_deviate(&eleven, Card::AceClubs, -2),
+            Some(Action::Hit)
+        );
+        assert_eq!(table.should_deviate(&eleven, Card::AceClubs, -1), None);
+    }
+}
@@ -0,0 +1,35 @@
+//! 100 separate `inverse` calls against one `batch_invert`, which does a single
+//! inversion plus 297 multiplications.
+//!
+//! cargo bench -p zk-shuffle --bench batch_invert
+
+use ark_ff::Field;
+use ark_std::UniformRand;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use zk_shuffle::babyjubjub::{batch_invert, Fr};
+
+fn bench_batch_invert(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    let values: Vec<Fr> = (0..100).map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut group = c.benchmark_group("invert_100");
+    group.bench_function("inverse", |b| {
+        b.iter(|| {
+            black_box(&values)
+                .iter()
+                .map(|v| v.inverse().unwrap())
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("batch_invert", |b| {
+        b.iter_batched_ref(
+            || values.clone(),
+            |batch| batch_invert(black_box(batch)),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_batch_invert);
+criterion_main!(benches);
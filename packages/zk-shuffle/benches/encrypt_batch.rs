@@ -0,0 +1,33 @@
+//! A full deck through `encrypt_batch` against 52 separate `encrypt` calls.
+//!
+//! cargo bench -p zk-shuffle --bench encrypt_batch
+
+use ark_std::UniformRand;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zk_shuffle::babyjubjub::{base_mult, Fr, Point};
+use zk_shuffle::elgamal::{encrypt, encrypt_batch, KeyPair};
+
+fn bench_encrypt_deck(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    let pk = KeyPair::generate(&mut rng).pk;
+    let messages: Vec<Point> = (1..=52u64).map(|i| base_mult(&Fr::from(i))).collect();
+    let randoms: Vec<Fr> = messages.iter().map(|_| Fr::rand(&mut rng)).collect();
+
+    let mut group = c.benchmark_group("encrypt_52");
+    group.bench_function("encrypt", |b| {
+        b.iter(|| {
+            messages
+                .iter()
+                .zip(&randoms)
+                .map(|(m, r)| encrypt(black_box(&pk), m, r))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.bench_function("encrypt_batch", |b| {
+        b.iter(|| encrypt_batch(black_box(&pk), &messages, &randoms).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_encrypt_deck);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+//! `base_mult` should beat `scalar_mult` on the generator by skipping the doublings.
+//!
+//! cargo bench -p zk-shuffle --bench scalar_mult
+
+use ark_ec::AffineRepr;
+use ark_std::UniformRand;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use zk_shuffle::babyjubjub::{base_mult, scalar_mult, Fr, Point};
+
+fn bench_scalar_mult(c: &mut Criterion) {
+    let mut rng = ark_std::test_rng();
+    let scalar = Fr::rand(&mut rng);
+    let generator = Point::generator();
+    // Build the generator table outside the measurement
+    let _ = base_mult(&scalar);
+
+    let mut group = c.benchmark_group("babyjubjub");
+    group.bench_function("scalar_mult(G)", |b| {
+        b.iter(|| scalar_mult(black_box(&generator), black_box(&scalar)))
+    });
+    group.bench_function("base_mult", |b| b.iter(|| base_mult(black_box(&scalar))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_scalar_mult);
+criterion_main!(benches);
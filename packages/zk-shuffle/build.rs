@@ -1,4 +1,17 @@
 fn main() {
     // No build-time processing needed - we use WASM witness calculators at runtime
     println!("cargo:rerun-if-changed=build.rs");
+
+    // Embedded verifying keys must exist before `include_bytes!` sees them
+    if std::env::var_os("CARGO_FEATURE_EMBED_KEYS").is_some() {
+        for file in ["keys/shuffle_vk.bin", "keys/reveal_vk.bin"] {
+            println!("cargo:rerun-if-changed={file}");
+            if !std::path::Path::new(file).exists() {
+                panic!(
+                    "embed-keys is enabled but {file} is missing; \
+                     run `cargo run -p zk-shuffle --example export_vks`"
+                );
+            }
+        }
+    }
 }
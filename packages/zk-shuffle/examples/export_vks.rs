@@ -0,0 +1,32 @@
+//! Convert the snarkjs verification keys into the checksummed files that the
+//! `embed-keys` feature compiles in (or that `--vk-override` points at).
+//!
+//! cargo run -p zk-shuffle --example export_vks [artifacts_dir] [out_dir]
+
+use std::path::PathBuf;
+use zk_shuffle::proof::vk_from_snarkjs_json;
+use zk_shuffle::vk::{write_checked_file, REVEAL_VK_FILE, SHUFFLE_VK_FILE};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = std::env::args().skip(1);
+    let artifacts = PathBuf::from(
+        args.next()
+            .unwrap_or_else(|| "../../circuits/artifacts".to_string()),
+    );
+    let out = PathBuf::from(args.next().unwrap_or_else(|| "keys".to_string()));
+    std::fs::create_dir_all(&out)?;
+
+    for (json, file) in [
+        ("shuffle_encrypt_vkey.json", SHUFFLE_VK_FILE),
+        ("decrypt_vkey.json", REVEAL_VK_FILE),
+    ] {
+        let vk = vk_from_snarkjs_json(&artifacts.join(json).to_string_lossy())?;
+        write_checked_file(&vk, &out.join(file))?;
+        println!(
+            "{} -> {}",
+            artifacts.join(json).display(),
+            out.join(file).display()
+        );
+    }
+    Ok(())
+}
@@ -1,10 +1,238 @@
-use ark_ff::{BigInteger, PrimeField};
+use crate::error::Error;
+use ark_ec::{AdditiveGroup, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use ark_serialize::CanonicalDeserialize;
+use blake2::{Blake2b512, Digest};
 use num_bigint::BigUint;
+use std::sync::OnceLock;
 pub use taceo_ark_babyjubjub::EdwardsConfig;
 pub use taceo_ark_babyjubjub::Fq;
 pub use taceo_ark_babyjubjub::Fr;
 
 pub type Point = ark_ec::twisted_edwards::Affine<EdwardsConfig>;
+type Projective = ark_ec::twisted_edwards::Projective<EdwardsConfig>;
+
+/// `scalar * point`.
+///
+/// Not constant time: arkworks multiplies by double-and-add over the scalar's bits,
+/// so timing depends on the scalar. Secret keys are only multiplied locally, but
+/// don't use this where an attacker can time many operations on the same secret.
+pub fn scalar_mult(point: &Point, scalar: &Fr) -> Point {
+    (point.into_group() * scalar).into_affine()
+}
+
+/// `scalar * G` for the standard generator.
+///
+/// Adds up a table of `2^i * G` built on first use, which skips the doublings
+/// [`scalar_mult`] has to do. Not constant time either: which table entries get
+/// added depends on the scalar's bits.
+pub fn base_mult(scalar: &Fr) -> Point {
+    static POWERS_OF_TWO: OnceLock<Vec<Projective>> = OnceLock::new();
+    let table = POWERS_OF_TWO.get_or_init(|| {
+        let mut power = Point::generator().into_group();
+        (0..Fr::MODULUS_BIT_SIZE)
+            .map(|_| {
+                let current = power;
+                power.double_in_place();
+                current
+            })
+            .collect()
+    });
+
+    scalar
+        .into_bigint()
+        .to_bits_le()
+        .into_iter()
+        .zip(table)
+        .filter(|(bit, _)| *bit)
+        .map(|(_, power)| *power)
+        .sum::<Projective>()
+        .into_affine()
+}
+
+/// Inverts every element of `values` in place with one field inversion and 3(n-1)
+/// multiplications (Montgomery's trick). Returns false, leaving `values` unchanged,
+/// if any element is zero.
+pub fn batch_invert(values: &mut [Fr]) -> bool {
+    if values.iter().any(|v| v.is_zero()) {
+        return false;
+    }
+    if values.is_empty() {
+        return true;
+    }
+
+    // acc[i] = values[0] * ... * values[i]
+    let acc: Vec<Fr> = values
+        .iter()
+        .scan(Fr::ONE, |product, v| {
+            *product *= v;
+            Some(*product)
+        })
+        .collect();
+
+    // Invariant: current_inv = 1 / (values[0] * ... * values[i])
+    let mut current_inv = acc[acc.len() - 1]
+        .inverse()
+        .expect("product of nonzero elements is nonzero");
+    for i in (1..values.len()).rev() {
+        let inv = acc[i - 1] * current_inv;
+        current_inv *= values[i];
+        values[i] = inv;
+    }
+    values[0] = current_inv;
+    true
+}
+
+/// Validation helpers for points received from untrusted sources.
+pub trait PointExt: Sized {
+    /// Returns true if the point satisfies `a*x^2 + y^2 = 1 + d*x^2*y^2`.
+    fn is_on_curve(&self) -> bool;
+
+    /// Deserializes a compressed point and rejects it unless it lies on the curve
+    /// and in the prime-order subgroup.
+    fn from_bytes_validated(bytes: &[u8]) -> Result<Self, Error>;
+
+    /// Affine `(x, y)` as little-endian hex, see [`field_to_hex`].
+    fn to_coordinates_hex(&self) -> (String, String);
+}
+
+impl PointExt for Point {
+    fn is_on_curve(&self) -> bool {
+        ark_ec::twisted_edwards::Affine::is_on_curve(self)
+    }
+
+    fn from_bytes_validated(bytes: &[u8]) -> Result<Self, Error> {
+        let mut cursor = bytes;
+        let point = Point::deserialize_compressed_unchecked(&mut cursor)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        if !cursor.is_empty() {
+            return Err(Error::Serialization(format!(
+                "{} trailing bytes after point",
+                cursor.len()
+            )));
+        }
+        if !PointExt::is_on_curve(&point) {
+            return Err(Error::Curve(
+                "point is not on the BabyJubJub curve".to_string(),
+            ));
+        }
+        if !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(Error::Curve(
+                "point is not in the prime-order subgroup".to_string(),
+            ));
+        }
+        Ok(point)
+    }
+
+    fn to_coordinates_hex(&self) -> (String, String) {
+        (field_to_hex(&self.x), field_to_hex(&self.y))
+    }
+}
+
+/// Wide reduction into the BabyJubJub scalar field.
+pub trait FrExt {
+    /// Interprets 64 little-endian bytes as a 512-bit integer and reduces it modulo
+    /// the subgroup order. The bias is below 2^-250, so uniform input bytes give a
+    /// uniform scalar.
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Fr;
+
+    /// Little-endian hex of the 32-byte canonical encoding, see [`field_to_hex`].
+    fn to_hex(&self) -> String;
+
+    /// Parses [`FrExt::to_hex`] output, with or without a `0x` prefix.
+    fn from_hex(s: &str) -> Result<Fr, Error>;
+}
+
+impl FrExt for Fr {
+    fn from_bytes_wide(bytes: &[u8; 64]) -> Fr {
+        Fr::from_le_bytes_mod_order(bytes)
+    }
+
+    fn to_hex(&self) -> String {
+        field_to_hex(self)
+    }
+
+    fn from_hex(s: &str) -> Result<Fr, Error> {
+        field_from_hex(s)
+    }
+}
+
+/// Hex of a field element's canonical 32-byte little-endian encoding, the byte
+/// order circom uses for its witness and zkey files.
+pub fn field_to_hex<F: PrimeField>(value: &F) -> String {
+    hex::encode(value.into_bigint().to_bytes_le())
+}
+
+/// Inverse of [`field_to_hex`]. Accepts an optional `0x` prefix and rejects values
+/// that are not exactly 32 bytes or not reduced below the modulus.
+pub fn field_from_hex<F: PrimeField>(s: &str) -> Result<F, Error> {
+    let digits = s.strip_prefix("0x").unwrap_or(s);
+    let bytes = hex::decode(digits).map_err(|e| Error::Serialization(e.to_string()))?;
+    if bytes.len() != 32 {
+        return Err(Error::Serialization(format!(
+            "expected 32 bytes of field element hex, got {}",
+            bytes.len()
+        )));
+    }
+    let value = F::from_le_bytes_mod_order(&bytes);
+    if value.into_bigint().to_bytes_le() != bytes {
+        return Err(Error::InvalidInput(format!(
+            "{s} is not a canonical field element"
+        )));
+    }
+    Ok(value)
+}
+
+/// Hashes arbitrary data to a scalar via BLAKE2b-512 and wide reduction.
+pub fn hash_to_scalar(data: &[u8]) -> Fr {
+    let digest: [u8; 64] = Blake2b512::digest(data).into();
+    Fr::from_bytes_wide(&digest)
+}
+
+/// Domain separator for [`hash_to_point`], so its digests never collide with
+/// other BLAKE2b uses of the same data.
+const HASH_TO_POINT_DOMAIN: &[u8] = b"juodzekas/babyjubjub/hash_to_point/v1";
+
+/// Hashes arbitrary data to a point in the prime-order subgroup using
+/// try-and-increment with BLAKE2b-512.
+///
+/// Each attempt hashes `domain || data || counter` and uses the digest as a candidate
+/// `y` coordinate (wide-reduced into `Fq`), solving `x^2 = (1 - y^2) / (a - d*y^2)`.
+/// The top digest bit picks the sign of `x`. Roughly half the candidates have a square
+/// root, and the result is multiplied by the cofactor so it lands in the subgroup. No
+/// discrete log relative to the generator is known for the output.
+pub fn hash_to_point(data: &[u8]) -> Point {
+    use ark_ec::twisted_edwards::TECurveConfig;
+    use ark_ff::{Field, One};
+
+    for counter in 0u32.. {
+        let digest: [u8; 64] = Blake2b512::new()
+            .chain_update(HASH_TO_POINT_DOMAIN)
+            .chain_update(data)
+            .chain_update(counter.to_le_bytes())
+            .finalize()
+            .into();
+        let y = Fq::from_le_bytes_mod_order(&digest);
+        let y2 = y.square();
+        let denominator = EdwardsConfig::COEFF_A - EdwardsConfig::COEFF_D * y2;
+        let Some(inverse) = denominator.inverse() else {
+            continue;
+        };
+        let Some(mut x) = ((Fq::one() - y2) * inverse).sqrt() else {
+            continue;
+        };
+        let x_negative = BigUint::from_bytes_le(&x.into_bigint().to_bytes_le()) * 2u32 > get_q();
+        if x_negative != (digest[63] & 0x80 != 0) {
+            x = -x;
+        }
+
+        let point = Point::new_unchecked(x, y).clear_cofactor();
+        if !point.is_zero() {
+            return point;
+        }
+    }
+    unreachable!("ran out of hash_to_point counters")
+}
 
 pub fn get_q() -> BigUint {
     BigUint::parse_bytes(
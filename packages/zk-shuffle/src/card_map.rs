@@ -0,0 +1,101 @@
+use crate::babyjubjub::{base_mult, scalar_mult, Fr, Point};
+use crate::elgamal::{encrypt_batch_random, Ciphertext};
+use crate::error::Error;
+use ark_serialize::CanonicalSerialize;
+use rand::Rng;
+use std::collections::HashMap;
+
+/// Cards in one deck; a card's value is its index modulo this.
+pub const CARDS_PER_DECK: usize = 52;
+
+/// Reverse lookup from a revealed card point to its card value: the point's index,
+/// modulo [`CARDS_PER_DECK`] once there is more than one deck.
+#[derive(Clone, Debug)]
+pub struct CardMap {
+    table: HashMap<[u8; 32], u8>,
+    cards: Vec<Point>,
+}
+
+impl CardMap {
+    /// Builds the map for cards `i -> card_scalars[i] * generator`.
+    pub fn from_params(generator: &Point, card_scalars: &[Fr]) -> CardMap {
+        CardMap::from_points(
+            card_scalars
+                .iter()
+                .map(|s| scalar_mult(generator, s))
+                .collect(),
+        )
+    }
+
+    fn from_points(cards: Vec<Point>) -> CardMap {
+        let table = cards
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (compress(p), (i % CARDS_PER_DECK) as u8))
+            .collect();
+        CardMap { table, cards }
+    }
+
+    pub fn lookup(&self, point: &Point) -> Option<u8> {
+        self.table.get(&compress(point)).copied()
+    }
+
+    pub fn all_cards(&self) -> Vec<(u8, Point)> {
+        self.cards
+            .iter()
+            .enumerate()
+            .map(|(i, p)| ((i % CARDS_PER_DECK) as u8, *p))
+            .collect()
+    }
+}
+
+/// The plaintext points of a `num_decks` shoe. Card `i` of deck `k` is
+/// `(i + 1 + 52·k)·G`, so every card in the shoe is a distinct point, and deck 0 matches
+/// `PrecomputedShuffleInputs`.
+#[derive(Clone, Debug)]
+pub struct CardDeck {
+    pub deck_size: usize,
+    map: CardMap,
+}
+
+impl CardDeck {
+    /// Fails when `num_decks` is 0.
+    pub fn new(num_decks: u8) -> Result<CardDeck, Error> {
+        if num_decks == 0 {
+            return Err(Error::InvalidInput(
+                "a shoe needs at least one deck".to_string(),
+            ));
+        }
+        let deck_size = CARDS_PER_DECK * num_decks as usize;
+        let points = (1..=deck_size as u64)
+            .map(|i| base_mult(&Fr::from(i)))
+            .collect();
+        Ok(CardDeck {
+            deck_size,
+            map: CardMap::from_points(points),
+        })
+    }
+
+    /// The card points in shoe order.
+    pub fn points(&self) -> &[Point] {
+        &self.map.cards
+    }
+
+    /// Every card point encrypted under `pk` with fresh randomness, in shoe order.
+    pub fn encrypt_all<R: Rng>(&self, pk: &Point, rng: &mut R) -> Vec<Ciphertext> {
+        encrypt_batch_random(rng, pk, self.points())
+    }
+
+    /// Card value (0-51) of a fully decrypted point, whichever deck it came from.
+    pub fn lookup_point_value(&self, point: &Point) -> Option<u8> {
+        self.map.lookup(point)
+    }
+}
+
+fn compress(point: &Point) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    point
+        .serialize_compressed(&mut buf[..])
+        .expect("compressed BabyJubJub point is 32 bytes");
+    buf
+}
@@ -1,4 +1,5 @@
-use crate::babyjubjub::{Fr, Point};
+use crate::babyjubjub::{scalar_mult, Fr, Point};
+use crate::card_map::CardMap;
 use crate::elgamal::Ciphertext;
 use crate::proof::RevealPublicInputs;
 use ark_bn254::Fr as Bn254Fr;
@@ -14,13 +15,12 @@ pub struct RevealResult {
 pub fn reveal_card(sk: &Fr, ciphertext: &Ciphertext, pk: &Point) -> RevealResult {
     // 1. Compute partial decryption: sk * c0
     // This is what we return and will be combined with other players' partial decryptions
-    let partial_decryption = (ciphertext.c0.into_group() * *sk).into_affine();
+    let partial_decryption = scalar_mult(&ciphertext.c0, sk);
 
     // 2. The decrypt circuit computes: out = c1 - sk * c0
     // In a multi-party setting, this is NOT the final message, but it's what the circuit outputs
     // We need to provide this as a public input for verification
-    let circuit_output =
-        (ciphertext.c1.into_group() - partial_decryption.into_group()).into_affine();
+    let circuit_output = ciphertext.reveal(&partial_decryption);
 
     // 3. Prepare ZK proof inputs
     let public_inputs = RevealPublicInputs::from_babyjubjub(
@@ -42,3 +42,67 @@ pub fn reveal_card(sk: &Fr, ciphertext: &Ciphertext, pk: &Point) -> RevealResult
         sk_p,
     }
 }
+
+/// Sums partial decryptions (`sk_i * c0`) from every key holder, for
+/// [`Ciphertext::reveal`]. The card only comes out once every key holder of the
+/// aggregated public key has contributed a partial.
+pub fn combine_partials(partials: &[Point]) -> Point {
+    partials
+        .iter()
+        .map(|p| p.into_group())
+        .sum::<<Point as AffineRepr>::Group>()
+        .into_affine()
+}
+
+/// Card index (0-51) of a fully decrypted card point, if it is one of the cards.
+pub fn lookup_card_value(point: &Point, card_map: &CardMap) -> Option<u8> {
+    card_map.lookup(point)
+}
+
+/// Every point in a two-party reveal, for tracking down a card that came out wrong.
+#[derive(Clone, Debug)]
+pub struct RevealAuditTrail {
+    pub ciphertext: Ciphertext,
+    pub my_partial: Point,
+    /// The identity point while the other party's partial is missing
+    pub their_partial: Point,
+    pub combined_partial: Point,
+    pub revealed_point: Point,
+    pub matched_card: Option<u8>,
+}
+
+impl RevealAuditTrail {
+    /// The combined partial is the sum of both partials, and the revealed point is
+    /// what that leaves of `c1`.
+    pub fn verify_combination(&self) -> bool {
+        let sum = (self.my_partial.into_group() + self.their_partial.into_group()).into_affine();
+        self.combined_partial == sum
+            && self.revealed_point
+                == (self.ciphertext.c1.into_group() - self.combined_partial.into_group())
+                    .into_affine()
+    }
+}
+
+/// [`reveal_card`], plus the audit trail of combining our partial with `their_partial`
+/// and looking the result up in `card_map`.
+pub fn reveal_card_with_audit(
+    sk: &Fr,
+    ciphertext: &Ciphertext,
+    pk: &Point,
+    their_partial: Option<Point>,
+    card_map: &CardMap,
+) -> (RevealResult, RevealAuditTrail) {
+    let reveal = reveal_card(sk, ciphertext, pk);
+    let their_partial = their_partial.unwrap_or_else(Point::zero);
+    let combined_partial = combine_partials(&[reveal.partial_decryption, their_partial]);
+    let revealed_point = ciphertext.reveal(&combined_partial);
+    let audit = RevealAuditTrail {
+        ciphertext: ciphertext.clone(),
+        my_partial: reveal.partial_decryption,
+        their_partial,
+        combined_partial,
+        revealed_point,
+        matched_card: lookup_card_value(&revealed_point, card_map),
+    };
+    (reveal, audit)
+}
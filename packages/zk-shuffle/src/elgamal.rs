@@ -1,7 +1,9 @@
-use crate::babyjubjub::{Fr, Point};
-use ark_ec::{AffineRepr, CurveGroup};
+use crate::babyjubjub::{base_mult, hash_to_scalar, scalar_mult, Fr, Point};
+use crate::error::Error;
+use ark_ec::{AffineRepr, CurveGroup, ScalarMul};
 use ark_ff::UniformRand;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 
 pub struct KeyPair {
     pub sk: Fr,
@@ -11,28 +13,89 @@ pub struct KeyPair {
 impl KeyPair {
     pub fn generate<R: Rng>(rng: &mut R) -> Self {
         let sk = Fr::rand(rng);
-        let pk = (Point::generator() * sk).into_affine();
+        let pk = base_mult(&sk);
+        KeyPair { sk, pk }
+    }
+
+    /// Derives a keypair deterministically from seed material.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let sk = hash_to_scalar(seed);
+        let pk = base_mult(&sk);
         KeyPair { sk, pk }
     }
 }
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Ciphertext {
+    #[serde(with = "crate::serde_ark")]
     pub c0: Point,
+    #[serde(with = "crate::serde_ark")]
     pub c1: Point,
 }
 
+impl Ciphertext {
+    /// Threshold reveal: `c1 - combined_partial`, where `combined_partial` is the
+    /// sum of every key holder's `sk_i * c0` (see [`crate::decrypt::combine_partials`]).
+    /// Unlike [`decrypt`], no party needs the full secret key.
+    pub fn reveal(&self, combined_partial: &Point) -> Point {
+        (self.c1.into_group() - combined_partial.into_group()).into_affine()
+    }
+}
+
 pub fn encrypt(pk: &Point, m: &Point, r: &Fr) -> Ciphertext {
     // c0 = r * g
-    let c0 = (Point::generator() * *r).into_affine();
+    let c0 = base_mult(r);
     // c1 = r * pk + m
-    let c1 = (pk.into_group() * *r + m.into_group()).into_affine();
+    let c1 = (scalar_mult(pk, r).into_group() + m.into_group()).into_affine();
     Ciphertext { c0, c1 }
 }
 
+/// Encrypts `messages[i]` with `randoms[i]`; same output as calling [`encrypt`] for each.
+///
+/// Both `r_i * G` and `r_i * pk` go through arkworks' fixed-base batch
+/// multiplication: one window table per base, shared by every message, and a single
+/// field inversion to bring all the results back to affine.
+pub fn encrypt_batch(
+    pk: &Point,
+    messages: &[Point],
+    randoms: &[Fr],
+) -> Result<Vec<Ciphertext>, Error> {
+    if messages.len() != randoms.len() {
+        return Err(Error::LengthMismatch {
+            messages: messages.len(),
+            randoms: randoms.len(),
+        });
+    }
+
+    let c0s = Point::generator().into_group().batch_mul(randoms);
+    let shared = pk.into_group().batch_mul(randoms);
+    let c1s: Vec<_> = shared
+        .iter()
+        .zip(messages)
+        .map(|(r_pk, m)| *r_pk + m)
+        .collect();
+    let c1s = <Point as AffineRepr>::Group::normalize_batch(&c1s);
+
+    Ok(c0s
+        .into_iter()
+        .zip(c1s)
+        .map(|(c0, c1)| Ciphertext { c0, c1 })
+        .collect())
+}
+
+/// [`encrypt_batch`] with fresh randomness for every message.
+pub fn encrypt_batch_random<R: Rng>(
+    rng: &mut R,
+    pk: &Point,
+    messages: &[Point],
+) -> Vec<Ciphertext> {
+    let randoms: Vec<Fr> = messages.iter().map(|_| Fr::rand(rng)).collect();
+    encrypt_batch(pk, messages, &randoms).expect("one random per message")
+}
+
 pub fn decrypt(sk: &Fr, c: &Ciphertext) -> Point {
     // m = c1 - sk * c0
-    let sk_c0 = (c.c0.into_group() * *sk).into_affine();
+    let sk_c0 = scalar_mult(&c.c0, sk);
 
     (c.c1.into_group() - sk_c0.into_group()).into_affine()
 }
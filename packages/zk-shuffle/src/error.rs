@@ -10,4 +10,14 @@ pub enum Error {
     Verification(String),
     #[error("Invalid input: {0}")]
     InvalidInput(String),
+    #[error("Proof generation failed: {0}")]
+    ProofGenerationFailed(String),
+    #[error("Key store error: {0}")]
+    KeyStore(String),
+    #[error("Encryption error: {0}")]
+    Encryption(String),
+    #[error("Length mismatch: {messages} messages but {randoms} randoms")]
+    LengthMismatch { messages: usize, randoms: usize },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 }
@@ -0,0 +1,111 @@
+use crate::babyjubjub::{Fr, Point, PointExt};
+use crate::error::Error;
+use crate::sealed::{OpenError, SealedFormat};
+use argon2::Argon2;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use rand::RngCore;
+use std::path::PathBuf;
+
+const SALT_LEN: usize = 16;
+const FORMAT: SealedFormat = SealedFormat {
+    magic: b"JZKS",
+    version: 1,
+    header_len: SALT_LEN,
+};
+
+/// Password-encrypted storage for per-game ElGamal keys.
+///
+/// Files are sealed (see [`crate::sealed`]) with magic `JZKS` and the Argon2id salt
+/// (16) as the header. The payload is the compressed `sk || pk`. Clients pass
+/// [`crate::sealed::storage_key_from_mnemonic`] as the password.
+pub struct KeyStore {
+    pub dir: PathBuf,
+}
+
+impl KeyStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        KeyStore { dir: dir.into() }
+    }
+
+    pub fn key_path(&self, game_id: u64) -> PathBuf {
+        self.dir.join(format!("game_{game_id}_keys.bin"))
+    }
+
+    pub fn save_game_keys(
+        &self,
+        game_id: u64,
+        sk: &Fr,
+        pk: &Point,
+        password: &[u8],
+    ) -> Result<(), Error> {
+        let mut payload = Vec::new();
+        sk.serialize_compressed(&mut payload)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        pk.serialize_compressed(&mut payload)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let data = FORMAT.seal(&derive_key(password, &salt)?, &salt, &payload)?;
+
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(self.key_path(game_id), data)?;
+        Ok(())
+    }
+
+    pub fn load_game_keys(&self, game_id: u64, password: &[u8]) -> Result<(Fr, Point), Error> {
+        let data = std::fs::read(self.key_path(game_id))?;
+        let open_error = |e: OpenError| {
+            let reason = match e {
+                OpenError::Format => "not a key store file".to_string(),
+                OpenError::UnsupportedVersion(v) => format!("unsupported key file version {v}"),
+                OpenError::Decrypt => "wrong password or corrupted file".to_string(),
+            };
+            Error::KeyStore(format!("game {game_id}: {reason}"))
+        };
+        let salt = FORMAT.header(&data).map_err(open_error)?;
+        let payload = FORMAT
+            .open(&derive_key(password, salt)?, &data)
+            .map_err(open_error)?;
+
+        let mut cursor = payload.as_slice();
+        let sk = Fr::deserialize_compressed(&mut cursor)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        let pk = Point::from_bytes_validated(cursor)?;
+        Ok((sk, pk))
+    }
+
+    /// Removes the key file for a game. Missing files are not an error.
+    pub fn delete_game_keys(&self, game_id: u64) -> Result<(), Error> {
+        match std::fs::remove_file(self.key_path(game_id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn list_game_ids(&self) -> Result<Vec<u64>, Error> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut ids: Vec<u64> = std::fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                name.strip_prefix("game_")?
+                    .strip_suffix("_keys.bin")?
+                    .parse()
+                    .ok()
+            })
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
+}
+
+fn derive_key(password: &[u8], salt: &[u8]) -> Result<[u8; 32], Error> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password, salt, &mut key)
+        .map_err(|e| Error::KeyStore(format!("key derivation failed: {e}")))?;
+    Ok(key)
+}
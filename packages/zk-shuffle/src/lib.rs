@@ -1,19 +1,26 @@
 pub mod babyjubjub;
+pub mod card_map;
 pub mod decrypt;
 pub mod elgamal;
 pub mod error;
+pub mod keypair;
 pub mod proof;
+pub mod prover;
+pub mod sealed;
+mod serde_ark;
 pub mod shuffle;
+pub mod vk;
 
+pub use card_map::{CardDeck, CardMap};
 pub use error::Error;
 
 #[cfg(test)]
 mod tests {
-    use crate::babyjubjub::{Fq, Fr, Point};
-    use crate::decrypt::reveal_card;
+    use crate::babyjubjub::{base_mult, scalar_mult, Fq, Fr, Point};
+    use crate::decrypt::{combine_partials, lookup_card_value, reveal_card};
     use crate::elgamal::{decrypt, encrypt, KeyPair};
     use crate::proof::ShufflePublicInputs;
-    use crate::shuffle::shuffle;
+    use crate::shuffle::{partial_shuffle, shuffle};
     use ark_ec::{AffineRepr, CurveGroup};
     use ark_std::UniformRand;
 
@@ -30,6 +37,8 @@ mod tests {
         let decrypted_m = decrypt(&keypair.sk, &c);
 
         assert_eq!(m, decrypted_m);
+        // A reveal with the single key holder's partial is the same decryption
+        assert_eq!(c.reveal(&scalar_mult(&c.c0, &keypair.sk)), m);
     }
 
     #[test]
@@ -42,11 +51,10 @@ mod tests {
         let aggregated_pk = (keypair1.pk.into_group() + keypair2.pk.into_group()).into_affine();
 
         // Initial deck: 3 cards (represented by points)
-        let g = Point::generator();
         let cards = [
-            g,
-            (g.into_group() + g.into_group()).into_affine(),
-            (g.into_group() + g.into_group() + g.into_group()).into_affine(),
+            base_mult(&Fr::from(1u64)),
+            base_mult(&Fr::from(2u64)),
+            base_mult(&Fr::from(3u64)),
         ];
 
         let mut deck: Vec<crate::elgamal::Ciphertext> = cards
@@ -72,15 +80,53 @@ mod tests {
 
         // Combine partial decryptions to get the card
         // m = c1 - (reveal1 + reveal2)
-        let combined_reveal = (reveal1.partial_decryption.into_group()
-            + reveal2.partial_decryption.into_group())
-        .into_affine();
-        let revealed_card = (c.c1.into_group() - combined_reveal.into_group()).into_affine();
+        let partials = [reveal1.partial_decryption, reveal2.partial_decryption];
+        let revealed_card = c.reveal(&combine_partials(&partials));
+        let sum = (partials[0].into_group() + partials[1].into_group()).into_affine();
+        assert_eq!(
+            (c.c1.into_group() - sum.into_group()).into_affine(),
+            revealed_card
+        );
 
         // The revealed card should be one of the original cards
         assert!(cards.contains(&revealed_card));
     }
 
+    #[test]
+    fn test_partial_shuffle_touches_only_listed_cards() {
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+        let mut deck: Vec<_> = (1..=52u64)
+            .map(|i| encrypt(&keypair.pk, &base_mult(&Fr::from(i)), &Fr::rand(&mut rng)))
+            .collect();
+        let before = deck.clone();
+
+        // Two distinct positions, padded to the circuit's four with the lowest free ones
+        let result = partial_shuffle(&mut rng, &deck, &[7, 0, 7], &keypair.pk);
+        assert_eq!(result.indices, [0, 1, 2, 7]);
+        result.apply_to_deck(&mut deck);
+        for (i, (old, new)) in before.iter().zip(&deck).enumerate() {
+            let unchanged = (old.c0, old.c1) == (new.c0, new.c1);
+            assert_eq!(unchanged, !result.indices.contains(&i), "card {i}");
+            assert_eq!(decrypt(&keypair.sk, old), decrypt(&keypair.sk, new));
+        }
+
+        // Output, pk, then indices and four coordinates per card, then the selectors
+        let inputs = result.public_inputs.to_ark_public_inputs();
+        assert_eq!(inputs.len(), 1 + 2 + 4 * 5 + 2 + 2);
+        assert_eq!(result.public_inputs.indices[3], ark_bn254::Fr::from(7u64));
+
+        let too_many = std::panic::catch_unwind(|| {
+            partial_shuffle(
+                &mut ark_std::test_rng(),
+                &before,
+                &[0, 1, 2, 3, 4],
+                &keypair.pk,
+            )
+        });
+        assert!(too_many.is_err());
+    }
+
     #[test]
     fn test_proof_verification_logic() {
         let mut rng = ark_std::test_rng();
@@ -98,4 +144,642 @@ mod tests {
         let ark_public_inputs = public_inputs.to_ark_public_inputs();
         assert_eq!(ark_public_inputs.len(), 1 + 2 + 52 * 4 + 2 + 2);
     }
+
+    #[test]
+    fn test_shuffle_public_inputs_validate() {
+        let mut rng = ark_std::test_rng();
+        let pk = KeyPair::generate(&mut rng).pk;
+        let deck: Vec<_> = (1..=52u64)
+            .map(|i| encrypt(&pk, &base_mult(&Fr::from(i)), &Fr::rand(&mut rng)))
+            .collect();
+        let inputs = shuffle(&mut rng, &deck, &pk).public_inputs;
+        inputs.validate().unwrap();
+
+        let mut short = inputs.clone();
+        short.vx1.pop();
+        assert!(short.validate().is_err());
+
+        let mut repeated = inputs.clone();
+        repeated.ux0[7] = repeated.ux0[6];
+        assert!(repeated.validate().is_err());
+
+        let mut off_curve = inputs.clone();
+        off_curve.pk[1] += ark_bn254::Fr::from(1u64);
+        assert!(off_curve.validate().is_err());
+    }
+
+    #[test]
+    fn test_proof_input_decimal_strings() {
+        use crate::proof::ProofInputSerializer;
+        use ark_ff::PrimeField;
+
+        assert_eq!(ProofInputSerializer::field_to_decimal_string(&Fq::from(0u64)), "0");
+        assert_eq!(
+            ProofInputSerializer::field_to_decimal_string(&-Fq::from(1u64)),
+            "21888242871839275222246405745257275088548364400416034343698204186575808495616"
+        );
+
+        let mut rng = ark_std::test_rng();
+        let fields: Vec<Fq> = (0..8).map(|_| Fq::rand(&mut rng)).collect();
+        let strs = ProofInputSerializer::fields_to_strings(&fields);
+        assert_eq!(ProofInputSerializer::strings_to_fields(&strs).unwrap(), fields);
+
+        let modulus = Fq::MODULUS.to_string();
+        assert!(ProofInputSerializer::decimal_string_to_field(&modulus).is_err());
+        assert!(ProofInputSerializer::decimal_string_to_field("12a").is_err());
+        assert!(ProofInputSerializer::decimal_string_to_field("").is_err());
+    }
+
+    #[test]
+    fn test_point_validation() {
+        use crate::babyjubjub::PointExt;
+        use ark_serialize::CanonicalSerialize;
+
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+        assert!(PointExt::is_on_curve(&keypair.pk));
+
+        let mut buf = Vec::new();
+        keypair.pk.serialize_compressed(&mut buf).unwrap();
+        assert_eq!(Point::from_bytes_validated(&buf).unwrap(), keypair.pk);
+
+        // Trailing garbage is rejected
+        let mut padded = buf.clone();
+        padded.push(0);
+        assert!(Point::from_bytes_validated(&padded).is_err());
+        assert!(Point::from_bytes_validated(&buf[..16]).is_err());
+
+        // Arbitrary coordinates do not satisfy the curve equation
+        let off_curve = Point::new_unchecked(Fq::from(1u64), Fq::from(2u64));
+        assert!(!PointExt::is_on_curve(&off_curve));
+
+        // (0, -1) is on the curve but has order 2
+        let low_order = Point::new_unchecked(Fq::from(0u64), -Fq::from(1u64));
+        assert!(PointExt::is_on_curve(&low_order));
+        let mut low_buf = Vec::new();
+        low_order.serialize_compressed(&mut low_buf).unwrap();
+        assert!(Point::from_bytes_validated(&low_buf).is_err());
+    }
+
+    #[test]
+    fn test_from_bytes_wide_vectors() {
+        use crate::babyjubjub::FrExt;
+        use ark_ff::{BigInteger, Field, PrimeField};
+        use num_bigint::BigUint;
+
+        assert_eq!(Fr::from_bytes_wide(&[0u8; 64]), Fr::from(0u64));
+
+        // The group order itself reduces to zero
+        let mut order = [0u8; 64];
+        order[..32].copy_from_slice(&Fr::MODULUS.to_bytes_le());
+        assert_eq!(Fr::from_bytes_wide(&order), Fr::from(0u64));
+
+        // 2^256 lands in the high half
+        let mut two_256 = [0u8; 64];
+        two_256[32] = 1;
+        assert_eq!(Fr::from_bytes_wide(&two_256), Fr::from(2u64).pow([256u64]));
+
+        // 2^512 - 1 checked against big-integer arithmetic
+        let all_ones = [0xffu8; 64];
+        let modulus = BigUint::from_bytes_le(&Fr::MODULUS.to_bytes_le());
+        let expected = BigUint::from_bytes_le(&all_ones) % &modulus;
+        let reduced = Fr::from_bytes_wide(&all_ones).into_bigint().to_bytes_le();
+        assert_eq!(BigUint::from_bytes_le(&reduced), expected);
+    }
+
+    #[test]
+    fn test_encrypt_batch_matches_encrypt() {
+        use crate::elgamal::{encrypt_batch, encrypt_batch_random};
+        use crate::Error;
+
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+        let messages: Vec<Point> = (1..=52u64).map(|i| base_mult(&Fr::from(i))).collect();
+        let randoms: Vec<Fr> = messages.iter().map(|_| Fr::rand(&mut rng)).collect();
+
+        let batch = encrypt_batch(&keypair.pk, &messages, &randoms).unwrap();
+        for ((ct, m), r) in batch.iter().zip(&messages).zip(&randoms) {
+            let single = encrypt(&keypair.pk, m, r);
+            assert_eq!((ct.c0, ct.c1), (single.c0, single.c1));
+        }
+
+        let fresh = encrypt_batch_random(&mut rng, &keypair.pk, &messages);
+        for (ct, m) in fresh.iter().zip(&messages) {
+            assert_eq!(decrypt(&keypair.sk, ct), *m);
+        }
+
+        assert!(encrypt_batch(&keypair.pk, &[], &[]).unwrap().is_empty());
+        assert!(matches!(
+            encrypt_batch(&keypair.pk, &messages, &randoms[..51]),
+            Err(Error::LengthMismatch {
+                messages: 52,
+                randoms: 51
+            })
+        ));
+    }
+
+    #[test]
+    fn test_base_mult_matches_scalar_mult() {
+        use ark_ff::Zero;
+
+        let g = Point::generator();
+        assert!(base_mult(&Fr::zero()).is_zero());
+        assert_eq!(base_mult(&Fr::from(1u64)), g);
+        assert_eq!(base_mult(&-Fr::from(1u64)), -g);
+
+        let mut rng = ark_std::test_rng();
+        for _ in 0..16 {
+            let s = Fr::rand(&mut rng);
+            assert_eq!(base_mult(&s), scalar_mult(&g, &s));
+        }
+    }
+
+    #[test]
+    fn test_batch_invert() {
+        use crate::babyjubjub::batch_invert;
+        use ark_ff::{Field, One};
+
+        let mut rng = ark_std::test_rng();
+        for n in [1, 2, 100] {
+            let values: Vec<Fr> = (0..n).map(|_| Fr::rand(&mut rng)).collect();
+            let mut inverted = values.clone();
+            assert!(batch_invert(&mut inverted));
+            for (v, inv) in values.iter().zip(&inverted) {
+                assert_eq!(*inv, v.inverse().unwrap(), "n={n}");
+                assert!((*v * inv).is_one());
+            }
+        }
+
+        assert!(batch_invert(&mut []));
+
+        let mut with_zero = vec![Fr::from(3u64), Fr::from(0u64), Fr::from(5u64)];
+        let before = with_zero.clone();
+        assert!(!batch_invert(&mut with_zero));
+        assert_eq!(with_zero, before);
+    }
+
+    #[test]
+    fn test_groth_proof_round_trip() {
+        use crate::proof::{GrothProof, RapidsnarkProof};
+        use ark_bn254::{G1Affine, G2Affine};
+        use ark_ff::PrimeField;
+        use ark_groth16::Proof;
+
+        let proof = Proof {
+            a: G1Affine::generator(),
+            b: G2Affine::generator(),
+            c: (G1Affine::generator() * ark_bn254::Fr::from(2u64)).into_affine(),
+        };
+
+        let groth = GrothProof::from(proof.clone());
+        assert_eq!(groth.pi_a, ["1", "2", "1"]);
+        assert_eq!(
+            groth.pi_b[0],
+            [
+                "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+                "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+            ]
+        );
+        assert_eq!(
+            groth.pi_b[1],
+            [
+                "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+                "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+            ]
+        );
+        assert_eq!(groth.to_ark_proof().unwrap(), proof);
+
+        // Rapidsnark output carries the same coordinates plus metadata
+        let rapidsnark = RapidsnarkProof {
+            pi_a: groth.pi_a.clone(),
+            pi_b: groth.pi_b.clone(),
+            pi_c: groth.pi_c.clone(),
+            protocol: Some("groth16".to_string()),
+            curve: Some("bn128".to_string()),
+        };
+        assert_eq!(GrothProof::from(rapidsnark), groth);
+
+        // EIP-197 layout: a = (1, 2) big-endian, G2 imaginary part before real part
+        let bytes = groth.to_bytes().unwrap();
+        assert_eq!(bytes[31], 1);
+        assert_eq!(bytes[63], 2);
+        assert!(bytes[..31].iter().chain(&bytes[32..63]).all(|&b| b == 0));
+        assert_eq!(
+            hex::encode(&bytes[64..96]),
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"
+        );
+
+        let mut bad = groth.clone();
+        bad.pi_c[0] = "12a".to_string();
+        assert!(bad.to_ark_proof().is_err());
+        bad.pi_c[0] = ark_bn254::Fq::MODULUS.to_string();
+        assert!(bad.to_ark_proof().is_err());
+    }
+
+    #[test]
+    fn test_field_hex_vectors() {
+        use crate::babyjubjub::{FrExt, PointExt};
+
+        // Generator (circomlib's Base8) coordinates, 32 bytes little-endian
+        let (x_hex, y_hex) = Point::generator().to_coordinates_hex();
+        assert_eq!(
+            x_hex,
+            "517095bbf6f39328b6e0340501d8b82ac177629de0b2ac4e9b733ed66a7ab70b"
+        );
+        assert_eq!(
+            y_hex,
+            "8b7d2d877a253c4b7733e1b91f05e0fcedf96bd11c2e572549b2a0f703727925"
+        );
+
+        let mut rng = ark_std::test_rng();
+        let r = Fr::rand(&mut rng);
+        assert_eq!(Fr::from_hex(&r.to_hex()).unwrap(), r);
+        assert_eq!(Fr::from_hex(&format!("0x{}", r.to_hex())).unwrap(), r);
+
+        let one = format!("01{}", "00".repeat(31));
+        assert_eq!(Fr::from(1u64).to_hex(), one);
+        assert_eq!(Fr::from_hex(&one).unwrap(), Fr::from(1u64));
+
+        // Wrong length, bad digits and unreduced values are rejected
+        assert!(Fr::from_hex("01").is_err());
+        assert!(Fr::from_hex(&"zz".repeat(32)).is_err());
+        assert!(Fr::from_hex(&"ff".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_hash_to_scalar_distribution() {
+        use crate::babyjubjub::hash_to_scalar;
+        use ark_ff::PrimeField;
+
+        assert_eq!(hash_to_scalar(b"juodzekas"), hash_to_scalar(b"juodzekas"));
+        assert_ne!(hash_to_scalar(b"juodzekas"), hash_to_scalar(b"juodzekaz"));
+        assert_eq!(
+            KeyPair::from_seed(b"seed").pk,
+            KeyPair::from_seed(b"seed").pk
+        );
+
+        assert_low_bits_uniform(
+            (0..10_000u64).map(|i| hash_to_scalar(&i.to_le_bytes()).into_bigint().0[0]),
+        );
+    }
+
+    /// Chi-squared over the low 4 bits of `samples`: 15 degrees of freedom, p = 0.001 cutoff
+    fn assert_low_bits_uniform(samples: impl Iterator<Item = u64>) {
+        const BUCKETS: usize = 16;
+        let mut counts = [0usize; BUCKETS];
+        for sample in samples {
+            counts[(sample % BUCKETS as u64) as usize] += 1;
+        }
+        let expected = counts.iter().sum::<usize>() as f64 / BUCKETS as f64;
+        let chi2: f64 = counts
+            .iter()
+            .map(|&c| (c as f64 - expected).powi(2) / expected)
+            .sum();
+        assert!(chi2 < 37.70, "chi-squared {chi2} too large: {counts:?}");
+    }
+
+    #[test]
+    fn test_hash_to_point() {
+        use crate::babyjubjub::{hash_to_point, PointExt};
+        use ark_ff::PrimeField;
+
+        let p = hash_to_point(b"juodzekas");
+        assert_eq!(p, hash_to_point(b"juodzekas"));
+        assert_ne!(p, hash_to_point(b"juodzekaz"));
+        assert_ne!(hash_to_point(b""), Point::zero());
+        assert!(PointExt::is_on_curve(&p));
+        assert!(p.is_in_correct_subgroup_assuming_on_curve());
+
+        // Round trips through the validated decoder used for untrusted points
+        let mut buf = Vec::new();
+        ark_serialize::CanonicalSerialize::serialize_compressed(&p, &mut buf).unwrap();
+        assert_eq!(Point::from_bytes_validated(&buf).unwrap(), p);
+
+        // x coordinates are uniform too
+        assert_low_bits_uniform((0..1000u64).map(|i| {
+            let point = hash_to_point(&i.to_le_bytes());
+            assert!(point.is_in_correct_subgroup_assuming_on_curve());
+            point.x.into_bigint().0[0]
+        }));
+    }
+
+    #[test]
+    fn test_mock_and_failing_provers() {
+        use crate::prover::{FailingProver, MockProver, Prover};
+        use crate::Error;
+        use std::sync::Arc;
+
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+        let ciphertext = encrypt(&keypair.pk, &Point::generator(), &Fr::rand(&mut rng));
+        let reveal = reveal_card(&keypair.sk, &ciphertext, &keypair.pk);
+
+        let mock: Arc<dyn Prover> = Arc::new(MockProver);
+        let a = mock
+            .prove_reveal(&reveal.public_inputs, reveal.sk_p)
+            .unwrap();
+        let b = mock
+            .prove_reveal(&reveal.public_inputs, reveal.sk_p)
+            .unwrap();
+        assert_eq!(a.pi_a, b.pi_a);
+        assert_eq!(a.pi_c, b.pi_c);
+
+        let failing: Arc<dyn Prover> = Arc::new(FailingProver);
+        assert!(matches!(
+            failing.prove_reveal(&reveal.public_inputs, reveal.sk_p),
+            Err(Error::ProofGenerationFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_key_store_roundtrip() {
+        use crate::keypair::KeyStore;
+
+        let dir = std::env::temp_dir().join(format!("zk-shuffle-keystore-{}", std::process::id()));
+        let store = KeyStore::new(&dir);
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+
+        store
+            .save_game_keys(7, &keypair.sk, &keypair.pk, b"hunter2")
+            .unwrap();
+        assert_eq!(store.list_game_ids().unwrap(), vec![7]);
+
+        let (sk, pk) = store.load_game_keys(7, b"hunter2").unwrap();
+        assert_eq!(sk, keypair.sk);
+        assert_eq!(pk, keypair.pk);
+        assert!(store.load_game_keys(7, b"wrong").is_err());
+
+        // Both file kinds share the sealed layout but not the magic
+        let err = crate::shuffle::ShuffleResult::load_from_file(&store.key_path(7), &[0; 32])
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("not a saved shuffle"), "{err}");
+
+        store.delete_game_keys(7).unwrap();
+        assert!(store.list_game_ids().unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_proof_cache() {
+        use crate::proof::ProofCache;
+        use crate::prover::{MockProver, Prover};
+
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+        let deck: Vec<_> = (1..=4u64)
+            .map(|i| encrypt(&keypair.pk, &base_mult(&Fr::from(i)), &Fr::rand(&mut rng)))
+            .collect();
+        let key = ProofCache::deck_key(&deck).unwrap();
+        assert_ne!(key, ProofCache::deck_key(&deck[1..]).unwrap());
+
+        let dir = std::env::temp_dir().join(format!("zk-shuffle-cache-{}", std::process::id()));
+        let cache = ProofCache::new(&dir, 2);
+        assert!(cache.get(&key).is_none());
+
+        let result = shuffle(&mut rng, &deck, &keypair.pk);
+        let proof = MockProver
+            .prove_shuffle(&result.public_inputs, result.private_inputs.clone())
+            .unwrap();
+        let pi_a = proof.pi_a.clone();
+        cache
+            .store(&key, proof, vec!["1".to_string(), "2".to_string()])
+            .unwrap();
+        let (cached, inputs) = cache.get(&key).unwrap();
+        assert_eq!(cached.pi_a, pi_a);
+        assert_eq!(inputs, vec!["1", "2"]);
+
+        // An entry made with a different proving key is dropped
+        let path = dir.join(format!("{}.json", hex::encode(key)));
+        let mut entry: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        entry["zkey_modified"] = serde_json::json!(1);
+        std::fs::write(&path, entry.to_string()).unwrap();
+        assert!(cache.get(&key).is_none());
+        assert!(!path.exists());
+
+        // Only the newest max_entries are kept
+        for i in 0..3u8 {
+            let proof = MockProver
+                .prove_shuffle(&result.public_inputs, result.private_inputs.clone())
+                .unwrap();
+            cache.store(&[i; 32], proof, vec![]).unwrap();
+        }
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 2);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_shuffle_result_file_roundtrip() {
+        use crate::sealed::storage_key_from_mnemonic;
+        use crate::shuffle::ShuffleResult;
+
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+        let deck: Vec<_> = (1..=4u64)
+            .map(|i| {
+                let m = base_mult(&Fr::from(i));
+                encrypt(&keypair.pk, &m, &Fr::rand(&mut rng))
+            })
+            .collect();
+        let result = shuffle(&mut rng, &deck, &keypair.pk);
+
+        let path =
+            std::env::temp_dir().join(format!("zk-shuffle-result-{}.bin", std::process::id()));
+        let key = storage_key_from_mnemonic("abandon abandon art");
+        result.save_to_file(&path, &key).unwrap();
+
+        // The permutation matrix must not be readable from the file
+        let raw = std::fs::read(&path).unwrap();
+        assert!(!raw.windows(b"UDelta0".len()).any(|w| w == b"UDelta0"));
+
+        let loaded = ShuffleResult::load_from_file(&path, &key).unwrap();
+        assert_eq!(loaded.deck.len(), result.deck.len());
+        for (a, b) in loaded.deck.iter().zip(&result.deck) {
+            assert_eq!((a.c0, a.c1), (b.c0, b.c1));
+        }
+        assert_eq!(
+            loaded.public_inputs.to_ark_public_inputs(),
+            result.public_inputs.to_ark_public_inputs()
+        );
+        assert_eq!(loaded.private_inputs, result.private_inputs);
+
+        let wrong = storage_key_from_mnemonic("abandon abandon zoo");
+        assert!(ShuffleResult::load_from_file(&path, &wrong).is_err());
+        assert_eq!(storage_key_from_mnemonic("  abandon abandon art\n"), key);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_precomputed_shuffle_inputs_file() {
+        use crate::shuffle::PrecomputedShuffleInputs;
+
+        let inputs = PrecomputedShuffleInputs::new();
+        assert_eq!(inputs.card_points.len(), 52);
+        assert_eq!(inputs.card_points[0], Point::generator());
+        assert_eq!(inputs.card_points[51], base_mult(&Fr::from(52u64)));
+
+        // A missing file is computed and written
+        let path =
+            std::env::temp_dir().join(format!("zk-shuffle-precomputed-{}.bin", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        assert_eq!(
+            PrecomputedShuffleInputs::load_from_file(&path).unwrap(),
+            inputs
+        );
+        assert!(path.exists());
+        assert_eq!(
+            PrecomputedShuffleInputs::load_from_file(&path).unwrap(),
+            inputs
+        );
+
+        std::fs::write(&path, b"not a table").unwrap();
+        assert!(PrecomputedShuffleInputs::load_from_file(&path).is_err());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_verification_key_files() {
+        use crate::vk::{self, VerificationKeys};
+        use ark_bn254::{Bn254, G1Affine, G2Affine};
+        use ark_groth16::VerifyingKey;
+
+        // Random points stand in for the circuit keys, which CI doesn't have
+        let mut rng = ark_std::test_rng();
+        let random_vk = |rng: &mut _| VerifyingKey::<Bn254> {
+            alpha_g1: G1Affine::rand(rng),
+            beta_g2: G2Affine::rand(rng),
+            gamma_g2: G2Affine::rand(rng),
+            delta_g2: G2Affine::rand(rng),
+            gamma_abc_g1: (0..3).map(|_| G1Affine::rand(rng)).collect(),
+        };
+        let shuffle_vk = random_vk(&mut rng);
+        let reveal_vk = random_vk(&mut rng);
+
+        let bytes = vk::to_checked_bytes(&shuffle_vk).unwrap();
+        assert_eq!(vk::from_checked_bytes(&bytes).unwrap(), shuffle_vk);
+        let mut tampered = bytes.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(vk::from_checked_bytes(&tampered).is_err());
+        assert!(vk::from_checked_bytes(&bytes[..16]).is_err());
+
+        let dir = std::env::temp_dir().join(format!("zk-shuffle-vk-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        vk::write_checked_file(&shuffle_vk, &dir.join(vk::SHUFFLE_VK_FILE)).unwrap();
+        vk::write_checked_file(&reveal_vk, &dir.join(vk::REVEAL_VK_FILE)).unwrap();
+        let keys = VerificationKeys::load(Some(&dir)).unwrap();
+        assert_eq!(keys.shuffle, shuffle_vk);
+        assert_eq!(keys.reveal, reveal_vk);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(VerificationKeys::load(None).is_ok(), vk::EMBEDDED);
+    }
+
+    #[test]
+    fn test_card_map_lookup() {
+        use crate::CardMap;
+
+        let g = Point::generator();
+        let scalars: Vec<Fr> = (1..=52u64).map(Fr::from).collect();
+        let card_map = CardMap::from_params(&g, &scalars);
+
+        for (index, point) in card_map.all_cards() {
+            assert_eq!(card_map.lookup(&point), Some(index));
+        }
+        let not_a_card = scalar_mult(&g, &Fr::from(53u64));
+        assert_eq!(card_map.lookup(&not_a_card), None);
+
+        // Round trip through encryption and two-party reveal
+        let mut rng = ark_std::test_rng();
+        let k1 = KeyPair::generate(&mut rng);
+        let k2 = KeyPair::generate(&mut rng);
+        let pk = (k1.pk.into_group() + k2.pk.into_group()).into_affine();
+        let (_, card) = card_map.all_cards()[17];
+        let c = encrypt(&pk, &card, &Fr::rand(&mut rng));
+        let partials = [
+            reveal_card(&k1.sk, &c, &k1.pk).partial_decryption,
+            reveal_card(&k2.sk, &c, &k2.pk).partial_decryption,
+        ];
+        let revealed = c.reveal(&combine_partials(&partials));
+        assert_eq!(lookup_card_value(&revealed, &card_map), Some(17));
+
+        // A single partial is not enough to decrypt
+        let half = c.reveal(&combine_partials(&partials[..1]));
+        assert_eq!(lookup_card_value(&half, &card_map), None);
+        assert_eq!(combine_partials(&[]), Point::zero());
+    }
+
+    #[test]
+    fn test_reveal_audit_trail() {
+        use crate::decrypt::reveal_card_with_audit;
+        use crate::CardMap;
+
+        let scalars: Vec<Fr> = (1..=52u64).map(Fr::from).collect();
+        let card_map = CardMap::from_params(&Point::generator(), &scalars);
+        let mut rng = ark_std::test_rng();
+        let k1 = KeyPair::generate(&mut rng);
+        let k2 = KeyPair::generate(&mut rng);
+        let pk = (k1.pk.into_group() + k2.pk.into_group()).into_affine();
+        let (_, card) = card_map.all_cards()[30];
+        let c = encrypt(&pk, &card, &Fr::rand(&mut rng));
+        let theirs = reveal_card(&k2.sk, &c, &k2.pk).partial_decryption;
+
+        let (reveal, audit) = reveal_card_with_audit(&k1.sk, &c, &k1.pk, Some(theirs), &card_map);
+        assert_eq!(audit.my_partial, reveal.partial_decryption);
+        assert_eq!(audit.revealed_point, card);
+        assert_eq!(audit.matched_card, Some(30));
+        assert!(audit.verify_combination());
+
+        // Half a reveal is consistent, it just isn't a card
+        let (_, half) = reveal_card_with_audit(&k1.sk, &c, &k1.pk, None, &card_map);
+        assert_eq!(half.their_partial, Point::zero());
+        assert_eq!(half.matched_card, None);
+        assert!(half.verify_combination());
+
+        let mut tampered = audit.clone();
+        tampered.combined_partial = theirs;
+        assert!(!tampered.verify_combination());
+        let mut tampered = audit;
+        tampered.revealed_point = card_map.all_cards()[31].1;
+        assert!(!tampered.verify_combination());
+    }
+
+    #[test]
+    fn test_card_deck_values() {
+        use crate::shuffle::PrecomputedShuffleInputs;
+        use crate::CardDeck;
+
+        let mut rng = ark_std::test_rng();
+        let keypair = KeyPair::generate(&mut rng);
+        for num_decks in [1u8, 2] {
+            let deck = CardDeck::new(num_decks).unwrap();
+            assert_eq!(deck.deck_size, 52 * num_decks as usize);
+            assert_eq!(deck.points().len(), deck.deck_size);
+            assert_eq!(
+                deck.points()[..52],
+                PrecomputedShuffleInputs::new().card_points[..]
+            );
+            let distinct: std::collections::HashSet<_> =
+                deck.points().iter().map(|p| (p.x, p.y)).collect();
+            assert_eq!(distinct.len(), deck.deck_size);
+
+            let encrypted = deck.encrypt_all(&keypair.pk, &mut rng);
+            assert_eq!(encrypted.len(), deck.deck_size);
+            for (i, c) in encrypted.iter().enumerate() {
+                let point = decrypt(&keypair.sk, c);
+                assert_eq!(deck.lookup_point_value(&point), Some((i % 52) as u8));
+            }
+            let past_the_shoe = base_mult(&Fr::from(deck.deck_size as u64 + 1));
+            assert_eq!(deck.lookup_point_value(&past_the_shoe), None);
+        }
+        assert!(matches!(
+            CardDeck::new(0),
+            Err(crate::Error::InvalidInput(_))
+        ));
+
+        // Past 255 cards the values still wrap per deck
+        let shoe = CardDeck::new(6).unwrap();
+        assert_eq!(shoe.lookup_point_value(&shoe.points()[300]), Some(300 % 52));
+    }
 }
@@ -1,13 +1,20 @@
-use crate::babyjubjub::Fq;
+use crate::babyjubjub::{field_to_hex, Fq, Point, PointExt};
+use crate::elgamal::Ciphertext;
+use crate::error::Error;
+use crate::shuffle::{DECK_SIZE, PARTIAL_SHUFFLE_CARDS};
 use ark_bn254::{Bn254, Fr as Bn254Fr};
 use ark_circom::{CircomBuilder, CircomConfig, WitnessCalculator};
+use ark_ec::AffineRepr;
 use ark_ff::{BigInteger, PrimeField};
 use ark_groth16::{Groth16, Proof, ProvingKey, VerifyingKey};
 pub use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_std::rand::{CryptoRng, Rng};
 use memmap2::Mmap;
+use num_bigint::BigUint;
+use sha2::{Digest, Sha256};
 use std::fs::File;
+use std::path::PathBuf;
 
 pub fn load_or_generate_keys<R: Rng + CryptoRng>(
     r1cs_path: &str,
@@ -140,14 +147,21 @@ pub unsafe fn load_keys_unsafe_mmap(
     Ok((pk, vk))
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct ShufflePublicInputs {
+    #[serde(with = "crate::serde_ark")]
     pub pk: [Bn254Fr; 2],
+    #[serde(with = "crate::serde_ark")]
     pub ux0: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
     pub ux1: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
     pub vx0: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
     pub vx1: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
     pub s_u: [Bn254Fr; 2],
+    #[serde(with = "crate::serde_ark")]
     pub s_v: [Bn254Fr; 2],
 }
 
@@ -173,6 +187,55 @@ impl ShufflePublicInputs {
         }
     }
 
+    /// Cheap consistency checks to run before spending a minute on a proof: the four
+    /// vectors hold one entry per card, `pk` is a point in the prime-order subgroup,
+    /// and no vector repeats a value in consecutive slots, which a real shuffle never
+    /// produces. Field elements are reduced on construction, so their range needs no check.
+    pub fn validate(&self) -> Result<(), Error> {
+        let deck_size = DECK_SIZE as usize;
+        for (name, values) in [
+            ("ux0", &self.ux0),
+            ("ux1", &self.ux1),
+            ("vx0", &self.vx0),
+            ("vx1", &self.vx1),
+        ] {
+            if values.len() != deck_size {
+                return Err(Error::InvalidInput(format!(
+                    "{name} has {} entries, expected {deck_size}",
+                    values.len()
+                )));
+            }
+            if let Some(i) = values.windows(2).position(|w| w[0] == w[1]) {
+                return Err(Error::InvalidInput(format!(
+                    "{name}[{i}] and {name}[{}] are identical",
+                    i + 1
+                )));
+            }
+        }
+
+        let convert = |f: &Bn254Fr| Fq::from_le_bytes_mod_order(&f.into_bigint().to_bytes_le());
+        let pk = Point::new_unchecked(convert(&self.pk[0]), convert(&self.pk[1]));
+        if !PointExt::is_on_curve(&pk) {
+            return Err(Error::Curve(
+                "pk is not on the BabyJubJub curve".to_string(),
+            ));
+        }
+        if !pk.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(Error::Curve(
+                "pk is not in the prime-order subgroup".to_string(),
+            ));
+        }
+
+        let expected = 1 + 2 + deck_size * 4 + 2 + 2;
+        let actual = self.to_ark_public_inputs().len();
+        if actual != expected {
+            return Err(Error::InvalidInput(format!(
+                "{actual} public inputs, expected {expected}"
+            )));
+        }
+        Ok(())
+    }
+
     pub fn to_ark_public_inputs(&self) -> Vec<Bn254Fr> {
         let mut inputs = Vec::new();
         // Circuit output comes first (dummy_output = pk[0] * pk[1])
@@ -214,6 +277,191 @@ impl ShufflePublicInputs {
             ("s_v".to_string(), self.s_v.to_vec()),
         ]
     }
+
+    /// Logs every input signal as little-endian field hex at debug level.
+    pub fn debug_print(&self) {
+        for (name, values) in self.get_input_mapping() {
+            for (i, value) in values.iter().enumerate() {
+                log::debug!("{name}[{i}] = {}", field_to_hex(value));
+            }
+        }
+    }
+}
+
+/// Public inputs of the partial re-encryption circuit (`circuits/partial_reencrypt`):
+/// the listed deck positions and their cards before (`u*`) and after (`v*`).
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct PartialShufflePublicInputs {
+    #[serde(with = "crate::serde_ark")]
+    pub pk: [Bn254Fr; 2],
+    #[serde(with = "crate::serde_ark")]
+    pub indices: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
+    pub ux0: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
+    pub ux1: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
+    pub vx0: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
+    pub vx1: Vec<Bn254Fr>,
+    #[serde(with = "crate::serde_ark")]
+    pub s_u: [Bn254Fr; 2],
+    #[serde(with = "crate::serde_ark")]
+    pub s_v: [Bn254Fr; 2],
+}
+
+impl PartialShufflePublicInputs {
+    /// Same order as `ShufflePublicInputs`, with `indices` after `pk`.
+    pub fn to_ark_public_inputs(&self) -> Vec<Bn254Fr> {
+        let mut inputs = vec![self.pk[0] * self.pk[1]];
+        inputs.extend(&self.pk);
+        inputs.extend(&self.indices);
+        inputs.extend(&self.ux0);
+        inputs.extend(&self.ux1);
+        inputs.extend(&self.vx0);
+        inputs.extend(&self.vx1);
+        inputs.extend(&self.s_u);
+        inputs.extend(&self.s_v);
+        inputs
+    }
+
+    pub fn get_input_mapping(&self) -> Vec<(String, Vec<Bn254Fr>)> {
+        vec![
+            ("pk".to_string(), self.pk.to_vec()),
+            ("indices".to_string(), self.indices.clone()),
+            ("UX0".to_string(), self.ux0.clone()),
+            ("UX1".to_string(), self.ux1.clone()),
+            ("VX0".to_string(), self.vx0.clone()),
+            ("VX1".to_string(), self.vx1.clone()),
+            ("s_u".to_string(), self.s_u.to_vec()),
+            ("s_v".to_string(), self.s_v.to_vec()),
+        ]
+    }
+}
+
+/// Where the shuffle proving key is looked up, relative to the working directory
+const SHUFFLE_ZKEY_PATHS: [&str; 3] = [
+    "circuits/circuit-artifacts/zkey/encrypt.zkey",
+    "../../circuits/circuit-artifacts/zkey/encrypt.zkey",
+    "../../../circuits/circuit-artifacts/zkey/encrypt.zkey",
+];
+
+fn shuffle_zkey_path() -> Option<&'static str> {
+    SHUFFLE_ZKEY_PATHS
+        .into_iter()
+        .find(|p| std::path::Path::new(p).exists())
+}
+
+/// Modification time of the shuffle proving key in seconds, if it can be found
+fn shuffle_zkey_modified() -> Option<u64> {
+    let modified = std::fs::metadata(shuffle_zkey_path()?)
+        .ok()?
+        .modified()
+        .ok()?;
+    Some(
+        modified
+            .duration_since(std::time::UNIX_EPOCH)
+            .ok()?
+            .as_secs(),
+    )
+}
+
+/// Shuffle proofs on disk, so one made ahead of time (e.g. while another game is in
+/// play) is ready when its deck is submitted. Entries are `<key_hex>.json`, keyed by
+/// [`ProofCache::deck_key`], and are dropped once the proving key's mtime changes.
+/// Only the newest `max_entries` are kept.
+pub struct ProofCache {
+    dir: PathBuf,
+    max_entries: usize,
+}
+
+#[derive(serde::Deserialize, serde::Serialize)]
+struct CachedProof {
+    zkey_modified: Option<u64>,
+    proof: RapidsnarkProof,
+    inputs: Vec<String>,
+}
+
+impl ProofCache {
+    pub fn new(dir: impl Into<PathBuf>, max_entries: usize) -> Self {
+        ProofCache {
+            dir: dir.into(),
+            max_entries,
+        }
+    }
+
+    /// SHA-256 over the compressed ciphertexts in order, the same deck hash the contract stores.
+    pub fn deck_key(deck: &[Ciphertext]) -> Result<[u8; 32], Error> {
+        let mut hasher = Sha256::new();
+        let mut buf = Vec::new();
+        for ct in deck {
+            buf.clear();
+            ct.c0
+                .serialize_compressed(&mut buf)
+                .and_then(|_| ct.c1.serialize_compressed(&mut buf))
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+            hasher.update(&buf);
+        }
+        Ok(hasher.finalize().into())
+    }
+
+    fn path(&self, key: &[u8; 32]) -> PathBuf {
+        self.dir.join(format!("{}.json", hex::encode(key)))
+    }
+
+    /// The proof and public inputs stored under `key`, unless missing, unreadable or
+    /// made with a proving key that has since changed (the entry is then removed).
+    pub fn get(&self, key: &[u8; 32]) -> Option<(RapidsnarkProof, Vec<String>)> {
+        let path = self.path(key);
+        let entry: CachedProof = serde_json::from_slice(&std::fs::read(&path).ok()?).ok()?;
+        if entry.zkey_modified != shuffle_zkey_modified() {
+            log::info!(
+                "Dropping cached proof {}: proving key changed",
+                path.display()
+            );
+            std::fs::remove_file(&path).ok();
+            return None;
+        }
+        Some((entry.proof, entry.inputs))
+    }
+
+    pub fn store(
+        &self,
+        key: &[u8; 32],
+        proof: RapidsnarkProof,
+        inputs: Vec<String>,
+    ) -> Result<(), Error> {
+        std::fs::create_dir_all(&self.dir)?;
+        let entry = CachedProof {
+            zkey_modified: shuffle_zkey_modified(),
+            proof,
+            inputs,
+        };
+        let json = serde_json::to_vec(&entry).map_err(|e| Error::Serialization(e.to_string()))?;
+        std::fs::write(self.path(key), json)?;
+        self.evict()
+    }
+
+    /// Removes the oldest entries beyond `max_entries`.
+    fn evict(&self) -> Result<(), Error> {
+        let mut entries = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let modified = std::fs::metadata(&path)?.modified()?;
+                entries.push((modified, path));
+            }
+        }
+        if entries.len() <= self.max_entries {
+            return Ok(());
+        }
+        entries.sort();
+        let excess = entries.len() - self.max_entries;
+        for (_, path) in entries.into_iter().take(excess) {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
 }
 
 /// Rapidsnark proof structure matching snarkjs output
@@ -228,6 +476,117 @@ pub struct RapidsnarkProof {
     pub curve: Option<String>,
 }
 
+/// Groth16 proof over BN254 in snarkjs' decimal-string layout. Points are
+/// projective with `z` last; snarkjs always emits them normalized (`z = 1`).
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct GrothProof {
+    pub pi_a: [String; 3],
+    pub pi_b: [[String; 2]; 3],
+    pub pi_c: [String; 3],
+}
+
+impl GrothProof {
+    pub fn to_ark_proof(&self) -> Result<Proof<Bn254>, Error> {
+        use ark_bn254::{Fq2, G1Affine, G2Affine};
+
+        let a = G1Affine::new_unchecked(
+            parse_base_field(&self.pi_a[0])?,
+            parse_base_field(&self.pi_a[1])?,
+        );
+        let b = G2Affine::new_unchecked(
+            Fq2::new(
+                parse_base_field(&self.pi_b[0][0])?,
+                parse_base_field(&self.pi_b[0][1])?,
+            ),
+            Fq2::new(
+                parse_base_field(&self.pi_b[1][0])?,
+                parse_base_field(&self.pi_b[1][1])?,
+            ),
+        );
+        let c = G1Affine::new_unchecked(
+            parse_base_field(&self.pi_c[0])?,
+            parse_base_field(&self.pi_c[1])?,
+        );
+        Ok(Proof { a, b, c })
+    }
+
+    /// Standard 256-byte encoding used by EVM-style verifiers (EIP-197): each
+    /// coordinate as 32 big-endian bytes, in the order
+    /// `a.x, a.y, b.x.c1, b.x.c0, b.y.c1, b.y.c0, c.x, c.y`.
+    pub fn to_bytes(&self) -> Result<[u8; 256], Error> {
+        let proof = self.to_ark_proof()?;
+        let coordinates = [
+            proof.a.x,
+            proof.a.y,
+            proof.b.x.c1,
+            proof.b.x.c0,
+            proof.b.y.c1,
+            proof.b.y.c0,
+            proof.c.x,
+            proof.c.y,
+        ];
+        let mut bytes = [0u8; 256];
+        for (chunk, coordinate) in bytes.chunks_exact_mut(32).zip(coordinates) {
+            chunk.copy_from_slice(&coordinate.into_bigint().to_bytes_be());
+        }
+        Ok(bytes)
+    }
+}
+
+impl From<Proof<Bn254>> for GrothProof {
+    fn from(proof: Proof<Bn254>) -> Self {
+        let one = || "1".to_string();
+        Self {
+            pi_a: [proof.a.x.to_string(), proof.a.y.to_string(), one()],
+            pi_b: [
+                [proof.b.x.c0.to_string(), proof.b.x.c1.to_string()],
+                [proof.b.y.c0.to_string(), proof.b.y.c1.to_string()],
+                [one(), "0".to_string()],
+            ],
+            pi_c: [proof.c.x.to_string(), proof.c.y.to_string(), one()],
+        }
+    }
+}
+
+/// Covers both `ShuffleProof` and `RevealProof`, which are aliases of `RapidsnarkProof`.
+impl From<RapidsnarkProof> for GrothProof {
+    fn from(proof: RapidsnarkProof) -> Self {
+        Self {
+            pi_a: proof.pi_a,
+            pi_b: proof.pi_b,
+            pi_c: proof.pi_c,
+        }
+    }
+}
+
+impl From<&RapidsnarkProof> for GrothProof {
+    fn from(proof: &RapidsnarkProof) -> Self {
+        Self {
+            pi_a: proof.pi_a.clone(),
+            pi_b: proof.pi_b.clone(),
+            pi_c: proof.pi_c.clone(),
+        }
+    }
+}
+
+impl From<&GrothProof> for GrothProof {
+    fn from(proof: &GrothProof) -> Self {
+        proof.clone()
+    }
+}
+
+/// Parses a decimal BN254 base field element, rejecting values not below the modulus.
+fn parse_base_field(s: &str) -> Result<ark_bn254::Fq, Error> {
+    let value = BigUint::parse_bytes(s.as_bytes(), 10)
+        .ok_or_else(|| Error::Serialization(format!("invalid decimal field element: {s:?}")))?;
+    if value >= BigUint::from(ark_bn254::Fq::MODULUS) {
+        return Err(Error::Serialization(format!(
+            "field element {s} is not below the BN254 base field modulus"
+        )));
+    }
+    Ok(ark_bn254::Fq::from_le_bytes_mod_order(&value.to_bytes_le()))
+}
+
 /// Convert witness Vec<BigInt> to .wtns binary format for rapidsnark
 fn witness_bigints_to_wtns(
     witness: &[num_bigint::BigInt],
@@ -348,16 +707,8 @@ pub fn generate_shuffle_proof_rapidsnark(
     );
 
     // Use rapidsnark for FAST proof generation
-    let possible_zkey_paths = [
-        "circuits/circuit-artifacts/zkey/encrypt.zkey",
-        "../../circuits/circuit-artifacts/zkey/encrypt.zkey",
-        "../../../circuits/circuit-artifacts/zkey/encrypt.zkey",
-    ];
-
-    let zkey_path = possible_zkey_paths
-        .iter()
-        .find(|p| std::path::Path::new(p).exists())
-        .ok_or("Could not find encrypt.zkey in any expected location")?;
+    let zkey_path =
+        shuffle_zkey_path().ok_or("Could not find encrypt.zkey in any expected location")?;
 
     log::info!("Using zkey: {zkey_path}");
     let rapidsnark_result =
@@ -371,6 +722,37 @@ pub fn generate_shuffle_proof_rapidsnark(
     Ok(proof)
 }
 
+/// Decimal string encoding of public inputs, as snarkjs and the contract expect them.
+pub struct ProofInputSerializer;
+
+impl ProofInputSerializer {
+    pub fn field_to_decimal_string(f: &Fq) -> String {
+        BigUint::from_bytes_le(&f.into_bigint().to_bytes_le()).to_string()
+    }
+
+    /// Parses a canonical decimal field element; values at or above the modulus are rejected.
+    pub fn decimal_string_to_field(s: &str) -> Result<Fq, Error> {
+        let value = BigUint::parse_bytes(s.as_bytes(), 10)
+            .ok_or_else(|| Error::InvalidInput(format!("not a decimal integer: {s:?}")))?;
+        if value >= BigUint::from_bytes_le(&Fq::MODULUS.to_bytes_le()) {
+            return Err(Error::InvalidInput(format!(
+                "{s} is not below the field modulus"
+            )));
+        }
+        Ok(Fq::from_le_bytes_mod_order(&value.to_bytes_le()))
+    }
+
+    pub fn fields_to_strings(fields: &[Fq]) -> Vec<String> {
+        fields.iter().map(Self::field_to_decimal_string).collect()
+    }
+
+    pub fn strings_to_fields(strs: &[String]) -> Result<Vec<Fq>, Error> {
+        strs.iter()
+            .map(|s| Self::decimal_string_to_field(s))
+            .collect()
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RevealPublicInputs {
     pub y: [Bn254Fr; 4],
@@ -486,81 +868,150 @@ pub fn generate_reveal_proof_rapidsnark(
     Ok(proof)
 }
 
-/// Verifies a rapidsnark proof using snarkjs verification key
+/// Generates a partial re-encryption proof (`circuits/partial_reencrypt`) using
+/// rapidsnark, from the inputs of a [`crate::shuffle::partial_shuffle`] result.
+pub fn generate_partial_shuffle_proof_rapidsnark(
+    public_inputs: &PartialShufflePublicInputs,
+    private_inputs: Vec<(String, Vec<Bn254Fr>)>,
+) -> Result<RapidsnarkProof, Box<dyn std::error::Error>> {
+    if public_inputs.indices.len() != PARTIAL_SHUFFLE_CARDS {
+        return Err(format!(
+            "partial re-encryption proofs cover {PARTIAL_SHUFFLE_CARDS} cards, got {}",
+            public_inputs.indices.len()
+        )
+        .into());
+    }
+
+    log::info!("Generating partial re-encryption witness using WASM calculator");
+
+    let possible_paths = [
+        "circuits/circuit-artifacts/wasm/partial_reencrypt.wasm",
+        "../../circuits/circuit-artifacts/wasm/partial_reencrypt.wasm",
+        "../../../circuits/circuit-artifacts/wasm/partial_reencrypt.wasm",
+    ];
+
+    let wasm_path = possible_paths
+        .iter()
+        .find(|p| std::path::Path::new(p).exists())
+        .ok_or("Could not find partial_reencrypt.wasm in any expected location")?;
+
+    let mut store = wasmer::Store::default();
+    let mut calculator = WitnessCalculator::new(&mut store, wasm_path)?;
+
+    let convert_to_bigint = |f: &Bn254Fr| -> num_bigint::BigInt {
+        num_bigint::BigInt::from_bytes_le(num_bigint::Sign::Plus, &f.into_bigint().to_bytes_le())
+    };
+
+    let inputs: Vec<(String, Vec<num_bigint::BigInt>)> = public_inputs
+        .get_input_mapping()
+        .into_iter()
+        .chain(private_inputs)
+        .map(|(name, vals)| (name, vals.iter().map(convert_to_bigint).collect()))
+        .collect();
+
+    let witness_bigints = calculator.calculate_witness(&mut store, inputs, false)?;
+    let wtns_bytes = witness_bigints_to_wtns(&witness_bigints)?;
+    log::info!(
+        "Partial re-encryption witness serialized ({} bytes), using rapidsnark for proof",
+        wtns_bytes.len()
+    );
+
+    let possible_zkey_paths = [
+        "circuits/circuit-artifacts/zkey/partial_reencrypt.zkey",
+        "../../circuits/circuit-artifacts/zkey/partial_reencrypt.zkey",
+        "../../../circuits/circuit-artifacts/zkey/partial_reencrypt.zkey",
+    ];
+
+    let zkey_path = possible_zkey_paths
+        .iter()
+        .find(|p| std::path::Path::new(p).exists())
+        .ok_or("Could not find partial_reencrypt.zkey in any expected location")?;
+
+    let rapidsnark_result =
+        rust_rapidsnark::groth16_prover_zkey_file_wrapper(zkey_path, wtns_bytes)
+            .map_err(|e| format!("Rapidsnark partial re-encryption proof failed: {e}"))?;
+
+    let proof: RapidsnarkProof = serde_json::from_str(&rapidsnark_result.proof)?;
+    Ok(proof)
+}
+
+/// Verifies a shuffle proof using a snarkjs verification key. Accepts rapidsnark
+/// output or any [`GrothProof`].
 pub fn verify_shuffle_proof_rapidsnark(
     vkey_path: &str,
-    proof: &RapidsnarkProof,
+    proof: impl Into<GrothProof>,
     public_inputs: &ShufflePublicInputs,
 ) -> Result<bool, Box<dyn std::error::Error>> {
-    // Load verification key from JSON
-    let vkey_json = std::fs::read_to_string(vkey_path)?;
-
-    // Convert public inputs to ark format
-    let pub_inputs_ark = public_inputs.to_ark_public_inputs();
-
-    // Parse and verify using arkworks as temporary solution
-    // This maintains compatibility while we transition fully to rapidsnark
-    let ark_proof = parse_rapidsnark_proof(proof)?;
-    let vkey_data: serde_json::Value = serde_json::from_str(&vkey_json)?;
-    let ark_vk = parse_snarkjs_vkey(&vkey_data)?;
-
-    Ok(Groth16::<Bn254>::verify(&ark_vk, &pub_inputs_ark, &ark_proof).unwrap_or(false))
+    let ark_vk = vk_from_snarkjs_json(vkey_path)?;
+    Ok(verify_shuffle_proof(&ark_vk, proof, public_inputs)?)
 }
 
-/// Verifies a rapidsnark reveal proof using snarkjs verification key
+/// Verifies a reveal proof using a snarkjs verification key. Accepts rapidsnark
+/// output or any [`GrothProof`].
 pub fn verify_reveal_proof_rapidsnark(
     vkey_path: &str,
-    proof: &RapidsnarkProof,
+    proof: impl Into<GrothProof>,
     public_inputs: &RevealPublicInputs,
 ) -> Result<bool, Box<dyn std::error::Error>> {
-    // Load verification key from JSON
-    let vkey_json = std::fs::read_to_string(vkey_path)?;
-
-    // Convert public inputs to ark format
-    let pub_inputs_ark = public_inputs.to_ark_public_inputs();
+    let ark_vk = vk_from_snarkjs_json(vkey_path)?;
+    Ok(verify_reveal_proof(&ark_vk, proof, public_inputs)?)
+}
 
-    // Parse and verify using arkworks as temporary solution
-    let ark_proof = parse_rapidsnark_proof(proof)?;
-    let vkey_data: serde_json::Value = serde_json::from_str(&vkey_json)?;
-    let ark_vk = parse_snarkjs_vkey(&vkey_data)?;
+/// Verifies a partial re-encryption proof using a snarkjs verification key. Accepts
+/// rapidsnark output or any [`GrothProof`].
+pub fn verify_partial_shuffle_proof_rapidsnark(
+    vkey_path: &str,
+    proof: impl Into<GrothProof>,
+    public_inputs: &PartialShufflePublicInputs,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let ark_vk = vk_from_snarkjs_json(vkey_path)?;
+    Ok(verify_partial_shuffle_proof(&ark_vk, proof, public_inputs)?)
+}
 
-    Ok(Groth16::<Bn254>::verify(&ark_vk, &pub_inputs_ark, &ark_proof).unwrap_or(false))
+/// Verifies a shuffle proof against an already loaded key (see [`crate::vk`]).
+pub fn verify_shuffle_proof(
+    vk: &VerifyingKey<Bn254>,
+    proof: impl Into<GrothProof>,
+    public_inputs: &ShufflePublicInputs,
+) -> Result<bool, Error> {
+    let ark_proof = proof.into().to_ark_proof()?;
+    let pub_inputs_ark = public_inputs.to_ark_public_inputs();
+    Ok(Groth16::<Bn254>::verify(vk, &pub_inputs_ark, &ark_proof).unwrap_or(false))
 }
 
-/// Parse rapidsnark/snarkjs proof JSON to arkworks format
-fn parse_rapidsnark_proof(
-    proof: &RapidsnarkProof,
-) -> Result<Proof<Bn254>, Box<dyn std::error::Error>> {
-    use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+/// Verifies a reveal proof against an already loaded key (see [`crate::vk`]).
+pub fn verify_reveal_proof(
+    vk: &VerifyingKey<Bn254>,
+    proof: impl Into<GrothProof>,
+    public_inputs: &RevealPublicInputs,
+) -> Result<bool, Error> {
+    let ark_proof = proof.into().to_ark_proof()?;
+    let pub_inputs_ark = public_inputs.to_ark_public_inputs();
+    Ok(Groth16::<Bn254>::verify(vk, &pub_inputs_ark, &ark_proof).unwrap_or(false))
+}
 
-    // Helper to parse decimal string to field element
-    let parse_fq = |s: &str| -> Result<Fq, Box<dyn std::error::Error>> {
-        let bigint = num_bigint::BigInt::parse_bytes(s.as_bytes(), 10)
-            .ok_or("Failed to parse decimal string")?;
-        let bytes = bigint.to_bytes_le().1; // Get little-endian bytes
-        Ok(Fq::from_le_bytes_mod_order(&bytes))
-    };
+/// Verifies a partial re-encryption proof against an already loaded key. Inputs for
+/// any other number of cards than [`PARTIAL_SHUFFLE_CARDS`] never verify.
+pub fn verify_partial_shuffle_proof(
+    vk: &VerifyingKey<Bn254>,
+    proof: impl Into<GrothProof>,
+    public_inputs: &PartialShufflePublicInputs,
+) -> Result<bool, Error> {
+    if public_inputs.indices.len() != PARTIAL_SHUFFLE_CARDS {
+        return Ok(false);
+    }
+    let ark_proof = proof.into().to_ark_proof()?;
+    let pub_inputs_ark = public_inputs.to_ark_public_inputs();
+    Ok(Groth16::<Bn254>::verify(vk, &pub_inputs_ark, &ark_proof).unwrap_or(false))
+}
 
-    // Parse pi_a (G1 point)
-    let a_x = parse_fq(&proof.pi_a[0])?;
-    let a_y = parse_fq(&proof.pi_a[1])?;
-    let a = G1Affine::new_unchecked(a_x, a_y);
-
-    // Parse pi_b (G2 point)
-    let b_x0 = parse_fq(&proof.pi_b[0][0])?;
-    let b_x1 = parse_fq(&proof.pi_b[0][1])?;
-    let b_y0 = parse_fq(&proof.pi_b[1][0])?;
-    let b_y1 = parse_fq(&proof.pi_b[1][1])?;
-    let b_x = Fq2::new(b_x0, b_x1);
-    let b_y = Fq2::new(b_y0, b_y1);
-    let b = G2Affine::new_unchecked(b_x, b_y);
-
-    // Parse pi_c (G1 point)
-    let c_x = parse_fq(&proof.pi_c[0])?;
-    let c_y = parse_fq(&proof.pi_c[1])?;
-    let c = G1Affine::new_unchecked(c_x, c_y);
-
-    Ok(Proof { a, b, c })
+/// Load a snarkjs verification key JSON file (e.g. `shuffle_encrypt_vkey.json`).
+pub fn vk_from_snarkjs_json(
+    vkey_path: &str,
+) -> Result<VerifyingKey<Bn254>, Box<dyn std::error::Error>> {
+    let vkey_json = std::fs::read_to_string(vkey_path)?;
+    let vkey_data: serde_json::Value = serde_json::from_str(&vkey_json)?;
+    parse_snarkjs_vkey(&vkey_data)
 }
 
 /// Parse snarkjs verification key JSON to arkworks format
@@ -0,0 +1,134 @@
+use crate::error::Error;
+use crate::proof::{
+    generate_reveal_proof_rapidsnark, generate_shuffle_proof_rapidsnark, RapidsnarkProof,
+    RevealPublicInputs, ShufflePublicInputs,
+};
+use ark_bn254::Fr as Bn254Fr;
+use ark_ff::{BigInteger, PrimeField};
+use blake2::{Blake2b512, Digest};
+
+/// Named private witness inputs for the shuffle circuit (see `ShuffleResult::private_inputs`).
+pub type ShufflePrivateInputs = Vec<(String, Vec<Bn254Fr>)>;
+pub type ShuffleProof = RapidsnarkProof;
+pub type RevealProof = RapidsnarkProof;
+
+/// Proof generation backend. Lets clients swap rapidsnark out in tests.
+pub trait Prover: Send + Sync {
+    fn prove_shuffle(
+        &self,
+        public_inputs: &ShufflePublicInputs,
+        private_inputs: ShufflePrivateInputs,
+    ) -> Result<ShuffleProof, Error>;
+
+    fn prove_reveal(
+        &self,
+        public_inputs: &RevealPublicInputs,
+        sk: Bn254Fr,
+    ) -> Result<RevealProof, Error>;
+}
+
+/// Production prover: WASM witness calculation + rapidsnark.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RapidsnarkProver;
+
+impl Prover for RapidsnarkProver {
+    fn prove_shuffle(
+        &self,
+        public_inputs: &ShufflePublicInputs,
+        private_inputs: ShufflePrivateInputs,
+    ) -> Result<ShuffleProof, Error> {
+        generate_shuffle_proof_rapidsnark(public_inputs, private_inputs)
+            .map_err(|e| Error::ProofGenerationFailed(e.to_string()))
+    }
+
+    fn prove_reveal(
+        &self,
+        public_inputs: &RevealPublicInputs,
+        sk: Bn254Fr,
+    ) -> Result<RevealProof, Error> {
+        generate_reveal_proof_rapidsnark(public_inputs, sk)
+            .map_err(|e| Error::ProofGenerationFailed(e.to_string()))
+    }
+}
+
+/// Returns a dummy proof derived from a hash of the public inputs.
+/// Only useful against a verifier that accepts everything (e.g. mocked chain queries).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct MockProver;
+
+impl MockProver {
+    fn dummy_proof(domain: &[u8], inputs: &[Bn254Fr]) -> RapidsnarkProof {
+        let mut hasher = Blake2b512::new();
+        hasher.update(domain);
+        for input in inputs {
+            hasher.update(input.into_bigint().to_bytes_le());
+        }
+        let digest = hasher.finalize();
+        let elem = |i: usize| {
+            let chunk = &digest[(i * 8) % 64..(i * 8) % 64 + 8];
+            Bn254Fr::from_le_bytes_mod_order(chunk).to_string()
+        };
+
+        RapidsnarkProof {
+            pi_a: [elem(0), elem(1), "1".to_string()],
+            pi_b: [
+                [elem(2), elem(3)],
+                [elem(4), elem(5)],
+                ["1".to_string(), "0".to_string()],
+            ],
+            pi_c: [elem(6), elem(7), "1".to_string()],
+            protocol: Some("groth16".to_string()),
+            curve: Some("bn128".to_string()),
+        }
+    }
+}
+
+impl Prover for MockProver {
+    fn prove_shuffle(
+        &self,
+        public_inputs: &ShufflePublicInputs,
+        _private_inputs: ShufflePrivateInputs,
+    ) -> Result<ShuffleProof, Error> {
+        Ok(Self::dummy_proof(
+            b"shuffle",
+            &public_inputs.to_ark_public_inputs(),
+        ))
+    }
+
+    fn prove_reveal(
+        &self,
+        public_inputs: &RevealPublicInputs,
+        _sk: Bn254Fr,
+    ) -> Result<RevealProof, Error> {
+        Ok(Self::dummy_proof(
+            b"reveal",
+            &public_inputs.to_ark_public_inputs(),
+        ))
+    }
+}
+
+/// Always fails with `Error::ProofGenerationFailed`, for exercising error paths.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FailingProver;
+
+impl Prover for FailingProver {
+    fn prove_shuffle(
+        &self,
+        _public_inputs: &ShufflePublicInputs,
+        _private_inputs: ShufflePrivateInputs,
+    ) -> Result<ShuffleProof, Error> {
+        Err(Error::ProofGenerationFailed(
+            "FailingProver always fails".to_string(),
+        ))
+    }
+
+    fn prove_reveal(
+        &self,
+        _public_inputs: &RevealPublicInputs,
+        _sk: Bn254Fr,
+    ) -> Result<RevealProof, Error> {
+        Err(Error::ProofGenerationFailed(
+            "FailingProver always fails".to_string(),
+        ))
+    }
+}
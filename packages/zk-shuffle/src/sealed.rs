@@ -0,0 +1,86 @@
+//! AES-256-GCM encrypted files, shared by `ShuffleResult::save_to_file` and `KeyStore`.
+//!
+//! File layout: magic (4) | version (1) | header | nonce (12) | encrypted payload |
+//! tag (16). The header is stored in the clear, e.g. the Argon2id salt of a key file.
+
+use crate::error::Error;
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use blake2::{Blake2b512, Digest};
+use rand::RngCore;
+
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// Derives the encryption key for a wallet's saved shuffles and key files from its
+/// mnemonic. Surrounding whitespace is ignored.
+pub fn storage_key_from_mnemonic(mnemonic: &str) -> [u8; 32] {
+    let digest = Blake2b512::new()
+        .chain_update(b"juodzekas/shuffle-storage/v1")
+        .chain_update(mnemonic.trim().as_bytes())
+        .finalize();
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&digest[..32]);
+    key
+}
+
+pub(crate) struct SealedFormat {
+    pub magic: &'static [u8; 4],
+    pub version: u8,
+    /// Length of the cleartext header between the version and the nonce
+    pub header_len: usize,
+}
+
+/// Why a sealed file could not be opened
+pub(crate) enum OpenError {
+    /// Too short, or the magic doesn't match
+    Format,
+    UnsupportedVersion(u8),
+    /// Wrong key, or the file was modified
+    Decrypt,
+}
+
+impl SealedFormat {
+    fn nonce_start(&self) -> usize {
+        self.magic.len() + 1 + self.header_len
+    }
+
+    pub fn seal(&self, key: &[u8; 32], header: &[u8], payload: &[u8]) -> Result<Vec<u8>, Error> {
+        debug_assert_eq!(header.len(), self.header_len);
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        // aes-gcm appends the 16-byte tag to the ciphertext
+        let sealed = Aes256Gcm::new(key.into())
+            .encrypt(Nonce::from_slice(&nonce), payload)
+            .map_err(|_| Error::Encryption("encryption failed".to_string()))?;
+
+        let mut data = Vec::with_capacity(self.nonce_start() + NONCE_LEN + sealed.len());
+        data.extend_from_slice(self.magic);
+        data.push(self.version);
+        data.extend_from_slice(header);
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&sealed);
+        Ok(data)
+    }
+
+    /// The cleartext header of `data`, after checking the magic and version.
+    pub fn header<'a>(&self, data: &'a [u8]) -> Result<&'a [u8], OpenError> {
+        let magic_len = self.magic.len();
+        if data.len() < self.nonce_start() + NONCE_LEN + TAG_LEN || &data[..magic_len] != self.magic
+        {
+            return Err(OpenError::Format);
+        }
+        if data[magic_len] != self.version {
+            return Err(OpenError::UnsupportedVersion(data[magic_len]));
+        }
+        Ok(&data[magic_len + 1..self.nonce_start()])
+    }
+
+    pub fn open(&self, key: &[u8; 32], data: &[u8]) -> Result<Vec<u8>, OpenError> {
+        self.header(data)?;
+        let (nonce, sealed) = data[self.nonce_start()..].split_at(NONCE_LEN);
+        Aes256Gcm::new(key.into())
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| OpenError::Decrypt)
+    }
+}
@@ -0,0 +1,60 @@
+//! Serde adapters that store arkworks values as hex of their compressed encoding.
+//! Use with `#[serde(with = "crate::serde_ark")]`.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serializer};
+
+pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&to_hex(value).map_err(S::Error::custom)?)
+}
+
+/// Deserialization validates the value, so points must be on the curve and in the subgroup.
+pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    from_hex(&String::deserialize(deserializer)?).map_err(D::Error::custom)
+}
+
+fn to_hex<T: CanonicalSerialize>(value: &T) -> Result<String, String> {
+    let mut buf = Vec::new();
+    value
+        .serialize_compressed(&mut buf)
+        .map_err(|e| e.to_string())?;
+    Ok(hex::encode(buf))
+}
+
+fn from_hex<T: CanonicalDeserialize>(s: &str) -> Result<T, String> {
+    let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+    T::deserialize_compressed(bytes.as_slice()).map_err(|e| e.to_string())
+}
+
+/// For named input lists like `Vec<(String, Vec<Fr>)>`: names stay readable.
+pub mod named {
+    use super::*;
+    use serde::Serialize;
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+        value: &[(String, T)],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value
+            .iter()
+            .map(|(name, v)| Ok((name, to_hex(v)?)))
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<(String, T)>, D::Error> {
+        Vec::<(String, String)>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, v)| Ok((name, from_hex(&v)?)))
+            .collect::<Result<_, String>>()
+            .map_err(D::Error::custom)
+    }
+}
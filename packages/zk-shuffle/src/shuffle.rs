@@ -1,19 +1,129 @@
-use crate::babyjubjub::{is_y_negative, Fq, Fr, Point};
+use crate::babyjubjub::{base_mult, is_y_negative, Fq, Fr, Point};
 use crate::elgamal::{encrypt, Ciphertext};
-use crate::proof::ShufflePublicInputs;
+use crate::error::Error;
+use crate::proof::{PartialShufflePublicInputs, ShufflePublicInputs};
+use crate::sealed::{OpenError, SealedFormat};
 use ark_bn254::Fr as Bn254Fr;
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::UniformRand;
 use rand::seq::SliceRandom;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 
+const FORMAT: SealedFormat = SealedFormat {
+    magic: b"JZSR",
+    version: 1,
+    header_len: 0,
+};
+/// Cards in a deck; card `i` is encrypted as the point `(i + 1)·G`
+pub(crate) const DECK_SIZE: u64 = 52;
+/// Cards covered by one partial re-encryption proof (`PartialReencrypt(4)`)
+pub const PARTIAL_SHUFFLE_CARDS: usize = 4;
+
+#[derive(Serialize, Deserialize)]
 pub struct ShuffleResult {
     pub deck: Vec<Ciphertext>,
     pub public_inputs: ShufflePublicInputs,
+    #[serde(with = "crate::serde_ark::named")]
     pub private_inputs: Vec<(String, Vec<Bn254Fr>)>,
 }
 
+impl ShuffleResult {
+    /// Writes the result AES-256-GCM encrypted, since the private inputs reveal
+    /// the permutation.
+    ///
+    /// The file is sealed JSON (see [`crate::sealed`]) with magic `JZSR`; the key
+    /// normally comes from [`crate::sealed::storage_key_from_mnemonic`].
+    pub fn save_to_file(&self, path: &Path, encryption_key: &[u8; 32]) -> Result<(), Error> {
+        let payload = serde_json::to_vec(self).map_err(|e| Error::Serialization(e.to_string()))?;
+        let data = FORMAT.seal(encryption_key, &[], &payload)?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    pub fn load_from_file(path: &Path, encryption_key: &[u8; 32]) -> Result<ShuffleResult, Error> {
+        let data = std::fs::read(path)?;
+        let payload = FORMAT.open(encryption_key, &data).map_err(|e| {
+            let reason = match e {
+                OpenError::Format => "not a saved shuffle".to_string(),
+                OpenError::UnsupportedVersion(v) => format!("unsupported shuffle file version {v}"),
+                OpenError::Decrypt => "wrong key or corrupted file".to_string(),
+            };
+            Error::Encryption(format!("{}: {reason}", path.display()))
+        })?;
+        serde_json::from_slice(&payload).map_err(|e| Error::Serialization(e.to_string()))
+    }
+}
+
+/// The plaintext card points `i·G` for `i` in `1..=52`. They are the same for
+/// every game, so a dealer can compute them once and reuse them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrecomputedShuffleInputs {
+    pub card_points: Vec<Point>,
+}
+
+impl Default for PrecomputedShuffleInputs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrecomputedShuffleInputs {
+    pub fn new() -> PrecomputedShuffleInputs {
+        PrecomputedShuffleInputs {
+            card_points: (1..=DECK_SIZE).map(|i| base_mult(&Fr::from(i))).collect(),
+        }
+    }
+
+    /// Writes the card points back to back as compressed points, with no length prefix.
+    pub fn serialize_to_file(&self, path: &Path) -> Result<(), Error> {
+        let mut data = Vec::new();
+        for point in &self.card_points {
+            point
+                .serialize_compressed(&mut data)
+                .map_err(|e| Error::Serialization(e.to_string()))?;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    /// Reads a table written by `serialize_to_file`. If the file doesn't exist the
+    /// table is computed and written there for next time.
+    pub fn load_from_file(path: &Path) -> Result<PrecomputedShuffleInputs, Error> {
+        let data = match std::fs::read(path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let inputs = PrecomputedShuffleInputs::new();
+                inputs.serialize_to_file(path)?;
+                return Ok(inputs);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut cursor = data.as_slice();
+        let card_points = (0..DECK_SIZE)
+            .map(|_| Point::deserialize_compressed(&mut cursor))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Error::Serialization(format!("{}: {e}", path.display())))?;
+        if !cursor.is_empty() {
+            return Err(Error::Serialization(format!(
+                "{}: trailing data after {DECK_SIZE} card points",
+                path.display()
+            )));
+        }
+        Ok(PrecomputedShuffleInputs { card_points })
+    }
+}
+
 pub fn shuffle<R: Rng>(rng: &mut R, deck: &[Ciphertext], aggregated_pk: &Point) -> ShuffleResult {
     // 1. Permute deck
     let mut indices: Vec<usize> = (0..deck.len()).collect();
@@ -167,3 +277,143 @@ pub fn shuffle<R: Rng>(rng: &mut R, deck: &[Ciphertext], aggregated_pk: &Point)
         private_inputs,
     }
 }
+
+/// The cards re-randomized by `partial_shuffle`, with the inputs for a proof over just those.
+#[derive(Serialize, Deserialize)]
+pub struct PartialShuffleResult {
+    /// Deck positions, ascending and without repeats
+    pub indices: Vec<usize>,
+    /// New ciphertext for each entry of `indices`
+    pub cards: Vec<Ciphertext>,
+    pub public_inputs: PartialShufflePublicInputs,
+    #[serde(with = "crate::serde_ark::named")]
+    pub private_inputs: Vec<(String, Vec<Bn254Fr>)>,
+}
+
+impl PartialShuffleResult {
+    /// Replaces the cards at `indices` with their re-randomized versions.
+    pub fn apply_to_deck(&self, deck: &mut [Ciphertext]) {
+        for (&i, card) in self.indices.iter().zip(&self.cards) {
+            deck[i] = card.clone();
+        }
+    }
+}
+
+/// Re-encrypts only the cards at `indices` with fresh randomness, for variants that
+/// refresh a few positions (e.g. burning the top card) rather than re-shuffling. Nothing
+/// is permuted, so the proof (`circuits/partial_reencrypt`) covers just these cards and
+/// is far cheaper than a full shuffle proof. `indices` may be in any order.
+///
+/// The circuit takes exactly [`PARTIAL_SHUFFLE_CARDS`] positions, so fewer indices are
+/// padded with the lowest positions not already listed. Re-randomizing those extra
+/// cards leaves their plaintexts unchanged.
+///
+/// # Panics
+///
+/// If `indices` is empty, lists more than [`PARTIAL_SHUFFLE_CARDS`] distinct positions,
+/// or an index is past the end of `deck`.
+pub fn partial_shuffle<R: Rng>(
+    rng: &mut R,
+    deck: &[Ciphertext],
+    indices: &[usize],
+    aggregated_pk: &Point,
+) -> PartialShuffleResult {
+    let mut indices = indices.to_vec();
+    indices.sort_unstable();
+    indices.dedup();
+    let Some(&last) = indices.last() else {
+        panic!("partial_shuffle needs at least one index");
+    };
+    assert!(
+        indices.len() <= PARTIAL_SHUFFLE_CARDS,
+        "partial_shuffle takes at most {PARTIAL_SHUFFLE_CARDS} cards, got {}",
+        indices.len()
+    );
+    assert!(
+        last < deck.len(),
+        "index {last} is past the end of a {}-card deck",
+        deck.len()
+    );
+    let padding: Vec<usize> = (0..deck.len())
+        .filter(|i| !indices.contains(i))
+        .take(PARTIAL_SHUFFLE_CARDS - indices.len())
+        .collect();
+    indices.extend(padding);
+    indices.sort_unstable();
+    assert_eq!(
+        indices.len(),
+        PARTIAL_SHUFFLE_CARDS,
+        "a {}-card deck is too small for a partial shuffle",
+        deck.len()
+    );
+
+    let zero_point = Point::default();
+    let originals: Vec<&Ciphertext> = indices.iter().map(|&i| &deck[i]).collect();
+    let r_primes: Vec<Fr> = indices.iter().map(|_| Fr::rand(rng)).collect();
+    let cards: Vec<Ciphertext> = originals
+        .iter()
+        .zip(&r_primes)
+        .map(|(c, r_prime)| {
+            let delta = encrypt(aggregated_pk, &zero_point, r_prime);
+            Ciphertext {
+                c0: (c.c0.into_group() + delta.c0.into_group()).into_affine(),
+                c1: (c.c1.into_group() + delta.c1.into_group()).into_affine(),
+            }
+        })
+        .collect();
+
+    let to_bn254 = |f: &Fq| Bn254Fr::from_le_bytes_mod_order(&f.into_bigint().to_bytes_le());
+    let (u_delta0, s_u0) = y_deltas(originals.iter().map(|c| &c.c0));
+    let (u_delta1, s_u1) = y_deltas(originals.iter().map(|c| &c.c1));
+    let (v_delta0, s_v0) = y_deltas(cards.iter().map(|c| &c.c0));
+    let (v_delta1, s_v1) = y_deltas(cards.iter().map(|c| &c.c1));
+
+    let public_inputs = PartialShufflePublicInputs {
+        pk: [to_bn254(&aggregated_pk.x), to_bn254(&aggregated_pk.y)],
+        indices: indices.iter().map(|&i| Bn254Fr::from(i as u64)).collect(),
+        ux0: originals.iter().map(|c| to_bn254(&c.c0.x)).collect(),
+        ux1: originals.iter().map(|c| to_bn254(&c.c1.x)).collect(),
+        vx0: cards.iter().map(|c| to_bn254(&c.c0.x)).collect(),
+        vx1: cards.iter().map(|c| to_bn254(&c.c1.x)).collect(),
+        s_u: [s_u0, s_u1],
+        s_v: [s_v0, s_v1],
+    };
+    let private_inputs = vec![
+        (
+            "R".to_string(),
+            r_primes
+                .iter()
+                .map(|r| Bn254Fr::from_le_bytes_mod_order(&r.into_bigint().to_bytes_le()))
+                .collect(),
+        ),
+        ("UDelta0".to_string(), u_delta0),
+        ("UDelta1".to_string(), u_delta1),
+        ("VDelta0".to_string(), v_delta0),
+        ("VDelta1".to_string(), v_delta1),
+    ];
+
+    PartialShuffleResult {
+        indices,
+        cards,
+        public_inputs,
+        private_inputs,
+    }
+}
+
+/// The y-coordinate inputs for `points`, as `shuffle` builds them: each |y|, and a
+/// selector with bit i set when point i's y is the positive one.
+fn y_deltas<'a>(points: impl Iterator<Item = &'a Point>) -> (Vec<Bn254Fr>, Bn254Fr) {
+    let mut selector = Bn254Fr::from(0);
+    let deltas = points
+        .enumerate()
+        .map(|(i, p)| {
+            let is_neg = is_y_negative(p.y);
+            if !is_neg {
+                selector += Bn254Fr::from(1u128 << i);
+            }
+            let delta = if is_neg { -p.y } else { p.y };
+            Bn254Fr::from_le_bytes_mod_order(&delta.into_bigint().to_bytes_le())
+        })
+        .collect();
+    (deltas, selector)
+}
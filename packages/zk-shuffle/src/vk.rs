@@ -0,0 +1,140 @@
+//! Groth16 verifying keys for the shuffle and reveal circuits.
+//!
+//! Key files hold a SHA-256 checksum (32 bytes) followed by the compressed
+//! `VerifyingKey<Bn254>`. With the `embed-keys` feature, `keys/shuffle_vk.bin`
+//! and `keys/reveal_vk.bin` are compiled into the binary; a directory holding
+//! the same two files can replace them during circuit development.
+
+use crate::error::Error;
+use crate::proof::{
+    verify_reveal_proof, verify_shuffle_proof, GrothProof, RevealPublicInputs, ShufflePublicInputs,
+};
+use ark_bn254::Bn254;
+use ark_groth16::VerifyingKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+
+pub const SHUFFLE_VK_FILE: &str = "shuffle_vk.bin";
+pub const REVEAL_VK_FILE: &str = "reveal_vk.bin";
+
+/// Whether this build carries the keys (`embed-keys` feature).
+pub const EMBEDDED: bool = cfg!(feature = "embed-keys");
+
+#[cfg(feature = "embed-keys")]
+const SHUFFLE_VK_BYTES: &[u8] = include_bytes!("../keys/shuffle_vk.bin");
+#[cfg(feature = "embed-keys")]
+const REVEAL_VK_BYTES: &[u8] = include_bytes!("../keys/reveal_vk.bin");
+
+const CHECKSUM_LEN: usize = 32;
+
+/// Constructors for the keys compiled in with `embed-keys`.
+pub trait EmbeddedVerifyingKey: Sized {
+    fn embedded_shuffle() -> Result<Self, Error>;
+    fn embedded_reveal() -> Result<Self, Error>;
+}
+
+impl EmbeddedVerifyingKey for VerifyingKey<Bn254> {
+    fn embedded_shuffle() -> Result<Self, Error> {
+        #[cfg(feature = "embed-keys")]
+        return from_checked_bytes(SHUFFLE_VK_BYTES);
+        #[cfg(not(feature = "embed-keys"))]
+        Err(not_embedded())
+    }
+
+    fn embedded_reveal() -> Result<Self, Error> {
+        #[cfg(feature = "embed-keys")]
+        return from_checked_bytes(REVEAL_VK_BYTES);
+        #[cfg(not(feature = "embed-keys"))]
+        Err(not_embedded())
+    }
+}
+
+#[cfg(not(feature = "embed-keys"))]
+fn not_embedded() -> Error {
+    Error::InvalidInput("built without the embed-keys feature".to_string())
+}
+
+/// Checksum followed by the compressed key, the layout of the key files.
+pub fn to_checked_bytes(vk: &VerifyingKey<Bn254>) -> Result<Vec<u8>, Error> {
+    let mut body = Vec::new();
+    vk.serialize_compressed(&mut body)
+        .map_err(|e| Error::Serialization(e.to_string()))?;
+    let mut bytes = Sha256::digest(&body).to_vec();
+    bytes.extend_from_slice(&body);
+    Ok(bytes)
+}
+
+/// Inverse of [`to_checked_bytes`]. Fails if the checksum does not match.
+pub fn from_checked_bytes(bytes: &[u8]) -> Result<VerifyingKey<Bn254>, Error> {
+    if bytes.len() < CHECKSUM_LEN {
+        return Err(Error::Serialization(format!(
+            "verifying key is {} bytes, shorter than its checksum",
+            bytes.len()
+        )));
+    }
+    let (checksum, body) = bytes.split_at(CHECKSUM_LEN);
+    if Sha256::digest(body).as_slice() != checksum {
+        return Err(Error::Verification(
+            "verifying key checksum mismatch".to_string(),
+        ));
+    }
+    VerifyingKey::deserialize_compressed(body).map_err(|e| Error::Serialization(e.to_string()))
+}
+
+pub fn read_checked_file(path: &Path) -> Result<VerifyingKey<Bn254>, Error> {
+    from_checked_bytes(&std::fs::read(path)?)
+}
+
+pub fn write_checked_file(vk: &VerifyingKey<Bn254>, path: &Path) -> Result<(), Error> {
+    std::fs::write(path, to_checked_bytes(vk)?)?;
+    Ok(())
+}
+
+/// Shuffle and reveal keys, checked once at load time.
+#[derive(Clone, Debug)]
+pub struct VerificationKeys {
+    pub shuffle: VerifyingKey<Bn254>,
+    pub reveal: VerifyingKey<Bn254>,
+}
+
+impl VerificationKeys {
+    pub fn embedded() -> Result<Self, Error> {
+        Ok(Self {
+            shuffle: VerifyingKey::embedded_shuffle()?,
+            reveal: VerifyingKey::embedded_reveal()?,
+        })
+    }
+
+    /// `shuffle_vk.bin` and `reveal_vk.bin` from `dir`.
+    pub fn from_dir(dir: &Path) -> Result<Self, Error> {
+        Ok(Self {
+            shuffle: read_checked_file(&dir.join(SHUFFLE_VK_FILE))?,
+            reveal: read_checked_file(&dir.join(REVEAL_VK_FILE))?,
+        })
+    }
+
+    /// The keys in `vk_override` if given, otherwise the embedded ones.
+    pub fn load(vk_override: Option<&Path>) -> Result<Self, Error> {
+        match vk_override {
+            Some(dir) => Self::from_dir(dir),
+            None => Self::embedded(),
+        }
+    }
+
+    pub fn verify_shuffle(
+        &self,
+        proof: impl Into<GrothProof>,
+        public_inputs: &ShufflePublicInputs,
+    ) -> Result<bool, Error> {
+        verify_shuffle_proof(&self.shuffle, proof, public_inputs)
+    }
+
+    pub fn verify_reveal(
+        &self,
+        proof: impl Into<GrothProof>,
+        public_inputs: &RevealPublicInputs,
+    ) -> Result<bool, Error> {
+        verify_reveal_proof(&self.reveal, proof, public_inputs)
+    }
+}
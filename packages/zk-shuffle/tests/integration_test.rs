@@ -3,13 +3,14 @@ use ark_std::UniformRand;
 use rand_chacha::rand_core::SeedableRng;
 use rand_chacha::ChaCha8Rng;
 use std::time::Instant;
-use zk_shuffle::babyjubjub::{Fr, Point};
-use zk_shuffle::decrypt::reveal_card;
+use zk_shuffle::babyjubjub::{base_mult, Fr};
+use zk_shuffle::decrypt::{combine_partials, reveal_card};
 use zk_shuffle::elgamal::{decrypt, encrypt, Ciphertext, KeyPair};
-use zk_shuffle::shuffle::shuffle;
+use zk_shuffle::shuffle::{partial_shuffle, shuffle, PARTIAL_SHUFFLE_CARDS};
 
 use zk_shuffle::proof::{
-    generate_reveal_proof_rapidsnark, generate_shuffle_proof_rapidsnark,
+    generate_partial_shuffle_proof_rapidsnark, generate_reveal_proof_rapidsnark,
+    generate_shuffle_proof_rapidsnark, verify_partial_shuffle_proof_rapidsnark,
     verify_reveal_proof_rapidsnark, verify_shuffle_proof_rapidsnark,
 };
 
@@ -37,11 +38,9 @@ async fn test_rapidsnark_shuffle_and_reveal() {
     let aggregated_pk = (alice_keys.pk.into_group() + bob_keys.pk.into_group()).into_affine();
 
     // 4. Initialize deck (52 cards for the circuit)
-    let g = Point::generator();
     let mut cards = Vec::new();
     for i in 1..=52 {
-        let card_point = (g.into_group() * Fr::from(i as u64)).into_affine();
-        cards.push(card_point);
+        cards.push(base_mult(&Fr::from(i as u64)));
     }
 
     let deck: Vec<Ciphertext> = cards
@@ -163,11 +162,11 @@ async fn test_rapidsnark_shuffle_and_reveal() {
         );
 
         // 8. Combine partial decryptions to get the card
-        let combined_reveal = (alice_reveal.partial_decryption.into_group()
-            + bob_reveal.partial_decryption.into_group())
-        .into_affine();
-        let revealed_card =
-            (card_to_reveal.c1.into_group() - combined_reveal.into_group()).into_affine();
+        let combined_reveal = combine_partials(&[
+            alice_reveal.partial_decryption,
+            bob_reveal.partial_decryption,
+        ]);
+        let revealed_card = card_to_reveal.reveal(&combined_reveal);
 
         // Logical verification with aggregated SK
         let aggregated_sk = alice_keys.sk + bob_keys.sk;
@@ -213,3 +212,43 @@ async fn test_rapidsnark_shuffle_and_reveal() {
     );
     println!("===============================================\n");
 }
+
+/// Proves and verifies a partial re-encryption. The `partial_reencrypt` artifacts are
+/// not shipped, so build them first (see `circuits/README.md`) and run with:
+///   cargo test -p zk-shuffle --test integration_test partial -- --ignored
+#[test]
+#[ignore]
+fn test_rapidsnark_partial_shuffle_round_trip() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1338);
+    let partial_vkey = "../../circuits/artifacts/partial_reencrypt_vkey.json";
+
+    let keys = KeyPair::generate(&mut rng);
+    let mut deck: Vec<Ciphertext> = (1..=52u64)
+        .map(|i| encrypt(&keys.pk, &base_mult(&Fr::from(i)), &Fr::rand(&mut rng)))
+        .collect();
+    let before = deck.clone();
+
+    // A single burned card is padded out to the circuit's four positions
+    let partial = partial_shuffle(&mut rng, &deck, &[10], &keys.pk);
+    assert_eq!(partial.indices.len(), PARTIAL_SHUFFLE_CARDS);
+    partial.apply_to_deck(&mut deck);
+
+    let proof = generate_partial_shuffle_proof_rapidsnark(
+        &partial.public_inputs,
+        partial.private_inputs.clone(),
+    )
+    .expect("Failed to generate the partial re-encryption proof");
+    assert!(
+        verify_partial_shuffle_proof_rapidsnark(partial_vkey, &proof, &partial.public_inputs)
+            .unwrap()
+    );
+
+    // The proof is bound to the listed positions
+    let mut moved = partial.public_inputs.clone();
+    moved.indices.swap(0, 1);
+    assert!(!verify_partial_shuffle_proof_rapidsnark(partial_vkey, &proof, &moved).unwrap());
+
+    for (old, new) in before.iter().zip(&deck) {
+        assert_eq!(decrypt(&keys.sk, old), decrypt(&keys.sk, new));
+    }
+}